@@ -1,54 +1,216 @@
 use anyhow::Result;
-use cogex_core::{Phase, StandardPhase, StimulusType};
-use cogex_experiment::{ExperimentConfig, ExperimentEvent, ExperimentStateMachine};
-use cogex_render::{render::FrameStats, SkiaRenderer};
+use cogex_cache::Interner;
+use cogex_core::{
+    Instructions, MouseButton, Questionnaire, ResponseKey, StandardPhase, StimulusType, TrialState,
+};
+#[cfg(feature = "gamepad")]
+use cogex_core::{RawEvent, RawInput};
+use cogex_experiment::{
+    DisplayInfo, ExperimentConfig, ExperimentEvent, ExperimentStateMachine, OutputManager,
+    ParadigmRegistry, RedrawMode, Screen, SessionInfo, DEFAULT_OUTPUT_ROOT,
+};
+
+use crate::console::ControlConsole;
+#[cfg(feature = "control")]
+use crate::control::ControlServer;
+use crate::form::{FormAction, MetadataForm};
+use crate::framelog::{FrameLog, FrameRecord};
+#[cfg(feature = "gamepad")]
+use crate::gamepad::{GamepadInput, GamepadMapping};
+use crate::input::InputCapture;
+use crate::mirror::{select_monitor, ExperimenterView};
+#[cfg(feature = "monitor")]
+use crate::monitor::SessionMonitor;
+use crate::scheduler::PresentationScheduler;
+#[cfg(feature = "gpu")]
+use cogex_render::WgpuRenderer;
+use cogex_render::{render::FrameStats, Catalog, SkiaRenderer};
 use cogex_timing::{HighPrecisionTimer, Timer};
 use pixels::{Pixels, SurfaceTexture};
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tiny_skia::Pixmap;
+#[cfg(feature = "gamepad")]
+use std::time::SystemTime;
+use std::time::{Duration, Instant};
+use tracing::{error, info, trace, warn};
 use winit::{
     application::ApplicationHandler,
     dpi::PhysicalSize,
-    event::WindowEvent,
-    event_loop::{ActiveEventLoop, EventLoop},
+    event::{KeyEvent, StartCause, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
     window::{Fullscreen, Icon, Window, WindowId},
 };
 
+/// Participant instructions, read relative to the working directory when present
+const INSTRUCTIONS_PATH: &str = "assets/instructions.md";
+/// Post-task questionnaire items, read like the instructions
+const QUESTIONNAIRE_PATH: &str = "assets/questionnaire.txt";
+/// How often an idle loop polls the console and input devices that do not
+/// wake the event loop, such as response boxes
+const IDLE_POLL: Duration = Duration::from_millis(50);
+/// Refreshes drawn ahead of a trial deadline, so the transition lands on a
+/// frame of an already running vsync-locked sequence
+const WAKE_FRAMES: f64 = 3.0;
+/// Wake margin while the refresh rate is unknown
+const WAKE_MARGIN_NS: u64 = 20_000_000;
+
+/// What a static screen shows; any change needs a redraw
+#[derive(PartialEq)]
+struct ScreenState {
+    phase: StandardPhase,
+    form: bool,
+    paused: bool,
+    interrupted: bool,
+    on_break: bool,
+    trial: Option<(usize, TrialState)>,
+    stimulus_shown: bool,
+    page: Option<usize>,
+    item: Option<usize>,
+    rating: Option<f32>,
+    aborted: bool,
+}
+
 pub struct App {
     window: Option<Arc<Window>>,
     pixels: Option<Pixels<'static>>,
-    experiment: ExperimentStateMachine<StandardPhase, StimulusType, HighPrecisionTimer, ThreadRng>,
+    experiment: ExperimentStateMachine<StandardPhase, StimulusType, HighPrecisionTimer, StdRng>,
     renderer: Option<SkiaRenderer>,
-    canvas: Option<Pixmap>,
+    /// Text IDs of this session's renderers, dropped with the session
+    interner: Interner,
+    /// Draws trial frames on the GPU; other screens still go through Skia
+    #[cfg(feature = "gpu")]
+    gpu: Option<WgpuRenderer>,
     icon: Icon,
     current_size: Option<PhysicalSize<u32>>,
     scale_factor: f64,
     refresh_rate: Option<f64>,
+    /// Snaps trial durations to the refresh rate, when it is known
+    scheduler: Option<PresentationScheduler>,
+    form: Option<MetadataForm>,
+    mirror: Option<ExperimenterView>,
+    console: Option<ControlConsole>,
+    /// Commands from an external orchestrator
+    #[cfg(feature = "control")]
+    control: Option<ControlServer>,
+    /// Broadcasts live trial events to remote dashboards
+    #[cfg(feature = "monitor")]
+    monitor: Option<SessionMonitor>,
+    /// Timestamped participant input, drained by the experiment each update
+    input: InputCapture,
+    /// Names and creates this session's output files
+    output: Option<OutputManager>,
+    /// Per-frame render timing, for `cogex-app report`
+    frame_log: Option<FrameLog>,
+    /// Results count at the last checkpoint
+    checkpointed: usize,
+    /// Last cursor position in window pixels
+    cursor: (f32, f32),
+    cursor_visible: bool,
+    /// Modifier keys held down, for the abort hotkey
+    modifiers: winit::keyboard::ModifiersState,
+    /// Whether results were written, so an abort saves them only once
+    saved: bool,
+    /// The participant left fullscreen; it is restored when they return
+    fullscreen_lost: bool,
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<GamepadInput>,
+    /// Records speech onsets for naming-latency tasks
+    #[cfg(feature = "voice")]
+    _voice_key: Option<cogex_input::VoiceKey>,
+    /// Serial and parallel port scanner triggers, read while open
+    #[cfg(feature = "scanner")]
+    scanner: Vec<cogex_input::ScannerTrigger>,
 
     should_exit: bool,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let config = ExperimentConfig::<StandardPhase>::default();
+    /// Runs the paradigm `registry` has as `experiment`; without `session`
+    /// the participant form is shown first
+    pub fn new(
+        config: ExperimentConfig<StandardPhase>,
+        registry: &ParadigmRegistry<StandardPhase>,
+        experiment: &str,
+        session: Option<SessionInfo>,
+    ) -> Result<Self> {
         let timer = HighPrecisionTimer::new();
-        let rng = rand::rng();
-        let experiment = ExperimentStateMachine::new(config, timer, rng);
+        let rng = StdRng::from_os_rng();
+        let (input, receiver) = InputCapture::spawn(timer.clone(), config.key_scan_ms);
+        let mut experiment = ExperimentStateMachine::new(config, timer, rng)
+            .with_named_paradigm(registry, experiment)?
+            .with_input(receiver);
+        let instructions = Path::new(INSTRUCTIONS_PATH);
+        if instructions.exists() {
+            match Instructions::load(instructions) {
+                Ok(pages) => experiment = experiment.with_instructions(pages),
+                Err(e) => warn!("Failed to load {}: {}", instructions.display(), e),
+            }
+        }
+        let questionnaire = Path::new(QUESTIONNAIRE_PATH);
+        if questionnaire.exists() {
+            match Questionnaire::load(questionnaire) {
+                Ok(items) => experiment = experiment.with_questionnaire(items),
+                Err(e) => warn!("Failed to load {}: {}", questionnaire.display(), e),
+            }
+        }
         let icon = Self::load_icon(include_bytes!("../../assets/icon.png"));
+        #[cfg(feature = "gamepad")]
+        let gamepad = GamepadInput::new(GamepadMapping::default())
+            .map_err(|e| warn!("Gamepad input unavailable: {}", e))
+            .ok();
+        #[cfg(feature = "voice")]
+        let voice_key = cogex_input::VoiceKey::open(
+            cogex_input::VoiceKeyConfig::default(),
+            &experiment.timer,
+            input.sender(),
+        )
+        .map_err(|e| warn!("Voice key unavailable: {}", e))
+        .ok();
 
-        Ok(Self {
+        let mut app = Self {
             window: None,
             pixels: None,
             experiment,
             renderer: None,
-            canvas: None,
-            icon: icon,
+            interner: Interner::new(),
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            icon,
             current_size: None,
             scale_factor: 1.0,
             refresh_rate: None,
+            scheduler: None,
+            form: Some(MetadataForm::new()),
+            mirror: None,
+            console: None,
+            #[cfg(feature = "control")]
+            control: None,
+            #[cfg(feature = "monitor")]
+            monitor: None,
+            input,
+            output: None,
+            frame_log: None,
+            checkpointed: 0,
+            cursor: (0.0, 0.0),
+            cursor_visible: true,
+            modifiers: Default::default(),
+            saved: false,
+            fullscreen_lost: false,
+            #[cfg(feature = "gamepad")]
+            gamepad,
+            #[cfg(feature = "voice")]
+            _voice_key: voice_key,
+            #[cfg(feature = "scanner")]
+            scanner: Vec::new(),
             should_exit: false,
-        })
+        };
+        if let Some(info) = session {
+            app.form = None;
+            app.start_session(info);
+        }
+        Ok(app)
     }
 
     pub fn run(mut self) -> Result<()> {
@@ -60,10 +222,10 @@ impl App {
         }
 
         let event_loop = EventLoop::new()?;
-        println!("=== COGNITIVE EXPERIMENT APPLICATION ===");
-        println!("Platform: {}", std::env::consts::OS);
-        println!("Architecture: {}", std::env::consts::ARCH);
-        println!("Press SPACE to start or ESC to exit.\n");
+        info!("=== COGNITIVE EXPERIMENT APPLICATION ===");
+        info!("Platform: {}", std::env::consts::OS);
+        info!("Architecture: {}", std::env::consts::ARCH);
+        info!("Press SPACE to start or ESC to exit.");
 
         let result = event_loop.run_app(&mut self);
 
@@ -78,10 +240,27 @@ impl App {
     }
 
     fn create_window_and_surface(&mut self, event_loop: &ActiveEventLoop) -> Result<()> {
-        let primary_monitor = event_loop
-            .primary_monitor()
-            .or_else(|| event_loop.available_monitors().next())
-            .ok_or_else(|| anyhow::anyhow!("No monitor available"))?;
+        let primary_monitor = select_monitor(event_loop, &self.experiment.config.monitor)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Monitor {:?} not available", self.experiment.config.monitor)
+            })?;
+
+        // Created first so the participant window ends up focused
+        if let Some(selection) = self.experiment.config.experimenter_monitor.clone() {
+            match select_monitor(event_loop, &selection) {
+                Some(monitor) if monitor != primary_monitor => {
+                    match ExperimenterView::new(
+                        event_loop,
+                        monitor,
+                        self.experiment.timer.clock().clone(),
+                    ) {
+                        Ok(view) => self.mirror = Some(view),
+                        Err(e) => warn!("Failed to open experimenter view: {}", e),
+                    }
+                }
+                _ => warn!("Experimenter monitor {:?} not available", selection),
+            }
+        }
 
         self.refresh_rate = primary_monitor
             .refresh_rate_millihertz()
@@ -94,20 +273,32 @@ impl App {
             .with_window_icon(Some(self.icon.clone()));
 
         let window = Arc::new(event_loop.create_window(window_attributes)?);
+        self.input.set_focused(window.has_focus());
         let physical_size = window.inner_size();
         let scale_factor = window.scale_factor();
 
         self.current_size = Some(physical_size);
         self.scale_factor = scale_factor;
+        self.experiment.set_screen(Screen::new(
+            physical_size.width,
+            physical_size.height,
+            scale_factor as f32,
+        ));
 
-        println!("Display Configuration:");
-        println!(
+        info!("Display Configuration:");
+        info!(
             "  Physical size: {}×{}",
             physical_size.width, physical_size.height
         );
-        println!("  Scale factor: {:.2}", scale_factor);
+        info!("  Scale factor: {:.2}", scale_factor);
         if let Some(refresh_rate) = self.refresh_rate {
-            println!("  Refresh rate: {:.1} Hz", refresh_rate);
+            info!("  Refresh rate: {:.1} Hz", refresh_rate);
+            let scheduler = PresentationScheduler::new(refresh_rate);
+            self.experiment.onset_lead_ns = scheduler.lead_ns();
+            self.experiment.timer.set_refresh_rate(Some(refresh_rate));
+            self.scheduler = Some(scheduler);
+        } else {
+            info!("  Refresh rate unknown, trial durations not snapped to frames");
         }
 
         let surface_texture =
@@ -119,14 +310,53 @@ impl App {
             surface_texture,
         )?);
 
-        self.canvas = Pixmap::new(physical_size.width, physical_size.height);
-        self.renderer = Some(SkiaRenderer::new(
+        let mut renderer = SkiaRenderer::new_with_interner(
             physical_size.width,
             physical_size.height,
-            self.experiment.config.experiment_trials,
-        ));
+            self.experiment.config.total_experiment_trials(),
+            self.interner.clone(),
+        );
+        renderer.set_display(self.experiment.config.display);
+        renderer.set_anti_alias(self.experiment.config.anti_alias);
+        renderer.set_theme(self.experiment.config.theme);
+        renderer.set_text_cache_budget(self.experiment.config.text_cache_mb << 20);
+        renderer.set_scale_factor(scale_factor as f32);
+        renderer.set_feedback_rt(self.experiment.config.feedback_show_rt);
+        renderer.set_iti_fixation(self.experiment.config.iti_display.shows_fixation());
+        renderer.set_preintern_plan(self.experiment.config.preintern_plan());
+        if let Err(e) = renderer.set_snapshot_dir(self.experiment.config.snapshot_dir.clone()) {
+            warn!("Snapshots disabled: {:#}", e);
+        }
+        let catalog = Catalog::resolve(&self.experiment.config.locale).unwrap_or_else(|e| {
+            warn!("Falling back to English text: {}", e);
+            Catalog::english()
+        });
+        renderer.set_catalog(catalog.with_messages(self.experiment.paradigm_messages()));
+        #[cfg(feature = "gpu")]
+        {
+            let pixels = self.pixels.as_ref().unwrap();
+            let mut gpu = WgpuRenderer::new_with_interner(
+                pixels.device(),
+                pixels.render_texture_format(),
+                physical_size.width,
+                physical_size.height,
+                self.experiment.config.total_experiment_trials(),
+                self.interner.clone(),
+            );
+            gpu.set_display(self.experiment.config.display);
+            gpu.set_anti_alias(self.experiment.config.anti_alias);
+            gpu.set_theme(self.experiment.config.theme);
+            gpu.set_scale_factor(scale_factor as f32);
+            gpu.set_feedback_rt(self.experiment.config.feedback_show_rt);
+            gpu.set_iti_fixation(self.experiment.config.iti_display.shows_fixation());
+            gpu.set_preintern_plan(self.experiment.config.preintern_plan());
+            gpu.set_catalog(renderer.catalog().clone());
+            self.gpu = Some(gpu);
+        }
+        self.renderer = Some(renderer);
 
-        window.set_cursor_visible(false);
+        self.cursor_visible = self.experiment.response_mapping.accepts_mouse();
+        window.set_cursor_visible(self.cursor_visible);
         window.request_redraw();
 
         self.window = Some(window);
@@ -138,95 +368,671 @@ impl App {
         let pix = self.pixels.as_mut().unwrap();
         let renderer = self.renderer.as_mut().unwrap();
 
-        let phase = self.experiment.current_phase();
-        let stim = self.experiment.current_stimulus();
-        let ts = self.experiment.current_trial_state();
-        let prog = self.experiment.trial_progress();
+        // Shares the frame statistics, so what is recorded here reaches
+        // calibration and dropped-frame detection
         let mut timer = self.experiment.timer.clone();
 
         let frame = pix.frame_mut();
 
-        let stats: FrameStats = renderer.render_frame(phase, stim, ts, prog, frame, &mut timer)?;
-        let now = timer.now();
-        pix.render()?;
-        let elapsed = timer.elapsed(now);
+        if self.experiment.is_aborted() {
+            renderer.render_session_ended(frame, &mut timer)?;
+            pix.render()?;
+            return Ok(());
+        }
 
-        // if self.experiment.phase.requires_calibration() && self.experiment.timer.frame_count() < 300
-        {
-            self.window.as_ref().unwrap().request_redraw(); // schedule next VSync‐driven render
+        if self.experiment.is_interrupted() {
+            renderer.render_interrupted(frame, &mut timer)?;
+            pix.render()?;
+            return Ok(());
         }
 
-        println!(
-            "outer: {:.3}ms,clear {:.3}ms, phase {:.3}ms, copy {:.3}ms, total {:.3}ms, dirty count {:.3}",
-            elapsed.as_secs_f64() * 1e3,
-            stats.clear.as_secs_f64() * 1e3,
-            stats.phase.as_secs_f64() * 1e3,
-            stats.copy.as_secs_f64() * 1e3,
-            stats.total.as_secs_f64() * 1e3,
-            stats.dirty_count,
-        );
+        if let Some(instructions) = self.experiment.current_instructions() {
+            renderer.render_instructions(instructions, frame, &mut timer)?;
+            pix.render()?;
+            return Ok(());
+        }
 
-        self.experiment
-            .handle_event(ExperimentEvent::CalibrationComplete);
+        if let Some(questionnaire) = self.experiment.current_questionnaire() {
+            renderer.render_questionnaire(questionnaire, frame, &mut timer)?;
+            pix.render()?;
+            return Ok(());
+        }
+
+        if let Some(form) = &self.form {
+            renderer.render_form(
+                MetadataForm::TITLE,
+                &form.lines(),
+                form.active(),
+                frame,
+                &mut timer,
+            )?;
+            pix.render()?;
+            return Ok(());
+        }
+
+        if self.experiment.is_final_phase() {
+            let details = self.experiment.debrief_summary();
+            renderer.render_debrief(&self.experiment.debrief(), &details, frame, &mut timer)?;
+            pix.render()?;
+            return Ok(());
+        }
+
+        let presented = self.present_frame(&mut timer)?;
+        let presented_ns = timer.now();
+        self.experiment.frame_presented(presented_ns);
+        self.warm_up();
+
+        if let Some(mirror) = &mut self.mirror {
+            let experiment = &self.experiment;
+            mirror.update(|| experimenter_status(experiment))?;
+        }
+
+        if let Some((stats, elapsed)) = presented {
+            trace!(
+                "outer: {:.3}ms,clear {:.3}ms, phase {:.3}ms, copy {:.3}ms, total {:.3}ms, dirty count {:.3}",
+                elapsed.as_secs_f64() * 1e3,
+                stats.clear.as_secs_f64() * 1e3,
+                stats.phase.as_secs_f64() * 1e3,
+                stats.copy.as_secs_f64() * 1e3,
+                stats.total.as_secs_f64() * 1e3,
+                stats.dirty_count,
+            );
+            if let Some(log) = &mut self.frame_log {
+                let trial = self.experiment.current.as_ref().map(|t| t.id);
+                if let Err(e) = log.write(FrameRecord::new(presented_ns, &stats, elapsed, trial)) {
+                    warn!("Frame log write failed, logging stopped: {}", e);
+                    self.frame_log = None;
+                }
+            }
+        }
 
         Ok(())
     }
 
+    /// Draws and presents a trial frame, returning the CPU frame stats and
+    /// present time when the Skia renderer drew it
+    fn present_frame(
+        &mut self,
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<Option<(FrameStats, Duration)>> {
+        let pix = self.pixels.as_mut().unwrap();
+        let phase = self.experiment.current_phase();
+        let stim = self.experiment.current_stimuli();
+        let ts = self.experiment.current_trial_state();
+        let prog = self.experiment.trial_progress();
+
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.set_stimulus_elapsed(self.experiment.stimulus_elapsed());
+            gpu.set_rating(self.experiment.current_rating());
+            gpu.set_primitives(self.experiment.paradigm_primitives());
+            gpu.set_widgets(self.experiment.widgets());
+            gpu.set_rest_remaining(self.experiment.rest_remaining());
+            gpu.set_feedback(self.experiment.feedback());
+            gpu.render_frame(phase, &stim, ts, prog)?;
+            pix.render_with(|encoder, target, context| {
+                gpu.encode(&context.device, &context.queue, encoder, target);
+                Ok(())
+            })?;
+            return Ok(None);
+        }
+
+        let renderer = self.renderer.as_mut().unwrap();
+        renderer.set_stimulus_elapsed(self.experiment.stimulus_elapsed());
+        renderer.set_rating(self.experiment.current_rating());
+        renderer.set_subpixel(self.experiment.subpixel_positioning());
+        renderer.set_primitives(self.experiment.paradigm_primitives());
+        renderer.set_widgets(self.experiment.widgets());
+        renderer.set_rest_remaining(self.experiment.rest_remaining());
+        renderer.set_feedback(self.experiment.feedback());
+        let stats = renderer.render_frame(phase, &stim, ts, prog, pix.frame_mut(), timer)?;
+        let now = timer.now();
+        pix.render()?;
+        Ok(Some((stats, timer.elapsed(now))))
+    }
+
+    /// Rasterizes the stimuli of the next onset into the renderer's caches
+    fn warm_up(&mut self) {
+        let stimuli = self.experiment.upcoming_stimuli();
+        if stimuli.is_empty() {
+            return;
+        }
+        let progress = self.experiment.trial_progress();
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.warm_up(&stimuli, progress);
+            return;
+        }
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.warm_up(&stimuli, progress);
+        }
+    }
+
     fn update(&mut self) -> Result<()> {
+        if self.form.is_some() {
+            // Keys typed into the form must not reach the experiment
+            self.input.discard();
+            return Ok(());
+        }
+        if let Some(console) = &mut self.console {
+            for event in console.poll() {
+                self.experiment.handle_event(event);
+            }
+        }
+        #[cfg(feature = "control")]
+        if let Some(control) = &mut self.control {
+            for event in control.poll() {
+                self.experiment.handle_event(event);
+            }
+        }
+        if self.experiment.is_aborted() {
+            // The participant sees the exit screen until the experimenter
+            // closes the window
+            self.save_results();
+            return Ok(());
+        }
+        #[cfg(feature = "gamepad")]
+        if let Some(gamepad) = &mut self.gamepad {
+            let now = self.experiment.timer.now();
+            for (key, time) in gamepad.poll() {
+                // Events carry the time the OS read them; map it onto the timer
+                let age = SystemTime::now().duration_since(time).unwrap_or_default();
+                let pressed_ns = now.saturating_sub(age.as_nanos() as u64);
+                self.input
+                    .push(RawInput::new(RawEvent::Key(key), pressed_ns));
+            }
+        }
+        self.schedule_trial();
         let events = self.experiment.update();
         for event in events {
             self.experiment.handle_event(event);
         }
+        self.schedule_trial();
+        self.restore_fullscreen();
+        self.update_cursor();
+        self.publish_progress();
+        if self.experiment.results().len() > self.checkpointed {
+            self.checkpointed = self.experiment.results().len();
+            let path = self.checkpoint_path();
+            if let Err(e) = self.experiment.save_checkpoint(&path) {
+                warn!("Failed to save checkpoint: {}", e);
+            }
+        }
         Ok(())
     }
 
-    fn handle_input(&mut self, key: winit::keyboard::PhysicalKey, event_loop: &ActiveEventLoop) {
+    /// Pauses the experiment on the return screen when the participant
+    /// leaves the window; not before the session starts or after it ends
+    fn interrupt(&mut self, reason: &str) {
+        if self.form.is_some() || self.experiment.is_aborted() || self.experiment.is_final_phase() {
+            return;
+        }
+        self.experiment.handle_event(ExperimentEvent::Interrupt {
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Whether losing focus means the participant left, rather than the
+    /// experimenter clicking into the console
+    fn interrupts_on_focus_loss(&self) -> bool {
+        self.experiment
+            .config
+            .interrupt_on_focus_loss
+            .unwrap_or_else(|| {
+                !self
+                    .console
+                    .as_ref()
+                    .is_some_and(ControlConsole::is_interactive)
+            })
+    }
+
+    /// Takes the window back to fullscreen once the participant who left it
+    /// has returned
+    fn restore_fullscreen(&mut self) {
+        if !self.fullscreen_lost || self.experiment.is_interrupted() {
+            return;
+        }
+        if let Some(window) = &self.window {
+            let monitor = window.current_monitor();
+            window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+            window.focus_window();
+        }
+        self.fullscreen_lost = false;
+    }
+
+    /// Sends new results and phase changes to the session monitor
+    fn publish_progress(&mut self) {
+        #[cfg(feature = "monitor")]
+        if let Some(monitor) = &mut self.monitor {
+            monitor.trials(self.experiment.results());
+            monitor.phase(format!("{:?}", self.experiment.current_phase()));
+        }
+    }
+
+    /// Shows the cursor while something on screen can be clicked
+    fn update_cursor(&mut self) {
+        let visible = self.experiment.response_mapping.accepts_mouse()
+            || self.experiment.current_questionnaire().is_some()
+            || self
+                .experiment
+                .current_stimulus()
+                .is_some_and(|(s, _)| s.is_rating_scale());
+        if visible != self.cursor_visible
+            && let Some(window) = &self.window
+        {
+            window.set_cursor_visible(visible);
+            self.cursor_visible = visible;
+        }
+    }
+
+    /// Whether frames are needed regardless of input: always in continuous
+    /// mode, otherwise only as the state machine's next deadline approaches
+    fn needs_frames(&self) -> bool {
+        if self.experiment.config.redraw == RedrawMode::Continuous {
+            return true;
+        }
+        if self.form.is_some() {
+            return false;
+        }
+        let now = self.experiment.timer.now();
+        self.experiment
+            .next_deadline_ns()
+            .is_some_and(|at| at <= now + self.wake_margin_ns())
+    }
+
+    fn wake_margin_ns(&self) -> u64 {
+        self.refresh_rate
+            .map_or(WAKE_MARGIN_NS, |hz| (WAKE_FRAMES * 1e9 / hz) as u64)
+    }
+
+    /// When the loop has to wake to start drawing towards the next deadline,
+    /// or to poll input
+    fn wake_at(&self) -> Instant {
+        let poll = Instant::now() + IDLE_POLL;
+        let Some(at) = self.experiment.next_deadline_ns() else {
+            return poll;
+        };
+        let wait = at
+            .saturating_sub(self.wake_margin_ns())
+            .saturating_sub(self.experiment.timer.now());
+        poll.min(Instant::now() + Duration::from_nanos(wait))
+    }
+
+    fn screen_state(&self) -> ScreenState {
+        ScreenState {
+            phase: *self.experiment.current_phase(),
+            form: self.form.is_some(),
+            paused: self.experiment.is_paused(),
+            interrupted: self.experiment.is_interrupted(),
+            on_break: self.experiment.is_on_break(),
+            trial: self
+                .experiment
+                .current
+                .as_ref()
+                .map(|t| (t.id, t.state.clone())),
+            stimulus_shown: self.experiment.current_stimulus().is_some(),
+            page: self
+                .experiment
+                .current_instructions()
+                .map(|i| i.page_index()),
+            item: self
+                .experiment
+                .current_questionnaire()
+                .map(|q| q.item_index()),
+            rating: self.experiment.current_rating(),
+            aborted: self.experiment.is_aborted(),
+        }
+    }
+
+    fn request_redraw(&self) {
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Snaps a trial started since the last update to whole frames
+    fn schedule_trial(&mut self) {
+        if let (Some(scheduler), Some(trial)) = (&mut self.scheduler, &mut self.experiment.current)
+        {
+            scheduler.schedule(trial);
+        }
+    }
+
+    fn handle_input(&mut self, event: &KeyEvent, event_loop: &ActiveEventLoop) {
         use winit::keyboard::{KeyCode, PhysicalKey};
-        if let PhysicalKey::Code(k) = key {
-            match k {
-                KeyCode::Space => {
-                    if self.experiment.current_phase().is_welcome() {
-                        self.experiment.handle_event(ExperimentEvent::SpacePressed);
-                    }
-                    if self.experiment.current_phase().allows_input() {
-                        self.experiment
-                            .handle_event(ExperimentEvent::ResponseReceived);
-                    }
-                }
-                KeyCode::Escape => self.cleanup_and_exit(event_loop),
-                _ => {}
+        if let Some(form) = &mut self.form {
+            if event.physical_key == PhysicalKey::Code(KeyCode::Escape) {
+                self.cleanup_and_exit(event_loop);
+            } else if let FormAction::Submitted(info) = form.handle_key(event) {
+                self.form = None;
+                self.start_session(info);
+            }
+            return;
+        }
+        if let PhysicalKey::Code(k) = event.physical_key {
+            let modifiers = self.modifiers;
+            if let (Some(hotkey), Some(key)) =
+                (self.experiment.config.abort_hotkey, response_key(k))
+                && hotkey.matches(
+                    key,
+                    modifiers.control_key(),
+                    modifiers.shift_key(),
+                    modifiers.alt_key(),
+                )
+            {
+                self.experiment.handle_event(ExperimentEvent::Abort {
+                    reason: "experimenter hotkey".to_string(),
+                });
+            } else if k == KeyCode::Escape {
+                self.cleanup_and_exit(event_loop);
+            } else if k == KeyCode::Backspace {
+                self.experiment.handle_event(ExperimentEvent::PreviousPage);
+            } else if let Some(key) = response_key(k) {
+                self.input.push_key(key);
             }
         }
     }
 
+    fn handle_click(&mut self, button: winit::event::MouseButton) {
+        if self.form.is_some() {
+            return;
+        }
+        if let (Some(item), Some(renderer)) = (
+            self.experiment
+                .current_questionnaire()
+                .and_then(|q| q.item()),
+            &self.renderer,
+        ) {
+            if button == winit::event::MouseButton::Left
+                && let Some(option) = renderer.questionnaire_option_at(item, self.cursor)
+            {
+                self.experiment
+                    .handle_event(ExperimentEvent::QuestionnaireAnswer { option });
+            }
+            return;
+        }
+        let button = match button {
+            winit::event::MouseButton::Left => MouseButton::Left,
+            winit::event::MouseButton::Right => MouseButton::Right,
+            winit::event::MouseButton::Middle => MouseButton::Middle,
+            winit::event::MouseButton::Back => MouseButton::Other(3),
+            winit::event::MouseButton::Forward => MouseButton::Other(4),
+            winit::event::MouseButton::Other(n) => MouseButton::Other(n),
+        };
+        self.input.push_click(button);
+    }
+
+    /// Converts the cursor to stimulus coordinates for the click queue
+    fn handle_cursor(&mut self, cursor: winit::dpi::PhysicalPosition<f64>) {
+        let mut position = (cursor.x as f32, cursor.y as f32);
+        self.cursor = position;
+        if let (Some(display), Some(size)) = (self.experiment.config.display, self.current_size) {
+            let centre = (size.width as f32 * 0.5, size.height as f32 * 0.5);
+            position = display.px_to_position(position, centre);
+        } else {
+            // Stimulus positions are in logical pixels
+            let scale = self.scale_factor as f32;
+            position = (position.0 / scale, position.1 / scale);
+        }
+        self.input.set_cursor(position);
+    }
+
     fn handle_resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        // Minimised; the renderers keep their size until it is restored
+        if new_size.width == 0 || new_size.height == 0 {
+            return;
+        }
         self.current_size = Some(new_size);
+        self.experiment.set_screen(Screen::new(
+            new_size.width,
+            new_size.height,
+            self.scale_factor as f32,
+        ));
         if let Some(pixels) = &mut self.pixels {
             if let Err(e) = pixels.resize_surface(new_size.width, new_size.height) {
-                eprintln!("Failed to resize surface: {}", e);
+                warn!("Failed to resize surface: {}", e);
             }
             if let Err(e) = pixels.resize_buffer(new_size.width, new_size.height) {
-                eprintln!("Failed to resize buffer: {}", e);
+                warn!("Failed to resize buffer: {}", e);
+            }
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.resize(new_size.width, new_size.height);
+        }
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &mut self.gpu {
+            gpu.resize(new_size.width, new_size.height);
+        }
+        info!("Display resized to: {}×{}", new_size.width, new_size.height);
+    }
+    /// Resumes the participant's checkpoint, if any, and opens the session's
+    /// console and frame log
+    fn start_session(&mut self, info: SessionInfo) {
+        info!(
+            "Participant: {}, session {}",
+            info.participant_id, info.session
+        );
+        self.experiment.session = Some(info);
+        let path = self.checkpoint_path();
+        if path.exists() {
+            match self.experiment.resume_from_checkpoint(&path) {
+                Ok(()) => self.checkpointed = self.experiment.results().len(),
+                Err(e) => warn!("Failed to resume from checkpoint: {}", e),
+            }
+        }
+        self.console = Some(ControlConsole::spawn());
+        self.open_control();
+        self.open_scanner();
+        self.open_monitor();
+        self.open_frame_log();
+    }
+
+    fn open_control(&mut self) {
+        let config = &self.experiment.config;
+        if config.control_http.is_none() && config.control_osc.is_none() {
+            return;
+        }
+        #[cfg(feature = "control")]
+        match ControlServer::spawn(
+            config.control_http,
+            config.control_osc,
+            self.experiment.timer.clone(),
+        ) {
+            Ok(control) => self.control = Some(control),
+            Err(e) => warn!("Control server unavailable: {:#}", e),
+        }
+        #[cfg(not(feature = "control"))]
+        warn!("The control server needs the control feature, which this build lacks");
+    }
+
+    fn open_scanner(&mut self) {
+        let Some(config) = &self.experiment.config.scanner else {
+            return;
+        };
+        if config.serial_port.is_none() && config.parallel_port.is_none() {
+            return;
+        }
+        #[cfg(feature = "scanner")]
+        {
+            let timer = &self.experiment.timer;
+            if let Some(path) = &config.serial_port {
+                match cogex_input::ScannerTrigger::serial(
+                    path,
+                    config.baud_rate,
+                    timer,
+                    self.input.sender(),
+                ) {
+                    Ok(trigger) => self.scanner.push(trigger),
+                    Err(e) => warn!("Scanner trigger on {} unavailable: {:#}", path, e),
+                }
+            }
+            if let Some(path) = &config.parallel_port {
+                #[cfg(target_os = "linux")]
+                match cogex_input::ScannerTrigger::parallel(path, timer, self.input.sender()) {
+                    Ok(trigger) => self.scanner.push(trigger),
+                    Err(e) => warn!("Scanner trigger on {} unavailable: {:#}", path, e),
+                }
+                #[cfg(not(target_os = "linux"))]
+                warn!("Scanner trigger on {} needs Linux", path);
+            }
+        }
+        #[cfg(not(feature = "scanner"))]
+        warn!("Scanner trigger ports need the scanner feature, which this build lacks");
+    }
+
+    fn open_monitor(&mut self) {
+        let Some(addr) = self.experiment.config.monitor_server else {
+            return;
+        };
+        #[cfg(feature = "monitor")]
+        {
+            let config = &self.experiment.config;
+            let total = config.practice_trials + config.total_experiment_trials();
+            match SessionMonitor::bind(addr, total) {
+                Ok(monitor) => self.monitor = Some(monitor),
+                Err(e) => warn!("Session monitor unavailable: {:#}", e),
+            }
+        }
+        #[cfg(not(feature = "monitor"))]
+        warn!(
+            "Session monitor on {} needs the monitor feature, which this build lacks",
+            addr
+        );
+    }
+
+    /// Creates the session's output manager unless it exists
+    fn ensure_output(&mut self) {
+        if self.output.is_none() {
+            match OutputManager::new(
+                Path::new(DEFAULT_OUTPUT_ROOT),
+                &self.experiment.config.task,
+                self.experiment.session.as_ref(),
+            ) {
+                Ok(output) => self.output = Some(output),
+                Err(e) => warn!("Output directory unavailable: {}", e),
+            }
+        }
+    }
+
+    fn open_frame_log(&mut self) {
+        self.ensure_output();
+        let Some(path) = self.output.as_ref().map(|o| o.path("_frames.bin.gz")) else {
+            return;
+        };
+        match FrameLog::create(&path) {
+            Ok(log) => {
+                info!("Logging frame timing to {}", path.display());
+                self.frame_log = Some(log);
             }
+            Err(e) => warn!("Frame timing will not be logged: {}", e),
         }
-        self.renderer
-            .as_mut()
-            .unwrap()
-            .resize(new_size.width, new_size.height);
-        println!("Display resized to: {}×{}", new_size.width, new_size.height);
     }
+
     fn cleanup_and_exit(&mut self, event_loop: &ActiveEventLoop) {
         if let Some(window) = &self.window {
             window.set_cursor_visible(true);
         }
-
-        println!("\nExperiment completed.");
-        println!("Results saved. Thank you!");
-
+        self.save_results();
         self.should_exit = true;
         event_loop.exit();
     }
 
+    /// Writes everything collected so far; later calls do nothing
+    fn save_results(&mut self) {
+        if std::mem::replace(&mut self.saved, true) {
+            return;
+        }
+        if let Some(log) = self.frame_log.take()
+            && let Err(e) = log.finish()
+        {
+            warn!("Failed to finish frame log: {}", e);
+        }
+
+        match self.experiment.abort_reason() {
+            Some(reason) => info!("Session aborted ({}), saving partial data", reason),
+            None => info!("Experiment completed."),
+        }
+        self.publish_progress();
+        #[cfg(feature = "monitor")]
+        if let Some(monitor) = &mut self.monitor {
+            monitor.ended(self.experiment.abort_reason());
+        }
+        self.experiment.sample_clock();
+        self.ensure_output();
+        let Some(output) = &self.output else {
+            return;
+        };
+        let mut metadata = self
+            .experiment
+            .metadata()
+            .with_crate("cogex-app", env!("CARGO_PKG_VERSION"))
+            .with_crate("cogex-render", cogex_render::VERSION);
+        if let (Some(window), Some(size)) = (&self.window, self.current_size) {
+            metadata = metadata.with_display(
+                DisplayInfo::new(size.width, size.height, self.scale_factor)
+                    .with_monitor(window.current_monitor().and_then(|m| m.name()))
+                    .with_refresh_hz(self.refresh_rate)
+                    .with_geometry(self.experiment.config.display),
+            );
+        }
+        match output.write_metadata(&metadata) {
+            Ok(path) => info!("Session metadata saved to {}", path.display()),
+            Err(e) => warn!("Failed to save session metadata: {}", e),
+        }
+        if let Some(questionnaire) = &self.experiment.questionnaire
+            && questionnaire.answers().iter().any(Option::is_some)
+        {
+            match cogex_experiment::write_questionnaire(
+                output,
+                self.experiment.session.as_ref(),
+                questionnaire,
+            ) {
+                Ok(path) => info!("Questionnaire saved to {}", path.display()),
+                Err(e) => warn!("Failed to save questionnaire: {}", e),
+            }
+        }
+        if !self.experiment.results().is_empty() {
+            match cogex_experiment::write_results(
+                output,
+                self.experiment.session.as_ref(),
+                self.experiment.results(),
+            ) {
+                Ok(path) => {
+                    info!("Results saved to {}. Thank you!", path.display());
+                    // A finished session has nothing left to resume
+                    if self.experiment.is_final_phase() {
+                        let _ = std::fs::remove_file(self.checkpoint_path());
+                    }
+                }
+                Err(e) => warn!("Failed to save results: {}", e),
+            }
+            match cogex_experiment::write_bids_events(
+                output,
+                self.experiment.session.as_ref(),
+                &self.experiment.config.task,
+                self.experiment.results(),
+                self.experiment.logged_markers(),
+                self.experiment.scanner_zero_ns(),
+            ) {
+                Ok(path) => info!("BIDS events saved to {}", path.display()),
+                Err(e) => warn!("Failed to save BIDS events: {}", e),
+            }
+            #[cfg(feature = "parquet")]
+            match cogex_experiment::write_results_parquet(
+                output,
+                self.experiment.session.as_ref(),
+                self.experiment.results(),
+            ) {
+                Ok(path) => info!("Results also saved to {}", path.display()),
+                Err(e) => warn!("Failed to save Parquet results: {}", e),
+            }
+        }
+    }
+
+    fn checkpoint_path(&self) -> PathBuf {
+        cogex_experiment::checkpoint_path(Path::new("."), self.experiment.session.as_ref())
+    }
+
     fn load_icon(bytes: &[u8]) -> Icon {
         let (icon_rgba, icon_width, icon_height) = {
             let image = image::load_from_memory(bytes).unwrap().into_rgba8();
@@ -238,54 +1044,208 @@ impl App {
     }
 }
 
+/// Status lines for the experimenter view
+fn experimenter_status(
+    experiment: &ExperimentStateMachine<StandardPhase, StimulusType, HighPrecisionTimer, StdRng>,
+) -> Vec<String> {
+    let trial = match experiment.trial_progress() {
+        Some((current, total)) => format!("Trial: {}/{}", current, total),
+        None => "Trial: -".to_string(),
+    };
+    let rt = match experiment.results().last().and_then(|r| r.reaction_time_ns) {
+        Some(ns) => format!("Last RT: {:.0} ms", ns as f64 / 1e6),
+        None => "Last RT: -".to_string(),
+    };
+    let stats = experiment.timer.calibration_stats();
+    let refresh = match (stats.estimated_refresh_hz, stats.reported_refresh_hz) {
+        (Some(hz), Some(reported)) if stats.refresh_mismatch() => {
+            format!("Refresh: {:.1} Hz, MONITOR REPORTS {:.1} Hz", hz, reported)
+        }
+        (Some(hz), _) => format!("Refresh: {:.1} Hz", hz),
+        (None, _) => "Refresh: -".to_string(),
+    };
+    let mut lines = vec![
+        format!("Phase: {:?}", experiment.current_phase()),
+        trial,
+        rt,
+        format!("Dropped frames: {}", experiment.timer.dropped_frames()),
+        refresh,
+    ];
+    if experiment.is_interrupted() {
+        lines.push("PARTICIPANT LEFT THE WINDOW".to_string());
+    }
+    lines
+}
+
+/// Translates a winit key into the backend-independent response key
+fn response_key(code: winit::keyboard::KeyCode) -> Option<ResponseKey> {
+    use winit::keyboard::KeyCode;
+    Some(match code {
+        KeyCode::Space => ResponseKey::Space,
+        KeyCode::ArrowLeft => ResponseKey::ArrowLeft,
+        KeyCode::ArrowRight => ResponseKey::ArrowRight,
+        KeyCode::ArrowUp => ResponseKey::ArrowUp,
+        KeyCode::ArrowDown => ResponseKey::ArrowDown,
+        KeyCode::KeyA => ResponseKey::Char('a'),
+        KeyCode::KeyB => ResponseKey::Char('b'),
+        KeyCode::KeyC => ResponseKey::Char('c'),
+        KeyCode::KeyD => ResponseKey::Char('d'),
+        KeyCode::KeyE => ResponseKey::Char('e'),
+        KeyCode::KeyF => ResponseKey::Char('f'),
+        KeyCode::KeyG => ResponseKey::Char('g'),
+        KeyCode::KeyH => ResponseKey::Char('h'),
+        KeyCode::KeyI => ResponseKey::Char('i'),
+        KeyCode::KeyJ => ResponseKey::Char('j'),
+        KeyCode::KeyK => ResponseKey::Char('k'),
+        KeyCode::KeyL => ResponseKey::Char('l'),
+        KeyCode::KeyM => ResponseKey::Char('m'),
+        KeyCode::KeyN => ResponseKey::Char('n'),
+        KeyCode::KeyO => ResponseKey::Char('o'),
+        KeyCode::KeyP => ResponseKey::Char('p'),
+        KeyCode::KeyQ => ResponseKey::Char('q'),
+        KeyCode::KeyR => ResponseKey::Char('r'),
+        KeyCode::KeyS => ResponseKey::Char('s'),
+        KeyCode::KeyT => ResponseKey::Char('t'),
+        KeyCode::KeyU => ResponseKey::Char('u'),
+        KeyCode::KeyV => ResponseKey::Char('v'),
+        KeyCode::KeyW => ResponseKey::Char('w'),
+        KeyCode::KeyX => ResponseKey::Char('x'),
+        KeyCode::KeyY => ResponseKey::Char('y'),
+        KeyCode::KeyZ => ResponseKey::Char('z'),
+        KeyCode::Digit0 => ResponseKey::Char('0'),
+        KeyCode::Digit1 => ResponseKey::Char('1'),
+        KeyCode::Digit2 => ResponseKey::Char('2'),
+        KeyCode::Digit3 => ResponseKey::Char('3'),
+        KeyCode::Digit4 => ResponseKey::Char('4'),
+        KeyCode::Digit5 => ResponseKey::Char('5'),
+        KeyCode::Digit6 => ResponseKey::Char('6'),
+        KeyCode::Digit7 => ResponseKey::Char('7'),
+        KeyCode::Digit8 => ResponseKey::Char('8'),
+        KeyCode::Digit9 => ResponseKey::Char('9'),
+        _ => return None,
+    })
+}
+
 impl Default for App {
     fn default() -> Self {
-        Self::new().expect("Failed to create application")
+        let registry = ParadigmRegistry::new();
+        Self::new(ExperimentConfig::default(), &registry, "default", None)
+            .expect("Failed to create application")
     }
 }
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if self.window.is_none() {
-            if let Err(e) = self.create_window_and_surface(event_loop) {
-                eprintln!("Failed to create window and surface: {}", e);
-                event_loop.exit();
-            }
+        if self.window.is_none()
+            && let Err(e) = self.create_window_and_surface(event_loop)
+        {
+            error!("Failed to create window and surface: {}", e);
+            event_loop.exit();
         }
     }
 
-    fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
+        if self.mirror.as_ref().is_some_and(|m| m.id() == id) {
+            if let WindowEvent::CloseRequested = event {
+                self.mirror = None;
+            }
+            return;
+        }
+        if let WindowEvent::Focused(focused) = event {
+            self.input.set_focused(focused);
+        }
         match event {
             WindowEvent::CloseRequested => self.cleanup_and_exit(event_loop),
             WindowEvent::RedrawRequested => {
+                let before = self.screen_state();
                 self.render().unwrap();
                 self.update().unwrap();
-                if let Some(win) = &self.window {
-                    win.request_redraw();
+                if self.needs_frames() || self.screen_state() != before {
+                    self.request_redraw();
                 }
             }
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers.state(),
             WindowEvent::KeyboardInput { event, .. } if event.state.is_pressed() => {
-                self.handle_input(event.physical_key, event_loop);
+                self.handle_input(&event, event_loop);
+                self.request_redraw();
+            }
+            WindowEvent::CursorMoved { position, .. } => self.handle_cursor(position),
+            WindowEvent::MouseInput { state, button, .. } if state.is_pressed() => {
+                self.handle_click(button);
+                self.request_redraw();
+            }
+            WindowEvent::Resized(sz) => {
+                self.handle_resize(sz);
+                if !self.fullscreen_lost
+                    && self
+                        .window
+                        .as_ref()
+                        .is_some_and(|w| w.fullscreen().is_none())
+                {
+                    self.fullscreen_lost = true;
+                    self.interrupt("fullscreen_exited");
+                }
+                self.request_redraw();
+            }
+            WindowEvent::Focused(false) if self.interrupts_on_focus_loss() => {
+                self.interrupt("focus_lost");
+                self.request_redraw();
             }
-            WindowEvent::Resized(sz) => self.handle_resize(sz),
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 self.scale_factor = scale_factor;
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.set_scale_factor(scale_factor as f32);
+                }
+                #[cfg(feature = "gpu")]
+                if let Some(gpu) = &mut self.gpu {
+                    gpu.set_scale_factor(scale_factor as f32);
+                }
                 if let Some(window) = &self.window {
                     self.handle_resize(window.inner_size());
                 }
+                self.request_redraw();
             }
             _ => {}
         }
     }
 
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
+        // Idle screens still have to notice console commands and box presses
+        if let StartCause::ResumeTimeReached { .. } = cause
+            && self.window.is_some()
+        {
+            let before = self.screen_state();
+            if let Err(e) = self.update() {
+                warn!("Update failed: {}", e);
+            }
+            if self.needs_frames() || self.screen_state() != before {
+                self.request_redraw();
+            }
+        }
+    }
+
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
         if self.should_exit {
             event_loop.exit();
+            return;
+        }
+        if self.needs_frames() {
+            event_loop.set_control_flow(ControlFlow::Wait);
+            return;
+        }
+        // The gap until the next frame is deliberate, not dropped frames
+        self.experiment.timer.reset_present();
+        let mut wake = self.wake_at();
+        if let ControlFlow::WaitUntil(at) = event_loop.control_flow()
+            && at > Instant::now()
+        {
+            wake = wake.min(at);
         }
+        event_loop.set_control_flow(ControlFlow::WaitUntil(wake));
     }
 }
 
 impl Drop for App {
     fn drop(&mut self) {
-        println!("Application resources cleaned up");
+        info!("Application resources cleaned up");
     }
 }
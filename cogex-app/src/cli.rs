@@ -0,0 +1,156 @@
+use crate::app::App;
+use crate::{logging, report};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use cogex_core::StandardPhase;
+use cogex_experiment::{ExperimentConfig, ParadigmRegistry, SessionInfo, DEFAULT_OUTPUT_ROOT};
+use cogex_sim::SimulateOptions;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "cogex",
+    version,
+    about = "Timing-critical cognitive experiments"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Runs an experiment with a participant
+    Run {
+        /// Registered paradigm, e.g. stroop; `default` runs the generated
+        /// shape trials
+        experiment: String,
+        /// TOML file overriding the default settings
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Skips the participant form
+        #[arg(long)]
+        participant: Option<String>,
+        #[arg(long, default_value_t = 1, requires = "participant")]
+        session: u32,
+        /// Saves a PNG of each screen and trial state into this directory,
+        /// overriding the config's `snapshot_dir`
+        #[arg(long, value_name = "DIR")]
+        snapshots: Option<PathBuf>,
+    },
+    /// Checks a config file and prints the settings it resolves to
+    ValidateConfig { config: PathBuf },
+    /// Runs an experiment headlessly with a simulated participant
+    Simulate {
+        /// Registered paradigm, e.g. stroop; `default` runs the generated
+        /// shape trials
+        experiment: String,
+        #[arg(long)]
+        config: Option<PathBuf>,
+        #[arg(long, default_value = "sim")]
+        participant: String,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Root of the output directory
+        #[arg(long, default_value = DEFAULT_OUTPUT_ROOT)]
+        out: PathBuf,
+        /// Scripted responses instead of a simulated participant
+        #[arg(long, conflicts_with_all = ["accuracy", "rt"])]
+        script: Option<PathBuf>,
+        #[arg(long, default_value_t = 0.9)]
+        accuracy: f64,
+        /// RT mean and optional SD in ms, e.g. 450,80
+        #[arg(
+            long,
+            value_name = "MEAN_MS[,SD_MS]",
+            value_parser = parse_rt,
+            default_value = "450,80"
+        )]
+        rt: (f64, f64),
+        #[arg(long, default_value_t = 60.0)]
+        refresh: f64,
+    },
+    /// Summarises a session's frame timing log
+    Report { frames: PathBuf },
+}
+
+fn parse_rt(value: &str) -> Result<(f64, f64)> {
+    let (mean, sd) = value.split_once(',').unwrap_or((value, "80"));
+    Ok((
+        mean.parse().context("Invalid RT mean")?,
+        sd.parse().context("Invalid RT SD")?,
+    ))
+}
+
+fn load_config(path: Option<&Path>) -> Result<ExperimentConfig<StandardPhase>> {
+    path.map_or_else(|| Ok(ExperimentConfig::default()), ExperimentConfig::load)
+}
+
+/// Runs `experiment` in a window with logging set up, as `cogex run` does;
+/// without a `session` the participant form asks for one
+pub fn run(
+    config: ExperimentConfig<StandardPhase>,
+    registry: &ParadigmRegistry<StandardPhase>,
+    experiment: &str,
+    session: Option<SessionInfo>,
+) -> Result<()> {
+    let _log = logging::init()?;
+    App::new(config, registry, experiment, session)?.run()
+}
+
+/// Parses the command line and runs it with the paradigms in `registry`. A
+/// lab's own binary registers its paradigms and calls this.
+pub fn main(registry: ParadigmRegistry<StandardPhase>) -> Result<()> {
+    match Cli::parse().command {
+        Command::Run {
+            experiment,
+            config,
+            participant,
+            session,
+            snapshots,
+        } => {
+            let mut config = load_config(config.as_deref())?;
+            if snapshots.is_some() {
+                config.snapshot_dir = snapshots;
+            }
+            let session = participant.map(|id| SessionInfo::new(id, None, session));
+            run(config, &registry, &experiment, session)
+        }
+        Command::ValidateConfig { config } => {
+            let config = load_config(Some(&config))?;
+            print!("{}", toml::to_string_pretty(&config)?);
+            Ok(())
+        }
+        Command::Simulate {
+            experiment,
+            config,
+            participant,
+            seed,
+            out,
+            script,
+            accuracy,
+            rt,
+            refresh,
+        } => {
+            // Experiment logs are per-trial noise here; `COGEX_LOG=info` shows them
+            let filter = tracing_subscriber::EnvFilter::try_from_env("COGEX_LOG")
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn"));
+            tracing_subscriber::fmt().with_env_filter(filter).init();
+            cogex_sim::simulate(
+                SimulateOptions {
+                    experiment,
+                    config: load_config(config.as_deref())?,
+                    participant,
+                    seed,
+                    out,
+                    script,
+                    accuracy,
+                    rt,
+                    refresh_hz: refresh,
+                },
+                &registry,
+            )
+        }
+        Command::Report { frames } => report::run(&frames),
+    }
+}
@@ -0,0 +1,59 @@
+use cogex_experiment::ExperimentEvent;
+use std::io::{BufRead, IsTerminal};
+use std::sync::mpsc::{self, Receiver};
+
+const HELP: &str = "Commands: pause (p), resume (r), skip (s), break (b), abort (q), help (h)";
+
+/// Experimenter commands typed on stdin, read on a background thread
+pub struct ControlConsole {
+    receiver: Receiver<ExperimentEvent>,
+}
+
+impl ControlConsole {
+    pub fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            println!("{}", HELP);
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match parse_command(&line) {
+                    Some(event) => {
+                        if sender.send(event).is_err() {
+                            break;
+                        }
+                    }
+                    None if line.trim().is_empty() => {}
+                    None => println!("{}", HELP),
+                }
+            }
+        });
+        Self { receiver }
+    }
+
+    /// Whether commands are typed at a terminal, which takes the focus
+    /// from the participant window while the experimenter does so
+    pub fn is_interactive(&self) -> bool {
+        std::io::stdin().is_terminal()
+    }
+
+    /// Commands received since the last poll
+    pub fn poll(&mut self) -> Vec<ExperimentEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+/// Event for a console command, by name or initial
+pub fn parse_command(line: &str) -> Option<ExperimentEvent> {
+    Some(match line.trim().to_lowercase().as_str() {
+        "pause" | "p" => ExperimentEvent::Pause,
+        "resume" | "r" => ExperimentEvent::Resume,
+        "skip" | "s" => ExperimentEvent::SkipBlock,
+        "break" | "b" => ExperimentEvent::InsertBreak,
+        "abort" | "q" => ExperimentEvent::Abort {
+            reason: "experimenter console".to_string(),
+        },
+        _ => return None,
+    })
+}
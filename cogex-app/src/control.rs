@@ -0,0 +1,155 @@
+use anyhow::{anyhow, Context, Result};
+use cogex_experiment::ExperimentEvent;
+use cogex_timing::{HighPrecisionTimer, Timer};
+use rosc::{OscPacket, OscType};
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::mpsc::{self, Receiver, Sender};
+use tiny_http::{Method, Response, Server};
+use tracing::{info, warn};
+
+/// Prefix of the OSC addresses commands are sent to, e.g. `/cogex/pause`
+const OSC_PREFIX: &str = "/cogex/";
+
+/// Commands from an external orchestrator or scanner computer, taken over
+/// HTTP (`POST /pause`, `POST /marker` with the label as the body) or OSC
+/// (`/cogex/pause`, `/cogex/marker "label"`), each on its own thread.
+///
+/// Commands are `start` (Space on a waiting screen), `pause`, `resume`,
+/// `skip`, `break`, `marker` with a label and `abort` with an optional
+/// reason. Markers are stamped when they arrive.
+pub struct ControlServer {
+    receiver: Receiver<ExperimentEvent>,
+}
+
+impl ControlServer {
+    pub fn spawn(
+        http: Option<SocketAddr>,
+        osc: Option<SocketAddr>,
+        timer: HighPrecisionTimer,
+    ) -> Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        if let Some(addr) = http {
+            let server = Server::http(addr)
+                .map_err(|e| anyhow!("Failed to listen for HTTP on {}: {}", addr, e))?;
+            info!("Control server listening on http://{}", addr);
+            let (sender, timer) = (sender.clone(), timer.clone());
+            std::thread::spawn(move || serve_http(server, sender, timer));
+        }
+        if let Some(addr) = osc {
+            let socket = UdpSocket::bind(addr)
+                .with_context(|| format!("Failed to listen for OSC on {}", addr))?;
+            info!("Control server listening for OSC on udp://{}", addr);
+            std::thread::spawn(move || serve_osc(socket, sender, timer));
+        }
+        Ok(Self { receiver })
+    }
+
+    /// Commands received since the last poll
+    pub fn poll(&mut self) -> Vec<ExperimentEvent> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+fn serve_http(server: Server, sender: Sender<ExperimentEvent>, timer: HighPrecisionTimer) {
+    for mut request in server.incoming_requests() {
+        let timestamp_ns = timer.now();
+        let (status, text) = if *request.method() != Method::Post {
+            (405, "Commands are POSTed".to_string())
+        } else {
+            let command = request.url().trim_start_matches('/').to_string();
+            let mut body = String::new();
+            let _ = request.as_reader().read_to_string(&mut body);
+            let argument = Some(body.trim().to_string()).filter(|b| !b.is_empty());
+            match command_event(&command, argument, timestamp_ns) {
+                Ok(event) => {
+                    if sender.send(event).is_err() {
+                        break;
+                    }
+                    (202, "ok".to_string())
+                }
+                Err(e) => (400, e),
+            }
+        };
+        let response = Response::from_string(format!("{}\n", text)).with_status_code(status);
+        if let Err(e) = request.respond(response) {
+            warn!("Failed to answer control request: {}", e);
+        }
+    }
+}
+
+fn serve_osc(socket: UdpSocket, sender: Sender<ExperimentEvent>, timer: HighPrecisionTimer) {
+    let mut buffer = [0u8; rosc::decoder::MTU];
+    loop {
+        let size = match socket.recv(&mut buffer) {
+            Ok(size) => size,
+            Err(e) => {
+                warn!("OSC control socket failed: {}", e);
+                return;
+            }
+        };
+        let timestamp_ns = timer.now();
+        let packet = match rosc::decoder::decode_udp(&buffer[..size]) {
+            Ok((_, packet)) => packet,
+            Err(e) => {
+                warn!("Ignoring malformed OSC packet: {}", e);
+                continue;
+            }
+        };
+        for event in osc_events(packet, timestamp_ns) {
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Events for the messages in `packet`, bundles included
+pub fn osc_events(packet: OscPacket, timestamp_ns: u64) -> Vec<ExperimentEvent> {
+    match packet {
+        OscPacket::Message(message) => {
+            let Some(command) = message.addr.strip_prefix(OSC_PREFIX) else {
+                warn!("Ignoring OSC message to {}", message.addr);
+                return Vec::new();
+            };
+            let argument = message.args.into_iter().find_map(|arg| match arg {
+                OscType::String(text) => Some(text),
+                _ => None,
+            });
+            match command_event(command, argument, timestamp_ns) {
+                Ok(event) => vec![event],
+                Err(e) => {
+                    warn!("Ignoring OSC message to {}: {}", message.addr, e);
+                    Vec::new()
+                }
+            }
+        }
+        OscPacket::Bundle(bundle) => bundle
+            .content
+            .into_iter()
+            .flat_map(|packet| osc_events(packet, timestamp_ns))
+            .collect(),
+    }
+}
+
+/// The event for a command name and its label or reason
+pub fn command_event(
+    command: &str,
+    argument: Option<String>,
+    timestamp_ns: u64,
+) -> Result<ExperimentEvent, String> {
+    Ok(match command {
+        "start" => ExperimentEvent::SpacePressed,
+        "pause" => ExperimentEvent::Pause,
+        "resume" => ExperimentEvent::Resume,
+        "skip" => ExperimentEvent::SkipBlock,
+        "break" => ExperimentEvent::InsertBreak,
+        "abort" => ExperimentEvent::Abort {
+            reason: argument.unwrap_or_else(|| "control server".to_string()),
+        },
+        "marker" => ExperimentEvent::Marker {
+            label: argument.ok_or("marker needs a label")?,
+            timestamp_ns: Some(timestamp_ns),
+        },
+        _ => return Err(format!("Unknown command {:?}", command)),
+    })
+}
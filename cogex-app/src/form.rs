@@ -0,0 +1,132 @@
+use cogex_experiment::SessionInfo;
+use winit::event::KeyEvent;
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldKind {
+    Text,
+    Number,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    label: &'static str,
+    value: String,
+    kind: FieldKind,
+    required: bool,
+}
+
+/// Outcome of feeding a key press to the form
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormAction {
+    Editing,
+    Submitted(SessionInfo),
+}
+
+/// Pre-experiment participant/session entry form.
+/// Tab/Enter move to the next field; Enter on the last field submits.
+#[derive(Debug, Clone)]
+pub struct MetadataForm {
+    fields: Vec<Field>,
+    active: usize,
+}
+
+impl MetadataForm {
+    pub const TITLE: &'static str = "SESSION SETUP";
+
+    pub fn new() -> Self {
+        Self {
+            fields: vec![
+                Field {
+                    label: "Participant ID",
+                    value: String::new(),
+                    kind: FieldKind::Text,
+                    required: true,
+                },
+                Field {
+                    label: "Age",
+                    value: String::new(),
+                    kind: FieldKind::Number,
+                    required: false,
+                },
+                Field {
+                    label: "Session",
+                    value: "1".to_string(),
+                    kind: FieldKind::Number,
+                    required: true,
+                },
+            ],
+            active: 0,
+        }
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// `(label, value)` pairs for rendering
+    pub fn lines(&self) -> Vec<(&str, &str)> {
+        self.fields
+            .iter()
+            .map(|f| (f.label, f.value.as_str()))
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, event: &KeyEvent) -> FormAction {
+        match event.physical_key {
+            PhysicalKey::Code(KeyCode::Backspace) => {
+                self.fields[self.active].value.pop();
+            }
+            PhysicalKey::Code(KeyCode::Tab) => {
+                self.active = (self.active + 1) % self.fields.len();
+            }
+            PhysicalKey::Code(KeyCode::Enter | KeyCode::NumpadEnter) => {
+                if self.active + 1 < self.fields.len() {
+                    self.active += 1;
+                } else if let Some(info) = self.submit() {
+                    return FormAction::Submitted(info);
+                }
+            }
+            _ => {
+                if let Some(text) = &event.text {
+                    let field = &mut self.fields[self.active];
+                    for c in text.chars() {
+                        let accepted = match field.kind {
+                            FieldKind::Text => !c.is_control(),
+                            FieldKind::Number => c.is_ascii_digit(),
+                        };
+                        if accepted && field.value.len() < 32 {
+                            field.value.push(c);
+                        }
+                    }
+                }
+            }
+        }
+        FormAction::Editing
+    }
+
+    /// Validates the fields, jumping to the first invalid one on failure
+    fn submit(&mut self) -> Option<SessionInfo> {
+        if let Some(i) = self
+            .fields
+            .iter()
+            .position(|f| f.required && f.value.trim().is_empty())
+        {
+            self.active = i;
+            return None;
+        }
+
+        let age = self.fields[1].value.parse().ok();
+        let Ok(session) = self.fields[2].value.parse() else {
+            self.active = 2;
+            return None;
+        };
+        Some(SessionInfo::new(self.fields[0].value.trim(), age, session))
+    }
+}
+
+impl Default for MetadataForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{bail, Context, Result};
+use cogex_render::render::FrameStats;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+const MAGIC: &[u8; 4] = b"CXFL";
+const VERSION: u8 = 1;
+/// Six `u64` fields, the dirty count and the trial id
+const RECORD_LEN: usize = 6 * 8 + 4 + 4;
+/// Trial id written for frames outside a trial
+const NO_TRIAL: u32 = u32::MAX;
+
+/// Timing of one presented frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameRecord {
+    /// Timer reading after the frame was presented
+    pub presented_ns: u64,
+    pub clear_ns: u64,
+    pub phase_ns: u64,
+    pub copy_ns: u64,
+    pub total_ns: u64,
+    /// Time spent handing the frame to the surface
+    pub present_ns: u64,
+    pub dirty_count: u32,
+    pub trial: Option<u32>,
+}
+
+impl FrameRecord {
+    pub fn new(
+        presented_ns: u64,
+        stats: &FrameStats,
+        present: Duration,
+        trial: Option<usize>,
+    ) -> Self {
+        Self {
+            presented_ns,
+            clear_ns: stats.clear.as_nanos() as u64,
+            phase_ns: stats.phase.as_nanos() as u64,
+            copy_ns: stats.copy.as_nanos() as u64,
+            total_ns: stats.total.as_nanos() as u64,
+            present_ns: present.as_nanos() as u64,
+            dirty_count: stats.dirty_count as u32,
+            trial: trial.map(|t| t as u32),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; RECORD_LEN] {
+        let mut bytes = [0u8; RECORD_LEN];
+        let fields = [
+            self.presented_ns,
+            self.clear_ns,
+            self.phase_ns,
+            self.copy_ns,
+            self.total_ns,
+            self.present_ns,
+        ];
+        for (chunk, field) in bytes.chunks_exact_mut(8).zip(fields) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+        bytes[48..52].copy_from_slice(&self.dirty_count.to_le_bytes());
+        bytes[52..56].copy_from_slice(&self.trial.unwrap_or(NO_TRIAL).to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; RECORD_LEN]) -> Self {
+        let u64_at = |i: usize| u64::from_le_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap());
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let trial = u32_at(52);
+        Self {
+            presented_ns: u64_at(0),
+            clear_ns: u64_at(1),
+            phase_ns: u64_at(2),
+            copy_ns: u64_at(3),
+            total_ns: u64_at(4),
+            present_ns: u64_at(5),
+            dirty_count: u32_at(48),
+            trial: (trial != NO_TRIAL).then_some(trial),
+        }
+    }
+}
+
+/// Gzip-compressed log of fixed-size little-endian frame records
+pub struct FrameLog {
+    writer: GzEncoder<BufWriter<File>>,
+}
+
+impl FrameLog {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        let mut writer = GzEncoder::new(BufWriter::new(file), Compression::fast());
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(Self { writer })
+    }
+
+    pub fn write(&mut self, record: FrameRecord) -> Result<()> {
+        self.writer.write_all(&record.to_bytes())?;
+        Ok(())
+    }
+
+    /// Flushes buffered records; without it the tail of the log is lost
+    pub fn finish(self) -> Result<()> {
+        self.writer.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+pub fn read_frame_log(path: &Path) -> Result<Vec<FrameRecord>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut reader = GzDecoder::new(BufReader::new(file));
+    let mut header = [0u8; 5];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        bail!("{} is not a frame log", path.display());
+    }
+    if header[4] != VERSION {
+        bail!("Unsupported frame log version {}", header[4]);
+    }
+    let mut records = Vec::new();
+    let mut bytes = [0u8; RECORD_LEN];
+    loop {
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => records.push(FrameRecord::from_bytes(&bytes)),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(records)
+}
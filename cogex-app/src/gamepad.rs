@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+use cogex_core::ResponseKey;
+use gilrs::{Axis, Button, EventType, Gilrs};
+use std::collections::{HashMap, HashSet};
+use std::time::SystemTime;
+use tracing::info;
+
+/// Maps gamepad buttons and stick directions to response keys
+#[derive(Debug, Clone)]
+pub struct GamepadMapping {
+    buttons: HashMap<Button, ResponseKey>,
+    /// `(axis, positive direction)` to key
+    axes: HashMap<(Axis, bool), ResponseKey>,
+    /// Deflection in 0..=1 that counts as a press
+    threshold: f32,
+}
+
+impl GamepadMapping {
+    pub fn new() -> Self {
+        Self {
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+            threshold: 0.6,
+        }
+    }
+
+    pub fn with_button(mut self, button: Button, key: ResponseKey) -> Self {
+        self.buttons.insert(button, key);
+        self
+    }
+
+    /// Maps deflecting `axis` in the positive or negative direction to `key`
+    pub fn with_axis(mut self, axis: Axis, positive: bool, key: ResponseKey) -> Self {
+        self.axes.insert((axis, positive), key);
+        self
+    }
+
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold.clamp(0.05, 1.0);
+        self
+    }
+}
+
+impl Default for GamepadMapping {
+    /// South button is Space; D-pad and left stick are the arrow keys
+    fn default() -> Self {
+        Self::new()
+            .with_button(Button::South, ResponseKey::Space)
+            .with_button(Button::DPadLeft, ResponseKey::ArrowLeft)
+            .with_button(Button::DPadRight, ResponseKey::ArrowRight)
+            .with_button(Button::DPadUp, ResponseKey::ArrowUp)
+            .with_button(Button::DPadDown, ResponseKey::ArrowDown)
+            .with_axis(Axis::LeftStickX, false, ResponseKey::ArrowLeft)
+            .with_axis(Axis::LeftStickX, true, ResponseKey::ArrowRight)
+            .with_axis(Axis::LeftStickY, true, ResponseKey::ArrowUp)
+            .with_axis(Axis::LeftStickY, false, ResponseKey::ArrowDown)
+    }
+}
+
+/// Polls connected gamepads and translates input into response keys.
+///
+/// Stick deflections fire once when crossing the threshold and re-arm only
+/// after the stick returns below half of it.
+pub struct GamepadInput {
+    gilrs: Gilrs,
+    mapping: GamepadMapping,
+    deflected: HashSet<(Axis, bool)>,
+}
+
+impl GamepadInput {
+    pub fn new(mapping: GamepadMapping) -> Result<Self> {
+        let gilrs = Gilrs::new().map_err(|e| anyhow!("Gamepad init failed: {}", e))?;
+        for (_, gamepad) in gilrs.gamepads() {
+            info!("Gamepad connected: {}", gamepad.name());
+        }
+        Ok(Self {
+            gilrs,
+            mapping,
+            deflected: HashSet::new(),
+        })
+    }
+
+    /// Drains pending gamepad events, returning the keys pressed since the
+    /// last poll with the time each event was read from the device
+    pub fn poll(&mut self) -> Vec<(ResponseKey, SystemTime)> {
+        let mut keys = Vec::new();
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(&key) = self.mapping.buttons.get(&button) {
+                        keys.push((key, event.time));
+                    }
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    for positive in [true, false] {
+                        let deflection = if positive { value } else { -value };
+                        let id = (axis, positive);
+                        if deflection >= self.mapping.threshold {
+                            if self.deflected.insert(id)
+                                && let Some(&key) = self.mapping.axes.get(&id)
+                            {
+                                keys.push((key, event.time));
+                            }
+                        } else if deflection < self.mapping.threshold * 0.5 {
+                            self.deflected.remove(&id);
+                        }
+                    }
+                }
+                EventType::Connected => {
+                    info!("Gamepad connected: {}", self.gilrs.gamepad(event.id).name());
+                }
+                EventType::Disconnected => info!("Gamepad disconnected"),
+                _ => {}
+            }
+        }
+        keys
+    }
+}
@@ -0,0 +1,252 @@
+use cogex_core::{MouseButton, RawEvent, RawInput, ResponseKey};
+use cogex_timing::{HighPrecisionTimer, Timer};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Queues participant input for the state machine, each event stamped when
+/// it was captured rather than when the next update handles it.
+///
+/// Window events are stamped on receipt by the event loop. With the `evdev`
+/// feature on Linux, keyboards and mice are read on their own threads with
+/// kernel timestamps, so capture no longer waits on a frame being presented;
+/// the matching window events are then dropped so nothing counts twice.
+/// Those threads see every keyboard on the system, so they drop input while
+/// the experiment window lacks focus.
+pub struct InputCapture {
+    sender: Sender<RawInput>,
+    /// Clone of the state machine's end, for discarding stale input
+    receiver: Receiver<RawInput>,
+    timer: HighPrecisionTimer,
+    scan_ns: u64,
+    /// Cursor in stimulus coordinates as two packed `f32`s, shared with the
+    /// device threads
+    cursor: Arc<AtomicU64>,
+    /// Whether the experiment window has focus, shared with the device
+    /// threads
+    focused: Arc<AtomicBool>,
+    keyboard_thread: bool,
+    mouse_thread: bool,
+}
+
+impl InputCapture {
+    /// Starts capture; `key_scan_ms` is taken off every key-down stamp
+    pub fn spawn(timer: HighPrecisionTimer, key_scan_ms: f64) -> (Self, Receiver<RawInput>) {
+        let (sender, receiver) = unbounded();
+        let capture = Self {
+            sender,
+            receiver: receiver.clone(),
+            timer,
+            scan_ns: (key_scan_ms * 1e6) as u64,
+            cursor: Arc::new(AtomicU64::new(0)),
+            focused: Arc::new(AtomicBool::new(false)),
+            keyboard_thread: false,
+            mouse_thread: false,
+        };
+        #[cfg(all(feature = "evdev", target_os = "linux"))]
+        let capture = capture.spawn_devices();
+        (capture, receiver)
+    }
+
+    /// Queues an already stamped event
+    pub fn push(&self, input: RawInput) {
+        // The state machine owns the other end for the whole run
+        let _ = self.sender.send(input);
+    }
+
+    /// Sender for input backends running outside the event loop
+    #[cfg(any(feature = "voice", feature = "scanner"))]
+    pub fn sender(&self) -> Sender<RawInput> {
+        self.sender.clone()
+    }
+
+    /// Queues a key from the window system, unless a device thread reads it
+    pub fn push_key(&self, key: ResponseKey) {
+        if !self.keyboard_thread {
+            let pressed_ns = self.timer.now().saturating_sub(self.scan_ns);
+            self.push(RawInput::new(RawEvent::Key(key), pressed_ns));
+        }
+    }
+
+    /// Queues a click at the current cursor, unless a device thread reads it
+    pub fn push_click(&self, button: MouseButton) {
+        if !self.mouse_thread {
+            let event = RawEvent::Click {
+                button,
+                position: unpack(self.cursor.load(Ordering::Relaxed)),
+            };
+            self.push(RawInput::new(event, self.timer.now()));
+        }
+    }
+
+    /// Cursor position in stimulus coordinates, for clicks
+    pub fn set_cursor(&self, position: (f32, f32)) {
+        self.cursor.store(pack(position), Ordering::Relaxed);
+    }
+
+    /// Tracks the experiment window's focus, so device threads ignore
+    /// typing meant for other windows
+    pub fn set_focused(&self, focused: bool) {
+        self.focused.store(focused, Ordering::Relaxed);
+    }
+
+    /// Drops queued input, e.g. keys typed into the participant form
+    pub fn discard(&self) {
+        while self.receiver.try_recv().is_ok() {}
+    }
+
+    #[cfg(all(feature = "evdev", target_os = "linux"))]
+    fn spawn_devices(mut self) -> Self {
+        use evdev::{InputEventKind, Key};
+        use std::time::SystemTime;
+        use tracing::{info, warn};
+
+        for (path, mut device) in evdev::enumerate() {
+            let keys = device.supported_keys();
+            let keyboard = keys.is_some_and(|k| k.contains(Key::KEY_SPACE));
+            let mouse = keys.is_some_and(|k| k.contains(Key::BTN_LEFT));
+            if !keyboard && !mouse {
+                continue;
+            }
+            let label = format!(
+                "{} ({})",
+                device.name().unwrap_or("unnamed"),
+                path.display()
+            );
+            let (sender, timer, cursor, focused) = (
+                self.sender.clone(),
+                self.timer.clone(),
+                self.cursor.clone(),
+                self.focused.clone(),
+            );
+            let scan_ns = self.scan_ns;
+            let spawned = std::thread::Builder::new()
+                .name("cogex-input".to_string())
+                .spawn(move || {
+                    loop {
+                        let events = match device.fetch_events() {
+                            Ok(events) => events,
+                            Err(e) => {
+                                warn!("Input device {} failed: {}", path.display(), e);
+                                return;
+                            }
+                        };
+                        for event in events {
+                            // Presses only, not releases or auto-repeat, and only
+                            // while the experiment window has focus
+                            let InputEventKind::Key(key) = event.kind() else {
+                                continue;
+                            };
+                            if event.value() != 1 || !focused.load(Ordering::Relaxed) {
+                                continue;
+                            }
+                            let (event_kind, scan) = match key {
+                                Key::BTN_LEFT => (click(MouseButton::Left, &cursor), 0),
+                                Key::BTN_RIGHT => (click(MouseButton::Right, &cursor), 0),
+                                Key::BTN_MIDDLE => (click(MouseButton::Middle, &cursor), 0),
+                                key => match evdev_key(key) {
+                                    Some(key) => (RawEvent::Key(key), scan_ns),
+                                    None => continue,
+                                },
+                            };
+                            // Kernel stamps are wall-clock; map them onto the timer
+                            let age = SystemTime::now()
+                                .duration_since(event.timestamp())
+                                .unwrap_or_default();
+                            let pressed_ns =
+                                timer.now().saturating_sub(age.as_nanos() as u64 + scan);
+                            if sender.send(RawInput::new(event_kind, pressed_ns)).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            match spawned {
+                Ok(_) => {
+                    info!("Reading input from {}", label);
+                    self.keyboard_thread |= keyboard;
+                    self.mouse_thread |= mouse;
+                }
+                Err(e) => warn!("Failed to start input thread for {}: {}", label, e),
+            }
+        }
+        self
+    }
+}
+
+fn pack((x, y): (f32, f32)) -> u64 {
+    ((x.to_bits() as u64) << 32) | y.to_bits() as u64
+}
+
+fn unpack(bits: u64) -> (f32, f32) {
+    (
+        f32::from_bits((bits >> 32) as u32),
+        f32::from_bits(bits as u32),
+    )
+}
+
+#[cfg(all(feature = "evdev", target_os = "linux"))]
+fn click(button: MouseButton, cursor: &AtomicU64) -> RawEvent {
+    RawEvent::Click {
+        button,
+        position: unpack(cursor.load(Ordering::Relaxed)),
+    }
+}
+
+/// Translates an evdev key into the backend-independent response key, for
+/// the same keys the window path accepts
+#[cfg(all(feature = "evdev", target_os = "linux"))]
+fn evdev_key(key: evdev::Key) -> Option<ResponseKey> {
+    use evdev::Key;
+    const CHARS: [(Key, char); 36] = [
+        (Key::KEY_A, 'a'),
+        (Key::KEY_B, 'b'),
+        (Key::KEY_C, 'c'),
+        (Key::KEY_D, 'd'),
+        (Key::KEY_E, 'e'),
+        (Key::KEY_F, 'f'),
+        (Key::KEY_G, 'g'),
+        (Key::KEY_H, 'h'),
+        (Key::KEY_I, 'i'),
+        (Key::KEY_J, 'j'),
+        (Key::KEY_K, 'k'),
+        (Key::KEY_L, 'l'),
+        (Key::KEY_M, 'm'),
+        (Key::KEY_N, 'n'),
+        (Key::KEY_O, 'o'),
+        (Key::KEY_P, 'p'),
+        (Key::KEY_Q, 'q'),
+        (Key::KEY_R, 'r'),
+        (Key::KEY_S, 's'),
+        (Key::KEY_T, 't'),
+        (Key::KEY_U, 'u'),
+        (Key::KEY_V, 'v'),
+        (Key::KEY_W, 'w'),
+        (Key::KEY_X, 'x'),
+        (Key::KEY_Y, 'y'),
+        (Key::KEY_Z, 'z'),
+        (Key::KEY_0, '0'),
+        (Key::KEY_1, '1'),
+        (Key::KEY_2, '2'),
+        (Key::KEY_3, '3'),
+        (Key::KEY_4, '4'),
+        (Key::KEY_5, '5'),
+        (Key::KEY_6, '6'),
+        (Key::KEY_7, '7'),
+        (Key::KEY_8, '8'),
+        (Key::KEY_9, '9'),
+    ];
+    Some(match key {
+        Key::KEY_SPACE => ResponseKey::Space,
+        Key::KEY_LEFT => ResponseKey::ArrowLeft,
+        Key::KEY_RIGHT => ResponseKey::ArrowRight,
+        Key::KEY_UP => ResponseKey::ArrowUp,
+        Key::KEY_DOWN => ResponseKey::ArrowDown,
+        key => {
+            return CHARS
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|&(_, c)| ResponseKey::Char(c))
+        }
+    })
+}
@@ -0,0 +1,26 @@
+mod app;
+pub mod cli;
+mod console;
+#[cfg(feature = "control")]
+mod control;
+mod form;
+mod framelog;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+mod input;
+mod logging;
+mod mirror;
+#[cfg(feature = "monitor")]
+mod monitor;
+mod report;
+mod scheduler;
+pub use app::App;
+pub use console::parse_command;
+#[cfg(feature = "control")]
+pub use control::{command_event, osc_events};
+pub use framelog::{read_frame_log, FrameLog, FrameRecord};
+pub use input::InputCapture;
+#[cfg(feature = "monitor")]
+pub use monitor::SessionMonitor;
+pub use report::timing_report;
+pub use scheduler::PresentationScheduler;
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+/// Directory of the rolling session logs, relative to the working directory
+const LOG_DIR: &str = "logs";
+
+/// Logs readable lines to the terminal and JSON lines to a daily rolling
+/// file under `logs/`, one object per event with its phase and trial spans.
+///
+/// The level comes from `COGEX_LOG` (e.g. `debug` or
+/// `cogex_experiment=trace`) and defaults to `info`. Buffered file lines are
+/// flushed when the returned guard drops, so keep it until exit.
+pub fn init() -> Result<WorkerGuard> {
+    let filter = || EnvFilter::try_from_env("COGEX_LOG").unwrap_or_else(|_| EnvFilter::new("info"));
+    let appender = tracing_appender::rolling::daily(LOG_DIR, "session.log");
+    let (file, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_target(false).with_filter(filter()))
+        .with(fmt::layer().json().with_writer(file).with_filter(filter()))
+        .try_init()?;
+    Ok(guard)
+}
@@ -1,9 +1,5 @@
-mod app;
-pub use app::App;
+use cogex_experiment::ParadigmRegistry;
 
 fn main() -> anyhow::Result<()> {
-    let app = App::new()?;
-    app.run()?;
-
-    Ok(())
+    cogex_app::cli::main(ParadigmRegistry::builtin())
 }
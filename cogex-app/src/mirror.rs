@@ -0,0 +1,94 @@
+use anyhow::{anyhow, Result};
+use cogex_cache::Interner;
+use cogex_experiment::MonitorSelection;
+use cogex_render::SkiaRenderer;
+use cogex_timing::{Clock, HighPrecisionTimer};
+use pixels::{wgpu::PresentMode, Pixels, PixelsBuilder, SurfaceTexture};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use winit::{
+    event_loop::ActiveEventLoop,
+    monitor::MonitorHandle,
+    window::{Fullscreen, Window, WindowId},
+};
+
+/// Finds the monitor described by `selection`
+pub fn select_monitor(
+    event_loop: &ActiveEventLoop,
+    selection: &MonitorSelection,
+) -> Option<MonitorHandle> {
+    match selection {
+        MonitorSelection::Primary => event_loop
+            .primary_monitor()
+            .or_else(|| event_loop.available_monitors().next()),
+        MonitorSelection::Index(i) => event_loop.available_monitors().nth(*i),
+        MonitorSelection::Name(name) => event_loop
+            .available_monitors()
+            .find(|m| m.name().is_some_and(|n| n.contains(name.as_str()))),
+    }
+}
+
+/// Reduced-rate status view for the experimenter on a second monitor.
+///
+/// Presents without vsync so it never blocks the participant window.
+pub struct ExperimenterView {
+    window: Arc<Window>,
+    pixels: Pixels<'static>,
+    renderer: SkiaRenderer,
+    timer: HighPrecisionTimer,
+    last_render: Option<Instant>,
+}
+
+impl ExperimenterView {
+    const INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Draws on `monitor`, timed on `clock` with frame statistics kept
+    /// apart from the participant window's
+    pub fn new(event_loop: &ActiveEventLoop, monitor: MonitorHandle, clock: Clock) -> Result<Self> {
+        let attributes = Window::default_attributes()
+            .with_title("Cogex - Experimenter")
+            .with_fullscreen(Some(Fullscreen::Borderless(Some(monitor))))
+            .with_resizable(false);
+        let window = Arc::new(event_loop.create_window(attributes)?);
+        let size = window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return Err(anyhow!("Experimenter monitor has no usable area"));
+        }
+
+        let surface = SurfaceTexture::new(size.width, size.height, window.clone());
+        let pixels = PixelsBuilder::new(size.width, size.height, surface)
+            .present_mode(PresentMode::AutoNoVsync)
+            .build()?;
+
+        Ok(Self {
+            window,
+            pixels,
+            renderer: SkiaRenderer::new_with_interner(size.width, size.height, 0, Interner::new()),
+            timer: HighPrecisionTimer::with_clock(clock),
+            last_render: None,
+        })
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Redraws with the lines from `status` if the refresh interval has passed
+    pub fn update<F>(&mut self, status: F) -> Result<()>
+    where
+        F: FnOnce() -> Vec<String>,
+    {
+        if self
+            .last_render
+            .is_some_and(|t| t.elapsed() < Self::INTERVAL)
+        {
+            return Ok(());
+        }
+        self.last_render = Some(Instant::now());
+
+        self.renderer
+            .render_text_lines(&status(), self.pixels.frame_mut(), &mut self.timer)?;
+        self.pixels.render()?;
+        Ok(())
+    }
+}
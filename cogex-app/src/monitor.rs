@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use cogex_core::{StimulusType, TrialResult};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+use tungstenite::Message;
+
+/// Event types in the order a late client is sent the latest of each
+const EVENT_TYPES: [&str; 3] = ["phase", "trial", "ended"];
+/// How long a connection may stall a read or write, the handshake included
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Queues of the connected clients' writer threads
+type Clients = Arc<Mutex<Vec<Sender<Arc<str>>>>>;
+/// Latest event of each type, for clients that connect late
+type Latest = Arc<Mutex<BTreeMap<String, Value>>>;
+
+/// WebSocket server broadcasting live session events as JSON, for an
+/// experimenter watching from another room (`assets/monitor.html`).
+///
+/// Each client is upgraded and written to on its own thread, with socket
+/// timeouts, so a half-open connection or a stalled dashboard holds up
+/// neither a frame nor the other clients; a client that connects late is
+/// first sent the latest event of each type.
+pub struct SessionMonitor {
+    sender: Sender<Value>,
+    addr: SocketAddr,
+    /// Trials the session runs, practice included
+    total: usize,
+    /// Results already broadcast
+    sent: usize,
+    /// Scored results and how many of them were correct
+    scored: usize,
+    correct: usize,
+    phase: Option<String>,
+}
+
+impl SessionMonitor {
+    pub fn bind(addr: SocketAddr, total: usize) -> Result<Self> {
+        let listener =
+            TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+        let addr = listener.local_addr()?;
+        info!("Session monitor listening on ws://{}", addr);
+        let clients = Clients::default();
+        let latest = Latest::default();
+
+        let (accepted, snapshot) = (clients.clone(), latest.clone());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let (clients, latest) = (accepted.clone(), snapshot.clone());
+                        std::thread::spawn(move || serve(stream, &latest, &clients));
+                    }
+                    Err(e) => warn!("Monitor connection failed: {}", e),
+                }
+            }
+        });
+
+        let (sender, receiver) = mpsc::channel::<Value>();
+        std::thread::spawn(move || {
+            for event in receiver {
+                let text: Arc<str> = event.to_string().into();
+                // Held while queueing, so a client joining now gets the
+                // event either in its backlog or from its queue, not both
+                let mut latest = latest.lock().unwrap();
+                if let Some(kind) = event["type"].as_str().map(str::to_string) {
+                    latest.insert(kind, event);
+                }
+                // A client whose writer thread has ended has gone away
+                clients
+                    .lock()
+                    .unwrap()
+                    .retain(|client| client.send(text.clone()).is_ok());
+            }
+        });
+
+        Ok(Self {
+            sender,
+            addr,
+            total,
+            sent: 0,
+            scored: 0,
+            correct: 0,
+            phase: None,
+        })
+    }
+
+    /// Address the server listens on, with the port it was given when bound
+    /// to port 0
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Announces `phase` when it differs from the last one announced
+    pub fn phase(&mut self, phase: String) {
+        if self.phase.as_ref() != Some(&phase) {
+            self.send(json!({ "type": "phase", "phase": phase }));
+            self.phase = Some(phase);
+        }
+    }
+
+    /// Broadcasts the results added since the last call, each with the
+    /// running accuracy over the scored results so far
+    pub fn trials(&mut self, results: &[TrialResult<StimulusType>]) {
+        for result in results.iter().skip(self.sent) {
+            self.sent += 1;
+            if let Some(correct) = result.correct {
+                self.scored += 1;
+                self.correct += correct as usize;
+            }
+            let accuracy = (self.scored > 0).then(|| self.correct as f64 / self.scored as f64);
+            self.send(json!({
+                "type": "trial",
+                "trial": self.sent,
+                "total": self.total,
+                "condition": result.condition,
+                "block": result.block,
+                "rt_ms": result.reaction_time_ns.map(|ns| ns as f64 / 1e6),
+                "correct": result.correct,
+                "accuracy": accuracy,
+                "dropped_frames": result.dropped_frames,
+            }));
+        }
+    }
+
+    /// Announces the end of the session, with the reason if it was aborted
+    pub fn ended(&mut self, aborted: Option<&str>) {
+        self.send(json!({ "type": "ended", "aborted": aborted }));
+    }
+
+    fn send(&self, event: Value) {
+        // Only fails once the broadcast thread is gone, at shutdown
+        let _ = self.sender.send(event);
+    }
+}
+
+/// Upgrades one connection, then writes it the latest events and every
+/// later one until it fails or times out
+fn serve(stream: TcpStream, latest: &Latest, clients: &Clients) {
+    let peer = stream.peer_addr().ok();
+    let _ = stream.set_nodelay(true);
+    if let Err(e) = stream
+        .set_read_timeout(Some(SOCKET_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(SOCKET_TIMEOUT)))
+    {
+        warn!("Monitor connection from {:?} failed: {}", peer, e);
+        return;
+    }
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("Monitor handshake with {:?} failed: {}", peer, e);
+            return;
+        }
+    };
+    info!("Monitor client connected: {:?}", peer);
+
+    let (sender, receiver) = mpsc::channel::<Arc<str>>();
+    let backlog: Vec<Arc<str>> = {
+        let latest = latest.lock().unwrap();
+        clients.lock().unwrap().push(sender);
+        EVENT_TYPES
+            .iter()
+            .filter_map(|kind| latest.get(*kind))
+            .map(|event| event.to_string().into())
+            .collect()
+    };
+    for text in backlog.into_iter().chain(receiver) {
+        if let Err(e) = socket.send(Message::text(&*text)) {
+            info!("Monitor client {:?} left: {}", peer, e);
+            return;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::framelog::{read_frame_log, FrameRecord};
+use anyhow::{bail, Context, Result};
+use std::fmt::Write;
+use std::path::Path;
+
+const PERCENTILES: [f64; 5] = [50.0, 90.0, 95.0, 99.0, 100.0];
+const HISTOGRAM_BINS: usize = 20;
+const HISTOGRAM_WIDTH: usize = 50;
+
+/// `cogex-app report <frames.bin.gz>`: prints render time percentiles and a
+/// histogram of frame intervals from a session's frame log
+pub fn run(path: &Path) -> Result<()> {
+    let records = read_frame_log(path)?;
+    let report = timing_report(&records).with_context(|| path.display().to_string())?;
+    println!("{}: {} frames", path.display(), records.len());
+    print!("{}", report);
+    Ok(())
+}
+
+/// Percentile table, late-frame count and interval histogram of `records`
+pub fn timing_report(records: &[FrameRecord]) -> Result<String> {
+    if records.len() < 2 {
+        bail!("Fewer than two frames");
+    }
+    let mut out = String::new();
+    let intervals: Vec<f64> = records
+        .windows(2)
+        .map(|w| w[1].presented_ns.saturating_sub(w[0].presented_ns) as f64 / 1e6)
+        .collect();
+    let column = |f: fn(&FrameRecord) -> u64| -> Vec<f64> {
+        records.iter().map(|r| f(r) as f64 / 1e6).collect()
+    };
+
+    writeln!(
+        out,
+        "\n{:<10}{}",
+        "ms",
+        PERCENTILES
+            .iter()
+            .map(|p| if *p == 100.0 {
+                format!("{:>9}", "max")
+            } else {
+                format!("{:>9}", format!("p{}", p))
+            })
+            .collect::<String>()
+    )?;
+    for (name, values) in [
+        ("interval", intervals.clone()),
+        ("clear", column(|r| r.clear_ns)),
+        ("phase", column(|r| r.phase_ns)),
+        ("copy", column(|r| r.copy_ns)),
+        ("render", column(|r| r.total_ns)),
+        ("present", column(|r| r.present_ns)),
+    ] {
+        let sorted = sorted(values);
+        let row: String = PERCENTILES
+            .iter()
+            .map(|p| format!("{:>9.3}", percentile(&sorted, *p)))
+            .collect();
+        writeln!(out, "{:<10}{}", name, row)?;
+    }
+
+    let sorted = sorted(intervals);
+    let median = percentile(&sorted, 50.0);
+    let late = sorted.iter().filter(|&&i| i > median * 1.5).count();
+    writeln!(
+        out,
+        "\n{} of {} intervals ({:.2}%) exceed 1.5x the median of {:.3} ms",
+        late,
+        sorted.len(),
+        late as f64 * 100.0 / sorted.len() as f64,
+        median
+    )?;
+    write_histogram(&mut out, &sorted)?;
+    Ok(out)
+}
+
+fn sorted(mut values: Vec<f64>) -> Vec<f64> {
+    values.sort_by(f64::total_cmp);
+    values
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Frame intervals from the minimum to the 99.9th percentile, so a few
+/// long stalls do not squash every other bin; longer ones go in the last bin
+fn write_histogram(out: &mut String, sorted: &[f64]) -> std::fmt::Result {
+    let low = sorted[0];
+    let high = percentile(sorted, 99.9).max(low + 1e-3);
+    let width = (high - low) / HISTOGRAM_BINS as f64;
+    let mut counts = [0usize; HISTOGRAM_BINS];
+    for &value in sorted {
+        let bin = ((value - low) / width) as usize;
+        counts[bin.min(HISTOGRAM_BINS - 1)] += 1;
+    }
+    let max = counts.iter().copied().max().unwrap_or(1).max(1);
+
+    writeln!(out, "\nFrame interval histogram (ms)")?;
+    for (i, count) in counts.iter().enumerate() {
+        let from = low + i as f64 * width;
+        let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(max));
+        writeln!(
+            out,
+            "{:>8.3}-{:<8.3} {:>7} {}",
+            from,
+            from + width,
+            count,
+            bar
+        )?;
+    }
+    Ok(())
+}
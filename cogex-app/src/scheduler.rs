@@ -0,0 +1,113 @@
+use cogex_experiment::Trial;
+use tracing::debug;
+
+/// Snaps trial durations to whole refresh frames, so every onset lands on a
+/// frame boundary instead of the first frame after an arbitrary deadline.
+///
+/// The phases of a trial are chained: each boundary is placed at the nearest
+/// frame to the requested cumulative time, so rounding never accumulates.
+/// Results record the snapped durations as requested.
+#[derive(Debug, Clone)]
+pub struct PresentationScheduler {
+    frame_ns: f64,
+    /// Trial whose durations were last snapped
+    scheduled: Option<usize>,
+}
+
+impl PresentationScheduler {
+    pub fn new(refresh_hz: f64) -> Self {
+        Self {
+            frame_ns: 1e9 / refresh_hz,
+            scheduled: None,
+        }
+    }
+
+    pub fn frame_ms(&self) -> f64 {
+        self.frame_ns / 1e6
+    }
+
+    /// Half a frame: enough lead for a deadline on a boundary to be taken on
+    /// the update before the frame is drawn, without reaching the one before
+    pub fn lead_ns(&self) -> u64 {
+        (self.frame_ns / 2.0) as u64
+    }
+
+    /// Nearest whole number of frames to `ms`, at least one unless `ms` is zero
+    pub fn frames(&self, ms: u64) -> u64 {
+        if ms == 0 {
+            return 0;
+        }
+        ((ms as f64 * 1e6 / self.frame_ns).round() as u64).max(1)
+    }
+
+    fn frames_to_ms(&self, frames: u64) -> u64 {
+        (frames as f64 * self.frame_ns / 1e6).round() as u64
+    }
+
+    /// Snaps a newly started trial's durations, once per trial, and logs
+    /// every duration the rounding changed
+    pub fn schedule<S, T>(&mut self, trial: &mut Trial<S, T>)
+    where
+        S: cogex_core::Stimulus,
+    {
+        if self.scheduled == Some(trial.id) {
+            return;
+        }
+        self.scheduled = Some(trial.id);
+
+        let frame_ms = self.frame_ms();
+        let mut changes = Vec::new();
+        let mut log = |name: &str, requested: u64, frames: u64, snapped: u64| {
+            if requested != snapped {
+                changes.push(format!(
+                    "{} {} ms -> {} frames ({:.2} ms)",
+                    name,
+                    requested,
+                    frames,
+                    frames as f64 * frame_ms
+                ));
+            }
+        };
+
+        let durations = &mut trial.durations;
+        let mut chain = vec![("fixation", &mut durations.fixation_ms)];
+        if trial.cue.is_some() {
+            chain.push(("cue-target SOA", &mut durations.cue_target_soa_ms));
+        }
+        chain.push(("stimulus", &mut durations.stimulus_ms));
+        chain.push(("response window", &mut durations.response_window_ms));
+        chain.push(("feedback", &mut durations.feedback_ms));
+
+        let (mut requested_end, mut frame_end, mut snapped_end) = (0, 0, 0);
+        for (name, ms) in chain {
+            requested_end += *ms;
+            let end = self.frames(requested_end).max(frame_end);
+            let boundary = self.frames_to_ms(end);
+            log(name, *ms, end - frame_end, boundary - snapped_end);
+            *ms = boundary - snapped_end;
+            (frame_end, snapped_end) = (end, boundary);
+        }
+
+        for (name, ms) in [
+            ("cue", &mut durations.cue_ms),
+            (
+                "inter-trial interval",
+                &mut durations.inter_trial_interval_ms,
+            ),
+        ] {
+            let frames = self.frames(*ms);
+            let snapped = self.frames_to_ms(frames);
+            log(name, *ms, frames, snapped);
+            *ms = snapped;
+        }
+
+        if !changes.is_empty() {
+            debug!(
+                "Trial {} snapped to {:.2} ms frames: {}",
+                trial.id,
+                frame_ms,
+                changes.join(", ")
+            );
+        }
+    }
+}
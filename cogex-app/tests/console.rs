@@ -0,0 +1,29 @@
+use cogex_app::parse_command;
+use cogex_experiment::ExperimentEvent;
+
+#[test]
+fn commands_are_recognised_by_name_or_initial() {
+    for (commands, event) in [
+        (["pause", "p"], ExperimentEvent::Pause),
+        (["resume", "r"], ExperimentEvent::Resume),
+        (["skip", "s"], ExperimentEvent::SkipBlock),
+        (["break", "b"], ExperimentEvent::InsertBreak),
+    ] {
+        for command in commands {
+            assert_eq!(parse_command(command), Some(event.clone()), "{}", command);
+        }
+    }
+    assert_eq!(
+        parse_command("  Abort \n"),
+        Some(ExperimentEvent::Abort {
+            reason: "experimenter console".to_string()
+        })
+    );
+}
+
+#[test]
+fn anything_else_is_not_a_command() {
+    for line in ["", "  ", "h", "help", "pause now", "x"] {
+        assert_eq!(parse_command(line), None, "{:?}", line);
+    }
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "control")]
+
+use cogex_app::{command_event, osc_events};
+use cogex_experiment::ExperimentEvent;
+use rosc::{OscBundle, OscMessage, OscPacket, OscTime, OscType};
+
+fn message(addr: &str, args: Vec<OscType>) -> OscPacket {
+    OscPacket::Message(OscMessage {
+        addr: addr.to_string(),
+        args,
+    })
+}
+
+fn bundle(content: Vec<OscPacket>) -> OscPacket {
+    OscPacket::Bundle(OscBundle {
+        timetag: OscTime {
+            seconds: 0,
+            fractional: 1,
+        },
+        content,
+    })
+}
+
+#[test]
+fn each_command_maps_to_its_event() {
+    let cases = [
+        ("start", ExperimentEvent::SpacePressed),
+        ("pause", ExperimentEvent::Pause),
+        ("resume", ExperimentEvent::Resume),
+        ("skip", ExperimentEvent::SkipBlock),
+        ("break", ExperimentEvent::InsertBreak),
+    ];
+    for (command, event) in cases {
+        assert_eq!(command_event(command, None, 7), Ok(event), "{}", command);
+    }
+    assert_eq!(
+        command_event("marker", Some("run-1".to_string()), 7),
+        Ok(ExperimentEvent::Marker {
+            label: "run-1".to_string(),
+            timestamp_ns: Some(7),
+        })
+    );
+}
+
+#[test]
+fn abort_carries_its_reason_or_names_the_control_server() {
+    assert_eq!(
+        command_event("abort", Some("scanner fault".to_string()), 0),
+        Ok(ExperimentEvent::Abort {
+            reason: "scanner fault".to_string()
+        })
+    );
+    assert_eq!(
+        command_event("abort", None, 0),
+        Ok(ExperimentEvent::Abort {
+            reason: "control server".to_string()
+        })
+    );
+}
+
+#[test]
+fn markers_need_a_label() {
+    assert!(command_event("marker", None, 0).is_err());
+}
+
+#[test]
+fn unknown_commands_are_rejected() {
+    assert!(command_event("reboot", None, 0).is_err());
+    assert!(command_event("", None, 0).is_err());
+    assert!(osc_events(message("/cogex/reboot", Vec::new()), 0).is_empty());
+}
+
+#[test]
+fn osc_messages_take_their_label_from_the_first_string_argument() {
+    let packet = message(
+        "/cogex/marker",
+        vec![OscType::Int(3), OscType::String("run-2".to_string())],
+    );
+    assert_eq!(
+        osc_events(packet, 42),
+        vec![ExperimentEvent::Marker {
+            label: "run-2".to_string(),
+            timestamp_ns: Some(42),
+        }]
+    );
+    assert!(osc_events(message("/cogex/marker", vec![OscType::Int(3)]), 0).is_empty());
+}
+
+#[test]
+fn osc_messages_outside_the_prefix_are_ignored() {
+    assert!(osc_events(message("/other/pause", Vec::new()), 0).is_empty());
+    assert!(osc_events(message("/pause", Vec::new()), 0).is_empty());
+}
+
+#[test]
+fn nested_osc_bundles_yield_their_messages_in_order() {
+    let packet = bundle(vec![
+        message("/cogex/pause", Vec::new()),
+        bundle(vec![
+            message("/cogex/marker", vec![OscType::String("inner".to_string())]),
+            message("/cogex/unknown", Vec::new()),
+            bundle(vec![message("/cogex/resume", Vec::new())]),
+        ]),
+        message("/cogex/skip", Vec::new()),
+    ]);
+    assert_eq!(
+        osc_events(packet, 5),
+        vec![
+            ExperimentEvent::Pause,
+            ExperimentEvent::Marker {
+                label: "inner".to_string(),
+                timestamp_ns: Some(5),
+            },
+            ExperimentEvent::Resume,
+            ExperimentEvent::SkipBlock,
+        ]
+    );
+}
@@ -0,0 +1,74 @@
+use cogex_app::{read_frame_log, timing_report, FrameLog, FrameRecord};
+use std::path::PathBuf;
+
+fn record(presented_ms: u64, trial: Option<u32>) -> FrameRecord {
+    FrameRecord {
+        presented_ns: presented_ms * 1_000_000,
+        clear_ns: 100_000,
+        phase_ns: 2_000_000,
+        copy_ns: 500_000,
+        total_ns: 2_600_000,
+        present_ns: 300_000,
+        dirty_count: 3,
+        trial,
+    }
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cogex_{}_{}.bin.gz", name, std::process::id()))
+}
+
+#[test]
+fn frame_logs_read_back_every_record() {
+    let path = temp_path("framelog");
+    let records: Vec<_> = (0..1000)
+        .map(|i| record(i * 16, (i >= 10).then_some(i as u32 / 10)))
+        .collect();
+    let mut log = FrameLog::create(&path).unwrap();
+    for &r in &records {
+        log.write(r).unwrap();
+    }
+    log.finish().unwrap();
+
+    let read = read_frame_log(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read, records);
+    assert_eq!(read[0].trial, None);
+    assert_eq!(read[999].trial, Some(99));
+}
+
+#[test]
+fn files_that_are_not_frame_logs_are_rejected() {
+    let path = temp_path("not_a_framelog");
+    std::fs::write(&path, b"plain text").unwrap();
+    let plain = read_frame_log(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(plain.is_err());
+    assert!(read_frame_log(&temp_path("missing")).is_err());
+}
+
+#[test]
+fn the_report_gives_percentiles_and_counts_late_frames() {
+    // Ten 16 ms intervals, one of them stretched to 48 ms
+    let mut records: Vec<_> = (0..10).map(|i| record(i * 16, None)).collect();
+    records.push(record(9 * 16 + 48, None));
+    let report = timing_report(&records).unwrap();
+
+    let row = |name: &str| {
+        report
+            .lines()
+            .find(|line| line.starts_with(name))
+            .unwrap()
+            .split_whitespace()
+            .skip(1)
+            .map(|v| v.parse::<f64>().unwrap())
+            .collect::<Vec<_>>()
+    };
+    // Nearest rank: p90 of ten is the ninth interval
+    assert_eq!(row("interval"), [16.0, 16.0, 48.0, 48.0, 48.0]);
+    assert_eq!(row("render"), [2.6; 5]);
+    assert!(report.contains("1 of 10 intervals (10.00%) exceed 1.5x the median of 16.000 ms"));
+    assert!(report.contains("Frame interval histogram"));
+
+    assert!(timing_report(&records[..1]).is_err());
+}
@@ -0,0 +1,48 @@
+use cogex_app::InputCapture;
+use cogex_core::{MouseButton, RawEvent, ResponseKey};
+use cogex_timing::{HighPrecisionTimer, Timer};
+
+#[test]
+fn window_keys_are_stamped_on_receipt_less_the_scan_delay() {
+    let timer = HighPrecisionTimer::new();
+    let (capture, receiver) = InputCapture::spawn(timer.clone(), 4.0);
+    timer.sleep(std::time::Duration::from_millis(10));
+    let before_ns = timer.now();
+    capture.push_key(ResponseKey::Space);
+    let after_ns = timer.now();
+
+    let input = receiver.try_recv().unwrap();
+    assert_eq!(input.event, RawEvent::Key(ResponseKey::Space));
+    assert!(input.timestamp_ns >= before_ns - 4_000_000);
+    assert!(input.timestamp_ns <= after_ns - 4_000_000);
+}
+
+#[test]
+fn clicks_carry_the_last_cursor_position() {
+    let timer = HighPrecisionTimer::new();
+    let (capture, receiver) = InputCapture::spawn(timer, 0.0);
+    capture.set_cursor((120.5, -40.25));
+    capture.push_click(MouseButton::Right);
+    assert_eq!(
+        receiver.try_recv().unwrap().event,
+        RawEvent::Click {
+            button: MouseButton::Right,
+            position: (120.5, -40.25),
+        }
+    );
+}
+
+#[test]
+fn discarded_input_never_reaches_the_experiment() {
+    let (capture, receiver) = InputCapture::spawn(HighPrecisionTimer::new(), 0.0);
+    capture.push_key(ResponseKey::Char('a'));
+    capture.push_click(MouseButton::Left);
+    capture.discard();
+    assert!(receiver.try_recv().is_err());
+
+    capture.push_key(ResponseKey::Char('b'));
+    assert_eq!(
+        receiver.try_recv().unwrap().event,
+        RawEvent::Key(ResponseKey::Char('b'))
+    );
+}
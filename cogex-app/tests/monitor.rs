@@ -0,0 +1,82 @@
+#![cfg(feature = "monitor")]
+
+use cogex_app::SessionMonitor;
+use cogex_core::{PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult};
+use std::net::TcpStream;
+use std::time::Duration;
+use tungstenite::Message;
+
+fn result(rt_ms: u64, correct: bool) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: StimulusType::Circle {
+            radius: 50.0,
+            color: [255, 255, 255, 255],
+        },
+        condition: "circle".to_string(),
+        set_size: 1,
+        response_key: Some(ResponseKey::Space),
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: Some(rt_ms * 1_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: Some(correct),
+        outcome: TrialOutcome::Response,
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 200,
+        requested_response_window_ms: 1000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn dashboards_receive_trials_past_a_connection_that_never_upgrades() {
+    let mut monitor = SessionMonitor::bind("127.0.0.1:0".parse().unwrap(), 10).unwrap();
+    let addr = monitor.local_addr();
+    // Opens a connection but never sends the handshake
+    let _stalled = TcpStream::connect(addr).unwrap();
+
+    let stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let (mut client, _) = tungstenite::client(format!("ws://{}", addr), stream).unwrap();
+    let mut first = result(420, true);
+    first.dropped_frames = 2;
+    monitor.trials(&[first.clone()]);
+    monitor.trials(&[first, result(380, false)]);
+
+    let Message::Text(text) = client.read().unwrap() else {
+        panic!("expected a text message");
+    };
+    let event: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(event["type"], "trial");
+    assert_eq!(event["trial"], 1);
+    assert_eq!(event["total"], 10);
+    assert_eq!(event["rt_ms"], 420.0);
+    assert_eq!(event["accuracy"], 1.0);
+    assert_eq!(event["dropped_frames"], 2);
+
+    let Message::Text(text) = client.read().unwrap() else {
+        panic!("expected a text message");
+    };
+    let event: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(event["trial"], 2);
+    assert_eq!(event["accuracy"], 0.5);
+    assert_eq!(event["dropped_frames"], 0);
+}
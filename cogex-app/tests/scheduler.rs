@@ -0,0 +1,79 @@
+use cogex_app::PresentationScheduler;
+use cogex_core::{ResponseKey, ResponseMapping, StandardPhase, StimulusType};
+use cogex_experiment::{
+    ExperimentConfig, ExperimentEvent, ExperimentStateMachine, Interval, SequencedTrial,
+    TrialSequence,
+};
+use cogex_timing::MockTimer;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+type Machine = ExperimentStateMachine<StandardPhase, StimulusType, MockTimer, StdRng>;
+
+/// A machine with its first practice trial just started
+fn practice_machine() -> Machine {
+    let mut config = ExperimentConfig::<StandardPhase>::default();
+    config.practice_trials = 1;
+    config.fixation_range_ms = (500, 500);
+    config.stimulus_duration_ms = 200;
+    config.response_window_ms = 1000;
+    config.feedback_duration_ms = 300;
+    config.inter_trial_interval = Interval::Fixed(250);
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut machine =
+        ExperimentStateMachine::new(config, MockTimer::default(), StdRng::seed_from_u64(7))
+            .with_response_mapping(ResponseMapping::new().with_fallback(ResponseKey::Space))
+            .with_practice_sequence(TrialSequence::from_trials(vec![SequencedTrial::new(
+                "circle", circle,
+            )]));
+    machine.handle_event(ExperimentEvent::SpacePressed);
+    machine.handle_event(ExperimentEvent::CalibrationComplete);
+    assert!(machine.current.is_some());
+    machine
+}
+
+#[test]
+fn milliseconds_round_to_the_nearest_whole_frame() {
+    let scheduler = PresentationScheduler::new(60.0);
+    assert!((scheduler.frame_ms() - 16.667).abs() < 0.001);
+    assert_eq!(scheduler.lead_ns(), 8_333_333);
+    assert_eq!(scheduler.frames(0), 0);
+    // Anything shown is shown for at least a frame
+    assert_eq!(scheduler.frames(1), 1);
+    assert_eq!(scheduler.frames(24), 1);
+    assert_eq!(scheduler.frames(26), 2);
+    assert_eq!(scheduler.frames(1000), 60);
+}
+
+#[test]
+fn trial_boundaries_land_on_the_nearest_frame_without_drift() {
+    let mut machine = practice_machine();
+    let trial = machine.current.as_mut().unwrap();
+    let mut scheduler = PresentationScheduler::new(144.0);
+    scheduler.schedule(trial);
+
+    // 500, 700, 1700 and 2000 ms fall nearest frames 72, 101, 245 and 288
+    let durations = &trial.durations;
+    assert_eq!(durations.fixation_ms, 500);
+    assert_eq!(durations.stimulus_ms, 201);
+    assert_eq!(durations.response_window_ms, 1000);
+    assert_eq!(durations.feedback_ms, 299);
+    // The cue and interval stand alone: 14 and 36 frames of 6.94 ms
+    assert_eq!(durations.cue_ms, 97);
+    assert_eq!(durations.inter_trial_interval_ms, 250);
+}
+
+#[test]
+fn each_trial_is_snapped_only_once() {
+    let mut machine = practice_machine();
+    let trial = machine.current.as_mut().unwrap();
+    let mut scheduler = PresentationScheduler::new(144.0);
+    scheduler.schedule(trial);
+    let stimulus_ms = trial.durations.stimulus_ms;
+    scheduler.schedule(trial);
+    assert_eq!(trial.durations.stimulus_ms, stimulus_ms);
+    assert_eq!(trial.durations.feedback_ms, 299);
+}
@@ -0,0 +1,9 @@
+pub mod player;
+pub mod stimulus;
+#[cfg(feature = "rodio")]
+pub use player::RodioPlayer;
+pub use player::{AudioEngine, AudioPlayer, NullPlayer};
+pub use stimulus::{AudioStimulus, Tone, WavClip};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,152 @@
+use crate::stimulus::{AudioStimulus, Tone, WavClip};
+use anyhow::Result;
+use cogex_core::{Atom, StimulusType};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Output device abstraction for auditory stimuli
+pub trait AudioPlayer {
+    /// Starts playback immediately, replacing anything still playing
+    fn play(&mut self, stimulus: &dyn AudioStimulus) -> Result<()>;
+    fn stop(&mut self);
+}
+
+/// Discards all audio; used when no output device is available
+#[derive(Debug, Clone, Default)]
+pub struct NullPlayer;
+
+impl AudioPlayer for NullPlayer {
+    fn play(&mut self, _stimulus: &dyn AudioStimulus) -> Result<()> {
+        Ok(())
+    }
+
+    fn stop(&mut self) {}
+}
+
+/// Plays through the default output device via rodio
+#[cfg(feature = "rodio")]
+pub struct RodioPlayer {
+    _stream: rodio::OutputStream,
+    handle: rodio::OutputStreamHandle,
+    sink: Option<rodio::Sink>,
+}
+
+#[cfg(feature = "rodio")]
+impl RodioPlayer {
+    pub fn new() -> Result<Self> {
+        let (stream, handle) = rodio::OutputStream::try_default()?;
+        Ok(Self {
+            _stream: stream,
+            handle,
+            sink: None,
+        })
+    }
+}
+
+#[cfg(feature = "rodio")]
+impl AudioPlayer for RodioPlayer {
+    fn play(&mut self, stimulus: &dyn AudioStimulus) -> Result<()> {
+        self.stop();
+        let sink = rodio::Sink::try_new(&self.handle)?;
+        sink.append(rodio::buffer::SamplesBuffer::new(
+            1,
+            stimulus.sample_rate(),
+            stimulus.samples().to_vec(),
+        ));
+        self.sink = Some(sink);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(sink) = self.sink.take() {
+            sink.stop();
+        }
+    }
+}
+
+/// Resolves audio stimuli to sample buffers and plays them on a player.
+///
+/// Tones are synthesized and WAV files decoded once, then cached so onset
+/// latency does not include synthesis or file I/O.
+pub struct AudioEngine {
+    player: Box<dyn AudioPlayer>,
+    tones: HashMap<(u32, u64, u32), Arc<Tone>>,
+    clips: HashMap<Atom, Arc<WavClip>>,
+}
+
+impl AudioEngine {
+    pub fn new(player: Box<dyn AudioPlayer>) -> Self {
+        Self {
+            player,
+            tones: HashMap::new(),
+            clips: HashMap::new(),
+        }
+    }
+
+    /// Engine backed by the default output device, or silence if unavailable
+    pub fn default_output() -> Self {
+        #[cfg(feature = "rodio")]
+        match crate::player::RodioPlayer::new() {
+            Ok(player) => return Self::new(Box::new(player)),
+            Err(e) => tracing::warn!("Audio output unavailable: {}", e),
+        }
+        Self::new(Box::new(NullPlayer))
+    }
+
+    /// Synthesizes or decodes `stimulus` ahead of its onset
+    pub fn preload(&mut self, stimulus: &StimulusType) -> Result<()> {
+        self.resolve(stimulus).map(|_| ())
+    }
+
+    /// Plays `stimulus` if it is auditory; returns whether playback started
+    pub fn play_stimulus(&mut self, stimulus: &StimulusType) -> Result<bool> {
+        match self.resolve(stimulus)? {
+            Some(clip) => {
+                self.player.play(clip.as_ref())?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.player.stop();
+    }
+
+    fn resolve(&mut self, stimulus: &StimulusType) -> Result<Option<Arc<dyn AudioStimulus>>> {
+        Ok(match *stimulus {
+            StimulusType::Tone {
+                frequency_hz,
+                duration_ms,
+                amplitude,
+            } => {
+                let key = (frequency_hz.to_bits(), duration_ms, amplitude.to_bits());
+                let tone = self
+                    .tones
+                    .entry(key)
+                    .or_insert_with(|| Arc::new(Tone::new(frequency_hz, duration_ms, amplitude)));
+                Some(tone.clone() as Arc<dyn AudioStimulus>)
+            }
+            StimulusType::Sound {
+                ref path,
+                amplitude,
+            } => {
+                if !self.clips.contains_key(path) {
+                    let clip = WavClip::load(&**path)?.with_amplitude(amplitude);
+                    self.clips.insert(path.clone(), Arc::new(clip));
+                }
+                Some(self.clips[path].clone() as Arc<dyn AudioStimulus>)
+            }
+            _ => None,
+        })
+    }
+}
+
+impl std::fmt::Debug for AudioEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AudioEngine")
+            .field("tones", &self.tones.len())
+            .field("clips", &self.clips.len())
+            .finish()
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Duration;
+
+/// Defines auditory stimuli as mono f32 sample buffers
+pub trait AudioStimulus: Send + Sync {
+    fn sample_rate(&self) -> u32;
+    fn samples(&self) -> &[f32];
+
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.samples().len() as f64 / self.sample_rate() as f64)
+    }
+}
+
+/// Pure sine tone with short raised-cosine ramps to avoid onset clicks
+#[derive(Debug, Clone)]
+pub struct Tone {
+    pub frequency_hz: f32,
+    pub duration_ms: u64,
+    pub amplitude: f32,
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl Tone {
+    pub const SAMPLE_RATE: u32 = 44_100;
+    const RAMP_MS: f32 = 5.0;
+
+    pub fn new(frequency_hz: f32, duration_ms: u64, amplitude: f32) -> Self {
+        let sample_rate = Self::SAMPLE_RATE;
+        let len = (duration_ms as usize * sample_rate as usize) / 1000;
+        let ramp = ((Self::RAMP_MS / 1000.0 * sample_rate as f32) as usize).min(len / 2);
+        let amplitude = amplitude.clamp(0.0, 1.0);
+
+        let samples = (0..len)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                let gain = if i < ramp {
+                    0.5 - 0.5 * (std::f32::consts::PI * i as f32 / ramp as f32).cos()
+                } else if i >= len - ramp {
+                    0.5 - 0.5 * (std::f32::consts::PI * (len - 1 - i) as f32 / ramp as f32).cos()
+                } else {
+                    1.0
+                };
+                amplitude * gain * (std::f32::consts::TAU * frequency_hz * t).sin()
+            })
+            .collect();
+
+        Self {
+            frequency_hz,
+            duration_ms,
+            amplitude,
+            sample_rate,
+            samples,
+        }
+    }
+}
+
+impl AudioStimulus for Tone {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+}
+
+/// Decoded WAV file, downmixed to mono
+#[derive(Debug, Clone)]
+pub struct WavClip {
+    sample_rate: u32,
+    samples: Vec<f32>,
+}
+
+impl WavClip {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let reader = hound::WavReader::open(path)
+            .with_context(|| format!("Failed to open WAV file {}", path.display()))?;
+        let spec = reader.spec();
+        let channels = spec.channels.max(1) as usize;
+
+        let interleaved: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>()?,
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|s| s.map(|v| v as f32 / scale))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+
+        let samples = interleaved
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect();
+
+        Ok(Self {
+            sample_rate: spec.sample_rate,
+            samples,
+        })
+    }
+
+    /// Scales all samples by `amplitude`
+    pub fn with_amplitude(mut self, amplitude: f32) -> Self {
+        for s in &mut self.samples {
+            *s *= amplitude;
+        }
+        self
+    }
+}
+
+impl AudioStimulus for WavClip {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+}
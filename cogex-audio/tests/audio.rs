@@ -0,0 +1,93 @@
+use cogex_audio::{AudioEngine, AudioPlayer, AudioStimulus, Tone, WavClip};
+use cogex_core::StimulusType;
+use std::sync::{Arc, Mutex};
+
+/// Keeps the sample count and rate of everything it is asked to play
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<(usize, u32)>>>);
+
+impl AudioPlayer for Recorder {
+    fn play(&mut self, stimulus: &dyn AudioStimulus) -> anyhow::Result<()> {
+        let played = (stimulus.samples().len(), stimulus.sample_rate());
+        self.0.lock().unwrap().push(played);
+        Ok(())
+    }
+
+    fn stop(&mut self) {}
+}
+
+fn write_wav(name: &str, channels: u16, frames: &[[i16; 2]]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("cogex_{}_{}.wav", name, std::process::id()));
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate: 8000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+    for frame in frames {
+        for &sample in &frame[..channels as usize] {
+            writer.write_sample(sample).unwrap();
+        }
+    }
+    writer.finalize().unwrap();
+    path
+}
+
+#[test]
+fn tones_ramp_in_and_out_at_their_frequency_and_level() {
+    let tone = Tone::new(1000.0, 100, 0.5);
+    let samples = tone.samples();
+    assert_eq!(samples.len(), 4410);
+    assert_eq!(tone.duration().as_millis(), 100);
+    assert_eq!(samples[0], 0.0);
+    assert!(samples[samples.len() - 1].abs() < 1e-3);
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!((peak - 0.5).abs() < 0.01);
+    // Two zero crossings per cycle, 100 cycles
+    let crossings = samples
+        .windows(2)
+        .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+        .count();
+    assert!((198..=201).contains(&crossings), "{}", crossings);
+    assert_eq!(Tone::new(440.0, 50, 3.0).amplitude, 1.0);
+}
+
+#[test]
+fn wav_clips_are_downmixed_to_mono_and_scaled() {
+    let path = write_wav("stereo", 2, &[[16384, 0], [-32768, -32768]]);
+    let clip = WavClip::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(clip.sample_rate(), 8000);
+    assert_eq!(clip.samples(), [0.25, -1.0]);
+    assert_eq!(clip.with_amplitude(0.5).samples(), [0.125, -0.5]);
+    assert!(WavClip::load("no/such/file.wav").is_err());
+}
+
+#[test]
+fn the_engine_plays_auditory_stimuli_from_its_cache() {
+    let recorder = Recorder::default();
+    let mut engine = AudioEngine::new(Box::new(recorder.clone()));
+    let tone = StimulusType::Tone {
+        frequency_hz: 500.0,
+        duration_ms: 20,
+        amplitude: 0.3,
+    };
+    let circle = StimulusType::Circle {
+        radius: 10.0,
+        color: [255, 255, 255, 255],
+    };
+    assert!(engine.play_stimulus(&tone).unwrap());
+    assert!(!engine.play_stimulus(&circle).unwrap());
+
+    let path = write_wav("mono", 1, &[[100, 0]; 80]);
+    let sound = StimulusType::Sound {
+        path: path.to_str().unwrap().into(),
+        amplitude: 1.0,
+    };
+    engine.preload(&sound).unwrap();
+    // Decoded once, so the file is no longer needed at onset
+    std::fs::remove_file(&path).unwrap();
+    assert!(engine.play_stimulus(&sound).unwrap());
+    assert_eq!(*recorder.0.lock().unwrap(), [(882, 44_100), (80, 8000)]);
+}
@@ -1,29 +1,118 @@
 use lazy_static::lazy_static;
-use std::sync::RwLock;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
 pub use string_cache::DefaultAtom as Atom;
 
+/// Interned texts by ID, with the IDs indexed by text
+#[derive(Default)]
+struct Table {
+    texts: Vec<Atom>,
+    ids: HashMap<Atom, usize>,
+}
+
+/// Texts numbered in order of first interning. Clones share one table, so a
+/// renderer and the code feeding it IDs hold the same interner, while
+/// separate experiments or tests each create their own and stay isolated.
+#[derive(Clone, Default)]
+pub struct Interner(Arc<RwLock<Table>>);
+
 lazy_static! {
-    static ref TEXT_INTERNER: RwLock<Vec<Atom>> = RwLock::new(Vec::new());
+    static ref GLOBAL: Interner = Interner::new();
 }
 
-/// Intern a string and return its ID
-pub fn intern_text(s: &str) -> usize {
-    let atom = Atom::from(s);
-    let mut v = TEXT_INTERNER.write().unwrap();
-    match v.iter().position(|a| *a == atom) {
-        Some(idx) => idx,
-        None => {
-            v.push(atom);
-            v.len() - 1
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process-wide interner behind [`intern_text`] and [`get_text`]
+    pub fn global() -> &'static Interner {
+        &GLOBAL
+    }
+
+    /// Interns `s` and returns its ID
+    pub fn intern(&self, s: &str) -> usize {
+        let atom = Atom::from(s);
+        if let Some(&id) = self.0.read().unwrap().ids.get(&atom) {
+            return id;
+        }
+        // Another thread may have interned it between the two locks
+        let mut table = self.0.write().unwrap();
+        if let Some(&id) = table.ids.get(&atom) {
+            return id;
         }
+        let id = table.texts.len();
+        table.texts.push(atom.clone());
+        table.ids.insert(atom, id);
+        id
+    }
+
+    /// Count of unique texts
+    pub fn len(&self) -> usize {
+        self.0.read().unwrap().texts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The text interned as `id`
+    ///
+    /// # Panics
+    ///
+    /// When `id` was not handed out by this interner; see [`Self::try_get`]
+    pub fn get(&self, id: usize) -> String {
+        self.try_get(id)
+            .unwrap_or_else(|| panic!("No text interned as {}", id))
+    }
+
+    /// The text interned as `id`, or `None` for an unknown ID
+    pub fn try_get(&self, id: usize) -> Option<String> {
+        self.0
+            .read()
+            .unwrap()
+            .texts
+            .get(id)
+            .map(|atom| atom.to_string())
+    }
+
+    /// Forgets every interned text. IDs handed out before are reused for new
+    /// texts, so nothing may hold one across the call.
+    pub fn clear(&self) {
+        let mut table = self.0.write().unwrap();
+        table.texts.clear();
+        table.ids.clear();
     }
 }
 
-/// Current count of unique texts
+/// Intern a string in the global interner and return its ID
+pub fn intern_text(s: &str) -> usize {
+    GLOBAL.intern(s)
+}
+
+/// Current count of unique texts in the global interner
 pub fn text_count() -> usize {
-    TEXT_INTERNER.read().unwrap().len()
+    GLOBAL.len()
 }
 
+/// The text interned globally as `id`
+///
+/// # Panics
+///
+/// When `id` was not handed out by [`intern_text`]; see [`try_get_text`]
 pub fn get_text(id: usize) -> String {
-    TEXT_INTERNER.read().unwrap()[id].to_string()
+    GLOBAL.get(id)
+}
+
+/// The text interned globally as `id`, or `None` for an unknown ID
+pub fn try_get_text(id: usize) -> Option<String> {
+    GLOBAL.try_get(id)
+}
+
+/// Forgets every globally interned text, so a process running several
+/// sessions doesn't keep each one's strings. IDs handed out before are
+/// reused for new texts, so nothing may hold one across the call: clear
+/// before building a session's renderer, not while one is drawing.
+pub fn clear() {
+    GLOBAL.clear();
 }
@@ -1,2 +1,2 @@
 pub mod cache;
-pub use cache::{get_text, intern_text, text_count, Atom};
+pub use cache::{clear, get_text, intern_text, text_count, try_get_text, Atom, Interner};
@@ -0,0 +1,39 @@
+use cogex_cache::{clear, get_text, intern_text, text_count, try_get_text, Interner};
+
+// One test for the global interner, as clearing it would race with others
+#[test]
+fn interning_is_stable_until_cleared() {
+    let threads: Vec<_> = (0..4)
+        .map(|_| std::thread::spawn(|| (0..100).map(|i| intern_text(&i.to_string())).collect()))
+        .collect();
+    let ids: Vec<Vec<usize>> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    assert!(ids.iter().all(|run| *run == ids[0]));
+    assert_eq!(get_text(ids[0][42]), "42");
+    assert_eq!(intern_text("42"), ids[0][42]);
+
+    let unknown = text_count();
+    assert_eq!(try_get_text(unknown), None);
+
+    clear();
+    assert_eq!(text_count(), 0);
+    assert_eq!(try_get_text(ids[0][0]), None);
+    assert_eq!(intern_text("after"), 0);
+}
+
+#[test]
+fn scoped_interners_share_ids_between_clones_only() {
+    let session = Interner::new();
+    let other = Interner::new();
+    let renderer = session.clone();
+
+    let id = session.intern("left");
+    assert_eq!(renderer.intern("left"), id);
+    assert_eq!(renderer.get(id), "left");
+    assert_eq!(other.try_get(id), None);
+    assert_eq!(other.intern("right"), 0);
+    assert_eq!(session.len(), 1);
+
+    renderer.clear();
+    assert!(session.is_empty());
+    assert_eq!(other.len(), 1);
+}
@@ -0,0 +1,280 @@
+use crate::stimulus::StimulusType;
+use std::time::Duration;
+
+/// Something the renderer can draw, positioned relative to the stimulus
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    /// Filled square centred on `offset`
+    Dot {
+        offset: (f32, f32),
+        size: f32,
+        color: [u8; 4],
+    },
+    /// Filled polygon through `points`
+    Polygon {
+        points: Vec<(f32, f32)>,
+        color: [u8; 4],
+    },
+    Text {
+        content: String,
+        offset: (f32, f32),
+        size: f32,
+        color: [u8; 4],
+    },
+    /// A static stimulus drawn as usual
+    Stimulus {
+        stimulus: StimulusType,
+        offset: (f32, f32),
+    },
+}
+
+/// A stimulus that changes every frame instead of being a cached pixmap.
+///
+/// The renderer calls `advance` with the time since the previous frame and
+/// then draws `primitives`.
+pub trait DynamicStimulus: Send {
+    fn advance(&mut self, dt: Duration);
+
+    fn primitives(&self) -> Vec<Primitive>;
+}
+
+impl StimulusType {
+    /// Starts the animation for a dynamic stimulus; `None` for static ones.
+    /// `seed` makes random animations reproducible.
+    pub fn animate(&self, seed: u64) -> Option<Box<dyn DynamicStimulus>> {
+        match self {
+            StimulusType::DotMotion { .. } => {
+                DotField::new(self, seed).map(|f| Box::new(f) as Box<dyn DynamicStimulus>)
+            }
+            StimulusType::RotatingBar {
+                width,
+                height,
+                speed,
+                color,
+            } => Some(Box::new(RotatingBar {
+                width: *width,
+                height: *height,
+                speed: *speed,
+                color: *color,
+                angle: 0.0,
+            })),
+            StimulusType::Countdown {
+                seconds,
+                size,
+                color,
+            } => Some(Box::new(Countdown {
+                remaining: Duration::from_secs(*seconds as u64),
+                size: *size,
+                color: *color,
+            })),
+            StimulusType::ProgressBar {
+                width,
+                height,
+                fraction,
+                color,
+            } => Some(Box::new(ProgressBar {
+                width: *width,
+                height: *height,
+                fraction: fraction.clamp(0.0, 1.0),
+                color: *color,
+            })),
+            _ => None,
+        }
+    }
+
+    /// True for stimuli animated through `animate`
+    pub fn is_dynamic(&self) -> bool {
+        matches!(
+            self,
+            StimulusType::DotMotion { .. }
+                | StimulusType::RotatingBar { .. }
+                | StimulusType::Countdown { .. }
+                | StimulusType::ProgressBar { .. }
+        )
+    }
+}
+
+/// Running state of a random-dot kinematogram: dot offsets from the
+/// aperture centre
+#[derive(Debug, Clone)]
+pub struct DotField {
+    dots: Vec<(f32, f32)>,
+    coherence: f32,
+    /// Step per second along the signal direction, screen y down
+    velocity: (f32, f32),
+    radius: f32,
+    dot_size: f32,
+    color: [u8; 4],
+    rng: u64,
+}
+
+impl DotField {
+    /// `None` for anything but `StimulusType::DotMotion`
+    pub fn new(stimulus: &StimulusType, seed: u64) -> Option<Self> {
+        let StimulusType::DotMotion {
+            coherence,
+            direction,
+            speed,
+            aperture_radius,
+            dot_size,
+            color,
+            ..
+        } = *stimulus
+        else {
+            return None;
+        };
+        let (sin, cos) = direction.to_radians().sin_cos();
+        let mut field = Self {
+            dots: Vec::new(),
+            coherence: coherence.clamp(0.0, 1.0),
+            velocity: (cos * speed, -sin * speed),
+            radius: aperture_radius.max(0.0),
+            dot_size,
+            color,
+            // xorshift must not start at zero
+            rng: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        };
+        let count = stimulus.dot_count().unwrap_or_default();
+        field.dots = (0..count).map(|_| field.random_point()).collect();
+        Some(field)
+    }
+
+    pub fn dots(&self) -> &[(f32, f32)] {
+        &self.dots
+    }
+
+    /// Uniform over the aperture disc
+    fn random_point(&mut self) -> (f32, f32) {
+        let r = self.radius * self.random().sqrt();
+        let (sin, cos) = (self.random() * std::f32::consts::TAU).sin_cos();
+        (r * cos, r * sin)
+    }
+
+    /// Uniform in 0..1 (xorshift64*)
+    fn random(&mut self) -> f32 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let bits = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40;
+        bits as f32 / (1u64 << 24) as f32
+    }
+}
+
+impl DynamicStimulus for DotField {
+    /// Moves signal dots by `dt` and replots noise dots and any dot that
+    /// left the aperture
+    fn advance(&mut self, dt: Duration) {
+        if dt.is_zero() {
+            return;
+        }
+        let step = (
+            self.velocity.0 * dt.as_secs_f32(),
+            self.velocity.1 * dt.as_secs_f32(),
+        );
+        for i in 0..self.dots.len() {
+            let signal = self.random() < self.coherence;
+            let (x, y) = self.dots[i];
+            let moved = (x + step.0, y + step.1);
+            self.dots[i] = if signal && moved.0.hypot(moved.1) <= self.radius {
+                moved
+            } else {
+                self.random_point()
+            };
+        }
+    }
+
+    fn primitives(&self) -> Vec<Primitive> {
+        self.dots
+            .iter()
+            .map(|&offset| Primitive::Dot {
+                offset,
+                size: self.dot_size,
+                color: self.color,
+            })
+            .collect()
+    }
+}
+
+/// Bar spinning about its centre
+#[derive(Debug, Clone)]
+pub struct RotatingBar {
+    pub width: f32,
+    pub height: f32,
+    /// Degrees per second, counter-clockwise
+    pub speed: f32,
+    pub color: [u8; 4],
+    /// Current angle in degrees
+    pub angle: f32,
+}
+
+impl DynamicStimulus for RotatingBar {
+    fn advance(&mut self, dt: Duration) {
+        self.angle = (self.angle + self.speed * dt.as_secs_f32()).rem_euclid(360.0);
+    }
+
+    fn primitives(&self) -> Vec<Primitive> {
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        let (hw, hh) = (self.width * 0.5, self.height * 0.5);
+        let points = [(-hw, -hh), (hw, -hh), (hw, hh), (-hw, hh)]
+            .into_iter()
+            .map(|(x, y)| (x * cos + y * sin, -x * sin + y * cos))
+            .collect();
+        vec![Primitive::Polygon {
+            points,
+            color: self.color,
+        }]
+    }
+}
+
+/// Whole seconds left, counting down to 1
+#[derive(Debug, Clone)]
+pub struct Countdown {
+    pub remaining: Duration,
+    pub size: f32,
+    pub color: [u8; 4],
+}
+
+impl DynamicStimulus for Countdown {
+    fn advance(&mut self, dt: Duration) {
+        self.remaining = self.remaining.saturating_sub(dt);
+    }
+
+    fn primitives(&self) -> Vec<Primitive> {
+        let seconds = self.remaining.as_secs_f32().ceil().max(1.0) as u64;
+        vec![Primitive::Text {
+            content: seconds.to_string(),
+            offset: (0.0, 0.0),
+            size: self.size,
+            color: self.color,
+        }]
+    }
+}
+
+/// Bar filled from the left; it changes only when given a new fraction
+#[derive(Debug, Clone)]
+pub struct ProgressBar {
+    pub width: f32,
+    pub height: f32,
+    pub fraction: f32,
+    pub color: [u8; 4],
+}
+
+impl DynamicStimulus for ProgressBar {
+    fn advance(&mut self, _dt: Duration) {}
+
+    /// The unfilled track at a quarter of the colour's opacity, under the
+    /// filled part
+    fn primitives(&self) -> Vec<Primitive> {
+        let (left, hh) = (-self.width * 0.5, self.height * 0.5);
+        let bar = |right: f32, color: [u8; 4]| Primitive::Polygon {
+            points: vec![(left, -hh), (right, -hh), (right, hh), (left, hh)],
+            color,
+        };
+        let [r, g, b, a] = self.color;
+        let mut primitives = vec![bar(-left, [r, g, b, a / 4])];
+        if self.fraction > 0.0 {
+            primitives.push(bar(left + self.width * self.fraction, self.color));
+        }
+        primitives
+    }
+}
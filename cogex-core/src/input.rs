@@ -0,0 +1,35 @@
+use crate::response::{MouseButton, ResponseKey};
+
+/// What a captured input event reports
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RawEvent {
+    Key(ResponseKey),
+    /// Click at a position in stimulus coordinates
+    Click {
+        button: MouseButton,
+        position: (f32, f32),
+    },
+    /// Speech onset picked up by a voice key
+    VoiceOnset,
+    /// Volume trigger (TR pulse) from an MRI scanner
+    ScannerPulse,
+}
+
+/// Input event stamped when it was captured, ahead of the state machine
+/// handling it
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RawInput {
+    pub event: RawEvent,
+    pub timestamp_ns: u64,
+}
+
+impl RawInput {
+    pub fn new(event: RawEvent, timestamp_ns: u64) -> Self {
+        Self {
+            event,
+            timestamp_ns,
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+/// One element of an instruction page, laid out top to bottom
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InstructionBlock {
+    Heading(String),
+    /// Text the renderer word-wraps to the screen width
+    Paragraph(String),
+    /// PNG image, resolved relative to the instruction file
+    Image(PathBuf),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstructionPage {
+    pub blocks: Vec<InstructionBlock>,
+}
+
+/// Participant-facing instruction pages, paged through with Space and
+/// Backspace.
+///
+/// Markdown or plain text: pages are separated by `---` lines, paragraphs
+/// by blank lines, `# ` lines are headings, `- ` lines list items, and
+/// `![alt](file.png)` on its own line embeds an image.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Instructions {
+    pages: Vec<InstructionPage>,
+    current: usize,
+}
+
+impl Instructions {
+    pub fn new(pages: Vec<InstructionPage>) -> Self {
+        Self { pages, current: 0 }
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&text, path.parent().unwrap_or(Path::new("."))))
+    }
+
+    /// Parses instruction text; image paths are relative to `base_dir`
+    pub fn parse(text: &str, base_dir: &Path) -> Self {
+        let mut pages = Vec::new();
+        let mut page = InstructionPage::default();
+        let mut paragraph = String::new();
+
+        fn flush(paragraph: &mut String, page: &mut InstructionPage) {
+            if !paragraph.is_empty() {
+                page.blocks
+                    .push(InstructionBlock::Paragraph(std::mem::take(paragraph)));
+            }
+        }
+
+        for line in text.lines() {
+            let line = line.trim();
+            if is_page_break(line) {
+                flush(&mut paragraph, &mut page);
+                if !page.blocks.is_empty() {
+                    pages.push(std::mem::take(&mut page));
+                }
+            } else if line.is_empty() {
+                flush(&mut paragraph, &mut page);
+            } else if let Some(heading) = line.strip_prefix('#') {
+                flush(&mut paragraph, &mut page);
+                let heading = heading.trim_start_matches('#').trim();
+                page.blocks
+                    .push(InstructionBlock::Heading(strip_emphasis(heading)));
+            } else if let Some(image) = parse_image(line) {
+                flush(&mut paragraph, &mut page);
+                page.blocks
+                    .push(InstructionBlock::Image(base_dir.join(image)));
+            } else if let Some(item) = line.strip_prefix("- ").or(line.strip_prefix("* ")) {
+                flush(&mut paragraph, &mut page);
+                page.blocks.push(InstructionBlock::Paragraph(format!(
+                    "• {}",
+                    strip_emphasis(item.trim())
+                )));
+            } else {
+                if !paragraph.is_empty() {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(&strip_emphasis(line));
+            }
+        }
+        flush(&mut paragraph, &mut page);
+        if !page.blocks.is_empty() {
+            pages.push(page);
+        }
+        Self::new(pages)
+    }
+
+    pub fn pages(&self) -> &[InstructionPage] {
+        &self.pages
+    }
+
+    pub fn page(&self) -> Option<&InstructionPage> {
+        self.pages.get(self.current)
+    }
+
+    pub fn page_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_last_page(&self) -> bool {
+        self.current + 1 >= self.pages.len()
+    }
+
+    /// Moves forward a page; false on the last page
+    pub fn next_page(&mut self) -> bool {
+        if self.is_last_page() {
+            return false;
+        }
+        self.current += 1;
+        true
+    }
+
+    /// Moves back a page; false on the first page
+    pub fn previous_page(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.current = 0;
+    }
+}
+
+fn is_page_break(line: &str) -> bool {
+    line == "\u{c}" || (line.len() >= 3 && line.chars().all(|c| c == '-'))
+}
+
+/// `![alt](path)` filling the whole line
+fn parse_image(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("![")?;
+    let (_, rest) = rest.split_once("](")?;
+    rest.strip_suffix(')').map(str::trim)
+}
+
+fn strip_emphasis(text: &str) -> String {
+    text.replace("**", "").replace("__", "").replace('`', "")
+}
@@ -1,7 +1,31 @@
+pub mod dynamic;
+pub mod input;
+pub mod instructions;
 pub mod phase;
+pub mod preintern;
+pub mod questionnaire;
+pub mod response;
 pub mod stimulus;
+pub mod summary;
+pub mod theme;
 pub mod trial;
+pub mod units;
 
-pub use phase::{Phase, StandardPhase};
+pub use cogex_cache::Atom;
+pub use dynamic::{Countdown, DotField, DynamicStimulus, Primitive, ProgressBar, RotatingBar};
+pub use input::{RawEvent, RawInput};
+pub use instructions::{InstructionBlock, InstructionPage, Instructions};
+pub use phase::{Phase, PhaseExit, PhaseGraph, StandardPhase};
+pub use preintern::PreinternPlan;
+pub use questionnaire::{QuestionItem, QuestionKind, Questionnaire};
+pub use response::{MouseButton, ResponseKey, ResponseMapping};
 pub use stimulus::{ArrowDirection, Stimulus, StimulusType};
-pub use trial::{TrialResult, TrialState};
+pub use summary::DebriefSummary;
+pub use theme::Theme;
+pub use trial::{
+    FeedbackOutcome, PresentationTimestamps, TrialFeedback, TrialOutcome, TrialResult, TrialState,
+};
+pub use units::{DisplayGeometry, VisualAngle};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
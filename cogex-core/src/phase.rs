@@ -14,23 +14,31 @@ pub trait Phase: Copy + Clone + PartialEq + Send + Sync + std::fmt::Debug + Defa
     fn is_welcome(&self) -> bool {
         false
     }
+
+    /// Instruction pages, advanced by the participant
+    fn is_instructions(&self) -> bool {
+        false
+    }
+
+    /// Questionnaire items, answered with number keys or clicks
+    fn is_questionnaire(&self) -> bool {
+        false
+    }
 }
 
-#[derive(Copy, Debug, Clone, PartialEq)]
+#[derive(Copy, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StandardPhase {
+    #[default]
     Welcome,
+    Instructions,
     Calibration,
     Practice,
     Experiment,
+    Questionnaire,
     Debrief,
 }
 
-impl Default for StandardPhase {
-    fn default() -> Self {
-        StandardPhase::Welcome
-    }
-}
-
 impl Phase for StandardPhase {
     fn allows_input(&self) -> bool {
         !matches!(self, Self::Calibration)
@@ -41,10 +49,12 @@ impl Phase for StandardPhase {
     fn next(&self) -> Option<Self> {
         use StandardPhase::*;
         Some(match self {
-            Welcome => Calibration,
+            Welcome => Instructions,
+            Instructions => Calibration,
             Calibration => Practice,
             Practice => Experiment,
-            Experiment => Debrief,
+            Experiment => Questionnaire,
+            Questionnaire => Debrief,
             Debrief => return None,
         })
     }
@@ -60,4 +70,137 @@ impl Phase for StandardPhase {
     fn is_welcome(&self) -> bool {
         matches!(self, StandardPhase::Welcome)
     }
+
+    fn is_instructions(&self) -> bool {
+        matches!(self, StandardPhase::Instructions)
+    }
+
+    fn is_questionnaire(&self) -> bool {
+        matches!(self, StandardPhase::Questionnaire)
+    }
+}
+
+/// How a phase went, for choosing the branch out of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhaseExit<P> {
+    pub phase: P,
+    /// Trials run since the phase was last entered
+    pub trials: usize,
+    /// Share of those trials scored correct, if any were scored
+    pub accuracy: Option<f64>,
+    /// Times the phase has been entered, counting this one
+    pub visits: usize,
+}
+
+type Guard<P> = Box<dyn Fn(&PhaseExit<P>) -> bool + Send + Sync>;
+
+struct Node<P> {
+    phase: P,
+    next: Option<P>,
+    branches: Vec<(P, Guard<P>)>,
+}
+
+/// Successors of each phase, consulted by the state machine in place of
+/// `Phase::next()` so phases can branch, repeat or interleave.
+///
+/// Leaving a phase tries its branches in the order they were added, then
+/// falls back to its plain edge. A phase with neither ends the experiment.
+pub struct PhaseGraph<P: Phase> {
+    nodes: Vec<Node<P>>,
+}
+
+impl<P: Phase> PhaseGraph<P> {
+    /// A graph holding only the starting phase, `P::default()`
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node {
+                phase: P::default(),
+                next: None,
+                branches: Vec::new(),
+            }],
+        }
+    }
+
+    /// The single chain `Phase::next()` walks, as a base for adding branches
+    pub fn linear() -> Self {
+        let mut graph = Self::new();
+        let mut phase = P::default();
+        while let Some(next) = phase.next() {
+            if graph.index_of(&next).is_some() {
+                break;
+            }
+            graph = graph.edge(phase, next);
+            phase = next;
+        }
+        graph
+    }
+
+    /// Makes `to` the successor of `from` when no branch applies, replacing
+    /// any earlier edge out of `from`
+    pub fn edge(mut self, from: P, to: P) -> Self {
+        self.node_mut(to);
+        self.node_mut(from).next = Some(to);
+        self
+    }
+
+    /// Leaves `from` for `to` when `guard` holds on the way out
+    pub fn branch(
+        mut self,
+        from: P,
+        to: P,
+        guard: impl Fn(&PhaseExit<P>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.node_mut(to);
+        self.node_mut(from).branches.push((to, Box::new(guard)));
+        self
+    }
+
+    /// The phase to enter on leaving `exit.phase`
+    pub fn next(&self, exit: &PhaseExit<P>) -> Option<P> {
+        let node = self.nodes.iter().find(|n| n.phase == exit.phase)?;
+        node.branches
+            .iter()
+            .find(|(_, guard)| guard(exit))
+            .map(|(to, _)| *to)
+            .or(node.next)
+    }
+
+    /// Whether `phase` has no way out, i.e. is where the experiment ends
+    pub fn is_terminal(&self, phase: &P) -> bool {
+        self.nodes
+            .iter()
+            .find(|n| n.phase == *phase)
+            .is_none_or(|n| n.next.is_none() && n.branches.is_empty())
+    }
+
+    /// Every phase in the graph, starting phase first
+    pub fn phases(&self) -> impl Iterator<Item = P> + '_ {
+        self.nodes.iter().map(|n| n.phase)
+    }
+
+    /// Position of `phase` in `phases()`
+    pub fn index_of(&self, phase: &P) -> Option<usize> {
+        self.nodes.iter().position(|n| n.phase == *phase)
+    }
+
+    fn node_mut(&mut self, phase: P) -> &mut Node<P> {
+        let index = match self.index_of(&phase) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(Node {
+                    phase,
+                    next: None,
+                    branches: Vec::new(),
+                });
+                self.nodes.len() - 1
+            }
+        };
+        &mut self.nodes[index]
+    }
+}
+
+impl<P: Phase> Default for PhaseGraph<P> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,33 @@
+/// Strings derived from the session settings that a renderer formats and
+/// interns before the first trial, so drawing a trial only looks their IDs
+/// up
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PreinternPlan {
+    /// Trial counts the progress counter runs up to, one per phase showing
+    /// it
+    pub progress_totals: Vec<usize>,
+    /// Longest reaction time feedback shows, in ms; `None` when feedback
+    /// shows none
+    pub feedback_rt_max_ms: Option<u64>,
+}
+
+impl PreinternPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a phase counting up to `trials`
+    pub fn with_progress_total(mut self, trials: usize) -> Self {
+        if !self.progress_totals.contains(&trials) {
+            self.progress_totals.push(trials);
+        }
+        self
+    }
+
+    /// Covers reaction times on feedback up to `max_ms`, keeping the longer
+    /// of this and any earlier limit
+    pub fn with_feedback_rt(mut self, max_ms: u64) -> Self {
+        self.feedback_rt_max_ms = Some(self.feedback_rt_max_ms.map_or(max_ms, |m| m.max(max_ms)));
+        self
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+/// How an item is answered
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QuestionKind {
+    /// Rating from 1 to `points`, with labels for the two ends
+    Likert {
+        points: u8,
+        low: String,
+        high: String,
+    },
+    MultipleChoice(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuestionItem {
+    /// Column name of the answer in the results file
+    pub id: String,
+    pub prompt: String,
+    pub kind: QuestionKind,
+}
+
+impl QuestionItem {
+    pub fn likert(id: &str, prompt: &str, points: u8, low: &str, high: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            prompt: prompt.to_string(),
+            kind: QuestionKind::Likert {
+                points: points.clamp(2, 9),
+                low: low.to_string(),
+                high: high.to_string(),
+            },
+        }
+    }
+
+    pub fn choice(id: &str, prompt: &str, options: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            prompt: prompt.to_string(),
+            kind: QuestionKind::MultipleChoice(options.iter().map(|o| o.to_string()).collect()),
+        }
+    }
+
+    pub fn option_count(&self) -> usize {
+        match &self.kind {
+            QuestionKind::Likert { points, .. } => *points as usize,
+            QuestionKind::MultipleChoice(options) => options.len(),
+        }
+    }
+
+    /// Text recorded for option `index`: the rating for Likert items, the
+    /// option itself for multiple choice
+    pub fn option_label(&self, index: usize) -> Option<String> {
+        match &self.kind {
+            QuestionKind::Likert { points, .. } => {
+                (index < *points as usize).then(|| (index + 1).to_string())
+            }
+            QuestionKind::MultipleChoice(options) => options.get(index).cloned(),
+        }
+    }
+}
+
+/// Post-task ratings shown one item per screen. Keys 1–9 or a click pick an
+/// option and move on; Backspace returns to the previous item.
+///
+/// Plain text, one item per line with `|`-separated fields:
+/// `likert <points> | <id> | <prompt> | <low label> | <high label>` or
+/// `choice | <id> | <prompt> | <option> | <option> ...` with two to nine
+/// options. Blank lines and `#` comments are skipped.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Questionnaire {
+    items: Vec<QuestionItem>,
+    answers: Vec<Option<usize>>,
+    current: usize,
+}
+
+impl Questionnaire {
+    pub fn new(items: Vec<QuestionItem>) -> Self {
+        Self {
+            answers: vec![None; items.len()],
+            items,
+            current: 0,
+        }
+    }
+
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut items = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+            let item = match fields.as_slice() {
+                [kind, id, prompt, low, high] if kind.starts_with("likert") => {
+                    let points = kind["likert".len()..].trim();
+                    let points = if points.is_empty() {
+                        7
+                    } else {
+                        points
+                            .parse()
+                            .map_err(|_| format!("Line {}: invalid point count", number + 1))?
+                    };
+                    QuestionItem::likert(id, prompt, points, low, high)
+                }
+                ["choice", id, prompt, options @ ..] if (2..=9).contains(&options.len()) => {
+                    QuestionItem::choice(id, prompt, options)
+                }
+                _ => {
+                    return Err(format!(
+                        "Line {}: expected a likert or choice item",
+                        number + 1
+                    ))
+                }
+            };
+            items.push(item);
+        }
+        Ok(Self::new(items))
+    }
+
+    pub fn items(&self) -> &[QuestionItem] {
+        &self.items
+    }
+
+    /// The item on screen, `None` once all are answered
+    pub fn item(&self) -> Option<&QuestionItem> {
+        self.items.get(self.current)
+    }
+
+    pub fn item_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Option picked for each item, by index
+    pub fn answers(&self) -> &[Option<usize>] {
+        &self.answers
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current >= self.items.len()
+    }
+
+    /// Answers the current item with option `index` and moves to the next;
+    /// false if there is no such option
+    pub fn answer(&mut self, index: usize) -> bool {
+        let Some(item) = self.item() else {
+            return false;
+        };
+        if index >= item.option_count() {
+            return false;
+        }
+        self.answers[self.current] = Some(index);
+        self.current += 1;
+        true
+    }
+
+    /// Goes back an item to change its answer; false on the first
+    pub fn previous(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.answers = vec![None; self.items.len()];
+        self.current = 0;
+    }
+}
@@ -0,0 +1,205 @@
+use crate::stimulus::{ArrowDirection, StimulusType};
+use std::sync::Arc;
+
+/// Backend-independent mouse buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u16),
+}
+
+/// Backend-independent response keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResponseKey {
+    Space,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Char(char),
+    Mouse(MouseButton),
+}
+
+impl ResponseKey {
+    /// Key from its name in scripts and config files: `space`, `left`,
+    /// `right`, `up`, `down`, `mouse-left`, `mouse-right`, `mouse-middle`
+    /// or a single character
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "space" => ResponseKey::Space,
+            "left" => ResponseKey::ArrowLeft,
+            "right" => ResponseKey::ArrowRight,
+            "up" => ResponseKey::ArrowUp,
+            "down" => ResponseKey::ArrowDown,
+            "mouse-left" => ResponseKey::Mouse(MouseButton::Left),
+            "mouse-right" => ResponseKey::Mouse(MouseButton::Right),
+            "mouse-middle" => ResponseKey::Mouse(MouseButton::Middle),
+            _ => {
+                let mut chars = name.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                ResponseKey::Char(c)
+            }
+        })
+    }
+
+    /// Name [`from_name`](Self::from_name) reads back; other mouse buttons
+    /// are `mouse-<n>`
+    pub fn name(&self) -> String {
+        match self {
+            ResponseKey::Space => "space".to_string(),
+            ResponseKey::ArrowLeft => "left".to_string(),
+            ResponseKey::ArrowRight => "right".to_string(),
+            ResponseKey::ArrowUp => "up".to_string(),
+            ResponseKey::ArrowDown => "down".to_string(),
+            ResponseKey::Char(c) => c.to_string(),
+            ResponseKey::Mouse(MouseButton::Left) => "mouse-left".to_string(),
+            ResponseKey::Mouse(MouseButton::Right) => "mouse-right".to_string(),
+            ResponseKey::Mouse(MouseButton::Middle) => "mouse-middle".to_string(),
+            ResponseKey::Mouse(MouseButton::Other(n)) => format!("mouse-{}", n),
+        }
+    }
+}
+
+impl From<ArrowDirection> for ResponseKey {
+    fn from(direction: ArrowDirection) -> Self {
+        match direction {
+            ArrowDirection::Up => ResponseKey::ArrowUp,
+            ArrowDirection::Down => ResponseKey::ArrowDown,
+            ArrowDirection::Left => ResponseKey::ArrowLeft,
+            ArrowDirection::Right => ResponseKey::ArrowRight,
+        }
+    }
+}
+
+type Rule<S> = Arc<dyn Fn(&S) -> Option<ResponseKey> + Send + Sync>;
+
+/// Maps stimuli to the key they expect as a correct response.
+///
+/// Rules are checked in insertion order; the first rule returning a key wins.
+/// A stimulus no rule matches expects the fallback key, and with no fallback
+/// the correct response is to withhold.
+#[derive(Clone)]
+pub struct ResponseMapping<S> {
+    rules: Vec<Rule<S>>,
+    fallback: Option<ResponseKey>,
+    keys: Vec<ResponseKey>,
+}
+
+impl<S> ResponseMapping<S> {
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            fallback: None,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Adds a rule mapping matching stimuli to `key`
+    pub fn with_rule<F>(mut self, key: ResponseKey, matches: F) -> Self
+    where
+        F: Fn(&S) -> bool + Send + Sync + 'static,
+    {
+        self.rules
+            .push(Arc::new(move |s| if matches(s) { Some(key) } else { None }));
+        self.register_key(key);
+        self
+    }
+
+    /// Adds a rule computing the expected key from the stimulus itself.
+    /// `keys` lists every key the rule can return so they are accepted as input.
+    pub fn with_rule_fn<F>(mut self, keys: &[ResponseKey], expected: F) -> Self
+    where
+        F: Fn(&S) -> Option<ResponseKey> + Send + Sync + 'static,
+    {
+        self.rules.push(Arc::new(expected));
+        for &key in keys {
+            self.register_key(key);
+        }
+        self
+    }
+
+    /// Key expected for stimuli no rule matches
+    pub fn with_fallback(mut self, key: ResponseKey) -> Self {
+        self.fallback = Some(key);
+        self.register_key(key);
+        self
+    }
+
+    /// Returns the expected key for `stimulus`, or `None` if it must be withheld
+    pub fn expected_key(&self, stimulus: &S) -> Option<ResponseKey> {
+        self.rules
+            .iter()
+            .find_map(|rule| rule(stimulus))
+            .or(self.fallback)
+    }
+
+    /// Returns true if `key` is part of the response set
+    pub fn accepts(&self, key: ResponseKey) -> bool {
+        self.keys.contains(&key)
+    }
+
+    /// All keys which count as a response
+    pub fn keys(&self) -> &[ResponseKey] {
+        &self.keys
+    }
+
+    /// Returns true if any mouse button counts as a response
+    pub fn accepts_mouse(&self) -> bool {
+        self.keys.iter().any(|k| matches!(k, ResponseKey::Mouse(_)))
+    }
+
+    /// Scores a response: correct when the pressed key matches the expected one,
+    /// or when nothing was pressed for a stimulus that expects no response.
+    pub fn is_correct(&self, stimulus: &S, pressed: Option<ResponseKey>) -> bool {
+        self.expected_key(stimulus) == pressed
+    }
+
+    fn register_key(&mut self, key: ResponseKey) {
+        if !self.keys.contains(&key) {
+            self.keys.push(key);
+        }
+    }
+}
+
+impl ResponseMapping<StimulusType> {
+    /// Arrows expect the matching arrow key, everything else Space
+    pub fn standard() -> Self {
+        Self::new()
+            .with_rule_fn(
+                &[
+                    ResponseKey::ArrowUp,
+                    ResponseKey::ArrowDown,
+                    ResponseKey::ArrowLeft,
+                    ResponseKey::ArrowRight,
+                ],
+                |s| match s {
+                    StimulusType::Arrow { direction, .. } => Some((*direction).into()),
+                    _ => None,
+                },
+            )
+            .with_fallback(ResponseKey::Space)
+    }
+}
+
+impl<S> Default for ResponseMapping<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> std::fmt::Debug for ResponseMapping<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseMapping")
+            .field("rules", &self.rules.len())
+            .field("fallback", &self.fallback)
+            .field("keys", &self.keys)
+            .finish()
+    }
+}
@@ -1,11 +1,28 @@
-use cogex_cache::intern_text;
+use crate::dynamic::Primitive;
+use cogex_cache::{Atom, Interner};
 /// Defines stimuli and their render data
 pub trait Stimulus: Clone + Send + Sync + std::fmt::Debug {
-    fn cache_id(&self) -> usize;
+    /// Cache slot of the stimulus, with text interned in the global interner
+    fn cache_id(&self) -> usize {
+        self.cache_id_in(Interner::global())
+    }
+    /// Cache slot of the stimulus, with text interned in `interner`
+    fn cache_id_in(&self, interner: &Interner) -> usize;
     fn is_text(&self) -> bool;
+
+    /// True for stimuli presented through the audio subsystem
+    fn is_audio(&self) -> bool {
+        false
+    }
+
+    /// Condition label used to group results
+    fn condition(&self) -> String {
+        self.cache_id().to_string()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StimulusType {
     Circle {
         radius: f32,
@@ -21,14 +38,242 @@ pub enum StimulusType {
         size: f32,
         color: [u8; 4],
     },
+    /// A word or string, e.g. from a word list loaded at runtime
     Text {
-        content: &'static str,
+        content: Atom,
+        size: f32,
+        color: [u8; 4],
+    },
+    /// Sinusoidal grating under a Gaussian envelope.
+    /// `spatial_freq` is in cycles per pixel, `sigma` in pixels (cycles per
+    /// degree and degrees when a `DisplayGeometry` is configured),
+    /// `orientation` and `phase` in degrees, `contrast` in 0..=1.
+    Gabor {
+        spatial_freq: f32,
+        orientation: f32,
+        contrast: f32,
+        sigma: f32,
+        phase: f32,
+    },
+    /// Random black and white checks of `grain` × `grain` filling
+    /// `width` × `height`, all in pixels (degrees with a `DisplayGeometry`),
+    /// e.g. a backward mask. Every mask of one size shows the same pattern.
+    NoiseMask {
+        width: f32,
+        height: f32,
+        grain: f32,
+    },
+    /// Sine tone, `amplitude` in 0..=1
+    Tone {
+        frequency_hz: f32,
+        duration_ms: u64,
+        amplitude: f32,
+    },
+    /// WAV file played from disk
+    Sound {
+        path: Atom,
+        amplitude: f32,
+    },
+    /// Random-dot kinematogram in a circular aperture. Each frame a
+    /// `coherence` share of dots steps in `direction` (degrees, 0 = right,
+    /// counter-clockwise) and the rest are replotted at random. `speed` is in
+    /// pixels per second, `density` in dots per square pixel, and
+    /// `aperture_radius` and `dot_size` in pixels (degrees throughout when a
+    /// `DisplayGeometry` is configured).
+    DotMotion {
+        coherence: f32,
+        direction: f32,
+        speed: f32,
+        density: f32,
+        aperture_radius: f32,
+        dot_size: f32,
+        color: [u8; 4],
+    },
+    /// Bar of `width` × `height` spinning at `speed` degrees per second
+    RotatingBar {
+        width: f32,
+        height: f32,
+        speed: f32,
+        color: [u8; 4],
+    },
+    /// Whole seconds counting down from `seconds`
+    Countdown {
+        seconds: u32,
         size: f32,
         color: [u8; 4],
     },
+    /// Horizontal bar of `width` × `height` filled from the left up to
+    /// `fraction`, in 0..=1, e.g. the share of trials done
+    ProgressBar {
+        width: f32,
+        height: f32,
+        fraction: f32,
+        color: [u8; 4],
+    },
+    /// Visual analog scale: a horizontal bar `width` long with `low` and
+    /// `high` labelling its ends. The participant places a marker on it with
+    /// the arrow keys or a click and confirms with Space.
+    RatingScale {
+        width: f32,
+        low: Atom,
+        high: Atom,
+        color: [u8; 4],
+    },
+}
+
+impl StimulusType {
+    /// Number of dots in a kinematogram's aperture
+    pub fn dot_count(&self) -> Option<usize> {
+        match self {
+            StimulusType::DotMotion {
+                density,
+                aperture_radius,
+                ..
+            } => Some(
+                (density * std::f32::consts::PI * aperture_radius * aperture_radius).round()
+                    as usize,
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn is_rating_scale(&self) -> bool {
+        matches!(self, StimulusType::RatingScale { .. })
+    }
+
+    /// Scale position, 0 at the left end and 1 at the right, of a point
+    /// `dx` to the right of the stimulus centre
+    pub fn rating_at(&self, dx: f32) -> Option<f32> {
+        match self {
+            StimulusType::RatingScale { width, .. } if *width > 0.0 => {
+                Some((0.5 + dx / width).clamp(0.0, 1.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// A rating scale drawn with its marker at `rating`, if placed; empty
+    /// for other stimuli
+    pub fn rating_primitives(&self, rating: Option<f32>) -> Vec<Primitive> {
+        let StimulusType::RatingScale {
+            width,
+            ref low,
+            ref high,
+            color,
+        } = *self
+        else {
+            return Vec::new();
+        };
+        let rect = |cx: f32, w: f32, h: f32, color: [u8; 4]| Primitive::Polygon {
+            points: vec![
+                (cx - w * 0.5, -h * 0.5),
+                (cx + w * 0.5, -h * 0.5),
+                (cx + w * 0.5, h * 0.5),
+                (cx - w * 0.5, h * 0.5),
+            ],
+            color,
+        };
+        let half = width * 0.5;
+        let thickness = (width * 0.01).max(2.0);
+        let tick = width * 0.04;
+        let label = |content: &str, x: f32| Primitive::Text {
+            content: content.to_string(),
+            offset: (x, tick + width * 0.04),
+            size: width * 0.05,
+            color,
+        };
+        let mut primitives = vec![
+            rect(0.0, width, thickness, color),
+            rect(-half, thickness, tick * 2.0, color),
+            rect(half, thickness, tick * 2.0, color),
+            label(low, -half),
+            label(high, half),
+        ];
+        if let Some(rating) = rating {
+            let x = (rating.clamp(0.0, 1.0) - 0.5) * width;
+            primitives.push(rect(x, thickness * 3.0, tick * 3.0, [255, 200, 0, 255]));
+        }
+        primitives
+    }
+
+    /// Radius of a circle around the stimulus's centre enclosing all of it,
+    /// in the units of its size; text is estimated from its length
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            StimulusType::Circle { radius, .. } => *radius,
+            StimulusType::Rectangle { width, height, .. }
+            | StimulusType::RotatingBar { width, height, .. }
+            | StimulusType::NoiseMask { width, height, .. }
+            | StimulusType::ProgressBar { width, height, .. } => width.hypot(*height) / 2.0,
+            StimulusType::Arrow { size, .. } | StimulusType::Countdown { size, .. } => *size,
+            StimulusType::Text { content, size, .. } => {
+                let chars = content.chars().count().max(1) as f32;
+                (0.3 * size * chars).hypot(0.5 * size)
+            }
+            StimulusType::Gabor { sigma, .. } => 3.0 * sigma,
+            StimulusType::DotMotion {
+                aperture_radius,
+                dot_size,
+                ..
+            } => aperture_radius + dot_size / 2.0,
+            StimulusType::RatingScale { width, .. } => width / 2.0,
+            StimulusType::Tone { .. } | StimulusType::Sound { .. } => 0.0,
+        }
+    }
+
+    /// The same stimulus with every length multiplied by `factor`, e.g.
+    /// from logical to physical pixels
+    pub fn scaled(&self, factor: f32) -> StimulusType {
+        let mut scaled = self.clone();
+        match &mut scaled {
+            StimulusType::Circle { radius, .. } => *radius *= factor,
+            StimulusType::Rectangle { width, height, .. }
+            | StimulusType::RotatingBar { width, height, .. }
+            | StimulusType::ProgressBar { width, height, .. } => {
+                *width *= factor;
+                *height *= factor;
+            }
+            StimulusType::Arrow { size, .. }
+            | StimulusType::Text { size, .. }
+            | StimulusType::Countdown { size, .. } => *size *= factor,
+            StimulusType::Gabor {
+                spatial_freq,
+                sigma,
+                ..
+            } => {
+                *spatial_freq /= factor;
+                *sigma *= factor;
+            }
+            StimulusType::NoiseMask {
+                width,
+                height,
+                grain,
+            } => {
+                *width *= factor;
+                *height *= factor;
+                *grain *= factor;
+            }
+            StimulusType::DotMotion {
+                speed,
+                density,
+                aperture_radius,
+                dot_size,
+                ..
+            } => {
+                *speed *= factor;
+                *density /= factor * factor;
+                *aperture_radius *= factor;
+                *dot_size *= factor;
+            }
+            StimulusType::RatingScale { width, .. } => *width *= factor,
+            StimulusType::Tone { .. } | StimulusType::Sound { .. } => {}
+        }
+        scaled
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ArrowDirection {
     Up,
     Down,
@@ -37,16 +282,53 @@ pub enum ArrowDirection {
 }
 
 impl Stimulus for StimulusType {
-    fn cache_id(&self) -> usize {
+    fn cache_id_in(&self, interner: &Interner) -> usize {
         match self {
             StimulusType::Circle { .. } => 0,
             StimulusType::Rectangle { .. } => 1,
             StimulusType::Arrow { .. } => 2,
-            StimulusType::Text { content, .. } => 3 + intern_text(content), // Add more variants here, ensuring unique IDs.
+            StimulusType::Gabor { .. } => 3,
+            StimulusType::Tone { .. } => 4,
+            StimulusType::Sound { .. } => 5,
+            StimulusType::DotMotion { .. } => 6,
+            StimulusType::RotatingBar { .. } => 7,
+            StimulusType::Countdown { .. } => 8,
+            StimulusType::RatingScale { .. } => 9,
+            StimulusType::NoiseMask { .. } => 10,
+            StimulusType::ProgressBar { .. } => 11,
+            StimulusType::Text { content, .. } => 12 + interner.intern(content), // Add more variants here, ensuring unique IDs.
         }
     }
 
     fn is_text(&self) -> bool {
         matches!(self, StimulusType::Text { .. })
     }
+
+    fn is_audio(&self) -> bool {
+        matches!(self, StimulusType::Tone { .. } | StimulusType::Sound { .. })
+    }
+
+    fn condition(&self) -> String {
+        match self {
+            StimulusType::Circle { .. } => "circle".to_string(),
+            StimulusType::Rectangle { .. } => "rectangle".to_string(),
+            StimulusType::Arrow { direction, .. } => {
+                format!("arrow_{}", format!("{:?}", direction).to_lowercase())
+            }
+            StimulusType::Text { content, .. } => format!("text:{}", content),
+            StimulusType::Gabor { .. } => "gabor".to_string(),
+            StimulusType::NoiseMask { .. } => "noise_mask".to_string(),
+            StimulusType::Tone { frequency_hz, .. } => format!("tone_{}hz", frequency_hz),
+            StimulusType::Sound { path, .. } => format!("sound:{}", path),
+            StimulusType::DotMotion {
+                coherence,
+                direction,
+                ..
+            } => format!("dots_{}deg_{}pct", direction, (coherence * 100.0).round()),
+            StimulusType::RotatingBar { .. } => "rotating_bar".to_string(),
+            StimulusType::Countdown { seconds, .. } => format!("countdown_{}s", seconds),
+            StimulusType::RatingScale { .. } => "rating_scale".to_string(),
+            StimulusType::ProgressBar { .. } => "progress_bar".to_string(),
+        }
+    }
 }
@@ -0,0 +1,45 @@
+use crate::trial::TrialResult;
+
+/// Accuracy and reaction times over a set of results, for the debrief screen
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebriefSummary {
+    pub trials: usize,
+    /// Trials scored correct; unscored trials count as incorrect
+    pub correct: usize,
+    /// Over correct trials with a response
+    pub mean_rt_ms: Option<f64>,
+    pub median_rt_ms: Option<f64>,
+    /// Refreshes missed across all trials
+    pub dropped_frames: u64,
+}
+
+impl DebriefSummary {
+    pub fn from_results<S>(results: &[TrialResult<S>]) -> Self {
+        let mut rts: Vec<f64> = results
+            .iter()
+            .filter(|r| r.correct == Some(true))
+            .filter_map(|r| r.reaction_time_ns)
+            .map(|ns| ns as f64 / 1e6)
+            .collect();
+        rts.sort_by(f64::total_cmp);
+        let mean_rt_ms = (!rts.is_empty()).then(|| rts.iter().sum::<f64>() / rts.len() as f64);
+        let median_rt_ms = match rts.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(rts[n / 2]),
+            n => Some((rts[n / 2 - 1] + rts[n / 2]) * 0.5),
+        };
+        Self {
+            trials: results.len(),
+            correct: results.iter().filter(|r| r.correct == Some(true)).count(),
+            mean_rt_ms,
+            median_rt_ms,
+            dropped_frames: results.iter().map(|r| r.dropped_frames).sum(),
+        }
+    }
+
+    /// Proportion correct, `None` without trials
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.trials > 0).then(|| self.correct as f64 / self.trials as f64)
+    }
+}
@@ -0,0 +1,44 @@
+/// Colours of the participant screen, as straight (not premultiplied) RGBA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct Theme {
+    /// Screen behind everything; its alpha is ignored, the screen is opaque
+    pub background: [u8; 4],
+    /// Fixation cross, labels, instructions and other participant text
+    pub foreground: [u8; 4],
+    /// Feedback verdicts
+    pub correct: [u8; 4],
+    pub wrong: [u8; 4],
+    pub too_slow: [u8; 4],
+    /// Circles, rectangles and arrows drawn from the startup cache, i.e.
+    /// without `display`, whatever colour their stimulus names
+    pub circle: [u8; 4],
+    pub rectangle: [u8; 4],
+    pub arrow: [u8; 4],
+}
+
+impl Theme {
+    /// `background` with full alpha
+    pub fn opaque_background(&self) -> [u8; 4] {
+        let [r, g, b, _] = self.background;
+        [r, g, b, 255]
+    }
+}
+
+impl Default for Theme {
+    /// White on black, with red circles, green rectangles and blue arrows
+    fn default() -> Self {
+        const WHITE: [u8; 4] = [255, 255, 255, 255];
+        Self {
+            background: [0, 0, 0, 255],
+            foreground: WHITE,
+            correct: WHITE,
+            wrong: WHITE,
+            too_slow: WHITE,
+            circle: [255, 0, 0, 255],
+            rectangle: [0, 255, 0, 255],
+            arrow: [0, 0, 255, 255],
+        }
+    }
+}
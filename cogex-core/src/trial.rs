@@ -1,20 +1,160 @@
+use crate::response::ResponseKey;
+
 /// Trial state machine events
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TrialState {
     Fixation,
+    /// Cue shown ahead of the target, then fixation until the cue-target
+    /// SOA has elapsed
+    Cue,
+    /// Stimulus on screen; responses are already accepted
     Stimulus,
+    /// Stimulus removed, response window still open
     Response,
     Feedback,
     Complete,
+    /// Inter-block rest; waits for Space before the trial starts
+    Break,
+    /// Seconds counted down on screen before a block's first trial
+    Countdown,
+}
+
+impl TrialState {
+    /// True from stimulus onset until the response window closes
+    pub fn accepts_response(&self) -> bool {
+        matches!(self, TrialState::Stimulus | TrialState::Response)
+    }
+}
+
+/// How the response window of a trial ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum TrialOutcome {
+    #[default]
+    Response,
+    /// The window elapsed without a response
+    Timeout,
+}
+
+impl TrialOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrialOutcome::Response => "response",
+            TrialOutcome::Timeout => "timeout",
+        }
+    }
+}
+
+/// Verdict shown on the feedback screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeedbackOutcome {
+    Correct,
+    Wrong,
+    /// No response where one was expected
+    TooSlow,
 }
 
-/// Recorded result per trial
-#[derive(Debug, Clone)]
+/// What the feedback screen shows for the trial just scored
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrialFeedback {
+    pub outcome: FeedbackOutcome,
+    pub reaction_time_ms: Option<u64>,
+}
+
+/// Timestamps (ns) of the first presented frame of each display event
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PresentationTimestamps {
+    pub fixation_onset_ns: Option<u64>,
+    pub cue_onset_ns: Option<u64>,
+    /// First frame after a frame-counted cue, e.g. a prime's mask
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cue_offset_ns: Option<u64>,
+    pub stimulus_onset_ns: Option<u64>,
+    pub stimulus_offset_ns: Option<u64>,
+    pub feedback_onset_ns: Option<u64>,
+}
+
+impl PresentationTimestamps {
+    /// How long a frame-counted cue was actually on screen
+    pub fn cue_duration_ns(&self) -> Option<u64> {
+        Some(self.cue_offset_ns?.saturating_sub(self.cue_onset_ns?))
+    }
+}
+
+/// Set size of results recorded before displays could hold several stimuli
+#[cfg(feature = "serde")]
+fn single() -> usize {
+    1
+}
+
+/// Recorded result per trial, with the stimulus that was presented
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrialResult<S> {
     pub trial_id: usize,
-    pub stimulus_type: String,
+    pub stimulus: S,
+    pub condition: String,
+    /// Stimuli on screen together, more than one for a multi-stimulus
+    /// display
+    #[cfg_attr(feature = "serde", serde(default = "single"))]
+    pub set_size: usize,
+    pub response_key: Option<ResponseKey>,
+    /// Mouse click position, for mouse responses
+    pub click_position: Option<(f32, f32)>,
+    /// Distance in pixels from the click to the stimulus centre
+    pub click_distance: Option<f32>,
     pub reaction_time_ns: Option<u64>,
+    /// Speech onset from a voice key, and its latency from stimulus onset
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub voice_onset_ns: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub voice_reaction_time_ns: Option<u64>,
+    /// Adaptive procedure intensity presented on this trial
+    pub intensity: Option<f64>,
+    /// Experiment block index, `None` for practice trials
+    pub block: Option<usize>,
     pub correct: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub outcome: TrialOutcome,
+    /// Confirmed position on a rating scale, 0 at the left end to 1 at the right
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rating: Option<f32>,
+    /// Nanoseconds since onset and marker position after each move
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rating_trajectory: Vec<(u64, f32)>,
+    /// Keys outside the response set pressed during the response window
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub invalid_keys: Vec<ResponseKey>,
     pub timestamp_ns: u64,
-    pub _marker: std::marker::PhantomData<S>,
+    /// Actual frame presentation times
+    pub presentation: PresentationTimestamps,
+    pub requested_fixation_ms: u64,
+    pub requested_stimulus_ms: u64,
+    pub requested_response_window_ms: u64,
+    pub requested_feedback_ms: u64,
+    /// Cue duration and cue-target SOA, for cued trials
+    pub requested_cue_ms: Option<u64>,
+    pub requested_soa_ms: Option<u64>,
+    /// Cue and mask refreshes, for frame-counted cues
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub requested_cue_frames: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub requested_mask_frames: Option<u64>,
+    /// Inter-trial interval drawn for the pause after this trial
+    pub requested_iti_ms: u64,
+    /// Refreshes missed while the trial was on screen
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dropped_frames: u64,
+    /// The participant left the experiment window while the trial ran, so
+    /// its timing and response may not be theirs to trust
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interrupted: bool,
 }
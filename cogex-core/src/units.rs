@@ -0,0 +1,141 @@
+use crate::stimulus::StimulusType;
+
+/// Size or eccentricity in degrees of visual angle
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VisualAngle(pub f32);
+
+impl VisualAngle {
+    pub fn degrees(self) -> f32 {
+        self.0
+    }
+
+    /// Extent in pixels when centred on the line of sight
+    pub fn to_pixels(self, display: &DisplayGeometry) -> f32 {
+        display.deg_to_px(self.0)
+    }
+
+    pub fn from_pixels(px: f32, display: &DisplayGeometry) -> Self {
+        Self(display.px_to_deg(px))
+    }
+}
+
+/// Physical monitor setup used to convert visual angles to pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayGeometry {
+    pub screen_width_cm: f32,
+    pub screen_width_px: u32,
+    pub viewing_distance_cm: f32,
+}
+
+impl Default for DisplayGeometry {
+    /// 53 cm wide 1920 px monitor viewed from 57 cm (~1 cm per degree)
+    fn default() -> Self {
+        Self {
+            screen_width_cm: 53.0,
+            screen_width_px: 1920,
+            viewing_distance_cm: 57.0,
+        }
+    }
+}
+
+impl DisplayGeometry {
+    pub fn new(screen_width_cm: f32, screen_width_px: u32, viewing_distance_cm: f32) -> Self {
+        Self {
+            screen_width_cm,
+            screen_width_px,
+            viewing_distance_cm,
+        }
+    }
+
+    pub fn pixels_per_cm(&self) -> f32 {
+        self.screen_width_px as f32 / self.screen_width_cm
+    }
+
+    /// Pixels per degree at the centre of the screen
+    pub fn pixels_per_degree(&self) -> f32 {
+        self.deg_to_px(1.0)
+    }
+
+    /// Pixel extent of an object subtending `deg`, centred on the line of sight
+    pub fn deg_to_px(&self, deg: f32) -> f32 {
+        2.0 * self.viewing_distance_cm * (deg.to_radians() * 0.5).tan() * self.pixels_per_cm()
+    }
+
+    pub fn px_to_deg(&self, px: f32) -> f32 {
+        (2.0 * (px / self.pixels_per_cm() / (2.0 * self.viewing_distance_cm)).atan()).to_degrees()
+    }
+
+    /// Converts an eccentricity in degrees from `centre` (x right, y down)
+    /// to screen pixels
+    pub fn position_to_px(&self, pos: (f32, f32), centre: (f32, f32)) -> (f32, f32) {
+        let offset =
+            |deg: f32| self.viewing_distance_cm * deg.to_radians().tan() * self.pixels_per_cm();
+        (centre.0 + offset(pos.0), centre.1 + offset(pos.1))
+    }
+
+    /// Inverse of `position_to_px`
+    pub fn px_to_position(&self, px: (f32, f32), centre: (f32, f32)) -> (f32, f32) {
+        let angle = |d: f32| {
+            (d / self.pixels_per_cm() / self.viewing_distance_cm)
+                .atan()
+                .to_degrees()
+        };
+        (angle(px.0 - centre.0), angle(px.1 - centre.1))
+    }
+
+    /// Converts a stimulus whose sizes are in degrees to pixel sizes.
+    /// Gabor spatial frequency goes from cycles per degree to cycles per pixel.
+    pub fn stimulus_to_px(&self, stimulus: &StimulusType) -> StimulusType {
+        let mut converted = stimulus.clone();
+        match &mut converted {
+            StimulusType::Circle { radius, .. } => *radius = self.deg_to_px(*radius * 2.0) * 0.5,
+            StimulusType::Rectangle { width, height, .. } => {
+                *width = self.deg_to_px(*width);
+                *height = self.deg_to_px(*height);
+            }
+            StimulusType::Arrow { size, .. } => *size = self.deg_to_px(*size * 2.0) * 0.5,
+            StimulusType::Text { size, .. } => *size = self.deg_to_px(*size),
+            StimulusType::Gabor {
+                spatial_freq,
+                sigma,
+                ..
+            } => {
+                *spatial_freq /= self.pixels_per_degree();
+                *sigma = self.deg_to_px(*sigma);
+            }
+            StimulusType::NoiseMask {
+                width,
+                height,
+                grain,
+            } => {
+                *width = self.deg_to_px(*width);
+                *height = self.deg_to_px(*height);
+                *grain = self.deg_to_px(*grain);
+            }
+            StimulusType::DotMotion {
+                speed,
+                density,
+                aperture_radius,
+                dot_size,
+                ..
+            } => {
+                let ppd = self.pixels_per_degree();
+                *speed *= ppd;
+                *density /= ppd * ppd;
+                *aperture_radius = self.deg_to_px(*aperture_radius * 2.0) * 0.5;
+                *dot_size = self.deg_to_px(*dot_size);
+            }
+            StimulusType::RotatingBar { width, height, .. }
+            | StimulusType::ProgressBar { width, height, .. } => {
+                *width = self.deg_to_px(*width);
+                *height = self.deg_to_px(*height);
+            }
+            StimulusType::Countdown { size, .. } => *size = self.deg_to_px(*size),
+            StimulusType::RatingScale { width, .. } => *width = self.deg_to_px(*width),
+            StimulusType::Tone { .. } | StimulusType::Sound { .. } => {}
+        }
+        converted
+    }
+}
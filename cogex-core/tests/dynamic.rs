@@ -0,0 +1,180 @@
+use cogex_core::{
+    Countdown, DotField, DynamicStimulus, Primitive, ProgressBar, RotatingBar, StimulusType,
+};
+use std::time::Duration;
+
+fn dots(coherence: f32) -> StimulusType {
+    StimulusType::DotMotion {
+        coherence,
+        direction: 0.0,
+        speed: 100.0,
+        density: 0.01,
+        aperture_radius: 50.0,
+        dot_size: 2.0,
+        color: [255, 255, 255, 255],
+    }
+}
+
+/// Dots that moved exactly one rightward step of `dx`
+fn moved_right(before: &[(f32, f32)], after: &[(f32, f32)], dx: f32) -> usize {
+    before
+        .iter()
+        .zip(after)
+        .filter(|(b, a)| (a.0 - b.0 - dx).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3)
+        .count()
+}
+
+#[test]
+fn dot_count_follows_density_over_the_aperture() {
+    // 0.01 dots per px² over π × 50² px²
+    assert_eq!(dots(0.5).dot_count(), Some(79));
+    let field = DotField::new(&dots(0.5), 1).unwrap();
+    assert_eq!(field.dots().len(), 79);
+    assert!(field.dots().iter().all(|d| d.0.hypot(d.1) <= 50.0));
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 255, 255, 255],
+    };
+    assert!(DotField::new(&circle, 1).is_none());
+}
+
+#[test]
+fn coherent_dots_move_together_and_stay_in_the_aperture() {
+    let mut field = DotField::new(&dots(1.0), 3).unwrap();
+    for _ in 0..30 {
+        let before = field.dots().to_vec();
+        field.advance(Duration::from_millis(10));
+        let after = field.dots();
+        assert!(after.iter().all(|d| d.0.hypot(d.1) <= 50.0));
+        // Only dots that would leave the aperture are replotted
+        let wrapped = before
+            .iter()
+            .filter(|d| (d.0 + 1.0).hypot(d.1) > 50.0)
+            .count();
+        assert_eq!(moved_right(&before, after, 1.0), before.len() - wrapped);
+    }
+}
+
+#[test]
+fn incoherent_dots_are_replotted_every_frame() {
+    let mut field = DotField::new(&dots(0.0), 3).unwrap();
+    let before = field.dots().to_vec();
+    field.advance(Duration::from_millis(10));
+    assert_eq!(moved_right(&before, field.dots(), 1.0), 0);
+
+    let mut half = DotField::new(&dots(0.5), 3).unwrap();
+    let mut signal = 0;
+    for _ in 0..20 {
+        let before = half.dots().to_vec();
+        half.advance(Duration::from_millis(10));
+        signal += moved_right(&before, half.dots(), 1.0);
+    }
+    // About half of 20 × 79 steps, less those leaving the aperture
+    assert!((600..=800).contains(&signal), "{}", signal);
+}
+
+#[test]
+fn fields_are_reproducible_from_their_seed() {
+    let run = |seed| {
+        let mut field = DotField::new(&dots(0.5), seed).unwrap();
+        for _ in 0..5 {
+            field.advance(Duration::from_millis(16));
+        }
+        field.dots().to_vec()
+    };
+    assert_eq!(run(9), run(9));
+    assert_ne!(run(9), run(10));
+    // A zero step leaves the field alone
+    let mut field = DotField::new(&dots(0.5), 9).unwrap();
+    let before = field.dots().to_vec();
+    field.advance(Duration::ZERO);
+    assert_eq!(field.dots(), before);
+}
+
+#[test]
+fn only_animated_stimuli_are_dynamic() {
+    let bar = StimulusType::RotatingBar {
+        width: 100.0,
+        height: 10.0,
+        speed: 90.0,
+        color: [255, 255, 255, 255],
+    };
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 255, 255, 255],
+    };
+    assert!(bar.is_dynamic() && dots(1.0).is_dynamic());
+    assert!(!circle.is_dynamic());
+    assert!(bar.animate(0).is_some());
+    assert!(circle.animate(0).is_none());
+}
+
+#[test]
+fn rotating_bars_turn_at_their_speed() {
+    let mut bar = RotatingBar {
+        width: 100.0,
+        height: 10.0,
+        speed: 90.0,
+        color: [255, 255, 255, 255],
+        angle: 0.0,
+    };
+    bar.advance(Duration::from_secs(1));
+    assert_eq!(bar.angle, 90.0);
+    bar.advance(Duration::from_secs(3));
+    assert_eq!(bar.angle, 0.0);
+
+    // A quarter turn stands the bar upright
+    bar.advance(Duration::from_secs(1));
+    let [Primitive::Polygon { points, .. }] = &bar.primitives()[..] else {
+        panic!("expected one polygon");
+    };
+    for &(x, y) in points {
+        assert!((x.abs() - 5.0).abs() < 1e-3 && (y.abs() - 50.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn countdowns_show_the_whole_seconds_left() {
+    let mut countdown = Countdown {
+        remaining: Duration::from_secs(3),
+        size: 48.0,
+        color: [255, 255, 255, 255],
+    };
+    let mut shown = Vec::new();
+    for _ in 0..8 {
+        let [Primitive::Text { content, .. }] = &countdown.primitives()[..] else {
+            panic!("expected text");
+        };
+        shown.push(content.clone());
+        countdown.advance(Duration::from_millis(500));
+    }
+    assert_eq!(shown, ["3", "3", "2", "2", "1", "1", "1", "1"]);
+}
+
+#[test]
+fn progress_bars_fill_from_the_left_over_a_faint_track() {
+    let primitives = |fraction| {
+        ProgressBar {
+            width: 200.0,
+            height: 10.0,
+            fraction,
+            color: [0, 0, 255, 200],
+        }
+        .primitives()
+    };
+    let [track, fill] = &primitives(0.25)[..] else {
+        panic!("expected track and fill");
+    };
+    let Primitive::Polygon { points, color } = track else {
+        panic!("expected a polygon");
+    };
+    assert_eq!(
+        (points[0], points[1], *color),
+        ((-100.0, -5.0), (100.0, -5.0), [0, 0, 255, 50])
+    );
+    let Primitive::Polygon { points, .. } = fill else {
+        panic!("expected a polygon");
+    };
+    assert_eq!((points[0], points[1]), ((-100.0, -5.0), (-50.0, -5.0)));
+    assert_eq!(primitives(0.0).len(), 1);
+}
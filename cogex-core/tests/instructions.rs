@@ -0,0 +1,88 @@
+use cogex_core::{InstructionBlock, InstructionPage, Instructions};
+use std::path::Path;
+
+const TEXT: &str = "\
+# Welcome
+
+Press **Space** to turn the page
+when you are ready.
+
+---
+## The task
+- Left for `F`
+- Right for __J__
+
+![Example trial](img/trial.png)
+-----
+
+Last page
+";
+
+fn paragraph(text: &str) -> InstructionBlock {
+    InstructionBlock::Paragraph(text.to_string())
+}
+
+#[test]
+fn markdown_splits_into_pages_of_headings_paragraphs_and_images() {
+    let instructions = Instructions::parse(TEXT, Path::new("tasks/flanker"));
+    assert_eq!(
+        instructions.pages(),
+        [
+            InstructionPage {
+                blocks: vec![
+                    InstructionBlock::Heading("Welcome".into()),
+                    paragraph("Press Space to turn the page when you are ready."),
+                ],
+            },
+            InstructionPage {
+                blocks: vec![
+                    InstructionBlock::Heading("The task".into()),
+                    paragraph("• Left for F"),
+                    paragraph("• Right for J"),
+                    InstructionBlock::Image(Path::new("tasks/flanker/img/trial.png").into()),
+                ],
+            },
+            InstructionPage {
+                blocks: vec![paragraph("Last page")],
+            },
+        ]
+    );
+}
+
+#[test]
+fn empty_pages_are_dropped() {
+    let instructions = Instructions::parse("---\n\n---\nOnly page\n---\n", Path::new("."));
+    assert_eq!(instructions.page_count(), 1);
+    assert_eq!(Instructions::parse("", Path::new(".")).page_count(), 0);
+}
+
+#[test]
+fn pages_turn_forward_and_back_within_bounds() {
+    let mut instructions = Instructions::parse(TEXT, Path::new("."));
+    assert!(!instructions.previous_page());
+    assert!(instructions.next_page());
+    assert!(instructions.next_page());
+    assert!(instructions.is_last_page());
+    assert!(!instructions.next_page());
+    assert_eq!(instructions.page_index(), 2);
+    assert!(instructions.previous_page());
+    assert_eq!(instructions.page(), Some(&instructions.pages()[1]));
+    instructions.reset();
+    assert_eq!(instructions.page_index(), 0);
+}
+
+#[test]
+fn instruction_files_load_with_images_beside_them() {
+    let dir = std::env::temp_dir().join(format!("cogex_instructions_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("instructions.md");
+    std::fs::write(&path, "![Keys](keys.png)\n").unwrap();
+    let instructions = Instructions::load(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        instructions.pages()[0].blocks,
+        [InstructionBlock::Image(dir.join("keys.png"))]
+    );
+    assert!(Instructions::load(&dir.join("missing.md")).is_err());
+}
@@ -0,0 +1,83 @@
+use cogex_core::{ArrowDirection, MouseButton, ResponseKey, ResponseMapping, StimulusType};
+
+fn arrow(direction: ArrowDirection) -> StimulusType {
+    StimulusType::Arrow {
+        direction,
+        size: 40.0,
+        color: [255, 255, 255, 255],
+    }
+}
+
+fn circle(red: u8) -> StimulusType {
+    StimulusType::Circle {
+        radius: 50.0,
+        color: [red, 0, 0, 255],
+    }
+}
+
+#[test]
+fn standard_mapping_expects_the_arrows_key_and_space_otherwise() {
+    let mapping = ResponseMapping::standard();
+    assert_eq!(
+        mapping.expected_key(&arrow(ArrowDirection::Left)),
+        Some(ResponseKey::ArrowLeft)
+    );
+    assert_eq!(mapping.expected_key(&circle(255)), Some(ResponseKey::Space));
+
+    assert!(mapping.is_correct(&arrow(ArrowDirection::Up), Some(ResponseKey::ArrowUp)));
+    assert!(!mapping.is_correct(&arrow(ArrowDirection::Up), Some(ResponseKey::ArrowDown)));
+    assert!(!mapping.is_correct(&arrow(ArrowDirection::Up), None));
+    assert!(mapping.accepts(ResponseKey::ArrowRight) && mapping.accepts(ResponseKey::Space));
+    assert!(!mapping.accepts(ResponseKey::Char('x')));
+}
+
+#[test]
+fn the_first_matching_rule_wins_and_unmatched_stimuli_are_withheld() {
+    let red = |s: &StimulusType| matches!(s, StimulusType::Circle { color, .. } if color[0] > 128);
+    let mapping = ResponseMapping::new()
+        .with_rule(ResponseKey::Char('f'), red)
+        .with_rule(ResponseKey::Char('j'), |s| {
+            matches!(s, StimulusType::Circle { .. })
+        });
+    assert_eq!(
+        mapping.expected_key(&circle(255)),
+        Some(ResponseKey::Char('f'))
+    );
+    assert_eq!(
+        mapping.expected_key(&circle(0)),
+        Some(ResponseKey::Char('j'))
+    );
+    assert_eq!(
+        mapping.keys(),
+        [ResponseKey::Char('f'), ResponseKey::Char('j')]
+    );
+
+    // Nothing expected: withholding is correct and any press is not
+    let arrow = arrow(ArrowDirection::Left);
+    assert_eq!(mapping.expected_key(&arrow), None);
+    assert!(mapping.is_correct(&arrow, None));
+    assert!(!mapping.is_correct(&arrow, Some(ResponseKey::Char('f'))));
+}
+
+#[test]
+fn mouse_buttons_count_as_responses_once_mapped() {
+    let left = ResponseKey::Mouse(MouseButton::Left);
+    assert!(!ResponseMapping::standard().accepts_mouse());
+    let mapping = ResponseMapping::<StimulusType>::new().with_fallback(left);
+    assert!(mapping.accepts_mouse());
+    assert!(mapping.is_correct(&circle(0), Some(left)));
+}
+
+#[test]
+fn key_names_read_back() {
+    for key in [
+        ResponseKey::Space,
+        ResponseKey::ArrowDown,
+        ResponseKey::Char('5'),
+        ResponseKey::Mouse(MouseButton::Middle),
+    ] {
+        assert_eq!(ResponseKey::from_name(&key.name()), Some(key));
+    }
+    assert_eq!(ResponseKey::from_name("ab"), None);
+    assert_eq!(ResponseKey::from_name(""), None);
+}
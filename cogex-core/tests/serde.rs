@@ -0,0 +1,45 @@
+#![cfg(feature = "serde")]
+
+use cogex_core::{StandardPhase, StimulusType, TrialState};
+use serde::{de::DeserializeOwned, Serialize};
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn stimuli_round_trip_through_json() {
+    let stimuli = [
+        StimulusType::Circle {
+            radius: 50.0,
+            color: [255, 0, 0, 255],
+        },
+        StimulusType::Text {
+            content: "GO".into(),
+            size: 48.0,
+            color: [255, 255, 255, 255],
+        },
+        StimulusType::Sound {
+            path: "assets/beep.wav".into(),
+            amplitude: 0.5,
+        },
+        StimulusType::RatingScale {
+            width: 600.0,
+            low: "Not at all".into(),
+            high: "Extremely".into(),
+            color: [255, 255, 255, 255],
+        },
+    ];
+    for stimulus in &stimuli {
+        assert_eq!(&round_trip(stimulus), stimulus);
+    }
+}
+
+#[test]
+fn phase_and_trial_state_round_trip_through_json() {
+    assert_eq!(
+        round_trip(&StandardPhase::Practice),
+        StandardPhase::Practice
+    );
+    assert_eq!(round_trip(&TrialState::Stimulus), TrialState::Stimulus);
+}
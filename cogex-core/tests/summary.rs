@@ -0,0 +1,86 @@
+use cogex_core::{
+    DebriefSummary, PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult,
+};
+
+fn result(correct: Option<bool>, rt_ms: Option<u64>) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: StimulusType::Circle {
+            radius: 50.0,
+            color: [255, 255, 255, 255],
+        },
+        condition: "circle".to_string(),
+        set_size: 1,
+        response_key: rt_ms.map(|_| ResponseKey::Space),
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: rt_ms.map(|rt| rt * 1_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct,
+        outcome: if rt_ms.is_some() {
+            TrialOutcome::Response
+        } else {
+            TrialOutcome::Timeout
+        },
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 200,
+        requested_response_window_ms: 1000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 1,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn reaction_times_are_summarised_over_correct_responses_only() {
+    let summary = DebriefSummary::from_results(&[
+        result(Some(true), Some(300)),
+        result(Some(true), Some(500)),
+        result(Some(true), Some(400)),
+        result(Some(false), Some(100)),
+        result(None, None),
+    ]);
+    assert_eq!(summary.trials, 5);
+    assert_eq!(summary.correct, 3);
+    assert_eq!(summary.accuracy(), Some(0.6));
+    assert_eq!(summary.mean_rt_ms, Some(400.0));
+    assert_eq!(summary.median_rt_ms, Some(400.0));
+    assert_eq!(summary.dropped_frames, 5);
+}
+
+#[test]
+fn an_even_count_takes_the_median_between_the_middle_two() {
+    let summary = DebriefSummary::from_results(&[
+        result(Some(true), Some(300)),
+        result(Some(true), Some(600)),
+        result(Some(true), Some(400)),
+        result(Some(true), Some(900)),
+    ]);
+    assert_eq!(summary.median_rt_ms, Some(500.0));
+    assert_eq!(summary.mean_rt_ms, Some(550.0));
+}
+
+#[test]
+fn no_trials_leave_accuracy_and_reaction_times_empty() {
+    let summary = DebriefSummary::from_results::<StimulusType>(&[]);
+    assert_eq!(summary.accuracy(), None);
+    assert_eq!(summary.mean_rt_ms, None);
+    assert_eq!(summary.median_rt_ms, None);
+
+    let misses = DebriefSummary::from_results(&[result(Some(false), None)]);
+    assert_eq!(misses.accuracy(), Some(0.0));
+    assert_eq!(misses.mean_rt_ms, None);
+}
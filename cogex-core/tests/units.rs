@@ -0,0 +1,68 @@
+use cogex_core::{DisplayGeometry, StimulusType, VisualAngle};
+
+fn approx(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+/// 32 cm, 320 px wide monitor, 10 px per cm, viewed from 57 cm
+fn display() -> DisplayGeometry {
+    DisplayGeometry::new(32.0, 320, 57.0)
+}
+
+#[test]
+fn a_degree_at_57_cm_is_about_a_centimetre() {
+    let display = display();
+    assert!((display.pixels_per_degree() - 9.95).abs() < 0.01);
+    assert!(approx(display.deg_to_px(0.0), 0.0));
+    // Extents grow faster than linearly off the line of sight
+    assert!(display.deg_to_px(20.0) > 20.0 * display.pixels_per_degree());
+}
+
+#[test]
+fn conversions_round_trip() {
+    let display = display();
+    for deg in [0.5, 1.0, 7.5, 30.0] {
+        let px = VisualAngle(deg).to_pixels(&display);
+        assert!(approx(
+            VisualAngle::from_pixels(px, &display).degrees(),
+            deg
+        ));
+    }
+    let centre = (160.0, 120.0);
+    assert_eq!(display.position_to_px((0.0, 0.0), centre), centre);
+    let px = display.position_to_px((5.0, -2.0), centre);
+    let (x, y) = display.px_to_position(px, centre);
+    assert!(approx(x, 5.0) && approx(y, -2.0), "{:?}", (x, y));
+}
+
+#[test]
+fn stimulus_sizes_convert_from_degrees() {
+    let display = display();
+    let ppd = display.pixels_per_degree();
+    let circle = StimulusType::Circle {
+        radius: 1.0,
+        color: [255, 255, 255, 255],
+    };
+    let StimulusType::Circle { radius, .. } = display.stimulus_to_px(&circle) else {
+        unreachable!();
+    };
+    assert!(approx(radius, display.deg_to_px(2.0) / 2.0));
+
+    let gabor = StimulusType::Gabor {
+        orientation: 0.0,
+        spatial_freq: 4.0,
+        phase: 0.0,
+        sigma: 0.5,
+        contrast: 1.0,
+    };
+    let StimulusType::Gabor {
+        spatial_freq,
+        sigma,
+        ..
+    } = display.stimulus_to_px(&gabor)
+    else {
+        unreachable!();
+    };
+    assert!(approx(spatial_freq, 4.0 / ppd));
+    assert!(approx(sigma, display.deg_to_px(0.5)));
+}
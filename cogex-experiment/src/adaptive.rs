@@ -0,0 +1,307 @@
+use anyhow::{bail, Result};
+use cogex_core::StimulusType;
+
+use crate::trial::Trial;
+
+/// Trial-by-trial adjustment of a stimulus intensity from response correctness
+pub trait AdaptiveProcedure: Send {
+    /// Intensity to present on the next trial
+    fn intensity(&self) -> f64;
+    /// Feeds back the outcome of a trial presented at `intensity()`
+    fn update(&mut self, correct: bool);
+    /// Current threshold estimate, if enough data has been collected
+    fn threshold(&self) -> Option<f64>;
+    fn trial_count(&self) -> usize;
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+/// Stimulus parameter driven by an adaptive procedure
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdaptiveParameter {
+    /// Gabor contrast in 0..=1
+    Contrast,
+    /// Gabor contrast as log10 units, as used by QUEST
+    LogContrast,
+    /// Stimulus presentation duration in milliseconds
+    StimulusDurationMs,
+    /// Tone/sound amplitude in 0..=1
+    Amplitude,
+}
+
+impl AdaptiveParameter {
+    /// Writes `value` into the matching parameter of `trial`
+    pub fn apply<T>(&self, value: f64, trial: &mut Trial<StimulusType, T>) {
+        match (self, &mut trial.stimulus) {
+            (AdaptiveParameter::Contrast, StimulusType::Gabor { contrast, .. }) => {
+                *contrast = value.clamp(0.0, 1.0) as f32;
+            }
+            (AdaptiveParameter::LogContrast, StimulusType::Gabor { contrast, .. }) => {
+                *contrast = 10f64.powf(value).clamp(0.0, 1.0) as f32;
+            }
+            (AdaptiveParameter::StimulusDurationMs, _) => {
+                trial.durations.stimulus_ms = value.max(0.0).round() as u64;
+            }
+            (
+                AdaptiveParameter::Amplitude,
+                StimulusType::Tone { amplitude, .. } | StimulusType::Sound { amplitude, .. },
+            ) => {
+                *amplitude = value.clamp(0.0, 1.0) as f32;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Fixed-step transformed up/down staircase (Levitt, 1971).
+///
+/// The intensity drops by `step` after `down` consecutive correct responses
+/// and rises after `up` consecutive errors, so lower values are harder.
+#[derive(Debug, Clone)]
+pub struct Staircase {
+    value: f64,
+    step: f64,
+    down: usize,
+    up: usize,
+    bounds: (f64, f64),
+    correct_run: usize,
+    incorrect_run: usize,
+    last_direction: i8,
+    reversals: Vec<f64>,
+    max_reversals: Option<usize>,
+    threshold_reversals: usize,
+    trials: usize,
+}
+
+impl Staircase {
+    pub fn new(start: f64, step: f64, down: usize, up: usize) -> Self {
+        Self {
+            value: start,
+            step,
+            down: down.max(1),
+            up: up.max(1),
+            bounds: (f64::NEG_INFINITY, f64::INFINITY),
+            correct_run: 0,
+            incorrect_run: 0,
+            last_direction: 0,
+            reversals: Vec::new(),
+            max_reversals: None,
+            threshold_reversals: 6,
+            trials: 0,
+        }
+    }
+
+    /// 1-up-2-down staircase converging on 70.7% correct
+    pub fn one_up_two_down(start: f64, step: f64) -> Self {
+        Self::new(start, step, 2, 1)
+    }
+
+    pub fn with_bounds(mut self, min: f64, max: f64) -> Self {
+        self.bounds = (min, max);
+        self.value = self.value.clamp(min, max);
+        self
+    }
+
+    /// Stops the staircase after `count` reversals
+    pub fn with_max_reversals(mut self, count: usize) -> Self {
+        self.max_reversals = Some(count);
+        self
+    }
+
+    /// Number of final reversals averaged for the threshold estimate
+    pub fn with_threshold_reversals(mut self, count: usize) -> Self {
+        self.threshold_reversals = count.max(1);
+        self
+    }
+
+    pub fn reversals(&self) -> &[f64] {
+        &self.reversals
+    }
+
+    fn step_by(&mut self, direction: i8) {
+        if self.last_direction != 0 && direction != self.last_direction {
+            self.reversals.push(self.value);
+        }
+        self.last_direction = direction;
+        self.value =
+            (self.value + direction as f64 * self.step).clamp(self.bounds.0, self.bounds.1);
+    }
+}
+
+impl AdaptiveProcedure for Staircase {
+    fn intensity(&self) -> f64 {
+        self.value
+    }
+
+    fn update(&mut self, correct: bool) {
+        self.trials += 1;
+        if correct {
+            self.incorrect_run = 0;
+            self.correct_run += 1;
+            if self.correct_run >= self.down {
+                self.correct_run = 0;
+                self.step_by(-1);
+            }
+        } else {
+            self.correct_run = 0;
+            self.incorrect_run += 1;
+            if self.incorrect_run >= self.up {
+                self.incorrect_run = 0;
+                self.step_by(1);
+            }
+        }
+    }
+
+    fn threshold(&self) -> Option<f64> {
+        if self.reversals.is_empty() {
+            return None;
+        }
+        let n = self.threshold_reversals.min(self.reversals.len());
+        let tail = &self.reversals[self.reversals.len() - n..];
+        Some(tail.iter().sum::<f64>() / n as f64)
+    }
+
+    fn trial_count(&self) -> usize {
+        self.trials
+    }
+
+    fn is_finished(&self) -> bool {
+        self.max_reversals
+            .is_some_and(|max| self.reversals.len() >= max)
+    }
+}
+
+/// Bayesian QUEST procedure (Watson & Pelli, 1983).
+///
+/// Maintains a gridded posterior over the threshold of a Weibull psychometric
+/// function and presents at the posterior mean. Intensities are in the same
+/// units as the prior, conventionally log10.
+#[derive(Debug, Clone)]
+pub struct Quest {
+    grid: Vec<f64>,
+    log_posterior: Vec<f64>,
+    beta: f64,
+    gamma: f64,
+    delta: f64,
+    trials: usize,
+}
+
+impl Quest {
+    /// Gaussian prior centred on `guess`; `gamma` is the guess rate
+    /// (e.g. 0.5 for 2AFC) and `delta` the lapse rate. Fails unless
+    /// `guess_sd` is positive, as the prior would have no width.
+    pub fn new(guess: f64, guess_sd: f64, beta: f64, gamma: f64, delta: f64) -> Result<Self> {
+        if guess_sd.is_nan() || guess_sd <= 0.0 {
+            bail!("QUEST prior SD must be positive, got {}", guess_sd);
+        }
+        let points = 401;
+        let half_range = guess_sd * 5.0;
+        let grain = 2.0 * half_range / (points - 1) as f64;
+        let grid: Vec<f64> = (0..points)
+            .map(|i| guess - half_range + i as f64 * grain)
+            .collect();
+        let log_posterior = grid
+            .iter()
+            .map(|t| -0.5 * ((t - guess) / guess_sd).powi(2))
+            .collect();
+        Ok(Self {
+            grid,
+            log_posterior,
+            beta,
+            gamma,
+            delta,
+            trials: 0,
+        })
+    }
+
+    /// 2AFC defaults: beta 3.5, gamma 0.5, delta 0.01
+    pub fn two_afc(guess: f64, guess_sd: f64) -> Result<Self> {
+        Self::new(guess, guess_sd, 3.5, 0.5, 0.01)
+    }
+
+    /// Probability of a correct response at `intensity` given `threshold`
+    pub fn p_correct(&self, intensity: f64, threshold: f64) -> f64 {
+        let weibull = 1.0 - (-(10f64.powf(self.beta * (intensity - threshold)))).exp();
+        self.delta * self.gamma + (1.0 - self.delta) * (self.gamma + (1.0 - self.gamma) * weibull)
+    }
+
+    /// Posterior standard deviation of the threshold
+    pub fn sd(&self) -> f64 {
+        let (weights, total) = self.weights();
+        let mean = self.mean(&weights, total);
+        let var = self
+            .grid
+            .iter()
+            .zip(&weights)
+            .map(|(t, w)| w * (t - mean).powi(2))
+            .sum::<f64>()
+            / total;
+        var.sqrt()
+    }
+
+    fn weights(&self) -> (Vec<f64>, f64) {
+        let max = self
+            .log_posterior
+            .iter()
+            .cloned()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = self
+            .log_posterior
+            .iter()
+            .map(|lp| (lp - max).exp())
+            .collect();
+        let total = weights.iter().sum();
+        (weights, total)
+    }
+
+    fn mean(&self, weights: &[f64], total: f64) -> f64 {
+        self.grid
+            .iter()
+            .zip(weights)
+            .map(|(t, w)| t * w)
+            .sum::<f64>()
+            / total
+    }
+}
+
+impl AdaptiveProcedure for Quest {
+    fn intensity(&self) -> f64 {
+        let (weights, total) = self.weights();
+        self.mean(&weights, total)
+    }
+
+    fn update(&mut self, correct: bool) {
+        let x = self.intensity();
+        for i in 0..self.grid.len() {
+            let p = self.p_correct(x, self.grid[i]).clamp(1e-12, 1.0 - 1e-12);
+            self.log_posterior[i] += if correct { p.ln() } else { (1.0 - p).ln() };
+        }
+        self.trials += 1;
+    }
+
+    fn threshold(&self) -> Option<f64> {
+        Some(self.intensity())
+    }
+
+    fn trial_count(&self) -> usize {
+        self.trials
+    }
+}
+
+/// Adaptive procedure bound to the stimulus parameter it controls
+pub struct AdaptiveControl {
+    pub procedure: Box<dyn AdaptiveProcedure>,
+    pub parameter: AdaptiveParameter,
+}
+
+impl std::fmt::Debug for AdaptiveControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdaptiveControl")
+            .field("parameter", &self.parameter)
+            .field("intensity", &self.procedure.intensity())
+            .field("trials", &self.procedure.trial_count())
+            .finish()
+    }
+}
@@ -0,0 +1,190 @@
+use crate::output::OutputManager;
+use crate::session::SessionInfo;
+use anyhow::Result;
+use cogex_core::TrialResult;
+use cogex_sync::Marker;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const COLUMNS: [&str; 7] = [
+    "onset",
+    "duration",
+    "trial_type",
+    "response_time",
+    "trial_id",
+    "response",
+    "correct",
+];
+
+/// BIDS `*_events.tsv` contents: one row per presented fixation, cue,
+/// stimulus and feedback screen, timed from the presentation timestamps,
+/// and a zero-duration row per logged marker, typed by its label.
+///
+/// Onsets are seconds since `zero_ns`, which should be the start of the
+/// recording (e.g. the first scanner trigger); `None` uses the first
+/// presented event. Rows are sorted by onset.
+pub fn bids_events_tsv<S>(
+    results: &[TrialResult<S>],
+    markers: &[Marker],
+    zero_ns: Option<u64>,
+) -> String {
+    let zero_ns = zero_ns.unwrap_or_else(|| first_onset_ns(results).unwrap_or_default());
+    let seconds = |ns: u64| format!("{:.6}", ns.saturating_sub(zero_ns) as f64 / 1e9);
+    let span = |from: u64, to: Option<u64>| {
+        to.map_or("n/a".to_string(), |to| {
+            format!("{:.6}", to.saturating_sub(from) as f64 / 1e9)
+        })
+    };
+
+    let mut rows: Vec<(u64, String)> = Vec::new();
+    for r in results {
+        let p = &r.presentation;
+        let trial = r.trial_id.to_string();
+        let mut push = |onset: u64, duration: String, trial_type: &str, stimulus: bool| {
+            let (response_time, response, correct) = if stimulus {
+                (
+                    r.reaction_time_ns
+                        .map_or("n/a".to_string(), |rt| format!("{:.6}", rt as f64 / 1e9)),
+                    r.response_key
+                        .map_or("n/a".to_string(), |k| format!("{:?}", k)),
+                    r.correct
+                        .map_or("n/a".to_string(), |c| (c as u8).to_string()),
+                )
+            } else {
+                ("n/a".to_string(), "n/a".to_string(), "n/a".to_string())
+            };
+            let row = [
+                seconds(onset),
+                duration,
+                trial_type.to_string(),
+                response_time,
+                trial.clone(),
+                response,
+                correct,
+            ]
+            .join("\t");
+            rows.push((onset, row));
+        };
+
+        // Each screen lasts until the next one replaces it
+        let next_after_fixation = p.cue_onset_ns.or(p.stimulus_onset_ns);
+        if let Some(onset) = p.fixation_onset_ns {
+            push(onset, span(onset, next_after_fixation), "fixation", false);
+        }
+        if let Some(onset) = p.cue_onset_ns {
+            let offset = p.cue_offset_ns.or(p.stimulus_onset_ns);
+            push(onset, span(onset, offset), "cue", false);
+        }
+        if let Some(onset) = p.cue_offset_ns.filter(|&t| Some(t) != p.stimulus_onset_ns) {
+            push(onset, span(onset, p.stimulus_onset_ns), "mask", false);
+        }
+        if let Some(onset) = p.stimulus_onset_ns {
+            push(onset, span(onset, p.stimulus_offset_ns), &r.condition, true);
+        }
+        if let Some(onset) = p.feedback_onset_ns {
+            let duration = format!("{:.6}", r.requested_feedback_ms as f64 / 1e3);
+            push(onset, duration, "feedback", false);
+        }
+    }
+    for marker in markers {
+        let onset = marker.timestamp_ns;
+        let row = format!(
+            "{}\t0.000000\t{}\tn/a\tn/a\tn/a\tn/a",
+            seconds(onset),
+            marker.event.label()
+        );
+        rows.push((onset, row));
+    }
+    rows.sort_by_key(|(onset, _)| *onset);
+
+    let mut tsv = COLUMNS.join("\t");
+    tsv.push('\n');
+    for (_, row) in rows {
+        let _ = writeln!(tsv, "{}", row);
+    }
+    tsv
+}
+
+/// Column descriptions for the events sidecar
+pub fn bids_events_sidecar() -> serde_json::Value {
+    json!({
+        "onset": {
+            "Description": "First presented frame of the event, from the start of the recording",
+            "Units": "s",
+        },
+        "duration": {
+            "Description": "Until the next screen was presented; feedback uses its requested duration, markers 0",
+            "Units": "s",
+        },
+        "trial_type": {
+            "Description": "Trial condition for stimulus events, fixation, cue or feedback, or a logged marker's label such as external/<label>",
+        },
+        "response_time": {
+            "Description": "Response latency from stimulus onset",
+            "Units": "s",
+        },
+        "trial_id": {
+            "Description": "Trial number across the whole session",
+        },
+        "response": {
+            "Description": "Response key or mouse button",
+        },
+        "correct": {
+            "Description": "Whether the response was scored correct",
+            "Levels": { "0": "incorrect", "1": "correct" },
+        },
+        "StimulusPresentation": {
+            "SoftwareName": "cogex",
+            "SoftwareVersion": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+/// Writes `sub-<id>[_ses-<n>]_task-<task>_events.tsv` and its `.json`
+/// sidecar into the experiment directory; returns the TSV path
+pub fn write_bids_events<S>(
+    output: &OutputManager,
+    session: Option<&SessionInfo>,
+    task: &str,
+    results: &[TrialResult<S>],
+    markers: &[Marker],
+    zero_ns: Option<u64>,
+) -> Result<PathBuf> {
+    let stem = session.map_or_else(
+        || format!("sub-anonymous_task-{}", bids_label(task)),
+        |s| s.bids_stem(task),
+    );
+    let tsv_path = output.write_named(
+        &format!("{}_events.tsv", stem),
+        bids_events_tsv(results, markers, zero_ns),
+    )?;
+    output.write_named(
+        &format!("{}_events.json", stem),
+        serde_json::to_string_pretty(&bids_events_sidecar())?,
+    )?;
+    Ok(tsv_path)
+}
+
+/// BIDS labels are alphanumeric only
+pub fn bids_label(label: &str) -> String {
+    label.chars().filter(char::is_ascii_alphanumeric).collect()
+}
+
+fn first_onset_ns<S>(results: &[TrialResult<S>]) -> Option<u64> {
+    results
+        .iter()
+        .filter_map(|r| {
+            let p = &r.presentation;
+            [
+                p.fixation_onset_ns,
+                p.cue_onset_ns,
+                p.stimulus_onset_ns,
+                p.feedback_onset_ns,
+            ]
+            .into_iter()
+            .flatten()
+            .min()
+        })
+        .min()
+}
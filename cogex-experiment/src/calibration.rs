@@ -0,0 +1,76 @@
+use crate::config::{CalibrationConfig, CalibrationDuration};
+use std::collections::VecDeque;
+
+/// Progress of the calibration phase: how long it has measured, and how the
+/// timer's jitter estimate has moved
+#[derive(Debug, Clone)]
+pub(crate) struct CalibrationProgress {
+    start_ns: u64,
+    /// Timer frame count when the phase started
+    start_frames: u64,
+    /// Frames measured and the jitter estimate at each check, oldest first,
+    /// reaching back just past `stable_frames`
+    jitter: VecDeque<(u64, f64)>,
+}
+
+impl CalibrationProgress {
+    pub fn new(now_ns: u64, frames: u64) -> Self {
+        Self {
+            start_ns: now_ns,
+            start_frames: frames,
+            jitter: VecDeque::new(),
+        }
+    }
+
+    /// Notes the timer's latest frame count and jitter estimate; true once
+    /// the phase should end
+    pub fn check(
+        &mut self,
+        config: &CalibrationConfig,
+        now_ns: u64,
+        frames: u64,
+        jitter_ns: f64,
+    ) -> bool {
+        let measured = self.measured(frames);
+        if self.jitter.back().is_none_or(|&(at, _)| at < measured) {
+            self.jitter.push_back((measured, jitter_ns));
+        }
+        while self.jitter.len() > 1 && self.jitter[1].0 + config.stable_frames <= measured {
+            self.jitter.pop_front();
+        }
+
+        let elapsed_s = self.elapsed_ns(now_ns) as f64 / 1e9;
+        let measured_enough = match config.duration {
+            CalibrationDuration::Frames(n) => measured >= n,
+            CalibrationDuration::Seconds(s) => elapsed_s >= s,
+        };
+        measured_enough
+            && (self.converged(config, frames, jitter_ns) != Some(false)
+                || elapsed_s >= config.max_seconds)
+    }
+
+    /// Whether the jitter estimate moved by at most the tolerance over the
+    /// last `stable_frames` frames; `None` without a tolerance
+    pub fn converged(
+        &self,
+        config: &CalibrationConfig,
+        frames: u64,
+        jitter_ns: f64,
+    ) -> Option<bool> {
+        let tolerance = config.jitter_tolerance?;
+        let measured = self.measured(frames);
+        Some(self.jitter.front().is_some_and(|&(at, earlier_ns)| {
+            measured >= at + config.stable_frames
+                && (jitter_ns - earlier_ns).abs() <= tolerance * jitter_ns.max(earlier_ns)
+        }))
+    }
+
+    pub fn elapsed_ns(&self, now_ns: u64) -> u64 {
+        now_ns.saturating_sub(self.start_ns)
+    }
+
+    /// Frames recorded since the phase started
+    pub fn measured(&self, frames: u64) -> u64 {
+        frames.saturating_sub(self.start_frames)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::config::{ExperimentConfig, PracticeCriterion};
+use crate::interval::Interval;
+use crate::session::SessionInfo;
+use anyhow::{Context, Result};
+use cogex_core::{Phase, StimulusType, TrialResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Design parameters a checkpoint must match to be resumed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    pub practice_trials: usize,
+    pub practice_criterion: Option<PracticeCriterion>,
+    pub experiment_trials: usize,
+    pub fixation_range_ms: (u64, u64),
+    pub stimulus_duration_ms: u64,
+    pub response_window_ms: u64,
+    pub feedback_duration_ms: u64,
+    pub inter_trial_interval: Interval,
+    /// `(trials, break_after)` per block
+    pub blocks: Vec<(usize, bool)>,
+    pub n_back: usize,
+    pub cue_duration_ms: u64,
+    pub cue_target_soa: Interval,
+}
+
+impl ConfigSnapshot {
+    pub fn of<P: Phase>(config: &ExperimentConfig<P>) -> Self {
+        Self {
+            practice_trials: config.practice_trials,
+            practice_criterion: config.practice_criterion,
+            experiment_trials: config.experiment_trials,
+            fixation_range_ms: config.fixation_range_ms,
+            stimulus_duration_ms: config.stimulus_duration_ms,
+            response_window_ms: config.response_window_ms,
+            feedback_duration_ms: config.feedback_duration_ms,
+            inter_trial_interval: config.inter_trial_interval,
+            blocks: config
+                .blocks
+                .iter()
+                .map(|b| (b.trials, b.break_after))
+                .collect(),
+            n_back: config.n_back,
+            cue_duration_ms: config.cue_duration_ms,
+            cue_target_soa: config.cue_target_soa,
+        }
+    }
+}
+
+/// Session state written between trials so an interrupted run can resume
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub session: Option<SessionInfo>,
+    /// Phase as the number of `Phase::next` steps from `P::default()`
+    pub phase_index: usize,
+    pub trial_number: usize,
+    pub phase_trial_number: usize,
+    pub current_block: usize,
+    pub block_trial_number: usize,
+    /// Practice repeats already run under the accuracy criterion
+    #[serde(default)]
+    pub practice_attempt: usize,
+    pub safe_margin_ns: u64,
+    /// The generator is reseeded with this at save time, so a resumed run
+    /// draws the same numbers as an uninterrupted one
+    pub rng_seed: u64,
+    pub config: ConfigSnapshot,
+    pub results: Vec<TrialResult<StimulusType>>,
+}
+
+impl Checkpoint {
+    /// Writes via a temporary file so a crash mid-write keeps the old checkpoint
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Invalid checkpoint {}", path.display()))
+    }
+}
+
+/// `checkpoint_<participant>_s<session>.json` in `dir`
+pub fn checkpoint_path(dir: &Path, session: Option<&SessionInfo>) -> PathBuf {
+    let stem = session
+        .map(SessionInfo::file_stem)
+        .unwrap_or_else(|| "anonymous".to_string());
+    dir.join(format!("checkpoint_{}.json", stem))
+}
+
+/// Steps from the default phase; `None` if `index` is past the last phase
+pub(crate) fn phase_at<P: Phase>(index: usize) -> Option<P> {
+    let mut phase = P::default();
+    for _ in 0..index {
+        phase = phase.next()?;
+    }
+    Some(phase)
+}
+
+pub(crate) fn phase_index<P: Phase>(phase: &P) -> usize {
+    let mut current = P::default();
+    let mut index = 0;
+    while current != *phase {
+        match current.next() {
+            Some(next) => current = next,
+            None => break,
+        }
+        index += 1;
+    }
+    index
+}
@@ -1,16 +1,446 @@
 use std::marker::PhantomData;
 
-use cogex_core::Phase;
+use crate::interval::Interval;
+use crate::layout::{Layout, LayoutSet};
+use anyhow::{bail, Context, Result};
+use cogex_core::{DisplayGeometry, Phase, PreinternPlan, ResponseKey, StimulusType, Theme};
+use cogex_sync::TriggerCodes;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+/// One block of experiment-phase trials
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockConfig {
+    pub trials: usize,
+    /// Stimuli sampled for this block; empty uses the default generator
+    #[serde(default)]
+    pub conditions: Vec<StimulusType>,
+    /// Show a "Press SPACE to continue" break screen after this block
+    #[serde(default = "break_after_default")]
+    pub break_after: bool,
+    /// Seconds of rest counted down on that break screen before Space
+    /// continues; 0 lets it be dismissed at once
+    #[serde(default)]
+    pub min_rest_s: u32,
+}
+
+fn break_after_default() -> bool {
+    true
+}
+
+impl BlockConfig {
+    pub fn new(trials: usize) -> Self {
+        Self {
+            trials,
+            conditions: Vec::new(),
+            break_after: true,
+            min_rest_s: 0,
+        }
+    }
+
+    pub fn with_conditions(mut self, conditions: Vec<StimulusType>) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    pub fn with_break(mut self, break_after: bool) -> Self {
+        self.break_after = break_after;
+        self
+    }
+
+    pub fn with_min_rest(mut self, seconds: u32) -> Self {
+        self.min_rest_s = seconds;
+        self
+    }
+}
+
+/// Accuracy practice must reach before the experiment phase starts
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PracticeCriterion {
+    /// Proportion correct, 0..=1
+    pub min_accuracy: f64,
+    /// Repeats allowed before moving on regardless
+    pub max_repeats: usize,
+}
+
+impl PracticeCriterion {
+    pub fn new(min_accuracy: f64, max_repeats: usize) -> Self {
+        Self {
+            min_accuracy,
+            max_repeats,
+        }
+    }
+}
+
+/// Least the calibration phase measures frame timing for
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CalibrationDuration {
+    Frames(u64),
+    Seconds(f64),
+}
+
+/// When the calibration phase ends: after its duration, once the timer's
+/// jitter estimate has settled, or at the time limit regardless
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalibrationConfig {
+    /// `{ Frames = 120 }` or `{ Seconds = 2.0 }`
+    pub duration: CalibrationDuration,
+    /// Largest relative change of the jitter estimate over the last
+    /// `stable_frames` frames that counts as settled; `None` ends the phase
+    /// at `duration`
+    pub jitter_tolerance: Option<f64>,
+    pub stable_frames: u64,
+    /// Ends the phase after this long even if the jitter has not settled,
+    /// which the calibration stats record
+    pub max_seconds: f64,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        Self {
+            duration: CalibrationDuration::Frames(120),
+            jitter_tolerance: Some(0.05),
+            stable_frames: 60,
+            max_seconds: 10.0,
+        }
+    }
+}
+
+/// MRI scanner volume triggers (TR pulses), taken from a key the trigger
+/// interface types and/or a serial or parallel port
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScannerConfig {
+    /// Key typed per pulse, usually `5`; it is never taken as a response
+    pub pulse_key: Option<ResponseKey>,
+    /// Serial port sending a byte per pulse, e.g. `/dev/ttyUSB0`
+    pub serial_port: Option<String>,
+    pub baud_rate: u32,
+    /// Parallel port whose ACK line goes high per pulse, e.g.
+    /// `/dev/parport0` (Linux)
+    pub parallel_port: Option<String>,
+    /// Holds the first experiment trial, on its fixation, until the first
+    /// pulse; event onsets are then timed from that pulse
+    pub wait_for_first_pulse: bool,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            pulse_key: Some(ResponseKey::Char('5')),
+            serial_port: None,
+            baud_rate: 115_200,
+            parallel_port: None,
+            wait_for_first_pulse: true,
+        }
+    }
+}
+
+/// On-screen widgets drawn over the trials: a countdown before each
+/// experiment block and a bar showing how far the session has got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WidgetConfig {
+    /// Seconds counted down before each experiment block; 0 starts blocks
+    /// at once
+    pub block_countdown_s: u32,
+    /// Font size of the countdown digits
+    pub countdown_size: f32,
+    pub countdown_layout: Layout,
+    /// Show the share of the phase's trials done
+    pub progress_bar: bool,
+    pub progress_width: f32,
+    pub progress_height: f32,
+    pub progress_layout: Layout,
+}
+
+impl Default for WidgetConfig {
+    /// No countdown, and a 300×8 progress bar near the bottom edge once
+    /// enabled
+    fn default() -> Self {
+        Self {
+            block_countdown_s: 0,
+            countdown_size: 96.0,
+            countdown_layout: Layout::Center,
+            progress_bar: false,
+            progress_width: 300.0,
+            progress_height: 8.0,
+            progress_layout: Layout::Anchored { x: 0.0, y: 0.9 },
+        }
+    }
+}
+
+impl WidgetConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.countdown_size.is_nan() || self.countdown_size <= 0.0 {
+            bail!("widgets.countdown_size must be positive");
+        }
+        if !(self.progress_width > 0.0 && self.progress_height > 0.0) {
+            bail!("widgets.progress_width and progress_height must be positive");
+        }
+        self.countdown_layout
+            .validate()
+            .context("widgets.countdown_layout")?;
+        self.progress_layout
+            .validate()
+            .context("widgets.progress_layout")
+    }
+}
+
+/// Which monitor a window is placed on
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum MonitorSelection {
+    #[default]
+    Primary,
+    /// Position in the platform's monitor list
+    Index(usize),
+    /// First monitor whose name contains this string
+    Name(String),
+}
+
+/// What the screen shows during the inter-trial interval
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum ItiDisplay {
+    #[default]
+    Blank,
+    /// The fixation cross, so fixation is held from one trial to the next
+    Fixation,
+    /// A stimulus at the screen centre
+    Stimulus(StimulusType),
+}
+
+impl ItiDisplay {
+    pub fn shows_fixation(&self) -> bool {
+        matches!(self, ItiDisplay::Fixation)
+    }
+
+    pub fn stimulus(&self) -> Option<&StimulusType> {
+        match self {
+            ItiDisplay::Stimulus(stimulus) => Some(stimulus),
+            _ => None,
+        }
+    }
+}
+
+/// When the participant window asks for new frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RedrawMode {
+    /// Every refresh, on every screen
+    Continuous,
+    /// Only when the screen changes or a timed transition is a few frames
+    /// away; instructions, breaks and long static trial stretches go idle
+    #[default]
+    OnDemand,
+}
+
+/// Keys a participant may answer with, by phase and condition. Other keys
+/// pressed during a response window are logged as invalid and not scored.
+/// Where no set applies, the response mapping's keys are accepted.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResponseKeySet<P> {
+    keys: Option<Vec<ResponseKey>>,
+    phases: Vec<(P, Vec<ResponseKey>)>,
+    conditions: Vec<(String, Vec<ResponseKey>)>,
+}
+
+impl<P: Phase> ResponseKeySet<P> {
+    pub fn new() -> Self {
+        Self {
+            keys: None,
+            phases: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    /// Keys accepted wherever no phase or condition set applies
+    pub fn with_keys(mut self, keys: &[ResponseKey]) -> Self {
+        self.keys = Some(keys.to_vec());
+        self
+    }
+
+    pub fn with_phase(mut self, phase: P, keys: &[ResponseKey]) -> Self {
+        self.phases.retain(|(p, _)| *p != phase);
+        self.phases.push((phase, keys.to_vec()));
+        self
+    }
+
+    pub fn with_condition(mut self, condition: &str, keys: &[ResponseKey]) -> Self {
+        self.conditions.retain(|(c, _)| c != condition);
+        self.conditions.push((condition.to_string(), keys.to_vec()));
+        self
+    }
+
+    /// Keys accepted on a `condition` trial in `phase`: the condition's set,
+    /// else the phase's, else the overall one
+    pub fn keys_for(&self, phase: &P, condition: &str) -> Option<&[ResponseKey]> {
+        let by_condition = self.conditions.iter().find(|(c, _)| c == condition);
+        let by_phase = || self.phases.iter().find(|(p, _)| p == phase);
+        by_condition
+            .map(|(_, keys)| keys)
+            .or_else(|| by_phase().map(|(_, keys)| keys))
+            .map(Vec::as_slice)
+            .or(self.keys.as_deref())
+    }
+}
+
+/// Experimenter key combination, e.g. Ctrl+Shift+Q
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotkey {
+    pub key: ResponseKey,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl Hotkey {
+    pub fn new(key: ResponseKey) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// True if `key` pressed with exactly these modifiers is the combination
+    pub fn matches(&self, key: ResponseKey, ctrl: bool, shift: bool, alt: bool) -> bool {
+        self.key == key && self.ctrl == ctrl && self.shift == shift && self.alt == alt
+    }
+}
+
+impl Default for Hotkey {
+    fn default() -> Self {
+        Self::new(ResponseKey::Char('q')).with_ctrl().with_shift()
+    }
+}
+
+/// Design and hardware settings for a session. Loaded from TOML, every field
+/// is optional and falls back to its default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ExperimentConfig<P: Phase> {
     pub practice_trials: usize,
+    /// Repeat practice while its accuracy is below the criterion
+    pub practice_criterion: Option<PracticeCriterion>,
     pub experiment_trials: usize,
     pub fixation_range_ms: (u64, u64),
     pub stimulus_duration_ms: u64,
     pub response_window_ms: u64,
     pub feedback_duration_ms: u64,
-    pub inter_trial_interval_ms: u64,
+    /// Show the reaction time under the feedback verdict
+    pub feedback_show_rt: bool,
+    /// Pause after each trial, drawn anew per trial
+    pub inter_trial_interval: Interval,
+    pub iti_display: ItiDisplay,
+    /// How long the cue of a cued trial stays on screen
+    pub cue_duration_ms: u64,
+    /// Cue onset to target onset, drawn anew per cued trial
+    pub cue_target_soa: Interval,
+    /// Experiment-phase blocks; when empty the phase is a single block of
+    /// `experiment_trials` trials
+    pub blocks: Vec<BlockConfig>,
+    /// Monitor hosting the fullscreen participant window
+    pub monitor: MonitorSelection,
+    /// Monitor for the experimenter status view; `None` disables it
+    pub experimenter_monitor: Option<MonitorSelection>,
+    /// Address of a WebSocket server broadcasting live trial events, e.g.
+    /// `0.0.0.0:9001`; `None` disables it
+    pub monitor_server: Option<SocketAddr>,
+    /// Address taking control commands over HTTP, e.g. `192.168.1.20:9002`.
+    /// Commands are unauthenticated, so bind the interface the orchestrator
+    /// shares rather than `0.0.0.0`; `None` disables it
+    pub control_http: Option<SocketAddr>,
+    /// UDP address taking control commands as OSC messages
+    pub control_osc: Option<SocketAddr>,
+    /// When set, stimulus sizes and positions are in degrees of visual angle,
+    /// with positions relative to the screen centre
+    pub display: Option<DisplayGeometry>,
+    /// Where stimuli go, by condition, when the trial sequence gives no
+    /// position
+    pub layouts: LayoutSet,
+    pub widgets: WidgetConfig,
+    /// Smooth the edges of circles and arrows instead of rasterizing them
+    /// to whole pixels, which keeps each pixel at the exact stimulus colour
+    pub anti_alias: bool,
+    /// Colours of the background, text, feedback and default shapes
+    pub theme: Theme,
+    /// Megabytes of rendered text the renderer keeps for reuse, per cache;
+    /// the least recently drawn strings are dropped beyond it
+    pub text_cache_mb: usize,
+    /// Mouse clicks farther than this from the stimulus centre (pixels, or
+    /// degrees with `display`) are scored incorrect; `None` scores clicks by
+    /// button only
+    pub click_radius: Option<f32>,
+    /// How many trials back the N-back paradigm compares against
+    pub n_back: usize,
+    /// Stimuli per display in the visual search paradigm, target included
+    pub search_set_sizes: Vec<usize>,
+    /// Refreshes the masked priming paradigm shows its prime for, and its
+    /// mask after it
+    pub prime_frames: u64,
+    pub mask_frames: u64,
+    /// Hardware trigger codes per event
+    pub triggers: TriggerCodes,
+    /// Language of participant-facing text: a bundled code (`en`, `de`,
+    /// `hu`) or a path to a catalog file
+    pub locale: String,
+    /// Dropped frames over the session above which the debrief warns that
+    /// timing may be compromised
+    pub max_dropped_frames: u64,
+    /// Mean keyboard scan delay subtracted from key-down timestamps, e.g.
+    /// 4 ms for a keyboard polled at 125 Hz
+    pub key_scan_ms: f64,
+    /// Seconds between the timer/UTC samples recorded in the session
+    /// metadata; 0 samples only at the start and on saving
+    pub clock_sync_interval_s: u64,
+    /// Task label in BIDS file names
+    pub task: String,
+    pub redraw: RedrawMode,
+    /// Accepted response keys; unset accepts whatever the response mapping
+    /// expects
+    pub response_keys: ResponseKeySet<P>,
+    /// Ends the session at once, saving what was collected; `None` disables
+    /// it
+    pub abort_hotkey: Option<Hotkey>,
+    /// Show the return screen when the participant window loses focus;
+    /// unset does so unless the experimenter types commands into a
+    /// terminal, which takes the focus itself
+    pub interrupt_on_focus_loss: Option<bool>,
+    /// Rhai script defining the trials of the `script` experiment
+    pub script: Option<PathBuf>,
+    /// Words of the `lexical` experiment, one per line
+    pub word_list: Option<PathBuf>,
+    /// Non-words of the `lexical` experiment, one per line
+    pub nonword_list: Option<PathBuf>,
+    /// Directory receiving a PNG of each screen and trial state as it is
+    /// first drawn, for checking layouts; `None` saves none
+    pub snapshot_dir: Option<PathBuf>,
+    /// TR pulses of an MRI scanner; `None` takes none
+    pub scanner: Option<ScannerConfig>,
+    pub calibration: CalibrationConfig,
+    #[serde(skip)]
     _phantom: std::marker::PhantomData<P>,
 }
 
@@ -19,13 +449,158 @@ impl<P: Phase> Default for ExperimentConfig<P> {
         /* same as before */
         Self {
             practice_trials: 20,
+            practice_criterion: None,
             experiment_trials: 100,
             fixation_range_ms: (500, 1500),
             stimulus_duration_ms: 200,
             response_window_ms: 2000,
             feedback_duration_ms: 500,
-            inter_trial_interval_ms: 1000,
+            feedback_show_rt: false,
+            inter_trial_interval: Interval::Fixed(1000),
+            iti_display: ItiDisplay::Blank,
+            cue_duration_ms: 100,
+            cue_target_soa: Interval::Fixed(300),
+            blocks: Vec::new(),
+            monitor: MonitorSelection::Primary,
+            experimenter_monitor: None,
+            monitor_server: None,
+            control_http: None,
+            control_osc: None,
+            display: None,
+            layouts: LayoutSet::default(),
+            widgets: WidgetConfig::default(),
+            anti_alias: false,
+            theme: Theme::default(),
+            text_cache_mb: 64,
+            click_radius: None,
+            n_back: 2,
+            search_set_sizes: vec![4, 8, 16],
+            prime_frames: 3,
+            mask_frames: 6,
+            triggers: TriggerCodes::default(),
+            locale: "en".to_string(),
+            max_dropped_frames: 10,
+            key_scan_ms: 0.0,
+            clock_sync_interval_s: 60,
+            task: "experiment".to_string(),
+            redraw: RedrawMode::OnDemand,
+            response_keys: ResponseKeySet::new(),
+            abort_hotkey: Some(Hotkey::default()),
+            interrupt_on_focus_loss: None,
+            script: None,
+            word_list: None,
+            nonword_list: None,
+            snapshot_dir: None,
+            scanner: None,
+            calibration: CalibrationConfig::default(),
             _phantom: PhantomData,
         }
     }
 }
+
+impl<P: Phase + DeserializeOwned> ExperimentConfig<P> {
+    /// Reads a TOML config file and checks it with [`validate`](Self::validate)
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_toml(&text).with_context(|| format!("Invalid config {}", path.display()))
+    }
+
+    /// Config from TOML text, such as one fetched by the browser build
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl<P: Phase> ExperimentConfig<P> {
+    /// Rejects settings no session could run with
+    pub fn validate(&self) -> Result<()> {
+        let (min, max) = self.fixation_range_ms;
+        if min > max {
+            bail!("fixation_range_ms minimum {} exceeds maximum {}", min, max);
+        }
+        if self.response_window_ms == 0 {
+            bail!("response_window_ms must be positive");
+        }
+        if let Some(criterion) = &self.practice_criterion
+            && !(0.0..=1.0).contains(&criterion.min_accuracy)
+        {
+            bail!(
+                "practice_criterion.min_accuracy {} is outside 0..=1",
+                criterion.min_accuracy
+            );
+        }
+        if let Some(i) = self.blocks.iter().position(|b| b.trials == 0) {
+            bail!("Block {} has no trials", i + 1);
+        }
+        if let Some(i) = self
+            .blocks
+            .iter()
+            .position(|b| b.min_rest_s > 0 && !b.break_after)
+        {
+            bail!("Block {} has a minimum rest but no break", i + 1);
+        }
+        if self.click_radius.is_some_and(|r| r.is_nan() || r <= 0.0) {
+            bail!("click_radius must be positive");
+        }
+        if self.key_scan_ms.is_nan() || self.key_scan_ms < 0.0 {
+            bail!("key_scan_ms must not be negative");
+        }
+        let calibration = &self.calibration;
+        if matches!(calibration.duration, CalibrationDuration::Seconds(s) if s.is_nan() || s < 0.0)
+        {
+            bail!("calibration.duration must not be negative");
+        }
+        if calibration
+            .jitter_tolerance
+            .is_some_and(|t| t.is_nan() || t < 0.0)
+        {
+            bail!("calibration.jitter_tolerance must not be negative");
+        }
+        if calibration.max_seconds.is_nan() || calibration.max_seconds <= 0.0 {
+            bail!("calibration.max_seconds must be positive");
+        }
+        self.layouts.validate()?;
+        self.widgets.validate()?;
+        if crate::bids::bids_label(&self.task).is_empty() {
+            bail!("task {:?} has no letters or digits", self.task);
+        }
+        Ok(())
+    }
+
+    /// Total experiment-phase trials across all blocks
+    pub fn total_experiment_trials(&self) -> usize {
+        if self.blocks.is_empty() {
+            self.experiment_trials
+        } else {
+            self.blocks.iter().map(|b| b.trials).sum()
+        }
+    }
+
+    /// Number of trials run in `phase`
+    pub fn trials_for(&self, phase: &P) -> usize {
+        if phase.is_practice() {
+            self.practice_trials
+        } else if phase.is_experiment() {
+            self.total_experiment_trials()
+        } else {
+            0
+        }
+    }
+
+    /// The trial counter and feedback strings these settings can show, for
+    /// the renderer to intern before the session starts. Feedback reaction
+    /// times are covered up to the response window; trials with a longer
+    /// window of their own format the rest when shown.
+    pub fn preintern_plan(&self) -> PreinternPlan {
+        let mut plan = PreinternPlan::new()
+            .with_progress_total(self.practice_trials)
+            .with_progress_total(self.total_experiment_trials());
+        if self.feedback_show_rt {
+            plan = plan.with_feedback_rt(self.response_window_ms);
+        }
+        plan
+    }
+}
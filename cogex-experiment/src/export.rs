@@ -0,0 +1,269 @@
+use crate::output::OutputManager;
+use crate::session::SessionInfo;
+use anyhow::Result;
+use cogex_core::{Questionnaire, TrialResult};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Serializes results with the session metadata as a header object
+pub fn results_to_json<S: Serialize>(
+    session: Option<&SessionInfo>,
+    results: &[TrialResult<S>],
+) -> Value {
+    let trials: Vec<Value> = results
+        .iter()
+        .map(|r| {
+            json!({
+                "trial_id": r.trial_id,
+                "stimulus": r.stimulus,
+                "condition": r.condition,
+                "set_size": r.set_size,
+                "response_key": r.response_key.map(|k| format!("{:?}", k)),
+                "click_position": r.click_position,
+                "click_distance": r.click_distance,
+                "reaction_time_ns": r.reaction_time_ns,
+                "voice_onset_ns": r.voice_onset_ns,
+                "voice_reaction_time_ns": r.voice_reaction_time_ns,
+                "correct": r.correct,
+                "outcome": r.outcome.as_str(),
+                "rating": r.rating,
+                "rating_trajectory": r.rating_trajectory,
+                "invalid_keys": r.invalid_keys.iter().map(|k| format!("{:?}", k)).collect::<Vec<_>>(),
+                "intensity": r.intensity,
+                "block": r.block,
+                "timestamp": r.timestamp_ns,
+                "dropped_frames": r.dropped_frames,
+                "interrupted": r.interrupted,
+                "presentation": {
+                    "fixation_onset_ns": r.presentation.fixation_onset_ns,
+                    "cue_onset_ns": r.presentation.cue_onset_ns,
+                    "cue_offset_ns": r.presentation.cue_offset_ns,
+                    "stimulus_onset_ns": r.presentation.stimulus_onset_ns,
+                    "stimulus_offset_ns": r.presentation.stimulus_offset_ns,
+                    "feedback_onset_ns": r.presentation.feedback_onset_ns,
+                },
+                "requested": {
+                    "fixation_ms": r.requested_fixation_ms,
+                    "stimulus_ms": r.requested_stimulus_ms,
+                    "response_window_ms": r.requested_response_window_ms,
+                    "feedback_ms": r.requested_feedback_ms,
+                    "cue_ms": r.requested_cue_ms,
+                    "soa_ms": r.requested_soa_ms,
+                    "cue_frames": r.requested_cue_frames,
+                    "mask_frames": r.requested_mask_frames,
+                    "iti_ms": r.requested_iti_ms,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "metadata": session,
+        "trials": trials,
+    })
+}
+
+/// Writes the session's `.json` results file
+pub fn write_results<S: Serialize>(
+    output: &OutputManager,
+    session: Option<&SessionInfo>,
+    results: &[TrialResult<S>],
+) -> Result<PathBuf> {
+    let json = serde_json::to_string_pretty(&results_to_json(session, results))?;
+    output.write(".json", json)
+}
+
+/// Serializes questionnaire answers with the session metadata; unanswered
+/// items are null
+pub fn questionnaire_to_json(
+    session: Option<&SessionInfo>,
+    questionnaire: &Questionnaire,
+) -> Value {
+    let items: Vec<Value> = questionnaire
+        .items()
+        .iter()
+        .zip(questionnaire.answers())
+        .map(|(item, answer)| {
+            json!({
+                "id": item.id,
+                "prompt": item.prompt,
+                "option": answer,
+                "answer": answer.and_then(|i| item.option_label(i)),
+            })
+        })
+        .collect();
+
+    json!({
+        "metadata": session,
+        "items": items,
+    })
+}
+
+/// Writes the session's `_questionnaire.json` answers file
+pub fn write_questionnaire(
+    output: &OutputManager,
+    session: Option<&SessionInfo>,
+    questionnaire: &Questionnaire,
+) -> Result<PathBuf> {
+    let json = serde_json::to_string_pretty(&questionnaire_to_json(session, questionnaire))?;
+    output.write("_questionnaire.json", json)
+}
+
+/// Flattens results into one Arrow column per field; nested values such as
+/// the click position are split into `_x`/`_y` columns
+#[cfg(feature = "parquet")]
+pub fn results_to_record_batch<S: Serialize>(
+    results: &[TrialResult<S>],
+) -> Result<arrow_array::RecordBatch> {
+    use arrow_array::{
+        ArrayRef, BooleanArray, Float32Array, Float64Array, RecordBatch, StringArray, UInt64Array,
+    };
+    use std::sync::Arc;
+
+    let u64s = |f: fn(&TrialResult<S>) -> Option<u64>| -> ArrayRef {
+        Arc::new(results.iter().map(f).collect::<UInt64Array>())
+    };
+    let f32s = |f: fn(&TrialResult<S>) -> Option<f32>| -> ArrayRef {
+        Arc::new(results.iter().map(f).collect::<Float32Array>())
+    };
+    let strings = |f: fn(&TrialResult<S>) -> Option<String>| -> ArrayRef {
+        Arc::new(results.iter().map(f).collect::<StringArray>())
+    };
+
+    let batch = RecordBatch::try_from_iter([
+        ("trial_id", u64s(|r| Some(r.trial_id as u64))),
+        (
+            "stimulus",
+            strings(|r| serde_json::to_string(&r.stimulus).ok()),
+        ),
+        ("condition", strings(|r| Some(r.condition.clone()))),
+        ("set_size", u64s(|r| Some(r.set_size as u64))),
+        (
+            "response_key",
+            strings(|r| r.response_key.map(|k| format!("{:?}", k))),
+        ),
+        ("click_x", f32s(|r| r.click_position.map(|p| p.0))),
+        ("click_y", f32s(|r| r.click_position.map(|p| p.1))),
+        ("click_distance", f32s(|r| r.click_distance)),
+        ("reaction_time_ns", u64s(|r| r.reaction_time_ns)),
+        ("voice_onset_ns", u64s(|r| r.voice_onset_ns)),
+        ("voice_reaction_time_ns", u64s(|r| r.voice_reaction_time_ns)),
+        (
+            "correct",
+            Arc::new(results.iter().map(|r| r.correct).collect::<BooleanArray>()),
+        ),
+        ("outcome", strings(|r| Some(r.outcome.as_str().to_string()))),
+        ("rating", f32s(|r| r.rating)),
+        (
+            "invalid_keys",
+            strings(|r| {
+                (!r.invalid_keys.is_empty()).then(|| {
+                    r.invalid_keys
+                        .iter()
+                        .map(|k| format!("{:?}", k))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+            }),
+        ),
+        (
+            "rating_trajectory",
+            strings(|r| {
+                (!r.rating_trajectory.is_empty())
+                    .then(|| serde_json::to_string(&r.rating_trajectory).ok())
+                    .flatten()
+            }),
+        ),
+        (
+            "intensity",
+            Arc::new(
+                results
+                    .iter()
+                    .map(|r| r.intensity)
+                    .collect::<Float64Array>(),
+            ),
+        ),
+        ("block", u64s(|r| r.block.map(|b| b as u64))),
+        ("timestamp_ns", u64s(|r| Some(r.timestamp_ns))),
+        ("dropped_frames", u64s(|r| Some(r.dropped_frames))),
+        (
+            "interrupted",
+            Arc::new(
+                results
+                    .iter()
+                    .map(|r| Some(r.interrupted))
+                    .collect::<BooleanArray>(),
+            ),
+        ),
+        (
+            "fixation_onset_ns",
+            u64s(|r| r.presentation.fixation_onset_ns),
+        ),
+        ("cue_onset_ns", u64s(|r| r.presentation.cue_onset_ns)),
+        ("cue_offset_ns", u64s(|r| r.presentation.cue_offset_ns)),
+        (
+            "stimulus_onset_ns",
+            u64s(|r| r.presentation.stimulus_onset_ns),
+        ),
+        (
+            "stimulus_offset_ns",
+            u64s(|r| r.presentation.stimulus_offset_ns),
+        ),
+        (
+            "feedback_onset_ns",
+            u64s(|r| r.presentation.feedback_onset_ns),
+        ),
+        (
+            "requested_fixation_ms",
+            u64s(|r| Some(r.requested_fixation_ms)),
+        ),
+        (
+            "requested_stimulus_ms",
+            u64s(|r| Some(r.requested_stimulus_ms)),
+        ),
+        (
+            "requested_response_window_ms",
+            u64s(|r| Some(r.requested_response_window_ms)),
+        ),
+        (
+            "requested_feedback_ms",
+            u64s(|r| Some(r.requested_feedback_ms)),
+        ),
+        ("requested_cue_ms", u64s(|r| r.requested_cue_ms)),
+        ("requested_soa_ms", u64s(|r| r.requested_soa_ms)),
+        ("requested_cue_frames", u64s(|r| r.requested_cue_frames)),
+        ("requested_mask_frames", u64s(|r| r.requested_mask_frames)),
+        ("requested_iti_ms", u64s(|r| Some(r.requested_iti_ms))),
+    ])?;
+    Ok(batch)
+}
+
+/// Writes the session's `.parquet` results file, with the session metadata
+/// as JSON under the `cogex.session` file metadata key
+#[cfg(feature = "parquet")]
+pub fn write_results_parquet<S: Serialize>(
+    output: &OutputManager,
+    session: Option<&SessionInfo>,
+    results: &[TrialResult<S>],
+) -> Result<PathBuf> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use parquet::format::KeyValue;
+
+    let batch = results_to_record_batch(results)?;
+    let metadata = KeyValue::new(
+        "cogex.session".to_string(),
+        serde_json::to_string(&session)?,
+    );
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_key_value_metadata(Some(vec![metadata]))
+        .build();
+    let (path, file) = output.create(".parquet")?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(path)
+}
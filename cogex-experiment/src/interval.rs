@@ -0,0 +1,101 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Distribution a jittered interval is drawn from, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Interval {
+    Fixed(u64),
+    Uniform {
+        min_ms: u64,
+        max_ms: u64,
+    },
+    /// `min_ms` plus an exponential with mean `scale_ms`, truncated at
+    /// `max_ms`; the usual choice for event-related fMRI
+    Exponential {
+        min_ms: u64,
+        scale_ms: f64,
+        max_ms: u64,
+    },
+    /// Normal restricted to `min_ms..=max_ms`
+    TruncatedNormal {
+        mean_ms: f64,
+        sd_ms: f64,
+        min_ms: u64,
+        max_ms: u64,
+    },
+}
+
+impl Interval {
+    /// Draws one interval
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        match *self {
+            Interval::Fixed(ms) => ms,
+            Interval::Uniform { min_ms, max_ms } => {
+                rng.random_range(min_ms.min(max_ms)..=max_ms.max(min_ms))
+            }
+            Interval::Exponential {
+                min_ms,
+                scale_ms,
+                max_ms,
+            } => {
+                let span = max_ms.saturating_sub(min_ms) as f64;
+                if scale_ms <= 0.0 || span == 0.0 {
+                    return min_ms;
+                }
+                // Inverse CDF of the exponential truncated to 0..=span
+                let mass = 1.0 - (-span / scale_ms).exp();
+                let u: f64 = rng.random();
+                let x = -scale_ms * (1.0 - u * mass).ln();
+                min_ms + (x.round() as u64).min(span as u64)
+            }
+            Interval::TruncatedNormal {
+                mean_ms,
+                sd_ms,
+                min_ms,
+                max_ms,
+            } => {
+                let (lo, hi) = (min_ms as f64, max_ms.max(min_ms) as f64);
+                if sd_ms > 0.0 {
+                    for _ in 0..1000 {
+                        let x = mean_ms + sd_ms * standard_normal(rng);
+                        if (lo..=hi).contains(&x) {
+                            return x.round() as u64;
+                        }
+                    }
+                }
+                // Degenerate or far out of range: fall back to the nearest bound
+                mean_ms.clamp(lo, hi).round() as u64
+            }
+        }
+    }
+
+    /// Shortest interval the distribution can produce
+    pub fn min_ms(&self) -> u64 {
+        match *self {
+            Interval::Fixed(ms) => ms,
+            Interval::Uniform { min_ms, max_ms } => min_ms.min(max_ms),
+            Interval::Exponential { min_ms, .. } | Interval::TruncatedNormal { min_ms, .. } => {
+                min_ms
+            }
+        }
+    }
+}
+
+impl Default for Interval {
+    fn default() -> Self {
+        Interval::Fixed(0)
+    }
+}
+
+impl From<u64> for Interval {
+    fn from(ms: u64) -> Self {
+        Interval::Fixed(ms)
+    }
+}
+
+/// Box–Muller
+fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
@@ -0,0 +1,298 @@
+use anyhow::{bail, Result};
+use cogex_core::DisplayGeometry;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Draws per stimulus before a display's arrangement is started over
+const PLACEMENT_ATTEMPTS: usize = 100;
+/// Fresh arrangements tried before giving up on a display
+const ARRANGEMENT_ATTEMPTS: usize = 50;
+
+/// Side of the vertical meridian a hemifield stimulus appears on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Side {
+    Left,
+    Right,
+    /// Left or right at random, trial by trial
+    #[default]
+    Either,
+}
+
+/// Where a trial's stimulus goes when its sequence entry gives no position.
+/// Distances are in logical pixels, or degrees with a `DisplayGeometry`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Layout {
+    /// Anywhere at least `margin`, a fraction of the screen's width and
+    /// height, from each edge
+    Random {
+        margin: f32,
+    },
+    Center,
+    /// `eccentricity` from the centre along the horizontal meridian
+    Hemifield {
+        side: Side,
+        eccentricity: f32,
+    },
+    /// A random cell of a `columns`×`rows` grid centred on the screen, its
+    /// cells `spacing` apart
+    Grid {
+        columns: usize,
+        rows: usize,
+        spacing: f32,
+    },
+    /// A random point `radius` from the centre; with `positions`, one of
+    /// that many evenly spaced around the circle starting at 12 o'clock
+    Annulus {
+        radius: f32,
+        positions: Option<usize>,
+    },
+    /// A fixed point, `x` and `y` being fractions of the way from the
+    /// centre to the right and bottom edges; negative toward left and top
+    Anchored {
+        x: f32,
+        y: f32,
+    },
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::Random { margin: 0.15 }
+    }
+}
+
+impl Layout {
+    /// Rejects layouts with nowhere to place a stimulus
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            Layout::Random { margin } if !(0.0..0.5).contains(&margin) => {
+                bail!("Random layout margin {} is outside 0..0.5", margin)
+            }
+            Layout::Hemifield { eccentricity, .. } if eccentricity.is_nan() => {
+                bail!("Hemifield eccentricity is not a number")
+            }
+            Layout::Grid { columns, rows, .. } if columns == 0 || rows == 0 => {
+                bail!("Grid layout needs at least one row and column")
+            }
+            Layout::Grid { spacing, .. } if spacing.is_nan() || spacing < 0.0 => {
+                bail!("Grid spacing must not be negative")
+            }
+            Layout::Annulus { radius, .. } if radius.is_nan() || radius < 0.0 => {
+                bail!("Annulus radius must not be negative")
+            }
+            Layout::Annulus {
+                positions: Some(0), ..
+            } => bail!("Annulus needs at least one position"),
+            Layout::Anchored { x, y }
+                if !(-1.0..=1.0).contains(&x) || !(-1.0..=1.0).contains(&y) =>
+            {
+                bail!("Anchored layout ({}, {}) is outside -1..=1", x, y)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Draws a position on `screen`: logical pixels from its top-left
+    /// corner, or degrees from its centre with `display`
+    pub fn place<R: Rng + ?Sized>(
+        &self,
+        screen: &Screen,
+        display: Option<&DisplayGeometry>,
+        rng: &mut R,
+    ) -> (f32, f32) {
+        let (dx, dy) = match *self {
+            Layout::Random { margin } => {
+                let (half_w, half_h) = screen.half_extent(display);
+                let spread = (1.0 - 2.0 * margin).max(0.0);
+                (
+                    rng.random_range(-1.0..=1.0) * half_w * spread,
+                    rng.random_range(-1.0..=1.0) * half_h * spread,
+                )
+            }
+            Layout::Center => (0.0, 0.0),
+            Layout::Hemifield { side, eccentricity } => {
+                let left = match side {
+                    Side::Left => true,
+                    Side::Right => false,
+                    Side::Either => rng.random_bool(0.5),
+                };
+                (if left { -eccentricity } else { eccentricity }, 0.0)
+            }
+            Layout::Grid {
+                columns,
+                rows,
+                spacing,
+            } => {
+                let offset = |i: usize, n: usize| (i as f32 - (n - 1) as f32 / 2.0) * spacing;
+                (
+                    offset(rng.random_range(0..columns.max(1)), columns.max(1)),
+                    offset(rng.random_range(0..rows.max(1)), rows.max(1)),
+                )
+            }
+            Layout::Annulus { radius, positions } => {
+                let angle = match positions {
+                    Some(n) if n > 0 => rng.random_range(0..n) as f32 * TAU / n as f32,
+                    _ => rng.random_range(0.0..TAU),
+                };
+                (radius * angle.sin(), -radius * angle.cos())
+            }
+            Layout::Anchored { x, y } => {
+                let (half_w, half_h) = screen.half_extent(display);
+                (x * half_w, y * half_h)
+            }
+        };
+        let (x, y) = screen.centre(display);
+        (x + dx, y + dy)
+    }
+
+    /// Positions for stimuli enclosed by circles of `radii`, drawn like
+    /// [`place`](Self::place) but each at least `gap` clear of the others and
+    /// of the `fixed` circles, given as centre and radius. `None` when no
+    /// such arrangement turns up, e.g. for more stimuli than the layout
+    /// has room for.
+    pub fn scatter<R: Rng + ?Sized>(
+        &self,
+        screen: &Screen,
+        display: Option<&DisplayGeometry>,
+        fixed: &[((f32, f32), f32)],
+        radii: &[f32],
+        gap: f32,
+        rng: &mut R,
+    ) -> Option<Vec<(f32, f32)>> {
+        let clear = |placed: &[((f32, f32), f32)], pos: (f32, f32), radius: f32| {
+            placed
+                .iter()
+                .all(|&((x, y), r)| (pos.0 - x).hypot(pos.1 - y) >= r + radius + gap)
+        };
+        'arrangement: for _ in 0..ARRANGEMENT_ATTEMPTS {
+            let mut placed = fixed.to_vec();
+            for &radius in radii {
+                let Some(pos) = (0..PLACEMENT_ATTEMPTS)
+                    .map(|_| self.place(screen, display, rng))
+                    .find(|&pos| clear(&placed, pos, radius))
+                else {
+                    continue 'arrangement;
+                };
+                placed.push((pos, radius));
+            }
+            return Some(placed[fixed.len()..].iter().map(|&(pos, _)| pos).collect());
+        }
+        None
+    }
+}
+
+/// Per-condition stimulus layouts, with one for every other condition
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutSet {
+    layout: Layout,
+    conditions: Vec<(String, Layout)>,
+    /// Least space between the stimuli of a multi-stimulus display
+    gap: f32,
+}
+
+impl LayoutSet {
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            conditions: Vec::new(),
+            gap: 0.0,
+        }
+    }
+
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn gap(&self) -> f32 {
+        self.gap
+    }
+
+    pub fn with_condition(mut self, condition: &str, layout: Layout) -> Self {
+        self.conditions.retain(|(c, _)| c != condition);
+        self.conditions.push((condition.to_string(), layout));
+        self
+    }
+
+    /// The layout of `condition` trials
+    pub fn layout_for(&self, condition: &str) -> &Layout {
+        self.conditions
+            .iter()
+            .find(|(c, _)| c == condition)
+            .map_or(&self.layout, |(_, layout)| layout)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.gap.is_nan() || self.gap < 0.0 {
+            bail!("Layout gap must not be negative");
+        }
+        self.layout.validate()?;
+        for (condition, layout) in &self.conditions {
+            if let Err(e) = layout.validate() {
+                bail!("Layout of condition {:?}: {}", condition, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Size of the participant window stimuli are laid out on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Screen {
+    pub width_px: u32,
+    pub height_px: u32,
+    /// Physical pixels per logical one
+    pub scale_factor: f32,
+}
+
+impl Default for Screen {
+    /// 800×600, until the front end reports the window's size
+    fn default() -> Self {
+        Self::new(800, 600, 1.0)
+    }
+}
+
+impl Screen {
+    pub fn new(width_px: u32, height_px: u32, scale_factor: f32) -> Self {
+        Self {
+            width_px,
+            height_px,
+            scale_factor,
+        }
+    }
+
+    pub fn logical_size(&self) -> (f32, f32) {
+        (
+            self.width_px as f32 / self.scale_factor,
+            self.height_px as f32 / self.scale_factor,
+        )
+    }
+
+    /// The screen's centre in layout units: logical pixels from the
+    /// top-left corner, or the origin with `display`
+    pub fn centre(&self, display: Option<&DisplayGeometry>) -> (f32, f32) {
+        match display {
+            Some(_) => (0.0, 0.0),
+            None => {
+                let (w, h) = self.logical_size();
+                (w / 2.0, h / 2.0)
+            }
+        }
+    }
+
+    /// Distance from the centre to the right and bottom edges, in layout
+    /// units
+    fn half_extent(&self, display: Option<&DisplayGeometry>) -> (f32, f32) {
+        match display {
+            Some(display) => {
+                let size = (self.width_px as f32, self.height_px as f32);
+                display.px_to_position(size, (size.0 / 2.0, size.1 / 2.0))
+            }
+            None => {
+                let (w, h) = self.logical_size();
+                (w / 2.0, h / 2.0)
+            }
+        }
+    }
+}
@@ -1,6 +1,46 @@
+pub mod adaptive;
+pub mod bids;
+mod calibration;
+pub mod checkpoint;
 pub mod config;
+pub mod export;
+pub mod interval;
+pub mod layout;
+pub mod output;
+pub mod paradigm;
+pub mod provenance;
+pub mod sequence;
+pub mod session;
 pub mod state;
 pub mod trial;
-pub use config::ExperimentConfig;
+pub use adaptive::{AdaptiveControl, AdaptiveParameter, AdaptiveProcedure, Quest, Staircase};
+pub use bids::{bids_events_sidecar, bids_events_tsv, write_bids_events};
+pub use checkpoint::{checkpoint_path, Checkpoint, ConfigSnapshot};
+pub use config::{
+    BlockConfig, CalibrationConfig, CalibrationDuration, ExperimentConfig, Hotkey, ItiDisplay,
+    MonitorSelection, PracticeCriterion, RedrawMode, ResponseKeySet, ScannerConfig, WidgetConfig,
+};
+pub use export::{questionnaire_to_json, results_to_json, write_questionnaire, write_results};
+#[cfg(feature = "parquet")]
+pub use export::{results_to_record_batch, write_results_parquet};
+pub use interval::Interval;
+pub use layout::{Layout, LayoutSet, Screen, Side};
+pub use output::{OutputManager, DEFAULT_OUTPUT_ROOT};
+#[cfg(feature = "script")]
+pub use paradigm::ScriptParadigm;
+pub use paradigm::{
+    condition_summaries, d_prime, ConditionSummary, CueKind, GoNoGo, GoNoGoScore, LexicalDecision,
+    MaskedPriming, NBack, NBackScore, Paradigm, ParadigmFactory, ParadigmRegistry, Posner,
+    SearchScore, Stroop, StroopColor, VisualSearch,
+};
+pub use provenance::{DisplayInfo, SessionMetadata};
+pub use sequence::{
+    balanced_latin_square_row, Factor, SequencedTrial, TrialSequence, TrialSequenceBuilder,
+    TrialSpec,
+};
+pub use session::{ClockSample, Interruption, SessionInfo};
 pub use state::{ExperimentEvent, ExperimentStateMachine};
-pub use trial::{Trial, TrialDurations, TrialTimestamps};
+pub use trial::{Cue, CueFrames, Trial, TrialDurations, TrialTimestamps};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,91 @@
+use crate::provenance::SessionMetadata;
+use crate::session::SessionInfo;
+use anyhow::{Context, Result};
+use cogex_core::Phase;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory output goes under unless told otherwise
+pub const DEFAULT_OUTPUT_ROOT: &str = "data";
+
+/// Names a session's output files
+/// `<root>/<experiment>/<participant>_s<session>_<timestamp>` plus a suffix.
+/// Files are created exclusively: one that already exists is an error, never
+/// overwritten.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputManager {
+    dir: PathBuf,
+    stem: String,
+}
+
+impl OutputManager {
+    /// Creates the experiment directory; the timestamp is the local time now
+    pub fn new(root: &Path, experiment: &str, session: Option<&SessionInfo>) -> Result<Self> {
+        let dir = root.join(crate::bids::bids_label(experiment));
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        let participant = session
+            .map(SessionInfo::file_stem)
+            .unwrap_or_else(|| "anonymous".to_string());
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        Ok(Self {
+            dir,
+            stem: format!("{}_{}", participant, timestamp),
+        })
+    }
+
+    /// The experiment directory, shared by all its sessions
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn stem(&self) -> &str {
+        &self.stem
+    }
+
+    /// Path of this session's file ending in `suffix`, e.g. `.json`
+    pub fn path(&self, suffix: &str) -> PathBuf {
+        self.dir.join(format!("{}{}", self.stem, suffix))
+    }
+
+    /// Creates `name` in the experiment directory, failing if it exists
+    pub fn create_named(&self, name: &str) -> Result<(PathBuf, File)> {
+        let path = self.dir.join(name);
+        let file = File::create_new(&path).with_context(|| {
+            format!(
+                "Failed to create {} (existing files are kept)",
+                path.display()
+            )
+        })?;
+        Ok((path, file))
+    }
+
+    /// Creates this session's file ending in `suffix`, failing if it exists
+    pub fn create(&self, suffix: &str) -> Result<(PathBuf, File)> {
+        self.create_named(&format!("{}{}", self.stem, suffix))
+    }
+
+    /// Writes a new file named like [`create_named`](Self::create_named)
+    pub fn write_named(&self, name: &str, contents: impl AsRef<[u8]>) -> Result<PathBuf> {
+        let (path, mut file) = self.create_named(name)?;
+        file.write_all(contents.as_ref())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Writes this session's file ending in `suffix`
+    pub fn write(&self, suffix: &str, contents: impl AsRef<[u8]>) -> Result<PathBuf> {
+        self.write_named(&format!("{}{}", self.stem, suffix), contents)
+    }
+
+    /// Records the config, software and display the session ran with as
+    /// `_metadata.json`
+    pub fn write_metadata<P: Phase + Serialize>(
+        &self,
+        metadata: &SessionMetadata<P>,
+    ) -> Result<PathBuf> {
+        let json = serde_json::to_string_pretty(metadata)?;
+        self.write("_metadata.json", json)
+    }
+}
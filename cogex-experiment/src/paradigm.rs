@@ -0,0 +1,180 @@
+pub mod gonogo;
+pub mod lexical;
+pub mod nback;
+pub mod posner;
+pub mod priming;
+pub mod registry;
+#[cfg(feature = "script")]
+pub mod script;
+pub mod search;
+pub mod stroop;
+pub use gonogo::{GoNoGo, GoNoGoScore};
+pub use lexical::LexicalDecision;
+pub use nback::{NBack, NBackScore};
+pub use posner::{CueKind, Posner};
+pub use priming::MaskedPriming;
+pub use registry::{ParadigmFactory, ParadigmRegistry};
+#[cfg(feature = "script")]
+pub use script::ScriptParadigm;
+pub use search::{SearchScore, VisualSearch};
+pub use stroop::{Stroop, StroopColor};
+
+use crate::sequence::TrialSequence;
+use anyhow::Result;
+use cogex_core::{Primitive, ResponseMapping, StimulusType, TrialResult};
+use rand::RngCore;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// A ready-made task: the trials it runs, what counts as correct, and how a
+/// session is summarised at debrief
+pub trait Paradigm: Send {
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence>;
+
+    fn response_mapping(&self) -> ResponseMapping<StimulusType>;
+
+    /// Debrief lines computed from the experiment-phase results
+    fn summary(&self, _results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Render hook: primitives drawn in place of `stimulus`, `elapsed` after
+    /// its onset, on every frame it is shown; `None` draws it as usual
+    fn draw(&self, _stimulus: &StimulusType, _elapsed: Duration) -> Option<Vec<Primitive>> {
+        None
+    }
+
+    /// True when `draw` changes over time for `stimulus`, so frames keep
+    /// coming while it is shown instead of only on changes
+    fn animates(&self, _stimulus: &StimulusType) -> bool {
+        false
+    }
+
+    /// Participant-facing text replacing the locale's, by message id such
+    /// as `feedback-correct`
+    fn messages(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+}
+
+/// Accuracy and speed of one condition
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionSummary {
+    pub trials: usize,
+    pub correct: usize,
+    /// Mean RT over correct responses
+    pub mean_rt_ms: Option<f64>,
+}
+
+impl ConditionSummary {
+    pub fn accuracy(&self) -> f64 {
+        if self.trials == 0 {
+            0.0
+        } else {
+            self.correct as f64 / self.trials as f64
+        }
+    }
+
+    /// Debrief line such as `congruent: 95% correct, 512 ms`
+    pub fn line(&self, condition: &str) -> String {
+        match self.mean_rt_ms {
+            Some(rt) => format!(
+                "{}: {:.0}% correct, {:.0} ms",
+                condition,
+                self.accuracy() * 100.0,
+                rt
+            ),
+            None => format!("{}: {:.0}% correct", condition, self.accuracy() * 100.0),
+        }
+    }
+}
+
+/// Per-condition accuracy and mean correct RT
+pub fn condition_summaries(
+    results: &[TrialResult<StimulusType>],
+) -> BTreeMap<String, ConditionSummary> {
+    let mut rt_sums: BTreeMap<&str, (u64, usize)> = BTreeMap::new();
+    let mut summaries: BTreeMap<String, ConditionSummary> = BTreeMap::new();
+    for result in results {
+        let summary = summaries.entry(result.condition.clone()).or_default();
+        summary.trials += 1;
+        if result.correct == Some(true) {
+            summary.correct += 1;
+            if let Some(rt) = result.reaction_time_ns {
+                let sum = rt_sums.entry(&result.condition).or_default();
+                sum.0 += rt;
+                sum.1 += 1;
+            }
+        }
+    }
+    for (condition, (sum, n)) in rt_sums {
+        if let Some(summary) = summaries.get_mut(condition) {
+            summary.mean_rt_ms = Some(sum as f64 / n as f64 / 1e6);
+        }
+    }
+    summaries
+}
+
+/// Sensitivity index z(H) - z(FA), with the log-linear correction so rates
+/// of 0 or 1 stay finite
+pub fn d_prime(hits: usize, misses: usize, false_alarms: usize, correct_rejections: usize) -> f64 {
+    let rate = |n: usize, total: usize| (n as f64 + 0.5) / (total as f64 + 1.0);
+    let hit_rate = rate(hits, hits + misses);
+    let fa_rate = rate(false_alarms, false_alarms + correct_rejections);
+    probit(hit_rate) - probit(fa_rate)
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation)
+pub fn probit(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const LOW: f64 = 0.02425;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
@@ -0,0 +1,151 @@
+use super::{d_prime, Paradigm};
+use crate::sequence::TrialSequence;
+use anyhow::{bail, Result};
+use cogex_core::{ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::RngCore;
+
+/// Go/No-Go task: respond to the go stimulus, withhold on the rarer no-go
+/// stimulus. Responses on no-go trials are commission errors.
+#[derive(Debug, Clone)]
+pub struct GoNoGo {
+    pub go: StimulusType,
+    pub no_go: StimulusType,
+    /// Share of trials showing the no-go stimulus, in 0..1
+    pub no_go_proportion: f64,
+    pub key: ResponseKey,
+}
+
+impl GoNoGo {
+    pub const GO: &'static str = "go";
+    pub const NO_GO: &'static str = "nogo";
+
+    pub fn new(go: StimulusType, no_go: StimulusType) -> Self {
+        Self {
+            go,
+            no_go,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_no_go_proportion(mut self, proportion: f64) -> Self {
+        self.no_go_proportion = proportion;
+        self
+    }
+
+    pub fn with_key(mut self, key: ResponseKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn score(&self, results: &[TrialResult<StimulusType>]) -> GoNoGoScore {
+        let mut score = GoNoGoScore::default();
+        let mut rt_sum_ns = 0u64;
+        for result in results {
+            let responded = result.response_key.is_some();
+            match (result.condition.as_str(), responded) {
+                (Self::GO, true) => {
+                    score.hits += 1;
+                    rt_sum_ns += result.reaction_time_ns.unwrap_or_default();
+                }
+                (Self::GO, false) => score.misses += 1,
+                (Self::NO_GO, true) => score.commission_errors += 1,
+                (Self::NO_GO, false) => score.correct_rejections += 1,
+                _ => {}
+            }
+        }
+        if score.hits > 0 {
+            score.mean_hit_rt_ms = Some(rt_sum_ns as f64 / score.hits as f64 / 1e6);
+        }
+        score
+    }
+}
+
+impl Default for GoNoGo {
+    fn default() -> Self {
+        Self {
+            go: StimulusType::Circle {
+                radius: 50.0,
+                color: [0, 200, 0, 255],
+            },
+            no_go: StimulusType::Circle {
+                radius: 50.0,
+                color: [220, 0, 0, 255],
+            },
+            no_go_proportion: 0.25,
+            key: ResponseKey::Space,
+        }
+    }
+}
+
+impl Paradigm for GoNoGo {
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        if !(0.0..1.0).contains(&self.no_go_proportion) {
+            bail!(
+                "No-go proportion must be in 0..1, got {}",
+                self.no_go_proportion
+            );
+        }
+        TrialSequence::builder()
+            .condition(Self::GO, self.go.clone())
+            .condition(Self::NO_GO, self.no_go.clone())
+            .weight(Self::GO, 1.0 - self.no_go_proportion)
+            .weight(Self::NO_GO, self.no_go_proportion)
+            .total_trials(trials)
+            .build(rng)
+    }
+
+    /// Only the go stimulus expects a key; everything else must be withheld
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        let go = self.go.clone();
+        ResponseMapping::new().with_rule(self.key, move |s| *s == go)
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let score = self.score(results);
+        let mut lines = vec![
+            format!("Hits: {}/{}", score.hits, score.hits + score.misses),
+            format!(
+                "Commission errors: {}/{}",
+                score.commission_errors,
+                score.commission_errors + score.correct_rejections
+            ),
+        ];
+        if let Some(rt) = score.mean_hit_rt_ms {
+            lines.push(format!("Mean RT: {:.0} ms", rt));
+        }
+        lines.push(format!("d′ = {:.2}", score.d_prime()));
+        lines
+    }
+}
+
+/// Signal-detection tally of a Go/No-Go session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoNoGoScore {
+    pub hits: usize,
+    pub misses: usize,
+    /// Responses on no-go trials
+    pub commission_errors: usize,
+    pub correct_rejections: usize,
+    pub mean_hit_rt_ms: Option<f64>,
+}
+
+impl GoNoGoScore {
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
+    pub fn false_alarm_rate(&self) -> Option<f64> {
+        let total = self.commission_errors + self.correct_rejections;
+        (total > 0).then(|| self.commission_errors as f64 / total as f64)
+    }
+
+    pub fn d_prime(&self) -> f64 {
+        d_prime(
+            self.hits,
+            self.misses,
+            self.commission_errors,
+            self.correct_rejections,
+        )
+    }
+}
@@ -0,0 +1,155 @@
+use super::{condition_summaries, Paradigm};
+use crate::config::ExperimentConfig;
+use crate::sequence::{SequencedTrial, TrialSequence};
+use anyhow::{bail, Context, Result};
+use cogex_core::{Atom, Phase, ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Lexical decision task: judge whether a letter string is a word, with
+/// one key for words and another for non-words
+#[derive(Debug, Clone)]
+pub struct LexicalDecision {
+    pub words: Vec<String>,
+    pub nonwords: Vec<String>,
+    pub word_key: ResponseKey,
+    pub nonword_key: ResponseKey,
+    /// Font size in pixels, or degrees with a `DisplayGeometry`
+    pub size: f32,
+    pub color: [u8; 4],
+}
+
+impl LexicalDecision {
+    pub const WORD: &'static str = "word";
+    pub const NONWORD: &'static str = "nonword";
+
+    /// Words on J and non-words on F
+    pub fn new(words: Vec<String>, nonwords: Vec<String>) -> Self {
+        Self {
+            words,
+            nonwords,
+            word_key: ResponseKey::Char('j'),
+            nonword_key: ResponseKey::Char('f'),
+            size: 64.0,
+            color: [255, 255, 255, 255],
+        }
+    }
+
+    /// Reads both lists from text files with one item per line, skipping
+    /// blank lines and `#` comments
+    pub fn load(words: impl AsRef<Path>, nonwords: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(read_list(words)?, read_list(nonwords)?))
+    }
+
+    /// The lists named by `config.word_list` and `config.nonword_list`
+    pub fn from_config<P: Phase>(config: &ExperimentConfig<P>) -> Result<Self> {
+        let (Some(words), Some(nonwords)) = (&config.word_list, &config.nonword_list) else {
+            bail!(
+                "The lexical decision experiment needs `word_list` and `nonword_list` set in the config"
+            );
+        };
+        Self::load(words, nonwords)
+    }
+
+    pub fn with_keys(mut self, word: ResponseKey, nonword: ResponseKey) -> Self {
+        self.word_key = word;
+        self.nonword_key = nonword;
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    fn text(&self, item: &str) -> StimulusType {
+        StimulusType::Text {
+            content: item.into(),
+            size: self.size,
+            color: self.color,
+        }
+    }
+}
+
+fn read_list(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read the list {}", path.display()))?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// `count` items of `list`, each used once per pass through the shuffled
+/// list before any repeats
+fn draw(list: &[String], count: usize, rng: &mut dyn RngCore) -> Vec<String> {
+    let mut items = Vec::with_capacity(count);
+    while items.len() < count {
+        let mut pass = list.to_vec();
+        pass.shuffle(rng);
+        pass.truncate(count - items.len());
+        items.extend(pass);
+    }
+    items
+}
+
+impl Paradigm for LexicalDecision {
+    /// Half the trials show words and half non-words, an odd one out going
+    /// to either at random
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        if self.words.is_empty() || self.nonwords.is_empty() {
+            bail!("Lexical decision needs at least one word and one non-word");
+        }
+        if let Some(item) = self.words.iter().find(|w| self.nonwords.contains(w)) {
+            bail!("\"{}\" is on both the word and the non-word list", item);
+        }
+        if self.word_key == self.nonword_key {
+            bail!("Words and non-words need different keys");
+        }
+
+        let word_trials = trials / 2 + (trials % 2 == 1 && rng.random_bool(0.5)) as usize;
+        let mut sequence: Vec<SequencedTrial> = draw(&self.words, word_trials, rng)
+            .into_iter()
+            .map(|item| SequencedTrial::new(Self::WORD, self.text(&item)))
+            .chain(
+                draw(&self.nonwords, trials - word_trials, rng)
+                    .into_iter()
+                    .map(|item| SequencedTrial::new(Self::NONWORD, self.text(&item))),
+            )
+            .collect();
+        sequence.shuffle(rng);
+        Ok(TrialSequence::from_trials(sequence))
+    }
+
+    /// Listed words expect the word key and listed non-words the other
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        let words: HashSet<Atom> = self.words.iter().map(|w| w.as_str().into()).collect();
+        let nonwords: HashSet<Atom> = self.nonwords.iter().map(|w| w.as_str().into()).collect();
+        let (word_key, nonword_key) = (self.word_key, self.nonword_key);
+        ResponseMapping::new().with_rule_fn(&[word_key, nonword_key], move |s| match s {
+            StimulusType::Text { content, .. } if words.contains(content) => Some(word_key),
+            StimulusType::Text { content, .. } if nonwords.contains(content) => Some(nonword_key),
+            _ => None,
+        })
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let summaries = condition_summaries(results);
+        let mut lines: Vec<String> = summaries
+            .iter()
+            .map(|(condition, s)| s.line(condition))
+            .collect();
+        if let (Some(word), Some(nonword)) = (
+            summaries.get(Self::WORD).and_then(|s| s.mean_rt_ms),
+            summaries.get(Self::NONWORD).and_then(|s| s.mean_rt_ms),
+        ) {
+            lines.push(format!("Lexicality effect: {:.0} ms", nonword - word));
+        }
+        lines
+    }
+}
@@ -0,0 +1,195 @@
+use super::{d_prime, Paradigm};
+use crate::config::ExperimentConfig;
+use crate::sequence::{SequencedTrial, TrialSequence};
+use anyhow::{bail, Result};
+use cogex_core::{Phase, ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::{Rng, RngCore};
+
+/// N-back task: press the key when the stimulus matches the one shown N
+/// trials earlier, and withhold otherwise
+#[derive(Debug, Clone)]
+pub struct NBack {
+    pub n: usize,
+    pub stimuli: Vec<StimulusType>,
+    /// Share of trials, from the (N+1)th on, that repeat the stimulus N back
+    pub target_proportion: f64,
+    pub key: ResponseKey,
+}
+
+impl NBack {
+    pub const TARGET: &'static str = "target";
+    pub const NON_TARGET: &'static str = "nontarget";
+
+    pub fn new(n: usize, stimuli: Vec<StimulusType>) -> Self {
+        Self {
+            n,
+            stimuli,
+            ..Self::default()
+        }
+    }
+
+    /// Default letter set with N taken from `config.n_back`
+    pub fn from_config<P: Phase>(config: &ExperimentConfig<P>) -> Self {
+        Self {
+            n: config.n_back,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_target_proportion(mut self, proportion: f64) -> Self {
+        self.target_proportion = proportion;
+        self
+    }
+
+    pub fn with_key(mut self, key: ResponseKey) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn score(&self, results: &[TrialResult<StimulusType>]) -> NBackScore {
+        let mut score = NBackScore {
+            n: self.n,
+            ..NBackScore::default()
+        };
+        let mut rt_sum_ns = 0u64;
+        for result in results {
+            match (result.condition.as_str(), result.response_key.is_some()) {
+                (Self::TARGET, true) => {
+                    score.hits += 1;
+                    rt_sum_ns += result.reaction_time_ns.unwrap_or_default();
+                }
+                (Self::TARGET, false) => score.misses += 1,
+                (Self::NON_TARGET, true) => score.false_alarms += 1,
+                (Self::NON_TARGET, false) => score.correct_rejections += 1,
+                _ => {}
+            }
+        }
+        if score.hits > 0 {
+            score.mean_hit_rt_ms = Some(rt_sum_ns as f64 / score.hits as f64 / 1e6);
+        }
+        score
+    }
+}
+
+impl Default for NBack {
+    fn default() -> Self {
+        let letter = |content: &str| StimulusType::Text {
+            content: content.into(),
+            size: 96.0,
+            color: [255, 255, 255, 255],
+        };
+        Self {
+            n: 2,
+            stimuli: ["B", "C", "D", "F", "H", "K", "L", "R"]
+                .into_iter()
+                .map(letter)
+                .collect(),
+            target_proportion: 0.3,
+            key: ResponseKey::Space,
+        }
+    }
+}
+
+impl Paradigm for NBack {
+    /// Draws trials one at a time from the running history, so targets are
+    /// exactly the planned repeats and no non-target matches by chance
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        if self.n == 0 {
+            bail!("N-back needs N of at least 1");
+        }
+        if self.stimuli.len() < 2 {
+            bail!("N-back needs at least two stimuli");
+        }
+        if !(0.0..=1.0).contains(&self.target_proportion) {
+            bail!(
+                "Target proportion must be in 0..=1, got {}",
+                self.target_proportion
+            );
+        }
+
+        let mut history: Vec<usize> = Vec::with_capacity(trials);
+        let mut sequence = Vec::with_capacity(trials);
+        for i in 0..trials {
+            let back = i.checked_sub(self.n).map(|j| history[j]);
+            let target = back.is_some() && rng.random_bool(self.target_proportion);
+            let index = match back {
+                Some(b) if target => b,
+                Some(b) => {
+                    let pick = rng.random_range(0..self.stimuli.len() - 1);
+                    if pick >= b {
+                        pick + 1
+                    } else {
+                        pick
+                    }
+                }
+                None => rng.random_range(0..self.stimuli.len()),
+            };
+            history.push(index);
+            let condition = if target {
+                Self::TARGET
+            } else {
+                Self::NON_TARGET
+            };
+            sequence.push(SequencedTrial {
+                expected_key: Some(target.then_some(self.key)),
+                ..SequencedTrial::new(condition, self.stimuli[index].clone())
+            });
+        }
+        Ok(TrialSequence::from_trials(sequence))
+    }
+
+    /// Accepts the key; each trial's sequence entry says whether it is expected
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        ResponseMapping::new().with_rule_fn(&[self.key], |_| None)
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let score = self.score(results);
+        let mut lines = vec![
+            format!("{}-back", score.n),
+            format!("Hits: {}/{}", score.hits, score.hits + score.misses),
+            format!(
+                "False alarms: {}/{}",
+                score.false_alarms,
+                score.false_alarms + score.correct_rejections
+            ),
+        ];
+        if let Some(rt) = score.mean_hit_rt_ms {
+            lines.push(format!("Mean RT: {:.0} ms", rt));
+        }
+        lines.push(format!("d′ = {:.2}", score.d_prime()));
+        lines
+    }
+}
+
+/// Signal-detection tally of an N-back session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NBackScore {
+    pub n: usize,
+    pub hits: usize,
+    pub misses: usize,
+    pub false_alarms: usize,
+    pub correct_rejections: usize,
+    pub mean_hit_rt_ms: Option<f64>,
+}
+
+impl NBackScore {
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+
+    pub fn false_alarm_rate(&self) -> Option<f64> {
+        let total = self.false_alarms + self.correct_rejections;
+        (total > 0).then(|| self.false_alarms as f64 / total as f64)
+    }
+
+    pub fn d_prime(&self) -> f64 {
+        d_prime(
+            self.hits,
+            self.misses,
+            self.false_alarms,
+            self.correct_rejections,
+        )
+    }
+}
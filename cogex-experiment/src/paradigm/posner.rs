@@ -0,0 +1,180 @@
+use super::{condition_summaries, Paradigm};
+use crate::sequence::{SequencedTrial, TrialSequence};
+use crate::trial::Cue;
+use anyhow::{bail, Result};
+use cogex_core::{ArrowDirection, ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::RngCore;
+
+/// How the target side is cued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CueKind {
+    /// Arrow at fixation pointing to the cued side (endogenous)
+    #[default]
+    Central,
+    /// Flash at the cued location (exogenous)
+    Peripheral,
+}
+
+/// Posner spatial cueing task: a cue precedes a target on the left or
+/// right, and the participant reports the target's side with the arrow keys.
+/// Valid cues point at the target, invalid ones at the other side, and
+/// neutral ones at neither.
+#[derive(Debug, Clone)]
+pub struct Posner {
+    pub cue: CueKind,
+    pub target: StimulusType,
+    /// Fixation point and the two target locations
+    pub centre: (f32, f32),
+    pub left: (f32, f32),
+    pub right: (f32, f32),
+    /// Relative frequencies of valid, invalid and neutral trials
+    pub valid_weight: f64,
+    pub invalid_weight: f64,
+    pub neutral_weight: f64,
+    /// Cue size: arrow half-length or peripheral box side
+    pub cue_size: f32,
+}
+
+impl Posner {
+    pub const VALID: &'static str = "valid";
+    pub const INVALID: &'static str = "invalid";
+    pub const NEUTRAL: &'static str = "neutral";
+
+    /// Locations in degrees from the screen centre, for use with a
+    /// `DisplayGeometry`
+    pub fn in_degrees(eccentricity: f32) -> Self {
+        Self {
+            target: StimulusType::Circle {
+                radius: 0.5,
+                color: [255, 255, 255, 255],
+            },
+            centre: (0.0, 0.0),
+            left: (-eccentricity, 0.0),
+            right: (eccentricity, 0.0),
+            cue_size: 1.0,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_cue(mut self, cue: CueKind) -> Self {
+        self.cue = cue;
+        self
+    }
+
+    /// Sets the valid, invalid and neutral trial frequencies
+    pub fn with_validity(mut self, valid: f64, invalid: f64, neutral: f64) -> Self {
+        self.valid_weight = valid;
+        self.invalid_weight = invalid;
+        self.neutral_weight = neutral;
+        self
+    }
+
+    fn cue_for(&self, cued: Option<ArrowDirection>) -> Cue<StimulusType> {
+        let color = [200, 200, 200, 255];
+        match (self.cue, cued) {
+            (CueKind::Central, Some(direction)) => Cue::new(
+                StimulusType::Arrow {
+                    direction,
+                    size: self.cue_size,
+                    color,
+                },
+                self.centre,
+            ),
+            // Double-headed arrows aren't a stimulus, so neutral is a bar
+            (CueKind::Central, None) => Cue::new(
+                StimulusType::Rectangle {
+                    width: self.cue_size * 2.0,
+                    height: self.cue_size * 0.3,
+                    color,
+                },
+                self.centre,
+            ),
+            (CueKind::Peripheral, cued) => Cue::new(
+                StimulusType::Rectangle {
+                    width: self.cue_size,
+                    height: self.cue_size,
+                    color,
+                },
+                match cued {
+                    Some(ArrowDirection::Left) => self.left,
+                    Some(ArrowDirection::Right) => self.right,
+                    _ => self.centre,
+                },
+            ),
+        }
+    }
+}
+
+impl Default for Posner {
+    /// Pixel locations for an 800×600 window
+    fn default() -> Self {
+        Self {
+            cue: CueKind::Central,
+            target: StimulusType::Circle {
+                radius: 15.0,
+                color: [255, 255, 255, 255],
+            },
+            centre: (400.0, 300.0),
+            left: (200.0, 300.0),
+            right: (600.0, 300.0),
+            valid_weight: 0.6,
+            invalid_weight: 0.2,
+            neutral_weight: 0.2,
+            cue_size: 30.0,
+        }
+    }
+}
+
+impl Paradigm for Posner {
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        let weights = [self.valid_weight, self.invalid_weight, self.neutral_weight];
+        if weights.iter().any(|w| *w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+            bail!("Posner validity weights must be non-negative and not all zero");
+        }
+
+        let mut builder = TrialSequence::builder()
+            .weight(Self::VALID, self.valid_weight)
+            .weight(Self::INVALID, self.invalid_weight)
+            .weight(Self::NEUTRAL, self.neutral_weight)
+            .total_trials(trials);
+        for (side, other, position) in [
+            (ArrowDirection::Left, ArrowDirection::Right, self.left),
+            (ArrowDirection::Right, ArrowDirection::Left, self.right),
+        ] {
+            for (label, cued) in [
+                (Self::VALID, Some(side)),
+                (Self::INVALID, Some(other)),
+                (Self::NEUTRAL, None),
+            ] {
+                builder = builder.trial(SequencedTrial {
+                    expected_key: Some(Some(side.into())),
+                    position: Some(position),
+                    cue: Some(self.cue_for(cued)),
+                    ..SequencedTrial::new(label, self.target.clone())
+                });
+            }
+        }
+        builder.build(rng)
+    }
+
+    /// Arrow keys report the side; each trial says which one it expects
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        ResponseMapping::new()
+            .with_rule_fn(&[ResponseKey::ArrowLeft, ResponseKey::ArrowRight], |_| None)
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let summaries = condition_summaries(results);
+        let mut lines: Vec<String> = summaries
+            .iter()
+            .map(|(condition, s)| s.line(condition))
+            .collect();
+        if let (Some(valid), Some(invalid)) = (
+            summaries.get(Self::VALID).and_then(|s| s.mean_rt_ms),
+            summaries.get(Self::INVALID).and_then(|s| s.mean_rt_ms),
+        ) {
+            lines.push(format!("Validity effect: {:.0} ms", invalid - valid));
+        }
+        lines
+    }
+}
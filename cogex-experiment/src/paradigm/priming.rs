@@ -0,0 +1,140 @@
+use super::{condition_summaries, Paradigm};
+use crate::config::ExperimentConfig;
+use crate::sequence::{SequencedTrial, TrialSequence};
+use crate::trial::Cue;
+use anyhow::{bail, Result};
+use cogex_core::{Phase, ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::RngCore;
+
+/// Masked number priming: a digit prime shown for a few refreshes and
+/// hidden by a pattern mask, then a target digit to classify as smaller or
+/// larger than 5
+#[derive(Debug, Clone)]
+pub struct MaskedPriming {
+    /// Primes and targets; none may be 5
+    pub digits: Vec<u32>,
+    pub prime_frames: u64,
+    pub mask_frames: u64,
+    pub mask: String,
+    pub smaller_key: ResponseKey,
+    pub larger_key: ResponseKey,
+    /// Font size in pixels, or degrees with a `DisplayGeometry`
+    pub size: f32,
+    /// Where prime, mask and target appear
+    pub centre: (f32, f32),
+}
+
+impl MaskedPriming {
+    pub const CONGRUENT: &'static str = "congruent";
+    pub const INCONGRUENT: &'static str = "incongruent";
+
+    /// Default digits with the prime and mask durations from
+    /// `config.prime_frames` and `config.mask_frames`
+    pub fn from_config<P: Phase>(config: &ExperimentConfig<P>) -> Self {
+        Self::default().with_frames(config.prime_frames, config.mask_frames)
+    }
+
+    pub fn with_frames(mut self, prime: u64, mask: u64) -> Self {
+        self.prime_frames = prime;
+        self.mask_frames = mask;
+        self
+    }
+
+    pub fn with_keys(mut self, smaller: ResponseKey, larger: ResponseKey) -> Self {
+        self.smaller_key = smaller;
+        self.larger_key = larger;
+        self
+    }
+
+    fn text(&self, content: &str) -> StimulusType {
+        StimulusType::Text {
+            content: content.into(),
+            size: self.size,
+            color: [255, 255, 255, 255],
+        }
+    }
+}
+
+impl Default for MaskedPriming {
+    /// 1, 4, 6 and 9 primed for 3 refreshes and masked for 6, smaller on F
+    /// and larger on J, centred in an 800×600 window
+    fn default() -> Self {
+        Self {
+            digits: vec![1, 4, 6, 9],
+            prime_frames: 3,
+            mask_frames: 6,
+            mask: "#####".to_string(),
+            smaller_key: ResponseKey::Char('f'),
+            larger_key: ResponseKey::Char('j'),
+            size: 48.0,
+            centre: (400.0, 300.0),
+        }
+    }
+}
+
+impl Paradigm for MaskedPriming {
+    /// Every prime-target pairing equally often; a pair is congruent when
+    /// both digits fall on the same side of 5
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        if self.prime_frames == 0 {
+            bail!("The prime needs at least one frame");
+        }
+        if let Some(digit) = self.digits.iter().find(|&&d| d == 5 || d > 9) {
+            bail!("{} is not a digit other than 5", digit);
+        }
+        if !self.digits.iter().any(|&d| d < 5) || !self.digits.iter().any(|&d| d > 5) {
+            bail!("Masked priming needs digits on both sides of 5");
+        }
+        if self.smaller_key == self.larger_key {
+            bail!("Smaller and larger responses need different keys");
+        }
+
+        let mut builder = TrialSequence::builder().total_trials(trials);
+        for &prime in &self.digits {
+            for &target in &self.digits {
+                let condition = if (prime < 5) == (target < 5) {
+                    Self::CONGRUENT
+                } else {
+                    Self::INCONGRUENT
+                };
+                let cue = Cue::new(self.text(&prime.to_string()), self.centre)
+                    .with_mask(self.text(&self.mask))
+                    .with_frames(self.prime_frames, self.mask_frames);
+                builder = builder.trial(SequencedTrial {
+                    position: Some(self.centre),
+                    cue: Some(cue),
+                    ..SequencedTrial::new(condition, self.text(&target.to_string()))
+                });
+            }
+        }
+        builder.build(rng)
+    }
+
+    /// Targets below 5 expect the smaller key and those above the larger
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        let (smaller, larger) = (self.smaller_key, self.larger_key);
+        ResponseMapping::new().with_rule_fn(&[smaller, larger], move |s| match s {
+            StimulusType::Text { content, .. } => match content.parse::<u32>() {
+                Ok(digit) if digit < 5 => Some(smaller),
+                Ok(digit) if digit > 5 => Some(larger),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let summaries = condition_summaries(results);
+        let mut lines: Vec<String> = summaries
+            .iter()
+            .map(|(condition, s)| s.line(condition))
+            .collect();
+        if let (Some(congruent), Some(incongruent)) = (
+            summaries.get(Self::CONGRUENT).and_then(|s| s.mean_rt_ms),
+            summaries.get(Self::INCONGRUENT).and_then(|s| s.mean_rt_ms),
+        ) {
+            lines.push(format!("Priming effect: {:.0} ms", incongruent - congruent));
+        }
+        lines
+    }
+}
@@ -0,0 +1,107 @@
+use super::{
+    GoNoGo, LexicalDecision, MaskedPriming, NBack, Paradigm, Posner, Stroop, VisualSearch,
+};
+use crate::config::ExperimentConfig;
+use anyhow::{bail, Context, Result};
+use cogex_core::Phase;
+use std::collections::BTreeMap;
+
+/// Builds a paradigm from the session's config
+pub type ParadigmFactory<P> = Box<dyn Fn(&ExperimentConfig<P>) -> Result<Box<dyn Paradigm>>>;
+
+/// Paradigms selectable by name, e.g. from the command line. Third-party
+/// crates add their own with [`register`](Self::register) and hand the
+/// registry to the front end.
+pub struct ParadigmRegistry<P: Phase> {
+    factories: BTreeMap<String, ParadigmFactory<P>>,
+}
+
+impl<P: Phase> ParadigmRegistry<P> {
+    /// Name that runs the generated shape trials without a paradigm
+    pub const DEFAULT: &'static str = "default";
+
+    pub fn new() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// The paradigms shipped with this crate
+    pub fn builtin() -> Self {
+        let registry = Self::new()
+            .with("gonogo", |_| Ok(Box::new(GoNoGo::default())))
+            .with("stroop", |_| Ok(Box::new(Stroop::default())))
+            .with("posner", |_| Ok(Box::new(Posner::default())))
+            .with("nback", |config| Ok(Box::new(NBack::from_config(config))))
+            .with("lexical", |config| {
+                Ok(Box::new(LexicalDecision::from_config(config)?))
+            })
+            .with("search", |config| {
+                Ok(Box::new(VisualSearch::from_config(config)))
+            })
+            .with("priming", |config| {
+                Ok(Box::new(MaskedPriming::from_config(config)))
+            });
+        #[cfg(feature = "script")]
+        let registry = registry.with("script", |config| {
+            let Some(path) = &config.script else {
+                bail!("The script experiment needs `script` set in the config");
+            };
+            Ok(Box::new(super::ScriptParadigm::load(path)?))
+        });
+        registry
+    }
+
+    /// Adds `name`, replacing any paradigm already registered under it
+    pub fn register(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&ExperimentConfig<P>) -> Result<Box<dyn Paradigm>> + 'static,
+    ) {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    pub fn with(
+        mut self,
+        name: &str,
+        factory: impl Fn(&ExperimentConfig<P>) -> Result<Box<dyn Paradigm>> + 'static,
+    ) -> Self {
+        self.register(name, factory);
+        self
+    }
+
+    /// Registered names, [`DEFAULT`](Self::DEFAULT) first
+    pub fn names(&self) -> Vec<&str> {
+        std::iter::once(Self::DEFAULT)
+            .chain(self.factories.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// Builds the paradigm registered as `name`; `None` for
+    /// [`DEFAULT`](Self::DEFAULT)
+    pub fn create(
+        &self,
+        name: &str,
+        config: &ExperimentConfig<P>,
+    ) -> Result<Option<Box<dyn Paradigm>>> {
+        if name == Self::DEFAULT {
+            return Ok(None);
+        }
+        match self.factories.get(name) {
+            Some(factory) => factory(config)
+                .map(Some)
+                .with_context(|| format!("Failed to set up experiment {}", name)),
+            None => bail!(
+                "Unknown experiment {}; expected one of {}",
+                name,
+                self.names().join(", ")
+            ),
+        }
+    }
+}
+
+impl<P: Phase> Default for ParadigmRegistry<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,193 @@
+use super::{condition_summaries, Paradigm};
+use crate::sequence::{SequencedTrial, TrialSequence, TrialSpec};
+use anyhow::{anyhow, bail, Context, Result};
+use cogex_core::{ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST, INT};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Paradigm defined by a [Rhai](https://rhai.rs) script, so trials,
+/// scoring and feedback text can change without recompiling.
+///
+/// The script defines:
+/// - `trials(n)`: an array of `n` trial maps with `condition`, `stimulus`
+///   (e.g. `#{ Text: #{ content: "RED", size: 64.0, color: [220, 30, 30, 255] } }`),
+///   `key` (a key name, or `()` to withhold the response) and optionally
+///   `response_window_ms`, `position` and `subpixel`
+/// - `keys()`: the names of the keys participants answer with
+/// - `summary(results)`, optional: debrief lines from an array of
+///   `#{ condition, correct, rt_ms, key }` maps
+/// - `messages()`, optional: a map of message id to text replacing the
+///   locale's, e.g. `#{ "feedback-correct": "Well done!" }`
+///
+/// Scripts draw random numbers with `random()`, `random_int(min, max)` and
+/// `array.shuffle()`, seeded from the session's generator so runs replay.
+pub struct ScriptParadigm {
+    path: PathBuf,
+    engine: Engine,
+    ast: AST,
+    rng: Arc<Mutex<StdRng>>,
+    keys: Vec<ResponseKey>,
+    messages: Vec<(String, String)>,
+}
+
+impl ScriptParadigm {
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::compile(path, &source)
+    }
+
+    /// Script from source text; `path` only names it in errors
+    pub fn compile(path: &Path, source: &str) -> Result<Self> {
+        let rng = Arc::new(Mutex::new(StdRng::seed_from_u64(0)));
+        let mut engine = Engine::new();
+        // Trial maps nest deeper than the debug-build defaults allow
+        engine.set_max_expr_depths(256, 128);
+        let random = rng.clone();
+        engine.register_fn("random", move || -> f64 { random.lock().unwrap().random() });
+        let random = rng.clone();
+        engine.register_fn("random_int", move |min: INT, max: INT| -> INT {
+            random.lock().unwrap().random_range(min..=max.max(min))
+        });
+        let random = rng.clone();
+        engine.register_fn("shuffle", move |array: &mut Array| {
+            array.shuffle(&mut *random.lock().unwrap());
+        });
+
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        let mut script = Self {
+            path: path.to_path_buf(),
+            engine,
+            ast,
+            rng,
+            keys: Vec::new(),
+            messages: Vec::new(),
+        };
+
+        let names: Array = script.call("keys", ())?;
+        for name in names {
+            let name = name.into_string().map_err(|t| {
+                anyhow!("{}: keys() must return strings, got {}", path.display(), t)
+            })?;
+            script.keys.push(script.key(&name)?);
+        }
+        if script.keys.is_empty() {
+            bail!("{}: keys() returned no keys", path.display());
+        }
+        if script.defines("messages") {
+            let messages: Map = script.call("messages", ())?;
+            for (id, text) in messages {
+                script.messages.push((id.to_string(), text.to_string()));
+            }
+        }
+        Ok(script)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn defines(&self, function: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == function)
+    }
+
+    fn call<T: Clone + Send + Sync + 'static>(
+        &self,
+        function: &str,
+        args: impl rhai::FuncArgs,
+    ) -> Result<T> {
+        self.engine
+            .call_fn(&mut Scope::new(), &self.ast, function, args)
+            .map_err(|e| anyhow!("{}: {}(): {}", self.path.display(), function, e))
+    }
+
+    fn key(&self, name: &str) -> Result<ResponseKey> {
+        ResponseKey::from_name(name)
+            .ok_or_else(|| anyhow!("{}: unknown key {:?}", self.path.display(), name))
+    }
+
+    fn trial(&self, value: &Dynamic) -> Result<SequencedTrial> {
+        // Through JSON so script floats and integers fill `f32` fields
+        rhai::serde::from_dynamic::<serde_json::Value>(value)
+            .map_err(|e| anyhow!("{}", e))
+            .and_then(|json| Ok(serde_json::from_value::<TrialSpec>(json)?))
+            .and_then(SequencedTrial::try_from)
+            .with_context(|| format!("{}: invalid trial {}", self.path.display(), value))
+    }
+}
+
+impl Paradigm for ScriptParadigm {
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(rng.next_u64());
+        let values: Array = self.call("trials", (trials as INT,))?;
+        if values.len() != trials {
+            bail!(
+                "{}: trials({}) returned {} trials",
+                self.path.display(),
+                trials,
+                values.len()
+            );
+        }
+        let sequence = values
+            .iter()
+            .map(|value| self.trial(value))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TrialSequence::from_trials(sequence))
+    }
+
+    /// The script's keys; each trial carries its own expected key
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        ResponseMapping::new().with_rule_fn(&self.keys, |_| None)
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        if !self.defines("summary") {
+            return condition_summaries(results)
+                .iter()
+                .map(|(condition, s)| s.line(condition))
+                .collect();
+        }
+        let results: Array = results
+            .iter()
+            .map(|result| {
+                let mut map = Map::new();
+                map.insert("condition".into(), result.condition.clone().into());
+                map.insert(
+                    "correct".into(),
+                    result.correct.map_or(Dynamic::UNIT, Dynamic::from),
+                );
+                map.insert(
+                    "rt_ms".into(),
+                    result
+                        .reaction_time_ns
+                        .map_or(Dynamic::UNIT, |ns| Dynamic::from(ns as f64 / 1e6)),
+                );
+                map.insert(
+                    "key".into(),
+                    result
+                        .response_key
+                        .map_or(Dynamic::UNIT, |key| key.name().into()),
+                );
+                map.into()
+            })
+            .collect();
+        match self.call::<Array>("summary", (results,)) {
+            Ok(lines) => lines.into_iter().map(|line| line.to_string()).collect(),
+            Err(e) => {
+                warn!("{}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn messages(&self) -> Vec<(String, String)> {
+        self.messages.clone()
+    }
+}
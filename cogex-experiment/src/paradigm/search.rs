@@ -0,0 +1,189 @@
+use super::{condition_summaries, ConditionSummary, Paradigm};
+use crate::config::ExperimentConfig;
+use crate::sequence::{SequencedTrial, TrialSequence};
+use anyhow::{bail, Result};
+use cogex_core::{Phase, ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::RngCore;
+use std::collections::BTreeMap;
+
+/// Visual search task: report whether the target is among the distractors,
+/// with the display's set size varied so RT can be regressed on it
+#[derive(Debug, Clone)]
+pub struct VisualSearch {
+    pub target: StimulusType,
+    pub distractor: StimulusType,
+    /// Stimuli per display, the target included
+    pub set_sizes: Vec<usize>,
+    pub present_key: ResponseKey,
+    pub absent_key: ResponseKey,
+}
+
+impl VisualSearch {
+    pub const PRESENT: &'static str = "present";
+    pub const ABSENT: &'static str = "absent";
+
+    pub fn new(target: StimulusType, distractor: StimulusType) -> Self {
+        Self {
+            target,
+            distractor,
+            ..Self::default()
+        }
+    }
+
+    /// Default displays with the set sizes from `config.search_set_sizes`
+    pub fn from_config<P: Phase>(config: &ExperimentConfig<P>) -> Self {
+        Self {
+            set_sizes: config.search_set_sizes.clone(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_set_sizes(mut self, set_sizes: &[usize]) -> Self {
+        self.set_sizes = set_sizes.to_vec();
+        self
+    }
+
+    pub fn with_keys(mut self, present: ResponseKey, absent: ResponseKey) -> Self {
+        self.present_key = present;
+        self.absent_key = absent;
+        self
+    }
+
+    /// Condition label of a display, e.g. `present_8`
+    pub fn condition(present: bool, set_size: usize) -> String {
+        let kind = if present { Self::PRESENT } else { Self::ABSENT };
+        format!("{}_{}", kind, set_size)
+    }
+
+    pub fn score(&self, results: &[TrialResult<StimulusType>]) -> SearchScore {
+        let summaries = condition_summaries(results);
+        let by_set_size = |present: bool| {
+            self.set_sizes
+                .iter()
+                .filter_map(|&n| {
+                    let summary = summaries.get(&Self::condition(present, n))?;
+                    Some((n, summary.clone()))
+                })
+                .collect()
+        };
+        SearchScore {
+            present: by_set_size(true),
+            absent: by_set_size(false),
+        }
+    }
+}
+
+impl Default for VisualSearch {
+    /// A T among Ls in sets of 4, 8 and 16, present on J and absent on F
+    fn default() -> Self {
+        let letter = |content: &str| StimulusType::Text {
+            content: content.into(),
+            size: 40.0,
+            color: [255, 255, 255, 255],
+        };
+        Self {
+            target: letter("T"),
+            distractor: letter("L"),
+            set_sizes: vec![4, 8, 16],
+            present_key: ResponseKey::Char('j'),
+            absent_key: ResponseKey::Char('f'),
+        }
+    }
+}
+
+impl Paradigm for VisualSearch {
+    /// Present and absent displays of each set size, equally often
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        if self.set_sizes.is_empty() {
+            bail!("Visual search needs at least one set size");
+        }
+        if self.set_sizes.contains(&0) {
+            bail!("Visual search set sizes must be at least 1");
+        }
+        if self.target == self.distractor {
+            bail!("The search target must differ from the distractors");
+        }
+        if self.present_key == self.absent_key {
+            bail!("Present and absent responses need different keys");
+        }
+
+        let mut builder = TrialSequence::builder().total_trials(trials);
+        for &n in &self.set_sizes {
+            for present in [true, false] {
+                let (stimulus, key) = if present {
+                    (&self.target, self.present_key)
+                } else {
+                    (&self.distractor, self.absent_key)
+                };
+                builder = builder.trial(SequencedTrial {
+                    expected_key: Some(Some(key)),
+                    array: vec![self.distractor.clone(); n - 1],
+                    ..SequencedTrial::new(Self::condition(present, n), stimulus.clone())
+                });
+            }
+        }
+        builder.build(rng)
+    }
+
+    /// Accepts both keys; each trial's sequence entry says which is expected
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        ResponseMapping::new().with_rule_fn(&[self.present_key, self.absent_key], |_| None)
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let score = self.score(results);
+        let mut lines = Vec::new();
+        for (present, by_set_size) in [(true, &score.present), (false, &score.absent)] {
+            for (&n, summary) in by_set_size {
+                lines.push(summary.line(&Self::condition(present, n)));
+            }
+        }
+        if let Some(slope) = score.present_slope() {
+            lines.push(format!(
+                "Search slope, target present: {:.1} ms/item",
+                slope
+            ));
+        }
+        if let Some(slope) = score.absent_slope() {
+            lines.push(format!("Search slope, target absent: {:.1} ms/item", slope));
+        }
+        lines
+    }
+}
+
+/// Accuracy and mean correct RT of a visual search session by set size
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchScore {
+    pub present: BTreeMap<usize, ConditionSummary>,
+    pub absent: BTreeMap<usize, ConditionSummary>,
+}
+
+impl SearchScore {
+    /// Growth of mean correct RT per added item on target-present displays,
+    /// in ms
+    pub fn present_slope(&self) -> Option<f64> {
+        slope(&self.present)
+    }
+
+    pub fn absent_slope(&self) -> Option<f64> {
+        slope(&self.absent)
+    }
+}
+
+/// Least-squares slope of mean RT over set size; `None` with fewer than two
+/// set sizes having an RT
+fn slope(by_set_size: &BTreeMap<usize, ConditionSummary>) -> Option<f64> {
+    let points: Vec<(f64, f64)> = by_set_size
+        .iter()
+        .filter_map(|(&n, summary)| Some((n as f64, summary.mean_rt_ms?)))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / count;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / count;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    Some(covariance / variance)
+}
@@ -0,0 +1,138 @@
+use super::{condition_summaries, Paradigm};
+use crate::sequence::TrialSequence;
+use anyhow::{bail, Result};
+use cogex_core::{ResponseKey, ResponseMapping, StimulusType, TrialResult};
+use rand::RngCore;
+
+/// One colour of the set: the word naming it, its ink and its response key
+#[derive(Debug, Clone, PartialEq)]
+pub struct StroopColor {
+    pub word: &'static str,
+    pub ink: [u8; 4],
+    pub key: ResponseKey,
+}
+
+impl StroopColor {
+    pub fn new(word: &'static str, ink: [u8; 4], key: ResponseKey) -> Self {
+        Self { word, ink, key }
+    }
+}
+
+/// Colour-word Stroop task: name the ink colour of a colour word, which is
+/// congruent when the word names its own ink
+#[derive(Debug, Clone)]
+pub struct Stroop {
+    pub colors: Vec<StroopColor>,
+    /// Share of congruent trials, in 0..=1
+    pub congruent_proportion: f64,
+    /// Font size in pixels, or degrees with a `DisplayGeometry`
+    pub size: f32,
+}
+
+impl Stroop {
+    pub const CONGRUENT: &'static str = "congruent";
+    pub const INCONGRUENT: &'static str = "incongruent";
+
+    pub fn new(colors: Vec<StroopColor>) -> Self {
+        Self {
+            colors,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_congruent_proportion(mut self, proportion: f64) -> Self {
+        self.congruent_proportion = proportion;
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    fn word(&self, word: &StroopColor, ink: &StroopColor) -> StimulusType {
+        StimulusType::Text {
+            content: word.word.into(),
+            size: self.size,
+            color: ink.ink,
+        }
+    }
+}
+
+impl Default for Stroop {
+    /// Red, green, blue and yellow on the D, F, J and K keys
+    fn default() -> Self {
+        Self {
+            colors: vec![
+                StroopColor::new("RED", [220, 30, 30, 255], ResponseKey::Char('d')),
+                StroopColor::new("GREEN", [30, 180, 30, 255], ResponseKey::Char('f')),
+                StroopColor::new("BLUE", [40, 80, 230, 255], ResponseKey::Char('j')),
+                StroopColor::new("YELLOW", [230, 210, 30, 255], ResponseKey::Char('k')),
+            ],
+            congruent_proportion: 0.5,
+            size: 64.0,
+        }
+    }
+}
+
+impl Paradigm for Stroop {
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        let n = self.colors.len();
+        if n < 2 {
+            bail!("Stroop needs at least two colours");
+        }
+        if !(0.0..=1.0).contains(&self.congruent_proportion) {
+            bail!(
+                "Congruent proportion must be in 0..=1, got {}",
+                self.congruent_proportion
+            );
+        }
+
+        // Weights apply per cell, so split each share across its cells
+        let mut builder = TrialSequence::builder()
+            .weight(Self::CONGRUENT, self.congruent_proportion / n as f64)
+            .weight(
+                Self::INCONGRUENT,
+                (1.0 - self.congruent_proportion) / (n * (n - 1)) as f64,
+            )
+            .total_trials(trials);
+        for word in &self.colors {
+            for ink in &self.colors {
+                let label = if word == ink {
+                    Self::CONGRUENT
+                } else {
+                    Self::INCONGRUENT
+                };
+                builder = builder.condition(label, self.word(word, ink));
+            }
+        }
+        builder.build(rng)
+    }
+
+    /// Each ink colour expects its own key, whatever the word says
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        let keys: Vec<ResponseKey> = self.colors.iter().map(|c| c.key).collect();
+        let colors = self.colors.clone();
+        ResponseMapping::new().with_rule_fn(&keys, move |s| match s {
+            StimulusType::Text { color, .. } => {
+                colors.iter().find(|c| c.ink == *color).map(|c| c.key)
+            }
+            _ => None,
+        })
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        let summaries = condition_summaries(results);
+        let mut lines: Vec<String> = summaries
+            .iter()
+            .map(|(condition, s)| s.line(condition))
+            .collect();
+        if let (Some(congruent), Some(incongruent)) = (
+            summaries.get(Self::CONGRUENT).and_then(|s| s.mean_rt_ms),
+            summaries.get(Self::INCONGRUENT).and_then(|s| s.mean_rt_ms),
+        ) {
+            lines.push(format!("Stroop effect: {:.0} ms", incongruent - congruent));
+        }
+        lines
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::ExperimentConfig;
+use crate::session::SessionInfo;
+use cogex_core::{DisplayGeometry, Phase};
+use cogex_timing::CalibrationStats;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Display a session was presented on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DisplayInfo {
+    pub monitor: Option<String>,
+    /// Physical pixels
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: Option<f64>,
+    /// Physical pixels per logical pixel, which stimulus sizes are given in
+    pub scale_factor: f64,
+    pub logical_width: f64,
+    pub logical_height: f64,
+    /// Size of the picture in centimetres, from the configured display
+    /// geometry assuming square pixels
+    pub width_cm: Option<f64>,
+    pub height_cm: Option<f64>,
+}
+
+impl DisplayInfo {
+    /// A display `width`×`height` physical pixels large at `scale_factor`
+    pub fn new(width: u32, height: u32, scale_factor: f64) -> Self {
+        Self {
+            monitor: None,
+            width,
+            height,
+            refresh_hz: None,
+            scale_factor,
+            logical_width: width as f64 / scale_factor,
+            logical_height: height as f64 / scale_factor,
+            width_cm: None,
+            height_cm: None,
+        }
+    }
+
+    pub fn with_monitor(mut self, monitor: Option<String>) -> Self {
+        self.monitor = monitor;
+        self
+    }
+
+    pub fn with_refresh_hz(mut self, refresh_hz: Option<f64>) -> Self {
+        self.refresh_hz = refresh_hz;
+        self
+    }
+
+    /// Fills in the physical size from `geometry`, when configured
+    pub fn with_geometry(mut self, geometry: Option<DisplayGeometry>) -> Self {
+        if let Some(g) = geometry.filter(|g| g.screen_width_px > 0) {
+            let cm_per_px = g.screen_width_cm as f64 / g.screen_width_px as f64;
+            self.width_cm = Some(self.width as f64 * cm_per_px);
+            self.height_cm = Some(self.height as f64 * cm_per_px);
+        }
+        self
+    }
+}
+
+/// Everything needed to reproduce a session: the resolved config, the
+/// software that ran it and the display it ran on
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionMetadata<'a, P: Phase> {
+    pub session: Option<&'a SessionInfo>,
+    pub config: &'a ExperimentConfig<P>,
+    /// Version per crate, e.g. `cogex-core` → `0.1.0`
+    pub crates: BTreeMap<&'static str, &'static str>,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub display: Option<DisplayInfo>,
+    /// Frame timing measured in the calibration phase, when it ran
+    pub calibration: Option<CalibrationStats>,
+    /// Known limits of the platform the session ran on, such as a browser's
+    /// coarse clock, for whoever analyses the data
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub caveats: Vec<String>,
+}
+
+impl<'a, P: Phase> SessionMetadata<'a, P> {
+    /// Records the versions of this crate and the cogex crates it builds on
+    pub fn new(config: &'a ExperimentConfig<P>, session: Option<&'a SessionInfo>) -> Self {
+        let crates = BTreeMap::from([
+            ("cogex-audio", cogex_audio::VERSION),
+            ("cogex-core", cogex_core::VERSION),
+            ("cogex-experiment", crate::VERSION),
+            ("cogex-eyetrack", cogex_eyetrack::VERSION),
+            ("cogex-sync", cogex_sync::VERSION),
+            ("cogex-timing", cogex_timing::VERSION),
+        ]);
+        Self {
+            session,
+            config,
+            crates,
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            display: None,
+            calibration: None,
+            caveats: Vec::new(),
+        }
+    }
+
+    /// Adds a crate the caller links, such as the front end that ran the session
+    pub fn with_crate(mut self, name: &'static str, version: &'static str) -> Self {
+        self.crates.insert(name, version);
+        self
+    }
+
+    pub fn with_display(mut self, display: DisplayInfo) -> Self {
+        self.display = Some(display);
+        self
+    }
+
+    pub fn with_calibration(mut self, stats: CalibrationStats) -> Self {
+        self.calibration = Some(stats);
+        self
+    }
+
+    pub fn with_caveat(mut self, caveat: impl Into<String>) -> Self {
+        self.caveats.push(caveat.into());
+        self
+    }
+}
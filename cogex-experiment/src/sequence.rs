@@ -0,0 +1,451 @@
+use crate::trial::Cue;
+use anyhow::{bail, Result};
+use cogex_core::{ResponseKey, Stimulus, StimulusType};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// One independent variable and its levels
+#[derive(Debug, Clone)]
+pub struct Factor {
+    pub name: String,
+    pub levels: Vec<String>,
+}
+
+/// A fully specified trial from the sequence
+#[derive(Debug, Clone)]
+pub struct SequencedTrial {
+    pub condition: String,
+    pub stimulus: StimulusType,
+    /// Overrides the configured response window for this trial
+    pub response_window_ms: Option<u64>,
+    /// Overrides the mapping's expected key for this trial, for tasks where
+    /// the answer depends on the sequence; `Some(None)` expects no response
+    pub expected_key: Option<Option<ResponseKey>>,
+    /// Fixed target position instead of a random one
+    pub position: Option<(f32, f32)>,
+    /// Further static stimuli shown with `stimulus`, e.g. a search
+    /// display's distractors, placed by the condition's layout where they
+    /// overlap neither it nor each other
+    pub array: Vec<StimulusType>,
+    pub cue: Option<Cue<StimulusType>>,
+    /// Draw the stimuli at their exact fractional position, resampled
+    /// across pixels, instead of snapped to whole pixels
+    pub subpixel: bool,
+}
+
+impl SequencedTrial {
+    pub fn new(condition: impl Into<String>, stimulus: StimulusType) -> Self {
+        Self {
+            condition: condition.into(),
+            stimulus,
+            response_window_ms: None,
+            expected_key: None,
+            position: None,
+            array: Vec::new(),
+            cue: None,
+            subpixel: false,
+        }
+    }
+}
+
+/// A trial as scripts and language bindings describe it, with the expected
+/// response named as in [`ResponseKey::from_name`]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrialSpec {
+    pub condition: String,
+    pub stimulus: StimulusType,
+    /// Key the trial expects; `None` withholds the response
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub response_window_ms: Option<u64>,
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+    /// Stimuli shown alongside `stimulus`
+    #[serde(default)]
+    pub array: Vec<StimulusType>,
+    /// Place the stimulus at its exact fractional position
+    #[serde(default)]
+    pub subpixel: bool,
+}
+
+impl TryFrom<TrialSpec> for SequencedTrial {
+    type Error = anyhow::Error;
+
+    fn try_from(spec: TrialSpec) -> Result<Self> {
+        let key = match spec.key.as_deref() {
+            Some(name) => match ResponseKey::from_name(name) {
+                Some(key) => Some(key),
+                None => bail!("Unknown key {:?}", name),
+            },
+            None => None,
+        };
+        Ok(Self {
+            response_window_ms: spec.response_window_ms,
+            expected_key: Some(key),
+            position: spec.position,
+            array: spec.array,
+            subpixel: spec.subpixel,
+            ..Self::new(spec.condition, spec.stimulus)
+        })
+    }
+}
+
+/// Pre-generated, ordered list of trials
+#[derive(Debug, Clone, Default)]
+pub struct TrialSequence {
+    trials: VecDeque<SequencedTrial>,
+}
+
+impl TrialSequence {
+    pub fn builder() -> TrialSequenceBuilder {
+        TrialSequenceBuilder::default()
+    }
+
+    /// Sequence presenting `trials` in the given order
+    pub fn from_trials(trials: impl IntoIterator<Item = SequencedTrial>) -> Self {
+        Self {
+            trials: trials.into_iter().collect(),
+        }
+    }
+
+    pub fn next_trial(&mut self) -> Option<SequencedTrial> {
+        self.trials.pop_front()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.trials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trials.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SequencedTrial> {
+        self.trials.iter()
+    }
+}
+
+type StimulusFn = Arc<dyn Fn(&[&str]) -> StimulusType + Send + Sync>;
+
+/// Builds trial sequences from factorial designs or explicit conditions.
+///
+/// Cells are the cartesian product of all factors' levels (labelled by joining
+/// the levels with `_`) plus any explicit conditions.
+#[derive(Clone, Default)]
+pub struct TrialSequenceBuilder {
+    factors: Vec<Factor>,
+    stimulus: Option<StimulusFn>,
+    conditions: Vec<SequencedTrial>,
+    repetitions: usize,
+    total: Option<usize>,
+    max_repeats: Option<usize>,
+    latin_square_row: Option<usize>,
+    response_windows: HashMap<String, u64>,
+    weights: HashMap<String, f64>,
+}
+
+impl TrialSequenceBuilder {
+    pub fn factor(mut self, name: &str, levels: &[&str]) -> Self {
+        self.factors.push(Factor {
+            name: name.to_string(),
+            levels: levels.iter().map(|l| l.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Builds the stimulus for a factorial cell from its levels, in factor order
+    pub fn stimulus<F>(mut self, make: F) -> Self
+    where
+        F: Fn(&[&str]) -> StimulusType + Send + Sync + 'static,
+    {
+        self.stimulus = Some(Arc::new(make));
+        self
+    }
+
+    /// Adds a single explicit condition cell
+    pub fn condition(mut self, label: &str, stimulus: StimulusType) -> Self {
+        self.conditions.push(SequencedTrial::new(label, stimulus));
+        self
+    }
+
+    /// Adds a fully specified cell, e.g. with a cue or fixed position
+    pub fn trial(mut self, trial: SequencedTrial) -> Self {
+        self.conditions.push(trial);
+        self
+    }
+
+    /// Adds one cell per stimulus, labelled by `Stimulus::condition`
+    pub fn stimuli(mut self, stimuli: impl IntoIterator<Item = StimulusType>) -> Self {
+        for stimulus in stimuli {
+            self.conditions
+                .push(SequencedTrial::new(stimulus.condition(), stimulus));
+        }
+        self
+    }
+
+    /// Each cell appears this many times
+    pub fn repetitions(mut self, repetitions: usize) -> Self {
+        self.repetitions = repetitions;
+        self
+    }
+
+    /// Fixed sequence length; cells are repeated as evenly as possible
+    pub fn total_trials(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// No condition appears more than `n` times in a row; `build` fails
+    /// for 0
+    pub fn max_repeats(mut self, n: usize) -> Self {
+        self.max_repeats = Some(n);
+        self
+    }
+
+    /// Response deadline for cells whose label, or one of whose factor
+    /// levels, equals `condition`
+    pub fn response_window(mut self, condition: &str, ms: u64) -> Self {
+        self.response_windows.insert(condition.to_string(), ms);
+        self
+    }
+
+    /// Relative frequency of cells labelled `condition`; unweighted cells
+    /// count 1. Ignored by Latin square ordering.
+    pub fn weight(mut self, condition: &str, weight: f64) -> Self {
+        self.weights.insert(condition.to_string(), weight.max(0.0));
+        self
+    }
+
+    /// Orders cells by the participant's row of a balanced Latin square
+    /// instead of shuffling
+    pub fn latin_square(mut self, participant: usize) -> Self {
+        self.latin_square_row = Some(participant);
+        self
+    }
+
+    pub fn build<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<TrialSequence> {
+        let cells = self.cells()?;
+        if cells.is_empty() {
+            bail!("Trial sequence has no conditions");
+        }
+
+        let trials = match self.latin_square_row {
+            Some(participant) => {
+                let order = balanced_latin_square_row(cells.len(), participant);
+                (0..self.length(cells.len()))
+                    .map(|i| cells[order[i % order.len()]].clone())
+                    .collect()
+            }
+            None => {
+                let counts = self.counts(&cells);
+                let order = match self.max_repeats {
+                    Some(max) => {
+                        let labels: Vec<usize> = cells
+                            .iter()
+                            .map(|c| {
+                                cells
+                                    .iter()
+                                    .take_while(|o| o.condition != c.condition)
+                                    .count()
+                            })
+                            .collect();
+                        constrained_order(&counts, &labels, max, rng)?
+                    }
+                    None => {
+                        let mut order: Vec<usize> = counts
+                            .iter()
+                            .enumerate()
+                            .flat_map(|(i, &n)| std::iter::repeat_n(i, n))
+                            .collect();
+                        order.shuffle(rng);
+                        order
+                    }
+                };
+                order.into_iter().map(|i| cells[i].clone()).collect()
+            }
+        };
+
+        Ok(TrialSequence { trials })
+    }
+
+    fn length(&self, cell_count: usize) -> usize {
+        self.total.unwrap_or(cell_count * self.repetitions.max(1))
+    }
+
+    /// Trials per cell, split by weight with largest remainders rounded up
+    fn counts(&self, cells: &[SequencedTrial]) -> Vec<usize> {
+        let length = self.length(cells.len());
+        let weights: Vec<f64> = cells
+            .iter()
+            .map(|c| self.weights.get(&c.condition).copied().unwrap_or(1.0))
+            .collect();
+        let sum: f64 = weights.iter().sum();
+        if sum <= 0.0 {
+            return vec![0; cells.len()];
+        }
+
+        let shares: Vec<f64> = weights.iter().map(|w| w / sum * length as f64).collect();
+        let mut counts: Vec<usize> = shares.iter().map(|s| s.floor() as usize).collect();
+        let mut by_remainder: Vec<usize> = (0..cells.len()).collect();
+        by_remainder.sort_by(|&a, &b| {
+            (shares[b] - shares[b].floor()).total_cmp(&(shares[a] - shares[a].floor()))
+        });
+        let assigned: usize = counts.iter().sum();
+        for &i in by_remainder.iter().take(length.saturating_sub(assigned)) {
+            counts[i] += 1;
+        }
+        counts
+    }
+
+    fn cells(&self) -> Result<Vec<SequencedTrial>> {
+        let mut cells = self.conditions.clone();
+        for cell in &mut cells {
+            if let Some(&ms) = self.response_windows.get(&cell.condition) {
+                cell.response_window_ms = Some(ms);
+            }
+        }
+        if self.factors.is_empty() {
+            return Ok(cells);
+        }
+        let Some(make) = &self.stimulus else {
+            bail!("Factorial design needs a stimulus builder");
+        };
+
+        let mut combos: Vec<Vec<&str>> = vec![Vec::new()];
+        for factor in &self.factors {
+            if factor.levels.is_empty() {
+                bail!("Factor '{}' has no levels", factor.name);
+            }
+            combos = combos
+                .into_iter()
+                .flat_map(|prefix| {
+                    factor.levels.iter().map(move |level| {
+                        let mut combo = prefix.clone();
+                        combo.push(level.as_str());
+                        combo
+                    })
+                })
+                .collect();
+        }
+
+        cells.extend(combos.into_iter().map(|levels| {
+            let condition = levels.join("_");
+            let response_window_ms = std::iter::once(condition.as_str())
+                .chain(levels.iter().copied())
+                .find_map(|key| self.response_windows.get(key).copied());
+            SequencedTrial {
+                response_window_ms,
+                ..SequencedTrial::new(condition, make(&levels))
+            }
+        }));
+        Ok(cells)
+    }
+}
+
+impl std::fmt::Debug for TrialSequenceBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrialSequenceBuilder")
+            .field("factors", &self.factors)
+            .field("conditions", &self.conditions)
+            .field("repetitions", &self.repetitions)
+            .field("total", &self.total)
+            .field("max_repeats", &self.max_repeats)
+            .field("latin_square_row", &self.latin_square_row)
+            .field("response_windows", &self.response_windows)
+            .field("weights", &self.weights)
+            .finish()
+    }
+}
+
+/// Row of a Williams balanced Latin square; odd `n` uses 2n rows
+/// (each row followed by its mirror) so first-order carryover stays balanced.
+pub fn balanced_latin_square_row(n: usize, participant: usize) -> Vec<usize> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut first = Vec::with_capacity(n);
+    let (mut lo, mut hi) = (0usize, n - 1);
+    for i in 0..n {
+        if i.is_multiple_of(2) {
+            first.push(lo);
+            lo += 1;
+        } else {
+            first.push(hi);
+            hi = hi.saturating_sub(1);
+        }
+    }
+
+    let rows = if n.is_multiple_of(2) { n } else { 2 * n };
+    let r = participant % rows;
+    let mut row: Vec<usize> = first.iter().map(|&c| (c + r % n) % n).collect();
+    if r >= n {
+        row.reverse();
+    }
+    row
+}
+
+/// Random order honouring a maximum run length, by weighted greedy
+/// sampling with restarts. Runs span cells sharing an entry in `labels`.
+fn constrained_order<R: Rng + ?Sized>(
+    counts: &[usize],
+    labels: &[usize],
+    max_run: usize,
+    rng: &mut R,
+) -> Result<Vec<usize>> {
+    const ATTEMPTS: usize = 1000;
+    if max_run == 0 {
+        bail!("Max repeats must be at least 1");
+    }
+    let total: usize = counts.iter().sum();
+
+    'attempt: for _ in 0..ATTEMPTS {
+        let mut left = counts.to_vec();
+        let mut order = Vec::with_capacity(total);
+        let mut run = 0usize;
+
+        for _ in 0..total {
+            let last = order.last().map(|&i: &usize| labels[i]);
+            let weight = |i: usize| {
+                if Some(labels[i]) == last && run >= max_run {
+                    0
+                } else {
+                    left[i]
+                }
+            };
+            let sum: usize = (0..left.len()).map(weight).sum();
+            if sum == 0 {
+                continue 'attempt;
+            }
+            let mut pick = rng.random_range(0..sum);
+            let mut chosen = 0;
+            for i in 0..left.len() {
+                let w = weight(i);
+                if pick < w {
+                    chosen = i;
+                    break;
+                }
+                pick -= w;
+            }
+
+            run = if Some(labels[chosen]) == last {
+                run + 1
+            } else {
+                1
+            };
+            left[chosen] -= 1;
+            order.push(chosen);
+        }
+        return Ok(order);
+    }
+
+    bail!(
+        "Could not satisfy max {} repeats in {} attempts",
+        max_run,
+        ATTEMPTS
+    )
+}
@@ -0,0 +1,115 @@
+use cogex_timing::Timer;
+use serde::{Deserialize, Serialize};
+
+/// Participant and session details collected before the experiment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub participant_id: String,
+    pub age: Option<u32>,
+    pub session: u32,
+    /// Unix time in seconds when the session started
+    pub started_at: u64,
+    /// Set when the session was ended before the last phase
+    #[serde(default)]
+    pub aborted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_reason: Option<String>,
+    /// Timer readings paired with UTC, at the start, periodically and on
+    /// saving, for aligning recordings from other devices
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clock_sync: Vec<ClockSample>,
+    /// Times the participant left the experiment window, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interruptions: Vec<Interruption>,
+}
+
+/// A stretch the participant spent away from the experiment window, in
+/// experiment timer nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interruption {
+    /// What the front end saw, e.g. `focus_lost` or `fullscreen_exited`
+    pub reason: String,
+    pub start_ns: u64,
+    /// When the participant pressed Space to carry on; `None` if they never
+    /// did
+    pub end_ns: Option<u64>,
+    /// Trial running when it happened, whose result is marked interrupted
+    pub trial_id: Option<usize>,
+}
+
+/// One reading of the experiment timer and the UTC wall clock, taken
+/// together.
+///
+/// Timer nanoseconds map to UTC by interpolating between samples, which
+/// also absorbs drift between the two clocks. The wall clock is only as
+/// good as the system's NTP sync.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClockSample {
+    /// Midpoint of the timer readings taken either side of the wall clock
+    pub timer_ns: u64,
+    /// Nanoseconds since the Unix epoch, UTC
+    pub utc_ns: i64,
+    /// Timer time the wall-clock read took; the pairing is no tighter
+    pub uncertainty_ns: u64,
+}
+
+impl ClockSample {
+    pub fn take(timer: &impl Timer<Timestamp = u64>) -> Self {
+        let before = timer.now();
+        let utc = chrono::Utc::now();
+        let after = timer.now();
+        Self {
+            timer_ns: before + (after - before) / 2,
+            utc_ns: utc.timestamp_nanos_opt().unwrap_or_default(),
+            uncertainty_ns: after - before,
+        }
+    }
+}
+
+impl SessionInfo {
+    pub fn new(participant_id: impl Into<String>, age: Option<u32>, session: u32) -> Self {
+        Self {
+            participant_id: participant_id.into(),
+            age,
+            session,
+            // Through chrono, whose clock also works in browsers
+            started_at: chrono::Utc::now().timestamp().max(0) as u64,
+            aborted: false,
+            abort_reason: None,
+            clock_sync: Vec::new(),
+            interruptions: Vec::new(),
+        }
+    }
+
+    /// Participant ID reduced to characters safe in file names
+    pub fn file_stem(&self) -> String {
+        let id: String = self
+            .participant_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}_s{}", id, self.session)
+    }
+
+    /// BIDS entity prefix, `sub-<id>_ses-<n>_task-<task>`
+    pub fn bids_stem(&self, task: &str) -> String {
+        let id = crate::bids::bids_label(&self.participant_id);
+        let id = if id.is_empty() {
+            "anonymous".to_string()
+        } else {
+            id
+        };
+        format!(
+            "sub-{}_ses-{}_task-{}",
+            id,
+            self.session,
+            crate::bids::bids_label(task)
+        )
+    }
+}
@@ -1,10 +1,32 @@
+use super::adaptive::{AdaptiveControl, AdaptiveParameter, AdaptiveProcedure};
+use super::calibration::CalibrationProgress;
+use super::checkpoint::{phase_at, phase_index, Checkpoint, ConfigSnapshot};
 use super::config::ExperimentConfig;
+use super::layout::Screen;
+use super::paradigm::{Paradigm, ParadigmRegistry};
+use super::provenance::SessionMetadata;
+use super::sequence::{SequencedTrial, TrialSequence};
+use super::session::{ClockSample, Interruption, SessionInfo};
 use super::trial::{Trial, TrialDurations, TrialTimestamps};
-use cogex_core::{ArrowDirection, Phase, Stimulus, StimulusType, TrialResult, TrialState};
-use cogex_timing::Timer;
-use rand::Rng;
-use std::marker::PhantomData;
+use anyhow::{bail, Result};
+use cogex_audio::AudioEngine;
+use cogex_core::{
+    ArrowDirection, DebriefSummary, FeedbackOutcome, Instructions, MouseButton, Phase, PhaseExit,
+    PhaseGraph, PresentationTimestamps, Primitive, Questionnaire, RawEvent, RawInput, ResponseKey,
+    ResponseMapping, Stimulus, StimulusType, TrialFeedback, TrialOutcome, TrialResult, TrialState,
+};
+use cogex_eyetrack::GazeMonitor;
+use cogex_sync::{EventMarker, Marker, MarkerOutlet, TriggerOutlet, TriggerPort};
+use cogex_timing::{CalibrationStats, Timer};
+use crossbeam_channel::Receiver;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::time::Duration;
+use tracing::{debug, info, info_span, warn, Span};
+
+/// Share of a rating scale one arrow key press moves the marker
+const RATING_STEP: f32 = 0.02;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExperimentEvent {
@@ -12,8 +34,56 @@ pub enum ExperimentEvent {
     CalibrationComplete,
     TrialComplete,
     PhaseComplete,
-    ResponseReceived,
+    ResponseReceived {
+        key: ResponseKey,
+        /// When the key went down, if stamped before the event was handled
+        timestamp_ns: Option<u64>,
+    },
+    MouseClicked {
+        position: (f32, f32),
+        button: MouseButton,
+        timestamp_ns: Option<u64>,
+    },
+    /// Speech onset from a voice key
+    VoiceOnset {
+        timestamp_ns: u64,
+    },
     Timeout,
+    /// Experimenter controls
+    Pause,
+    Resume,
+    /// Ends the session at once, e.g. when the participant withdraws
+    Abort {
+        reason: String,
+    },
+    SkipBlock,
+    InsertBreak,
+    /// Back a page on the instruction screens, or an item in the
+    /// questionnaire
+    PreviousPage,
+    /// Option picked for the current questionnaire item, counted from 0
+    QuestionnaireAnswer {
+        option: usize,
+    },
+    /// Labelled marker from an external controller, sent to the marker
+    /// outlets and logged for the events file; taken in any phase, paused or
+    /// not
+    Marker {
+        label: String,
+        /// When it arrived, if stamped before the event was handled
+        timestamp_ns: Option<u64>,
+    },
+    /// MRI scanner volume trigger; logged as a marker in any phase, paused
+    /// or not, and releases a run held for the first one
+    ScannerPulse {
+        timestamp_ns: u64,
+    },
+    /// The participant left the experiment window, e.g. it lost focus or
+    /// left fullscreen; the clock pauses and the return screen waits for
+    /// Space
+    Interrupt {
+        reason: String,
+    },
 }
 
 pub struct ExperimentStateMachine<P, S, T, R>
@@ -27,13 +97,71 @@ where
     pub timer: T,
     pub rng: R,
     pub config: ExperimentConfig<P>,
+    pub response_mapping: ResponseMapping<S>,
+    pub audio: Option<AudioEngine>,
+    pub markers: Vec<Box<dyn MarkerOutlet>>,
+    pub adaptive: Option<AdaptiveControl>,
+    /// Holds each trial in fixation until gaze is kept on target
+    pub gaze: Option<GazeMonitor>,
+    pub paradigm: Option<Box<dyn Paradigm>>,
+    pub instructions: Option<Instructions>,
+    pub questionnaire: Option<Questionnaire>,
+    /// Captured input, drained at the start of every update
+    pub input: Option<Receiver<RawInput>>,
+    pub practice_sequence: Option<TrialSequence>,
+    pub experiment_sequence: Option<TrialSequence>,
+    pub session: Option<SessionInfo>,
+    /// Successors of each phase; `Phase::next()` is followed without one
+    pub phase_graph: Option<PhaseGraph<P>>,
+    /// Phases entered so far, in order
+    visited: Vec<P>,
+    sequence: Option<TrialSequence>,
+    /// Trial id and state shown by the last presented frame
+    last_presented: Option<(usize, TrialState)>,
+    /// Presentation time of the latest frame
+    last_frame_ns: Option<u64>,
+    paused_at: Option<u64>,
+    /// Start of the current interruption, and whether returning from it
+    /// resumes the clock, i.e. no experimenter pause was already on
+    interrupted: Option<(u64, bool)>,
+    /// Why the session was ended early
+    aborted: Option<String>,
+    /// Markers logged outside trials, in order
+    logged_markers: Vec<Marker>,
+    /// Scanner pulses received so far
+    scanner_volumes: usize,
+    /// The pulse event onsets are timed from
+    scanner_zero_ns: Option<u64>,
+    /// The experiment phase is held, paused, until the first pulse
+    waiting_for_scanner: bool,
+    /// When the current trial ran out, which the inter-trial interval is
+    /// timed from
+    completed_ns: Option<u64>,
+    /// End of the rest enforced on the current break screen
+    rest_until_ns: Option<u64>,
+    /// Window the configured layouts place stimuli on
+    screen: Screen,
     pub current: Option<Trial<S, T::Timestamp>>,
     pub trial_number: usize,
     pub phase_trial_number: usize,
     pub results: Vec<TrialResult<S>>,
     pub calibrated: bool,
+    /// Set on the calibration phase's first update
+    calibration_progress: Option<CalibrationProgress>,
+    /// Frame timing as measured when calibration ended
+    calibration: Option<CalibrationStats>,
     pub safe_margin_ns: u64,
+    /// Trial deadlines fire this much early, so one falling on a frame
+    /// boundary is taken on the update before that frame is drawn
+    pub onset_lead_ns: u64,
     pub awaiting_input: bool,
+    pub current_block: usize,
+    pub block_trial_number: usize,
+    /// Practice repeats run so far because of the accuracy criterion
+    pub practice_attempt: usize,
+    /// Log spans of the current phase and trial
+    phase_span: Span,
+    trial_span: Span,
 }
 
 impl<P, T, R> ExperimentStateMachine<P, StimulusType, T, R>
@@ -48,102 +176,418 @@ where
             timer,
             rng,
             config,
+            response_mapping: ResponseMapping::standard(),
+            audio: None,
+            markers: Vec::new(),
+            adaptive: None,
+            gaze: None,
+            paradigm: None,
+            instructions: None,
+            questionnaire: None,
+            input: None,
+            practice_sequence: None,
+            experiment_sequence: None,
+            session: None,
+            phase_graph: None,
+            visited: vec![P::default()],
+            sequence: None,
+            last_presented: None,
+            last_frame_ns: None,
+            paused_at: None,
+            interrupted: None,
+            aborted: None,
+            logged_markers: Vec::new(),
+            scanner_volumes: 0,
+            scanner_zero_ns: None,
+            waiting_for_scanner: false,
+            completed_ns: None,
+            rest_until_ns: None,
+            screen: Screen::default(),
             current: None,
             trial_number: 0,
             phase_trial_number: 0,
             results: Vec::new(),
             calibrated: false,
+            calibration_progress: None,
+            calibration: None,
             safe_margin_ns: 0,
+            onset_lead_ns: 0,
             awaiting_input: true,
+            current_block: 0,
+            block_trial_number: 0,
+            practice_attempt: 0,
+            phase_span: phase_span(&P::default()),
+            trial_span: Span::none(),
+        }
+    }
+
+    pub fn with_screen(mut self, screen: Screen) -> Self {
+        self.set_screen(screen);
+        self
+    }
+
+    /// Lays out later trials' stimuli for a window of this size
+    pub fn set_screen(&mut self, screen: Screen) {
+        self.screen = screen;
+    }
+
+    /// Replaces the stimulus → key mapping used for scoring
+    pub fn with_response_mapping(mut self, mapping: ResponseMapping<StimulusType>) -> Self {
+        self.response_mapping = mapping;
+        self
+    }
+
+    /// Enables playback of auditory stimuli at stimulus onset
+    pub fn with_audio(mut self, engine: AudioEngine) -> Self {
+        self.audio = Some(engine);
+        self
+    }
+
+    /// Sends stimulus onset, response and feedback markers to `outlet`
+    pub fn with_markers(mut self, outlet: Box<dyn MarkerOutlet>) -> Self {
+        self.markers.push(outlet);
+        self
+    }
+
+    /// Sends the configured trigger codes to a hardware port
+    pub fn with_triggers(self, port: Box<dyn TriggerPort>) -> Self {
+        let outlet = TriggerOutlet::new(port, self.config.triggers.clone());
+        self.with_markers(Box::new(outlet))
+    }
+
+    /// Makes trial onset gaze-contingent: after the fixation period the
+    /// trial waits until the monitor's gate reports fixation held
+    pub fn with_gaze_contingency(mut self, monitor: GazeMonitor) -> Self {
+        self.gaze = Some(monitor);
+        self
+    }
+
+    /// Drives `parameter` on experiment-phase trials from an adaptive procedure
+    pub fn with_adaptive(
+        mut self,
+        procedure: impl AdaptiveProcedure + 'static,
+        parameter: AdaptiveParameter,
+    ) -> Self {
+        self.adaptive = Some(AdaptiveControl {
+            procedure: Box::new(procedure),
+            parameter,
+        });
+        self
+    }
+
+    /// Runs a ready-made task: its sequences replace the practice and
+    /// experiment trials, and its mapping scores responses
+    pub fn with_paradigm(self, paradigm: impl Paradigm + 'static) -> Result<Self> {
+        self.with_boxed_paradigm(Box::new(paradigm))
+    }
+
+    pub fn with_boxed_paradigm(mut self, paradigm: Box<dyn Paradigm>) -> Result<Self> {
+        self.practice_sequence =
+            Some(paradigm.sequence(self.config.practice_trials, &mut self.rng)?);
+        self.experiment_sequence =
+            Some(paradigm.sequence(self.config.total_experiment_trials(), &mut self.rng)?);
+        self.response_mapping = paradigm.response_mapping();
+        self.paradigm = Some(paradigm);
+        Ok(self)
+    }
+
+    /// Applies the paradigm registered as `name`, as given on the command
+    /// line
+    pub fn with_named_paradigm(self, registry: &ParadigmRegistry<P>, name: &str) -> Result<Self> {
+        match registry.create(name, &self.config)? {
+            Some(paradigm) => self.with_boxed_paradigm(paradigm),
+            None => Ok(self),
         }
     }
 
+    /// Pages shown in the instructions phase; without any the phase is
+    /// skipped
+    pub fn with_instructions(mut self, instructions: Instructions) -> Self {
+        self.instructions = Some(instructions);
+        self
+    }
+
+    /// Items asked in the questionnaire phase; without any the phase is
+    /// skipped
+    pub fn with_questionnaire(mut self, questionnaire: Questionnaire) -> Self {
+        self.questionnaire = Some(questionnaire);
+        self
+    }
+
+    /// Takes participant input from `receiver` instead of direct events
+    pub fn with_input(mut self, receiver: Receiver<RawInput>) -> Self {
+        self.input = Some(receiver);
+        self
+    }
+
+    /// Uses a pre-built sequence for the practice phase
+    pub fn with_practice_sequence(mut self, sequence: TrialSequence) -> Self {
+        self.practice_sequence = Some(sequence);
+        self
+    }
+
+    /// Uses a pre-built sequence for the experiment phase. Blocks that list
+    /// their own conditions take precedence for their trials.
+    pub fn with_experiment_sequence(mut self, sequence: TrialSequence) -> Self {
+        self.experiment_sequence = Some(sequence);
+        self
+    }
+
+    /// Routes phase transitions through `graph` instead of `Phase::next()`
+    pub fn with_phase_graph(mut self, graph: PhaseGraph<P>) -> Self {
+        self.phase_graph = Some(graph);
+        self
+    }
+
     pub fn advance_phase(&mut self) -> bool {
-        if let Some(next) = self.phase.next() {
+        if let Some(next) = self.next_phase() {
             self.phase = next;
+            self.visited.push(next);
+            self.phase_span = phase_span(&self.phase);
             self.phase_trial_number = 0;
+            self.current_block = 0;
+            self.block_trial_number = 0;
             self.awaiting_input = self.phase.is_welcome();
+            self.prepare_sequence();
+            if self.is_final_phase() {
+                self.warn_dropped_frames();
+            }
 
+            if self.phase.is_instructions() {
+                match &mut self.instructions {
+                    Some(instructions) if instructions.page_count() > 0 => instructions.reset(),
+                    _ => return self.advance_phase(),
+                }
+            }
+            if self.phase.is_questionnaire() {
+                match &mut self.questionnaire {
+                    Some(questionnaire) if questionnaire.item_count() > 0 => questionnaire.reset(),
+                    _ => return self.advance_phase(),
+                }
+            }
             true
         } else {
             false
         }
     }
 
+    /// Whether the calibration phase has measured long enough, per
+    /// `config.calibration`
+    fn calibration_finished(&mut self) -> bool {
+        let now_ns = self.timer.now();
+        let frames = self.timer.frame_count();
+        let jitter_ns = self.timer.calibration_stats().jitter_ns;
+        self.calibration_progress
+            .get_or_insert_with(|| CalibrationProgress::new(now_ns, frames))
+            .check(&self.config.calibration, now_ns, frames, jitter_ns)
+    }
+
     pub fn apply_calibration(&mut self) {
-        let stats = self.timer.calibration_stats();
+        let mut stats = self.timer.calibration_stats();
+        if let Some(progress) = self.calibration_progress.take() {
+            stats.converged =
+                progress.converged(&self.config.calibration, stats.frames, stats.jitter_ns);
+            stats.duration_ns = Some(progress.elapsed_ns(self.timer.now()));
+            stats.frames = progress.measured(stats.frames);
+        }
         self.safe_margin_ns = (stats.jitter_ns * 3.0) as u64;
         self.calibrated = true;
         // Add margin to stimulus duration for safety
         // self.config.stimulus_duration_ms += self.safe_margin_ns / 1_000_000;
-        println!(
+        info!(
             "Calibration: {:.3} ms/frame, {:.1} Hz, jitter {:.3} ms, safe margin {} ns",
             stats.average_frame_time_ns / 1_000_000.0,
             stats.effective_fps,
             stats.jitter_ns / 1_000_000.0,
             self.safe_margin_ns,
         );
+        if let Some(duration_ns) = stats.duration_ns {
+            info!(
+                "Calibration measured {} frames over {:.2} s, jitter {}",
+                stats.frames,
+                duration_ns as f64 / 1e9,
+                match stats.converged {
+                    Some(true) => "settled",
+                    Some(false) => "still moving at the time limit",
+                    None => "not checked",
+                }
+            );
+        }
+        info!(
+            "Input latency estimate: {:.3} ms (key scan {:.3} ms compensated, event dispatch {:.3} ms)",
+            self.estimated_input_latency().as_secs_f64() * 1e3,
+            self.config.key_scan_ms,
+            stats.average_frame_time_ns / 2_000_000.0,
+        );
+        if let Some(caveat) = refresh_mismatch(&stats) {
+            warn!("{}", caveat);
+        }
+        self.calibration = Some(stats);
+    }
+
+    /// Expected delay from a key going down to its recorded timestamp: the
+    /// keyboard scan interval plus, for events queued behind a blocking
+    /// present, half a frame on average. Key-down stamps already subtract
+    /// `config.key_scan_ms`, so only the dispatch part remains uncorrected.
+    pub fn estimated_input_latency(&self) -> Duration {
+        let frame_ns = self.timer.calibration_stats().average_frame_time_ns;
+        Duration::from_secs_f64((self.config.key_scan_ms * 1e6 + frame_ns / 2.0) / 1e9)
     }
 
     pub fn start_trial(&mut self) {
         let id = self.trial_number;
-        let stim = self.generate_stimulus();
-        let pos = self.generate_position();
+        let SequencedTrial {
+            condition,
+            stimulus: stim,
+            response_window_ms,
+            expected_key,
+            position,
+            array,
+            cue,
+            subpixel,
+        } = self.next_sequenced_trial();
+        let (pos, array) = self.place_display(&condition, &stim, position, array);
         let fixation_ms = self
             .rng
             .random_range(self.config.fixation_range_ms.0..=self.config.fixation_range_ms.1);
-        let now_ns = self.timer.now() as u64;
+        let (cue_ms, cue_target_soa_ms) = match cue.as_ref().and_then(|c| c.frames) {
+            // Nominal at the calibrated refresh; the frames themselves are
+            // counted as they are presented
+            Some(frames) => {
+                let frame_ms = self.timer.calibration_stats().average_frame_time_ns / 1e6;
+                (
+                    (frames.cue as f64 * frame_ms).round() as u64,
+                    ((frames.cue + frames.mask) as f64 * frame_ms).round() as u64,
+                )
+            }
+            None => (
+                self.config.cue_duration_ms,
+                self.config.cue_target_soa.sample(&mut self.rng),
+            ),
+        };
+        let inter_trial_interval_ms = self.config.inter_trial_interval.sample(&mut self.rng);
+        let now_ns = self.timer.now();
+
+        // Decode/synthesize during fixation so onset only starts playback
+        if let Some(audio) = &mut self.audio
+            && let Err(e) = audio.preload(&stim)
+        {
+            warn!("Failed to preload audio stimulus: {}", e);
+        }
 
-        let trial = Trial {
+        let mut trial = Trial {
             id,
+            condition,
             stimulus: stim,
             position: pos,
+            array,
+            subpixel,
             durations: TrialDurations {
                 fixation_ms,
                 stimulus_ms: self.config.stimulus_duration_ms,
-                response_window_ms: self.config.response_window_ms,
+                response_window_ms: response_window_ms.unwrap_or(self.config.response_window_ms),
                 feedback_ms: self.config.feedback_duration_ms,
+                cue_ms,
+                cue_target_soa_ms,
+                inter_trial_interval_ms,
             },
             timestamps: TrialTimestamps {
                 start: now_ns,
                 fixation_start: now_ns,
+                cue_start: None,
                 stimulus_start: None,
                 response: None,
+                voice_onset: None,
+                fixation_presented: None,
+                cue_presented: None,
+                cue_offset_presented: None,
+                stimulus_presented: None,
+                stimulus_offset_presented: None,
+                feedback_presented: None,
             },
             state: TrialState::Fixation,
+            response_key: None,
+            click_position: None,
+            intensity: None,
+            cue,
+            expected_key,
+            dropped_frames: 0,
+            cue_frames: 0,
+            gaze_wait_ns: 0,
+            timed_out: false,
+            rating: None,
+            rating_trajectory: Vec::new(),
+            invalid_keys: Vec::new(),
+            interrupted: false,
         };
 
+        if self.phase.is_experiment()
+            && let Some(adaptive) = &self.adaptive
+        {
+            let intensity = adaptive.procedure.intensity();
+            adaptive.parameter.apply(intensity, &mut trial);
+            trial.intensity = Some(intensity);
+        }
+
+        if let Some(gaze) = &mut self.gaze {
+            gaze.reset();
+        }
+        self.trial_span = info_span!(
+            parent: &self.phase_span,
+            "trial",
+            id,
+            condition = %trial.condition
+        );
         self.current = Some(trial);
-        println!("Trial {} started at {:?} ns", id, now_ns);
+        let _span = self.trial_span.clone().entered();
+        info!("Trial {} started at {:?} ns", id, now_ns);
     }
 
     pub fn update(&mut self) -> Vec<ExperimentEvent> {
+        let _span = self.span().entered();
+        self.sample_clock_if_due();
+        // Responses are dated by their capture stamps, not by this update
+        let pending: Vec<RawInput> = self
+            .input
+            .as_ref()
+            .map(|input| input.try_iter().collect())
+            .unwrap_or_default();
+        for input in pending {
+            self.handle_raw_input(input);
+        }
+
         let mut events = Vec::new();
+        if self.is_paused() || self.is_aborted() {
+            return events;
+        }
 
         // Handle phase-specific logic
         match self.phase {
-            phase if phase.is_welcome() => {
-                // Waiting for space press - no automatic updates
+            phase if phase.is_welcome() || phase.is_instructions() || phase.is_questionnaire() => {
+                // Waiting for the participant - no automatic updates
                 return events;
             }
-            phase if phase.requires_calibration() => {
-                if self.timer.frame_count() >= 120 && !self.calibrated {
-                    events.push(ExperimentEvent::CalibrationComplete);
-                    println!("hello from state machine update fn to stop calibration")
-                }
+            phase if phase.requires_calibration() && !self.calibrated => {
+                events.extend(
+                    self.calibration_finished()
+                        .then_some(ExperimentEvent::CalibrationComplete),
+                );
             }
             phase if phase.is_practice() || phase.is_experiment() => {
                 // Handle trial-level updates
                 self.update_trial(&mut events);
 
                 // Check if phase is complete
-                let target_trials = if phase.is_practice() {
-                    self.config.practice_trials
-                } else {
-                    self.config.experiment_trials
-                };
+                let target_trials = self.config.trials_for(&phase);
+
+                let converged = phase.is_experiment()
+                    && self.current.is_none()
+                    && self
+                        .adaptive
+                        .as_ref()
+                        .is_some_and(|a| a.procedure.is_finished());
 
-                if self.phase_trial_number >= target_trials {
+                if self.phase_trial_number >= target_trials || converged {
                     events.push(ExperimentEvent::PhaseComplete);
                 }
             }
@@ -156,16 +600,115 @@ where
     }
 
     pub fn handle_event(&mut self, event: ExperimentEvent) -> bool {
+        let _span = self.span().entered();
+        if self.is_aborted()
+            || (self.is_paused()
+                && !matches!(
+                    event,
+                    ExperimentEvent::Resume
+                        | ExperimentEvent::Abort { .. }
+                        | ExperimentEvent::Marker { .. }
+                        | ExperimentEvent::ScannerPulse { .. }
+                        | ExperimentEvent::Interrupt { .. }
+                )
+                && !(self.is_interrupted() && ExperimentEvent::SpacePressed == event))
+        {
+            return false;
+        }
+
         match (&self.phase, &event) {
+            (_, ExperimentEvent::Interrupt { reason }) => self.interrupt(reason.clone()),
+            (_, ExperimentEvent::SpacePressed) if self.is_interrupted() => {
+                self.return_from_interruption()
+            }
+            // The experimenter carries on for the participant
+            (_, ExperimentEvent::Resume) if self.is_interrupted() => {
+                self.interrupted = self.interrupted.map(|(start_ns, _)| (start_ns, true));
+                self.return_from_interruption()
+            }
+            (_, ExperimentEvent::Pause) => self.pause(),
+            (_, ExperimentEvent::Resume) => self.resume(),
+            (_, ExperimentEvent::Abort { reason }) => {
+                self.abort(reason.clone());
+                true
+            }
+            (
+                _,
+                ExperimentEvent::Marker {
+                    label,
+                    timestamp_ns,
+                },
+            ) => {
+                let timestamp_ns = timestamp_ns.unwrap_or_else(|| self.timer.now());
+                info!("Marker {:?} at {}", label, timestamp_ns);
+                self.log_marker(
+                    EventMarker::External {
+                        label: label.clone(),
+                    },
+                    timestamp_ns,
+                );
+                true
+            }
+            (_, ExperimentEvent::ScannerPulse { timestamp_ns }) => {
+                self.scanner_pulse(*timestamp_ns);
+                true
+            }
+            (phase, ExperimentEvent::SkipBlock) if phase.is_practice() || phase.is_experiment() => {
+                self.skip_block()
+            }
+            (phase, ExperimentEvent::InsertBreak)
+                if phase.is_practice() || phase.is_experiment() =>
+            {
+                self.insert_break()
+            }
+
             // Welcome phase - space advances to calibration
             (phase, ExperimentEvent::SpacePressed) if phase.is_welcome() => {
-                if self.advance_phase() {
+                let advanced = self.advance_phase();
+                if advanced {
                     self.awaiting_input = false;
-                    true
-                } else {
-                    false
                 }
+                advanced
+            }
+
+            // Instructions - space turns the page, then leaves the phase
+            (phase, ExperimentEvent::SpacePressed) if phase.is_instructions() => {
+                if self.instructions.as_mut().is_some_and(|i| i.next_page()) {
+                    return true;
+                }
+                let advanced = self.advance_phase();
+                if advanced {
+                    self.awaiting_input = false;
+                }
+                advanced
+            }
+            (phase, ExperimentEvent::PreviousPage) if phase.is_instructions() => self
+                .instructions
+                .as_mut()
+                .is_some_and(|i| i.previous_page()),
+
+            // Questionnaire - an answer moves to the next item, the last leaves the phase
+            (phase, ExperimentEvent::QuestionnaireAnswer { option })
+                if phase.is_questionnaire() =>
+            {
+                let Some(questionnaire) = &mut self.questionnaire else {
+                    return false;
+                };
+                if !questionnaire.answer(*option) {
+                    return false;
+                }
+                if !questionnaire.is_complete() {
+                    return true;
+                }
+                info!("Questionnaire complete");
+                self.advance_phase()
             }
+            (phase, ExperimentEvent::PreviousPage) if phase.is_questionnaire() => {
+                self.questionnaire.as_mut().is_some_and(|q| q.previous())
+            }
+
+            // Break screen - space starts the next block
+            (_, ExperimentEvent::SpacePressed) if self.is_on_break() => self.resume_from_break(),
 
             // Calibration complete - advance to practice and start first trial
             (phase, ExperimentEvent::CalibrationComplete) if phase.requires_calibration() => {
@@ -173,21 +716,106 @@ where
                 if self.advance_phase() {
                     self.phase_trial_number = 0;
                     self.start_trial();
+                    self.count_down_block();
+                    self.hold_for_scanner();
                     true
                 } else {
                     false
                 }
             }
 
+            // Rating scale - arrows move the marker and Space confirms it
+            (phase, ExperimentEvent::ResponseReceived { key, timestamp_ns })
+                if (phase.is_practice() || phase.is_experiment()) && self.rating_open() =>
+            {
+                self.handle_rating_key(*key, *timestamp_ns)
+            }
+            (
+                phase,
+                ExperimentEvent::MouseClicked {
+                    position,
+                    timestamp_ns,
+                    ..
+                },
+            ) if (phase.is_practice() || phase.is_experiment()) && self.rating_open() => {
+                let timestamp_ns = timestamp_ns.unwrap_or_else(|| self.timer.now());
+                let Some(trial) = &mut self.current else {
+                    return false;
+                };
+                match trial.stimulus.rating_at(position.0 - trial.position.0) {
+                    Some(rating) => {
+                        move_rating(trial, rating, timestamp_ns);
+                        true
+                    }
+                    None => false,
+                }
+            }
+
             // Response received during response window
-            (phase, ExperimentEvent::ResponseReceived)
+            (phase, ExperimentEvent::ResponseReceived { key, timestamp_ns })
+                if (phase.is_practice() || phase.is_experiment())
+                    && self.accepts_key(*key)
+                    && self
+                        .current
+                        .as_ref()
+                        .is_some_and(|t| t.state.accepts_response()) =>
+            {
+                self.record_response_at(*key, *timestamp_ns);
+                true
+            }
+
+            // Mouse click during response window
+            (
+                phase,
+                ExperimentEvent::MouseClicked {
+                    position,
+                    button,
+                    timestamp_ns,
+                },
+            ) if (phase.is_practice() || phase.is_experiment())
+                && self.accepts_key(ResponseKey::Mouse(*button))
+                && self
+                    .current
+                    .as_ref()
+                    .is_some_and(|t| t.state.accepts_response()) =>
+            {
+                self.record_click_at(*position, *button, *timestamp_ns);
+                true
+            }
+
+            // Anything else pressed in the response window - logged, not scored
+            (phase, ExperimentEvent::ResponseReceived { key, .. })
+                if (phase.is_practice() || phase.is_experiment()) =>
+            {
+                self.record_invalid_key(*key);
+                false
+            }
+            (phase, ExperimentEvent::MouseClicked { button, .. })
+                if (phase.is_practice() || phase.is_experiment()) =>
+            {
+                self.record_invalid_key(ResponseKey::Mouse(*button));
+                false
+            }
+
+            // Speech onset while the stimulus or feedback is up
+            (phase, ExperimentEvent::VoiceOnset { timestamp_ns })
+                if (phase.is_practice() || phase.is_experiment())
+                    && self.current.as_ref().is_some_and(|t| {
+                        t.state.accepts_response() || TrialState::Feedback == t.state
+                    }) =>
+            {
+                self.record_voice_onset(*timestamp_ns)
+            }
+
+            // Response window elapsed without a response
+            (phase, ExperimentEvent::Timeout)
                 if (phase.is_practice() || phase.is_experiment())
                     && self
                         .current
                         .as_ref()
-                        .map_or(false, |t| TrialState::Response == t.state) =>
+                        .is_some_and(|t| t.state.accepts_response()) =>
             {
-                self.record_response();
+                self.record_timeout();
                 true
             }
 
@@ -201,18 +829,29 @@ where
 
             // Phase completed - advance to next phase
             (_, ExperimentEvent::PhaseComplete) => {
-                if self.advance_phase() {
-                    self.phase_trial_number = 0;
-
-                    // Start trial if entering practice/experiment phase
-                    if self.phase.is_practice() || self.phase.is_experiment() {
-                        self.start_trial();
-                    }
-                    true
-                } else {
+                if self.phase.is_practice() && self.repeat_practice() {
+                    return true;
+                }
+                let leaving_experiment = self.phase.is_experiment();
+                if !self.advance_phase() {
                     // Experiment is complete
-                    false
+                    return false;
+                }
+                self.phase_trial_number = 0;
+                if leaving_experiment {
+                    self.report_adaptive();
+                    for line in self.debrief_summary() {
+                        info!("{}", line);
+                    }
                 }
+
+                // Start trial if entering practice/experiment phase
+                if self.phase.is_practice() || self.phase.is_experiment() {
+                    self.start_trial();
+                    self.count_down_block();
+                    self.hold_for_scanner();
+                }
+                true
             }
 
             _ => false, // Event not handled
@@ -224,76 +863,353 @@ where
             return;
         }
 
+        // Shifting `now` forward moves every deadline below earlier by the lead
         let now_ns = self.timer.now();
+        let due_ns = now_ns + self.onset_lead_ns;
+        let gaze_held = self.gaze.as_mut().is_none_or(GazeMonitor::poll);
         if let Some(trial) = &mut self.current {
+            let mut onset = false;
             match trial.state {
+                TrialState::Countdown => {
+                    let countdown_ns = self.config.widgets.block_countdown_s as u64 * 1_000_000_000;
+                    if due_ns - trial.timestamps.start >= countdown_ns {
+                        trial.state = TrialState::Fixation;
+                        trial.timestamps.start = now_ns;
+                        trial.timestamps.fixation_start = now_ns;
+                        debug!("Countdown ended at {}", now_ns);
+                    }
+                }
                 TrialState::Fixation => {
-                    if now_ns - trial.timestamps.fixation_start
-                        >= trial.durations.fixation_ms * 1_000_000
-                    {
-                        trial.state = TrialState::Response;
-                        trial.timestamps.stimulus_start = Some(now_ns);
-                        println!("Stimulus started at {}", now_ns);
-
-                        println!("Response window opened at {}", now_ns);
+                    let fixation_ns = trial.durations.fixation_ms * 1_000_000;
+                    let fixated_ns = due_ns - trial.timestamps.fixation_start;
+                    if fixated_ns >= fixation_ns && gaze_held {
+                        if self.gaze.is_some() {
+                            trial.gaze_wait_ns = fixated_ns - fixation_ns;
+                            if trial.gaze_wait_ns >= 1_000_000 {
+                                info!(
+                                    "Fixation held after waiting {:.1} ms",
+                                    trial.gaze_wait_ns as f64 / 1_000_000.0
+                                );
+                            }
+                        }
+                        if trial.cue.is_some() {
+                            trial.state = TrialState::Cue;
+                            trial.timestamps.cue_start = Some(now_ns);
+                            debug!("Cue started at {}", now_ns);
+                        } else {
+                            onset = true;
+                        }
                     }
                 }
-                TrialState::Stimulus => {
-                    // if let Some(start_ns) = trial.timestamps.stimulus_start {
-                    //     if now_ns - start_ns >= dur_ns {
-                    //         trial.state = TrialState::Response;
-                    //         println!("Response window opened at {}", now_ns);
-                    //     }
-                    // }
-                    unreachable!("Should transition directly from Fixation to Response")
+                TrialState::Cue => {
+                    onset = match trial.cue.as_ref().and_then(|c| c.frames) {
+                        // The target replaces the mask's last refresh
+                        Some(frames) => trial.cue_frames >= frames.cue + frames.mask,
+                        None => trial.timestamps.cue_start.is_some_and(|cue_ns| {
+                            due_ns - cue_ns >= trial.durations.cue_target_soa_ms * 1_000_000
+                        }),
+                    };
                 }
-                TrialState::Response => {
-                    let total_ns = (trial.durations.stimulus_ms
-                        + trial.durations.response_window_ms)
-                        * 1_000_000
+                TrialState::Stimulus | TrialState::Response => {
+                    // The window runs from onset, so it stays open past the offset
+                    let offset_ns = trial.durations.stimulus_ms * 1_000_000;
+                    let total_ns = offset_ns
+                        + trial.durations.response_window_ms * 1_000_000
                         + self.safe_margin_ns;
                     if let Some(start_ns) = trial.timestamps.stimulus_start {
-                        if now_ns - start_ns >= total_ns {
-                            // Timeout - no response received
-                            events.push(ExperimentEvent::TrialComplete);
+                        let shown_ns = due_ns - start_ns;
+                        // A rating scale stays up until it is confirmed
+                        if TrialState::Stimulus == trial.state
+                            && shown_ns >= offset_ns
+                            && !trial.stimulus.is_rating_scale()
+                        {
+                            trial.state = TrialState::Response;
+                            debug!("Stimulus offset at {}", now_ns);
+                        }
+                        if shown_ns >= total_ns {
+                            events.push(ExperimentEvent::Timeout);
                         }
                     }
                 }
                 TrialState::Feedback => {
+                    let soa_ms = trial
+                        .cue
+                        .as_ref()
+                        .map_or(0, |_| trial.durations.cue_target_soa_ms);
                     let total_ns = (trial.durations.fixation_ms
+                        + soa_ms
                         + trial.durations.stimulus_ms
                         + trial.durations.response_window_ms
                         + trial.durations.feedback_ms)
                         * 1_000_000
+                        + trial.gaze_wait_ns
                         + self.safe_margin_ns;
-                    if now_ns - trial.timestamps.start >= total_ns {
+                    if due_ns - trial.timestamps.start >= total_ns {
                         trial.state = TrialState::Complete;
-                        events.push(ExperimentEvent::TrialComplete);
+                        self.completed_ns = Some(now_ns);
+                        if let (Some(audio), Some(stimulus)) =
+                            (&mut self.audio, self.config.iti_display.stimulus())
+                            && let Err(e) = audio.play_stimulus(stimulus)
+                        {
+                            warn!("Failed to play the inter-trial interval's sound: {}", e);
+                        }
                     }
                 }
                 TrialState::Complete => {
-                    // Already complete
+                    // Waiting out the inter-trial interval, checked below
+                }
+                TrialState::Break => {
+                    // Waiting for space press before the next block
+                }
+            }
+
+            // The next trial starts once the inter-trial interval runs out
+            if TrialState::Complete == trial.state {
+                let iti_ns = trial.durations.inter_trial_interval_ms * 1_000_000;
+                if self.completed_ns.is_none_or(|at| due_ns >= at + iti_ns) {
+                    events.push(ExperimentEvent::TrialComplete);
+                }
+            }
+
+            if onset {
+                trial.state = TrialState::Stimulus;
+                trial.timestamps.stimulus_start = Some(now_ns);
+                debug!("Stimulus started at {}", now_ns);
+                if let Some(audio) = &mut self.audio
+                    && let Err(e) = audio.play_stimulus(&trial.stimulus)
+                {
+                    warn!("Failed to play audio stimulus: {}", e);
                 }
+                push_marker(
+                    &mut self.markers,
+                    EventMarker::StimulusOnset {
+                        trial_id: trial.id,
+                        condition: trial.condition.clone(),
+                    },
+                    now_ns,
+                );
+
+                debug!("Response window opened at {}", now_ns);
             }
         }
     }
 
     /// Records a response for the current trial during the Response state
-    pub fn record_response(&mut self) {
-        if let Some(trial) = &mut self.current {
-            if TrialState::Response == trial.state {
-                let now_ns = self.timer.now();
-                trial.timestamps.response = Some(now_ns);
-                trial.state = TrialState::Feedback;
-
-                let rt = now_ns - trial.timestamps.stimulus_start.unwrap_or(now_ns);
-                println!(
-                    "Response recorded at {}, RT = {:.3} ms",
-                    now_ns,
-                    rt as f64 / 1_000_000.0
+    pub fn record_response(&mut self, key: ResponseKey) {
+        self.record_response_at(key, None);
+    }
+
+    /// Records a response that went down at `timestamp_ns`, or now without
+    /// one. A key pressed before the stimulus onset is ignored as anticipatory.
+    pub fn record_response_at(&mut self, key: ResponseKey, timestamp_ns: Option<u64>) {
+        if let Some(trial) = &mut self.current
+            && trial.state.accepts_response()
+        {
+            let now_ns = self.timer.now();
+            let pressed_ns = timestamp_ns.map_or(now_ns, |t| t.min(now_ns));
+            let onset_ns = trial.timestamps.stimulus_start.unwrap_or(pressed_ns);
+            if pressed_ns < onset_ns {
+                info!(
+                    "Response {:?} went down {:.3} ms before onset, ignored",
+                    key,
+                    (onset_ns - pressed_ns) as f64 / 1_000_000.0
                 );
+                return;
+            }
+            trial.timestamps.response = Some(pressed_ns);
+            trial.response_key = Some(key);
+            trial.state = TrialState::Feedback;
+
+            let rt = pressed_ns - onset_ns;
+            info!(
+                "Response {:?} recorded at {} ({:.3} ms before handling), RT = {:.3} ms",
+                key,
+                pressed_ns,
+                (now_ns - pressed_ns) as f64 / 1_000_000.0,
+                rt as f64 / 1_000_000.0
+            );
+
+            let trial_id = trial.id;
+            let correct = score(&self.response_mapping, self.config.click_radius, trial);
+            push_marker(
+                &mut self.markers,
+                EventMarker::Response { trial_id, key },
+                pressed_ns,
+            );
+            push_marker(
+                &mut self.markers,
+                EventMarker::Feedback { trial_id, correct },
+                now_ns,
+            );
+        }
+    }
+
+    /// Whether `key` counts as a response on the current trial: it is in the
+    /// configured key set, or without one, in the response mapping
+    fn accepts_key(&self, key: ResponseKey) -> bool {
+        let condition = self.current.as_ref().map_or("", |t| t.condition.as_str());
+        match self.config.response_keys.keys_for(&self.phase, condition) {
+            Some(keys) => keys.contains(&key),
+            None => self.response_mapping.accepts(key),
+        }
+    }
+
+    /// Notes a key outside the response set pressed while a response was
+    /// expected. Keys the mapping ignores anyway pass silently unless a key
+    /// set is configured.
+    fn record_invalid_key(&mut self, key: ResponseKey) {
+        let configured = self.current.as_ref().is_some_and(|t| {
+            self.config
+                .response_keys
+                .keys_for(&self.phase, &t.condition)
+                .is_some()
+        });
+        if let Some(trial) = &mut self.current
+            && trial.state.accepts_response()
+            && configured
+        {
+            warn!("Trial {}: invalid key {:?}", trial.id, key);
+            trial.invalid_keys.push(key);
+        }
+    }
+
+    /// Whether the current trial is a rating scale taking input
+    fn rating_open(&self) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|t| t.stimulus.is_rating_scale() && t.state.accepts_response())
+    }
+
+    /// Steps the rating marker with the arrow keys, starting from the middle,
+    /// and records the rating once one is placed and Space is pressed
+    fn handle_rating_key(&mut self, key: ResponseKey, timestamp_ns: Option<u64>) -> bool {
+        let pressed_ns = timestamp_ns.unwrap_or_else(|| self.timer.now());
+        let Some(trial) = &mut self.current else {
+            return false;
+        };
+        let step = match key {
+            ResponseKey::ArrowLeft => -RATING_STEP,
+            ResponseKey::ArrowRight => RATING_STEP,
+            ResponseKey::Space if trial.rating.is_some() => {
+                self.record_response_at(key, timestamp_ns);
+                return true;
+            }
+            _ => return false,
+        };
+        let rating = trial.rating.map_or(0.5, |r| (r + step).clamp(0.0, 1.0));
+        move_rating(trial, rating, pressed_ns);
+        true
+    }
+
+    /// Where the marker sits on the rating scale on screen
+    pub fn current_rating(&self) -> Option<f32> {
+        self.current.as_ref().and_then(|t| t.rating)
+    }
+
+    /// Whether the trial on screen places its stimuli at their exact
+    /// fractional position rather than on whole pixels
+    pub fn subpixel_positioning(&self) -> bool {
+        self.current.as_ref().is_some_and(|t| t.subpixel)
+    }
+
+    /// Closes the response window unanswered and shows feedback: an omission
+    /// where a response was expected, a correct withhold otherwise
+    fn record_timeout(&mut self) {
+        if let Some(trial) = &mut self.current
+            && trial.state.accepts_response()
+        {
+            trial.timed_out = true;
+            trial.state = TrialState::Feedback;
+            let now_ns = self.timer.now();
+            let correct = score(&self.response_mapping, self.config.click_radius, trial);
+            info!("Trial {} timed out, correct = {}", trial.id, correct);
+            let trial_id = trial.id;
+            push_marker(
+                &mut self.markers,
+                EventMarker::Feedback { trial_id, correct },
+                now_ns,
+            );
+        }
+    }
+
+    /// Records the first speech onset after stimulus onset on the current
+    /// trial; it is stored alongside, and does not replace, a key response
+    pub fn record_voice_onset(&mut self, timestamp_ns: u64) -> bool {
+        let Some(trial) = &mut self.current else {
+            return false;
+        };
+        let Some(onset_ns) = trial.timestamps.stimulus_start else {
+            return false;
+        };
+        if trial.timestamps.voice_onset.is_some() || timestamp_ns < onset_ns {
+            return false;
+        }
+        trial.timestamps.voice_onset = Some(timestamp_ns);
+        info!(
+            "Voice onset at {}, vocal RT = {:.3} ms",
+            timestamp_ns,
+            (timestamp_ns - onset_ns) as f64 / 1_000_000.0
+        );
+        true
+    }
+
+    /// Routes captured input: scanner pulses (and the pulse key) are logged,
+    /// Space answers a waiting screen, digits the questionnaire, anything
+    /// else is a response
+    pub fn handle_raw_input(&mut self, input: RawInput) -> bool {
+        let timestamp_ns = Some(input.timestamp_ns);
+        let pulse_key = self.config.scanner.as_ref().and_then(|s| s.pulse_key);
+        let event = match input.event {
+            RawEvent::ScannerPulse => ExperimentEvent::ScannerPulse {
+                timestamp_ns: input.timestamp_ns,
+            },
+            RawEvent::Key(key) if Some(key) == pulse_key => ExperimentEvent::ScannerPulse {
+                timestamp_ns: input.timestamp_ns,
+            },
+            RawEvent::Key(ResponseKey::Space) if self.is_awaiting_input() => {
+                ExperimentEvent::SpacePressed
+            }
+            RawEvent::Key(ResponseKey::Char(c)) if self.phase.is_questionnaire() => {
+                match c.to_digit(10) {
+                    Some(digit @ 1..=9) => ExperimentEvent::QuestionnaireAnswer {
+                        option: digit as usize - 1,
+                    },
+                    _ => return false,
+                }
             }
+            _ if !self.phase.allows_input() => return false,
+            RawEvent::Key(key) => ExperimentEvent::ResponseReceived { key, timestamp_ns },
+            RawEvent::Click { button, position } => ExperimentEvent::MouseClicked {
+                position,
+                button,
+                timestamp_ns,
+            },
+            RawEvent::VoiceOnset => ExperimentEvent::VoiceOnset {
+                timestamp_ns: input.timestamp_ns,
+            },
+        };
+        self.handle_event(event)
+    }
+
+    /// Records a mouse click as the response for the current trial
+    pub fn record_click(&mut self, position: (f32, f32), button: MouseButton) {
+        self.record_click_at(position, button, None);
+    }
+
+    /// Records a click made at `timestamp_ns`, or now without one
+    pub fn record_click_at(
+        &mut self,
+        position: (f32, f32),
+        button: MouseButton,
+        timestamp_ns: Option<u64>,
+    ) {
+        if let Some(trial) = &mut self.current
+            && trial.state.accepts_response()
+        {
+            trial.click_position = Some(position);
+            debug!("Click at ({:.0}, {:.0})", position.0, position.1);
         }
+        self.record_response_at(ResponseKey::Mouse(button), timestamp_ns);
     }
 
     /// Completes the current trial and stores the results
@@ -303,110 +1219,1113 @@ where
                 .timestamps
                 .response
                 .map(|r| r - trial.timestamps.stimulus_start.unwrap_or(r));
-            let correct = reaction_ns.is_some();
+            let correct = score(&self.response_mapping, self.config.click_radius, trial);
 
             let result = TrialResult {
                 trial_id: trial.id,
-                stimulus_type: trial.stimulus.cache_id().to_string(),
+                stimulus: trial.stimulus.clone(),
+                condition: trial.condition.clone(),
+                set_size: 1 + trial.array.len(),
+                response_key: trial.response_key,
+                click_position: trial.click_position,
+                click_distance: trial
+                    .click_position
+                    .map(|c| click_distance(c, trial.position)),
                 reaction_time_ns: reaction_ns,
-                correct: Some(correct),
+                voice_onset_ns: trial.timestamps.voice_onset,
+                voice_reaction_time_ns: trial
+                    .timestamps
+                    .voice_onset
+                    .zip(trial.timestamps.stimulus_start)
+                    .map(|(voice, onset)| voice - onset),
+                intensity: trial.intensity,
+                block: self.phase.is_experiment().then_some(self.current_block),
+                // Ratings have no right answer
+                correct: (!trial.stimulus.is_rating_scale()).then_some(correct),
+                rating: trial.rating.filter(|_| !trial.timed_out),
+                rating_trajectory: trial.rating_trajectory.clone(),
+                invalid_keys: trial.invalid_keys.clone(),
+                outcome: if trial.timed_out {
+                    TrialOutcome::Timeout
+                } else {
+                    TrialOutcome::Response
+                },
                 timestamp_ns: timestamp.unwrap_or_default(),
-                _marker: PhantomData,
+                presentation: PresentationTimestamps {
+                    fixation_onset_ns: trial.timestamps.fixation_presented,
+                    cue_onset_ns: trial.timestamps.cue_presented,
+                    cue_offset_ns: trial.timestamps.cue_offset_presented,
+                    stimulus_onset_ns: trial.timestamps.stimulus_presented,
+                    stimulus_offset_ns: trial.timestamps.stimulus_offset_presented,
+                    feedback_onset_ns: trial.timestamps.feedback_presented,
+                },
+                requested_fixation_ms: trial.durations.fixation_ms,
+                requested_stimulus_ms: trial.durations.stimulus_ms,
+                requested_response_window_ms: trial.durations.response_window_ms,
+                requested_feedback_ms: trial.durations.feedback_ms,
+                requested_cue_ms: trial.cue.as_ref().map(|_| trial.durations.cue_ms),
+                requested_soa_ms: trial
+                    .cue
+                    .as_ref()
+                    .map(|_| trial.durations.cue_target_soa_ms),
+                requested_cue_frames: trial.cue.as_ref().and_then(|c| c.frames).map(|f| f.cue),
+                requested_mask_frames: trial.cue.as_ref().and_then(|c| c.frames).map(|f| f.mask),
+                requested_iti_ms: trial.durations.inter_trial_interval_ms,
+                dropped_frames: trial.dropped_frames,
+                interrupted: trial.interrupted,
             };
+            self.check_cue_frames(&result);
+
+            if trial.intensity.is_some()
+                && let Some(adaptive) = &mut self.adaptive
+            {
+                adaptive.procedure.update(correct);
+            }
 
             self.results.push(result);
         }
         self.current = None;
+        self.trial_span = Span::none();
         self.trial_number += 1;
         self.phase_trial_number += 1;
+        self.completed_ns = None;
 
-        self.timer
-            .sleep(Duration::from_millis(self.config.inter_trial_interval_ms));
+        let target_trials = self.config.trials_for(&self.phase);
 
-        let target_trials = if self.phase.is_practice() {
-            self.config.practice_trials
-        } else if self.phase.is_experiment() {
-            self.config.experiment_trials
-        } else {
-            0
-        };
+        let converged = self.phase.is_experiment()
+            && self
+                .adaptive
+                .as_ref()
+                .is_some_and(|a| a.procedure.is_finished());
 
-        if self.trial_number < target_trials {
-            self.start_trial();
+        if self.phase_trial_number >= target_trials || converged {
+            return;
         }
-    }
 
-    fn generate_stimulus(&mut self) -> StimulusType {
-        // Example: generate a random standard stimulus
-        match self.rng.random_range(0..3) {
-            0 => StimulusType::Circle {
-                radius: 50.0,
-                color: [255, 0, 0, 255],
-            },
-            1 => StimulusType::Rectangle {
-                width: 80.0,
-                height: 60.0,
-                color: [0, 255, 0, 255],
-            },
-            2 => StimulusType::Arrow {
-                direction: ArrowDirection::Right,
-                size: 60.0,
-                color: [0, 0, 255, 255],
-            },
-            _ => StimulusType::Arrow {
-                direction: ArrowDirection::Right,
-                size: 60.0,
-                color: [0, 0, 255, 255],
-            },
-            // _ => StimulusType::Text {
-            //     content: "Test",
-            //     size: 24.0,
-            //     color: [255, 255, 255, 255],
-            // },
+        let mut take_break = false;
+        let mut new_block = false;
+        let mut rest_ns = 0;
+        if self.phase.is_experiment() {
+            self.block_trial_number += 1;
+            if let Some(block) = self.config.blocks.get(self.current_block)
+                && self.block_trial_number >= block.trials
+            {
+                take_break = block.break_after;
+                new_block = true;
+                rest_ns = block.min_rest_s as u64 * 1_000_000_000;
+                self.current_block += 1;
+                self.block_trial_number = 0;
+                self.prepare_sequence();
+            }
         }
-    }
 
-    /// Generates stimulus position
-    fn generate_position(&mut self) -> (f32, f32) {
-        let x = self.rng.random_range(100.0..700.0);
-        let y = self.rng.random_range(100.0..500.0);
-        (x, y)
+        self.start_trial();
+        if take_break && let Some(trial) = &mut self.current {
+            trial.state = TrialState::Break;
+            self.awaiting_input = true;
+            if rest_ns > 0 {
+                self.rest_until_ns = Some(trial.timestamps.start + rest_ns);
+            }
+            info!("Break before block {}", self.current_block + 1);
+        } else if new_block {
+            self.count_down_block();
+        }
     }
 
-    /// Returns true if experiment is calibrated
-    pub fn is_calibrated(&self) -> bool {
-        self.calibrated
-    }
+    /// Restarts practice from a break screen when the run just finished is
+    /// below the accuracy criterion; false once it is met or the repeats are
+    /// used up
+    fn repeat_practice(&mut self) -> bool {
+        let Some(criterion) = self.config.practice_criterion else {
+            return false;
+        };
+        let run_start = self.results.len().saturating_sub(self.phase_trial_number);
+        let scored: Vec<bool> = self.results[run_start..]
+            .iter()
+            .filter(|r| r.block.is_none())
+            .filter_map(|r| r.correct)
+            .collect();
+        if scored.is_empty() {
+            return false;
+        }
+        let accuracy = scored.iter().filter(|&&c| c).count() as f64 / scored.len() as f64;
+        if accuracy >= criterion.min_accuracy {
+            info!("Practice accuracy {:.0}% meets criterion", accuracy * 100.0);
+            return false;
+        }
+        if self.practice_attempt >= criterion.max_repeats {
+            info!(
+                "Practice accuracy {:.0}% below {:.0}% after {} repeats, continuing",
+                accuracy * 100.0,
+                criterion.min_accuracy * 100.0,
+                self.practice_attempt
+            );
+            return false;
+        }
 
-    /// Returns current phase
-    pub fn current_phase(&self) -> &P {
-        &self.phase
+        self.practice_attempt += 1;
+        info!(
+            "Practice accuracy {:.0}% below {:.0}%, repeating practice ({}/{})",
+            accuracy * 100.0,
+            criterion.min_accuracy * 100.0,
+            self.practice_attempt,
+            criterion.max_repeats
+        );
+        self.phase_trial_number = 0;
+        self.sequence = match &self.paradigm {
+            Some(paradigm) => match paradigm.sequence(self.config.practice_trials, &mut self.rng) {
+                Ok(sequence) => Some(sequence),
+                Err(e) => {
+                    warn!("Failed to build practice sequence: {}", e);
+                    None
+                }
+            },
+            None => self.practice_sequence.clone(),
+        };
+        self.start_trial();
+        if let Some(trial) = &mut self.current {
+            trial.state = TrialState::Break;
+            self.awaiting_input = true;
+        }
+        true
     }
 
-    /// Returns current stimulus and position if any
-    pub fn current_stimulus(&self) -> Option<(&StimulusType, (f32, f32))> {
-        self.current.as_ref().map(|t| (&t.stimulus, t.position))
+    /// Freezes trial timing until `resume`
+    fn pause(&mut self) -> bool {
+        if self.paused_at.is_some() {
+            return false;
+        }
+        self.paused_at = Some(self.timer.now());
+        info!("Experiment paused");
+        true
     }
 
-    pub fn is_awaiting_input(&self) -> bool {
-        self.awaiting_input || self.phase.is_welcome()
+    /// Unfreezes, shifting the current trial's timestamps by the paused time
+    fn resume(&mut self) -> bool {
+        self.resume_at(self.timer.now())
+    }
+
+    /// Unfreezes as of `now_ns`; also releases a hold for the scanner
+    fn resume_at(&mut self, now_ns: u64) -> bool {
+        let Some(paused_at) = self.paused_at.take() else {
+            return false;
+        };
+        self.waiting_for_scanner = false;
+        let paused_ns = now_ns.saturating_sub(paused_at);
+        for until_ns in [&mut self.rest_until_ns, &mut self.completed_ns]
+            .into_iter()
+            .flatten()
+        {
+            *until_ns += paused_ns;
+        }
+        if let Some(trial) = &mut self.current {
+            let ts = &mut trial.timestamps;
+            ts.start += paused_ns;
+            ts.fixation_start += paused_ns;
+            for t in [&mut ts.cue_start, &mut ts.stimulus_start, &mut ts.response]
+                .into_iter()
+                .flatten()
+            {
+                *t += paused_ns;
+            }
+        }
+        info!("Experiment resumed after {:.1} s", paused_ns as f64 / 1e9);
+        true
+    }
+
+    /// Stops the session; results collected so far are kept and the session
+    /// metadata records why it ended
+    fn abort(&mut self, reason: String) {
+        if let Some(session) = &mut self.session {
+            session.aborted = true;
+            session.abort_reason = Some(reason.clone());
+        }
+        self.aborted = Some(reason);
+        self.paused_at = None;
+        self.interrupted = None;
+        self.waiting_for_scanner = false;
+        self.current = None;
+        self.trial_span = Span::none();
+        self.awaiting_input = false;
+        info!(
+            "Experiment aborted after {} trials: {}",
+            self.results.len(),
+            self.aborted.as_deref().unwrap_or_default()
+        );
+    }
+
+    /// Drops the current trial and moves to the next block, or ends the
+    /// phase when there is no further block
+    fn skip_block(&mut self) -> bool {
+        self.current = None;
+        self.rest_until_ns = None;
+        self.trial_span = Span::none();
+        self.awaiting_input = false;
+        if self.phase.is_experiment() && self.current_block + 1 < self.config.blocks.len() {
+            self.current_block += 1;
+            self.block_trial_number = 0;
+            self.phase_trial_number = self.config.blocks[..self.current_block]
+                .iter()
+                .map(|b| b.trials)
+                .sum();
+            self.prepare_sequence();
+            self.start_trial();
+            self.count_down_block();
+            info!("Skipped to block {}", self.current_block + 1);
+        } else {
+            self.phase_trial_number = self.config.trials_for(&self.phase);
+            info!("Skipped rest of {:?}", self.phase);
+        }
+        true
+    }
+
+    /// Shows the break screen before the next trial. A trial in progress is
+    /// restarted after the break; one already answered is completed first.
+    fn insert_break(&mut self) -> bool {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|t| TrialState::Feedback == t.state)
+        {
+            self.complete_current_trial(Some(self.timer.now()));
+        }
+        match &mut self.current {
+            Some(trial) if TrialState::Break != trial.state => {
+                trial.state = TrialState::Break;
+                trial.response_key = None;
+                trial.click_position = None;
+                let ts = &mut trial.timestamps;
+                ts.stimulus_start = None;
+                ts.response = None;
+                ts.fixation_presented = None;
+                ts.stimulus_presented = None;
+                ts.stimulus_offset_presented = None;
+                ts.feedback_presented = None;
+                self.awaiting_input = true;
+                info!("Break inserted");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Pauses for a participant who left the window, logging when and
+    /// during which trial; further interruptions before they return are
+    /// part of the same one
+    fn interrupt(&mut self, reason: String) -> bool {
+        if self.is_interrupted() {
+            return false;
+        }
+        let now_ns = self.timer.now();
+        let paused = self.pause();
+        self.interrupted = Some((now_ns, paused));
+        let trial_id = match &mut self.current {
+            Some(trial)
+                if !matches!(
+                    trial.state,
+                    TrialState::Complete | TrialState::Break | TrialState::Countdown
+                ) =>
+            {
+                trial.interrupted = true;
+                Some(trial.id)
+            }
+            _ => None,
+        };
+        if let Some(session) = &mut self.session {
+            session.interruptions.push(Interruption {
+                reason: reason.clone(),
+                start_ns: now_ns,
+                end_ns: None,
+                trial_id,
+            });
+        }
+        self.log_marker(
+            EventMarker::Interrupted {
+                reason: reason.clone(),
+            },
+            now_ns,
+        );
+        warn!("Participant left the experiment: {}", reason);
+        true
+    }
+
+    /// Leaves the return screen, resuming the clock unless the experimenter
+    /// had paused it
+    fn return_from_interruption(&mut self) -> bool {
+        let Some((start_ns, resumes)) = self.interrupted.take() else {
+            return false;
+        };
+        let now_ns = self.timer.now();
+        if resumes {
+            self.resume_at(now_ns);
+        }
+        if let Some(interruption) = self
+            .session
+            .as_mut()
+            .and_then(|s| s.interruptions.last_mut())
+        {
+            interruption.end_ns = Some(now_ns);
+        }
+        self.log_marker(EventMarker::Returned, now_ns);
+        info!(
+            "Participant returned after {:.1} s",
+            now_ns.saturating_sub(start_ns) as f64 / 1e9
+        );
+        true
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Whether the participant left the window and neither they, with
+    /// Space, nor the experimenter, with a resume, has carried on yet
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.is_some()
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.aborted.is_some()
+    }
+
+    pub fn abort_reason(&self) -> Option<&str> {
+        self.aborted.as_deref()
+    }
+
+    /// Records a timer/UTC sample in the session metadata, if there is a
+    /// session
+    pub fn sample_clock(&mut self) {
+        if let Some(session) = &mut self.session {
+            session.clock_sync.push(ClockSample::take(&self.timer));
+        }
+    }
+
+    /// Samples on the first update with a session, then every
+    /// `clock_sync_interval_s`
+    fn sample_clock_if_due(&mut self) {
+        let Some(session) = &self.session else {
+            return;
+        };
+        let interval_ns = self
+            .config
+            .clock_sync_interval_s
+            .saturating_mul(1_000_000_000);
+        let due = match session.clock_sync.last() {
+            None => true,
+            Some(last) => {
+                interval_ns > 0 && self.timer.now().saturating_sub(last.timer_ns) >= interval_ns
+            }
+        };
+        if due {
+            self.sample_clock();
+        }
+    }
+
+    /// Markers logged outside trials, such as external ones, in order
+    pub fn logged_markers(&self) -> &[Marker] {
+        &self.logged_markers
+    }
+
+    /// Pulse event onsets are timed from: the one that released the held
+    /// run, or else the first one received
+    pub fn scanner_zero_ns(&self) -> Option<u64> {
+        self.scanner_zero_ns
+    }
+
+    /// Whether the run is held until the first scanner pulse
+    pub fn is_waiting_for_scanner(&self) -> bool {
+        self.waiting_for_scanner
+    }
+
+    /// Pauses on the first experiment trial's fixation when the config asks
+    /// to wait for the scanner and no pulse has released the run yet
+    fn hold_for_scanner(&mut self) {
+        if self.phase.is_experiment()
+            && self.current.is_some()
+            && self.paused_at.is_none()
+            && self
+                .config
+                .scanner
+                .as_ref()
+                .is_some_and(|s| s.wait_for_first_pulse)
+        {
+            self.paused_at = Some(self.timer.now());
+            self.waiting_for_scanner = true;
+            info!("Waiting for the first scanner pulse");
+        }
+    }
+
+    /// Logs a pulse; the first one while held starts the run and becomes
+    /// the zero of event onsets
+    fn scanner_pulse(&mut self, timestamp_ns: u64) {
+        self.scanner_volumes += 1;
+        self.log_marker(
+            EventMarker::ScannerPulse {
+                volume: self.scanner_volumes,
+            },
+            timestamp_ns,
+        );
+        if self.waiting_for_scanner {
+            // The fixation on screen is stamped again by the next frame
+            if let Some(trial) = &mut self.current {
+                trial.timestamps.fixation_presented = None;
+            }
+            self.last_presented = None;
+            self.resume_at(timestamp_ns);
+            self.scanner_zero_ns = Some(timestamp_ns);
+            info!("Scanner pulse {} started the run", self.scanner_volumes);
+        } else if self.scanner_zero_ns.is_none() {
+            self.scanner_zero_ns = Some(timestamp_ns);
+        }
+    }
+
+    /// Sends `event` to the marker outlets and keeps it for the events file
+    fn log_marker(&mut self, event: EventMarker, timestamp_ns: u64) {
+        push_marker(&mut self.markers, event.clone(), timestamp_ns);
+        self.logged_markers.push(Marker::new(event, timestamp_ns));
+    }
+
+    /// Leaves the break screen and starts the pending trial's fixation, or
+    /// the countdown when that trial opens a block. Space is ignored until
+    /// the break's minimum rest is over.
+    fn resume_from_break(&mut self) -> bool {
+        if self.rest_remaining().is_some() {
+            debug!("Space ignored during the minimum rest");
+            return false;
+        }
+        let now_ns = self.timer.now();
+        match &mut self.current {
+            Some(trial) if TrialState::Break == trial.state => {
+                trial.state = TrialState::Fixation;
+                trial.timestamps.start = now_ns;
+                trial.timestamps.fixation_start = now_ns;
+                self.awaiting_input = false;
+                self.rest_until_ns = None;
+            }
+            _ => return false,
+        }
+        if self.block_trial_number == 0 {
+            self.count_down_block();
+        }
+        true
+    }
+
+    /// Counts down `widgets.block_countdown_s` seconds before the trial
+    /// just started, which opens an experiment block. The countdown's start
+    /// stands in the trial's start until fixation begins.
+    fn count_down_block(&mut self) {
+        let seconds = self.config.widgets.block_countdown_s;
+        if seconds == 0 || !self.phase.is_experiment() {
+            return;
+        }
+        if let Some(trial) = &mut self.current {
+            trial.state = TrialState::Countdown;
+            trial.timestamps.start = self.timer.now();
+            info!(
+                "Counting down {} s to block {}",
+                seconds,
+                self.current_block + 1
+            );
+        }
+    }
+
+    /// Pops the next trial from the active sequence, falling back to a
+    /// balanced shuffle of the standard stimuli for the rest of the phase
+    fn next_sequenced_trial(&mut self) -> SequencedTrial {
+        if let Some(trial) = self.sequence.as_mut().and_then(|s| s.next_trial()) {
+            return trial;
+        }
+
+        let remaining = self
+            .config
+            .trials_for(&self.phase)
+            .saturating_sub(self.phase_trial_number)
+            .max(1);
+        let mut sequence = TrialSequence::builder()
+            .stimuli(standard_stimuli())
+            .total_trials(remaining)
+            .build(&mut self.rng)
+            .expect("standard stimuli form a valid sequence");
+        let trial = sequence
+            .next_trial()
+            .expect("sequence has at least one trial");
+        self.sequence = Some(sequence);
+        trial
+    }
+
+    /// Selects the sequence for the phase or block about to start
+    fn prepare_sequence(&mut self) {
+        if self.phase.is_practice() {
+            // Kept for practice repeats
+            self.sequence = self.practice_sequence.clone();
+        } else if self.phase.is_experiment() {
+            if self.current_block == 0 {
+                self.sequence = self.experiment_sequence.take();
+            }
+            if let Some(block) = self.config.blocks.get(self.current_block)
+                && !block.conditions.is_empty()
+            {
+                match TrialSequence::builder()
+                    .stimuli(block.conditions.iter().cloned())
+                    .total_trials(block.trials)
+                    .build(&mut self.rng)
+                {
+                    Ok(sequence) => self.sequence = Some(sequence),
+                    Err(e) => warn!("Failed to build block sequence: {}", e),
+                }
+            }
+        } else {
+            self.sequence = None;
+        }
+    }
+
+    /// Places a `condition` display by its configured layout: the stimulus
+    /// at `position` when given, and the array where it overlaps neither
+    /// the stimulus nor itself
+    #[allow(clippy::type_complexity)]
+    fn place_display(
+        &mut self,
+        condition: &str,
+        stimulus: &StimulusType,
+        position: Option<(f32, f32)>,
+        array: Vec<StimulusType>,
+    ) -> ((f32, f32), Vec<(StimulusType, (f32, f32))>) {
+        let layout = *self.config.layouts.layout_for(condition);
+        let display = self.config.display.as_ref();
+        if array.is_empty() {
+            let pos =
+                position.unwrap_or_else(|| layout.place(&self.screen, display, &mut self.rng));
+            return (pos, Vec::new());
+        }
+
+        // The stimulus is placed with the array unless its position is given
+        let fixed: Vec<_> = position
+            .map(|pos| (pos, stimulus.bounding_radius()))
+            .into_iter()
+            .collect();
+        let radii: Vec<f32> = position
+            .is_none()
+            .then(|| stimulus.bounding_radius())
+            .into_iter()
+            .chain(array.iter().map(StimulusType::bounding_radius))
+            .collect();
+        let gap = self.config.layouts.gap();
+        let placed = layout
+            .scatter(&self.screen, display, &fixed, &radii, gap, &mut self.rng)
+            .unwrap_or_else(|| {
+                warn!(
+                    "No room for {} {} stimuli without overlap; placing them regardless",
+                    radii.len(),
+                    condition
+                );
+                (0..radii.len())
+                    .map(|_| layout.place(&self.screen, display, &mut self.rng))
+                    .collect()
+            });
+        let mut placed = placed.into_iter();
+        let pos = position.or_else(|| placed.next()).unwrap_or_default();
+        (pos, array.into_iter().zip(placed).collect())
+    }
+
+    /// Returns true if experiment is calibrated
+    pub fn is_calibrated(&self) -> bool {
+        self.calibrated
+    }
+
+    /// Provenance for this session, with the calibration stats once measured
+    pub fn metadata(&self) -> SessionMetadata<'_, P> {
+        let metadata = SessionMetadata::new(&self.config, self.session.as_ref());
+        if !self.calibrated {
+            return metadata;
+        }
+        let stats = self
+            .calibration
+            .clone()
+            .unwrap_or_else(|| self.timer.calibration_stats());
+        match refresh_mismatch(&stats) {
+            Some(caveat) => metadata.with_caveat(caveat),
+            None => metadata,
+        }
+        .with_calibration(stats)
+    }
+
+    /// Returns current phase
+    pub fn current_phase(&self) -> &P {
+        &self.phase
+    }
+
+    /// The phase entered when the current one ends
+    pub fn next_phase(&self) -> Option<P> {
+        match &self.phase_graph {
+            Some(graph) => graph.next(&self.phase_exit()),
+            None => self.phase.next(),
+        }
+    }
+
+    /// Whether the current phase is the last, the one the experiment ends on
+    pub fn is_final_phase(&self) -> bool {
+        match &self.phase_graph {
+            Some(graph) => graph.is_terminal(&self.phase),
+            None => self.phase.next().is_none(),
+        }
+    }
+
+    fn phase_exit(&self) -> PhaseExit<P> {
+        let run_start = self.results.len().saturating_sub(self.phase_trial_number);
+        let scored: Vec<bool> = self.results[run_start..]
+            .iter()
+            .filter_map(|r| r.correct)
+            .collect();
+        PhaseExit {
+            phase: self.phase,
+            trials: self.phase_trial_number,
+            accuracy: (!scored.is_empty())
+                .then(|| scored.iter().filter(|&&c| c).count() as f64 / scored.len() as f64),
+            visits: self.visited.iter().filter(|&&p| p == self.phase).count(),
+        }
+    }
+
+    /// Items and answers while the questionnaire phase is showing
+    pub fn current_questionnaire(&self) -> Option<&Questionnaire> {
+        self.questionnaire
+            .as_ref()
+            .filter(|_| self.phase.is_questionnaire())
+    }
+
+    /// Instruction pages while the instructions phase is showing
+    pub fn current_instructions(&self) -> Option<&Instructions> {
+        self.instructions
+            .as_ref()
+            .filter(|_| self.phase.is_instructions())
+    }
+
+    /// Returns current stimulus and position if any
+    /// During the cue state this is the cue while it is on screen, and its
+    /// mask, if any, for the rest of the cue-target interval; after the
+    /// stimulus offset it is nothing for the rest of the response window,
+    /// and once the trial is complete it is the inter-trial interval's
+    /// stimulus, if it shows one
+    pub fn current_stimulus(&self) -> Option<(&StimulusType, (f32, f32))> {
+        let trial = self.current.as_ref()?;
+        match trial.state {
+            TrialState::Response => return None,
+            TrialState::Complete => {
+                let display = self.config.display.as_ref();
+                let stimulus = self.config.iti_display.stimulus()?;
+                return Some((stimulus, self.screen.centre(display)));
+            }
+            _ => {}
+        }
+        if TrialState::Cue != trial.state {
+            return Some((&trial.stimulus, trial.position));
+        }
+        let cue = trial.cue.as_ref()?;
+        let shown = match cue.frames {
+            Some(frames) => trial.cue_frames < frames.cue,
+            None => {
+                let cue_ns = trial.timestamps.cue_start?;
+                self.timer.now() + self.onset_lead_ns - cue_ns < trial.durations.cue_ms * 1_000_000
+            }
+        };
+        if shown {
+            Some((&cue.stimulus, cue.position))
+        } else {
+            cue.mask.as_ref().map(|mask| (mask, cue.position))
+        }
+    }
+
+    /// What [`current_stimulus`](Self::current_stimulus) shows, followed
+    /// while it is the trial's stimulus by the rest of its display
+    pub fn current_stimuli(&self) -> Vec<(&StimulusType, (f32, f32))> {
+        let Some(shown) = self.current_stimulus() else {
+            return Vec::new();
+        };
+        let mut stimuli = vec![shown];
+        if let Some(trial) = &self.current
+            && !matches!(trial.state, TrialState::Cue | TrialState::Complete)
+        {
+            stimuli.extend(trial.array.iter().map(|(s, pos)| (s, *pos)));
+        }
+        stimuli
+    }
+
+    /// Stimuli the next onset shows, cue first: the current trial's during
+    /// fixation and, once it has run out, the next sequenced trial's.
+    /// Renderers rasterize these ahead of time so onset frames only blit.
+    pub fn upcoming_stimuli(&self) -> Vec<&StimulusType> {
+        let Some(trial) = &self.current else {
+            return Vec::new();
+        };
+        let (cue, stimulus, array): (_, _, Vec<&StimulusType>) = match trial.state {
+            TrialState::Countdown | TrialState::Fixation => (
+                trial.cue.as_ref(),
+                &trial.stimulus,
+                trial.array.iter().map(|(s, _)| s).collect(),
+            ),
+            TrialState::Complete => {
+                let Some(next) = self.sequence.as_ref().and_then(|s| s.iter().next()) else {
+                    return Vec::new();
+                };
+                (
+                    next.cue.as_ref(),
+                    &next.stimulus,
+                    next.array.iter().collect(),
+                )
+            }
+            _ => return Vec::new(),
+        };
+        cue.into_iter()
+            .flat_map(|c| [Some(&c.stimulus), c.mask.as_ref()])
+            .flatten()
+            .chain([stimulus])
+            .chain(array)
+            .collect()
+    }
+
+    /// Text the paradigm replaces in the locale catalog
+    pub fn paradigm_messages(&self) -> Vec<(String, String)> {
+        self.paradigm
+            .as_ref()
+            .map_or_else(Vec::new, |paradigm| paradigm.messages())
+    }
+
+    /// What the paradigm's render hook draws in place of the current
+    /// stimulus this frame
+    pub fn paradigm_primitives(&self) -> Option<Vec<Primitive>> {
+        let paradigm = self.paradigm.as_ref()?;
+        let trial = self
+            .current
+            .as_ref()
+            .filter(|t| TrialState::Stimulus == t.state)?;
+        paradigm.draw(&trial.stimulus, self.stimulus_elapsed()?)
+    }
+
+    /// Time from the stimulus's first presented frame to the latest one,
+    /// zero until it is first presented, or from the start of the
+    /// inter-trial interval for its stimulus. Animated stimuli step by this
+    /// so motion follows the display's actual refresh.
+    pub fn stimulus_elapsed(&self) -> Option<Duration> {
+        let trial = self.current.as_ref()?;
+        let onset_ns = match trial.state {
+            TrialState::Stimulus => trial.timestamps.stimulus_presented,
+            // The inter-trial interval's stimulus, from when the trial ran out
+            TrialState::Complete => self.completed_ns,
+            _ => return None,
+        };
+        let elapsed = match (onset_ns, self.last_frame_ns) {
+            (Some(onset), Some(last)) => last.saturating_sub(onset),
+            _ => 0,
+        };
+        Some(Duration::from_nanos(elapsed))
+    }
+
+    /// Timer reading at which `update` next changes the trial or what it
+    /// shows without any input; `None` while waiting for input or paused.
+    /// Readings at or before now mean frames are needed continuously, e.g.
+    /// for animated stimuli or between trials.
+    pub fn next_deadline_ns(&self) -> Option<u64> {
+        let now_ns = self.timer.now();
+        if self.is_paused() || self.is_aborted() {
+            return None;
+        }
+        if self.phase.requires_calibration() && !self.calibrated {
+            return Some(now_ns);
+        }
+        if !(self.phase.is_practice() || self.phase.is_experiment()) {
+            return None;
+        }
+        let Some(trial) = &self.current else {
+            return Some(now_ns);
+        };
+        let ms = |ms: u64| ms * 1_000_000;
+        let at = match trial.state {
+            // Redrawn as each second runs out
+            TrialState::Countdown => {
+                let elapsed_ns =
+                    (now_ns + self.onset_lead_ns).saturating_sub(trial.timestamps.start);
+                trial.timestamps.start + (elapsed_ns / 1_000_000_000 + 1) * 1_000_000_000
+            }
+            TrialState::Fixation if self.gaze.is_some() => now_ns,
+            TrialState::Fixation => {
+                trial.timestamps.fixation_start + ms(trial.durations.fixation_ms)
+            }
+            // Frame-counted cues move on with every presented frame
+            TrialState::Cue if trial.cue.as_ref().is_some_and(|c| c.frames.is_some()) => now_ns,
+            TrialState::Cue => {
+                let cue_ns = trial.timestamps.cue_start?;
+                let hidden_ns = cue_ns + ms(trial.durations.cue_ms);
+                let onset_ns = cue_ns + ms(trial.durations.cue_target_soa_ms);
+                if hidden_ns > now_ns + self.onset_lead_ns {
+                    hidden_ns.min(onset_ns)
+                } else {
+                    onset_ns
+                }
+            }
+            TrialState::Stimulus
+                if trial.stimulus.is_dynamic()
+                    || self
+                        .paradigm
+                        .as_ref()
+                        .is_some_and(|p| p.animates(&trial.stimulus)) =>
+            {
+                now_ns
+            }
+            TrialState::Stimulus if !trial.stimulus.is_rating_scale() => {
+                trial.timestamps.stimulus_start? + ms(trial.durations.stimulus_ms)
+            }
+            TrialState::Stimulus | TrialState::Response => {
+                trial.timestamps.stimulus_start?
+                    + ms(trial.durations.stimulus_ms + trial.durations.response_window_ms)
+                    + self.safe_margin_ns
+            }
+            TrialState::Feedback => {
+                let soa_ms = trial
+                    .cue
+                    .as_ref()
+                    .map_or(0, |_| trial.durations.cue_target_soa_ms);
+                trial.timestamps.start
+                    + ms(trial.durations.fixation_ms
+                        + soa_ms
+                        + trial.durations.stimulus_ms
+                        + trial.durations.response_window_ms
+                        + trial.durations.feedback_ms)
+                    + trial.gaze_wait_ns
+                    + self.safe_margin_ns
+            }
+            // Redrawn as each second of the minimum rest runs out
+            TrialState::Break => {
+                let until_ns = self.rest_until_ns.filter(|&until_ns| until_ns > now_ns)?;
+                let seconds = (until_ns - now_ns).div_ceil(1_000_000_000);
+                until_ns - (seconds - 1) * 1_000_000_000
+            }
+            // Animated interval stimuli need every frame
+            TrialState::Complete
+                if self
+                    .config
+                    .iti_display
+                    .stimulus()
+                    .is_some_and(|s| s.is_dynamic()) =>
+            {
+                now_ns
+            }
+            TrialState::Complete => self.completed_ns.map_or(now_ns, |at| {
+                at + ms(trial.durations.inter_trial_interval_ms)
+            }),
+        };
+        Some(at.saturating_sub(self.onset_lead_ns))
+    }
+
+    /// Innermost open log span, for entering around state changes
+    fn span(&self) -> Span {
+        if self.trial_span.is_none() {
+            self.phase_span.clone()
+        } else {
+            self.trial_span.clone()
+        }
+    }
+
+    pub fn is_awaiting_input(&self) -> bool {
+        self.awaiting_input
+            || self.is_interrupted()
+            || self.phase.is_welcome()
+            || self.phase.is_instructions()
     }
 
     pub fn should_show_stimulus(&self) -> bool {
         self.current
             .as_ref()
-            .map_or(false, |t| TrialState::Stimulus == t.state)
+            .is_some_and(|t| TrialState::Stimulus == t.state)
     }
 
     pub fn should_show_fixation(&self) -> bool {
         self.current
             .as_ref()
-            .map_or(false, |t| TrialState::Fixation == t.state)
+            .is_some_and(|t| TrialState::Fixation == t.state)
     }
     pub fn should_show_feedback(&self) -> bool {
         self.current
             .as_ref()
-            .map_or(false, |t| TrialState::Feedback == t.state)
+            .is_some_and(|t| TrialState::Feedback == t.state)
+    }
+
+    /// Threshold estimated by the adaptive procedure, if one is configured
+    pub fn adaptive_threshold(&self) -> Option<f64> {
+        self.adaptive.as_ref().and_then(|a| a.procedure.threshold())
+    }
+
+    fn report_adaptive(&self) {
+        if let Some(adaptive) = &self.adaptive {
+            match adaptive.procedure.threshold() {
+                Some(t) => info!(
+                    "Adaptive {:?} threshold: {:.4} after {} trials",
+                    adaptive.parameter,
+                    t,
+                    adaptive.procedure.trial_count()
+                ),
+                None => info!(
+                    "Adaptive {:?}: no threshold estimate after {} trials",
+                    adaptive.parameter,
+                    adaptive.procedure.trial_count()
+                ),
+            }
+        }
+    }
+
+    /// Scored verdict and reaction time while the current trial is in
+    /// feedback
+    pub fn feedback(&self) -> Option<TrialFeedback> {
+        let trial = self.current.as_ref()?;
+        if trial.state != TrialState::Feedback {
+            return None;
+        }
+        let outcome = if score(&self.response_mapping, self.config.click_radius, trial) {
+            FeedbackOutcome::Correct
+        } else if trial.response_key.is_none() {
+            FeedbackOutcome::TooSlow
+        } else {
+            FeedbackOutcome::Wrong
+        };
+        let reaction_time_ms = trial
+            .timestamps
+            .response
+            .zip(trial.timestamps.stimulus_start)
+            .map(|(response, onset)| response.saturating_sub(onset) / 1_000_000);
+        Some(TrialFeedback {
+            outcome,
+            reaction_time_ms,
+        })
+    }
+
+    /// Accuracy and reaction times over the experiment-phase results
+    pub fn debrief(&self) -> DebriefSummary {
+        let experiment: Vec<_> = self
+            .results
+            .iter()
+            .filter(|r| r.block.is_some())
+            .cloned()
+            .collect();
+        DebriefSummary::from_results(&experiment)
+    }
+
+    /// Warns when a frame-counted cue's presentation timestamps show it up
+    /// for other than the requested number of refreshes
+    fn check_cue_frames(&self, result: &TrialResult<StimulusType>) {
+        let (Some(requested), Some(shown_ns)) = (
+            result.requested_cue_frames,
+            result.presentation.cue_duration_ns(),
+        ) else {
+            return;
+        };
+        let frame_ns = self.timer.calibration_stats().average_frame_time_ns;
+        let shown = (shown_ns as f64 / frame_ns).round() as u64;
+        if shown != requested {
+            warn!(
+                "Trial {}: cue shown for {} frames ({:.3} ms) instead of {}",
+                result.trial_id,
+                shown,
+                shown_ns as f64 / 1e6,
+                requested
+            );
+        }
+    }
+
+    /// Warns when more frames were dropped than `config.max_dropped_frames`
+    fn warn_dropped_frames(&self) {
+        let dropped: u64 = self.results.iter().map(|r| r.dropped_frames).sum();
+        if dropped > self.config.max_dropped_frames {
+            let trials = self.results.iter().filter(|r| r.dropped_frames > 0).count();
+            warn!(
+                "{} frames dropped across {} of {} trials (limit {}); presentation timing may be unreliable",
+                dropped,
+                trials,
+                self.results.len(),
+                self.config.max_dropped_frames
+            );
+        }
+    }
+
+    /// Paradigm summary of the experiment-phase results, empty without a
+    /// paradigm
+    pub fn debrief_summary(&self) -> Vec<String> {
+        let Some(paradigm) = &self.paradigm else {
+            return Vec::new();
+        };
+        let experiment: Vec<_> = self
+            .results
+            .iter()
+            .filter(|r| r.block.is_some())
+            .cloned()
+            .collect();
+        paradigm.summary(&experiment)
+    }
+
+    /// Records the time the frame just rendered reached the display.
+    ///
+    /// The first frame of each trial state sets the matching presentation
+    /// timestamp; the first frame without the stimulus sets its offset, even if
+    /// the trial has already been stored in the results.
+    pub fn frame_presented(&mut self, timestamp_ns: u64) {
+        self.last_frame_ns = Some(timestamp_ns);
+        let dropped = self.timer.record_present(timestamp_ns);
+        if dropped > 0
+            && let Some(trial) = &mut self.current
+        {
+            trial.dropped_frames += dropped;
+            warn!("Trial {}: {} dropped frame(s)", trial.id, dropped);
+        }
+        if let Some(trial) = &mut self.current
+            && let Some(frames) = trial.cue.as_ref().and_then(|c| c.frames)
+        {
+            let offset = &mut trial.timestamps.cue_offset_presented;
+            match trial.state {
+                TrialState::Cue => {
+                    if trial.cue_frames >= frames.cue {
+                        offset.get_or_insert(timestamp_ns);
+                    }
+                    // Refreshes missed since the previous cue-state frame
+                    // kept that frame up for longer
+                    let missed = if trial.cue_frames > 0 { dropped } else { 0 };
+                    trial.cue_frames += 1 + missed;
+                }
+                TrialState::Stimulus => {
+                    offset.get_or_insert(timestamp_ns);
+                }
+                _ => {}
+            }
+        }
+        let shown = self.current.as_ref().map(|t| (t.id, t.state.clone()));
+        if shown == self.last_presented {
+            return;
+        }
+        let stimulus_of = |s: &Option<(usize, TrialState)>| match s {
+            Some((id, TrialState::Stimulus)) => Some(*id),
+            _ => None,
+        };
+
+        let previous = std::mem::replace(&mut self.last_presented, shown.clone());
+        if let Some(id) = stimulus_of(&previous)
+            && stimulus_of(&shown) != Some(id)
+        {
+            if let Some(trial) = self.current.as_mut().filter(|t| t.id == id) {
+                trial.timestamps.stimulus_offset_presented = Some(timestamp_ns);
+            } else if let Some(result) = self.results.iter_mut().rev().find(|r| r.trial_id == id) {
+                result.presentation.stimulus_offset_ns = Some(timestamp_ns);
+            }
+        }
+
+        if let Some(trial) = &mut self.current {
+            let slot = match trial.state {
+                TrialState::Fixation => &mut trial.timestamps.fixation_presented,
+                TrialState::Cue => &mut trial.timestamps.cue_presented,
+                TrialState::Stimulus => &mut trial.timestamps.stimulus_presented,
+                TrialState::Feedback => &mut trial.timestamps.feedback_presented,
+                TrialState::Response
+                | TrialState::Complete
+                | TrialState::Break
+                | TrialState::Countdown => return,
+            };
+            slot.get_or_insert(timestamp_ns);
+        }
     }
 
     /// Experiment results
@@ -414,17 +2333,309 @@ where
         &self.results
     }
 
+    /// Proportion of correct trials per stimulus condition
+    pub fn accuracy_by_condition(&self) -> BTreeMap<String, f64> {
+        let mut tally: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+        for result in &self.results {
+            let entry = tally.entry(result.condition.clone()).or_default();
+            entry.1 += 1;
+            if result.correct == Some(true) {
+                entry.0 += 1;
+            }
+        }
+        tally
+            .into_iter()
+            .map(|(condition, (correct, total))| (condition, correct as f64 / total as f64))
+            .collect()
+    }
+
     pub fn current_trial_state(&self) -> Option<&TrialState> {
         self.current.as_ref().map(|trial| &trial.state)
     }
 
     pub fn trial_progress(&self) -> Option<(usize, usize)> {
-        if self.phase.is_practice() {
-            Some((self.phase_trial_number + 1, self.config.practice_trials))
-        } else if self.phase.is_experiment() {
-            Some((self.phase_trial_number + 1, self.config.experiment_trials))
+        if self.phase.is_practice() || self.phase.is_experiment() {
+            Some((
+                self.phase_trial_number + 1,
+                self.config.trials_for(&self.phase),
+            ))
         } else {
             None
         }
     }
+
+    /// Current experiment block index, if the phase is split into blocks
+    pub fn current_block(&self) -> Option<usize> {
+        (self.phase.is_experiment() && !self.config.blocks.is_empty()).then_some(self.current_block)
+    }
+
+    /// The countdown before a block and the session's progress bar, as
+    /// configured in `config.widgets`, placed like stimuli
+    pub fn widgets(&self) -> Vec<(StimulusType, (f32, f32))> {
+        if !(self.phase.is_practice() || self.phase.is_experiment()) {
+            return Vec::new();
+        }
+        let widgets = &self.config.widgets;
+        let color = self.config.theme.foreground;
+        let display = self.config.display.as_ref();
+        // Widgets stay put, whatever their layout draws
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(0);
+        let mut shown = Vec::new();
+        if let Some(trial) = &self.current
+            && TrialState::Countdown == trial.state
+        {
+            let total_ns = widgets.block_countdown_s as u64 * 1_000_000_000;
+            let elapsed_ns =
+                (self.timer.now() + self.onset_lead_ns).saturating_sub(trial.timestamps.start);
+            let countdown = StimulusType::Countdown {
+                seconds: total_ns.saturating_sub(elapsed_ns).div_ceil(1_000_000_000) as u32,
+                size: widgets.countdown_size,
+                color,
+            };
+            let pos = widgets.countdown_layout.place(&self.screen, display, rng);
+            shown.push((countdown, pos));
+        }
+        if widgets.progress_bar {
+            let total = self.config.trials_for(&self.phase).max(1);
+            let bar = StimulusType::ProgressBar {
+                width: widgets.progress_width,
+                height: widgets.progress_height,
+                fraction: self.phase_trial_number.min(total) as f32 / total as f32,
+                color,
+            };
+            let pos = widgets.progress_layout.place(&self.screen, display, rng);
+            shown.push((bar, pos));
+        }
+        shown
+    }
+
+    /// Whole seconds of the break screen's minimum rest still to go, before
+    /// Space continues
+    pub fn rest_remaining(&self) -> Option<u32> {
+        if !self.is_on_break() {
+            return None;
+        }
+        let now_ns = self.timer.now();
+        let until_ns = self.rest_until_ns.filter(|&until_ns| until_ns > now_ns)?;
+        Some((until_ns - now_ns).div_ceil(1_000_000_000) as u32)
+    }
+
+    pub fn is_on_break(&self) -> bool {
+        self.current
+            .as_ref()
+            .is_some_and(|t| TrialState::Break == t.state)
+    }
+}
+
+/// Default stimulus set used when no sequence is configured
+impl<P, T, R> ExperimentStateMachine<P, StimulusType, T, R>
+where
+    P: Phase + Default,
+    T: Timer<Timestamp = u64>,
+    R: Rng + SeedableRng,
+{
+    /// Writes the session state to `path`. Call between trials; the trial in
+    /// progress is not saved and reruns on resume.
+    pub fn save_checkpoint(&mut self, path: &Path) -> Result<()> {
+        let rng_seed = self.rng.random();
+        self.rng = R::seed_from_u64(rng_seed);
+        Checkpoint {
+            session: self.session.clone(),
+            phase_index: match &self.phase_graph {
+                Some(graph) => graph.index_of(&self.phase).unwrap_or_default(),
+                None => phase_index(&self.phase),
+            },
+            trial_number: self.trial_number,
+            phase_trial_number: self.phase_trial_number,
+            current_block: self.current_block,
+            block_trial_number: self.block_trial_number,
+            practice_attempt: self.practice_attempt,
+            safe_margin_ns: self.safe_margin_ns,
+            rng_seed,
+            config: ConfigSnapshot::of(&self.config),
+            results: self.results.clone(),
+        }
+        .save(path)
+    }
+
+    /// Restores a session saved by `save_checkpoint` under the same
+    /// configuration. The next trial starts from a break screen; adaptive
+    /// procedures are replayed from the stored results.
+    pub fn resume_from_checkpoint(&mut self, path: &Path) -> Result<()> {
+        let checkpoint = Checkpoint::load(path)?;
+        if checkpoint.config != ConfigSnapshot::of(&self.config) {
+            bail!(
+                "Checkpoint {} was saved with a different configuration",
+                path.display()
+            );
+        }
+        let phase = match &self.phase_graph {
+            Some(graph) => graph.phases().nth(checkpoint.phase_index),
+            None => phase_at::<P>(checkpoint.phase_index),
+        };
+        let Some(phase) = phase else {
+            bail!("Checkpoint phase {} does not exist", checkpoint.phase_index);
+        };
+
+        self.phase = phase;
+        self.visited = vec![phase];
+        self.phase_span = phase_span(&self.phase);
+        self.session = checkpoint.session.or(self.session.take());
+        self.trial_number = checkpoint.trial_number;
+        self.phase_trial_number = checkpoint.phase_trial_number;
+        self.current_block = checkpoint.current_block;
+        self.block_trial_number = checkpoint.block_trial_number;
+        self.practice_attempt = checkpoint.practice_attempt;
+        self.safe_margin_ns = checkpoint.safe_margin_ns;
+        self.rng = R::seed_from_u64(checkpoint.rng_seed);
+        self.results = checkpoint.results;
+        self.current = None;
+        self.trial_span = Span::none();
+        self.paused_at = None;
+        self.interrupted = None;
+        self.waiting_for_scanner = false;
+
+        if let Some(adaptive) = &mut self.adaptive {
+            for result in &self.results {
+                if result.block.is_some()
+                    && result.intensity.is_some()
+                    && let Some(correct) = result.correct
+                {
+                    adaptive.procedure.update(correct);
+                }
+            }
+        }
+
+        info!(
+            "Resumed {:?} at trial {} from {}",
+            self.phase,
+            self.phase_trial_number + 1,
+            path.display()
+        );
+
+        if !(self.phase.is_practice() || self.phase.is_experiment()) {
+            self.calibrated = false;
+            self.calibration_progress = None;
+            self.awaiting_input = self.phase.is_welcome();
+            self.prepare_sequence();
+            return Ok(());
+        }
+
+        self.calibrated = true;
+        self.restore_sequence();
+        self.start_trial();
+        if let Some(trial) = &mut self.current {
+            trial.state = TrialState::Break;
+            self.awaiting_input = true;
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the phase's trial source without the trials already run
+    fn restore_sequence(&mut self) {
+        let generated_block = self.phase.is_experiment()
+            && self
+                .config
+                .blocks
+                .get(self.current_block)
+                .is_some_and(|b| !b.conditions.is_empty());
+
+        if generated_block {
+            let block = &self.config.blocks[self.current_block];
+            let remaining = block.trials.saturating_sub(self.block_trial_number).max(1);
+            match TrialSequence::builder()
+                .stimuli(block.conditions.iter().cloned())
+                .total_trials(remaining)
+                .build(&mut self.rng)
+            {
+                Ok(sequence) => self.sequence = Some(sequence),
+                Err(e) => warn!("Failed to build block sequence: {}", e),
+            }
+            return;
+        }
+
+        // Caller-supplied sequences are deterministic, so skip what was run
+        self.sequence = if self.phase.is_practice() {
+            self.practice_sequence.clone()
+        } else {
+            self.experiment_sequence.take()
+        };
+        if let Some(sequence) = &mut self.sequence {
+            for _ in 0..self.phase_trial_number {
+                sequence.next_trial();
+            }
+        }
+    }
+}
+
+fn standard_stimuli() -> Vec<StimulusType> {
+    vec![
+        StimulusType::Circle {
+            radius: 50.0,
+            color: [255, 0, 0, 255],
+        },
+        StimulusType::Rectangle {
+            width: 80.0,
+            height: 60.0,
+            color: [0, 255, 0, 255],
+        },
+        StimulusType::Arrow {
+            direction: ArrowDirection::Right,
+            size: 60.0,
+            color: [0, 0, 255, 255],
+        },
+    ]
+}
+
+fn phase_span<P: Phase>(phase: &P) -> Span {
+    info_span!(parent: None, "phase", phase = ?phase)
+}
+
+fn push_marker(outlets: &mut [Box<dyn MarkerOutlet>], event: EventMarker, timestamp_ns: u64) {
+    let marker = Marker::new(event, timestamp_ns);
+    for outlet in outlets {
+        if let Err(e) = outlet.push(&marker) {
+            warn!("Failed to push event marker: {}", e);
+        }
+    }
+}
+
+/// Scores a trial by its response key and, for clicks, distance to target
+fn score<T>(
+    mapping: &ResponseMapping<StimulusType>,
+    click_radius: Option<f32>,
+    trial: &Trial<StimulusType, T>,
+) -> bool {
+    let on_target = match (trial.click_position, click_radius) {
+        (Some(click), Some(radius)) => click_distance(click, trial.position) <= radius,
+        _ => true,
+    };
+    on_target && trial.expected_response(mapping) == trial.response_key
+}
+
+fn click_distance(click: (f32, f32), target: (f32, f32)) -> f32 {
+    (click.0 - target.0).hypot(click.1 - target.1)
+}
+
+/// Moves the rating marker, noting when relative to stimulus onset
+fn move_rating(trial: &mut Trial<StimulusType, u64>, rating: f32, timestamp_ns: u64) {
+    let onset_ns = trial.timestamps.stimulus_start.unwrap_or(timestamp_ns);
+    trial.rating = Some(rating);
+    trial
+        .rating_trajectory
+        .push((timestamp_ns.saturating_sub(onset_ns), rating));
+}
+
+/// Warning for presents running at another rate than the monitor reported
+fn refresh_mismatch(stats: &CalibrationStats) -> Option<String> {
+    let (Some(estimated), Some(reported)) = (stats.estimated_refresh_hz, stats.reported_refresh_hz)
+    else {
+        return None;
+    };
+    stats.refresh_mismatch().then(|| {
+        format!(
+            "Monitor reported {:.1} Hz but frames were presented at {:.1} Hz",
+            reported, estimated
+        )
+    })
 }
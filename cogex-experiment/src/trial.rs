@@ -1,11 +1,90 @@
-use cogex_core::Stimulus;
+use cogex_core::{ResponseKey, ResponseMapping, Stimulus};
 pub struct Trial<S: Stimulus, T> {
     pub id: usize,
+    pub condition: String,
     pub stimulus: S,
     pub position: (f32, f32),
+    /// The rest of a multi-stimulus display, shown with `stimulus`
+    pub array: Vec<(S, (f32, f32))>,
+    /// Stimuli are placed at their exact fractional position
+    pub subpixel: bool,
     pub durations: TrialDurations,
     pub timestamps: TrialTimestamps<T>,
     pub state: cogex_core::TrialState,
+    pub response_key: Option<cogex_core::ResponseKey>,
+    pub click_position: Option<(f32, f32)>,
+    pub intensity: Option<f64>,
+    /// Shown before the target on cued trials
+    pub cue: Option<Cue<S>>,
+    /// Per-trial override of the mapping's expected key
+    pub expected_key: Option<Option<ResponseKey>>,
+    /// Refreshes missed so far while the trial was on screen
+    pub dropped_frames: u64,
+    /// Refreshes the cue state has been on screen, for frame-counted cues
+    pub cue_frames: u64,
+    /// Fixation time beyond `fixation_ms` spent waiting for gaze on target
+    pub gaze_wait_ns: u64,
+    /// The response window closed without a response
+    pub timed_out: bool,
+    /// Marker position on a rating scale, 0 to 1, once placed
+    pub rating: Option<f32>,
+    /// Nanoseconds since onset and marker position after each move
+    pub rating_trajectory: Vec<(u64, f32)>,
+    /// Keys outside the response set pressed during the response window
+    pub invalid_keys: Vec<ResponseKey>,
+    /// The window lost focus or left fullscreen during the trial
+    pub interrupted: bool,
+}
+
+/// Stimulus presented ahead of the target, e.g. a spatial cue or a prime
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue<S> {
+    pub stimulus: S,
+    pub position: (f32, f32),
+    /// Shown at `position` from the cue's offset until the target, e.g. a
+    /// pattern mask after a prime
+    pub mask: Option<S>,
+    /// Cue and mask durations counted in presented frames, in place of
+    /// `cue_duration_ms` and the cue-target SOA
+    pub frames: Option<CueFrames>,
+}
+
+impl<S> Cue<S> {
+    pub fn new(stimulus: S, position: (f32, f32)) -> Self {
+        Self {
+            stimulus,
+            position,
+            mask: None,
+            frames: None,
+        }
+    }
+
+    pub fn with_mask(mut self, mask: S) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Shows the cue for exactly `cue` refreshes and the mask, or a blank,
+    /// for the following `mask`, then the target
+    pub fn with_frames(mut self, cue: u64, mask: u64) -> Self {
+        self.frames = Some(CueFrames { cue, mask });
+        self
+    }
+}
+
+/// Refreshes a frame-counted cue and its mask stay on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CueFrames {
+    pub cue: u64,
+    pub mask: u64,
+}
+
+impl<S: Stimulus, T> Trial<S, T> {
+    /// Key this trial expects, `None` when the response must be withheld
+    pub fn expected_response(&self, mapping: &ResponseMapping<S>) -> Option<ResponseKey> {
+        self.expected_key
+            .unwrap_or_else(|| mapping.expected_key(&self.stimulus))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -14,12 +93,27 @@ pub struct TrialDurations {
     pub stimulus_ms: u64,
     pub response_window_ms: u64,
     pub feedback_ms: u64,
+    pub cue_ms: u64,
+    pub cue_target_soa_ms: u64,
+    /// Pause after the trial ends
+    pub inter_trial_interval_ms: u64,
 }
 
 #[derive(Debug, Clone)]
 pub struct TrialTimestamps<T> {
     pub start: T,
     pub fixation_start: T,
+    pub cue_start: Option<T>,
     pub stimulus_start: Option<T>,
     pub response: Option<T>,
+    /// First speech onset picked up by a voice key
+    pub voice_onset: Option<T>,
+    /// Presentation times of the first frame showing each event
+    pub fixation_presented: Option<T>,
+    pub cue_presented: Option<T>,
+    /// First frame after a frame-counted cue, showing its mask
+    pub cue_offset_presented: Option<T>,
+    pub stimulus_presented: Option<T>,
+    pub stimulus_offset_presented: Option<T>,
+    pub feedback_presented: Option<T>,
 }
@@ -0,0 +1,66 @@
+use cogex_experiment::{AdaptiveProcedure, Quest, Staircase};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Feeds `pattern`, `c` for correct and `w` for wrong, into `procedure`
+fn answer(procedure: &mut impl AdaptiveProcedure, pattern: &str) {
+    for c in pattern.chars() {
+        procedure.update(c == 'c');
+    }
+}
+
+#[test]
+fn one_up_two_down_reverses_where_the_direction_turns() {
+    let mut staircase = Staircase::one_up_two_down(10.0, 1.0)
+        .with_max_reversals(3)
+        .with_threshold_reversals(2);
+    assert_eq!(staircase.threshold(), None);
+
+    answer(&mut staircase, "ccccw");
+    assert_eq!(staircase.intensity(), 9.0);
+    assert_eq!(staircase.reversals(), [8.0]);
+    answer(&mut staircase, "wcccc");
+    assert_eq!(staircase.intensity(), 8.0);
+    assert!(!staircase.is_finished());
+    answer(&mut staircase, "w");
+
+    assert_eq!(staircase.reversals(), [8.0, 10.0, 8.0]);
+    assert!(staircase.is_finished());
+    assert_eq!(staircase.threshold(), Some(9.0));
+    assert_eq!(staircase.trial_count(), 11);
+}
+
+#[test]
+fn staircase_stays_within_its_bounds() {
+    let mut staircase = Staircase::new(5.0, 1.0, 1, 1).with_bounds(0.0, 2.0);
+    assert_eq!(staircase.intensity(), 2.0);
+    answer(&mut staircase, "ccc");
+    assert_eq!(staircase.intensity(), 0.0);
+    answer(&mut staircase, "wwww");
+    assert_eq!(staircase.intensity(), 2.0);
+}
+
+#[test]
+fn quest_converges_on_a_simulated_observers_threshold() {
+    let threshold = -1.0;
+    let mut quest = Quest::two_afc(-0.5, 0.5).unwrap();
+    let prior_sd = quest.sd();
+    let observer = quest.clone();
+    let mut rng = StdRng::seed_from_u64(3);
+    for _ in 0..200 {
+        let p = observer.p_correct(quest.intensity(), threshold);
+        quest.update(rng.random_bool(p));
+    }
+
+    let estimate = quest.threshold().unwrap();
+    assert!((estimate - threshold).abs() < 0.1, "{}", estimate);
+    assert!(quest.sd() < prior_sd / 4.0);
+    assert_eq!(quest.trial_count(), 200);
+}
+
+#[test]
+fn quest_rejects_a_prior_without_width() {
+    for sd in [0.0, -0.5, f64::NAN] {
+        assert!(Quest::two_afc(-1.0, sd).is_err(), "{}", sd);
+    }
+}
@@ -0,0 +1,186 @@
+use cogex_core::{StandardPhase, StimulusType, Theme};
+use cogex_experiment::{
+    ExperimentConfig, Interval, ItiDisplay, Layout, MonitorSelection, Screen, Side,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::PathBuf;
+
+type Config = ExperimentConfig<StandardPhase>;
+
+/// Writes `text` to a temporary file and loads it
+fn load(name: &str, text: &str) -> anyhow::Result<Config> {
+    let path: PathBuf =
+        std::env::temp_dir().join(format!("cogex_config_{}_{}.toml", name, std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    let config = Config::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    config
+}
+
+#[test]
+fn partial_config_keeps_defaults_for_missing_fields() {
+    let config = load(
+        "partial",
+        r#"
+practice_trials = 4
+task = "flanker"
+inter_trial_interval = { Uniform = { min_ms = 500, max_ms = 900 } }
+iti_display = { Stimulus = { Circle = { radius = 4.0, color = [255, 255, 255, 255] } } }
+
+[[blocks]]
+trials = 6
+"#,
+    )
+    .unwrap();
+    let defaults = Config::default();
+    assert_eq!(config.practice_trials, 4);
+    assert_eq!(config.task, "flanker");
+    assert_eq!(
+        config.inter_trial_interval,
+        Interval::Uniform {
+            min_ms: 500,
+            max_ms: 900
+        }
+    );
+    assert_eq!(
+        config.iti_display,
+        ItiDisplay::Stimulus(StimulusType::Circle {
+            radius: 4.0,
+            color: [255, 255, 255, 255]
+        })
+    );
+    assert_eq!(config.total_experiment_trials(), 6);
+    assert!(config.blocks[0].break_after);
+    assert_eq!(config.response_window_ms, defaults.response_window_ms);
+    assert_eq!(config.abort_hotkey, defaults.abort_hotkey);
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    assert!(load("unknown", "practice_trails = 4").is_err());
+    assert!(load("range", "fixation_range_ms = [900, 100]").is_err());
+    assert!(load(
+        "criterion",
+        "practice_criterion = { min_accuracy = 1.5, max_repeats = 2 }"
+    )
+    .is_err());
+    assert!(load("block", "[[blocks]]\ntrials = 0").is_err());
+    assert!(load("calibration", "[calibration]\nmax_seconds = 0.0").is_err());
+    assert!(Config::default().validate().is_ok());
+}
+
+#[test]
+fn windows_are_placed_on_monitors_chosen_by_name_or_index() {
+    let config = load(
+        "monitors",
+        r#"
+monitor = { Name = "DELL U2720Q" }
+experimenter_monitor = { Index = 0 }
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.monitor, MonitorSelection::Name("DELL U2720Q".into()));
+    assert_eq!(
+        config.experimenter_monitor,
+        Some(MonitorSelection::Index(0))
+    );
+
+    let defaults = Config::default();
+    assert_eq!(defaults.monitor, MonitorSelection::Primary);
+    assert_eq!(defaults.experimenter_monitor, None);
+}
+
+#[test]
+fn a_partial_theme_keeps_the_default_colours() {
+    let config = load(
+        "theme",
+        r#"
+[theme]
+background = [128, 128, 128, 255]
+correct = [0, 200, 0, 255]
+"#,
+    )
+    .unwrap();
+    let defaults = Theme::default();
+    assert_eq!(config.theme.background, [128, 128, 128, 255]);
+    assert_eq!(config.theme.correct, [0, 200, 0, 255]);
+    assert_eq!(config.theme.foreground, defaults.foreground);
+    assert_eq!(config.theme.circle, defaults.circle);
+    assert!(load("theme_unknown", "[theme]\nforground = [0, 0, 0, 255]").is_err());
+}
+
+#[test]
+fn the_preintern_plan_covers_each_phase_and_the_response_window() {
+    let config = load(
+        "preintern",
+        r#"
+practice_trials = 12
+response_window_ms = 1500
+feedback_show_rt = true
+
+[[blocks]]
+trials = 40
+
+[[blocks]]
+trials = 40
+"#,
+    )
+    .unwrap();
+    let plan = config.preintern_plan();
+    assert_eq!(plan.progress_totals, [12, 80]);
+    assert_eq!(plan.feedback_rt_max_ms, Some(1500));
+    assert_eq!(Config::default().preintern_plan().feedback_rt_max_ms, None);
+}
+
+#[test]
+fn layouts_place_stimuli_by_condition_on_the_actual_screen() {
+    let config = load(
+        "layouts",
+        r#"
+[layouts]
+layout = "Center"
+conditions = [
+    ["left", { Hemifield = { side = "Left", eccentricity = 200.0 } }],
+    ["ring", { Annulus = { radius = 100.0, positions = 4 } }],
+]
+"#,
+    )
+    .unwrap();
+    let screen = Screen::new(2560, 1440, 2.0);
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut place = |condition: &str| {
+        config
+            .layouts
+            .layout_for(condition)
+            .place(&screen, None, &mut rng)
+    };
+    assert_eq!(place("other"), (640.0, 360.0));
+    assert_eq!(place("left"), (440.0, 360.0));
+    for _ in 0..8 {
+        let (x, y) = place("ring");
+        let slot = ((x - 640.0).round(), (y - 360.0).round());
+        assert!([(0.0, -100.0), (100.0, 0.0), (0.0, 100.0), (-100.0, 0.0)].contains(&slot));
+    }
+
+    let random = Layout::Random { margin: 0.25 };
+    for _ in 0..20 {
+        let (x, y) = random.place(&screen, None, &mut rng);
+        assert!((320.0..=960.0).contains(&x) && (180.0..=540.0).contains(&y));
+    }
+    let hemifield = Layout::Hemifield {
+        side: Side::Right,
+        eccentricity: 5.0,
+    };
+    let display = Default::default();
+    assert_eq!(
+        hemifield.place(&screen, Some(&display), &mut rng),
+        (5.0, 0.0)
+    );
+
+    let empty_grid = r#"
+[layouts]
+layout = { Grid = { columns = 0, rows = 3, spacing = 50.0 } }
+"#;
+    assert!(load("empty_grid", empty_grid).is_err());
+}
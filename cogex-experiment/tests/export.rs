@@ -0,0 +1,196 @@
+use cogex_core::{PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult};
+use cogex_experiment::{
+    bids_events_sidecar, bids_events_tsv, results_to_json, write_bids_events, OutputManager,
+    SessionInfo,
+};
+
+fn result(trial_id: usize, key: Option<ResponseKey>) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id,
+        stimulus: StimulusType::Circle {
+            radius: 40.0,
+            color: [255, 0, 0, 255],
+        },
+        condition: "circle".to_string(),
+        set_size: 1,
+        response_key: key,
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: key.map(|_| 412_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: Some(key.is_some()),
+        outcome: if key.is_some() {
+            TrialOutcome::Response
+        } else {
+            TrialOutcome::Timeout
+        },
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 1_000_000_000,
+        presentation: PresentationTimestamps {
+            fixation_onset_ns: Some(100_000_000),
+            stimulus_onset_ns: Some(600_000_000),
+            ..PresentationTimestamps::default()
+        },
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 200,
+        requested_response_window_ms: 1000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn results_carry_the_participants_session_as_their_header() {
+    let session = SessionInfo::new("P01", Some(27), 2);
+    let results = [result(0, Some(ResponseKey::Space)), result(1, None)];
+    let json = results_to_json(Some(&session), &results);
+
+    let metadata = &json["metadata"];
+    assert_eq!(metadata["participant_id"], "P01");
+    assert_eq!(metadata["age"], 27);
+    assert_eq!(metadata["session"], 2);
+    assert_eq!(metadata["started_at"], session.started_at);
+    assert_eq!(metadata["aborted"], false);
+    assert!(metadata.get("abort_reason").is_none());
+
+    let trials = json["trials"].as_array().unwrap();
+    assert_eq!(trials.len(), 2);
+    assert_eq!(trials[0]["response_key"], "Space");
+    assert_eq!(trials[0]["reaction_time_ns"], 412_000_000);
+    assert_eq!(trials[0]["presentation"]["stimulus_onset_ns"], 600_000_000);
+    assert_eq!(trials[1]["outcome"], "timeout");
+    assert!(trials[1]["response_key"].is_null());
+
+    assert!(results_to_json::<StimulusType>(None, &[])["metadata"].is_null());
+}
+
+#[test]
+fn participant_ids_are_made_safe_for_file_names() {
+    assert_eq!(SessionInfo::new("P01", None, 1).file_stem(), "P01_s1");
+    // One underscore per character outside [A-Za-z0-9_-]
+    assert_eq!(
+        SessionInfo::new("../é x/1", None, 3).file_stem(),
+        "_____x_1_s3"
+    );
+}
+
+#[test]
+fn bids_events_time_each_screen_from_its_presented_frame() {
+    let mut answered = result(0, Some(ResponseKey::Space));
+    answered.presentation.stimulus_offset_ns = Some(800_000_000);
+    answered.presentation.feedback_onset_ns = Some(1_000_000_000);
+    let mut missed = result(1, None);
+    missed.presentation.fixation_onset_ns = Some(2_100_000_000);
+    missed.presentation.stimulus_onset_ns = Some(2_600_000_000);
+
+    let tsv = bids_events_tsv(&[answered, missed], &[], None);
+    let rows: Vec<&str> = tsv.lines().collect();
+    assert_eq!(
+        rows,
+        [
+            "onset\tduration\ttrial_type\tresponse_time\ttrial_id\tresponse\tcorrect",
+            "0.000000\t0.500000\tfixation\tn/a\t0\tn/a\tn/a",
+            "0.500000\t0.200000\tcircle\t0.412000\t0\tSpace\t1",
+            "0.900000\t0.300000\tfeedback\tn/a\t0\tn/a\tn/a",
+            "2.000000\t0.500000\tfixation\tn/a\t1\tn/a\tn/a",
+            "2.500000\tn/a\tcircle\tn/a\t1\tn/a\t0",
+        ]
+    );
+
+    // An explicit zero, such as the first scanner trigger, shifts every onset
+    let tsv = bids_events_tsv(&[result(0, None)], &[], Some(0));
+    assert!(tsv.lines().nth(1).unwrap().starts_with("0.100000\t"));
+}
+
+#[test]
+fn bids_files_are_named_by_subject_session_and_task() {
+    let root = std::env::temp_dir().join(format!("cogex_bids_{}", std::process::id()));
+    let session = SessionInfo::new("P-01", None, 2);
+    let output = OutputManager::new(&root, "bids", Some(&session)).unwrap();
+    let results = [result(0, Some(ResponseKey::Space))];
+    let tsv = write_bids_events(&output, Some(&session), "go/no-go", &results, &[], None).unwrap();
+    assert_eq!(
+        tsv.file_name().unwrap(),
+        "sub-P01_ses-2_task-gonogo_events.tsv"
+    );
+    let sidecar =
+        std::fs::read_to_string(tsv.with_file_name("sub-P01_ses-2_task-gonogo_events.json"))
+            .unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+    let sidecar: serde_json::Value = serde_json::from_str(&sidecar).unwrap();
+    assert_eq!(sidecar, bids_events_sidecar());
+    assert_eq!(sidecar["onset"]["Units"], "s");
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_batches_hold_one_column_per_field() {
+    use arrow_array::{Array, StringArray, UInt64Array};
+
+    let mut clicked = result(1, None);
+    clicked.click_position = Some((12.5, -3.0));
+    let batch =
+        cogex_experiment::results_to_record_batch(&[result(0, Some(ResponseKey::Space)), clicked])
+            .unwrap();
+    assert_eq!(batch.num_rows(), 2);
+
+    let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+    let rts = column("reaction_time_ns");
+    let rts = rts.as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(rts.value(0), 412_000_000);
+    assert!(rts.is_null(1));
+    let keys = column("response_key");
+    let keys = keys.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(keys.value(0), "Space");
+    assert_eq!(column("click_x").null_count(), 1);
+    assert_eq!(column("click_y").null_count(), 1);
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn parquet_files_read_back_with_the_session_metadata() {
+    use cogex_experiment::write_results_parquet;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let root = std::env::temp_dir().join(format!("cogex_parquet_{}", std::process::id()));
+    let session = SessionInfo::new("P01", None, 1);
+    let output = OutputManager::new(&root, "export", Some(&session)).unwrap();
+    let results: Vec<_> = (0..100)
+        .map(|i| result(i, Some(ResponseKey::Space)))
+        .collect();
+    let path = write_results_parquet(&output, Some(&session), &results).unwrap();
+    assert_eq!(path.extension().unwrap(), "parquet");
+
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+    let metadata = builder
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .unwrap();
+    let session_json = metadata
+        .iter()
+        .find(|kv| kv.key == "cogex.session")
+        .and_then(|kv| kv.value.as_deref())
+        .unwrap();
+    let session_json: serde_json::Value = serde_json::from_str(session_json).unwrap();
+    assert_eq!(session_json["participant_id"], "P01");
+    let rows: usize = builder
+        .build()
+        .unwrap()
+        .map(|b| b.unwrap().num_rows())
+        .sum();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(rows, 100);
+}
@@ -0,0 +1,117 @@
+use cogex_core::{PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult};
+use cogex_experiment::{d_prime, GoNoGo, GoNoGoScore, Paradigm};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn result(condition: &str, rt_ms: Option<u64>) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: GoNoGo::default().go,
+        condition: condition.to_string(),
+        set_size: 1,
+        response_key: rt_ms.map(|_| ResponseKey::Space),
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: rt_ms.map(|rt| rt * 1_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: None,
+        outcome: if rt_ms.is_some() {
+            TrialOutcome::Response
+        } else {
+            TrialOutcome::Timeout
+        },
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 200,
+        requested_response_window_ms: 1000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn no_go_trials_make_up_the_configured_share() {
+    let task = GoNoGo::default().with_no_go_proportion(0.2);
+    let sequence = task.sequence(40, &mut StdRng::seed_from_u64(7)).unwrap();
+    let no_go = sequence
+        .iter()
+        .filter(|t| t.condition == GoNoGo::NO_GO)
+        .inspect(|t| assert_eq!(t.stimulus, task.no_go))
+        .count();
+    assert_eq!(sequence.remaining(), 40);
+    assert_eq!(no_go, 8);
+
+    for proportion in [-0.1, 1.0] {
+        let task = GoNoGo::default().with_no_go_proportion(proportion);
+        assert!(task.sequence(40, &mut StdRng::seed_from_u64(7)).is_err());
+    }
+}
+
+#[test]
+fn only_the_go_stimulus_expects_a_response() {
+    let task = GoNoGo::default().with_key(ResponseKey::Char('j'));
+    let mapping = task.response_mapping();
+    assert_eq!(mapping.expected_key(&task.go), Some(ResponseKey::Char('j')));
+    assert_eq!(mapping.expected_key(&task.no_go), None);
+    // Pressing on a no-go trial is an error, withholding is correct
+    assert!(!mapping.is_correct(&task.no_go, Some(ResponseKey::Char('j'))));
+    assert!(mapping.is_correct(&task.no_go, None));
+}
+
+#[test]
+fn responses_on_no_go_trials_are_commission_errors() {
+    let results = [
+        result(GoNoGo::GO, Some(300)),
+        result(GoNoGo::GO, Some(400)),
+        result(GoNoGo::GO, Some(500)),
+        result(GoNoGo::GO, None),
+        result(GoNoGo::NO_GO, Some(250)),
+        result(GoNoGo::NO_GO, None),
+        result(GoNoGo::NO_GO, None),
+        result(GoNoGo::NO_GO, None),
+    ];
+    let task = GoNoGo::default();
+    let score = task.score(&results);
+    assert_eq!(
+        score,
+        GoNoGoScore {
+            hits: 3,
+            misses: 1,
+            commission_errors: 1,
+            correct_rejections: 3,
+            mean_hit_rt_ms: Some(400.0),
+        }
+    );
+    assert_eq!(score.hit_rate(), Some(0.75));
+    assert_eq!(score.false_alarm_rate(), Some(0.25));
+    // Symmetric rates give z(H) = -z(FA)
+    assert!((score.d_prime() - 2.0 * 0.5244).abs() < 1e-3);
+    assert_eq!(score.d_prime(), d_prime(3, 1, 1, 3));
+
+    let summary = task.summary(&results);
+    assert_eq!(
+        summary[..3],
+        ["Hits: 3/4", "Commission errors: 1/4", "Mean RT: 400 ms"]
+    );
+    assert_eq!(summary[3], format!("d′ = {:.2}", score.d_prime()));
+}
+
+#[test]
+fn d_prime_stays_finite_at_perfect_rates() {
+    let perfect = d_prime(20, 0, 0, 20);
+    assert!(perfect.is_finite() && perfect > 3.0);
+    assert_eq!(d_prime(10, 10, 10, 10), 0.0);
+}
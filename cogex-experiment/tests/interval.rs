@@ -0,0 +1,74 @@
+use cogex_experiment::Interval;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn samples(interval: Interval) -> Vec<u64> {
+    let mut rng = StdRng::seed_from_u64(5);
+    (0..5000).map(|_| interval.sample(&mut rng)).collect()
+}
+
+fn mean(samples: &[u64]) -> f64 {
+    samples.iter().sum::<u64>() as f64 / samples.len() as f64
+}
+
+#[test]
+fn uniform_intervals_cover_their_range() {
+    let drawn = samples(Interval::Uniform {
+        min_ms: 500,
+        max_ms: 900,
+    });
+    assert_eq!(drawn.iter().min(), Some(&500));
+    assert_eq!(drawn.iter().max(), Some(&900));
+    assert!((mean(&drawn) - 700.0).abs() < 10.0);
+    // Reversed bounds are taken as the same range
+    let reversed = samples(Interval::Uniform {
+        min_ms: 900,
+        max_ms: 500,
+    });
+    assert!(reversed.iter().all(|ms| (500..=900).contains(ms)));
+}
+
+#[test]
+fn exponential_intervals_are_shifted_and_truncated() {
+    let interval = Interval::Exponential {
+        min_ms: 2000,
+        scale_ms: 1000.0,
+        max_ms: 8000,
+    };
+    let drawn = samples(interval);
+    assert!(drawn.iter().all(|ms| (2000..=8000).contains(ms)));
+    // Mean of an exponential with scale 1 s truncated at 6 s, plus 2 s
+    let expected = 2000.0 + 1000.0 - 6000.0 / (6f64.exp() - 1.0);
+    assert!((mean(&drawn) - expected).abs() < 40.0, "{}", mean(&drawn));
+    // Short intervals are the most frequent
+    let short = drawn.iter().filter(|&&ms| ms < 3000).count();
+    assert!(short > drawn.len() / 2);
+    assert_eq!(interval.min_ms(), 2000);
+}
+
+#[test]
+fn truncated_normal_intervals_stay_within_bounds() {
+    let drawn = samples(Interval::TruncatedNormal {
+        mean_ms: 1000.0,
+        sd_ms: 300.0,
+        min_ms: 800,
+        max_ms: 1500,
+    });
+    assert!(drawn.iter().all(|ms| (800..=1500).contains(ms)));
+    assert!(mean(&drawn) > 1000.0);
+
+    let degenerate = Interval::TruncatedNormal {
+        mean_ms: 5000.0,
+        sd_ms: 0.0,
+        min_ms: 800,
+        max_ms: 1500,
+    };
+    assert_eq!(samples(degenerate)[0], 1500);
+}
+
+#[test]
+fn fixed_intervals_never_vary() {
+    assert!(samples(Interval::Fixed(1200)).iter().all(|&ms| ms == 1200));
+    assert_eq!(Interval::from(300), Interval::Fixed(300));
+    assert_eq!(Interval::default().min_ms(), 0);
+}
@@ -0,0 +1,64 @@
+use cogex_core::{ResponseKey, StimulusType};
+use cogex_experiment::{LexicalDecision, Paradigm};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn list(name: &str, text: &str) -> std::path::PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("cogex_lexical_{}_{}.txt", name, std::process::id()));
+    std::fs::write(&path, text).unwrap();
+    path
+}
+
+fn content(stimulus: &StimulusType) -> String {
+    match stimulus {
+        StimulusType::Text { content, .. } => content.to_string(),
+        other => panic!("Expected text, got {:?}", other),
+    }
+}
+
+#[test]
+fn lists_load_skipping_blanks_and_comments() {
+    let words = list("words", "# frequent\nHOUSE\n\n  TABLE \nGARDEN\n");
+    let nonwords = list("nonwords", "HOUTE\nBALTE\nGORDEN\n");
+    let task = LexicalDecision::load(&words, &nonwords).unwrap();
+    std::fs::remove_file(words).unwrap();
+    std::fs::remove_file(nonwords).unwrap();
+
+    assert_eq!(task.words, ["HOUSE", "TABLE", "GARDEN"]);
+    assert_eq!(task.nonwords, ["HOUTE", "BALTE", "GORDEN"]);
+    assert!(LexicalDecision::load("/nonexistent/words.txt", "/nonexistent/nonwords.txt").is_err());
+}
+
+#[test]
+fn trials_balance_lexicality_and_map_it_to_two_keys() {
+    let task = LexicalDecision::new(
+        vec!["HOUSE".into(), "TABLE".into(), "GARDEN".into()],
+        vec!["HOUTE".into(), "BALTE".into()],
+    );
+    let mapping = task.response_mapping();
+    let mut sequence = task.sequence(12, &mut StdRng::seed_from_u64(3)).unwrap();
+
+    let mut shown = Vec::new();
+    while let Some(trial) = sequence.next_trial() {
+        let item = content(&trial.stimulus);
+        let (list, key) = match trial.condition.as_str() {
+            LexicalDecision::WORD => (&task.words, ResponseKey::Char('j')),
+            _ => (&task.nonwords, ResponseKey::Char('f')),
+        };
+        assert!(list.contains(&item));
+        assert_eq!(mapping.expected_key(&trial.stimulus), Some(key));
+        shown.push((trial.condition, item));
+    }
+    let words: Vec<_> = shown.iter().filter(|(c, _)| c == "word").collect();
+    assert_eq!(words.len(), 6);
+    // Every word shows twice before any shows a third time
+    for word in &task.words {
+        assert_eq!(words.iter().filter(|(_, item)| item == word).count(), 2);
+    }
+
+    let overlapping = LexicalDecision::new(vec!["HOUSE".into()], vec!["HOUSE".into()]);
+    assert!(overlapping
+        .sequence(4, &mut StdRng::seed_from_u64(3))
+        .is_err());
+}
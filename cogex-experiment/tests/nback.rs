@@ -0,0 +1,119 @@
+use cogex_core::{
+    PresentationTimestamps, ResponseKey, StandardPhase, StimulusType, TrialOutcome, TrialResult,
+};
+use cogex_experiment::{ExperimentConfig, NBack, NBackScore, Paradigm};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn result(condition: &str, rt_ms: Option<u64>) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: NBack::default().stimuli[0].clone(),
+        condition: condition.to_string(),
+        set_size: 1,
+        response_key: rt_ms.map(|_| ResponseKey::Space),
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: rt_ms.map(|rt| rt * 1_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: None,
+        outcome: if rt_ms.is_some() {
+            TrialOutcome::Response
+        } else {
+            TrialOutcome::Timeout
+        },
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 500,
+        requested_response_window_ms: 2000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn targets_are_exactly_the_trials_repeating_the_one_n_back() {
+    for n in [1, 2, 3] {
+        let task = NBack::default().with_target_proportion(0.4);
+        let task = NBack { n, ..task };
+        let trials: Vec<_> = task
+            .sequence(60, &mut StdRng::seed_from_u64(n as u64))
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+        let mut targets = 0;
+        for (i, trial) in trials.iter().enumerate() {
+            let repeats = i >= n && trial.stimulus == trials[i - n].stimulus;
+            assert_eq!(trial.condition == NBack::TARGET, repeats, "n={} i={}", n, i);
+            let expected = repeats.then_some(ResponseKey::Space);
+            assert_eq!(trial.expected_key, Some(expected));
+            targets += usize::from(repeats);
+        }
+        assert!((10..=36).contains(&targets), "{} targets", targets);
+    }
+}
+
+#[test]
+fn n_comes_from_the_config_and_must_be_positive() {
+    let mut config = ExperimentConfig::<StandardPhase>::default();
+    config.n_back = 3;
+    assert_eq!(NBack::from_config(&config).n, 3);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    assert!(NBack::new(0, NBack::default().stimuli)
+        .sequence(10, &mut rng)
+        .is_err());
+    assert!(NBack::new(2, NBack::default().stimuli[..1].to_vec())
+        .sequence(10, &mut rng)
+        .is_err());
+    let task = NBack::default().with_target_proportion(1.2);
+    assert!(task.sequence(10, &mut rng).is_err());
+}
+
+#[test]
+fn responses_score_as_hits_and_false_alarms() {
+    let results = [
+        result(NBack::TARGET, Some(450)),
+        result(NBack::TARGET, Some(550)),
+        result(NBack::TARGET, None),
+        result(NBack::NON_TARGET, Some(300)),
+        result(NBack::NON_TARGET, None),
+        result(NBack::NON_TARGET, None),
+    ];
+    let task = NBack::default();
+    let score = task.score(&results);
+    assert_eq!(
+        score,
+        NBackScore {
+            n: 2,
+            hits: 2,
+            misses: 1,
+            false_alarms: 1,
+            correct_rejections: 2,
+            mean_hit_rt_ms: Some(500.0),
+        }
+    );
+    assert_eq!(
+        task.summary(&results)[..4],
+        [
+            "2-back",
+            "Hits: 2/3",
+            "False alarms: 1/3",
+            "Mean RT: 500 ms"
+        ]
+    );
+}
@@ -0,0 +1,76 @@
+use cogex_core::{ArrowDirection, ResponseKey, StimulusType};
+use cogex_experiment::{CueKind, Paradigm, Posner};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Side a cue points at, `None` for a neutral one
+fn cued_side(task: &Posner, stimulus: &StimulusType, position: (f32, f32)) -> Option<(f32, f32)> {
+    match (task.cue, stimulus) {
+        (CueKind::Central, StimulusType::Arrow { direction, .. }) => Some(match direction {
+            ArrowDirection::Left => task.left,
+            _ => task.right,
+        }),
+        (CueKind::Central, _) => None,
+        (CueKind::Peripheral, _) => (position != task.centre).then_some(position),
+    }
+}
+
+#[test]
+fn cue_validity_follows_the_condition() {
+    for kind in [CueKind::Central, CueKind::Peripheral] {
+        let task = Posner::default().with_cue(kind);
+        let sequence = task.sequence(40, &mut StdRng::seed_from_u64(2)).unwrap();
+        let mut counts = [0; 3];
+        for trial in sequence.iter() {
+            let position = trial.position.unwrap();
+            let cue = trial.cue.as_ref().unwrap();
+            let cued = cued_side(&task, &cue.stimulus, cue.position);
+            let condition = match trial.condition.as_str() {
+                Posner::VALID => 0,
+                Posner::INVALID => 1,
+                _ => 2,
+            };
+            match condition {
+                0 => assert_eq!(cued, Some(position)),
+                1 => assert!(cued.is_some() && cued != Some(position)),
+                _ => assert_eq!(cued, None),
+            }
+            counts[condition] += 1;
+            let key = if position == task.left {
+                ResponseKey::ArrowLeft
+            } else {
+                ResponseKey::ArrowRight
+            };
+            assert_eq!(trial.expected_key, Some(Some(key)));
+        }
+        // 60/20/20 by default
+        assert_eq!(counts, [24, 8, 8]);
+    }
+}
+
+#[test]
+fn validity_weights_set_the_trial_mix() {
+    let task = Posner::default().with_validity(0.8, 0.2, 0.0);
+    let sequence = task.sequence(20, &mut StdRng::seed_from_u64(4)).unwrap();
+    let valid = sequence
+        .iter()
+        .filter(|t| t.condition == Posner::VALID)
+        .count();
+    assert_eq!(valid, 16);
+    assert!(sequence.iter().all(|t| t.condition != Posner::NEUTRAL));
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let negative = Posner::default().with_validity(1.0, -0.1, 0.0);
+    assert!(negative.sequence(10, &mut rng).is_err());
+    let none = Posner::default().with_validity(0.0, 0.0, 0.0);
+    assert!(none.sequence(10, &mut rng).is_err());
+}
+
+#[test]
+fn degree_layouts_centre_on_the_line_of_sight() {
+    let task = Posner::in_degrees(8.0);
+    assert_eq!(
+        (task.centre, task.left, task.right),
+        ((0.0, 0.0), (-8.0, 0.0), (8.0, 0.0))
+    );
+}
@@ -0,0 +1,51 @@
+use cogex_core::{ResponseKey, StimulusType};
+use cogex_experiment::{CueFrames, MaskedPriming, Paradigm};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn digit(stimulus: &StimulusType) -> u32 {
+    match stimulus {
+        StimulusType::Text { content, .. } => content.parse().unwrap(),
+        other => panic!("Expected a digit, got {:?}", other),
+    }
+}
+
+#[test]
+fn primes_are_masked_for_the_configured_frames() {
+    let task = MaskedPriming::default().with_frames(2, 5);
+    let sequence = task.sequence(32, &mut StdRng::seed_from_u64(9)).unwrap();
+    let mapping = task.response_mapping();
+
+    let mut congruent = 0;
+    for trial in sequence.iter() {
+        let cue = trial.cue.as_ref().unwrap();
+        assert_eq!(cue.frames, Some(CueFrames { cue: 2, mask: 5 }));
+        assert!(matches!(
+            &cue.mask,
+            Some(StimulusType::Text { content, .. }) if &**content == "#####"
+        ));
+        let (prime, target) = (digit(&cue.stimulus), digit(&trial.stimulus));
+        let same_side = (prime < 5) == (target < 5);
+        assert_eq!(trial.condition == MaskedPriming::CONGRUENT, same_side);
+        congruent += same_side as usize;
+
+        let expected = if target < 5 {
+            ResponseKey::Char('f')
+        } else {
+            ResponseKey::Char('j')
+        };
+        assert_eq!(mapping.expected_key(&trial.stimulus), Some(expected));
+    }
+    assert_eq!(congruent, 16);
+
+    let mut rng = StdRng::seed_from_u64(9);
+    assert!(MaskedPriming::default()
+        .with_frames(0, 5)
+        .sequence(8, &mut rng)
+        .is_err());
+    let one_sided = MaskedPriming {
+        digits: vec![1, 4],
+        ..MaskedPriming::default()
+    };
+    assert!(one_sided.sequence(8, &mut rng).is_err());
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "script")]
+
+use cogex_core::{PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult};
+use cogex_experiment::{Paradigm, ScriptParadigm};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::Path;
+
+const GO_NOGO: &str = r#"
+fn keys() { ["space"] }
+
+fn trials(n) {
+    let trials = [];
+    for i in 0..n {
+        if i % 4 == 0 {
+            trials.push(#{
+                condition: "nogo",
+                stimulus: #{ Circle: #{ radius: 40.0, color: [220, 30, 30, 255] } },
+                key: (),
+            });
+        } else {
+            trials.push(#{
+                condition: "go",
+                stimulus: #{ Circle: #{ radius: 40.0, color: [30, 180, 30, 255] } },
+                key: "space",
+                response_window_ms: 800,
+            });
+        }
+    }
+    trials.shuffle();
+    trials
+}
+
+fn summary(results) {
+    let hits = results.filter(|r| r.condition == "go" && r.correct == true).len();
+    [`Hits: ${hits}`]
+}
+
+fn messages() { #{ "feedback-correct": "Well done!" } }
+"#;
+
+fn result(condition: &str, key: Option<ResponseKey>, correct: bool) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: StimulusType::Circle {
+            radius: 40.0,
+            color: [30, 180, 30, 255],
+        },
+        condition: condition.to_string(),
+        set_size: 1,
+        response_key: key,
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: key.map(|_| 400_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: Some(correct),
+        outcome: if key.is_some() {
+            TrialOutcome::Response
+        } else {
+            TrialOutcome::Timeout
+        },
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 200,
+        requested_response_window_ms: 800,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn script_defines_trials_scoring_and_feedback_text() {
+    let script = ScriptParadigm::compile(Path::new("gonogo.rhai"), GO_NOGO).unwrap();
+    assert_eq!(script.response_mapping().keys(), &[ResponseKey::Space]);
+    assert_eq!(
+        script.messages(),
+        vec![("feedback-correct".to_string(), "Well done!".to_string())]
+    );
+
+    let sequence = script.sequence(8, &mut StdRng::seed_from_u64(1)).unwrap();
+    assert_eq!(sequence.remaining(), 8);
+    let nogo: Vec<_> = sequence.iter().filter(|t| t.condition == "nogo").collect();
+    assert_eq!(nogo.len(), 2);
+    assert!(nogo.iter().all(|t| t.expected_key == Some(None)));
+    assert!(sequence
+        .iter()
+        .filter(|t| t.condition == "go")
+        .all(|t| t.expected_key == Some(Some(ResponseKey::Space))
+            && t.response_window_ms == Some(800)));
+
+    // The order replays from the session seed
+    let conditions = |seed| -> Vec<String> {
+        let sequence = script
+            .sequence(8, &mut StdRng::seed_from_u64(seed))
+            .unwrap();
+        sequence.iter().map(|t| t.condition.clone()).collect()
+    };
+    assert_eq!(conditions(3), conditions(3));
+
+    let results = [
+        result("go", Some(ResponseKey::Space), true),
+        result("go", None, false),
+        result("nogo", None, true),
+    ];
+    assert_eq!(script.summary(&results), vec!["Hits: 1".to_string()]);
+}
+
+#[test]
+fn script_errors_name_the_file_and_function() {
+    let Err(missing) = ScriptParadigm::compile(Path::new("empty.rhai"), "fn trials(n) { [] }")
+    else {
+        panic!("script without keys() was accepted");
+    };
+    assert!(missing.to_string().contains("empty.rhai"));
+    assert!(missing.to_string().contains("keys()"));
+
+    let script = ScriptParadigm::compile(
+        Path::new("short.rhai"),
+        r#"fn keys() { ["f", "j"] } fn trials(n) { [] }"#,
+    )
+    .unwrap();
+    let Err(short) = script.sequence(4, &mut StdRng::seed_from_u64(1)) else {
+        panic!("too few trials were accepted");
+    };
+    assert!(short.to_string().contains("returned 0 trials"));
+}
@@ -0,0 +1,118 @@
+use cogex_core::{PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult};
+use cogex_experiment::{Paradigm, VisualSearch};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+fn result(
+    condition: &str,
+    set_size: usize,
+    rt_ms: u64,
+    correct: bool,
+) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: VisualSearch::default().target,
+        condition: condition.to_string(),
+        set_size,
+        response_key: Some(ResponseKey::Char('j')),
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: Some(rt_ms * 1_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: Some(correct),
+        outcome: TrialOutcome::Response,
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 2000,
+        requested_response_window_ms: 2000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn displays_cross_presence_with_set_size() {
+    let task = VisualSearch::default().with_set_sizes(&[4, 8]);
+    let sequence = task.sequence(40, &mut StdRng::seed_from_u64(3)).unwrap();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for trial in sequence.iter() {
+        *counts.entry(trial.condition.clone()).or_default() += 1;
+        let present = trial.condition.starts_with(VisualSearch::PRESENT);
+        let set_size: usize = trial.condition.rsplit('_').next().unwrap().parse().unwrap();
+        assert_eq!(1 + trial.array.len(), set_size);
+        assert!(trial.array.iter().all(|s| *s == task.distractor));
+        if present {
+            assert_eq!(trial.stimulus, task.target);
+            assert_eq!(trial.expected_key, Some(Some(task.present_key)));
+        } else {
+            assert_eq!(trial.stimulus, task.distractor);
+            assert_eq!(trial.expected_key, Some(Some(task.absent_key)));
+        }
+    }
+    assert_eq!(counts.len(), 4);
+    assert!(counts.values().all(|&n| n == 10), "{:?}", counts);
+
+    assert!(VisualSearch::default()
+        .with_set_sizes(&[])
+        .sequence(8, &mut StdRng::seed_from_u64(3))
+        .is_err());
+    assert!(VisualSearch::default()
+        .with_set_sizes(&[0, 4])
+        .sequence(8, &mut StdRng::seed_from_u64(3))
+        .is_err());
+}
+
+#[test]
+fn search_slopes_regress_correct_rt_on_set_size() {
+    let task = VisualSearch::default();
+    let mut results = Vec::new();
+    for n in [4, 8, 16] {
+        // 20 ms per item when present, 40 when absent; errors are ignored
+        results.push(result(
+            &VisualSearch::condition(true, n),
+            n,
+            400 + 20 * n as u64,
+            true,
+        ));
+        results.push(result(&VisualSearch::condition(true, n), n, 5000, false));
+        results.push(result(
+            &VisualSearch::condition(false, n),
+            n,
+            500 + 40 * n as u64,
+            true,
+        ));
+    }
+
+    let score = task.score(&results);
+    assert_eq!(
+        score.present.keys().copied().collect::<Vec<_>>(),
+        [4, 8, 16]
+    );
+    assert_eq!(score.present[&8].trials, 2);
+    assert_eq!(score.present[&8].mean_rt_ms, Some(560.0));
+    assert!((score.present_slope().unwrap() - 20.0).abs() < 1e-9);
+    assert!((score.absent_slope().unwrap() - 40.0).abs() < 1e-9);
+
+    let lines = task.summary(&results);
+    assert!(lines.contains(&"Search slope, target present: 20.0 ms/item".to_string()));
+    assert!(lines.contains(&"Search slope, target absent: 40.0 ms/item".to_string()));
+    assert_eq!(
+        VisualSearch::default().score(&results[..2]).present_slope(),
+        None
+    );
+}
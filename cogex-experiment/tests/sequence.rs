@@ -0,0 +1,151 @@
+use cogex_core::StimulusType;
+use cogex_experiment::{balanced_latin_square_row, TrialSequence, TrialSequenceBuilder};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+fn circle() -> StimulusType {
+    StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 255, 255, 255],
+    }
+}
+
+fn conditions(sequence: &TrialSequence) -> Vec<String> {
+    sequence.iter().map(|t| t.condition.clone()).collect()
+}
+
+/// Times each ordered pair of conditions follows one another
+type Carryover = HashMap<(usize, usize), usize>;
+
+/// Rows for every participant of an `n`-condition square, and their
+/// carryover
+fn carryover(n: usize) -> (Vec<Vec<usize>>, Carryover) {
+    let rows_in_square = if n.is_multiple_of(2) { n } else { 2 * n };
+    let rows: Vec<Vec<usize>> = (0..rows_in_square)
+        .map(|p| balanced_latin_square_row(n, p))
+        .collect();
+    let mut pairs = HashMap::new();
+    for row in &rows {
+        for pair in row.windows(2) {
+            *pairs.entry((pair[0], pair[1])).or_default() += 1;
+        }
+    }
+    (rows, pairs)
+}
+
+#[test]
+fn balanced_latin_square_rows_are_permutations_with_balanced_carryover() {
+    for n in [4, 6] {
+        let (rows, pairs) = carryover(n);
+        for row in &rows {
+            let mut sorted = row.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+        }
+        assert_eq!(pairs.len(), n * (n - 1));
+        assert!(pairs.values().all(|&count| count == 1), "{:?}", pairs);
+    }
+}
+
+#[test]
+fn odd_latin_squares_balance_carryover_over_mirrored_rows() {
+    for n in [3, 5] {
+        let (rows, pairs) = carryover(n);
+        for row in &rows {
+            let mut sorted = row.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..n).collect::<Vec<_>>());
+        }
+        // 2n rows of n - 1 transitions cover every ordered pair twice
+        assert_eq!(pairs.len(), n * (n - 1));
+        assert!(pairs.values().all(|&count| count == 2), "{:?}", pairs);
+        assert_eq!(balanced_latin_square_row(n, 2 * n), rows[0]);
+    }
+}
+
+#[test]
+fn max_repeats_limits_runs_of_one_condition() {
+    let builder = TrialSequence::builder()
+        .condition("a", circle())
+        .condition("b", circle())
+        .weight("a", 3.0)
+        .total_trials(40)
+        .max_repeats(3);
+    for seed in 0..20 {
+        let order = conditions(&builder.build(&mut StdRng::seed_from_u64(seed)).unwrap());
+        assert_eq!(order.iter().filter(|c| *c == "a").count(), 30);
+        let longest = order
+            .chunk_by(|a, b| a == b)
+            .map(|run| run.len())
+            .max()
+            .unwrap();
+        assert!(longest <= 3, "{:?}", order);
+    }
+}
+
+#[test]
+fn max_repeats_counts_cells_sharing_a_label_as_one_condition() {
+    let coloured = |color| StimulusType::Circle {
+        radius: 50.0,
+        color,
+    };
+    let builder = TrialSequence::builder()
+        .stimuli([coloured([255, 0, 0, 255]), coloured([0, 0, 255, 255])])
+        .condition("square", circle())
+        .total_trials(30)
+        .max_repeats(2);
+    for seed in 0..20 {
+        let order = conditions(&builder.build(&mut StdRng::seed_from_u64(seed)).unwrap());
+        assert_eq!(order.iter().filter(|c| *c == "circle").count(), 20);
+        let longest = order
+            .chunk_by(|a, b| a == b)
+            .map(|run| run.len())
+            .max()
+            .unwrap();
+        assert!(longest <= 2, "{:?}", order);
+    }
+}
+
+#[test]
+fn unsatisfiable_max_repeats_fail_to_build() {
+    let lopsided = || {
+        TrialSequenceBuilder::default()
+            .condition("a", circle())
+            .condition("b", circle())
+            .weight("a", 10.0)
+            .total_trials(11)
+    };
+    let mut rng = StdRng::seed_from_u64(1);
+    assert!(lopsided().max_repeats(1).build(&mut rng).is_err());
+    assert!(lopsided().max_repeats(0).build(&mut rng).is_err());
+    assert!(lopsided().max_repeats(10).build(&mut rng).is_ok());
+}
+
+#[test]
+fn response_windows_follow_condition_labels_and_factor_levels() {
+    let sequence = TrialSequence::builder()
+        .condition("catch", circle())
+        .factor("emphasis", &["speeded", "accurate"])
+        .factor("side", &["left", "right"])
+        .stimulus(|_| circle())
+        .response_window("catch", 2000)
+        .response_window("speeded", 400)
+        .build(&mut StdRng::seed_from_u64(3))
+        .unwrap();
+    let mut windows: Vec<_> = sequence
+        .iter()
+        .map(|t| (t.condition.as_str(), t.response_window_ms))
+        .collect();
+    windows.sort_unstable();
+    assert_eq!(
+        windows,
+        [
+            ("accurate_left", None),
+            ("accurate_right", None),
+            ("catch", Some(2000)),
+            ("speeded_left", Some(400)),
+            ("speeded_right", Some(400)),
+        ]
+    );
+}
@@ -0,0 +1,1761 @@
+use cogex_core::{
+    ArrowDirection, FeedbackOutcome, Instructions, MouseButton, PhaseGraph, Primitive,
+    QuestionItem, Questionnaire, RawEvent, RawInput, ResponseKey, ResponseMapping, StandardPhase,
+    StimulusType, TrialOutcome, TrialState,
+};
+use cogex_experiment::{
+    bids_events_tsv, checkpoint_path, questionnaire_to_json, results_to_json, BlockConfig,
+    CalibrationDuration, Cue, ExperimentConfig, ExperimentEvent, ExperimentStateMachine, Interval,
+    ItiDisplay, Layout, LayoutSet, OutputManager, Paradigm, ParadigmRegistry, PracticeCriterion,
+    RedrawMode, ResponseKeySet, ScannerConfig, Screen, SequencedTrial, SessionInfo, TrialSequence,
+};
+use cogex_eyetrack::{FixationGate, GazeMonitor, GazeSample};
+use cogex_sync::{Marker, MarkerOutlet};
+use cogex_timing::{HighPrecisionTimer, MockTimer, Timer};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const FIXATION_MS: u64 = 500;
+const STIMULUS_MS: u64 = 200;
+const RESPONSE_WINDOW_MS: u64 = 1000;
+const FEEDBACK_MS: u64 = 300;
+const PRACTICE_TRIALS: usize = 3;
+
+type Machine = ExperimentStateMachine<StandardPhase, StimulusType, MockTimer, StdRng>;
+
+/// A machine on the welcome screen, every duration fixed and every trial a
+/// circle expecting Space
+fn welcome_machine() -> Machine {
+    let mut config = ExperimentConfig::<StandardPhase>::default();
+    config.practice_trials = PRACTICE_TRIALS;
+    config.fixation_range_ms = (FIXATION_MS, FIXATION_MS);
+    config.stimulus_duration_ms = STIMULUS_MS;
+    config.response_window_ms = RESPONSE_WINDOW_MS;
+    config.feedback_duration_ms = FEEDBACK_MS;
+    config.inter_trial_interval = Interval::Fixed(0);
+
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let trials = vec![SequencedTrial::new("circle", circle); PRACTICE_TRIALS];
+    ExperimentStateMachine::new(config, MockTimer::default(), StdRng::seed_from_u64(7))
+        .with_response_mapping(ResponseMapping::new().with_fallback(ResponseKey::Space))
+        .with_practice_sequence(TrialSequence::from_trials(trials))
+}
+
+/// Dismisses the welcome screen and completes calibration
+fn start(mut machine: Machine) -> Machine {
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(*machine.current_phase(), StandardPhase::Calibration);
+    assert!(machine.handle_event(ExperimentEvent::CalibrationComplete));
+    assert_eq!(*machine.current_phase(), StandardPhase::Practice);
+    machine
+}
+
+/// A machine in practice with its first trial just started
+fn practice_machine() -> Machine {
+    start(welcome_machine())
+}
+
+/// Runs the current trial to its end without responding
+fn miss_trial(machine: &mut Machine) {
+    step(machine, FIXATION_MS);
+    step(machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+    step(machine, FEEDBACK_MS);
+}
+
+/// Answers the current trial correctly
+fn hit_trial(machine: &mut Machine) {
+    step(machine, FIXATION_MS + 100);
+    assert!(press(machine, ResponseKey::Space));
+    step(
+        machine,
+        STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS - 100,
+    );
+}
+
+/// Advances the clock and runs one update, handling the events it raises
+fn step(machine: &mut Machine, ms: u64) {
+    machine.timer.advance_ms(ms);
+    for event in machine.update() {
+        machine.handle_event(event);
+    }
+}
+
+fn state(machine: &Machine) -> Option<TrialState> {
+    machine.current_trial_state().cloned()
+}
+
+fn press(machine: &mut Machine, key: ResponseKey) -> bool {
+    let timestamp_ns = Some(machine.timer.now());
+    machine.handle_event(ExperimentEvent::ResponseReceived { key, timestamp_ns })
+}
+
+#[test]
+fn instruction_pages_turn_with_space_and_backspace_before_calibration() {
+    let instructions = Instructions::parse("Page one\n---\nPage two\n", Path::new("."));
+    let mut machine = welcome_machine().with_instructions(instructions);
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(*machine.current_phase(), StandardPhase::Instructions);
+
+    let page = |machine: &Machine| machine.instructions.as_ref().unwrap().page_index();
+    assert!(!machine.handle_event(ExperimentEvent::PreviousPage));
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(page(&machine), 1);
+    assert!(machine.handle_event(ExperimentEvent::PreviousPage));
+    assert_eq!(page(&machine), 0);
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(*machine.current_phase(), StandardPhase::Calibration);
+}
+
+#[test]
+fn instructions_without_pages_are_skipped() {
+    let empty = Instructions::parse("", Path::new("."));
+    let mut machine = welcome_machine().with_instructions(empty);
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(*machine.current_phase(), StandardPhase::Calibration);
+}
+
+#[test]
+fn calibration_starts_first_trial_in_fixation() {
+    let machine = practice_machine();
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    assert_eq!(machine.current.as_ref().unwrap().id, 0);
+}
+
+#[test]
+fn calibration_ends_once_its_frames_show_settled_jitter() {
+    // Steps one frame at a time through calibration, returning how many
+    // frames it took
+    fn calibrate(mut machine: Machine) -> (Machine, u64) {
+        assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+        let mut frames = 0;
+        while *machine.current_phase() == StandardPhase::Calibration {
+            let frame = machine.timer.frame_duration();
+            machine.timer.record_frame(frame);
+            step(&mut machine, 16);
+            frames += 1;
+            assert!(frames < 1000, "calibration never ended");
+        }
+        (machine, frames)
+    }
+
+    let mut machine = welcome_machine();
+    machine.config.calibration.duration = CalibrationDuration::Frames(10);
+    machine.config.calibration.stable_frames = 20;
+    let (machine, frames) = calibrate(machine);
+    // The first frame starts the count, then 20 until the jitter is known
+    // to have settled
+    assert_eq!(frames, 21);
+    let json = serde_json::to_value(machine.metadata()).unwrap();
+    assert_eq!(json["calibration"]["frames"], 20);
+    assert_eq!(json["calibration"]["converged"], true);
+    assert_eq!(json["calibration"]["duration_ns"], 20 * 16_000_000);
+
+    let mut machine = welcome_machine();
+    machine.config.calibration.duration = CalibrationDuration::Seconds(0.5);
+    machine.config.calibration.jitter_tolerance = None;
+    let (machine, frames) = calibrate(machine);
+    assert_eq!(frames, 33);
+    let json = serde_json::to_value(machine.metadata()).unwrap();
+    assert!(json["calibration"]["converged"].is_null());
+}
+
+#[test]
+fn fixation_holds_for_its_duration() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS - 1);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    step(&mut machine, 1);
+    assert_ne!(state(&machine), Some(TrialState::Fixation));
+    assert_eq!(
+        machine.current.as_ref().unwrap().timestamps.stimulus_start,
+        Some(FIXATION_MS * 1_000_000)
+    );
+}
+
+#[test]
+fn stimulus_state_precedes_response_window() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert!(machine.current_stimulus().is_some());
+    step(&mut machine, STIMULUS_MS - 1);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    step(&mut machine, 1);
+    assert_eq!(state(&machine), Some(TrialState::Response));
+    assert!(machine.current_stimulus().is_none());
+}
+
+#[test]
+fn response_during_stimulus_is_accepted() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, STIMULUS_MS / 2);
+    assert!(press(&mut machine, ResponseKey::Space));
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+}
+
+#[test]
+fn stimulus_offset_is_stamped_on_the_first_blank_frame() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    machine.frame_presented(machine.timer.now());
+    step(&mut machine, STIMULUS_MS);
+    let offset_ns = machine.timer.now();
+    machine.frame_presented(offset_ns);
+
+    let timestamps = &machine.current.as_ref().unwrap().timestamps;
+    assert_eq!(timestamps.stimulus_presented, Some(FIXATION_MS * 1_000_000));
+    assert_eq!(timestamps.stimulus_offset_presented, Some(offset_ns));
+}
+
+#[test]
+fn results_keep_the_first_presented_frame_of_each_trial_event() {
+    let mut machine = practice_machine();
+    let start_ns = machine.timer.now();
+    let ms = |ms: u64| start_ns + ms * 1_000_000;
+    // A frame every 10 ms, each drawn after the update before it
+    machine.frame_presented(start_ns);
+    while machine.results().is_empty() {
+        step(&mut machine, 10);
+        machine.frame_presented(machine.timer.now());
+    }
+
+    let presentation = &machine.results()[0].presentation;
+    assert_eq!(presentation.fixation_onset_ns, Some(ms(0)));
+    assert_eq!(presentation.stimulus_onset_ns, Some(ms(FIXATION_MS)));
+    assert_eq!(
+        presentation.stimulus_offset_ns,
+        Some(ms(FIXATION_MS + STIMULUS_MS))
+    );
+    assert_eq!(
+        presentation.feedback_onset_ns,
+        Some(ms(FIXATION_MS + STIMULUS_MS + RESPONSE_WINDOW_MS))
+    );
+    assert_eq!(presentation.cue_onset_ns, None);
+}
+
+#[test]
+fn cues_precede_the_target_by_the_configured_soa() {
+    let arrow = StimulusType::Arrow {
+        direction: ArrowDirection::Left,
+        size: 30.0,
+        color: [200, 200, 200, 255],
+    };
+    let target = StimulusType::Circle {
+        radius: 15.0,
+        color: [255, 255, 255, 255],
+    };
+    let trial = SequencedTrial {
+        cue: Some(Cue::new(arrow.clone(), (400.0, 300.0))),
+        position: Some((200.0, 300.0)),
+        ..SequencedTrial::new("valid", target.clone())
+    };
+    let mut machine = welcome_machine().with_practice_sequence(TrialSequence::from_trials([trial]));
+    machine.config.cue_duration_ms = 100;
+    machine.config.cue_target_soa = Interval::Fixed(250);
+    let mut machine = start(machine);
+
+    step(&mut machine, FIXATION_MS);
+    assert_eq!(state(&machine), Some(TrialState::Cue));
+    assert_eq!(machine.current_stimulus(), Some((&arrow, (400.0, 300.0))));
+    // Responses before the target are not taken
+    assert!(!press(&mut machine, ResponseKey::Space));
+    step(&mut machine, 100);
+    assert_eq!(state(&machine), Some(TrialState::Cue));
+    assert_eq!(machine.current_stimulus(), None);
+    step(&mut machine, 150);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert_eq!(machine.current_stimulus(), Some((&target, (200.0, 300.0))));
+
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+    step(&mut machine, FEEDBACK_MS);
+    let result = &machine.results()[0];
+    assert_eq!(result.requested_cue_ms, Some(100));
+    assert_eq!(result.requested_soa_ms, Some(250));
+}
+
+#[test]
+fn frame_counted_cues_show_prime_and_mask_for_exact_frames() {
+    let text = |content: &str| StimulusType::Text {
+        content: content.into(),
+        size: 48.0,
+        color: [255, 255, 255, 255],
+    };
+    let (prime, mask, target) = (text("4"), text("#####"), text("6"));
+    let trial = SequencedTrial {
+        cue: Some(
+            Cue::new(prime.clone(), (400.0, 300.0))
+                .with_mask(mask.clone())
+                .with_frames(3, 2),
+        ),
+        ..SequencedTrial::new("primed", target.clone())
+    };
+    let mut machine =
+        start(welcome_machine().with_practice_sequence(TrialSequence::from_trials(vec![trial])));
+    assert_eq!(machine.upcoming_stimuli(), [&prime, &mask, &target]);
+
+    step(&mut machine, FIXATION_MS);
+    let frame = machine.timer.frame_duration();
+    let mut shown = Vec::new();
+    while state(&machine) == Some(TrialState::Cue) {
+        assert!(machine.next_deadline_ns() <= Some(machine.timer.now()));
+        shown.push(machine.current_stimulus().unwrap().0.clone());
+        machine.frame_presented(machine.timer.now());
+        machine.timer.advance(frame);
+        for event in machine.update() {
+            machine.handle_event(event);
+        }
+        assert!(shown.len() < 10, "the cue never ended");
+    }
+    assert_eq!(
+        shown,
+        [&prime, &prime, &prime, &mask, &mask].map(Clone::clone)
+    );
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert_eq!(machine.current_stimulus().unwrap().0, &target);
+    machine.frame_presented(machine.timer.now());
+
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+    step(&mut machine, FEEDBACK_MS + 100);
+    let result = &machine.results()[0];
+    assert_eq!(result.requested_cue_frames, Some(3));
+    assert_eq!(result.requested_mask_frames, Some(2));
+    assert_eq!(
+        result.presentation.cue_duration_ns(),
+        Some(3 * frame.as_nanos() as u64)
+    );
+    assert_eq!(
+        result.presentation.stimulus_onset_ns.unwrap() - result.presentation.cue_offset_ns.unwrap(),
+        2 * frame.as_nanos() as u64
+    );
+}
+
+#[test]
+fn response_is_accepted_after_onset() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, 350);
+    assert!(press(&mut machine, ResponseKey::Space));
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+
+    step(
+        &mut machine,
+        STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS - 350,
+    );
+    let result = &machine.results()[0];
+    assert_eq!(result.response_key, Some(ResponseKey::Space));
+    assert_eq!(result.reaction_time_ns, Some(350 * 1_000_000));
+    assert_eq!(result.correct, Some(true));
+    assert_eq!(result.outcome, TrialOutcome::Response);
+}
+
+#[test]
+fn reaction_times_run_to_the_key_down_stamp_not_its_handling() {
+    let respond = |stamp_ms: fn(u64) -> u64| {
+        let mut machine = practice_machine();
+        step(&mut machine, FIXATION_MS);
+        step(&mut machine, 350);
+        let now_ms = machine.timer.now() / 1_000_000;
+        machine.handle_event(ExperimentEvent::ResponseReceived {
+            key: ResponseKey::Space,
+            timestamp_ns: Some(stamp_ms(now_ms) * 1_000_000),
+        });
+        let recorded = state(&machine) == Some(TrialState::Feedback);
+        step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+        step(&mut machine, FEEDBACK_MS);
+        (recorded, machine.results()[0].reaction_time_ns)
+    };
+
+    // Down 100 ms before the event was handled
+    assert_eq!(respond(|now| now - 100), (true, Some(250 * 1_000_000)));
+    // Stamps from the future are clamped to the time of handling
+    assert_eq!(respond(|now| now + 50), (true, Some(350 * 1_000_000)));
+    // Down before the stimulus appeared: anticipatory, so ignored
+    assert_eq!(respond(|now| now - 400), (false, None));
+}
+
+#[test]
+fn queued_input_is_handled_on_update_with_its_capture_stamp() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut machine = welcome_machine().with_input(receiver);
+    let key = |key, timestamp_ns| RawInput::new(RawEvent::Key(key), timestamp_ns);
+    sender.send(key(ResponseKey::Space, 0)).unwrap();
+    assert_eq!(*machine.current_phase(), StandardPhase::Welcome);
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Calibration);
+    assert!(machine.handle_event(ExperimentEvent::CalibrationComplete));
+
+    step(&mut machine, FIXATION_MS);
+    let onset_ns = machine.timer.now();
+    sender
+        .send(key(ResponseKey::Space, onset_ns + 120 * 1_000_000))
+        .unwrap();
+    // Handled 350 ms after onset, but timed from its capture
+    machine.timer.advance_ms(350);
+    step(&mut machine, 0);
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+    step(
+        &mut machine,
+        STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS - 350,
+    );
+    assert_eq!(machine.results()[0].reaction_time_ns, Some(120 * 1_000_000));
+}
+
+#[test]
+fn voice_onsets_are_stored_alongside_the_key_response() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    let onset_ns = machine.timer.now();
+    let voice = |machine: &mut Machine, after_ms: u64| {
+        machine.handle_event(ExperimentEvent::VoiceOnset {
+            timestamp_ns: onset_ns + after_ms * 1_000_000,
+        })
+    };
+    step(&mut machine, 300);
+    // Only the first onset after the stimulus counts
+    assert!(!machine.handle_event(ExperimentEvent::VoiceOnset {
+        timestamp_ns: onset_ns - 1_000_000,
+    }));
+    assert!(voice(&mut machine, 280));
+    assert!(!voice(&mut machine, 290));
+    assert!(press(&mut machine, ResponseKey::Space));
+    step(
+        &mut machine,
+        STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS - 300,
+    );
+
+    let result = &machine.results()[0];
+    assert_eq!(result.reaction_time_ns, Some(300 * 1_000_000));
+    assert_eq!(result.voice_onset_ns, Some(onset_ns + 280 * 1_000_000));
+    assert_eq!(result.voice_reaction_time_ns, Some(280 * 1_000_000));
+}
+
+#[test]
+fn gaze_contingent_trials_wait_in_fixation_until_gaze_is_held() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let gate = FixationGate::new((400.0, 300.0), 50.0, 100);
+    let mut machine =
+        start(welcome_machine().with_gaze_contingency(GazeMonitor::new(receiver, gate)));
+    let look = |machine: &Machine| {
+        let sample = GazeSample {
+            timestamp_ns: machine.timer.now(),
+            position: Some((400.0, 300.0)),
+        };
+        sender.send(sample).unwrap();
+    };
+
+    step(&mut machine, FIXATION_MS + 200);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    look(&machine);
+    step(&mut machine, 100);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    look(&machine);
+    step(&mut machine, 0);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert_eq!(
+        machine.current.as_ref().unwrap().gaze_wait_ns,
+        300 * 1_000_000
+    );
+}
+
+/// Log lines written while a test's subscriber is the default
+#[derive(Clone, Default)]
+struct LogCapture(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for LogCapture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn log_events_carry_their_phase_and_trial_spans() {
+    let log = LogCapture::default();
+    let writer = log.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        let mut machine = practice_machine();
+        step(&mut machine, FIXATION_MS + 100);
+        assert!(press(&mut machine, ResponseKey::Space));
+    });
+
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    let line = |text: &str| log.lines().find(|line| line.contains(text)).unwrap();
+    assert!(line("Trial 0 started").contains("phase{phase=Practice}:trial{id=0 condition=circle}"));
+    assert!(line("Response Space recorded").contains("trial{id=0 condition=circle}"));
+}
+
+#[test]
+fn input_latency_adds_the_key_scan_to_half_a_frame() {
+    let mut machine = practice_machine();
+    assert_eq!(machine.estimated_input_latency().as_micros(), 8_333);
+    machine.config.key_scan_ms = 4.0;
+    assert_eq!(machine.estimated_input_latency().as_micros(), 12_333);
+}
+
+#[test]
+fn response_during_fixation_is_ignored() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS / 2);
+    assert!(!press(&mut machine, ResponseKey::Space));
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+}
+
+#[test]
+fn anticipatory_response_stamp_is_ignored() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, 100);
+    let before_onset = Some((FIXATION_MS - 10) * 1_000_000);
+    machine.handle_event(ExperimentEvent::ResponseReceived {
+        key: ResponseKey::Space,
+        timestamp_ns: before_onset,
+    });
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+}
+
+#[test]
+fn feedback_lasts_until_the_trial_total() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, 100);
+    press(&mut machine, ResponseKey::Space);
+
+    // Feedback ends on the trial's full schedule, not a fixed time after the response
+    let total = FIXATION_MS + STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS;
+    step(&mut machine, total - FIXATION_MS - 100 - 1);
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+    step(&mut machine, 1);
+    assert_eq!(machine.results().len(), 1);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    assert_eq!(machine.current.as_ref().unwrap().id, 1);
+}
+
+#[test]
+fn feedback_shows_the_scored_verdict_and_reaction_time() {
+    let keys = [ResponseKey::Char('f'), ResponseKey::Char('j')];
+    let mut machine = start(welcome_machine().with_response_mapping(
+        ResponseMapping::new().with_rule_fn(&keys, |_| Some(ResponseKey::Char('j'))),
+    ));
+    assert_eq!(machine.feedback(), None);
+
+    for (key, outcome) in [
+        (keys[1], FeedbackOutcome::Correct),
+        (keys[0], FeedbackOutcome::Wrong),
+    ] {
+        step(&mut machine, FIXATION_MS);
+        step(&mut machine, 250);
+        assert!(press(&mut machine, key));
+        let feedback = machine.feedback().unwrap();
+        assert_eq!(feedback.outcome, outcome);
+        assert_eq!(feedback.reaction_time_ms, Some(250));
+        step(
+            &mut machine,
+            STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS - 250,
+        );
+        assert_eq!(machine.feedback(), None);
+    }
+}
+
+#[test]
+fn missed_response_window_shows_too_slow() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS - 1);
+    assert_eq!(state(&machine), Some(TrialState::Response));
+    step(&mut machine, 1);
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+    assert_eq!(
+        machine.feedback().map(|f| f.outcome),
+        Some(FeedbackOutcome::TooSlow)
+    );
+    assert!(!press(&mut machine, ResponseKey::Space));
+}
+
+#[test]
+fn missed_response_window_is_scored_as_an_omission() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+    step(&mut machine, FEEDBACK_MS);
+
+    let result = &machine.results()[0];
+    assert_eq!(result.outcome, TrialOutcome::Timeout);
+    assert_eq!(result.response_key, None);
+    assert_eq!(result.reaction_time_ns, None);
+    assert_eq!(result.correct, Some(false));
+}
+
+#[test]
+fn upcoming_stimuli_are_warmed_within_the_inter_trial_interval() {
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let text = StimulusType::Text {
+        content: "next".into(),
+        size: 48.0,
+        color: [255, 255, 255, 255],
+    };
+    let trials = vec![
+        SequencedTrial::new("circle", circle.clone()),
+        SequencedTrial::new("text", text.clone()),
+    ];
+    let mut machine = welcome_machine().with_practice_sequence(TrialSequence::from_trials(trials));
+    machine.config.inter_trial_interval = Interval::Fixed(300);
+    let mut machine = start(machine);
+    assert_eq!(machine.upcoming_stimuli(), vec![&circle]);
+    step(&mut machine, FIXATION_MS);
+    assert!(machine.upcoming_stimuli().is_empty());
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+
+    // Once the trial runs out the next one is known ahead of its fixation
+    step(&mut machine, FEEDBACK_MS);
+    assert_eq!(state(&machine), Some(TrialState::Complete));
+    assert_eq!(machine.upcoming_stimuli(), vec![&text]);
+    step(&mut machine, 120);
+    assert_eq!(machine.upcoming_stimuli(), vec![&text]);
+    step(&mut machine, 180);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    assert_eq!(machine.upcoming_stimuli(), vec![&text]);
+}
+
+#[test]
+fn inter_trial_interval_is_a_deadline_rather_than_a_sleep() {
+    let mut machine = welcome_machine();
+    machine.config.inter_trial_interval = Interval::Fixed(300);
+    let mut machine = start(machine);
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+    step(&mut machine, FEEDBACK_MS);
+    let completed_ns = machine.timer.now();
+    assert_eq!(state(&machine), Some(TrialState::Complete));
+    assert_eq!(machine.results().len(), 0);
+    assert_eq!(
+        machine.next_deadline_ns(),
+        Some(completed_ns + 300 * 1_000_000)
+    );
+
+    // Updates within the interval return at once, leaving the clock alone
+    step(&mut machine, 299);
+    assert_eq!(state(&machine), Some(TrialState::Complete));
+    assert_eq!(machine.timer.now(), completed_ns + 299 * 1_000_000);
+
+    machine.timer.advance_ms(1);
+    assert_eq!(machine.update(), vec![ExperimentEvent::TrialComplete]);
+    assert!(machine.handle_event(ExperimentEvent::TrialComplete));
+    assert_eq!(machine.timer.now(), completed_ns + 300 * 1_000_000);
+    assert_eq!(machine.results().len(), 1);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+}
+
+#[test]
+fn animation_frames_keep_coming_through_the_inter_trial_interval() {
+    // The browser front end draws then updates once per animation frame
+    let square = StimulusType::Rectangle {
+        width: 20.0,
+        height: 20.0,
+        color: [255, 255, 255, 255],
+    };
+    let mut machine = welcome_machine();
+    machine.config.inter_trial_interval = Interval::Fixed(300);
+    machine.config.iti_display = ItiDisplay::Stimulus(square.clone());
+    let mut machine = start(machine);
+    miss_trial(&mut machine);
+    assert_eq!(state(&machine), Some(TrialState::Complete));
+
+    let mut interval_frames = 0;
+    while state(&machine) == Some(TrialState::Complete) {
+        assert_eq!(machine.current_stimuli(), vec![(&square, (400.0, 300.0))]);
+        let now_ns = machine.timer.now();
+        machine.frame_presented(now_ns);
+        assert_eq!(
+            machine.stimulus_elapsed(),
+            Some(Duration::from_millis(16 * interval_frames))
+        );
+        for event in machine.update() {
+            machine.handle_event(event);
+        }
+        assert_eq!(machine.timer.now(), now_ns);
+        machine.timer.advance_ms(16);
+        interval_frames += 1;
+    }
+    // Drawn at 0, 16, ..., 304 ms, the update after the last starting the
+    // next trial
+    assert_eq!(interval_frames, 20);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+}
+
+#[test]
+fn each_trial_draws_and_records_its_own_inter_trial_interval() {
+    let mut machine = welcome_machine();
+    machine.config.inter_trial_interval = Interval::Uniform {
+        min_ms: 200,
+        max_ms: 600,
+    };
+    let mut machine = start(machine);
+    let mut intervals = Vec::new();
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+        let iti_ns = machine.next_deadline_ns().unwrap() - machine.timer.now();
+        step(&mut machine, iti_ns / 1_000_000 - 1);
+        assert_eq!(state(&machine), Some(TrialState::Complete));
+        step(&mut machine, 1);
+        intervals.push(iti_ns / 1_000_000);
+    }
+    let recorded: Vec<_> = machine
+        .results()
+        .iter()
+        .map(|r| r.requested_iti_ms)
+        .collect();
+    assert_eq!(recorded, intervals);
+    assert!(recorded.iter().all(|ms| (200..=600).contains(ms)));
+    assert!(recorded.windows(2).any(|w| w[0] != w[1]), "{:?}", recorded);
+}
+
+#[test]
+fn static_screens_have_no_deadline_to_redraw_for() {
+    let mut machine = welcome_machine();
+    assert_eq!(machine.config.redraw, RedrawMode::OnDemand);
+    assert_eq!(machine.next_deadline_ns(), None);
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    // Calibration measures every frame
+    assert_eq!(machine.next_deadline_ns(), Some(machine.timer.now()));
+    assert!(machine.handle_event(ExperimentEvent::CalibrationComplete));
+    assert!(machine.next_deadline_ns() > Some(machine.timer.now()));
+    assert!(machine.handle_event(ExperimentEvent::Pause));
+    assert_eq!(machine.next_deadline_ns(), None);
+}
+
+#[test]
+fn deadlines_follow_each_state_of_the_trial() {
+    let mut machine = practice_machine();
+    let start_ns = machine.timer.now();
+    let at = |ms: u64| Some(start_ns + ms * 1_000_000);
+    assert_eq!(machine.next_deadline_ns(), at(FIXATION_MS));
+
+    step(&mut machine, FIXATION_MS);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert_eq!(machine.next_deadline_ns(), at(FIXATION_MS + STIMULUS_MS));
+    step(&mut machine, STIMULUS_MS);
+    assert_eq!(state(&machine), Some(TrialState::Response));
+    assert_eq!(
+        machine.next_deadline_ns(),
+        at(FIXATION_MS + STIMULUS_MS + RESPONSE_WINDOW_MS)
+    );
+    // Feedback after an early answer still lasts until the trial total
+    step(&mut machine, 100);
+    assert!(press(&mut machine, ResponseKey::Space));
+    assert_eq!(
+        machine.next_deadline_ns(),
+        at(FIXATION_MS + STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS)
+    );
+}
+
+#[test]
+fn pausing_within_the_inter_trial_interval_extends_it() {
+    let mut machine = welcome_machine();
+    machine.config.inter_trial_interval = Interval::Fixed(300);
+    let mut machine = start(machine);
+    miss_trial(&mut machine);
+    let completed_ns = machine.timer.now();
+    step(&mut machine, 100);
+    assert!(machine.handle_event(ExperimentEvent::Pause));
+    step(&mut machine, 1000);
+    assert!(machine.handle_event(ExperimentEvent::Resume));
+    assert_eq!(
+        machine.next_deadline_ns(),
+        Some(completed_ns + 1300 * 1_000_000)
+    );
+    step(&mut machine, 199);
+    assert_eq!(state(&machine), Some(TrialState::Complete));
+    step(&mut machine, 1);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+}
+
+#[test]
+fn a_trials_own_response_window_replaces_the_configured_one() {
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let speeded = SequencedTrial {
+        response_window_ms: Some(400),
+        ..SequencedTrial::new("speeded", circle.clone())
+    };
+    let accurate = SequencedTrial::new("accurate", circle.clone());
+    let mut machine = start(
+        welcome_machine().with_practice_sequence(TrialSequence::from_trials([
+            speeded,
+            accurate,
+            SequencedTrial::new("x", circle),
+        ])),
+    );
+
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, STIMULUS_MS + 400);
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+    step(&mut machine, FEEDBACK_MS);
+
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, STIMULUS_MS + 400);
+    assert_eq!(state(&machine), Some(TrialState::Response));
+    step(&mut machine, RESPONSE_WINDOW_MS - 400);
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+    step(&mut machine, FEEDBACK_MS);
+
+    let windows: Vec<_> = machine
+        .results()
+        .iter()
+        .map(|r| r.requested_response_window_ms)
+        .collect();
+    assert_eq!(windows, [400, RESPONSE_WINDOW_MS]);
+}
+
+#[test]
+fn a_sequenced_expected_key_overrides_the_mapping() {
+    let letter = StimulusType::Text {
+        content: "K".into(),
+        size: 96.0,
+        color: [255, 255, 255, 255],
+    };
+    let non_target = SequencedTrial {
+        expected_key: Some(None),
+        ..SequencedTrial::new("nontarget", letter.clone())
+    };
+    let trials = [
+        non_target.clone(),
+        non_target,
+        SequencedTrial::new("target", letter),
+    ];
+    let mut machine =
+        start(welcome_machine().with_practice_sequence(TrialSequence::from_trials(trials)));
+
+    // The mapping expects Space for every stimulus, the sequence does not
+    step(&mut machine, FIXATION_MS);
+    assert!(press(&mut machine, ResponseKey::Space));
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS);
+    miss_trial(&mut machine);
+    step(&mut machine, FIXATION_MS);
+    assert!(press(&mut machine, ResponseKey::Space));
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS);
+
+    let correct: Vec<_> = machine.results().iter().map(|r| r.correct).collect();
+    assert_eq!(correct, [Some(false), Some(true), Some(true)]);
+}
+
+#[test]
+fn clicks_score_by_button_and_distance_from_the_target() {
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let trial = SequencedTrial {
+        position: Some((400.0, 300.0)),
+        ..SequencedTrial::new("circle", circle)
+    };
+    let mut machine = welcome_machine()
+        .with_response_mapping(ResponseMapping::new().with_rule_fn(
+            &[
+                ResponseKey::Mouse(MouseButton::Left),
+                ResponseKey::Mouse(MouseButton::Right),
+            ],
+            |_| Some(ResponseKey::Mouse(MouseButton::Left)),
+        ))
+        .with_practice_sequence(TrialSequence::from_trials(vec![trial; 3]));
+    machine.config.click_radius = Some(50.0);
+    let mut machine = start(machine);
+
+    for (position, button) in [
+        ((430.0, 340.0), MouseButton::Left),
+        ((480.0, 300.0), MouseButton::Left),
+        ((400.0, 300.0), MouseButton::Right),
+    ] {
+        step(&mut machine, FIXATION_MS + 100);
+        let timestamp_ns = Some(machine.timer.now());
+        assert!(machine.handle_event(ExperimentEvent::MouseClicked {
+            position,
+            button,
+            timestamp_ns,
+        }));
+        step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS);
+    }
+
+    let scored: Vec<_> = machine
+        .results()
+        .iter()
+        .map(|r| (r.click_distance, r.correct))
+        .collect();
+    assert_eq!(
+        scored,
+        [
+            (Some(50.0), Some(true)),
+            (Some(80.0), Some(false)),
+            (Some(0.0), Some(false)),
+        ]
+    );
+    assert_eq!(machine.results()[0].click_position, Some((430.0, 340.0)));
+}
+
+#[test]
+fn multi_stimulus_displays_are_laid_out_without_overlap() {
+    let disc = |color| StimulusType::Circle {
+        radius: 40.0,
+        color,
+    };
+    let target = disc([255, 0, 0, 255]);
+    let trial = SequencedTrial {
+        array: vec![disc([0, 0, 255, 255]); 5],
+        ..SequencedTrial::new("search", target.clone())
+    };
+    let mut machine = welcome_machine()
+        .with_practice_sequence(TrialSequence::from_trials(vec![trial; PRACTICE_TRIALS]))
+        .with_screen(Screen::new(1280, 720, 1.0));
+    machine.config.layouts = LayoutSet::default()
+        .with_condition(
+            "search",
+            Layout::Annulus {
+                radius: 200.0,
+                positions: Some(8),
+            },
+        )
+        .with_gap(10.0);
+    let mut machine = start(machine);
+    assert_eq!(machine.upcoming_stimuli().len(), 6);
+
+    for _ in 0..PRACTICE_TRIALS {
+        step(&mut machine, FIXATION_MS);
+        let shown = machine.current_stimuli();
+        assert_eq!(shown.len(), 6);
+        assert_eq!(shown[0].0, &target);
+        for (i, (_, a)) in shown.iter().enumerate() {
+            assert!(((a.0 - 640.0).hypot(a.1 - 360.0) - 200.0).abs() < 0.01);
+            for (_, b) in &shown[i + 1..] {
+                assert!((a.0 - b.0).hypot(a.1 - b.1) >= 90.0);
+            }
+        }
+        step(&mut machine, STIMULUS_MS);
+        assert!(machine.current_stimuli().is_empty());
+        step(&mut machine, RESPONSE_WINDOW_MS);
+        step(&mut machine, FEEDBACK_MS);
+    }
+
+    // Nine discs cannot fit on eight slots
+    let crowded = Layout::Annulus {
+        radius: 200.0,
+        positions: Some(8),
+    };
+    let screen = Screen::default();
+    let mut rng = StdRng::seed_from_u64(1);
+    assert!(crowded
+        .scatter(&screen, None, &[], &[40.0; 9], 0.0, &mut rng)
+        .is_none());
+    assert!(crowded
+        .scatter(&screen, None, &[], &[40.0; 8], 0.0, &mut rng)
+        .is_some());
+}
+
+#[test]
+fn last_practice_trial_leaves_the_phase() {
+    let mut machine = practice_machine();
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    assert_eq!(machine.results().len(), PRACTICE_TRIALS);
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+}
+
+#[test]
+fn blocks_open_with_a_countdown_under_the_progress_bar() {
+    let mut machine = welcome_machine();
+    machine.config.blocks = vec![BlockConfig::new(1).with_break(false), BlockConfig::new(1)];
+    machine.config.widgets.block_countdown_s = 3;
+    machine.config.widgets.progress_bar = true;
+    let mut machine = start(machine);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+
+    let countdown = |seconds| StimulusType::Countdown {
+        seconds,
+        size: 96.0,
+        color: [255, 255, 255, 255],
+    };
+    let bar = |fraction| StimulusType::ProgressBar {
+        width: 300.0,
+        height: 8.0,
+        fraction,
+        color: [255, 255, 255, 255],
+    };
+    assert_eq!(state(&machine), Some(TrialState::Countdown));
+    assert_eq!(
+        machine.widgets(),
+        vec![(countdown(3), (400.0, 300.0)), (bar(0.0), (400.0, 570.0))]
+    );
+    step(&mut machine, 1500);
+    assert_eq!(machine.widgets()[0].0, countdown(2));
+    step(&mut machine, 1500);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    assert_eq!(machine.widgets(), vec![(bar(0.0), (400.0, 570.0))]);
+
+    // The second block follows without a break, so its countdown starts at once
+    miss_trial(&mut machine);
+    assert_eq!(state(&machine), Some(TrialState::Countdown));
+    assert_eq!(machine.widgets()[1].0, bar(0.5));
+}
+
+#[test]
+fn skipping_a_block_moves_on_to_the_next_then_ends_the_phase() {
+    let disc = StimulusType::Circle {
+        radius: 30.0,
+        color: [0, 255, 0, 255],
+    };
+    let square = StimulusType::Rectangle {
+        width: 40.0,
+        height: 40.0,
+        color: [0, 0, 255, 255],
+    };
+    let mut machine = welcome_machine();
+    machine.config.blocks = vec![
+        BlockConfig::new(3).with_conditions(vec![disc]),
+        BlockConfig::new(2).with_conditions(vec![square.clone()]),
+    ];
+    let mut machine = start(machine);
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    step(&mut machine, FIXATION_MS);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+
+    assert!(machine.handle_event(ExperimentEvent::SkipBlock));
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    miss_trial(&mut machine);
+    assert!(machine.handle_event(ExperimentEvent::SkipBlock));
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Debrief);
+
+    let experiment: Vec<_> = machine.results()[PRACTICE_TRIALS..]
+        .iter()
+        .map(|r| (r.block, &r.stimulus))
+        .collect();
+    assert_eq!(experiment, [(Some(1), &square)]);
+}
+
+#[test]
+fn an_inserted_break_restarts_the_trial_in_progress() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    assert!(machine.handle_event(ExperimentEvent::InsertBreak));
+    assert!(machine.is_on_break() && machine.is_awaiting_input());
+    assert!(!machine.handle_event(ExperimentEvent::InsertBreak));
+    step(&mut machine, 10_000);
+    assert!(machine.is_on_break());
+
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    assert!(machine.results().is_empty());
+    miss_trial(&mut machine);
+    assert_eq!(machine.results().len(), 1);
+}
+
+#[test]
+fn practice_below_the_criterion_repeats_up_to_the_limit() {
+    let mut machine = welcome_machine();
+    machine.config.practice_criterion = Some(PracticeCriterion::new(0.6, 1));
+    let mut machine = start(machine);
+    hit_trial(&mut machine);
+    miss_trial(&mut machine);
+    miss_trial(&mut machine);
+
+    // One of three is below 60%: practice restarts after a break
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Practice);
+    assert!(machine.is_on_break() && machine.is_awaiting_input());
+    assert_eq!(machine.practice_attempt, 1);
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+
+    // The one repeat allowed is used up, so the experiment starts anyway
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+    assert_eq!(machine.results().len(), 2 * PRACTICE_TRIALS);
+}
+
+#[test]
+fn practice_meeting_the_criterion_moves_on() {
+    let mut machine = welcome_machine();
+    machine.config.practice_criterion = Some(PracticeCriterion::new(0.6, 3));
+    let mut machine = start(machine);
+    hit_trial(&mut machine);
+    miss_trial(&mut machine);
+    hit_trial(&mut machine);
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+    assert_eq!(machine.practice_attempt, 0);
+}
+
+#[test]
+fn dropped_frames_are_charged_to_the_trial_on_screen() {
+    let mut config = ExperimentConfig::<StandardPhase>::default();
+    config.practice_trials = 1;
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut machine = ExperimentStateMachine::new(
+        config,
+        HighPrecisionTimer::new().with_refresh_rate(60.0),
+        StdRng::seed_from_u64(7),
+    )
+    .with_practice_sequence(TrialSequence::from_trials(vec![SequencedTrial::new(
+        "circle", circle,
+    )]));
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert!(machine.handle_event(ExperimentEvent::CalibrationComplete));
+
+    let period_ns = 16_666_667;
+    machine.frame_presented(0);
+    machine.frame_presented(period_ns);
+    assert_eq!(machine.current.as_ref().unwrap().dropped_frames, 0);
+    // Four periods later: three refreshes went by without a new frame
+    machine.frame_presented(5 * period_ns);
+    assert_eq!(machine.current.as_ref().unwrap().dropped_frames, 3);
+    assert_eq!(machine.timer.dropped_frames(), 3);
+}
+
+#[test]
+fn deadlines_fire_early_by_the_onset_lead() {
+    let mut machine = practice_machine();
+    machine.onset_lead_ns = 8_000_000;
+    step(&mut machine, FIXATION_MS - 10);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    step(&mut machine, 2);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+}
+
+#[test]
+fn the_debrief_summarises_experiment_trials_but_not_practice() {
+    let mut machine = practice_machine();
+    for _ in 0..PRACTICE_TRIALS {
+        hit_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+    assert_eq!(machine.debrief().trials, 0);
+
+    hit_trial(&mut machine);
+    miss_trial(&mut machine);
+    let debrief = machine.debrief();
+    assert_eq!(debrief.trials, 2);
+    assert_eq!(debrief.correct, 1);
+    assert_eq!(debrief.accuracy(), Some(0.5));
+    assert!(debrief.mean_rt_ms.is_some());
+    assert_eq!(debrief.mean_rt_ms, debrief.median_rt_ms);
+}
+
+#[test]
+fn blocks_draw_their_own_conditions_with_a_break_between() {
+    let disc = StimulusType::Circle {
+        radius: 30.0,
+        color: [0, 255, 0, 255],
+    };
+    let square = StimulusType::Rectangle {
+        width: 40.0,
+        height: 40.0,
+        color: [0, 0, 255, 255],
+    };
+    let mut machine = welcome_machine();
+    machine.config.blocks = vec![
+        BlockConfig::new(2).with_conditions(vec![disc.clone()]),
+        BlockConfig::new(1).with_conditions(vec![square.clone()]),
+    ];
+    assert_eq!(machine.config.trials_for(&StandardPhase::Experiment), 3);
+    let mut machine = start(machine);
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+
+    miss_trial(&mut machine);
+    assert!(!machine.is_on_break());
+    miss_trial(&mut machine);
+    assert!(machine.is_on_break() && machine.is_awaiting_input());
+    // The break holds the next trial until Space
+    step(&mut machine, 10_000);
+    assert!(machine.is_on_break());
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+    miss_trial(&mut machine);
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Debrief);
+
+    let experiment: Vec<_> = machine.results()[PRACTICE_TRIALS..]
+        .iter()
+        .map(|r| (r.block, &r.stimulus))
+        .collect();
+    assert_eq!(
+        experiment,
+        [(Some(0), &disc), (Some(0), &disc), (Some(1), &square)]
+    );
+    assert!(machine.results()[..PRACTICE_TRIALS]
+        .iter()
+        .all(|r| r.block.is_none()));
+}
+
+#[test]
+fn block_breaks_hold_space_until_the_minimum_rest_is_over() {
+    let mut machine = welcome_machine();
+    machine.config.blocks = vec![BlockConfig::new(1).with_min_rest(5), BlockConfig::new(1)];
+    let mut machine = start(machine);
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    miss_trial(&mut machine);
+    assert_eq!(state(&machine), Some(TrialState::Break));
+    assert_eq!(machine.rest_remaining(), Some(5));
+
+    assert!(!machine.handle_event(ExperimentEvent::SpacePressed));
+    step(&mut machine, 2500);
+    assert_eq!(machine.rest_remaining(), Some(3));
+    assert!(!press(&mut machine, ResponseKey::Char('f')));
+    assert_eq!(state(&machine), Some(TrialState::Break));
+
+    step(&mut machine, 2500);
+    assert_eq!(machine.rest_remaining(), None);
+    assert!(machine.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+}
+
+#[test]
+fn inter_trial_interval_shows_its_stimulus_at_the_centre() {
+    let square = StimulusType::Rectangle {
+        width: 20.0,
+        height: 20.0,
+        color: [255, 255, 255, 255],
+    };
+    for (iti, shown) in [
+        (ItiDisplay::Blank, vec![]),
+        (ItiDisplay::Fixation, vec![]),
+        (
+            ItiDisplay::Stimulus(square.clone()),
+            vec![(&square, (400.0, 300.0))],
+        ),
+    ] {
+        let mut machine = welcome_machine();
+        machine.config.iti_display = iti;
+        let mut machine = start(machine);
+        step(&mut machine, FIXATION_MS);
+        step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS);
+        machine.timer.advance_ms(FEEDBACK_MS);
+        assert_eq!(machine.update(), vec![ExperimentEvent::TrialComplete]);
+        assert_eq!(state(&machine), Some(TrialState::Complete));
+        assert_eq!(machine.current_stimuli(), shown);
+    }
+}
+
+#[test]
+fn pause_freezes_the_trial() {
+    let mut machine = practice_machine();
+    step(&mut machine, 100);
+    assert!(machine.handle_event(ExperimentEvent::Pause));
+    step(&mut machine, FIXATION_MS * 4);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+}
+
+/// Keeps every marker pushed to it
+#[derive(Clone, Default)]
+struct MarkerLog(Arc<Mutex<Vec<Marker>>>);
+
+impl MarkerOutlet for MarkerLog {
+    fn push(&mut self, marker: &Marker) -> anyhow::Result<()> {
+        self.0.lock().unwrap().push(marker.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn trial_events_reach_marker_outlets_stamped_when_they_happened() {
+    let log = MarkerLog::default();
+    let mut machine = practice_machine().with_markers(Box::new(log.clone()));
+    step(&mut machine, FIXATION_MS);
+    let onset_ns = machine.timer.now();
+    machine.timer.advance_ms(150);
+    let pressed_ns = machine.timer.now();
+    // Handled a little after the key went down
+    machine.timer.advance_ms(5);
+    assert!(machine.handle_event(ExperimentEvent::ResponseReceived {
+        key: ResponseKey::Space,
+        timestamp_ns: Some(pressed_ns),
+    }));
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS);
+    miss_trial(&mut machine);
+    let missed_ns = machine.timer.now() - FEEDBACK_MS * 1_000_000;
+
+    let markers: Vec<(String, u64)> = log
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|m| (m.event.label(), m.timestamp_ns))
+        .collect();
+    assert_eq!(
+        markers,
+        [
+            ("stimulus_onset/0/circle".to_string(), onset_ns),
+            ("response/0/Space".to_string(), pressed_ns),
+            ("feedback/0/correct".to_string(), pressed_ns + 5_000_000),
+            (
+                "stimulus_onset/1/circle".to_string(),
+                missed_ns - (STIMULUS_MS + RESPONSE_WINDOW_MS) * 1_000_000
+            ),
+            ("feedback/1/incorrect".to_string(), missed_ns),
+        ]
+    );
+}
+
+#[test]
+fn external_markers_are_logged_while_paused_and_exported() {
+    let mut machine = practice_machine();
+    assert!(machine.handle_event(ExperimentEvent::Pause));
+    assert!(machine.handle_event(ExperimentEvent::Marker {
+        label: "run1".to_string(),
+        timestamp_ns: Some(2_500_000_000),
+    }));
+    let [marker] = machine.logged_markers() else {
+        panic!("expected one marker");
+    };
+    assert_eq!(marker.timestamp_ns, 2_500_000_000);
+
+    let tsv = bids_events_tsv(machine.results(), machine.logged_markers(), Some(0));
+    assert!(
+        tsv.contains("2.500000\t0.000000\texternal/run1\tn/a"),
+        "{}",
+        tsv
+    );
+}
+
+#[test]
+fn scanner_pulses_are_logged_and_hold_the_run_until_the_first() {
+    let mut machine = practice_machine();
+    machine.config.scanner = Some(ScannerConfig::default());
+    // The trigger interface types '5' per volume; it is never a response
+    let now = machine.timer.now();
+    assert!(machine.handle_raw_input(RawInput::new(RawEvent::Key(ResponseKey::Char('5')), now)));
+    assert_eq!(machine.current.as_ref().unwrap().response_key, None);
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+    assert!(machine.is_waiting_for_scanner());
+    step(&mut machine, FIXATION_MS * 4);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+
+    let first_ns = machine.timer.now();
+    assert!(machine.handle_raw_input(RawInput::new(RawEvent::ScannerPulse, first_ns)));
+    assert!(!machine.is_waiting_for_scanner());
+    assert_eq!(machine.scanner_zero_ns(), Some(first_ns));
+    step(&mut machine, FIXATION_MS);
+    assert_ne!(state(&machine), Some(TrialState::Fixation));
+
+    let labels: Vec<String> = machine
+        .logged_markers()
+        .iter()
+        .map(|m| m.event.label())
+        .collect();
+    assert_eq!(labels, ["scanner_pulse/1", "scanner_pulse/2"]);
+}
+
+#[test]
+fn clock_is_sampled_against_utc_at_start_periodically_and_on_request() {
+    let mut machine = practice_machine();
+    machine.session = Some(SessionInfo::new("P01", None, 1));
+    step(&mut machine, 0);
+    let start_ns = machine.timer.now();
+    step(&mut machine, 30_000);
+    step(&mut machine, 30_000);
+    machine.sample_clock();
+
+    let samples = &machine.session.as_ref().unwrap().clock_sync;
+    let timer_ns: Vec<u64> = samples.iter().map(|s| s.timer_ns).collect();
+    let minute_ns = start_ns + 60_000_000_000;
+    assert_eq!(timer_ns, [start_ns, minute_ns, minute_ns]);
+    assert!(samples.iter().all(|s| s.utc_ns > 0));
+
+    let json = results_to_json(machine.session.as_ref(), machine.results());
+    assert_eq!(json["metadata"]["clock_sync"][1]["timer_ns"], minute_ns);
+}
+
+#[test]
+fn abort_keeps_results_and_records_the_reason() {
+    let mut machine = practice_machine();
+    machine.session = Some(SessionInfo::new("P01", None, 1));
+    miss_trial(&mut machine);
+    step(&mut machine, FIXATION_MS);
+    assert!(machine.handle_event(ExperimentEvent::Abort {
+        reason: "participant withdrew".to_string(),
+    }));
+    assert!(!press(&mut machine, ResponseKey::Space));
+    assert_eq!(machine.results().len(), 1);
+
+    let json = results_to_json(machine.session.as_ref(), machine.results());
+    assert_eq!(json["metadata"]["aborted"], true);
+    assert_eq!(json["metadata"]["abort_reason"], "participant withdrew");
+}
+
+#[test]
+fn leaving_the_window_pauses_on_the_return_screen_and_marks_the_trial() {
+    let mut machine = practice_machine();
+    machine.session = Some(SessionInfo::new("P01", None, 1));
+    step(&mut machine, 100);
+    let left_ns = machine.timer.now();
+    let interrupt = || ExperimentEvent::Interrupt {
+        reason: "focus_lost".to_string(),
+    };
+    assert!(machine.handle_event(interrupt()));
+    assert!(!machine.handle_event(interrupt()));
+    assert!(machine.is_paused() && machine.is_awaiting_input());
+    step(&mut machine, FIXATION_MS * 4);
+    assert_eq!(state(&machine), Some(TrialState::Fixation));
+
+    let back_ns = machine.timer.now();
+    assert!(machine.handle_raw_input(RawInput::new(RawEvent::Key(ResponseKey::Space), back_ns)));
+    assert!(!machine.is_interrupted() && !machine.is_paused());
+    miss_trial(&mut machine);
+    assert!(machine.results()[0].interrupted);
+
+    let session = machine.session.as_ref().unwrap();
+    let [interruption] = session.interruptions.as_slice() else {
+        panic!("expected one interruption");
+    };
+    assert_eq!(interruption.reason, "focus_lost");
+    assert_eq!(
+        (interruption.start_ns, interruption.end_ns),
+        (left_ns, Some(back_ns))
+    );
+    assert_eq!(interruption.trial_id, Some(machine.results()[0].trial_id));
+    let labels: Vec<String> = machine
+        .logged_markers()
+        .iter()
+        .map(|m| m.event.label())
+        .collect();
+    assert_eq!(labels, ["interrupted/focus_lost", "returned"]);
+
+    let json = results_to_json(machine.session.as_ref(), machine.results());
+    assert_eq!(json["metadata"]["interruptions"][0]["end_ns"], back_ns);
+}
+
+#[test]
+fn the_experimenters_resume_ends_an_interruption() {
+    let mut machine = practice_machine();
+    machine.session = Some(SessionInfo::new("P01", None, 1));
+    step(&mut machine, 100);
+    assert!(machine.handle_event(ExperimentEvent::Interrupt {
+        reason: "fullscreen_exited".to_string(),
+    }));
+    step(&mut machine, 1000);
+    let resumed_ns = machine.timer.now();
+    assert!(machine.handle_event(ExperimentEvent::Resume));
+    assert!(!machine.is_interrupted() && !machine.is_paused());
+    assert!(!machine.is_awaiting_input());
+
+    // The clock picks up where it stopped
+    step(&mut machine, FIXATION_MS - 100);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    let session = machine.session.as_ref().unwrap();
+    assert_eq!(session.interruptions[0].end_ns, Some(resumed_ns));
+    let labels: Vec<String> = machine
+        .logged_markers()
+        .iter()
+        .map(|m| m.event.label())
+        .collect();
+    assert_eq!(labels, ["interrupted/fullscreen_exited", "returned"]);
+
+    // An interruption while the experimenter had paused resumes too
+    assert!(machine.handle_event(ExperimentEvent::Pause));
+    assert!(machine.handle_event(ExperimentEvent::Interrupt {
+        reason: "focus_lost".to_string(),
+    }));
+    assert!(machine.handle_event(ExperimentEvent::Resume));
+    assert!(!machine.is_interrupted() && !machine.is_paused());
+}
+
+#[test]
+fn checkpoint_restores_results_with_their_stimuli() {
+    let mut machine = practice_machine();
+    step(&mut machine, FIXATION_MS);
+    step(&mut machine, 100);
+    press(&mut machine, ResponseKey::Space);
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS);
+    assert_eq!(machine.results().len(), 1);
+
+    let path = std::env::temp_dir().join(format!("cogex_checkpoint_{}.json", std::process::id()));
+    machine.save_checkpoint(&path).unwrap();
+    let mut resumed = practice_machine();
+    resumed.resume_from_checkpoint(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(resumed.results(), machine.results());
+    assert!(matches!(
+        resumed.results()[0].stimulus,
+        StimulusType::Circle { .. }
+    ));
+}
+
+#[test]
+fn a_resumed_session_continues_at_the_next_trial_after_a_break() {
+    let mut machine = practice_machine();
+    miss_trial(&mut machine);
+    miss_trial(&mut machine);
+    let path = std::env::temp_dir().join(format!(
+        "cogex_checkpoint_resume_{}.json",
+        std::process::id()
+    ));
+    machine.save_checkpoint(&path).unwrap();
+
+    let mut changed = welcome_machine();
+    changed.config.response_window_ms += 1;
+    assert!(changed.resume_from_checkpoint(&path).is_err());
+
+    let mut resumed = welcome_machine();
+    resumed.resume_from_checkpoint(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(*resumed.current_phase(), StandardPhase::Practice);
+    assert!(resumed.is_on_break() && resumed.is_awaiting_input());
+    assert!(resumed.handle_event(ExperimentEvent::SpacePressed));
+    assert_eq!(state(&resumed), Some(TrialState::Fixation));
+
+    miss_trial(&mut resumed);
+    step(&mut resumed, 0);
+    assert_eq!(resumed.results().len(), PRACTICE_TRIALS);
+    assert_eq!(*resumed.current_phase(), StandardPhase::Experiment);
+}
+
+#[test]
+fn checkpoints_are_named_per_session() {
+    let dir = std::path::Path::new("sessions");
+    let session = SessionInfo::new("P 01", None, 2);
+    assert_eq!(
+        checkpoint_path(dir, Some(&session)),
+        dir.join("checkpoint_P_01_s2.json")
+    );
+    assert_eq!(
+        checkpoint_path(dir, None),
+        dir.join("checkpoint_anonymous.json")
+    );
+}
+
+#[test]
+fn output_files_are_named_per_session_and_never_overwritten() {
+    let root = std::env::temp_dir().join(format!("cogex_output_{}", std::process::id()));
+    let session = SessionInfo::new("P 01", None, 2);
+    let output = OutputManager::new(&root, "Go/No-Go", Some(&session)).unwrap();
+    assert_eq!(output.dir(), root.join("GoNoGo"));
+    assert!(output.stem().starts_with("P_01_s2_"));
+
+    let path = output.write(".json", "first").unwrap();
+    assert_eq!(path, output.path(".json"));
+    assert!(output.write(".json", "second").is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn session_metadata_records_config_and_calibration() {
+    let welcome = welcome_machine();
+    let json = serde_json::to_value(welcome.metadata()).unwrap();
+    assert_eq!(json["config"]["practice_trials"], PRACTICE_TRIALS);
+    assert_eq!(
+        json["crates"]["cogex-experiment"],
+        cogex_experiment::VERSION
+    );
+    assert!(json["calibration"].is_null());
+    assert!(json.get("caveats").is_none());
+
+    let practice = practice_machine();
+    let metadata = practice.metadata().with_caveat("Browser clock");
+    let json = serde_json::to_value(metadata).unwrap();
+    assert!(json["calibration"]["effective_fps"].as_f64().unwrap() > 0.0);
+    assert_eq!(json["caveats"][0], "Browser clock");
+}
+
+/// Third-party paradigm drawing its own stimulus
+struct Dots;
+
+impl Paradigm for Dots {
+    fn sequence(&self, trials: usize, _rng: &mut dyn RngCore) -> anyhow::Result<TrialSequence> {
+        let circle = StimulusType::Circle {
+            radius: 10.0,
+            color: [255; 4],
+        };
+        let trials = vec![SequencedTrial::new("dot", circle); trials];
+        Ok(TrialSequence::from_trials(trials))
+    }
+
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        ResponseMapping::new().with_fallback(ResponseKey::Space)
+    }
+
+    fn draw(&self, _stimulus: &StimulusType, elapsed: Duration) -> Option<Vec<Primitive>> {
+        Some(vec![Primitive::Dot {
+            offset: (elapsed.as_millis() as f32, 0.0),
+            size: 4.0,
+            color: [255; 4],
+        }])
+    }
+}
+
+#[test]
+fn registered_paradigm_runs_by_name_and_draws_its_stimulus() {
+    let registry = ParadigmRegistry::builtin().with("dots", |_| Ok(Box::new(Dots)));
+    assert!(registry.names().contains(&"dots"));
+    let Err(unknown) = welcome_machine().with_named_paradigm(&registry, "flanker") else {
+        panic!("unregistered paradigm was accepted");
+    };
+    assert!(unknown.to_string().contains("dots"));
+
+    let machine = welcome_machine()
+        .with_named_paradigm(&registry, "dots")
+        .unwrap();
+    let mut machine = start(machine);
+    assert_eq!(machine.paradigm_primitives(), None);
+    step(&mut machine, FIXATION_MS);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert!(matches!(
+        machine.paradigm_primitives().as_deref(),
+        Some([Primitive::Dot { .. }])
+    ));
+}
+
+#[test]
+fn phase_graph_branches_on_practice_accuracy() {
+    // Failed practice repeats once, then goes on to the experiment anyway
+    let graph =
+        PhaseGraph::linear().branch(StandardPhase::Practice, StandardPhase::Practice, |exit| {
+            exit.accuracy < Some(0.5) && exit.visits < 2
+        });
+    let mut machine = start(welcome_machine().with_phase_graph(graph));
+    assert!(!machine.is_final_phase());
+
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Practice);
+    assert_eq!(machine.phase_trial_number, 0);
+    assert_eq!(machine.results().len(), PRACTICE_TRIALS);
+
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Experiment);
+}
+
+#[test]
+fn questionnaire_collects_answers_before_debrief() {
+    let questionnaire = Questionnaire::new(vec![
+        QuestionItem::likert("effort", "How hard did you try?", 7, "Not at all", "Very"),
+        QuestionItem::choice("strategy", "What did you favour?", &["Speed", "Accuracy"]),
+    ]);
+    let graph = PhaseGraph::linear().edge(StandardPhase::Practice, StandardPhase::Questionnaire);
+    let mut machine = start(
+        welcome_machine()
+            .with_phase_graph(graph)
+            .with_questionnaire(questionnaire),
+    );
+    for _ in 0..PRACTICE_TRIALS {
+        miss_trial(&mut machine);
+    }
+    step(&mut machine, 0);
+    assert_eq!(*machine.current_phase(), StandardPhase::Questionnaire);
+
+    let key = |c| RawInput::new(RawEvent::Key(ResponseKey::Char(c)), 0);
+    assert!(machine.handle_raw_input(key('6')));
+    assert!(!machine.handle_raw_input(key('3')));
+    assert!(machine.handle_raw_input(key('2')));
+    assert_eq!(*machine.current_phase(), StandardPhase::Debrief);
+
+    let json = questionnaire_to_json(None, machine.questionnaire.as_ref().unwrap());
+    assert_eq!(json["items"][0]["answer"], "6");
+    assert_eq!(json["items"][1]["answer"], "Accuracy");
+}
+
+#[test]
+fn rating_scale_records_the_confirmed_rating_and_its_trajectory() {
+    let scale = StimulusType::RatingScale {
+        width: 600.0,
+        low: "Not at all".into(),
+        high: "Extremely".into(),
+        color: [255, 255, 255, 255],
+    };
+    let trials = vec![SequencedTrial::new("rating", scale); PRACTICE_TRIALS];
+    let mut machine =
+        start(welcome_machine().with_practice_sequence(TrialSequence::from_trials(trials)));
+    step(&mut machine, FIXATION_MS);
+
+    // Space does nothing until the marker is placed
+    assert!(!press(&mut machine, ResponseKey::Space));
+    assert!(press(&mut machine, ResponseKey::ArrowRight));
+    step(&mut machine, 100);
+    assert!(press(&mut machine, ResponseKey::ArrowRight));
+    step(&mut machine, STIMULUS_MS);
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert!(press(&mut machine, ResponseKey::Space));
+    assert_eq!(state(&machine), Some(TrialState::Feedback));
+
+    step(&mut machine, RESPONSE_WINDOW_MS + FEEDBACK_MS);
+    let result = &machine.results()[0];
+    assert!((result.rating.unwrap() - 0.52).abs() < 1e-6);
+    assert_eq!(result.rating_trajectory.len(), 2);
+    assert_eq!(result.rating_trajectory[1].0, 100 * 1_000_000);
+    assert_eq!(result.correct, None);
+}
+
+#[test]
+fn keys_outside_the_response_set_are_logged_but_not_scored() {
+    let mut machine = welcome_machine();
+    machine.config.response_keys = ResponseKeySet::new().with_phase(
+        StandardPhase::Practice,
+        &[ResponseKey::Char('f'), ResponseKey::Char('j')],
+    );
+    let mut machine = start(machine);
+    step(&mut machine, FIXATION_MS);
+
+    assert!(!press(&mut machine, ResponseKey::Char('x')));
+    assert!(!press(&mut machine, ResponseKey::Space));
+    assert_eq!(state(&machine), Some(TrialState::Stimulus));
+    assert!(press(&mut machine, ResponseKey::Char('f')));
+    step(&mut machine, STIMULUS_MS + RESPONSE_WINDOW_MS + FEEDBACK_MS);
+
+    let result = &machine.results()[0];
+    assert_eq!(result.response_key, Some(ResponseKey::Char('f')));
+    assert_eq!(result.correct, Some(false));
+    assert_eq!(
+        result.invalid_keys,
+        vec![ResponseKey::Char('x'), ResponseKey::Space]
+    );
+}
@@ -0,0 +1,95 @@
+use cogex_core::{PresentationTimestamps, ResponseKey, StimulusType, TrialOutcome, TrialResult};
+use cogex_experiment::{condition_summaries, Paradigm, Stroop};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn result(condition: &str, rt_ms: u64, correct: bool) -> TrialResult<StimulusType> {
+    TrialResult {
+        trial_id: 0,
+        stimulus: StimulusType::Text {
+            content: "RED".into(),
+            size: 64.0,
+            color: [220, 30, 30, 255],
+        },
+        condition: condition.to_string(),
+        set_size: 1,
+        response_key: Some(ResponseKey::Char('d')),
+        click_position: None,
+        click_distance: None,
+        reaction_time_ns: Some(rt_ms * 1_000_000),
+        voice_onset_ns: None,
+        voice_reaction_time_ns: None,
+        intensity: None,
+        block: Some(0),
+        correct: Some(correct),
+        outcome: TrialOutcome::Response,
+        rating: None,
+        rating_trajectory: Vec::new(),
+        invalid_keys: Vec::new(),
+        timestamp_ns: 0,
+        presentation: PresentationTimestamps::default(),
+        requested_fixation_ms: 500,
+        requested_stimulus_ms: 2000,
+        requested_response_window_ms: 2000,
+        requested_feedback_ms: 300,
+        requested_cue_ms: None,
+        requested_soa_ms: None,
+        requested_cue_frames: None,
+        requested_mask_frames: None,
+        requested_iti_ms: 0,
+        dropped_frames: 0,
+        interrupted: false,
+    }
+}
+
+#[test]
+fn words_are_congruent_when_they_name_their_ink() {
+    let task = Stroop::default().with_congruent_proportion(0.25);
+    let mapping = task.response_mapping();
+    let sequence = task.sequence(48, &mut StdRng::seed_from_u64(11)).unwrap();
+
+    let mut congruent = 0;
+    for trial in sequence.iter() {
+        let StimulusType::Text { content, color, .. } = &trial.stimulus else {
+            panic!("Expected text, got {:?}", trial.stimulus);
+        };
+        let word = task.colors.iter().find(|c| *c.word == **content).unwrap();
+        let ink = task.colors.iter().find(|c| c.ink == *color).unwrap();
+        assert_eq!(trial.condition == Stroop::CONGRUENT, word == ink);
+        // The ink, not the word, decides the key
+        assert_eq!(mapping.expected_key(&trial.stimulus), Some(ink.key));
+        congruent += usize::from(word == ink);
+    }
+    assert_eq!(congruent, 12);
+}
+
+#[test]
+fn invalid_designs_are_rejected() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let one_colour = Stroop::new(Stroop::default().colors[..1].to_vec());
+    assert!(one_colour.sequence(8, &mut rng).is_err());
+    let task = Stroop::default().with_congruent_proportion(1.5);
+    assert!(task.sequence(8, &mut rng).is_err());
+}
+
+#[test]
+fn the_summary_reports_rt_per_condition_and_the_stroop_effect() {
+    let results = [
+        result(Stroop::CONGRUENT, 500, true),
+        result(Stroop::CONGRUENT, 600, true),
+        result(Stroop::INCONGRUENT, 700, true),
+        result(Stroop::INCONGRUENT, 300, false),
+    ];
+    let summaries = condition_summaries(&results);
+    assert_eq!(summaries[Stroop::INCONGRUENT].accuracy(), 0.5);
+    assert_eq!(summaries[Stroop::INCONGRUENT].mean_rt_ms, Some(700.0));
+
+    assert_eq!(
+        Stroop::default().summary(&results),
+        [
+            "congruent: 100% correct, 550 ms",
+            "incongruent: 50% correct, 700 ms",
+            "Stroop effect: 150 ms",
+        ]
+    );
+}
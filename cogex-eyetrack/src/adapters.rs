@@ -0,0 +1,79 @@
+use crate::tracker::{Calibration, EyeTracker, GazeSample};
+use anyhow::{bail, Result};
+use crossbeam_channel::Receiver;
+
+/// Tobii Pro tracker, found by its device address.
+///
+/// Only the connection settings exist so far; the SDK is not bound, so
+/// every operation fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TobiiTracker {
+    /// e.g. `tobii-prp://IS404-100107417574`; empty picks the first found
+    pub address: String,
+}
+
+impl TobiiTracker {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+        }
+    }
+}
+
+impl EyeTracker for TobiiTracker {
+    fn name(&self) -> &str {
+        "Tobii Pro"
+    }
+
+    fn connect(&mut self) -> Result<()> {
+        bail!("Tobii Pro SDK is not available in this build")
+    }
+
+    fn calibrate(&mut self, _targets: &[(f32, f32)]) -> Result<Calibration> {
+        bail!("Tobii Pro SDK is not available in this build")
+    }
+
+    fn subscribe(&mut self) -> Result<Receiver<GazeSample>> {
+        bail!("Tobii Pro SDK is not available in this build")
+    }
+}
+
+/// SR Research EyeLink, reached through its host PC; like `TobiiTracker`,
+/// a skeleton until the SDK is bound
+#[derive(Debug, Clone, PartialEq)]
+pub struct EyeLinkTracker {
+    pub host: String,
+}
+
+impl EyeLinkTracker {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+        }
+    }
+}
+
+impl Default for EyeLinkTracker {
+    /// The host PC's factory address
+    fn default() -> Self {
+        Self::new("100.1.1.1")
+    }
+}
+
+impl EyeTracker for EyeLinkTracker {
+    fn name(&self) -> &str {
+        "EyeLink"
+    }
+
+    fn connect(&mut self) -> Result<()> {
+        bail!("EyeLink SDK is not available in this build")
+    }
+
+    fn calibrate(&mut self, _targets: &[(f32, f32)]) -> Result<Calibration> {
+        bail!("EyeLink SDK is not available in this build")
+    }
+
+    fn subscribe(&mut self) -> Result<Receiver<GazeSample>> {
+        bail!("EyeLink SDK is not available in this build")
+    }
+}
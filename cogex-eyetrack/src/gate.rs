@@ -0,0 +1,76 @@
+use crate::tracker::GazeSample;
+use crossbeam_channel::Receiver;
+
+/// Tracks whether gaze has stayed on a target for a minimum time
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixationGate {
+    pub target: (f32, f32),
+    pub radius: f32,
+    pub hold_ms: u64,
+    /// First sample of the current run on target
+    since_ns: Option<u64>,
+    last_ns: u64,
+}
+
+impl FixationGate {
+    pub fn new(target: (f32, f32), radius: f32, hold_ms: u64) -> Self {
+        Self {
+            target,
+            radius,
+            hold_ms,
+            since_ns: None,
+            last_ns: 0,
+        }
+    }
+
+    /// Looking away or losing the eye restarts the hold
+    pub fn feed(&mut self, sample: &GazeSample) {
+        let on_target = sample.position.is_some_and(|(x, y)| {
+            let (dx, dy) = (x - self.target.0, y - self.target.1);
+            (dx * dx + dy * dy).sqrt() <= self.radius
+        });
+        if !on_target {
+            self.since_ns = None;
+        } else if self.since_ns.is_none() {
+            self.since_ns = Some(sample.timestamp_ns);
+        }
+        self.last_ns = sample.timestamp_ns;
+    }
+
+    /// Whether the latest samples complete the hold
+    pub fn is_held(&self) -> bool {
+        self.since_ns
+            .is_some_and(|since| self.last_ns - since >= self.hold_ms * 1_000_000)
+    }
+
+    pub fn reset(&mut self) {
+        self.since_ns = None;
+    }
+}
+
+/// Gaze stream checked against a fixation gate, for gaze-contingent trials
+#[derive(Debug)]
+pub struct GazeMonitor {
+    pub samples: Receiver<GazeSample>,
+    pub gate: FixationGate,
+}
+
+impl GazeMonitor {
+    pub fn new(samples: Receiver<GazeSample>, gate: FixationGate) -> Self {
+        Self { samples, gate }
+    }
+
+    /// Feeds pending samples to the gate; returns whether fixation is held
+    pub fn poll(&mut self) -> bool {
+        for sample in self.samples.try_iter() {
+            self.gate.feed(&sample);
+        }
+        self.gate.is_held()
+    }
+
+    /// Starts a new hold, dropping samples from before it
+    pub fn reset(&mut self) {
+        while self.samples.try_recv().is_ok() {}
+        self.gate.reset();
+    }
+}
@@ -0,0 +1,11 @@
+pub mod adapters;
+pub mod gate;
+pub mod mock;
+pub mod tracker;
+pub use adapters::{EyeLinkTracker, TobiiTracker};
+pub use gate::{FixationGate, GazeMonitor};
+pub use mock::{MockGaze, MockTracker};
+pub use tracker::{Calibration, EyeTracker, GazeSample};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
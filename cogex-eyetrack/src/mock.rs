@@ -0,0 +1,117 @@
+use crate::tracker::{Calibration, EyeTracker, GazeSample};
+use anyhow::{bail, Result};
+use cogex_timing::Timer;
+use crossbeam_channel::{unbounded, Receiver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Where the mock tracker reports gaze; shared with the test or script
+/// driving it
+#[derive(Debug, Clone, Default)]
+pub struct MockGaze(Arc<Mutex<Option<(f32, f32)>>>);
+
+impl MockGaze {
+    pub fn look_at(&self, position: (f32, f32)) {
+        *self.0.lock().unwrap() = Some(position);
+    }
+
+    /// Reports tracking loss, as for a blink
+    pub fn lose(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    pub fn get(&self) -> Option<(f32, f32)> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// Tracker without hardware, streaming `MockGaze` at a fixed rate
+pub struct MockTracker<T: Timer<Timestamp = u64>> {
+    timer: T,
+    rate_hz: f64,
+    gaze: MockGaze,
+    connected: bool,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T: Timer<Timestamp = u64> + 'static> MockTracker<T> {
+    pub fn new(timer: T, rate_hz: f64) -> Self {
+        Self {
+            timer,
+            rate_hz: rate_hz.max(1.0),
+            gaze: MockGaze::default(),
+            connected: false,
+            stop: Arc::new(AtomicBool::new(false)),
+            worker: None,
+        }
+    }
+
+    /// Handle for moving the reported gaze
+    pub fn gaze(&self) -> MockGaze {
+        self.gaze.clone()
+    }
+}
+
+impl<T: Timer<Timestamp = u64> + 'static> EyeTracker for MockTracker<T> {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn connect(&mut self) -> Result<()> {
+        self.connected = true;
+        Ok(())
+    }
+
+    /// Reports the distance from each target to the current gaze
+    fn calibrate(&mut self, targets: &[(f32, f32)]) -> Result<Calibration> {
+        if !self.connected {
+            bail!("Mock tracker is not connected");
+        }
+        let errors = targets
+            .iter()
+            .map(|&(tx, ty)| {
+                self.gaze.get().map_or(f32::INFINITY, |(x, y)| {
+                    ((x - tx).powi(2) + (y - ty).powi(2)).sqrt()
+                })
+            })
+            .collect();
+        Ok(Calibration { errors })
+    }
+
+    fn subscribe(&mut self) -> Result<Receiver<GazeSample>> {
+        if !self.connected {
+            bail!("Mock tracker is not connected");
+        }
+        if self.worker.is_some() {
+            bail!("Mock tracker is already streaming");
+        }
+        let (sender, receiver) = unbounded();
+        let (timer, gaze, stop) = (self.timer.clone(), self.gaze.clone(), self.stop.clone());
+        let period = Duration::from_secs_f64(1.0 / self.rate_hz);
+        self.worker = Some(std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                let sample = GazeSample {
+                    timestamp_ns: timer.now(),
+                    position: gaze.get(),
+                };
+                if sender.send(sample).is_err() {
+                    return;
+                }
+                timer.sleep(period);
+            }
+        }));
+        Ok(receiver)
+    }
+}
+
+impl<T: Timer<Timestamp = u64>> Drop for MockTracker<T> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
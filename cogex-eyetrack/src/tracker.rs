@@ -0,0 +1,36 @@
+use anyhow::Result;
+use crossbeam_channel::Receiver;
+
+/// One gaze estimate, timed on the experiment timer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GazeSample {
+    pub timestamp_ns: u64,
+    /// Binocular gaze in stimulus coordinates; `None` while tracking is lost
+    pub position: Option<(f32, f32)>,
+}
+
+/// Per-target accuracy of a calibration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calibration {
+    /// Validation error at each target, in stimulus units
+    pub errors: Vec<f32>,
+}
+
+impl Calibration {
+    pub fn mean_error(&self) -> f32 {
+        if self.errors.is_empty() {
+            return 0.0;
+        }
+        self.errors.iter().sum::<f32>() / self.errors.len() as f32
+    }
+}
+
+/// Eye tracker backend
+pub trait EyeTracker: Send {
+    fn name(&self) -> &str;
+    fn connect(&mut self) -> Result<()>;
+    /// Runs the tracker's calibration over `targets` in stimulus coordinates
+    fn calibrate(&mut self, targets: &[(f32, f32)]) -> Result<Calibration>;
+    /// Starts streaming; samples arrive until the tracker is dropped
+    fn subscribe(&mut self) -> Result<Receiver<GazeSample>>;
+}
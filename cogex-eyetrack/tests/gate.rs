@@ -0,0 +1,48 @@
+use cogex_eyetrack::{FixationGate, GazeMonitor, GazeSample};
+
+fn sample(ms: u64, position: Option<(f32, f32)>) -> GazeSample {
+    GazeSample {
+        timestamp_ns: ms * 1_000_000,
+        position,
+    }
+}
+
+#[test]
+fn fixation_is_held_once_gaze_stays_on_target_long_enough() {
+    let mut gate = FixationGate::new((400.0, 300.0), 50.0, 100);
+    gate.feed(&sample(0, Some((420.0, 310.0))));
+    gate.feed(&sample(60, Some((380.0, 280.0))));
+    assert!(!gate.is_held());
+    gate.feed(&sample(100, Some((400.0, 350.0))));
+    assert!(gate.is_held());
+}
+
+#[test]
+fn looking_away_or_losing_the_eye_restarts_the_hold() {
+    let mut gate = FixationGate::new((400.0, 300.0), 50.0, 100);
+    gate.feed(&sample(0, Some((400.0, 300.0))));
+    gate.feed(&sample(50, Some((460.0, 300.0))));
+    gate.feed(&sample(60, Some((400.0, 300.0))));
+    gate.feed(&sample(140, Some((400.0, 300.0))));
+    assert!(!gate.is_held());
+    gate.feed(&sample(150, None));
+    gate.feed(&sample(160, Some((400.0, 300.0))));
+    gate.feed(&sample(259, Some((400.0, 300.0))));
+    assert!(!gate.is_held());
+    gate.feed(&sample(260, Some((400.0, 300.0))));
+    assert!(gate.is_held());
+    gate.reset();
+    assert!(!gate.is_held());
+}
+
+#[test]
+fn the_monitor_drops_samples_from_before_a_reset() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let mut monitor = GazeMonitor::new(receiver, FixationGate::new((0.0, 0.0), 10.0, 100));
+    sender.send(sample(0, Some((0.0, 0.0)))).unwrap();
+    monitor.reset();
+    sender.send(sample(100, Some((0.0, 0.0)))).unwrap();
+    assert!(!monitor.poll());
+    sender.send(sample(200, Some((0.0, 0.0)))).unwrap();
+    assert!(monitor.poll());
+}
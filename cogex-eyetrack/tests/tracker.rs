@@ -0,0 +1,50 @@
+use cogex_eyetrack::{EyeLinkTracker, EyeTracker, MockTracker, TobiiTracker};
+use cogex_timing::HighPrecisionTimer;
+use std::time::Duration;
+
+#[test]
+fn the_mock_tracker_streams_the_gaze_it_is_given() {
+    let mut tracker = MockTracker::new(HighPrecisionTimer::new(), 500.0);
+    assert!(tracker.subscribe().is_err());
+    tracker.connect().unwrap();
+    let gaze = tracker.gaze();
+    gaze.look_at((100.0, 200.0));
+    let samples = tracker.subscribe().unwrap();
+    assert!(tracker.subscribe().is_err());
+
+    let first = samples.recv_timeout(Duration::from_secs(1)).unwrap();
+    assert_eq!(first.position, Some((100.0, 200.0)));
+    gaze.lose();
+    // Samples already queued may still show the old gaze
+    let lost = std::iter::repeat_with(|| samples.recv_timeout(Duration::from_secs(1)).unwrap())
+        .find(|sample| sample.position.is_none())
+        .unwrap();
+    assert!(lost.timestamp_ns > first.timestamp_ns);
+}
+
+#[test]
+fn mock_calibration_reports_the_distance_to_each_target() {
+    let mut tracker = MockTracker::new(HighPrecisionTimer::new(), 500.0);
+    assert!(tracker.calibrate(&[(0.0, 0.0)]).is_err());
+    tracker.connect().unwrap();
+    tracker.gaze().look_at((3.0, 4.0));
+    let calibration = tracker.calibrate(&[(0.0, 0.0), (3.0, 4.0)]).unwrap();
+    assert_eq!(calibration.errors, [5.0, 0.0]);
+    assert_eq!(calibration.mean_error(), 2.5);
+
+    tracker.gaze().lose();
+    let calibration = tracker.calibrate(&[(0.0, 0.0)]).unwrap();
+    assert_eq!(calibration.errors, [f32::INFINITY]);
+}
+
+#[test]
+fn hardware_adapters_fail_until_their_sdk_is_bound() {
+    let mut trackers: Vec<Box<dyn EyeTracker>> = vec![
+        Box::new(TobiiTracker::new("")),
+        Box::new(EyeLinkTracker::default()),
+    ];
+    for tracker in &mut trackers {
+        assert!(tracker.connect().is_err(), "{}", tracker.name());
+        assert!(tracker.subscribe().is_err(), "{}", tracker.name());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod response_box;
+pub mod scanner;
+pub mod voice;
+#[cfg(feature = "serial")]
+pub use response_box::ResponseBox;
+pub use response_box::{ButtonMapping, ButtonPress, ResponseBoxDecoder, ResponseBoxKind};
+pub use scanner::ScannerTrigger;
+#[cfg(feature = "voice")]
+pub use voice::VoiceKey;
+pub use voice::{OnsetDetector, VoiceKeyConfig};
@@ -0,0 +1,247 @@
+use cogex_core::ResponseKey;
+use std::collections::BTreeMap;
+
+/// Sample period of the PST Serial Response Box in continuous reporting mode
+pub const PST_SAMPLE_MS: f64 = 1.25;
+
+/// Serial response box families
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseBoxKind {
+    /// Cedrus RB-series in XID mode; presses carry the box's RT timer
+    Cedrus,
+    /// PST Serial Response Box; it has no clock, so presses are timed by
+    /// counting its fixed-rate samples
+    Pst,
+}
+
+impl ResponseBoxKind {
+    pub fn baud_rate(&self) -> u32 {
+        match self {
+            ResponseBoxKind::Cedrus => 115_200,
+            ResponseBoxKind::Pst => 19_200,
+        }
+    }
+
+    /// Puts the box into reporting mode with its time base at zero
+    pub fn start_command(&self) -> &'static [u8] {
+        match self {
+            // Select XID mode, then reset the RT timer
+            ResponseBoxKind::Cedrus => b"c10e5",
+            // Start continuous reporting
+            ResponseBoxKind::Pst => &[0xA0],
+        }
+    }
+
+    pub fn stop_command(&self) -> &'static [u8] {
+        match self {
+            ResponseBoxKind::Cedrus => &[],
+            ResponseBoxKind::Pst => &[0x20],
+        }
+    }
+}
+
+/// A button going down, timed on the box's own clock
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonPress {
+    /// Button number as the box reports it
+    pub button: u8,
+    /// Since the start command
+    pub device_ms: f64,
+}
+
+/// Maps response box buttons to response keys
+#[derive(Debug, Clone)]
+pub struct ButtonMapping {
+    buttons: BTreeMap<u8, ResponseKey>,
+}
+
+impl ButtonMapping {
+    pub fn new() -> Self {
+        Self {
+            buttons: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_button(mut self, button: u8, key: ResponseKey) -> Self {
+        self.buttons.insert(button, key);
+        self
+    }
+
+    pub fn key(&self, button: u8) -> Option<ResponseKey> {
+        self.buttons.get(&button).copied()
+    }
+}
+
+impl Default for ButtonMapping {
+    /// Buttons 1-8 are the digit keys of the same number
+    fn default() -> Self {
+        (1..=8u8).fold(Self::new(), |mapping, button| {
+            mapping.with_button(button, ResponseKey::Char((b'0' + button) as char))
+        })
+    }
+}
+
+/// Turns the byte stream from a response box into button presses.
+///
+/// Cedrus boxes send 6-byte `k` packets: a key byte (port in bits 0-3,
+/// pressed in bit 4, key number in bits 5-7) and the RT timer in ms as a
+/// little-endian `u32`. The PST box sends one byte per sample with buttons
+/// 1-5 in bits 0-4, so a press is a bit going high.
+#[derive(Debug, Clone)]
+pub struct ResponseBoxDecoder {
+    kind: ResponseBoxKind,
+    packet: Vec<u8>,
+    samples: u64,
+    held: u8,
+}
+
+impl ResponseBoxDecoder {
+    const XID_PACKET_LEN: usize = 6;
+
+    pub fn new(kind: ResponseBoxKind) -> Self {
+        Self {
+            kind,
+            packet: Vec::with_capacity(Self::XID_PACKET_LEN),
+            samples: 0,
+            held: 0,
+        }
+    }
+
+    /// Presses completed by `byte`
+    pub fn feed(&mut self, byte: u8) -> Vec<ButtonPress> {
+        match self.kind {
+            ResponseBoxKind::Cedrus => self.feed_xid(byte).into_iter().collect(),
+            ResponseBoxKind::Pst => self.feed_pst(byte),
+        }
+    }
+
+    fn feed_xid(&mut self, byte: u8) -> Option<ButtonPress> {
+        // Resynchronise on the packet marker after noise or a partial packet
+        if self.packet.is_empty() && byte != b'k' {
+            return None;
+        }
+        self.packet.push(byte);
+        if self.packet.len() < Self::XID_PACKET_LEN {
+            return None;
+        }
+        let info = self.packet[1];
+        let rt = u32::from_le_bytes([
+            self.packet[2],
+            self.packet[3],
+            self.packet[4],
+            self.packet[5],
+        ]);
+        self.packet.clear();
+        (info & 0x10 != 0).then_some(ButtonPress {
+            button: info >> 5,
+            device_ms: rt as f64,
+        })
+    }
+
+    fn feed_pst(&mut self, byte: u8) -> Vec<ButtonPress> {
+        let state = byte & 0x1F;
+        let pressed = state & !self.held;
+        self.held = state;
+        let device_ms = self.samples as f64 * PST_SAMPLE_MS;
+        self.samples += 1;
+        (0..5u8)
+            .filter(|bit| pressed & (1 << bit) != 0)
+            .map(|bit| ButtonPress {
+                button: bit + 1,
+                device_ms,
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "serial")]
+pub use serial::ResponseBox;
+
+#[cfg(feature = "serial")]
+mod serial {
+    use super::{ButtonMapping, ResponseBoxDecoder, ResponseBoxKind};
+    use anyhow::Result;
+    use cogex_core::{RawEvent, RawInput};
+    use cogex_timing::Timer;
+    use crossbeam_channel::Sender;
+    use std::io::{ErrorKind, Read, Write};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+    use tracing::{info, warn};
+
+    /// Response box on a serial port, read on its own thread.
+    ///
+    /// Presses are sent as key input stamped from the box's clock, offset by
+    /// the timer reading when the start command went out, so the RT does
+    /// not depend on when the host gets around to reading the port.
+    pub struct ResponseBox {
+        stop: Arc<AtomicBool>,
+        worker: Option<JoinHandle<()>>,
+    }
+
+    impl ResponseBox {
+        pub fn open(
+            path: &str,
+            kind: ResponseBoxKind,
+            mapping: ButtonMapping,
+            timer: &impl Timer<Timestamp = u64>,
+            sender: Sender<RawInput>,
+        ) -> Result<Self> {
+            let mut port = serialport::new(path, kind.baud_rate())
+                .timeout(Duration::from_millis(10))
+                .open()?;
+            port.write_all(kind.start_command())?;
+            port.flush()?;
+            let start_ns = timer.now();
+            info!("Response box ({:?}) on {}", kind, path);
+
+            let stop = Arc::new(AtomicBool::new(false));
+            let stopped = stop.clone();
+            let path = path.to_string();
+            let worker = std::thread::spawn(move || {
+                let mut decoder = ResponseBoxDecoder::new(kind);
+                let mut buf = [0u8; 64];
+                while !stopped.load(Ordering::Relaxed) {
+                    let n = match port.read(&mut buf) {
+                        Ok(n) => n,
+                        Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+                        Err(e) => {
+                            warn!("Response box on {} failed: {}", path, e);
+                            return;
+                        }
+                    };
+                    for press in buf[..n].iter().flat_map(|&b| decoder.feed(b)) {
+                        let Some(key) = mapping.key(press.button) else {
+                            continue;
+                        };
+                        let pressed_ns = start_ns + (press.device_ms * 1e6) as u64;
+                        if sender
+                            .send(RawInput::new(RawEvent::Key(key), pressed_ns))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+                if let Err(e) = port.write_all(kind.stop_command()) {
+                    warn!("Failed to stop response box on {}: {}", path, e);
+                }
+            });
+            Ok(Self {
+                stop,
+                worker: Some(worker),
+            })
+        }
+    }
+
+    impl Drop for ResponseBox {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+            if let Some(worker) = self.worker.take() {
+                let _ = worker.join();
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use cogex_core::{RawEvent, RawInput};
+use cogex_timing::Timer;
+use crossbeam_channel::Sender;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// MRI scanner trigger input, read on its own thread.
+///
+/// Each TR pulse is sent as a `ScannerPulse` input stamped when the thread
+/// read it. Serial trigger interfaces send a byte per pulse (usually the
+/// character `5`); on a parallel port the pulse drives the ACK line high,
+/// which is polled every 100 µs. Scanners that type `5` on a keyboard need
+/// none of this: `scanner.pulse_key` in the config catches them.
+pub struct ScannerTrigger {
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ScannerTrigger {
+    /// Counts every byte arriving on the serial port `path` as a pulse
+    #[cfg(feature = "serial")]
+    pub fn serial(
+        path: &str,
+        baud_rate: u32,
+        timer: &(impl Timer<Timestamp = u64> + 'static),
+        sender: Sender<RawInput>,
+    ) -> Result<Self> {
+        use std::io::{ErrorKind, Read};
+        use std::time::Duration;
+
+        let mut port = serialport::new(path, baud_rate)
+            .timeout(Duration::from_millis(10))
+            .open()?;
+        tracing::info!("Scanner trigger on {}", path);
+        let timer = timer.clone();
+        let path = path.to_string();
+        Ok(Self::spawn(move |stopped| {
+            let mut buf = [0u8; 64];
+            while !stopped.load(Ordering::Relaxed) {
+                let n = match port.read(&mut buf) {
+                    Ok(n) => n,
+                    Err(e) if e.kind() == ErrorKind::TimedOut => continue,
+                    Err(e) => {
+                        tracing::warn!("Scanner trigger on {} failed: {}", path, e);
+                        return;
+                    }
+                };
+                let read_ns = timer.now();
+                for _ in 0..n {
+                    if sender
+                        .send(RawInput::new(RawEvent::ScannerPulse, read_ns))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Polls the ACK line of the parallel port `path` (ppdev) for rising
+    /// edges
+    #[cfg(target_os = "linux")]
+    pub fn parallel(
+        path: &str,
+        timer: &(impl Timer<Timestamp = u64> + 'static),
+        sender: Sender<RawInput>,
+    ) -> Result<Self> {
+        use anyhow::anyhow;
+        use std::os::fd::AsRawFd;
+        use std::time::Duration;
+
+        // _IO('p', 0x8b), _IO('p', 0x8c), _IOR('p', 0x81, unsigned char)
+        const PPCLAIM: libc::c_ulong = 0x708b;
+        const PPRELEASE: libc::c_ulong = 0x708c;
+        const PPRSTATUS: libc::c_ulong = 0x8001_7081;
+        const ACK: u8 = 0x40;
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+        if unsafe { libc::ioctl(file.as_raw_fd(), PPCLAIM as _) } < 0 {
+            return Err(anyhow!(
+                "Failed to claim {}: {}",
+                path,
+                std::io::Error::last_os_error()
+            ));
+        }
+        tracing::info!("Scanner trigger on {}", path);
+        let timer = timer.clone();
+        let path = path.to_string();
+        Ok(Self::spawn(move |stopped| {
+            let mut high = false;
+            while !stopped.load(Ordering::Relaxed) {
+                let mut status: libc::c_uchar = 0;
+                if unsafe { libc::ioctl(file.as_raw_fd(), PPRSTATUS as _, &mut status) } < 0 {
+                    tracing::warn!(
+                        "Scanner trigger on {} failed: {}",
+                        path,
+                        std::io::Error::last_os_error()
+                    );
+                    break;
+                }
+                let now_high = status & ACK != 0;
+                if now_high
+                    && !high
+                    && sender
+                        .send(RawInput::new(RawEvent::ScannerPulse, timer.now()))
+                        .is_err()
+                {
+                    break;
+                }
+                high = now_high;
+                std::thread::sleep(Duration::from_micros(100));
+            }
+            unsafe {
+                libc::ioctl(file.as_raw_fd(), PPRELEASE as _);
+            }
+        }))
+    }
+
+    fn spawn(read: impl FnOnce(Arc<AtomicBool>) + Send + 'static) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        Self {
+            stop,
+            worker: Some(std::thread::spawn(move || read(stopped))),
+        }
+    }
+}
+
+impl Drop for ScannerTrigger {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,195 @@
+/// Voice key detection parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoiceKeyConfig {
+    /// RMS level, in full-scale units, that counts as speech
+    pub threshold_rms: f32,
+    /// Length of the RMS analysis window
+    pub window_ms: f64,
+    /// How long the level must stay above threshold for an onset, so clicks
+    /// and breaths are not taken for speech
+    pub hold_ms: f64,
+    /// Quiet needed after an onset before the next one can fire
+    pub rearm_ms: f64,
+}
+
+impl Default for VoiceKeyConfig {
+    fn default() -> Self {
+        Self {
+            threshold_rms: 0.05,
+            window_ms: 2.0,
+            hold_ms: 20.0,
+            rearm_ms: 200.0,
+        }
+    }
+}
+
+/// Finds speech onsets in a mono sample stream.
+///
+/// The stream is cut into fixed windows; an onset is reported at the start
+/// of the first window of a run above threshold lasting `hold_ms`, so the
+/// hold time does not add to the measured latency.
+#[derive(Debug, Clone)]
+pub struct OnsetDetector {
+    threshold_sq: f32,
+    window_len: usize,
+    hold_windows: usize,
+    rearm_windows: usize,
+    /// Samples consumed so far
+    position: u64,
+    sum_sq: f32,
+    filled: usize,
+    /// Start of the current run above threshold, and its length in windows
+    run: Option<(u64, usize)>,
+    quiet_windows: usize,
+    armed: bool,
+}
+
+impl OnsetDetector {
+    pub fn new(config: VoiceKeyConfig, sample_rate: u32) -> Self {
+        let windows = |ms: f64| (ms / config.window_ms).ceil().max(1.0) as usize;
+        Self {
+            threshold_sq: config.threshold_rms * config.threshold_rms,
+            window_len: ((config.window_ms * sample_rate as f64 / 1000.0) as usize).max(1),
+            hold_windows: windows(config.hold_ms),
+            rearm_windows: windows(config.rearm_ms),
+            position: 0,
+            sum_sq: 0.0,
+            filled: 0,
+            run: None,
+            quiet_windows: 0,
+            armed: true,
+        }
+    }
+
+    /// Samples consumed so far; the index the next sample will have
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Sample indices of onsets confirmed by `samples`
+    pub fn feed(&mut self, samples: &[f32]) -> Vec<u64> {
+        let mut onsets = Vec::new();
+        for &sample in samples {
+            self.sum_sq += sample * sample;
+            self.filled += 1;
+            self.position += 1;
+            if self.filled == self.window_len {
+                let loud = self.sum_sq / self.window_len as f32 >= self.threshold_sq;
+                let start = self.position - self.window_len as u64;
+                self.sum_sq = 0.0;
+                self.filled = 0;
+                if let Some(onset) = self.window(loud, start) {
+                    onsets.push(onset);
+                }
+            }
+        }
+        onsets
+    }
+
+    fn window(&mut self, loud: bool, start: u64) -> Option<u64> {
+        if !loud {
+            self.run = None;
+            self.quiet_windows += 1;
+            if self.quiet_windows >= self.rearm_windows {
+                self.armed = true;
+            }
+            return None;
+        }
+        self.quiet_windows = 0;
+        let (run_start, windows) = self.run.get_or_insert((start, 0));
+        *windows += 1;
+        if self.armed && *windows >= self.hold_windows {
+            self.armed = false;
+            return Some(*run_start);
+        }
+        None
+    }
+}
+
+#[cfg(feature = "voice")]
+pub use device::VoiceKey;
+
+#[cfg(feature = "voice")]
+mod device {
+    use super::{OnsetDetector, VoiceKeyConfig};
+    use anyhow::{anyhow, bail, Result};
+    use cogex_core::{RawEvent, RawInput};
+    use cogex_timing::Timer;
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use crossbeam_channel::Sender;
+    use tracing::{info, warn};
+
+    /// Voice key on the default input device.
+    ///
+    /// Onsets are dated from the capture time cpal reports for each buffer
+    /// plus the onset's offset into it, so callback scheduling does not add
+    /// to the vocal RT.
+    pub struct VoiceKey {
+        _stream: cpal::Stream,
+    }
+
+    impl VoiceKey {
+        pub fn open(
+            config: VoiceKeyConfig,
+            timer: &(impl Timer<Timestamp = u64> + 'static),
+            sender: Sender<RawInput>,
+        ) -> Result<Self> {
+            let device = cpal::default_host()
+                .default_input_device()
+                .ok_or_else(|| anyhow!("No audio input device"))?;
+            let supported = device.default_input_config()?;
+            let channels = supported.channels() as usize;
+            let rate = supported.sample_rate().0;
+            let mut detector = OnsetDetector::new(config, rate);
+            let timer = timer.clone();
+            let mut mono = Vec::new();
+            let mut on_data = move |data: &[f32], info: &cpal::InputCallbackInfo| {
+                let stamp = info.timestamp();
+                let latency = stamp.callback.duration_since(&stamp.capture);
+                let capture_ns = timer
+                    .now()
+                    .saturating_sub(latency.unwrap_or_default().as_nanos() as u64);
+                // First channel only; a voice key needs one microphone
+                mono.clear();
+                mono.extend(data.iter().step_by(channels));
+                let buffer_start = detector.position();
+                for onset in detector.feed(&mono) {
+                    let offset_ns =
+                        (onset as i64 - buffer_start as i64) * 1_000_000_000 / rate as i64;
+                    let onset_ns = (capture_ns as i64 + offset_ns).max(0) as u64;
+                    let _ = sender.send(RawInput::new(RawEvent::VoiceOnset, onset_ns));
+                }
+            };
+            let on_error = |e: cpal::StreamError| warn!("Voice key stream error: {}", e);
+            let stream = match supported.sample_format() {
+                cpal::SampleFormat::F32 => {
+                    device.build_input_stream(&supported.config(), on_data, on_error, None)?
+                }
+                cpal::SampleFormat::I16 => {
+                    let mut converted = Vec::new();
+                    device.build_input_stream(
+                        &supported.config(),
+                        move |data: &[i16], info: &cpal::InputCallbackInfo| {
+                            converted.clear();
+                            converted.extend(data.iter().map(|&s| s as f32 / i16::MAX as f32));
+                            on_data(&converted, info);
+                        },
+                        on_error,
+                        None,
+                    )?
+                }
+                format => bail!("Unsupported input sample format {:?}", format),
+            };
+            stream.play()?;
+            info!(
+                "Voice key on {} ({} Hz, threshold {:.3} RMS)",
+                device
+                    .name()
+                    .unwrap_or_else(|_| "unnamed device".to_string()),
+                rate,
+                config.threshold_rms
+            );
+            Ok(Self { _stream: stream })
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use cogex_core::ResponseKey;
+use cogex_input::{ButtonMapping, ButtonPress, ResponseBoxDecoder, ResponseBoxKind};
+
+fn feed(decoder: &mut ResponseBoxDecoder, bytes: &[u8]) -> Vec<ButtonPress> {
+    bytes.iter().flat_map(|&byte| decoder.feed(byte)).collect()
+}
+
+/// XID key packet for `key` on port 0 at `rt_ms` on the box's timer
+fn xid(key: u8, pressed: bool, rt_ms: u32) -> Vec<u8> {
+    let info = (key << 5) | if pressed { 0x10 } else { 0 };
+    let mut packet = vec![b'k', info];
+    packet.extend(rt_ms.to_le_bytes());
+    packet
+}
+
+#[test]
+fn cedrus_packets_report_presses_with_the_box_timer() {
+    let mut decoder = ResponseBoxDecoder::new(ResponseBoxKind::Cedrus);
+    let mut bytes = xid(3, true, 412);
+    bytes.extend(xid(3, false, 530));
+    bytes.extend(xid(6, true, 70_000));
+    assert_eq!(
+        feed(&mut decoder, &bytes),
+        [
+            ButtonPress {
+                button: 3,
+                device_ms: 412.0,
+            },
+            ButtonPress {
+                button: 6,
+                device_ms: 70_000.0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn cedrus_decoding_resynchronises_on_the_packet_marker() {
+    let mut decoder = ResponseBoxDecoder::new(ResponseBoxKind::Cedrus);
+    let mut bytes = vec![0x00, 0xFF, b'x'];
+    bytes.extend(xid(2, true, 25));
+    let presses = feed(&mut decoder, &bytes);
+    assert_eq!(presses.len(), 1);
+    assert_eq!(presses[0].button, 2);
+
+    // A packet arriving in pieces completes on its last byte
+    let packet = xid(1, true, 9);
+    assert!(feed(&mut decoder, &packet[..4]).is_empty());
+    assert_eq!(feed(&mut decoder, &packet[4..]).len(), 1);
+}
+
+#[test]
+fn pst_presses_are_timed_by_counting_samples() {
+    let mut decoder = ResponseBoxDecoder::new(ResponseBoxKind::Pst);
+    // Idle, button 2 down and held, then buttons 1 and 5 together
+    let presses = feed(&mut decoder, &[0x00, 0x02, 0x02, 0x02, 0x13]);
+    assert_eq!(
+        presses,
+        [
+            ButtonPress {
+                button: 2,
+                device_ms: 1.25,
+            },
+            ButtonPress {
+                button: 1,
+                device_ms: 5.0,
+            },
+            ButtonPress {
+                button: 5,
+                device_ms: 5.0,
+            },
+        ]
+    );
+    // Releasing and pressing again counts as a new press
+    assert!(feed(&mut decoder, &[0x00]).is_empty());
+    assert_eq!(feed(&mut decoder, &[0x01])[0].device_ms, 7.5);
+}
+
+#[test]
+fn buttons_map_to_digit_keys_unless_told_otherwise() {
+    let mapping = ButtonMapping::default();
+    assert_eq!(mapping.key(1), Some(ResponseKey::Char('1')));
+    assert_eq!(mapping.key(8), Some(ResponseKey::Char('8')));
+    assert_eq!(mapping.key(9), None);
+
+    let mapping = ButtonMapping::new().with_button(1, ResponseKey::Space);
+    assert_eq!(mapping.key(1), Some(ResponseKey::Space));
+    assert_eq!(mapping.key(2), None);
+}
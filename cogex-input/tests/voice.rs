@@ -0,0 +1,52 @@
+use cogex_input::{OnsetDetector, VoiceKeyConfig};
+
+/// At 1 kHz the default config has 2-sample windows, a 10-window hold and
+/// a 100-window rearm
+const SAMPLE_RATE: u32 = 1000;
+
+fn detector() -> OnsetDetector {
+    OnsetDetector::new(VoiceKeyConfig::default(), SAMPLE_RATE)
+}
+
+fn quiet(ms: usize) -> Vec<f32> {
+    vec![0.001; ms]
+}
+
+fn loud(ms: usize) -> Vec<f32> {
+    (0..ms)
+        .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+        .collect()
+}
+
+#[test]
+fn onsets_are_dated_from_the_start_of_the_held_run() {
+    let mut detector = detector();
+    assert!(detector.feed(&quiet(100)).is_empty());
+    // Confirmed only once the level has held for 20 ms
+    assert!(detector.feed(&loud(18)).is_empty());
+    assert_eq!(detector.feed(&loud(2)), [100]);
+    assert_eq!(detector.position(), 120);
+}
+
+#[test]
+fn bursts_shorter_than_the_hold_are_not_speech() {
+    let mut detector = detector();
+    let mut samples = quiet(50);
+    samples.extend(loud(10));
+    samples.extend(quiet(50));
+    samples.extend(loud(30));
+    assert_eq!(detector.feed(&samples), [110]);
+}
+
+#[test]
+fn a_new_onset_needs_quiet_for_the_rearm_time() {
+    let mut detector = detector();
+    let mut samples = loud(30);
+    samples.extend(quiet(100));
+    samples.extend(loud(30));
+    assert_eq!(detector.feed(&samples), [0]);
+
+    let mut samples = quiet(200);
+    samples.extend(loud(30));
+    assert_eq!(detector.feed(&samples), [360]);
+}
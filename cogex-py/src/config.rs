@@ -0,0 +1,74 @@
+use crate::{from_py, to_py};
+use cogex_core::StandardPhase;
+use cogex_experiment::ExperimentConfig;
+use pyo3::exceptions::{PyAttributeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::path::PathBuf;
+
+/// `ExperimentConfig` with its fields as keyword arguments, named and
+/// nested as in the TOML config file
+#[pyclass(name = "ExperimentConfig", module = "cogex", frozen)]
+#[derive(Clone)]
+pub struct PyExperimentConfig {
+    pub inner: ExperimentConfig<StandardPhase>,
+}
+
+impl PyExperimentConfig {
+    fn validated(inner: ExperimentConfig<StandardPhase>) -> PyResult<Self> {
+        inner
+            .validate()
+            .map_err(|e| PyValueError::new_err(format!("Invalid config: {:#}", e)))?;
+        Ok(Self { inner })
+    }
+}
+
+#[pymethods]
+impl PyExperimentConfig {
+    /// Defaults with `fields` replaced; unknown fields are an error
+    #[new]
+    #[pyo3(signature = (**fields))]
+    fn new(fields: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        match fields {
+            Some(fields) => Self::validated(from_py(fields.as_any())?),
+            None => Ok(Self {
+                inner: ExperimentConfig::default(),
+            }),
+        }
+    }
+
+    /// Config from a TOML file
+    #[staticmethod]
+    fn load(path: PathBuf) -> PyResult<Self> {
+        Ok(Self {
+            inner: ExperimentConfig::load(&path)?,
+        })
+    }
+
+    /// Copy with `fields` replaced
+    #[pyo3(signature = (**fields))]
+    fn replace(&self, py: Python<'_>, fields: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let merged = self.to_dict(py)?;
+        if let Some(fields) = fields {
+            merged.update(fields.as_mapping())?;
+        }
+        Self::validated(from_py(merged.as_any())?)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        Ok(to_py(py, &self.inner)?.downcast_into::<PyDict>()?)
+    }
+
+    fn __getattr__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.to_dict(py)?
+            .get_item(name)?
+            .ok_or_else(|| PyAttributeError::new_err(format!("No config field {}", name)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ExperimentConfig(task={:?}, practice_trials={}, experiment_trials={})",
+            self.inner.task, self.inner.practice_trials, self.inner.experiment_trials
+        )
+    }
+}
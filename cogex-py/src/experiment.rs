@@ -0,0 +1,145 @@
+use crate::config::PyExperimentConfig;
+use crate::{paradigm, to_py};
+use cogex_core::{ResponseKey, StandardPhase, StimulusType};
+use cogex_experiment::{
+    ExperimentEvent, ExperimentStateMachine, OutputManager, SessionInfo, DEFAULT_OUTPUT_ROOT,
+};
+use cogex_timing::HighPrecisionTimer;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::PathBuf;
+
+type Machine = ExperimentStateMachine<StandardPhase, StimulusType, HighPrecisionTimer, StdRng>;
+
+/// The experiment state machine on the high-precision clock, for front ends
+/// written in Python. Call `update()` every frame; it returns the names of
+/// the events it handled.
+#[pyclass(name = "Experiment", module = "cogex", unsendable)]
+pub struct PyExperiment {
+    machine: Machine,
+}
+
+#[pymethods]
+impl PyExperiment {
+    #[new]
+    #[pyo3(signature = (config, paradigm = None, seed = None, participant = None, session = 1))]
+    fn new(
+        config: &PyExperimentConfig,
+        paradigm: Option<&Bound<'_, PyAny>>,
+        seed: Option<u64>,
+        participant: Option<String>,
+        session: u32,
+    ) -> PyResult<Self> {
+        let (registry, name) = paradigm::registry(paradigm)?;
+        let rng = seed.map_or_else(StdRng::from_os_rng, StdRng::seed_from_u64);
+        let mut machine = Machine::new(config.inner.clone(), HighPrecisionTimer::new(), rng)
+            .with_named_paradigm(&registry, &name)?;
+        machine.session = participant.map(|id| SessionInfo::new(id, None, session));
+        Ok(Self { machine })
+    }
+
+    /// Phase name, e.g. `Practice`
+    #[getter]
+    fn phase(&self) -> String {
+        format!("{:?}", self.machine.current_phase())
+    }
+
+    /// State of the running trial, e.g. `Stimulus`
+    #[getter]
+    fn trial_state(&self) -> Option<String> {
+        self.machine
+            .current_trial_state()
+            .map(|state| format!("{:?}", state))
+    }
+
+    /// Stimulus of the running trial and its position, the stimulus as a
+    /// dict like the exported results'
+    #[allow(clippy::type_complexity)]
+    fn stimulus<'py>(&self, py: Python<'py>) -> PyResult<Option<(Bound<'py, PyAny>, (f32, f32))>> {
+        self.machine
+            .current_stimulus()
+            .map(|(stimulus, position)| Ok((to_py(py, stimulus)?, position)))
+            .transpose()
+    }
+
+    fn update(&mut self) -> Vec<String> {
+        let events = self.machine.update();
+        events
+            .into_iter()
+            .map(|event| {
+                let name = format!("{:?}", event);
+                self.machine.handle_event(event);
+                name
+            })
+            .collect()
+    }
+
+    /// Space on the welcome, instruction and break screens
+    fn space(&mut self) -> bool {
+        self.machine.handle_event(ExperimentEvent::SpacePressed)
+    }
+
+    /// Ends the calibration phase with the frames recorded so far
+    fn complete_calibration(&mut self) -> bool {
+        self.machine.apply_calibration();
+        self.machine
+            .handle_event(ExperimentEvent::CalibrationComplete)
+    }
+
+    /// A key by name, e.g. `f` or `space`; false when it was not taken
+    fn respond(&mut self, key: &str) -> PyResult<bool> {
+        let key = ResponseKey::from_name(key)
+            .ok_or_else(|| PyValueError::new_err(format!("Unknown key {:?}", key)))?;
+        Ok(self
+            .machine
+            .handle_event(ExperimentEvent::ResponseReceived {
+                key,
+                timestamp_ns: None,
+            }))
+    }
+
+    fn abort(&mut self, reason: String) -> bool {
+        self.machine.handle_event(ExperimentEvent::Abort { reason })
+    }
+
+    /// Results so far, as written to the results file
+    fn results<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(
+            py,
+            &cogex_experiment::results_to_json(
+                self.machine.session.as_ref(),
+                self.machine.results(),
+            ),
+        )
+    }
+
+    fn debrief_summary(&self) -> Vec<String> {
+        self.machine.debrief_summary()
+    }
+
+    /// Writes the session metadata, results and BIDS events under `out`,
+    /// returning their paths
+    #[pyo3(signature = (out = PathBuf::from(DEFAULT_OUTPUT_ROOT)))]
+    fn save(&self, out: PathBuf) -> PyResult<Vec<PathBuf>> {
+        let session = self.machine.session.as_ref();
+        let output = OutputManager::new(&out, &self.machine.config.task, session)?;
+        let metadata = self
+            .machine
+            .metadata()
+            .with_crate("cogex-py", crate::VERSION);
+        Ok(vec![
+            output.write_metadata(&metadata)?,
+            cogex_experiment::write_results(&output, session, self.machine.results())?,
+            cogex_experiment::write_bids_events(
+                &output,
+                session,
+                &self.machine.config.task,
+                self.machine.results(),
+                self.machine.logged_markers(),
+                self.machine.scanner_zero_ns(),
+            )?,
+        ])
+    }
+}
@@ -0,0 +1,118 @@
+//! Python bindings: build with `maturin develop` in this directory, then
+//! `import cogex`.
+
+mod config;
+mod experiment;
+mod paradigm;
+
+pub use config::PyExperimentConfig;
+pub use experiment::PyExperiment;
+pub use paradigm::PyParadigm;
+
+use cogex_core::StandardPhase;
+use cogex_experiment::{ParadigmRegistry, SessionInfo, DEFAULT_OUTPUT_ROOT};
+use cogex_sim::SimulateOptions;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Crate version, reported as `cogex.__version__`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Python value of anything serde can write, through `json`
+fn to_py<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let json = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    py.import("json")?.call_method1("loads", (json,))
+}
+
+/// Rust value from plain Python dicts, lists, strings and numbers
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Runs an experiment in a window, as `cogex run` does. `paradigm` is a
+/// built-in name or an object with `keys()` and `trials(n, rng)`; without a
+/// `participant` the participant form asks for one.
+#[pyfunction]
+#[pyo3(signature = (config, paradigm = None, participant = None, session = 1))]
+fn run(
+    config: &PyExperimentConfig,
+    paradigm: Option<&Bound<'_, PyAny>>,
+    participant: Option<String>,
+    session: u32,
+) -> PyResult<()> {
+    let (registry, name) = paradigm::registry(paradigm)?;
+    let session = participant.map(|id| SessionInfo::new(id, None, session));
+    cogex_app::cli::run(config.inner.clone(), &registry, &name, session)?;
+    Ok(())
+}
+
+/// Runs an experiment headlessly with a simulated participant, as
+/// `cogex simulate` does, writing its output under `out`
+#[pyfunction]
+#[pyo3(signature = (
+    config,
+    paradigm = None,
+    participant = "sim".to_string(),
+    seed = 0,
+    out = PathBuf::from(DEFAULT_OUTPUT_ROOT),
+    accuracy = 0.9,
+    rt = (450.0, 80.0),
+    refresh_hz = 60.0,
+))]
+#[allow(clippy::too_many_arguments)]
+fn simulate(
+    config: &PyExperimentConfig,
+    paradigm: Option<&Bound<'_, PyAny>>,
+    participant: String,
+    seed: u64,
+    out: PathBuf,
+    accuracy: f64,
+    rt: (f64, f64),
+    refresh_hz: f64,
+) -> PyResult<()> {
+    let (registry, experiment) = paradigm::registry(paradigm)?;
+    cogex_sim::simulate(
+        SimulateOptions {
+            experiment,
+            config: config.inner.clone(),
+            participant,
+            seed,
+            out,
+            script: None,
+            accuracy,
+            rt,
+            refresh_hz,
+        },
+        &registry,
+    )?;
+    Ok(())
+}
+
+/// Names of the built-in paradigms
+#[pyfunction]
+fn paradigms() -> Vec<String> {
+    ParadigmRegistry::<StandardPhase>::builtin()
+        .names()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+#[pymodule]
+pub fn cogex(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", VERSION)?;
+    m.add_class::<PyExperimentConfig>()?;
+    m.add_class::<PyExperiment>()?;
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(paradigms, m)?)?;
+    Ok(())
+}
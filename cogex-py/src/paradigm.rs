@@ -0,0 +1,140 @@
+use crate::{from_py, to_py};
+use anyhow::{bail, Result};
+use cogex_core::{ResponseKey, ResponseMapping, StandardPhase, StimulusType, TrialResult};
+use cogex_experiment::{
+    condition_summaries, results_to_json, Paradigm, ParadigmRegistry, SequencedTrial,
+    TrialSequence, TrialSpec,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::RngCore;
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Registry name a Python paradigm object runs under
+const PYTHON: &str = "python";
+
+/// Paradigm defined by a Python object, with the methods a Rhai script
+/// defines:
+///
+/// - `keys()`: names of the keys participants answer with
+/// - `trials(n, rng)`: `n` trial dicts with `condition`, `stimulus`, `key`
+///   (`None` withholds the response) and optionally `response_window_ms`,
+///   `position` and `subpixel`; `rng` is a `random.Random` seeded from the
+///   session
+/// - `summary(results)`, optional: debrief lines from the exported trial
+///   dicts
+/// - `messages()`, optional: a dict of message id to text replacing the
+///   locale's
+pub struct PyParadigm {
+    object: Py<PyAny>,
+    keys: Vec<ResponseKey>,
+    messages: Vec<(String, String)>,
+}
+
+impl PyParadigm {
+    pub fn new(object: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let names: Vec<String> = object.call_method0("keys")?.extract()?;
+        let keys = names
+            .iter()
+            .map(|name| {
+                ResponseKey::from_name(name)
+                    .ok_or_else(|| PyValueError::new_err(format!("Unknown key {:?}", name)))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        if keys.is_empty() {
+            return Err(PyValueError::new_err("keys() returned no keys"));
+        }
+        let messages = if object.hasattr("messages")? {
+            let messages: BTreeMap<String, String> = object.call_method0("messages")?.extract()?;
+            messages.into_iter().collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            object: object.clone().unbind(),
+            keys,
+            messages,
+        })
+    }
+}
+
+impl Clone for PyParadigm {
+    fn clone(&self) -> Self {
+        Python::with_gil(|py| Self {
+            object: self.object.clone_ref(py),
+            keys: self.keys.clone(),
+            messages: self.messages.clone(),
+        })
+    }
+}
+
+impl Paradigm for PyParadigm {
+    fn sequence(&self, trials: usize, rng: &mut dyn RngCore) -> Result<TrialSequence> {
+        Python::with_gil(|py| {
+            let random = py
+                .import("random")?
+                .call_method1("Random", (rng.next_u64(),))?;
+            let values = self
+                .object
+                .bind(py)
+                .call_method1("trials", (trials, random))?;
+            let specs: Vec<TrialSpec> = from_py(&values)?;
+            if specs.len() != trials {
+                bail!("trials({}) returned {} trials", trials, specs.len());
+            }
+            let sequence = specs
+                .into_iter()
+                .map(SequencedTrial::try_from)
+                .collect::<Result<Vec<_>>>()?;
+            Ok(TrialSequence::from_trials(sequence))
+        })
+    }
+
+    /// The object's keys; each trial carries its own expected key
+    fn response_mapping(&self) -> ResponseMapping<StimulusType> {
+        ResponseMapping::new().with_rule_fn(&self.keys, |_| None)
+    }
+
+    fn summary(&self, results: &[TrialResult<StimulusType>]) -> Vec<String> {
+        Python::with_gil(|py| {
+            let object = self.object.bind(py);
+            if !object.hasattr("summary")? {
+                return Ok(condition_summaries(results)
+                    .iter()
+                    .map(|(condition, s)| s.line(condition))
+                    .collect());
+            }
+            let trials = to_py(py, &results_to_json(None, results)["trials"])?;
+            object.call_method1("summary", (trials,))?.extract()
+        })
+        .unwrap_or_else(|e: PyErr| {
+            warn!("summary() failed: {}", e);
+            Vec::new()
+        })
+    }
+
+    fn messages(&self) -> Vec<(String, String)> {
+        self.messages.clone()
+    }
+}
+
+/// Registry holding `paradigm` and the name to run it by: a built-in name
+/// as given, a Python object under `python`, nothing as `default`
+pub fn registry(
+    paradigm: Option<&Bound<'_, PyAny>>,
+) -> PyResult<(ParadigmRegistry<StandardPhase>, String)> {
+    let registry = ParadigmRegistry::builtin();
+    let Some(paradigm) = paradigm else {
+        return Ok((
+            registry,
+            ParadigmRegistry::<StandardPhase>::DEFAULT.to_string(),
+        ));
+    };
+    if let Ok(name) = paradigm.extract::<String>() {
+        return Ok((registry, name));
+    }
+    let paradigm = PyParadigm::new(paradigm)?;
+    let registry = registry.with(PYTHON, move |_| Ok(Box::new(paradigm.clone())));
+    Ok((registry, PYTHON.to_string()))
+}
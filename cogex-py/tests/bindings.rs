@@ -0,0 +1,110 @@
+use pyo3::ffi::c_str;
+use pyo3::prelude::*;
+use std::ffi::CStr;
+
+/// A two-key flanker task written the way a Python lab would
+const FLANKER: &CStr = c_str!(
+    r#"
+import cogex
+
+class Flanker:
+    def keys(self):
+        return ["f", "j"]
+
+    def trials(self, n, rng):
+        trials = []
+        for i in range(n):
+            left = i % 2 == 0
+            trials.append({
+                "condition": "congruent" if i % 4 < 2 else "incongruent",
+                "stimulus": {"Text": {"content": "<<<<<" if left else ">>>>>",
+                                      "size": 48, "color": [255, 255, 255, 255]}},
+                "key": "f" if left else "j",
+            })
+        rng.shuffle(trials)
+        return trials
+
+    def summary(self, results):
+        return [f"{len(results)} flanker trials"]
+
+    def messages(self):
+        return {"feedback-correct": "Nice!"}
+
+config = cogex.ExperimentConfig(practice_trials=2, experiment_trials=8, task="flanker")
+"#
+);
+
+/// Runs `code` after `FLANKER` with the module importable as `cogex`
+fn run_python(code: &CStr) {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let module = pyo3::wrap_pymodule!(cogex::cogex)(py);
+        py.import("sys")?
+            .getattr("modules")?
+            .set_item("cogex", module)?;
+        let globals = pyo3::types::PyDict::new(py);
+        py.run(FLANKER, Some(&globals), None)?;
+        py.run(code, Some(&globals), None)
+    })
+    .unwrap_or_else(|e| panic!("{}", e));
+}
+
+#[test]
+fn config_takes_fields_as_keywords_and_validates_them() {
+    run_python(c_str!(
+        r#"
+assert config.practice_trials == 2
+assert config.replace(practice_trials=4).practice_trials == 4
+assert config.task == "flanker"
+assert "stroop" in cogex.paradigms()
+
+for fields in [{"response_window_ms": 0}, {"colour": "red"}]:
+    try:
+        cogex.ExperimentConfig(**fields)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError(f"{fields} was accepted")
+"#
+    ));
+}
+
+#[test]
+fn python_paradigm_drives_the_state_machine() {
+    run_python(c_str!(
+        r#"
+experiment = cogex.Experiment(config, paradigm=Flanker(), seed=3, participant="P01")
+assert experiment.phase == "Welcome"
+assert experiment.space()
+assert experiment.phase == "Calibration"
+assert experiment.complete_calibration()
+assert experiment.phase == "Practice"
+assert experiment.trial_state == "Fixation"
+assert experiment.results()["trials"] == []
+
+try:
+    experiment.respond("escape")
+except ValueError:
+    pass
+else:
+    raise AssertionError("unknown key was accepted")
+"#
+    ));
+}
+
+#[test]
+fn simulate_runs_a_python_paradigm_and_writes_its_output() {
+    run_python(c_str!(
+        r#"
+import json, pathlib, tempfile
+
+with tempfile.TemporaryDirectory() as out:
+    cogex.simulate(config, paradigm=Flanker(), participant="P01", seed=1, out=out)
+    [results] = pathlib.Path(out, "flanker").glob("P01_s1_*[0-9].json")
+    trials = json.loads(results.read_text())["trials"]
+    assert len(trials) == 10
+    assert {t["condition"] for t in trials} == {"congruent", "incongruent"}
+    assert all(t["stimulus"]["Text"]["content"] in ("<<<<<", ">>>>>") for t in trials)
+"#
+    ));
+}
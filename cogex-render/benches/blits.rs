@@ -1,8 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use pprof::criterion::{Output, PProfProfiler};
 use std::time::Duration;
 
-use cogex_core::{ArrowDirection, StimulusType};
+use cogex_render::blend::{source_over, source_over_scalar};
 use cogex_render::{Renderer as _, SkiaRenderer};
 
 /// Initialize renderer and prewarm cached assets so that subsequent blits are fast and realistic.
@@ -63,6 +63,58 @@ pub fn bench_blit_cached(c: &mut Criterion) {
     group.finish();
 }
 
+/// Blends a whole 4K frame of translucent pixels row by row, with the vector
+/// path and the scalar reference, then blits text across a 4K canvas.
+pub fn bench_blend_4k(c: &mut Criterion) {
+    const WIDTH: usize = 3840;
+    const HEIGHT: usize = 2160;
+    let mut group = c.benchmark_group("blend_4k");
+    group
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(10))
+        .warm_up_time(Duration::from_secs(2));
+
+    // Premultiplied, every alpha level across each row
+    let src: Vec<u8> = (0..WIDTH * HEIGHT)
+        .flat_map(|i| {
+            let a = (i % 256) as u8;
+            [a / 2, a / 3, a / 4, a]
+        })
+        .collect();
+    let frame = vec![128u8; WIDTH * HEIGHT * 4];
+
+    for (name, blend) in [
+        ("simd", source_over as fn(&mut [u8], &[u8])),
+        ("scalar", source_over_scalar),
+    ] {
+        group.bench_function(name, |b| {
+            let mut dst = frame.clone();
+            b.iter(|| {
+                for (d, s) in dst
+                    .chunks_exact_mut(WIDTH * 4)
+                    .zip(src.chunks_exact(WIDTH * 4))
+                {
+                    blend(d, black_box(s));
+                }
+            });
+        });
+    }
+
+    group.bench_function("text_center", |b| {
+        let mut renderer = prepare_renderer(WIDTH as u32, HEIGHT as u32);
+        let id = renderer
+            .interner()
+            .intern("The quick brown fox jumps over the lazy dog");
+        let pos = (1920.0, 1080.0);
+        b.iter(|| {
+            renderer.blit_text_by_intern_id(id, black_box(pos));
+            black_box(());
+        });
+    });
+
+    group.finish();
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default()
@@ -70,7 +122,7 @@ criterion_group! {
         .confidence_level(0.95)
         .noise_threshold(0.02)
         .significance_level(0.05);
-    targets = bench_blit_cached
+    targets = bench_blit_cached, bench_blend_4k
 }
 
 criterion_main!(benches);
@@ -1,9 +1,9 @@
 use std::time::Duration;
 
 use cogex_core::{ArrowDirection, Phase, StimulusType, TrialState};
-use cogex_render::{PhaseRenderer as _, SkiaRenderer};
+use cogex_render::SkiaRenderer;
 use cogex_timing::HighPrecisionTimer;
-use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use pprof::criterion::{Output, PProfProfiler};
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -67,7 +67,7 @@ pub fn bench_frame_response(c: &mut Criterion) {
         b.iter(|| {
             let _stats = r.render_frame(
                 &p,
-                Some((&s, (740.0, 360.0))),
+                &[(&s, (740.0, 360.0))],
                 Some(&TrialState::Response),
                 Some((10, 40)),
                 &mut fb,
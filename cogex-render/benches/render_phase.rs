@@ -1,5 +1,5 @@
 use cogex_core::{ArrowDirection, Phase, StimulusType, TrialState};
-use cogex_render::{PhaseRenderer as _, Renderer as _, SkiaRenderer};
+use cogex_render::{PhaseRenderer as _, SkiaRenderer};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use pprof::criterion::{Output, PProfProfiler};
 
@@ -57,7 +57,7 @@ pub fn bench_render_phase(c: &mut Criterion) {
     };
     let _ = renderer.render_phase(
         &phase,
-        Some((&warm, (640.0, 360.0))),
+        &[(&warm, (640.0, 360.0))],
         Some(&TrialState::Stimulus),
         Some((10, 40)),
     );
@@ -65,8 +65,7 @@ pub fn bench_render_phase(c: &mut Criterion) {
     // Benchmark 1: fixation
     group.bench_function("fixation_center", |b| {
         b.iter(|| {
-            let _ =
-                renderer.render_phase(&phase, None, Some(&TrialState::Fixation), Some((10, 40)));
+            let _ = renderer.render_phase(&phase, &[], Some(&TrialState::Fixation), Some((10, 40)));
             black_box(());
         });
     });
@@ -81,7 +80,7 @@ pub fn bench_render_phase(c: &mut Criterion) {
         b.iter(|| {
             let _ = renderer.render_phase(
                 &phase,
-                Some((&rect_stim, (540.0, 360.0))),
+                &[(&rect_stim, (540.0, 360.0))],
                 Some(&TrialState::Stimulus),
                 Some((10, 40)),
             );
@@ -99,7 +98,7 @@ pub fn bench_render_phase(c: &mut Criterion) {
         b.iter(|| {
             let _ = renderer.render_phase(
                 &phase,
-                Some((&arrow_stim, (740.0, 360.0))),
+                &[(&arrow_stim, (740.0, 360.0))],
                 Some(&TrialState::Response),
                 Some((10, 40)),
             );
@@ -0,0 +1,31 @@
+welcome = WILLKOMMEN
+calibrating = KALIBRIERUNG...
+respond = antworten
+feedback = RÜCKMELDUNG
+practice-mode = ÜBUNGSMODUS
+break-prompt = Drücken Sie die LEERTASTE, um fortzufahren
+break-rest = Bitte ruhen Sie sich aus: { $seconds } s
+
+feedback-correct = RICHTIG
+feedback-wrong = FALSCH
+feedback-too-slow = ZU LANGSAM
+response-time = Reaktionszeit: { $ms } ms
+accuracy = Genauigkeit: { $percent } %
+trial-progress = Durchgang: { $current }/{ $total }
+
+instructions-next = LEERTASTE: weiter, RÜCKTASTE: zurück
+instructions-start = LEERTASTE: beginnen, RÜCKTASTE: zurück
+questionnaire-hint = Zahl drücken oder Antwort anklicken, RÜCKTASTE: zurück
+
+debrief-title = Vielen Dank für Ihre Teilnahme!
+debrief-trials = { $correct } von { $total } Durchgängen richtig
+debrief-accuracy = Genauigkeit: { $percent } %
+debrief-mean-rt = Mittlere Reaktionszeit: { $ms } ms
+debrief-median-rt = Median der Reaktionszeit: { $ms } ms
+debrief-exit = Drücken Sie ESC zum Beenden
+
+session-ended = Die Sitzung ist beendet.
+session-ended-contact = Bitte geben Sie der Versuchsleitung Bescheid.
+
+interrupted = Bitte kehren Sie zum Experiment zurück.
+interrupted-continue = Drücken Sie die LEERTASTE, um fortzufahren
@@ -0,0 +1,34 @@
+# Participant-facing strings. Placeables like { $total } are filled in at
+# run time; copy this file to add a language.
+
+welcome = WELCOME
+calibrating = CALIBRATING...
+respond = respond
+feedback = FEEDBACK
+practice-mode = PRACTICE MODE
+break-prompt = Press SPACE to continue
+break-rest = Please rest: { $seconds } s
+
+feedback-correct = CORRECT
+feedback-wrong = WRONG
+feedback-too-slow = TOO SLOW
+response-time = Response time: { $ms }ms
+accuracy = Accuracy: { $percent }%
+trial-progress = Trial: { $current }/{ $total }
+
+instructions-next = Press SPACE to continue, BACKSPACE to go back
+instructions-start = Press SPACE to start, BACKSPACE to go back
+questionnaire-hint = Press a number or click an option, BACKSPACE to go back
+
+debrief-title = Thank you for taking part!
+debrief-trials = { $correct } of { $total } trials correct
+debrief-accuracy = Accuracy: { $percent }%
+debrief-mean-rt = Mean response time: { $ms } ms
+debrief-median-rt = Median response time: { $ms } ms
+debrief-exit = Press ESC to exit
+
+session-ended = The session has ended.
+session-ended-contact = Please contact the experimenter.
+
+interrupted = Please return to the experiment.
+interrupted-continue = Press SPACE to continue
@@ -0,0 +1,31 @@
+welcome = ÜDVÖZÖLJÜK
+calibrating = KALIBRÁLÁS...
+respond = válaszoljon
+feedback = VISSZAJELZÉS
+practice-mode = GYAKORLÁS
+break-prompt = A folytatáshoz nyomja meg a SZÓKÖZT
+break-rest = Kérjük, pihenjen: { $seconds } mp
+
+feedback-correct = HELYES
+feedback-wrong = HIBÁS
+feedback-too-slow = TÚL LASSÚ
+response-time = Reakcióidő: { $ms } ms
+accuracy = Pontosság: { $percent }%
+trial-progress = Próba: { $current }/{ $total }
+
+instructions-next = SZÓKÖZ: tovább, BACKSPACE: vissza
+instructions-start = SZÓKÖZ: kezdés, BACKSPACE: vissza
+questionnaire-hint = Számbillentyű vagy kattintás: válasz, BACKSPACE: vissza
+
+debrief-title = Köszönjük a részvételt!
+debrief-trials = { $total } próbából { $correct } helyes
+debrief-accuracy = Pontosság: { $percent }%
+debrief-mean-rt = Átlagos reakcióidő: { $ms } ms
+debrief-median-rt = Reakcióidő mediánja: { $ms } ms
+debrief-exit = A kilépéshez nyomja meg az ESC billentyűt
+
+session-ended = A foglalkozás véget ért.
+session-ended-contact = Kérjük, jelezze a kísérletvezetőnek.
+
+interrupted = Kérjük, térjen vissza a kísérlethez.
+interrupted-continue = A folytatáshoz nyomja meg a SZÓKÖZT
@@ -0,0 +1,108 @@
+//! Frame damage tracked on a fixed grid of tiles.
+
+use tiny_skia::Rect;
+
+/// Side of a damage tile in pixels
+pub const TILE_SIZE: u32 = 64;
+
+/// Damage on a canvas as a grid of `TILE_SIZE` tiles. Marking a rect flags
+/// every tile it touches, so overlapping damage merges at the cost of a few
+/// flag writes, and the rects handed back never overlap: no pixel is
+/// copied twice however many text lines were drawn over each other.
+#[derive(Debug, Clone)]
+pub struct TileGrid {
+    width: u32,
+    height: u32,
+    columns: usize,
+    dirty: Vec<bool>,
+    /// Whether any tile is flagged
+    marked: bool,
+}
+
+impl TileGrid {
+    pub fn new(width: u32, height: u32) -> Self {
+        let columns = width.div_ceil(TILE_SIZE) as usize;
+        let rows = height.div_ceil(TILE_SIZE) as usize;
+        Self {
+            width,
+            height,
+            columns,
+            dirty: vec![false; columns * rows],
+            marked: false,
+        }
+    }
+
+    /// Flags the tiles `rect` touches; the part off the canvas is ignored
+    pub fn mark(&mut self, rect: &Rect) {
+        let clamp = |v: f32, max: u32| v.max(0.0).min(max as f32) as u32;
+        let (x0, y0) = (
+            clamp(rect.left().floor(), self.width),
+            clamp(rect.top().floor(), self.height),
+        );
+        let (x1, y1) = (
+            clamp(rect.right().ceil(), self.width),
+            clamp(rect.bottom().ceil(), self.height),
+        );
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let (c0, c1) = ((x0 / TILE_SIZE) as usize, x1.div_ceil(TILE_SIZE) as usize);
+        for row in (y0 / TILE_SIZE) as usize..y1.div_ceil(TILE_SIZE) as usize {
+            let start = row * self.columns;
+            self.dirty[start + c0..start + c1].fill(true);
+        }
+        self.marked = true;
+    }
+
+    /// Moves the damage into `out` as disjoint rects, clipped to the canvas,
+    /// and clears the grid. Each run of flagged tiles in a row is one rect,
+    /// extended down over the rows that repeat the same run.
+    pub fn drain_into(&mut self, out: &mut Vec<Rect>) {
+        if !self.marked {
+            return;
+        }
+        // Runs still growing downwards, as (first column, end column, first row)
+        let mut open: Vec<(usize, usize, usize)> = Vec::new();
+        let mut next = Vec::new();
+        let rows = self.dirty.len() / self.columns.max(1);
+        for row in 0..rows {
+            let flags = &self.dirty[row * self.columns..(row + 1) * self.columns];
+            let mut column = 0;
+            while column < flags.len() {
+                if !flags[column] {
+                    column += 1;
+                    continue;
+                }
+                let start = column;
+                while column < flags.len() && flags[column] {
+                    column += 1;
+                }
+                match open
+                    .iter()
+                    .position(|&(c0, c1, _)| (c0, c1) == (start, column))
+                {
+                    Some(i) => next.push(open.swap_remove(i)),
+                    None => next.push((start, column, row)),
+                }
+            }
+            for run in open.drain(..) {
+                out.extend(self.tile_rect(run, row));
+            }
+            std::mem::swap(&mut open, &mut next);
+        }
+        for run in open {
+            out.extend(self.tile_rect(run, rows));
+        }
+        self.dirty.fill(false);
+        self.marked = false;
+    }
+
+    /// The pixels of a run of tiles ending before `end_row`
+    fn tile_rect(&self, (c0, c1, r0): (usize, usize, usize), end_row: usize) -> Option<Rect> {
+        let x0 = c0 as u32 * TILE_SIZE;
+        let y0 = r0 as u32 * TILE_SIZE;
+        let x1 = (c1 as u32 * TILE_SIZE).min(self.width);
+        let y1 = (end_row as u32 * TILE_SIZE).min(self.height);
+        Rect::from_ltrb(x0 as f32, y0 as f32, x1 as f32, y1 as f32)
+    }
+}
@@ -0,0 +1,232 @@
+use ab_glyph::{point, Font, FontRef, Glyph, GlyphId, OutlinedGlyph, PxScale, ScaleFont};
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tracing::warn;
+
+/// Fonts tried after the bundled one for characters it lacks. Paths that do
+/// not exist are skipped.
+const SYSTEM_FALLBACKS: &[&str] = &[
+    // Linux
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansArabic-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansHebrew-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansDevanagari-Regular.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansThai-Regular.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/noto/NotoEmoji-Regular.ttf",
+    "/usr/share/fonts/TTF/NotoEmoji-Regular.ttf",
+    // macOS
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    // Windows
+    "C:\\Windows\\Fonts\\Nirmala.ttf",
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\seguiemj.ttf",
+    "C:\\Windows\\Fonts\\seguisym.ttf",
+];
+
+/// One font, for shaping and for rasterizing
+#[derive(Clone)]
+struct Face {
+    glyphs: FontRef<'static>,
+    shaper: rustybuzz::Face<'static>,
+    /// Bytes of a font read at runtime, which the two views above borrow.
+    /// Declared last so it is dropped after them.
+    _owned: Option<Arc<[u8]>>,
+}
+
+impl Face {
+    fn new(data: &'static [u8]) -> Result<Self> {
+        Self::parse(data, None)
+    }
+
+    fn owned(data: Arc<[u8]>) -> Result<Self> {
+        // SAFETY: the bytes sit behind the Arc, which never moves them and
+        // which every clone of the face holds for as long as the views do
+        let bytes: &'static [u8] = unsafe { &*Arc::as_ptr(&data) };
+        Self::parse(bytes, Some(data))
+    }
+
+    fn parse(data: &'static [u8], owned: Option<Arc<[u8]>>) -> Result<Self> {
+        Ok(Self {
+            glyphs: FontRef::try_from_slice_and_index(data, 0).context("Unreadable font")?,
+            shaper: rustybuzz::Face::from_slice(data, 0).context("Unreadable font")?,
+            _owned: owned,
+        })
+    }
+
+    fn covers(&self, ch: char) -> bool {
+        self.glyphs.glyph_id(ch).0 != 0
+    }
+}
+
+/// A glyph positioned by shaping, with the font it is drawn from
+#[derive(Debug, Clone)]
+pub struct ShapedGlyph {
+    pub face: usize,
+    pub glyph: Glyph,
+}
+
+/// Primary font plus fallbacks tried in order for characters it lacks.
+///
+/// Runs of text are shaped with rustybuzz, so ligatures, combining marks
+/// and complex scripts lay out correctly; right-to-left runs come out in
+/// visual order. Fallback glyphs are scaled to the primary font's em size.
+#[derive(Clone)]
+pub struct FontSet {
+    faces: Arc<Vec<Face>>,
+}
+
+impl FontSet {
+    /// Font set of the bundled DejaVu Sans
+    pub fn bundled() -> Self {
+        Self::from_static(include_bytes!("../../assets/DejaVuSans.ttf")).expect("Font load")
+    }
+
+    /// Bundled font with whichever system fallbacks exist, loaded once per
+    /// process
+    pub fn system() -> Self {
+        static SYSTEM: OnceLock<FontSet> = OnceLock::new();
+        SYSTEM
+            .get_or_init(|| {
+                SYSTEM_FALLBACKS
+                    .iter()
+                    .map(Path::new)
+                    .filter(|p| p.exists())
+                    .fold(Self::bundled(), |fonts, path| {
+                        match fonts.clone().load_fallback(path) {
+                            Ok(fonts) => fonts,
+                            Err(e) => {
+                                warn!("Skipping fallback font {}: {}", path.display(), e);
+                                fonts
+                            }
+                        }
+                    })
+            })
+            .clone()
+    }
+
+    pub fn from_static(data: &'static [u8]) -> Result<Self> {
+        Ok(Self {
+            faces: Arc::new(vec![Face::new(data)?]),
+        })
+    }
+
+    pub fn with_fallback(mut self, data: &'static [u8]) -> Result<Self> {
+        Arc::make_mut(&mut self.faces).push(Face::new(data)?);
+        Ok(self)
+    }
+
+    /// Adds a fallback font file; its data is freed with the last clone of
+    /// the set
+    pub fn load_fallback(mut self, path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("Failed to read font {}", path.display()))?;
+        Arc::make_mut(&mut self.faces).push(Face::owned(data.into())?);
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    /// Ascent and descent of the primary font at `size`, descent negative
+    pub fn v_metrics(&self, size: f32) -> (f32, f32) {
+        let sf = self.faces[0].glyphs.as_scaled(PxScale::from(size));
+        (sf.ascent(), sf.descent())
+    }
+
+    /// Shapes one line with its baseline starting at `origin`, returning the
+    /// glyphs and the advance width
+    pub fn shape(&self, text: &str, size: f32, origin: (f32, f32)) -> (Vec<ShapedGlyph>, f32) {
+        let px_per_unit = self.faces[0]
+            .glyphs
+            .as_scaled(PxScale::from(size))
+            .h_scale_factor();
+        let mut glyphs = Vec::new();
+        let mut pen_x = origin.0;
+        for (face_index, run) in self.runs(text) {
+            let face = &self.faces[face_index];
+            let scale = PxScale::from(px_per_unit * face.glyphs.height_unscaled());
+            let mut buffer = rustybuzz::UnicodeBuffer::new();
+            buffer.push_str(run);
+            buffer.guess_segment_properties();
+            let output = rustybuzz::shape(&face.shaper, &[], buffer);
+            for (info, pos) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+                glyphs.push(ShapedGlyph {
+                    face: face_index,
+                    glyph: Glyph {
+                        id: GlyphId(info.glyph_id as u16),
+                        scale,
+                        position: point(
+                            pen_x + pos.x_offset as f32 * px_per_unit,
+                            origin.1 - pos.y_offset as f32 * px_per_unit,
+                        ),
+                    },
+                });
+                pen_x += pos.x_advance as f32 * px_per_unit;
+            }
+        }
+        (glyphs, pen_x - origin.0)
+    }
+
+    pub fn outline(&self, glyph: &ShapedGlyph) -> Option<OutlinedGlyph> {
+        self.faces[glyph.face]
+            .glyphs
+            .outline_glyph(glyph.glyph.clone())
+    }
+
+    /// Splits `text` into runs drawn from one face each. A run keeps its
+    /// face while that face covers the next character, and marks and joiners
+    /// always stay with the character before them.
+    fn runs<'a>(&self, text: &'a str) -> Vec<(usize, &'a str)> {
+        let mut runs = Vec::new();
+        let mut current: Option<(usize, usize)> = None;
+        for (i, ch) in text.char_indices() {
+            let face = match current {
+                Some((face, _)) if is_cluster_continuation(ch) || self.faces[face].covers(ch) => {
+                    face
+                }
+                Some((face, _)) if ch.is_whitespace() => face,
+                _ => self
+                    .faces
+                    .iter()
+                    .position(|f| f.covers(ch))
+                    .unwrap_or_else(|| current.map_or(0, |(face, _)| face)),
+            };
+            match current {
+                Some((run_face, start)) if run_face != face => {
+                    runs.push((run_face, &text[start..i]));
+                    current = Some((face, i));
+                }
+                None => current = Some((face, i)),
+                _ => {}
+            }
+        }
+        if let Some((face, start)) = current {
+            runs.push((face, &text[start..]));
+        }
+        runs
+    }
+}
+
+/// Combining marks, joiners, variation selectors and emoji modifiers
+fn is_cluster_continuation(ch: char) -> bool {
+    matches!(ch as u32,
+        0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x20D0..=0x20FF
+        | 0xFE20..=0xFE2F
+        | 0x200C..=0x200D
+        | 0xFE00..=0xFE0F
+        | 0x1F3FB..=0x1F3FF
+        | 0xE0020..=0xE007F
+        | 0xE0100..=0xE01EF)
+}
@@ -0,0 +1,98 @@
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Per-channel 8-bit lookup table linearizing display luminance
+#[derive(Debug, Clone, PartialEq)]
+pub struct GammaLut {
+    pub r: [u8; 256],
+    pub g: [u8; 256],
+    pub b: [u8; 256],
+}
+
+impl GammaLut {
+    pub fn identity() -> Self {
+        let table = std::array::from_fn(|i| i as u8);
+        Self {
+            r: table,
+            g: table,
+            b: table,
+        }
+    }
+
+    /// Inverse power-law correction for a display with the given gamma
+    pub fn from_gamma(gamma: f32) -> Self {
+        Self::from_channel_gammas(gamma, gamma, gamma)
+    }
+
+    pub fn from_channel_gammas(r: f32, g: f32, b: f32) -> Self {
+        Self {
+            r: inverse_gamma_table(r),
+            g: inverse_gamma_table(g),
+            b: inverse_gamma_table(b),
+        }
+    }
+
+    /// Loads a calibration file of 256 rows with one `r g b` triple per
+    /// input level, values in 0..=1, separated by whitespace or commas.
+    /// Blank lines and `#` comments are ignored.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid gamma table {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lut = Self::identity();
+        let mut rows = 0usize;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if rows >= 256 {
+                bail!("More than 256 rows");
+            }
+            let values: Vec<f32> = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|v| !v.is_empty())
+                .map(|v| v.parse::<f32>())
+                .collect::<Result<_, _>>()
+                .with_context(|| format!("Row {}: bad number", rows + 1))?;
+            let [r, g, b] = values[..] else {
+                bail!("Row {}: expected 3 values, got {}", rows + 1, values.len());
+            };
+            lut.r[rows] = to_u8(r);
+            lut.g[rows] = to_u8(g);
+            lut.b[rows] = to_u8(b);
+            rows += 1;
+        }
+        if rows != 256 {
+            bail!("Expected 256 rows, got {}", rows);
+        }
+        Ok(lut)
+    }
+
+    /// Maps the RGB channels of RGBA8 pixels in place, leaving alpha untouched
+    pub fn apply(&self, pixels: &mut [u8]) {
+        for px in pixels.chunks_exact_mut(4) {
+            px[0] = self.r[px[0] as usize];
+            px[1] = self.g[px[1] as usize];
+            px[2] = self.b[px[2] as usize];
+        }
+    }
+}
+
+impl Default for GammaLut {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+fn inverse_gamma_table(gamma: f32) -> [u8; 256] {
+    let exponent = 1.0 / gamma.max(0.01);
+    std::array::from_fn(|i| to_u8((i as f32 / 255.0).powf(exponent)))
+}
+
+fn to_u8(v: f32) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
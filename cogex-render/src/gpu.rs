@@ -0,0 +1,1202 @@
+use anyhow::Result;
+use bytemuck::cast_slice;
+use cogex_cache::Interner;
+use cogex_core::{
+    DisplayGeometry, DynamicStimulus, FeedbackOutcome, Phase, PreinternPlan, Primitive, Stimulus,
+    StimulusType, Theme, TrialFeedback, TrialState,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+use tiny_skia::{Color, Pixmap, Rect};
+
+use crate::font::FontSet;
+use crate::locale::Catalog;
+use crate::render::{
+    label_color, on_screen, render_fixation_pixmap, render_gabor_pixmap, render_noise_pixmap,
+    render_shape_pixmap, render_text_pixmap, rest_text, CacheIndex, GaborKey, NoiseKey,
+    PhaseRenderer, PhotodiodeMarker, PlannedText, Renderer, ShapeKey, LABEL_SIZE,
+    MAX_FEEDBACK_RT_MS, STATIC_LABELS, TEXT_CACHE_SIZE,
+};
+
+const SHADER: &str = r#"
+struct Screen {
+    size: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> screen: Screen;
+@group(1) @binding(0) var sprite: texture_2d<f32>;
+@group(1) @binding(1) var sprite_sampler: sampler;
+
+struct VertexOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+) -> VertexOut {
+    var out: VertexOut;
+    let ndc = position / screen.size.xy * 2.0 - 1.0;
+    out.position = vec4<f32>(ndc.x, -ndc.y, 0.0, 1.0);
+    out.uv = uv;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOut) -> @location(0) vec4<f32> {
+    return textureSample(sprite, sprite_sampler, in.uv) * in.color;
+}
+"#;
+
+/// Pixel position, texture coordinate and premultiplied tint
+type Vertex = [f32; 8];
+
+const WHITE: [f32; 4] = [1.0; 4];
+
+/// Cached texture identity, mirroring the Skia renderer's pixmap caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SpriteKey {
+    Static(usize),
+    /// Interned string at the default 24 px text size
+    Text(usize),
+    Shape(ShapeKey),
+    Gabor(GaborKey),
+    Noise(NoiseKey),
+    /// 1×1 white texel tinted for solid fills
+    Solid,
+}
+
+/// A cached texture; the pixmap is held until the next upload
+struct Sprite {
+    width: u32,
+    height: u32,
+    pixmap: Option<Pixmap>,
+    texture: Option<(wgpu::Texture, wgpu::BindGroup)>,
+}
+
+/// Consecutive vertices drawn from one sprite
+struct Batch {
+    sprite: SpriteKey,
+    vertices: Range<u32>,
+}
+
+/// GPU backend drawing the same cached pixmaps as [`crate::SkiaRenderer`] as
+/// textured quads, so a frame costs a handful of draw calls instead of a CPU
+/// canvas copy.
+///
+/// Each frame is recorded with `render_frame` and then encoded into a render
+/// pass with `encode`, which redraws the whole target. Gamma LUTs are not
+/// applied and sprites always sit on whole pixels; use the Skia renderer
+/// where calibrated luminance or sub-pixel placement matters.
+pub struct WgpuRenderer {
+    width: u32,
+    height: u32,
+    center: (f32, f32),
+
+    fonts: FontSet,
+    interner: Interner,
+    catalog: Catalog,
+    plan: PreinternPlan,
+    planned_text: PlannedText,
+
+    sprites: HashMap<SpriteKey, Sprite>,
+    vertices: Vec<Vertex>,
+    batches: Vec<Batch>,
+
+    animation: Option<(StimulusType, Box<dyn DynamicStimulus>)>,
+    animation_seed: u64,
+    stimulus_elapsed: Option<Duration>,
+    animation_elapsed: Option<Duration>,
+    /// Marker position on a rating scale stimulus
+    rating: Option<f32>,
+    /// Drawn in place of the stimulus, from a paradigm's render hook
+    primitives: Option<Vec<Primitive>>,
+    /// Countdown and progress bar drawn over the trial
+    widgets: Vec<(StimulusType, (f32, f32))>,
+    /// Seconds of enforced rest left on the break screen
+    rest_remaining: Option<u32>,
+    /// The fixation cross stays up through the inter-trial interval
+    iti_fixation: bool,
+    feedback: Option<TrialFeedback>,
+    feedback_rt: bool,
+    display: Option<DisplayGeometry>,
+    /// Shapes are rasterized with smoothed edges
+    anti_alias: bool,
+    theme: Theme,
+    /// Physical pixels per logical pixel
+    scale_factor: f32,
+
+    photodiode: Option<PhotodiodeMarker>,
+    photodiode_white: bool,
+    last_trial_state: Option<TrialState>,
+
+    /// Whether the target encodes sRGB, so tints are converted to linear
+    srgb: bool,
+    pipeline: wgpu::RenderPipeline,
+    texture_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    screen_buffer: wgpu::Buffer,
+    screen_bind_group: wgpu::BindGroup,
+    vertex_buffer: wgpu::Buffer,
+}
+
+impl WgpuRenderer {
+    /// Builds the pipeline for render targets of `format`, interning text in
+    /// the process-wide interner
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        max_trials: usize,
+    ) -> Self {
+        Self::new_with_interner(
+            device,
+            format,
+            width,
+            height,
+            max_trials,
+            Interner::global().clone(),
+        )
+    }
+
+    /// A renderer interning its text in `interner`; see
+    /// [`crate::SkiaRenderer::new_with_interner`]
+    pub fn new_with_interner(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        max_trials: usize,
+        interner: Interner,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cogex sprite shader"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let screen_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cogex screen layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let texture_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cogex sprite layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cogex sprite pipeline layout"),
+            bind_group_layouts: &[&screen_layout, &texture_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("cogex sprite pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x2,
+                        2 => Float32x4,
+                    ],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        // Sprites are drawn 1:1 on pixel boundaries, so nearest keeps them exact
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("cogex sprite sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let screen_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cogex screen size"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let screen_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("cogex screen"),
+            layout: &screen_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: screen_buffer.as_entire_binding(),
+            }],
+        });
+        let vertex_buffer = Self::create_vertex_buffer(device, 1024);
+
+        let fonts = FontSet::system();
+        let mut renderer = Self {
+            width,
+            height,
+            center: (width as f32 / 2.0, height as f32 / 2.0),
+            fonts,
+            interner,
+            catalog: Catalog::english(),
+            plan: PreinternPlan::new()
+                .with_progress_total(max_trials)
+                .with_feedback_rt(MAX_FEEDBACK_RT_MS),
+            planned_text: PlannedText::default(),
+            sprites: HashMap::new(),
+            vertices: Vec::with_capacity(1024),
+            batches: Vec::with_capacity(16),
+            animation: None,
+            animation_seed: 0,
+            stimulus_elapsed: None,
+            animation_elapsed: None,
+            rating: None,
+            primitives: None,
+            widgets: Vec::new(),
+            rest_remaining: None,
+            iti_fixation: false,
+            feedback: None,
+            feedback_rt: false,
+            display: None,
+            anti_alias: false,
+            theme: Theme::default(),
+            scale_factor: 1.0,
+            photodiode: None,
+            photodiode_white: false,
+            last_trial_state: None,
+            srgb: format.is_srgb(),
+            pipeline,
+            texture_layout,
+            sampler,
+            screen_buffer,
+            screen_bind_group,
+            vertex_buffer,
+        };
+        renderer.init_cache();
+        renderer
+    }
+
+    /// Interprets stimulus sizes and positions as degrees of visual angle
+    pub fn with_display(mut self, display: DisplayGeometry) -> Self {
+        self.set_display(Some(display));
+        self
+    }
+
+    pub fn set_display(&mut self, display: Option<DisplayGeometry>) {
+        self.display = display;
+        self.sprites.retain(|key, _| {
+            !matches!(
+                key,
+                SpriteKey::Shape(_) | SpriteKey::Gabor(_) | SpriteKey::Noise(_)
+            )
+        });
+    }
+
+    /// Smooths the edges of circles and arrows by partial coverage instead
+    /// of rasterizing them to whole pixels
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.set_anti_alias(anti_alias);
+        self
+    }
+
+    pub fn set_anti_alias(&mut self, anti_alias: bool) {
+        self.anti_alias = anti_alias;
+        self.sprites
+            .retain(|key, _| !matches!(key, SpriteKey::Shape(_)));
+        self.init_cache();
+    }
+
+    /// Draws in `theme`'s colours, re-rendering the labels, default shapes
+    /// and interned text
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.sprites
+            .retain(|key, _| !matches!(key, SpriteKey::Text(_)));
+        self.init_cache();
+    }
+
+    /// Physical pixels per logical pixel, scaling stimuli, text and the
+    /// trial screen layout like [`crate::SkiaRenderer::set_scale_factor`]
+    pub fn with_scale_factor(mut self, scale: f32) -> Self {
+        self.set_scale_factor(scale);
+        self
+    }
+
+    pub fn set_scale_factor(&mut self, scale: f32) {
+        if scale == self.scale_factor {
+            return;
+        }
+        self.scale_factor = scale;
+        self.sprites
+            .retain(|key, _| !matches!(key, SpriteKey::Text(_)));
+        self.init_cache();
+    }
+
+    /// `logical` pixels in physical ones
+    fn px(&self, logical: f32) -> f32 {
+        logical * self.scale_factor
+    }
+
+    /// Time since the current stimulus was first presented, driving animated
+    /// stimuli; set before each frame
+    pub fn set_stimulus_elapsed(&mut self, elapsed: Option<Duration>) {
+        self.stimulus_elapsed = elapsed;
+    }
+
+    /// Where the marker sits on a rating scale stimulus; set before each frame
+    pub fn set_rating(&mut self, rating: Option<f32>) {
+        self.rating = rating;
+    }
+
+    /// Primitives a paradigm draws in place of the stimulus; set before each
+    /// frame
+    pub fn set_primitives(&mut self, primitives: Option<Vec<Primitive>>) {
+        self.primitives = primitives;
+    }
+
+    /// Widgets drawn over practice and experiment screens, positioned like
+    /// stimuli; set before each frame
+    pub fn set_widgets(&mut self, widgets: Vec<(StimulusType, (f32, f32))>) {
+        self.widgets = widgets;
+    }
+
+    /// Seconds the break screen still holds the participant for, shown in
+    /// place of its Space prompt; set before each frame
+    pub fn set_rest_remaining(&mut self, seconds: Option<u32>) {
+        self.rest_remaining = seconds;
+    }
+
+    /// Verdict for the feedback screen; set before each frame
+    pub fn set_feedback(&mut self, feedback: Option<TrialFeedback>) {
+        self.feedback = feedback;
+    }
+
+    /// Adds the reaction time under the feedback verdict
+    pub fn with_feedback_rt(mut self, show: bool) -> Self {
+        self.set_feedback_rt(show);
+        self
+    }
+
+    pub fn set_feedback_rt(&mut self, show: bool) {
+        self.feedback_rt = show;
+    }
+
+    /// Shows the fixation cross during the inter-trial interval instead of
+    /// a blank screen
+    pub fn with_iti_fixation(mut self, fixation: bool) -> Self {
+        self.set_iti_fixation(fixation);
+        self
+    }
+
+    pub fn set_iti_fixation(&mut self, fixation: bool) {
+        self.iti_fixation = fixation;
+    }
+
+    /// Enables the photodiode marker
+    pub fn with_photodiode(mut self, marker: PhotodiodeMarker) -> Self {
+        self.photodiode = Some(marker);
+        self
+    }
+
+    pub fn set_photodiode(&mut self, marker: Option<PhotodiodeMarker>) {
+        self.photodiode = marker;
+    }
+
+    /// Current marker state, true when white
+    pub fn photodiode_state(&self) -> bool {
+        self.photodiode_white
+    }
+
+    /// Switches participant-facing text to `catalog`, re-rendering every
+    /// cached string in that language
+    pub fn with_catalog(mut self, catalog: Catalog) -> Self {
+        self.set_catalog(catalog);
+        self
+    }
+
+    pub fn set_catalog(&mut self, catalog: Catalog) {
+        self.catalog = catalog;
+        self.sprites
+            .retain(|key, _| !matches!(key, SpriteKey::Static(_) | SpriteKey::Text(_)));
+        self.init_cache();
+    }
+
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// Interns the trial counter and feedback strings `plan` names up
+    /// front; see [`crate::SkiaRenderer::with_preintern_plan`]
+    pub fn with_preintern_plan(mut self, plan: PreinternPlan) -> Self {
+        self.set_preintern_plan(plan);
+        self
+    }
+
+    pub fn set_preintern_plan(&mut self, plan: PreinternPlan) {
+        self.plan = plan;
+        self.planned_text = PlannedText::intern(&self.plan, &self.catalog, &self.interner);
+    }
+
+    /// The interner text IDs passed to this renderer come from
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// Lays out the next frame for a new target size; sprites do not depend
+    /// on it and are kept. A zero-sized target is ignored.
+    pub fn resize(&mut self, new_width: u32, new_height: u32) {
+        if new_width == 0 || new_height == 0 {
+            return;
+        }
+        self.width = new_width;
+        self.height = new_height;
+        self.center = (new_width as f32 / 2.0, new_height as f32 / 2.0);
+    }
+
+    fn init_cache(&mut self) {
+        for (index, id) in STATIC_LABELS {
+            let [r, g, b, a] = label_color(&self.theme, index);
+            let pixmap = render_text_pixmap(
+                self.catalog.get(id),
+                self.px(LABEL_SIZE),
+                &self.fonts,
+                Color::from_rgba8(r, g, b, a),
+            );
+            self.insert_sprite(SpriteKey::Static(index as usize), pixmap);
+        }
+        let shapes = [
+            (
+                CacheIndex::CircleStim,
+                StimulusType::Circle {
+                    radius: 50.0,
+                    color: self.theme.circle,
+                },
+            ),
+            (
+                CacheIndex::RectStim,
+                StimulusType::Rectangle {
+                    width: 80.0,
+                    height: 60.0,
+                    color: self.theme.rectangle,
+                },
+            ),
+            (
+                CacheIndex::ArrowStim,
+                StimulusType::Arrow {
+                    direction: cogex_core::ArrowDirection::Right,
+                    size: 60.0,
+                    color: self.theme.arrow,
+                },
+            ),
+        ];
+        for (index, stimulus) in shapes {
+            self.insert_sprite(
+                SpriteKey::Static(index as usize),
+                render_shape_pixmap(&stimulus.scaled(self.scale_factor), self.anti_alias),
+            );
+        }
+        self.insert_sprite(
+            SpriteKey::Static(CacheIndex::FixationCross as usize),
+            render_fixation_pixmap(self.theme.foreground, self.scale_factor),
+        );
+        let mut solid = Pixmap::new(1, 1).expect("pixmap");
+        solid.fill(Color::WHITE);
+        self.insert_sprite(SpriteKey::Solid, solid);
+
+        self.planned_text = PlannedText::intern(&self.plan, &self.catalog, &self.interner);
+    }
+
+    fn insert_sprite(&mut self, key: SpriteKey, pixmap: Pixmap) {
+        self.sprites.insert(
+            key,
+            Sprite {
+                width: pixmap.width(),
+                height: pixmap.height(),
+                pixmap: Some(pixmap),
+                texture: None,
+            },
+        );
+    }
+
+    /// Rasterizes what the next onset shows, like
+    /// [`crate::SkiaRenderer::warm_up`]; the textures are uploaded by the
+    /// next `encode`
+    pub fn warm_up(&mut self, stimuli: &[&StimulusType], progress: Option<(usize, usize)>) {
+        for &stimulus in stimuli {
+            match on_screen(
+                self.display,
+                self.scale_factor,
+                self.center,
+                stimulus,
+                (0.0, 0.0),
+            ) {
+                Some((converted, _)) => self.warm_stimulus(&converted),
+                None => self.warm_stimulus(stimulus),
+            }
+        }
+        if let Some((current, total)) = progress {
+            let ids: Vec<usize> = self
+                .planned_text
+                .progress_from(current, total)
+                .iter()
+                .take(2)
+                .copied()
+                .collect();
+            for intern_id in ids {
+                self.cache_interned(intern_id);
+            }
+        }
+    }
+
+    /// Records one frame's draw list; call `encode` to put it on screen
+    pub fn render_frame<P: Phase>(
+        &mut self,
+        phase: &P,
+        stimuli: &[(&StimulusType, (f32, f32))],
+        trial_state: Option<&TrialState>,
+        progress: Option<(usize, usize)>,
+    ) -> Result<()> {
+        let state = trial_state.cloned();
+        let showing =
+            |s: &Option<TrialState>| matches!(s, Some(TrialState::Stimulus | TrialState::Response));
+        if state == Some(TrialState::Stimulus) && !showing(&self.last_trial_state) {
+            self.photodiode_white = !self.photodiode_white;
+        }
+        self.last_trial_state = state;
+
+        let converted: Vec<_> = stimuli
+            .iter()
+            .map(|&(s, pos)| on_screen(self.display, self.scale_factor, self.center, s, pos))
+            .collect();
+        let stimuli: Vec<_> = stimuli
+            .iter()
+            .zip(&converted)
+            .map(|(&given, converted)| match converted {
+                Some((s, pos)) => (s, *pos),
+                None => given,
+            })
+            .collect();
+
+        self.vertices.clear();
+        self.batches.clear();
+        self.render_phase(phase, &stimuli, trial_state, progress)?;
+        self.draw_photodiode();
+        Ok(())
+    }
+
+    /// Uploads new textures and encodes the recorded frame into `encoder`,
+    /// clearing `target` to black first
+    pub fn encode(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+    ) {
+        let format = if self.srgb {
+            wgpu::TextureFormat::Rgba8UnormSrgb
+        } else {
+            wgpu::TextureFormat::Rgba8Unorm
+        };
+        for sprite in self.sprites.values_mut() {
+            let Some(pixmap) = sprite.pixmap.take() else {
+                continue;
+            };
+            let size = wgpu::Extent3d {
+                width: sprite.width,
+                height: sprite.height,
+                depth_or_array_layers: 1,
+            };
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("cogex sprite"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                pixmap.data(),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(sprite.width * 4),
+                    rows_per_image: Some(sprite.height),
+                },
+                size,
+            );
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("cogex sprite"),
+                layout: &self.texture_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+            sprite.texture = Some((texture, bind_group));
+        }
+
+        let screen = [self.width as f32, self.height as f32, 0.0, 0.0];
+        queue.write_buffer(&self.screen_buffer, 0, cast_slice(&screen));
+        let bytes: &[u8] = cast_slice(&self.vertices);
+        if bytes.len() as u64 > self.vertex_buffer.size() {
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.vertices.len());
+        }
+        if !bytes.is_empty() {
+            queue.write_buffer(&self.vertex_buffer, 0, bytes);
+        }
+
+        let [r, g, b, a] = self.tint(self.theme.opaque_background());
+        let background = wgpu::Color {
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+            a: a as f64,
+        };
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("cogex frame"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(background),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        if self.batches.is_empty() {
+            return;
+        }
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.screen_bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        for batch in &self.batches {
+            let Some((_, bind_group)) = self
+                .sprites
+                .get(&batch.sprite)
+                .and_then(|s| s.texture.as_ref())
+            else {
+                continue;
+            };
+            pass.set_bind_group(1, bind_group, &[]);
+            pass.draw(batch.vertices.clone(), 0..1);
+        }
+    }
+
+    fn create_vertex_buffer(device: &wgpu::Device, vertices: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("cogex vertices"),
+            size: (vertices.next_power_of_two() * std::mem::size_of::<Vertex>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Appends triangles drawn from `sprite`, extending the last batch when
+    /// it uses the same texture
+    fn push_triangles(&mut self, sprite: SpriteKey, vertices: &[Vertex]) {
+        let start = self.vertices.len() as u32;
+        self.vertices.extend_from_slice(vertices);
+        let end = self.vertices.len() as u32;
+        match self.batches.last_mut() {
+            Some(batch) if batch.sprite == sprite && batch.vertices.end == start => {
+                batch.vertices.end = end;
+            }
+            _ => self.batches.push(Batch {
+                sprite,
+                vertices: start..end,
+            }),
+        }
+    }
+
+    /// Draws a cached sprite centred at `pos` on whole pixels
+    fn push_sprite(&mut self, sprite: SpriteKey, pos: (f32, f32)) {
+        let Some(&Sprite { width, height, .. }) = self.sprites.get(&sprite) else {
+            return;
+        };
+        let x0 = (pos.0 - width as f32 * 0.5).floor();
+        let y0 = (pos.1 - height as f32 * 0.5).floor();
+        let (x1, y1) = (x0 + width as f32, y0 + height as f32);
+        let v = |x: f32, y: f32, u: f32, t: f32| -> Vertex {
+            [x, y, u, t, WHITE[0], WHITE[1], WHITE[2], WHITE[3]]
+        };
+        self.push_triangles(
+            sprite,
+            &[
+                v(x0, y0, 0.0, 0.0),
+                v(x1, y0, 1.0, 0.0),
+                v(x0, y1, 0.0, 1.0),
+                v(x0, y1, 0.0, 1.0),
+                v(x1, y0, 1.0, 0.0),
+                v(x1, y1, 1.0, 1.0),
+            ],
+        );
+    }
+
+    /// Fills a convex polygon with a solid colour
+    fn push_solid(&mut self, points: &[(f32, f32)], color: [u8; 4]) {
+        if points.len() < 3 {
+            return;
+        }
+        let tint = self.tint(color);
+        let v =
+            |(x, y): (f32, f32)| -> Vertex { [x, y, 0.5, 0.5, tint[0], tint[1], tint[2], tint[3]] };
+        let fan: Vec<Vertex> = points
+            .windows(2)
+            .skip(1)
+            .flat_map(|pair| [v(points[0]), v(pair[0]), v(pair[1])])
+            .collect();
+        self.push_triangles(SpriteKey::Solid, &fan);
+    }
+
+    fn push_rect(&mut self, rect: Rect, color: [u8; 4]) {
+        let (x0, y0, x1, y1) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+        self.push_solid(&[(x0, y0), (x1, y0), (x1, y1), (x0, y1)], color);
+    }
+
+    /// Premultiplied tint in the target's colour space
+    fn tint(&self, [r, g, b, a]: [u8; 4]) -> [f32; 4] {
+        let alpha = a as f32 / 255.0;
+        let channel = |c: u8| {
+            let c = c as f32 / 255.0;
+            let c = if !self.srgb {
+                c
+            } else if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            };
+            c * alpha
+        };
+        [channel(r), channel(g), channel(b), alpha]
+    }
+
+    fn draw_photodiode(&mut self) {
+        let Some(rect) = self
+            .photodiode
+            .and_then(|m| m.rect(self.width, self.height))
+        else {
+            return;
+        };
+        let color = if self.photodiode_white {
+            [255, 255, 255, 255]
+        } else {
+            [0, 0, 0, 255]
+        };
+        self.push_rect(rect, color);
+    }
+
+    /// Draws the verdict and, when enabled, the reaction time below it
+    fn draw_feedback(&mut self) {
+        let Some(feedback) = self.feedback else {
+            self.blit_cached(CacheIndex::Feedback as usize, self.center);
+            return;
+        };
+        let index = match feedback.outcome {
+            FeedbackOutcome::Correct => CacheIndex::Correct,
+            FeedbackOutcome::Wrong => CacheIndex::Wrong,
+            FeedbackOutcome::TooSlow => CacheIndex::TooSlow,
+        };
+        self.blit_cached(index as usize, self.center);
+        if self.feedback_rt
+            && let Some(rt) = feedback.reaction_time_ms
+        {
+            let intern_id = self.planned_text.rt(rt, &self.catalog, &self.interner);
+            let pos = (self.center.0, self.center.1 + self.px(50.0));
+            self.blit_text_by_intern_id(intern_id, pos);
+        }
+    }
+
+    /// Draws a display's stimuli, the first on top; the paradigm's
+    /// primitives stand in for the first only
+    fn blit_stimuli(&mut self, stimuli: &[(&StimulusType, (f32, f32))]) {
+        let Some((&(stimulus, pos), rest)) = stimuli.split_first() else {
+            return;
+        };
+        let primitives = self.primitives.take();
+        for &(s, pos) in rest {
+            self.blit_stimulus(s, pos);
+        }
+        self.primitives = primitives;
+        self.blit_stimulus(stimulus, pos);
+    }
+
+    /// Draws a visual stimulus; auditory ones keep the fixation cross up
+    fn blit_stimulus(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        if stimulus.is_audio() {
+            self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+            return;
+        }
+        // Taken while drawing so a nested stimulus primitive draws as usual
+        if let Some(primitives) = self.primitives.take() {
+            self.draw_primitives(primitives.clone(), pos);
+            self.primitives = Some(primitives);
+            return;
+        }
+        match stimulus {
+            StimulusType::Gabor { .. } => {
+                if let Some(key) = self.cache_gabor(stimulus) {
+                    self.push_sprite(key, pos);
+                }
+            }
+            StimulusType::NoiseMask { .. } => {
+                if let Some(key) = self.cache_noise(stimulus) {
+                    self.push_sprite(key, pos);
+                }
+            }
+            s if s.is_dynamic() => self.draw_dynamic(s, pos),
+            s if s.is_rating_scale() => self.draw_primitives(s.rating_primitives(self.rating), pos),
+            s if self.display.is_some() || s.is_text() => self.blit_shape(s, pos),
+            StimulusType::Circle { .. } => self.blit_cached(CacheIndex::CircleStim as usize, pos),
+            StimulusType::Rectangle { .. } => self.blit_cached(CacheIndex::RectStim as usize, pos),
+            StimulusType::Arrow { .. } => self.blit_cached(CacheIndex::ArrowStim as usize, pos),
+            other => panic!("unexpected StimType passed to render phase: {:?}", other),
+        }
+    }
+
+    /// Draws a shape or text stimulus in its own size and colour
+    fn blit_shape(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        if let Some(key) = self.cache_shape(stimulus) {
+            self.push_sprite(key, pos);
+        }
+    }
+
+    fn blit_text(&mut self, content: &str, size: f32, color: [u8; 4], pos: (f32, f32)) {
+        let key = self.cache_text(content, size, color);
+        self.push_sprite(key, pos);
+    }
+
+    fn cache_shape(&mut self, stimulus: &StimulusType) -> Option<SpriteKey> {
+        if let StimulusType::Text {
+            content,
+            size,
+            color,
+        } = stimulus
+        {
+            return Some(self.cache_text(content, *size, *color));
+        }
+        let key = SpriteKey::Shape(ShapeKey::from_stimulus(&self.interner, stimulus)?);
+        if !self.sprites.contains_key(&key) {
+            self.insert_sprite(key, render_shape_pixmap(stimulus, self.anti_alias));
+        }
+        Some(key)
+    }
+
+    fn cache_text(&mut self, content: &str, size: f32, color: [u8; 4]) -> SpriteKey {
+        let key = SpriteKey::Shape(ShapeKey::text(&self.interner, content, size, color));
+        if !self.sprites.contains_key(&key) {
+            let [r, g, b, a] = color;
+            let pixmap =
+                render_text_pixmap(content, size, &self.fonts, Color::from_rgba8(r, g, b, a));
+            self.insert_sprite(key, pixmap);
+        }
+        key
+    }
+
+    fn cache_gabor(&mut self, stimulus: &StimulusType) -> Option<SpriteKey> {
+        let key = SpriteKey::Gabor(GaborKey::from_stimulus(stimulus)?);
+        if !self.sprites.contains_key(&key) {
+            self.insert_sprite(key, render_gabor_pixmap(stimulus));
+        }
+        Some(key)
+    }
+
+    fn cache_noise(&mut self, stimulus: &StimulusType) -> Option<SpriteKey> {
+        let key = SpriteKey::Noise(NoiseKey::from_stimulus(stimulus)?);
+        if !self.sprites.contains_key(&key) {
+            self.insert_sprite(key, render_noise_pixmap(stimulus));
+        }
+        Some(key)
+    }
+
+    /// Interned text at the default size and colour
+    fn cache_interned(&mut self, intern_id: usize) -> SpriteKey {
+        let key = SpriteKey::Text(intern_id);
+        if !self.sprites.contains_key(&key) {
+            let [r, g, b, a] = self.theme.foreground;
+            let pixmap = render_text_pixmap(
+                &self.interner.get(intern_id),
+                self.px(TEXT_CACHE_SIZE),
+                &self.fonts,
+                Color::from_rgba8(r, g, b, a),
+            );
+            self.insert_sprite(key, pixmap);
+        }
+        key
+    }
+
+    /// Rasterizes a stimulus the way `blit_stimulus` would draw it, without
+    /// drawing
+    fn warm_stimulus(&mut self, stimulus: &StimulusType) {
+        match stimulus {
+            s if s.is_audio() || s.is_dynamic() => {}
+            StimulusType::Gabor { .. } => {
+                self.cache_gabor(stimulus);
+            }
+            StimulusType::NoiseMask { .. } => {
+                self.cache_noise(stimulus);
+            }
+            s if s.is_rating_scale() => {
+                for primitive in s.rating_primitives(None) {
+                    match primitive {
+                        Primitive::Text {
+                            content,
+                            size,
+                            color,
+                            ..
+                        } => {
+                            self.cache_text(&content, size, color);
+                        }
+                        Primitive::Stimulus { stimulus, .. } => self.warm_stimulus(&stimulus),
+                        _ => {}
+                    }
+                }
+            }
+            s if self.display.is_some() || s.is_text() => {
+                self.cache_shape(s);
+            }
+            _ => {}
+        }
+    }
+
+    /// Steps the animation to this frame's time and draws its primitives. A
+    /// new stimulus, or time running backwards into a new trial, restarts it.
+    fn draw_dynamic(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        let elapsed = self.stimulus_elapsed.unwrap_or_default();
+        let running = match (&mut self.animation, self.animation_elapsed) {
+            (Some((s, animation)), Some(last)) if s == stimulus && elapsed >= last => {
+                animation.advance(elapsed - last);
+                true
+            }
+            _ => false,
+        };
+        if !running {
+            self.animation_seed += 1;
+            self.animation = stimulus
+                .animate(self.animation_seed)
+                .map(|animation| (stimulus.clone(), animation));
+        }
+        self.animation_elapsed = Some(elapsed);
+
+        if let Some(primitives) = self.animation.as_ref().map(|(_, a)| a.primitives()) {
+            self.draw_primitives(primitives, pos);
+        }
+    }
+
+    /// Draws each widget's primitives as they stand. Widgets are never
+    /// advanced, so they leave the trial's animation running.
+    fn draw_widgets(&mut self) {
+        for (widget, pos) in self.widgets.clone() {
+            let (widget, pos) =
+                on_screen(self.display, self.scale_factor, self.center, &widget, pos)
+                    .unwrap_or((widget, pos));
+            if let Some(primitives) = widget.animate(0).map(|w| w.primitives()) {
+                self.draw_primitives(primitives, pos);
+            }
+        }
+    }
+
+    /// Draws primitives relative to `pos`
+    fn draw_primitives(&mut self, primitives: Vec<Primitive>, pos: (f32, f32)) {
+        for primitive in primitives {
+            match primitive {
+                Primitive::Dot {
+                    offset: (dx, dy),
+                    size,
+                    color,
+                } => {
+                    let (x, y) = (pos.0 + dx - size * 0.5, pos.1 + dy - size * 0.5);
+                    if let Some(rect) = Rect::from_xywh(x, y, size, size) {
+                        self.push_rect(rect, color);
+                    }
+                }
+                Primitive::Polygon { points, color } => {
+                    let points: Vec<_> = points
+                        .iter()
+                        .map(|&(dx, dy)| (pos.0 + dx, pos.1 + dy))
+                        .collect();
+                    self.push_solid(&points, color);
+                }
+                Primitive::Text {
+                    content,
+                    offset: (dx, dy),
+                    size,
+                    color,
+                } => self.blit_text(&content, size, color, (pos.0 + dx, pos.1 + dy)),
+                // A dynamic stimulus nested in another would fight over the animation
+                Primitive::Stimulus {
+                    stimulus,
+                    offset: (dx, dy),
+                } if !stimulus.is_dynamic() => {
+                    self.blit_stimulus(&stimulus, (pos.0 + dx, pos.1 + dy))
+                }
+                Primitive::Stimulus { .. } => {}
+            }
+        }
+    }
+}
+
+impl Renderer for WgpuRenderer {
+    /// Every frame redraws the whole target, so there is no damage to clear
+    fn clear_dirty(&mut self, _dirty: &[Rect]) {}
+
+    fn blit_cached(&mut self, index: usize, pos: (f32, f32)) {
+        self.push_sprite(SpriteKey::Static(index), pos);
+    }
+
+    fn blit_text_by_intern_id(&mut self, intern_id: usize, pos: (f32, f32)) {
+        if intern_id >= self.interner.len() {
+            return;
+        }
+        let key = self.cache_interned(intern_id);
+        self.push_sprite(key, pos);
+    }
+}
+
+impl<P> PhaseRenderer<P> for WgpuRenderer
+where
+    P: Phase,
+{
+    fn render_phase(
+        &mut self,
+        phase: &P,
+        stimuli: &[(&StimulusType, (f32, f32))],
+        trial_state: Option<&TrialState>,
+        progress: Option<(usize, usize)>,
+    ) -> Result<()> {
+        match phase {
+            p if p.is_welcome() => {
+                self.blit_cached(CacheIndex::Welcome as usize, self.center);
+            }
+            p if p.requires_calibration() => {
+                self.blit_cached(CacheIndex::Calibrating as usize, self.center);
+            }
+            p if p.is_practice() || p.is_experiment() => {
+                if let Some(state) = trial_state {
+                    match state {
+                        TrialState::Fixation => {
+                            self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+                        }
+                        TrialState::Cue => {
+                            self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+                            self.blit_stimuli(stimuli);
+                        }
+                        TrialState::Stimulus | TrialState::Response => {
+                            self.blit_stimuli(stimuli);
+                            if *state == TrialState::Response {
+                                self.blit_cached(
+                                    CacheIndex::Respond as usize,
+                                    (self.center.0, self.center.1 + self.px(100.0)),
+                                );
+                            }
+                        }
+                        TrialState::Feedback => self.draw_feedback(),
+                        TrialState::Complete => {
+                            // The inter-trial interval, blank unless it shows
+                            // the fixation cross or a stimulus
+                            if self.iti_fixation {
+                                self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+                            }
+                            self.blit_stimuli(stimuli);
+                        }
+                        TrialState::Break => match self.rest_remaining {
+                            Some(seconds) => {
+                                let text = rest_text(&self.catalog, seconds);
+                                let size = self.px(LABEL_SIZE);
+                                self.blit_text(&text, size, self.theme.foreground, self.center);
+                            }
+                            None => {
+                                self.blit_cached(CacheIndex::BreakPrompt as usize, self.center);
+                            }
+                        },
+                        TrialState::Countdown => {
+                            // The countdown is one of the widgets
+                        }
+                    }
+                    self.draw_widgets();
+                    if let Some((current, total)) = progress
+                        && let Some(&intern_id) =
+                            self.planned_text.progress_from(current, total).first()
+                    {
+                        self.blit_text_by_intern_id(intern_id, (self.px(50.0), self.px(30.0)));
+                    }
+                }
+
+                if p.is_practice() {
+                    self.blit_cached(
+                        CacheIndex::PracticeMode as usize,
+                        (self.center.0 - self.px(100.0), self.px(30.0)),
+                    );
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -1,2 +1,22 @@
+pub mod blend;
+pub mod damage;
+pub mod font;
+pub mod gamma;
+#[cfg(feature = "wgpu")]
+pub mod gpu;
+pub mod locale;
+pub mod lru;
 pub mod render;
-pub use render::{PhaseRenderer, Renderer, SkiaRenderer};
+mod rows;
+pub mod text;
+pub use font::FontSet;
+pub use gamma::GammaLut;
+#[cfg(feature = "wgpu")]
+pub use gpu::WgpuRenderer;
+pub use locale::Catalog;
+pub use lru::CacheStats;
+pub use render::{Corner, PhaseRenderer, PhotodiodeMarker, Renderer, SkiaRenderer};
+pub use text::{TextAlign, TextLayout};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,137 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+
+const BUILTIN: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+    ("hu", include_str!("../locales/hu.ftl")),
+];
+
+/// Participant-facing strings for one language.
+///
+/// Catalogs use the basic Fluent syntax: `id = text` messages, `#`
+/// comments, indented continuation lines and `{ $name }` placeables.
+/// Messages a catalog lacks fall back to English.
+#[derive(Debug, Clone)]
+pub struct Catalog {
+    language: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn english() -> Self {
+        Self::builtin("en").expect("English catalog is built in")
+    }
+
+    /// One of the bundled languages: `en`, `de`, `hu`
+    pub fn builtin(language: &str) -> Option<Self> {
+        let (_, text) = BUILTIN.iter().find(|(code, _)| *code == language)?;
+        Some(Self::parse(language, text).expect("bundled catalogs parse"))
+    }
+
+    /// Catalog file, named after its language (`fr.ftl`)
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let language = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        Self::parse(language, &text).with_context(|| format!("Invalid catalog {}", path.display()))
+    }
+
+    /// A bundled language code, or a path to a catalog file
+    pub fn resolve(spec: &str) -> Result<Self> {
+        match Self::builtin(spec) {
+            Some(catalog) => Ok(catalog),
+            None if Path::new(spec).exists() => Self::load(Path::new(spec)),
+            None => bail!("Unknown language {}", spec),
+        }
+    }
+
+    pub fn parse(language: &str, text: &str) -> Result<Self> {
+        let mut messages = HashMap::new();
+        let mut last: Option<String> = None;
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            if line.starts_with(char::is_whitespace) {
+                let Some(id) = &last else {
+                    bail!("Line {}: continuation without a message", number + 1);
+                };
+                let value: &mut String = messages.get_mut(id).expect("message was inserted");
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+                continue;
+            }
+            let Some((id, value)) = line.split_once('=') else {
+                bail!("Line {}: expected `id = text`", number + 1);
+            };
+            let id = id.trim().to_string();
+            messages.insert(id.clone(), value.trim().to_string());
+            last = Some(id);
+        }
+
+        if language != "en" {
+            for (id, value) in Self::english().messages {
+                messages.entry(id).or_insert(value);
+            }
+        }
+        Ok(Self {
+            language: language.to_string(),
+            messages,
+        })
+    }
+
+    /// Replaces messages by id, such as a paradigm's own feedback text
+    pub fn with_messages(mut self, messages: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.messages.extend(messages);
+        self
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Message text, or the id itself when no catalog has it
+    pub fn get<'a>(&'a self, id: &'a str) -> &'a str {
+        self.messages.get(id).map_or(id, String::as_str)
+    }
+
+    /// Message with its `{ $name }` placeables filled from `args`
+    pub fn format(&self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        let template = self.get(id);
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            out.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                rest = &rest[open..];
+                break;
+            };
+            let placeable = &rest[open..open + close + 1];
+            let name = placeable[1..placeable.len() - 1].trim();
+            match name
+                .strip_prefix('$')
+                .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+            {
+                Some((_, value)) => out.push_str(&value.to_string()),
+                None => out.push_str(placeable),
+            }
+            rest = &rest[open + close + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self::english()
+    }
+}
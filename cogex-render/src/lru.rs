@@ -0,0 +1,148 @@
+//! Pixmap caches bounded by a byte budget, evicting the least recently used.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Index;
+use std::sync::Arc;
+use tiny_skia::Pixmap;
+
+/// Byte budget of the renderer's text caches unless configured otherwise
+pub const DEFAULT_BUDGET: usize = 64 * 1024 * 1024;
+
+/// Lookups of a cache since its counts were last taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    /// Entries dropped to stay within the budget
+    pub evictions: usize,
+}
+
+struct Entry {
+    pixmap: Arc<Pixmap>,
+    /// Frame the entry was last looked up in
+    used: u64,
+}
+
+/// Pixmaps by key, holding at most `budget` bytes of pixel data. Once over
+/// it, inserting drops the entries least recently looked up, except those
+/// looked up in the current frame: a screen needing more than the budget
+/// overshoots it until the next frame rather than losing pixmaps it is
+/// about to draw.
+pub struct PixmapLru<K> {
+    entries: HashMap<K, Entry>,
+    bytes: usize,
+    budget: usize,
+    frame: u64,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone> PixmapLru<K> {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            bytes: 0,
+            budget,
+            frame: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Looks up `key`, counting a hit or a miss
+    pub fn get(&mut self, key: &K) -> Option<Arc<Pixmap>> {
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                entry.used = self.frame;
+                self.stats.hits += 1;
+                Some(Arc::clone(&entry.pixmap))
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Caches `pixmap` under `key`, then evicts down to the budget
+    pub fn insert(&mut self, key: K, pixmap: Pixmap) -> Arc<Pixmap> {
+        let pixmap = Arc::new(pixmap);
+        self.bytes += pixmap.data().len();
+        let entry = Entry {
+            pixmap: Arc::clone(&pixmap),
+            used: self.frame,
+        };
+        if let Some(old) = self.entries.insert(key, entry) {
+            self.bytes -= old.pixmap.data().len();
+        }
+        self.evict();
+        pixmap
+    }
+
+    /// Starts a new frame; entries looked up before it become evictable by
+    /// the next insert
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict();
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Bytes of pixel data held
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    /// Counts since the last call, resetting them
+    pub fn take_stats(&mut self) -> CacheStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    fn evict(&mut self) {
+        while self.bytes > self.budget {
+            let Some(key) = self
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.used < self.frame)
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone())
+            else {
+                return;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.bytes -= entry.pixmap.data().len();
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq> Index<&K> for PixmapLru<K> {
+    type Output = Pixmap;
+
+    fn index(&self, key: &K) -> &Pixmap {
+        &self.entries[key].pixmap
+    }
+}
@@ -1,21 +1,105 @@
-use ab_glyph::{point, Font, FontRef, Glyph, PxScale, ScaleFont};
-use anyhow::Result;
-use bytemuck::{cast_slice, cast_slice_mut};
-use cogex_cache::{get_text, intern_text, text_count, Atom};
-use cogex_core::{ArrowDirection, Phase, StimulusType, TrialState};
+use anyhow::{Context, Result};
+use cogex_cache::{Atom, Interner};
+use cogex_core::{
+    ArrowDirection, DebriefSummary, DisplayGeometry, DynamicStimulus, FeedbackOutcome,
+    InstructionBlock, Instructions, Phase, PreinternPlan, Primitive, QuestionItem, QuestionKind,
+    Questionnaire, Stimulus, StimulusType, Theme, TrialFeedback, TrialState,
+};
 use cogex_timing::{HighPrecisionTimer, Timer};
+
+use crate::blend;
+use crate::damage::TileGrid;
+use crate::font::FontSet;
+use crate::gamma::GammaLut;
+use crate::locale::Catalog;
+use crate::lru::{self, CacheStats, PixmapLru};
+use crate::rows::for_each_row;
+pub use crate::text::render_text_pixmap;
+use crate::text::{render_paragraph_pixmap, TextAlign, TextLayout};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tiny_skia::{
-    Color, FillRule, FilterQuality, Paint, PathBuilder, Pixmap, PixmapPaint, PremultipliedColorU8,
-    Rect, Transform,
+    Color, FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, PremultipliedColorU8, Rect, Transform,
 };
+use tracing::warn;
+
+/// Feedback reaction times are shown rounded to this, so every string a
+/// [`PreinternPlan`] covers can be interned at startup
+pub(crate) const FEEDBACK_RT_STEP_MS: u64 = 10;
+/// Reaction times interned for feedback without a plan
+pub(crate) const MAX_FEEDBACK_RT_MS: u64 = 3000;
+
+/// IDs of the strings a [`PreinternPlan`] names
+#[derive(Debug, Default)]
+pub(crate) struct PlannedText {
+    /// Trial counter strings by total, then by current trial
+    progress: HashMap<usize, Vec<usize>>,
+    /// Feedback reaction times by multiple of `FEEDBACK_RT_STEP_MS`
+    rt: Vec<usize>,
+}
+
+impl PlannedText {
+    /// Formats `plan`'s strings in `catalog`'s language and interns them
+    pub(crate) fn intern(plan: &PreinternPlan, catalog: &Catalog, interner: &Interner) -> Self {
+        let progress = plan
+            .progress_totals
+            .iter()
+            .map(|&total| {
+                let row = (0..=total)
+                    .map(|current| interner.intern(&progress_text(catalog, current, total)))
+                    .collect();
+                (total, row)
+            })
+            .collect();
+        let rt = plan.feedback_rt_max_ms.map_or_else(Vec::new, |max| {
+            (0..=max.div_ceil(FEEDBACK_RT_STEP_MS))
+                .map(|i| interner.intern(&rt_text(catalog, i * FEEDBACK_RT_STEP_MS)))
+                .collect()
+        });
+        Self { progress, rt }
+    }
+
+    /// Counter strings of trial `current` of `total` and the trials after it
+    pub(crate) fn progress_from(&self, current: usize, total: usize) -> &[usize] {
+        self.progress
+            .get(&total)
+            .and_then(|row| row.get(current..))
+            .unwrap_or(&[])
+    }
+
+    /// `rt_ms` rounded to `FEEDBACK_RT_STEP_MS`; one beyond the plan, e.g.
+    /// from a trial with a longer response window, is formatted and interned
+    /// here
+    pub(crate) fn rt(&self, rt_ms: u64, catalog: &Catalog, interner: &Interner) -> usize {
+        let step = (rt_ms + FEEDBACK_RT_STEP_MS / 2) / FEEDBACK_RT_STEP_MS;
+        match self.rt.get(step as usize) {
+            Some(&id) => id,
+            None => interner.intern(&rt_text(catalog, step * FEEDBACK_RT_STEP_MS)),
+        }
+    }
+}
+
+pub(crate) fn progress_text(catalog: &Catalog, current: usize, total: usize) -> String {
+    catalog.format(
+        "trial-progress",
+        &[("current", &current), ("total", &total)],
+    )
+}
+
+pub(crate) fn rest_text(catalog: &Catalog, seconds: u32) -> String {
+    catalog.format("break-rest", &[("seconds", &seconds)])
+}
+
+fn rt_text(catalog: &Catalog, rt_ms: u64) -> String {
+    catalog.format("response-time", &[("ms", &rt_ms)])
+}
 
 #[repr(usize)]
 #[derive(Debug, Clone, Copy)]
-enum CacheIndex {
+pub(crate) enum CacheIndex {
     // Static text labels (0-4)
     Welcome = 0,
     Calibrating = 1,
@@ -30,158 +114,610 @@ enum CacheIndex {
 
     // Fixation cross parts (8-9)
     FixationCross = 8,
+
+    // Inter-block break prompt
+    BreakPrompt = 9,
+
+    // Feedback verdicts (10-12)
+    Correct = 10,
+    Wrong = 11,
+    TooSlow = 12,
 }
 
 impl CacheIndex {
-    const STATIC_COUNT: usize = 19;
+    pub(crate) const STATIC_COUNT: usize = 19;
+}
+
+/// Cached text labels and the catalog message each shows
+pub(crate) const STATIC_LABELS: [(CacheIndex, &str); 9] = [
+    (CacheIndex::Welcome, "welcome"),
+    (CacheIndex::Calibrating, "calibrating"),
+    (CacheIndex::Respond, "respond"),
+    (CacheIndex::Feedback, "feedback"),
+    (CacheIndex::PracticeMode, "practice-mode"),
+    (CacheIndex::BreakPrompt, "break-prompt"),
+    (CacheIndex::Correct, "feedback-correct"),
+    (CacheIndex::Wrong, "feedback-wrong"),
+    (CacheIndex::TooSlow, "feedback-too-slow"),
+];
+
+/// Logical size of the static labels and of interned text
+pub(crate) const LABEL_SIZE: f32 = 32.0;
+pub(crate) const TEXT_CACHE_SIZE: f32 = 24.0;
+
+/// A stimulus and its position as drawn on screen, in physical pixels:
+/// converted from degrees with `display`, otherwise scaled from logical
+/// pixels by `scale`. `None` when it is drawn as given.
+pub(crate) fn on_screen(
+    display: Option<DisplayGeometry>,
+    scale: f32,
+    center: (f32, f32),
+    stimulus: &StimulusType,
+    pos: (f32, f32),
+) -> Option<(StimulusType, (f32, f32))> {
+    match display {
+        Some(display) => Some((
+            display.stimulus_to_px(stimulus),
+            display.position_to_px(pos, center),
+        )),
+        None if scale != 1.0 => Some((stimulus.scaled(scale), (pos.0 * scale, pos.1 * scale))),
+        None => None,
+    }
+}
+
+/// Colour a static label is rendered in
+pub(crate) fn label_color(theme: &Theme, index: CacheIndex) -> [u8; 4] {
+    match index {
+        CacheIndex::Correct => theme.correct,
+        CacheIndex::Wrong => theme.wrong,
+        CacheIndex::TooSlow => theme.too_slow,
+        _ => theme.foreground,
+    }
+}
+
+/// A `width`×`height` RGBA8 frame of the theme's background
+fn background_buffer(theme: &Theme, width: u32, height: u32) -> Vec<u8> {
+    theme
+        .opaque_background()
+        .into_iter()
+        .cycle()
+        .take((width * height * 4) as usize)
+        .collect()
 }
 
 struct TextCache {
-    font: FontRef<'static>,
+    fonts: FontSet,
     size_px: f32,
-    map: HashMap<Atom, Arc<Pixmap>>,
+    color: [u8; 4],
+    map: PixmapLru<Atom>,
 }
 
 impl TextCache {
-    fn new(font: FontRef<'static>, size_px: f32) -> Self {
+    fn new(fonts: FontSet, size_px: f32, color: [u8; 4]) -> Self {
         Self {
-            font,
+            fonts,
             size_px,
-            map: HashMap::new(),
+            color,
+            map: PixmapLru::new(lru::DEFAULT_BUDGET),
+        }
+    }
+
+    /// Renders text at `size_px` in `color` from now on, dropping what was
+    /// cached
+    fn restyle(&mut self, size_px: f32, color: [u8; 4]) {
+        if (self.size_px, self.color) != (size_px, color) {
+            self.size_px = size_px;
+            self.color = color;
+            self.map.clear();
         }
     }
 
     fn get_or_render(&mut self, atom: Atom) -> Arc<Pixmap> {
         if let Some(p) = self.map.get(&atom) {
-            return Arc::clone(p);
+            return p;
         }
-        let pm = Arc::new(render_text_pixmap(
+        let [r, g, b, a] = self.color;
+        let pm = render_text_pixmap(
             atom.as_ref(),
             self.size_px,
-            self.font.clone(),
-            Color::from_rgba8(255, 255, 255, 255),
-        ));
-        self.map.insert(atom, Arc::clone(&pm));
-        pm
-    }
-}
-
-pub fn render_text_pixmap(
-    text: &str,
-    font_size: f32,
-    font: FontRef<'static>,
-    color: Color,
-) -> Pixmap {
-    let scale = PxScale::from(font_size);
-    let sf = font.as_scaled(scale);
-
-    // 1) Layout with baseline at ascent
-    let mut pen_x = 0.0f32;
-    let mut glyphs = Vec::<Glyph>::new();
-    for ch in text.chars() {
-        let id = font.glyph_id(ch);
-        if let Some(prev) = glyphs.last() {
-            pen_x += sf.kern(prev.id, id);
-        }
-        glyphs.push(Glyph {
-            id,
-            scale,
-            position: point(pen_x, sf.ascent()),
-        });
-        pen_x += sf.h_advance(id);
+            &self.fonts,
+            Color::from_rgba8(r, g, b, a),
+        );
+        self.map.insert(atom, pm)
     }
+}
+
+/// Bit-exact Gabor parameters used as a cache key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct GaborKey([u32; 5]);
+
+impl GaborKey {
+    pub(crate) fn from_stimulus(stimulus: &StimulusType) -> Option<Self> {
+        match stimulus {
+            StimulusType::Gabor {
+                spatial_freq,
+                orientation,
+                contrast,
+                sigma,
+                phase,
+            } => Some(Self([
+                spatial_freq.to_bits(),
+                orientation.to_bits(),
+                contrast.to_bits(),
+                sigma.to_bits(),
+                phase.to_bits(),
+            ])),
+            _ => None,
+        }
+    }
+}
+
+/// Bit-exact noise mask size and grain used as a cache key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct NoiseKey([u32; 3]);
+
+impl NoiseKey {
+    pub(crate) fn from_stimulus(stimulus: &StimulusType) -> Option<Self> {
+        match stimulus {
+            StimulusType::NoiseMask {
+                width,
+                height,
+                grain,
+            } => Some(Self([width.to_bits(), height.to_bits(), grain.to_bits()])),
+            _ => None,
+        }
+    }
+}
 
-    // 2) Union pixel bounds from outlined glyphs
-    let mut min_x = f32::INFINITY;
-    let mut min_y = f32::INFINITY;
-    let mut max_x = f32::NEG_INFINITY;
-    let mut max_y = f32::NEG_INFINITY;
+/// Bit-exact shape parameters and colour used as a cache key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ShapeKey(u8, [u32; 2], [u8; 4]);
 
-    for g in &glyphs {
-        if let Some(out) = font.outline_glyph(g.clone()) {
-            let b = out.px_bounds();
-            min_x = min_x.min(b.min.x);
-            min_y = min_y.min(b.min.y);
-            max_x = max_x.max(b.max.x);
-            max_y = max_y.max(b.max.y);
+impl ShapeKey {
+    pub(crate) fn from_stimulus(interner: &Interner, stimulus: &StimulusType) -> Option<Self> {
+        match stimulus {
+            StimulusType::Circle { radius, color } => Some(Self(0, [radius.to_bits(), 0], *color)),
+            StimulusType::Rectangle {
+                width,
+                height,
+                color,
+            } => Some(Self(1, [width.to_bits(), height.to_bits()], *color)),
+            StimulusType::Arrow {
+                direction,
+                size,
+                color,
+            } => Some(Self(2, [size.to_bits(), *direction as u32], *color)),
+            StimulusType::Text {
+                content,
+                size,
+                color,
+            } => Some(Self::text(interner, content, *size, *color)),
+            _ => None,
         }
     }
 
-    if min_x == f32::INFINITY {
+    pub(crate) fn text(interner: &Interner, content: &str, size: f32, color: [u8; 4]) -> Self {
+        Self(3, [interner.intern(content) as u32, size.to_bits()], color)
+    }
+}
+
+/// Bit-exact paragraph text, size, colour and layout used as a cache key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ParagraphKey(usize, [u32; 3], TextAlign, [u8; 4]);
+
+impl ParagraphKey {
+    fn new(
+        interner: &Interner,
+        text: &str,
+        size: f32,
+        color: [u8; 4],
+        layout: &TextLayout,
+    ) -> Self {
+        Self(
+            interner.intern(text),
+            [
+                size.to_bits(),
+                layout.max_width.map_or(0, f32::to_bits),
+                layout.line_spacing.to_bits(),
+            ],
+            layout.align,
+            color,
+        )
+    }
+}
+
+/// Rasterizes a Gabor patch into a premultiplied pixmap.
+///
+/// The carrier is encoded as mean-gray luminance and the Gaussian envelope as
+/// alpha, so over a mid-gray background the blend reproduces the Gabor exactly.
+pub fn render_gabor_pixmap(stimulus: &StimulusType) -> Pixmap {
+    let StimulusType::Gabor {
+        spatial_freq,
+        orientation,
+        contrast,
+        sigma,
+        phase,
+    } = *stimulus
+    else {
         return Pixmap::new(1, 1).expect("pixmap");
+    };
+
+    // Envelope is negligible beyond 3 sigma
+    let size = (sigma * 6.0).ceil().max(1.0) as u32;
+    let mut pm = Pixmap::new(size, size).expect("pixmap");
+    let c = size as f32 * 0.5;
+    let (sin_t, cos_t) = orientation.to_radians().sin_cos();
+    let phase = phase.to_radians();
+    let two_sigma_sq = 2.0 * sigma * sigma;
+    let contrast = contrast.clamp(0.0, 1.0);
+
+    let stride = size as usize;
+    let dst = pm.pixels_mut();
+    for y in 0..stride {
+        let dy = y as f32 + 0.5 - c;
+        for x in 0..stride {
+            let dx = x as f32 + 0.5 - c;
+            let xr = dx * cos_t + dy * sin_t;
+            let envelope = (-(dx * dx + dy * dy) / two_sigma_sq).exp();
+            let carrier = (std::f32::consts::TAU * spatial_freq * xr + phase).cos();
+            let luminance = 0.5 * (1.0 + contrast * carrier);
+
+            let a = (envelope * 255.0).round() as u8;
+            let v = (luminance * envelope * 255.0).round().min(a as f32) as u8;
+            dst[y * stride + x] = PremultipliedColorU8::from_rgba(v, v, v, a).unwrap();
+        }
     }
 
-    let w = (max_x.ceil() - min_x.floor()).max(1.0) as u32;
-    let h = (max_y.ceil() - min_y.floor()).max(1.0) as u32;
+    pm
+}
 
-    // 3) Create transparent, premultiplied pixmap
-    let mut pm = Pixmap::new(w, h).expect("pixmap");
-    let mut clear = Paint::default();
-    clear.set_color(Color::from_rgba8(0, 0, 0, 0));
-    pm.fill_rect(
-        Rect::from_xywh(0.0, 0.0, w as f32, h as f32).unwrap(),
-        &clear,
-        Transform::identity(),
-        None,
+/// Rasterizes a noise mask into an opaque pixmap of black and white checks.
+///
+/// The checks come from a generator seeded by the mask's size and grain, so a
+/// mask of one size looks the same every time and in every renderer.
+pub fn render_noise_pixmap(stimulus: &StimulusType) -> Pixmap {
+    let StimulusType::NoiseMask {
+        width,
+        height,
+        grain,
+    } = *stimulus
+    else {
+        return Pixmap::new(1, 1).expect("pixmap");
+    };
+
+    let (w, h) = (
+        width.round().max(1.0) as u32,
+        height.round().max(1.0) as u32,
     );
+    let grain = grain.round().max(1.0) as usize;
+    let mut pm = Pixmap::new(w, h).expect("pixmap");
+    let (w, h) = (w as usize, h as usize);
+    let columns = w.div_ceil(grain);
+
+    let seed = ((w as u64) << 40) ^ ((h as u64) << 20) ^ grain as u64;
+    // xorshift must not start at zero
+    let mut rng = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    let checks: Vec<bool> = (0..columns * h.div_ceil(grain))
+        .map(|_| {
+            rng ^= rng >> 12;
+            rng ^= rng << 25;
+            rng ^= rng >> 27;
+            rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 63 == 1
+        })
+        .collect();
 
-    // 4) Rasterize with premultiplied alpha blending
-    let stride = pm.width() as usize;
     let dst = pm.pixels_mut();
+    for y in 0..h {
+        for x in 0..w {
+            let v = if checks[y / grain * columns + x / grain] {
+                255
+            } else {
+                0
+            };
+            dst[y * w + x] = PremultipliedColorU8::from_rgba(v, v, v, 255).unwrap();
+        }
+    }
 
-    // Convert desired color to straight u8
-    let cu = [
-        (color.red() * 255.0) as u8,
-        (color.green() * 255.0) as u8,
-        (color.blue() * 255.0) as u8,
-        (color.alpha() * 255.0) as u8,
-    ];
-
-    for g in &glyphs {
-        if let Some(out) = font.outline_glyph(g.clone()) {
-            let b = out.px_bounds();
-            out.draw(|x, y, cov| {
-                if cov <= f32::EPSILON {
-                    return;
+    pm
+}
+
+/// Rasterizes a circle, rectangle or arrow stimulus at its own size and
+/// colour. With `anti_alias` the edge pixels are partially covered, stored
+/// premultiplied so blits blend them; without it every pixel is either the
+/// full colour or empty.
+pub fn render_shape_pixmap(stimulus: &StimulusType, anti_alias: bool) -> Pixmap {
+    let (width, height) = match stimulus {
+        StimulusType::Circle { radius, .. } => {
+            let size = (radius * 2.0).ceil() as u32;
+            (size, size)
+        }
+        StimulusType::Rectangle { width, height, .. } => (*width as u32, *height as u32),
+        StimulusType::Arrow { size, .. } => {
+            let size = (size * 2.0).ceil() as u32;
+            (size, size)
+        }
+        _ => (100, 100),
+    };
+
+    let mut pixmap = Pixmap::new(width, height).unwrap();
+    let mut paint = Paint {
+        anti_alias,
+        ..Default::default()
+    };
+
+    match stimulus {
+        StimulusType::Circle { radius, color } => {
+            paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
+            let mut pb = PathBuilder::new();
+            pb.push_circle(*radius, *radius, *radius);
+            pixmap.fill_path(
+                &pb.finish().unwrap(),
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+        StimulusType::Rectangle {
+            width: w,
+            height: h,
+            color,
+        } => {
+            paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
+            let rect = Rect::from_xywh(0.0, 0.0, *w, *h).unwrap();
+            pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+        StimulusType::Arrow {
+            direction,
+            size,
+            color,
+        } => {
+            paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
+            let mut pb = PathBuilder::new();
+            let cx = *size;
+            let cy = *size;
+            match direction {
+                ArrowDirection::Right => {
+                    pb.move_to(cx + size, cy);
+                    pb.line_to(cx, cy - size);
+                    pb.line_to(cx, cy + size);
+                    pb.close();
                 }
-                // Map local outline coords to pixmap coords
-                let fx = x as f32 + b.min.x - min_x;
-                let fy = y as f32 + b.min.y - min_y;
-
-                let ix = fx.floor() as i32;
-                let iy = fy.floor() as i32;
-                if ix < 0 || iy < 0 || ix >= w as i32 || iy >= h as i32 {
-                    return;
+                ArrowDirection::Left => {
+                    pb.move_to(cx - size, cy);
+                    pb.line_to(cx, cy - size);
+                    pb.line_to(cx, cy + size);
+                    pb.close();
                 }
-
-                let i = iy as usize * stride + ix as usize;
-                if i >= dst.len() {
-                    return;
+                ArrowDirection::Up => {
+                    pb.move_to(cx, cy - size);
+                    pb.line_to(cx - size, cy);
+                    pb.line_to(cx + size, cy);
+                    pb.close();
                 }
+                ArrowDirection::Down => {
+                    pb.move_to(cx, cy + size);
+                    pb.line_to(cx - size, cy);
+                    pb.line_to(cx + size, cy);
+                    pb.close();
+                }
+            }
+            pixmap.fill_path(
+                &pb.finish().unwrap(),
+                &paint,
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+        _ => {}
+    }
+
+    pixmap
+}
 
-                // Premultiply source by (coverage * alpha)
-                let a_lin = (cov * cu[3] as f32 / 255.0).clamp(0.0, 1.0);
-                let sr = (cu[0] as f32 * a_lin) as u8;
-                let sg = (cu[1] as f32 * a_lin) as u8;
-                let sb = (cu[2] as f32 * a_lin) as u8;
-                let sa = (a_lin * 255.0) as u8;
+/// Rasterizes the 40 px fixation cross in `color`, `scale` times larger
+pub fn render_fixation_pixmap(color: [u8; 4], scale: f32) -> Pixmap {
+    let size = (40.0 * scale).round().max(1.0) as u32; // full extent of cross
+    let bar = (2.0 * scale).round().max(1.0);
+    let mut pm = Pixmap::new(size, size).unwrap();
 
-                let src = PremultipliedColorU8::from_rgba(sr, sg, sb, sa).unwrap();
-                let bg = dst[i];
+    let mut paint = Paint {
+        anti_alias: false,
+        ..Default::default()
+    };
+    paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
 
-                // Porter-Duff over in premultiplied space: out = src + bg * (1 - src.a)
-                let inv = 1.0 - (sa as f32 / 255.0);
-                let r = src.red().saturating_add((bg.red() as f32 * inv) as u8);
-                let g = src.green().saturating_add((bg.green() as f32 * inv) as u8);
-                let b = src.blue().saturating_add((bg.blue() as f32 * inv) as u8);
-                let a = src.alpha().saturating_add((bg.alpha() as f32 * inv) as u8);
+    // horizontal bar (40x2) centered
+    let h = Rect::from_xywh(0.0, (size as f32 - bar) * 0.5, size as f32, bar).unwrap();
+    pm.fill_rect(h, &paint, Transform::identity(), None);
 
-                dst[i] = PremultipliedColorU8::from_rgba(r, g, b, a).unwrap();
-            });
+    // vertical bar (2x40) centered
+    let v = Rect::from_xywh((size as f32 - bar) * 0.5, 0.0, bar, size as f32).unwrap();
+    pm.fill_rect(v, &paint, Transform::identity(), None);
+
+    pm
+}
+
+/// Draws a pixmap centered exactly at `pos`, returning the touched region.
+/// The pixmap is bilinearly resampled by the fraction of a pixel its corner
+/// falls off the grid, which spreads it one pixel wider and taller, then
+/// blitted on whole pixels.
+fn blit_pixmap_subpixel(canvas: &mut Pixmap, pixmap: &Pixmap, pos: (f32, f32)) -> Option<Rect> {
+    let (w, h) = (pixmap.width(), pixmap.height());
+    let x = pos.0 - w as f32 * 0.5;
+    let y = pos.1 - h as f32 * 0.5;
+    let shifted = shift_subpixel(pixmap, x - x.floor(), y - y.floor())?;
+    let center = (
+        x.floor() + (w + 1) as f32 * 0.5,
+        y.floor() + (h + 1) as f32 * 0.5,
+    );
+    blit_pixmap(canvas, &shifted, center)
+}
+
+/// `pixmap` moved right by `fx` and down by `fy` of a pixel, both in
+/// `[0, 1)`, into a pixmap one pixel larger each way. Each output pixel
+/// mixes the source pixel over it with the one up and to its left, in
+/// 8-bit fixed point on the premultiplied channels.
+fn shift_subpixel(pixmap: &Pixmap, fx: f32, fy: f32) -> Option<Pixmap> {
+    let (w, h) = (pixmap.width() as usize, pixmap.height() as usize);
+    let mut out = Pixmap::new(w as u32 + 1, h as u32 + 1)?;
+    let (ax, ay) = ((fx * 256.0).round() as u32, (fy * 256.0).round() as u32);
+    let src = pixmap.data();
+    let mut row = vec![0u32; (w + 1) * 4];
+    let mut above = vec![0u32; (w + 1) * 4];
+    let dst = out.data_mut();
+    for y in 0..=h {
+        // Horizontal pass for source row `y`, zero below the last one
+        row.fill(0);
+        if y < h {
+            let line = &src[y * w * 4..(y + 1) * w * 4];
+            for (i, &c) in line.iter().enumerate() {
+                row[i] += c as u32 * (256 - ax);
+                row[i + 4] += c as u32 * ax;
+            }
+        }
+        // Vertical pass mixing it with the row above
+        let line = &mut dst[y * (w + 1) * 4..(y + 1) * (w + 1) * 4];
+        for (i, d) in line.iter_mut().enumerate() {
+            *d = ((row[i] * (256 - ay) + above[i] * ay + (1 << 15)) >> 16) as u8;
         }
+        std::mem::swap(&mut row, &mut above);
     }
+    Some(out)
+}
 
-    pm
+/// Blits a stimulus pixmap, at its exact position when `subpixel` is set
+/// and snapped to whole pixels otherwise
+fn blit_placed(
+    canvas: &mut Pixmap,
+    pixmap: &Pixmap,
+    pos: (f32, f32),
+    subpixel: bool,
+) -> Option<Rect> {
+    if subpixel {
+        blit_pixmap_subpixel(canvas, pixmap, pos)
+    } else {
+        blit_pixmap(canvas, pixmap, pos)
+    }
+}
+
+/// Blits a premultiplied pixmap centered at `pos`, returning the touched region
+fn blit_pixmap(canvas: &mut Pixmap, pixmap: &Pixmap, pos: (f32, f32)) -> Option<Rect> {
+    let w = pixmap.width() as usize;
+    let h = pixmap.height() as usize;
+
+    // Compute top-left corner
+    let x0 = (pos.0 - w as f32 * 0.5).floor() as i32;
+    let y0 = (pos.1 - h as f32 * 0.5).floor() as i32;
+
+    // Skip if entirely outside canvas
+    if x0 >= canvas.width() as i32 || y0 >= canvas.height() as i32 {
+        return None;
+    }
+    if x0 + w as i32 <= 0 || y0 + h as i32 <= 0 {
+        return None;
+    }
+
+    // Determine source and destination ranges
+    let dst_x_start = x0.max(0) as usize;
+    let dst_y_start = y0.max(0) as usize;
+    let dst_x_end = (x0 + w as i32).min(canvas.width() as i32) as usize;
+    let dst_y_end = (y0 + h as i32).min(canvas.height() as i32) as usize;
+
+    let src_x_start = if x0 < 0 { (-x0) as usize } else { 0 };
+    let src_y_start = if y0 < 0 { (-y0) as usize } else { 0 };
+
+    let max_w = dst_x_end - dst_x_start;
+    let max_h = dst_y_end - dst_y_start;
+
+    if max_w == 0 || max_h == 0 {
+        return None;
+    }
+
+    let src_data = pixmap.data();
+    let canvas_stride = canvas.width() as usize;
+    let dst_data = canvas.data_mut();
+
+    let pixmap_stride = pixmap.width() as usize;
+
+    // Check if the region is fully opaque
+    let mut fully_opaque = true;
+    'opaque_check: for y in 0..max_h {
+        let row_start = (src_y_start + y) * pixmap_stride + src_x_start;
+        for x in 0..max_w {
+            let alpha = src_data[(row_start + x) * 4 + 3];
+            if alpha != 255 {
+                fully_opaque = false;
+                break 'opaque_check;
+            }
+        }
+    }
+
+    // Memcpy rows of opaque regions, otherwise blend them (premultiplied)
+    for_each_row(
+        dst_data,
+        canvas_stride * 4,
+        dst_y_start..dst_y_start + max_h,
+        dst_x_start * 4..(dst_x_start + max_w) * 4,
+        |y, dst| {
+            let src_row_start =
+                (src_y_start + y - dst_y_start) * pixmap_stride * 4 + src_x_start * 4;
+            let src = &src_data[src_row_start..src_row_start + max_w * 4];
+            if fully_opaque {
+                dst.copy_from_slice(src);
+            } else {
+                blend::source_over(dst, src);
+            }
+        },
+    );
+
+    Rect::from_xywh(
+        dst_x_start as f32,
+        dst_y_start as f32,
+        max_w as f32,
+        max_h as f32,
+    )
+}
+
+/// Screen corner holding the photodiode marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+/// Square patch for photodiode timing checks. It toggles between white and
+/// black on every stimulus-onset frame, so each onset is one luminance edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhotodiodeMarker {
+    pub corner: Corner,
+    pub size: u32,
+}
+
+impl Default for PhotodiodeMarker {
+    fn default() -> Self {
+        Self {
+            corner: Corner::default(),
+            size: 40,
+        }
+    }
+}
+
+impl PhotodiodeMarker {
+    pub(crate) fn rect(&self, width: u32, height: u32) -> Option<Rect> {
+        let size = self.size.min(width).min(height) as f32;
+        let (x, y) = match self.corner {
+            Corner::TopLeft => (0.0, 0.0),
+            Corner::TopRight => (width as f32 - size, 0.0),
+            Corner::BottomLeft => (0.0, height as f32 - size),
+            Corner::BottomRight => (width as f32 - size, height as f32 - size),
+        };
+        Rect::from_xywh(x, y, size, size)
+    }
 }
 
 pub struct FrameStats {
@@ -190,6 +726,8 @@ pub struct FrameStats {
     pub copy: Duration,
     pub total: Duration,
     pub dirty_count: usize,
+    /// Text and paragraph cache lookups since the previous frame
+    pub text_cache: CacheStats,
 }
 
 pub trait Renderer {
@@ -199,32 +737,90 @@ pub trait Renderer {
 }
 
 pub trait PhaseRenderer<P: Phase>: Renderer {
+    /// Draws `phase`; `stimuli` is what the trial shows, the stimulus first
+    /// and the rest of a multi-stimulus display after it
     fn render_phase(
         &mut self,
         phase: &P,
-        stimulus: Option<(&StimulusType, (f32, f32))>,
+        stimuli: &[(&StimulusType, (f32, f32))],
         trial_state: Option<&TrialState>,
         progress: Option<(usize, usize)>,
     ) -> Result<()>;
 }
 
+/// Debug mode saving a PNG of each screen as it is first drawn
+struct Snapshots {
+    dir: PathBuf,
+    taken: usize,
+    /// Label of the screen last saved
+    last: Option<String>,
+}
+
 pub struct SkiaRenderer {
     width: u32,
     height: u32,
     center: (f32, f32),
 
-    font: FontRef<'static>,
+    fonts: FontSet,
+    /// Resolves the text IDs the caches and `blit_text_by_intern_id` take
+    interner: Interner,
 
     static_cache: Vec<Pixmap>,
     static_sizes: Vec<(u32, u32)>,
     text_cache: TextCache,
 
-    progress_text_interns: Vec<Vec<usize>>, // [trial_count][current_trial]
-    progress_text_pixmaps: Vec<Vec<Arc<Pixmap>>>,
+    plan: PreinternPlan,
+    planned_text: PlannedText,
+    gabor_cache: HashMap<GaborKey, Pixmap>,
+    noise_cache: HashMap<NoiseKey, Pixmap>,
+    shape_cache: HashMap<ShapeKey, Pixmap>,
+    /// Dynamic stimulus being animated, with the stimulus it was built from
+    animation: Option<(StimulusType, Box<dyn DynamicStimulus>)>,
+    animation_seed: u64,
+    /// Time since stimulus onset for this frame, and when the animation last
+    /// advanced
+    stimulus_elapsed: Option<Duration>,
+    animation_elapsed: Option<Duration>,
+    /// Marker position on a rating scale stimulus
+    rating: Option<f32>,
+    /// Stimuli go at their exact fractional position, resampled
+    subpixel: bool,
+    /// Drawn in place of the stimulus, from a paradigm's render hook
+    primitives: Option<Vec<Primitive>>,
+    /// Countdown and progress bar drawn over the trial
+    widgets: Vec<(StimulusType, (f32, f32))>,
+    /// Seconds of enforced rest left on the break screen
+    rest_remaining: Option<u32>,
+    /// The fixation cross stays up through the inter-trial interval
+    iti_fixation: bool,
+    feedback: Option<TrialFeedback>,
+    feedback_rt: bool,
+    /// Instruction images by path, `None` when loading failed
+    image_cache: HashMap<PathBuf, Option<Pixmap>>,
+    paragraph_cache: PixmapLru<ParagraphKey>,
+    catalog: Catalog,
+    display: Option<DisplayGeometry>,
+    /// Shapes are rasterized with smoothed edges
+    anti_alias: bool,
+    theme: Theme,
+    /// Physical pixels per logical pixel
+    scale_factor: f32,
+    gamma: Option<GammaLut>,
+
+    photodiode: Option<PhotodiodeMarker>,
+    photodiode_white: bool,
+    last_trial_state: Option<TrialState>,
+    snapshots: Option<Snapshots>,
 
     // Rendering state
     canvas: Pixmap,
     dirty_regions: Vec<Rect>,
+    /// Last frame's damage while the front list takes this frame's, and the
+    /// merged rects copied out; kept to reuse their allocations
+    back_dirty: Vec<Rect>,
+    present_rects: Vec<Rect>,
+    /// Last frame's and this frame's damage, merged for the copy
+    damage: TileGrid,
     first_frame: bool,
 
     // Performance tracking
@@ -233,18 +829,27 @@ pub struct SkiaRenderer {
 }
 
 impl SkiaRenderer {
+    /// A renderer interning its text in the process-wide interner
     pub fn new(width: u32, height: u32, max_trials: usize) -> Self {
-        // Pre-intern all predictable text patterns
-        Self::pre_intern_text_patterns(max_trials);
+        Self::new_with_interner(width, height, max_trials, Interner::global().clone())
+    }
 
-        let font = FontRef::try_from_slice(include_bytes!("../../assets/DejaVuSans.ttf"))
-            .expect("Font load");
+    /// A renderer interning its text in `interner`, so renderers for
+    /// concurrent experiments don't share text IDs
+    pub fn new_with_interner(
+        width: u32,
+        height: u32,
+        max_trials: usize,
+        interner: Interner,
+    ) -> Self {
+        let fonts = FontSet::system();
 
         let mut canvas = Pixmap::new(width, height).unwrap();
         // Make canvas opaque once so the whole pipeline stays premultiplied + memcpy.
         {
+            let [r, g, b, a] = Theme::default().opaque_background();
             let mut p = Paint::default();
-            p.set_color(Color::from_rgba8(0, 0, 0, 255));
+            p.set_color(Color::from_rgba8(r, g, b, a));
             let r = Rect::from_xywh(0.0, 0.0, width as f32, height as f32).unwrap();
             canvas.fill_rect(r, &p, Transform::identity(), None);
         }
@@ -253,31 +858,257 @@ impl SkiaRenderer {
             width,
             height,
             center: (width as f32 / 2.0, height as f32 / 2.0),
-            font: font.clone(),
+            fonts: fonts.clone(),
+            interner,
             static_cache: vec![Pixmap::new(1, 1).unwrap(); CacheIndex::STATIC_COUNT],
             static_sizes: vec![(1, 1); CacheIndex::STATIC_COUNT],
-            text_cache: TextCache::new(font, 24.0),
-            progress_text_interns: Vec::new(),
-            progress_text_pixmaps: Vec::new(),
-            canvas: canvas,
+            text_cache: TextCache::new(fonts, TEXT_CACHE_SIZE, Theme::default().foreground),
+            plan: PreinternPlan::new()
+                .with_progress_total(max_trials)
+                .with_feedback_rt(MAX_FEEDBACK_RT_MS),
+            planned_text: PlannedText::default(),
+            gabor_cache: HashMap::new(),
+            noise_cache: HashMap::new(),
+            shape_cache: HashMap::new(),
+            animation: None,
+            animation_seed: 0,
+            stimulus_elapsed: None,
+            animation_elapsed: None,
+            rating: None,
+            subpixel: false,
+            primitives: None,
+            widgets: Vec::new(),
+            rest_remaining: None,
+            iti_fixation: false,
+            feedback: None,
+            feedback_rt: false,
+            image_cache: HashMap::new(),
+            paragraph_cache: PixmapLru::new(lru::DEFAULT_BUDGET),
+            catalog: Catalog::english(),
+            display: None,
+            anti_alias: false,
+            theme: Theme::default(),
+            scale_factor: 1.0,
+            gamma: None,
+            photodiode: None,
+            photodiode_white: false,
+            last_trial_state: None,
+            snapshots: None,
+            canvas,
             dirty_regions: Vec::with_capacity(16),
+            back_dirty: Vec::with_capacity(16),
+            present_rects: Vec::with_capacity(16),
+            damage: TileGrid::new(width, height),
             first_frame: true,
             component_timers: ["phase", "clear", "copy", "total"]
                 .iter()
                 .map(|&k| (k, RefCell::new(HighPrecisionTimer::new())))
                 .collect(),
-            clear_buffer: vec![0u8, 0, 0, 255]
-                .into_iter()
-                .cycle()
-                .take((width * height * 4) as usize)
-                .collect(),
+            clear_buffer: background_buffer(&Theme::default(), width, height),
         };
 
-        renderer.init_cache(max_trials);
+        renderer.init_cache();
         renderer
     }
 
+    /// Interprets stimulus sizes and positions as degrees of visual angle
+    pub fn with_display(mut self, display: DisplayGeometry) -> Self {
+        self.set_display(Some(display));
+        self
+    }
+
+    pub fn set_display(&mut self, display: Option<DisplayGeometry>) {
+        self.display = display;
+        self.shape_cache.clear();
+        self.gabor_cache.clear();
+        self.noise_cache.clear();
+    }
+
+    /// Smooths the edges of circles and arrows by partial coverage instead
+    /// of rasterizing them to whole pixels
+    pub fn with_anti_alias(mut self, anti_alias: bool) -> Self {
+        self.set_anti_alias(anti_alias);
+        self
+    }
+
+    pub fn set_anti_alias(&mut self, anti_alias: bool) {
+        self.anti_alias = anti_alias;
+        self.shape_cache.clear();
+        self.cache_stimuli();
+    }
+
+    /// Draws in `theme`'s colours, re-rendering every cached pixmap that
+    /// carries one and repainting the whole screen on the next frame
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.set_theme(theme);
+        self
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.text_cache
+            .restyle(self.px(TEXT_CACHE_SIZE), theme.foreground);
+        self.paragraph_cache.clear();
+        self.clear_buffer = background_buffer(&theme, self.width, self.height);
+        self.cache_static_text();
+        self.cache_stimuli();
+        self.cache_fixation();
+        self.first_frame = true;
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Bytes of rendered text each of the string and paragraph caches may
+    /// hold before dropping the least recently drawn; feedback showing
+    /// reaction times renders a new string for most trials
+    pub fn with_text_cache_budget(mut self, bytes: usize) -> Self {
+        self.set_text_cache_budget(bytes);
+        self
+    }
+
+    pub fn set_text_cache_budget(&mut self, bytes: usize) {
+        self.text_cache.map.set_budget(bytes);
+        self.paragraph_cache.set_budget(bytes);
+    }
+
+    /// Physical pixels per logical pixel, e.g. 2 on a Retina display.
+    /// Stimulus sizes and positions, text and the trial screen layout are
+    /// logical and scaled by this, except with a display geometry, which
+    /// already maps degrees to physical pixels.
+    pub fn with_scale_factor(mut self, scale: f32) -> Self {
+        self.set_scale_factor(scale);
+        self
+    }
+
+    pub fn set_scale_factor(&mut self, scale: f32) {
+        if scale == self.scale_factor {
+            return;
+        }
+        self.scale_factor = scale;
+        self.text_cache
+            .restyle(self.px(TEXT_CACHE_SIZE), self.theme.foreground);
+        self.cache_static_text();
+        self.cache_stimuli();
+        self.cache_fixation();
+        self.first_frame = true;
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// `logical` pixels in physical ones
+    fn px(&self, logical: f32) -> f32 {
+        logical * self.scale_factor
+    }
+
+    fn background(&self) -> Color {
+        let [r, g, b, a] = self.theme.opaque_background();
+        Color::from_rgba8(r, g, b, a)
+    }
+
+    /// Time since the current stimulus was first presented, driving animated
+    /// stimuli; set before each frame
+    pub fn set_stimulus_elapsed(&mut self, elapsed: Option<Duration>) {
+        self.stimulus_elapsed = elapsed;
+    }
+
+    /// Where the marker sits on a rating scale stimulus; set before each frame
+    pub fn set_rating(&mut self, rating: Option<f32>) {
+        self.rating = rating;
+    }
+
+    /// Whether stimuli are drawn at their exact fractional position,
+    /// bilinearly resampled, rather than snapped to whole pixels; set
+    /// before each frame
+    pub fn set_subpixel(&mut self, subpixel: bool) {
+        self.subpixel = subpixel;
+    }
+
+    /// Primitives a paradigm draws in place of the stimulus; set before each
+    /// frame
+    pub fn set_primitives(&mut self, primitives: Option<Vec<Primitive>>) {
+        self.primitives = primitives;
+    }
+
+    /// Widgets drawn over practice and experiment screens, positioned like
+    /// stimuli; set before each frame
+    pub fn set_widgets(&mut self, widgets: Vec<(StimulusType, (f32, f32))>) {
+        self.widgets = widgets;
+    }
+
+    /// Seconds the break screen still holds the participant for, shown in
+    /// place of its Space prompt; set before each frame
+    pub fn set_rest_remaining(&mut self, seconds: Option<u32>) {
+        self.rest_remaining = seconds;
+    }
+
+    /// Verdict for the feedback screen; set before each frame. Without one
+    /// the feedback screen shows a plain "FEEDBACK" label.
+    pub fn set_feedback(&mut self, feedback: Option<TrialFeedback>) {
+        self.feedback = feedback;
+    }
+
+    /// Adds the reaction time under the feedback verdict
+    pub fn with_feedback_rt(mut self, show: bool) -> Self {
+        self.set_feedback_rt(show);
+        self
+    }
+
+    pub fn set_feedback_rt(&mut self, show: bool) {
+        self.feedback_rt = show;
+    }
+
+    /// Shows the fixation cross during the inter-trial interval instead of
+    /// a blank screen
+    pub fn with_iti_fixation(mut self, fixation: bool) -> Self {
+        self.set_iti_fixation(fixation);
+        self
+    }
+
+    pub fn set_iti_fixation(&mut self, fixation: bool) {
+        self.iti_fixation = fixation;
+    }
+
+    /// Applies `lut` to every pixel copied to the frame buffer
+    pub fn with_gamma(mut self, lut: GammaLut) -> Self {
+        self.set_gamma(Some(lut));
+        self
+    }
+
+    pub fn set_gamma(&mut self, lut: Option<GammaLut>) {
+        self.gamma = lut;
+        self.first_frame = true;
+    }
+
+    /// Enables the photodiode marker
+    pub fn with_photodiode(mut self, marker: PhotodiodeMarker) -> Self {
+        self.photodiode = Some(marker);
+        self
+    }
+
+    pub fn set_photodiode(&mut self, marker: Option<PhotodiodeMarker>) {
+        self.photodiode = marker;
+    }
+
+    /// Current marker state, true when white
+    pub fn photodiode_state(&self) -> bool {
+        self.photodiode_white
+    }
+
+    /// Adapts to a new canvas size. The canvas, clear buffer and damage grid
+    /// are reallocated, paragraphs wrapped to the old width are dropped,
+    /// damage recorded at the old size is discarded and the next frame
+    /// repaints the whole screen around the new centre. Interned text and
+    /// the size-independent pixmap caches are kept. A zero-sized canvas, as
+    /// from a minimised window, is ignored.
     pub fn resize(&mut self, new_width: u32, new_height: u32) {
+        if new_width == 0 || new_height == 0 || (new_width, new_height) == (self.width, self.height)
+        {
+            return;
+        }
         // Update dimensions and center
         self.width = new_width;
         self.height = new_height;
@@ -285,61 +1116,104 @@ impl SkiaRenderer {
 
         // Recreate the canvas pixmap
         self.canvas = Pixmap::new(new_width, new_height).expect("Failed to resize canvas pixmap");
-        self.canvas.fill(Color::from_rgba8(0, 0, 0, 255));
+        self.canvas.fill(self.background());
+        self.damage = TileGrid::new(new_width, new_height);
 
         // Reallocate the clear buffer to match the new size
-        self.clear_buffer = vec![0u8, 0, 0, 255]
-            .into_iter()
-            .cycle()
-            .take((new_width * new_height * 4) as usize)
-            .collect();
+        self.clear_buffer = background_buffer(&self.theme, new_width, new_height);
 
+        self.paragraph_cache.clear();
+        self.dirty_regions.clear();
+        self.back_dirty.clear();
+        self.present_rects.clear();
         self.first_frame = true;
     }
 
-    /// Pre-intern all predictable text patterns at startup
-    fn pre_intern_text_patterns(max_trials: usize) {
-        // Common progress patterns - pre-compute all combinations
-        for trial in 0..=max_trials {
-            for current in 0..=trial {
-                intern_text(&format!("Trial: {}/{}", current, max_trials));
-            }
-        }
+    /// Switches participant-facing text to `catalog`, re-interning and
+    /// re-rendering every cached string in that language
+    pub fn with_catalog(mut self, catalog: Catalog) -> Self {
+        self.set_catalog(catalog);
+        self
+    }
 
-        // Pre-intern percentage patterns for feedback
-        for pct in (0..=100).step_by(5) {
-            intern_text(&format!("Accuracy: {}%", pct));
-        }
+    pub fn set_catalog(&mut self, catalog: Catalog) {
+        self.catalog = catalog;
+        self.intern_plan();
+        self.cache_static_text();
+    }
+
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// The interner text IDs passed to this renderer come from
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// Interns the trial counter and feedback strings `plan` names up
+    /// front, so drawing a trial never formats one. Without a plan the
+    /// renderer covers a single phase of the `max_trials` it was built with
+    /// and reaction times up to 3 s.
+    pub fn with_preintern_plan(mut self, plan: PreinternPlan) -> Self {
+        self.set_preintern_plan(plan);
+        self
+    }
+
+    pub fn set_preintern_plan(&mut self, plan: PreinternPlan) {
+        self.plan = plan;
+        self.intern_plan();
+    }
 
-        // Pre-intern common response times
-        for rt in (100..=2000).step_by(50) {
-            intern_text(&format!("Response time: {}ms", rt));
+    fn intern_plan(&mut self) {
+        self.planned_text = PlannedText::intern(&self.plan, &self.catalog, &self.interner);
+    }
+
+    /// Rasterizes what the next onset shows into the caches: `stimuli`, and
+    /// the trial counter for this trial and the one after it. Called between
+    /// frames ahead of onset, so the first frame showing them only blits.
+    pub fn warm_up(&mut self, stimuli: &[&StimulusType], progress: Option<(usize, usize)>) {
+        for &stimulus in stimuli {
+            match on_screen(
+                self.display,
+                self.scale_factor,
+                self.center,
+                stimulus,
+                (0.0, 0.0),
+            ) {
+                Some((converted, _)) => self.warm_stimulus(&converted),
+                None => self.warm_stimulus(stimulus),
+            }
+        }
+        if let Some((current, total)) = progress {
+            for &intern_id in self
+                .planned_text
+                .progress_from(current, total)
+                .iter()
+                .take(2)
+            {
+                self.text_cache
+                    .get_or_render(Atom::from(self.interner.get(intern_id).as_str()));
+            }
         }
     }
 
-    fn init_cache(&mut self, max_trials: usize) {
+    fn init_cache(&mut self) {
+        self.intern_plan();
         self.cache_static_text();
         self.cache_stimuli();
         self.cache_fixation();
-        // Build progress lookup as intern IDs (idempotent, no new strings)
-        self.precompute_progress_pixmaps(max_trials);
     }
 
     fn cache_static_text(&mut self) {
-        let labels = [
-            (CacheIndex::Welcome as usize, "WELCOME"),
-            (CacheIndex::Calibrating as usize, "CALIBRATING..."),
-            (CacheIndex::Respond as usize, "respond"),
-            (CacheIndex::Feedback as usize, "FEEDBACK"),
-            (CacheIndex::PracticeMode as usize, "PRACTICE MODE"),
-        ];
-
-        for (index, text) in labels {
+        for (index, id) in STATIC_LABELS {
+            let [r, g, b, a] = label_color(&self.theme, index);
+            let index = index as usize;
             let pixmap = render_text_pixmap(
-                text,
-                32.0,
-                self.font.clone(),
-                Color::from_rgba8(255, 255, 255, 255),
+                self.catalog.get(id),
+                self.px(LABEL_SIZE),
+                &self.fonts,
+                Color::from_rgba8(r, g, b, a),
             );
             self.static_sizes[index] = (pixmap.width(), pixmap.height());
             self.static_cache[index] = pixmap;
@@ -348,160 +1222,57 @@ impl SkiaRenderer {
 
     fn cache_stimuli(&mut self) {
         // Circle
-        let circle_pixmap = self.render_stimulus_to_pixmap(&StimulusType::Circle {
-            radius: 50.0,
-            color: [255, 0, 0, 255],
-        });
+        let circle_pixmap = render_shape_pixmap(
+            &StimulusType::Circle {
+                radius: 50.0,
+                color: self.theme.circle,
+            }
+            .scaled(self.scale_factor),
+            self.anti_alias,
+        );
         self.static_sizes[CacheIndex::CircleStim as usize] =
             (circle_pixmap.width(), circle_pixmap.height());
         self.static_cache[CacheIndex::CircleStim as usize] = circle_pixmap;
 
         // Rectangle
-        let rect_pixmap = self.render_stimulus_to_pixmap(&StimulusType::Rectangle {
-            width: 80.0,
-            height: 60.0,
-            color: [0, 255, 0, 255],
-        });
+        let rect_pixmap = render_shape_pixmap(
+            &StimulusType::Rectangle {
+                width: 80.0,
+                height: 60.0,
+                color: self.theme.rectangle,
+            }
+            .scaled(self.scale_factor),
+            self.anti_alias,
+        );
         self.static_sizes[CacheIndex::RectStim as usize] =
             (rect_pixmap.width(), rect_pixmap.height());
         self.static_cache[CacheIndex::RectStim as usize] = rect_pixmap;
 
         // Arrow
-        let arrow_pixmap = self.render_stimulus_to_pixmap(&StimulusType::Arrow {
-            direction: ArrowDirection::Right,
-            size: 60.0,
-            color: [0, 0, 255, 255],
-        });
+        let arrow_pixmap = render_shape_pixmap(
+            &StimulusType::Arrow {
+                direction: ArrowDirection::Right,
+                size: 60.0,
+                color: self.theme.arrow,
+            }
+            .scaled(self.scale_factor),
+            self.anti_alias,
+        );
         self.static_sizes[CacheIndex::ArrowStim as usize] =
             (arrow_pixmap.width(), arrow_pixmap.height());
         self.static_cache[CacheIndex::ArrowStim as usize] = arrow_pixmap;
     }
 
     fn cache_fixation(&mut self) {
-        let size = 40u32; // full extent of cross
-        let mut pm = Pixmap::new(size, size).unwrap();
-
-        let mut paint = Paint::default();
-        paint.anti_alias = false;
-        paint.set_color(Color::from_rgba8(255, 255, 255, 255));
-
-        // horizontal bar (40x2) centered
-        let h = Rect::from_xywh(0.0, (size as f32 - 2.0) * 0.5, size as f32, 2.0).unwrap();
-        pm.fill_rect(h, &paint, Transform::identity(), None);
-
-        // vertical bar (2x40) centered
-        let v = Rect::from_xywh((size as f32 - 2.0) * 0.5, 0.0, 2.0, size as f32).unwrap();
-        pm.fill_rect(v, &paint, Transform::identity(), None);
-
-        self.static_sizes[CacheIndex::FixationCross as usize] = (pm.width(), pm.height());
-        self.static_cache[CacheIndex::FixationCross as usize] = pm;
-    }
-
-    fn precompute_progress_pixmaps(&mut self, max_trials: usize) {
-        self.progress_text_interns = Vec::with_capacity(max_trials + 1);
-        for total in 0..=max_trials {
-            let mut row: Vec<usize> = Vec::with_capacity(total + 1);
-            for current in 0..=total {
-                let s = format!("Trial: {}/{}", current, total);
-                let intern_id = intern_text(&s);
-                row.push(intern_id);
-            }
-            self.progress_text_interns.push(row);
-        }
-    }
-
-    fn render_stimulus_to_pixmap(&self, stimulus: &StimulusType) -> Pixmap {
-        let (width, height) = match stimulus {
-            StimulusType::Circle { radius, .. } => {
-                let size = (radius * 2.0).ceil() as u32;
-                (size, size)
-            }
-            StimulusType::Rectangle { width, height, .. } => (*width as u32, *height as u32),
-            StimulusType::Arrow { size, .. } => {
-                let size = (size * 2.0).ceil() as u32;
-                (size, size)
-            }
-            _ => (100, 100),
-        };
-
-        let mut pixmap = Pixmap::new(width, height).unwrap();
-        let mut paint = Paint::default();
-        paint.anti_alias = false;
-
-        match stimulus {
-            StimulusType::Circle { radius, color } => {
-                paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
-                let mut pb = PathBuilder::new();
-                pb.push_circle(*radius, *radius, *radius);
-                pixmap.fill_path(
-                    &pb.finish().unwrap(),
-                    &paint,
-                    FillRule::Winding,
-                    Transform::identity(),
-                    None,
-                );
-            }
-            StimulusType::Rectangle {
-                width: w,
-                height: h,
-                color,
-            } => {
-                paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
-                let rect = Rect::from_xywh(0.0, 0.0, *w, *h).unwrap();
-                pixmap.fill_rect(rect, &paint, Transform::identity(), None);
-            }
-            StimulusType::Arrow {
-                direction,
-                size,
-                color,
-            } => {
-                paint.set_color(Color::from_rgba8(color[0], color[1], color[2], color[3]));
-                let mut pb = PathBuilder::new();
-                let cx = *size;
-                let cy = *size;
-                match direction {
-                    ArrowDirection::Right => {
-                        pb.move_to(cx + size, cy);
-                        pb.line_to(cx, cy - size);
-                        pb.line_to(cx, cy + size);
-                        pb.close();
-                    }
-                    ArrowDirection::Left => {
-                        pb.move_to(cx - size, cy);
-                        pb.line_to(cx, cy - size);
-                        pb.line_to(cx, cy + size);
-                        pb.close();
-                    }
-                    ArrowDirection::Up => {
-                        pb.move_to(cx, cy - size);
-                        pb.line_to(cx - size, cy);
-                        pb.line_to(cx + size, cy);
-                        pb.close();
-                    }
-                    ArrowDirection::Down => {
-                        pb.move_to(cx, cy + size);
-                        pb.line_to(cx - size, cy);
-                        pb.line_to(cx + size, cy);
-                        pb.close();
-                    }
-                }
-                pixmap.fill_path(
-                    &pb.finish().unwrap(),
-                    &paint,
-                    FillRule::Winding,
-                    Transform::identity(),
-                    None,
-                );
-            }
-            _ => {}
-        }
-
-        pixmap
+        let pm = render_fixation_pixmap(self.theme.foreground, self.scale_factor);
+        self.static_sizes[CacheIndex::FixationCross as usize] = (pm.width(), pm.height());
+        self.static_cache[CacheIndex::FixationCross as usize] = pm;
     }
 
     fn clear_dirty(&mut self, dirty: &[Rect]) {
         let stride = self.width as usize * 4;
         let canvas_data = self.canvas.data_mut();
+        let clear_buffer = &self.clear_buffer;
 
         for rect in dirty {
             let x0 = rect.x().floor().max(0.0).min(self.width as f32) as usize;
@@ -511,12 +1282,10 @@ impl SkiaRenderer {
             if x1 <= x0 || y1 <= y0 {
                 continue;
             }
-            let row_len = (x1 - x0) * 4;
-            for y in y0..y1 {
+            for_each_row(canvas_data, stride, y0..y1, x0 * 4..x1 * 4, |y, row| {
                 let off = y * stride + x0 * 4;
-                canvas_data[off..off + row_len]
-                    .copy_from_slice(&self.clear_buffer[off..off + row_len]);
-            }
+                row.copy_from_slice(&clear_buffer[off..off + row.len()]);
+            });
         }
     }
 
@@ -536,95 +1305,590 @@ impl SkiaRenderer {
         let row_bytes = (self.width as usize) * 4;
         let canvas_data = self.canvas.data();
 
-        for row in y0..y1 {
-            let off = row * row_bytes + x0 * 4;
-            unsafe {
-                std::ptr::copy_nonoverlapping(
-                    canvas_data.as_ptr().add(off),
-                    frame_buffer.as_mut_ptr().add(off),
-                    bytes,
-                );
+        let gamma = self.gamma.as_ref();
+        for_each_row(frame_buffer, row_bytes, y0..y1, x0 * 4..x1 * 4, |y, dst| {
+            let off = y * row_bytes + x0 * 4;
+            dst.copy_from_slice(&canvas_data[off..off + bytes]);
+            if let Some(lut) = gamma {
+                lut.apply(dst);
             }
+        });
+    }
+
+    pub fn render_frame<P: Phase>(
+        &mut self,
+        phase: &P,
+        stimuli: &[(&StimulusType, (f32, f32))],
+        trial_state: Option<&TrialState>,
+        progress: Option<(usize, usize)>,
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        // Onset is the first frame drawing the stimulus of a trial
+        let state = trial_state.cloned();
+        let showing =
+            |s: &Option<TrialState>| matches!(s, Some(TrialState::Stimulus | TrialState::Response));
+        if state == Some(TrialState::Stimulus) && !showing(&self.last_trial_state) {
+            self.photodiode_white = !self.photodiode_white;
+        }
+        self.last_trial_state = state;
+
+        let converted: Vec<_> = stimuli
+            .iter()
+            .map(|&(s, pos)| on_screen(self.display, self.scale_factor, self.center, s, pos))
+            .collect();
+        let stimuli: Vec<_> = stimuli
+            .iter()
+            .zip(&converted)
+            .map(|(&given, converted)| match converted {
+                Some((s, pos)) => (s, *pos),
+                None => given,
+            })
+            .collect();
+
+        let stats = self.present(frame_buffer, timer, |r| {
+            r.render_phase(phase, &stimuli, trial_state, progress)?;
+            r.draw_photodiode();
+            Ok(())
+        })?;
+        if self.snapshots.is_some() {
+            let screen = match trial_state {
+                Some(state) => format!("{:?}_{:?}", phase, state),
+                None => format!("{:?}", phase),
+            };
+            self.snapshot_screen(&screen);
+        }
+        Ok(stats)
+    }
+
+    /// Saves the canvas as a PNG. This is the frame as drawn, before the
+    /// gamma correction applied on the way to the frame buffer.
+    pub fn snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        self.canvas
+            .save_png(path)
+            .with_context(|| format!("Failed to save snapshot {}", path.display()))
+    }
+
+    /// Turns on saving a PNG of each phase, trial state, instruction page,
+    /// questionnaire item and text screen into `dir` as it is first drawn,
+    /// numbered in order of appearance; `None` turns it off
+    pub fn set_snapshot_dir(&mut self, dir: Option<PathBuf>) -> Result<()> {
+        if let Some(dir) = &dir {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        self.snapshots = dir.map(|dir| Snapshots {
+            dir,
+            taken: 0,
+            last: None,
+        });
+        Ok(())
+    }
+
+    /// Saves the frame just drawn when snapshots are on and `screen` differs
+    /// from the last one saved
+    fn snapshot_screen(&mut self, screen: &str) {
+        let Some(snapshots) = &mut self.snapshots else {
+            return;
+        };
+        let screen: String = screen
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        if snapshots.last.as_deref() == Some(screen.as_str()) {
+            return;
+        }
+        let path = snapshots
+            .dir
+            .join(format!("{:04}_{}.png", snapshots.taken, screen));
+        snapshots.taken += 1;
+        snapshots.last = Some(screen);
+        if let Err(e) = self.snapshot(&path) {
+            warn!("{:#}", e);
+        }
+    }
+
+    fn draw_photodiode(&mut self) {
+        let Some(rect) = self
+            .photodiode
+            .and_then(|m| m.rect(self.width, self.height))
+        else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(if self.photodiode_white {
+            Color::WHITE
+        } else {
+            Color::BLACK
+        });
+        self.canvas
+            .fill_rect(rect, &paint, Transform::identity(), None);
+        self.dirty_regions.push(rect);
+    }
+
+    /// Renders the participant metadata form: a title and one line per
+    /// `(label, value)` field, with a caret on the active field
+    pub fn render_form(
+        &mut self,
+        title: &str,
+        fields: &[(&str, &str)],
+        active: usize,
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let mut lines = vec![title.to_string(), String::new()];
+        for (i, (label, value)) in fields.iter().enumerate() {
+            let caret = if i == active { "_" } else { "" };
+            lines.push(format!("{}: {}{}", label, value, caret));
         }
+        self.render_text_lines(&lines, frame_buffer, timer)
     }
 
-    fn coalesce_dirty(rects: &mut Vec<Rect>) {
-        rects.sort_by(|a, b| {
-            a.y()
-                .partial_cmp(&b.y())
-                .unwrap()
-                .then(a.x().partial_cmp(&b.x()).unwrap())
+    /// Renders the current instruction page: headings, wrapped paragraphs
+    /// and images stacked and centred, with a paging hint below
+    pub fn render_instructions(
+        &mut self,
+        instructions: &Instructions,
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let heading_size = self.px(40.0);
+        let text_size = self.px(28.0);
+        let foreground = self.theme.foreground;
+
+        enum Item {
+            Text(ParagraphKey),
+            Image(PathBuf, f32),
+        }
+
+        let max_width = self.width as f32 * 0.8;
+        let max_image_height = self.height as f32 * 0.4;
+        let layout = TextLayout::default().with_max_width(max_width);
+        let gap = text_size * 0.5;
+        let mut items = Vec::new();
+        for block in instructions.page().map_or(&[][..], |p| &p.blocks) {
+            match block {
+                InstructionBlock::Heading(text) => {
+                    items.push(Item::Text(self.paragraph(
+                        text,
+                        heading_size,
+                        foreground,
+                        &layout,
+                    )));
+                }
+                InstructionBlock::Paragraph(text) => {
+                    items.push(Item::Text(
+                        self.paragraph(text, text_size, foreground, &layout),
+                    ));
+                }
+                InstructionBlock::Image(path) => {
+                    let image = self.image_cache.entry(path.clone()).or_insert_with(|| {
+                        Pixmap::load_png(path)
+                            .map_err(|e| warn!("Failed to load {}: {}", path.display(), e))
+                            .ok()
+                    });
+                    if let Some(pm) = image {
+                        let scale = (max_width / pm.width() as f32)
+                            .min(max_image_height / pm.height() as f32)
+                            .min(1.0);
+                        items.push(Item::Image(path.clone(), scale));
+                    }
+                }
+            }
+        }
+
+        let height_of = |r: &Self, item: &Item| match item {
+            Item::Text(key) => r.paragraph_cache[key].height() as f32,
+            Item::Image(path, scale) => r.image_cache[path]
+                .as_ref()
+                .map_or(0.0, |pm| pm.height() as f32 * scale),
+        };
+        let total = items.iter().map(|i| height_of(self, i)).sum::<f32>()
+            + gap * items.len().saturating_sub(1) as f32;
+        let footer = self.catalog.get(if instructions.is_last_page() {
+            "instructions-start"
+        } else {
+            "instructions-next"
         });
-        let mut out: Vec<Rect> = Vec::with_capacity(rects.len());
-        for r in rects.drain(..) {
-            if let Some(last) = out.last_mut() {
-                let same_row =
-                    (r.y() - last.y()).abs() < 1.0 && (r.height() - last.height()).abs() < 1.0;
-                let touching = r.x() <= last.x() + last.width() + 1.0;
-                if same_row && touching {
-                    let nx = last.x().min(r.x());
-                    let nx2 = (last.x() + last.width()).max(r.x() + r.width());
-                    *last = Rect::from_xywh(nx, last.y(), nx2 - nx, last.height()).unwrap();
-                    continue;
+        let footer = self.interner.intern(footer);
+        let page = format!(
+            "{}/{}",
+            instructions.page_index() + 1,
+            instructions.page_count()
+        );
+
+        let stats = self.present(frame_buffer, timer, |r| {
+            let mut y = (r.center.1 - total * 0.5).max(r.px(20.0));
+            for item in &items {
+                let h = height_of(r, item);
+                match item {
+                    Item::Text(key) => {
+                        let pos = (r.center.0, y + h * 0.5);
+                        if let Some(rect) = blit_pixmap(&mut r.canvas, &r.paragraph_cache[key], pos)
+                        {
+                            r.dirty_regions.push(rect);
+                        }
+                    }
+                    Item::Image(path, scale) => {
+                        if let Some(pm) = &r.image_cache[path] {
+                            let w = pm.width() as f32 * scale;
+                            let x = r.center.0 - w * 0.5;
+                            r.canvas.draw_pixmap(
+                                0,
+                                0,
+                                pm.as_ref(),
+                                &PixmapPaint::default(),
+                                Transform::from_row(*scale, 0.0, 0.0, *scale, x, y),
+                                None,
+                            );
+                            if let Some(rect) =
+                                Rect::from_xywh(x.floor(), y.floor(), w + 1.0, h + 1.0)
+                            {
+                                r.dirty_regions.push(rect);
+                            }
+                        }
+                    }
                 }
+                y += h + gap;
+            }
+            let bottom = r.height as f32 - r.px(40.0);
+            r.blit_text_by_intern_id(footer, (r.center.0, bottom));
+            r.blit_text_by_intern_id(
+                r.interner.intern(&page),
+                (r.width as f32 - r.px(60.0), bottom),
+            );
+            Ok(())
+        })?;
+        self.snapshot_screen(&format!("instructions_p{}", instructions.page_index() + 1));
+        Ok(stats)
+    }
+
+    /// Renders the current questionnaire item: the prompt, a numbered box per
+    /// option (a row for Likert scales, a column for multiple choice) and an
+    /// answering hint below
+    pub fn render_questionnaire(
+        &mut self,
+        questionnaire: &Questionnaire,
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let prompt_size = self.px(32.0);
+        let foreground = self.theme.foreground;
+
+        let Some(item) = questionnaire.item() else {
+            return self.render_text_lines(&[], frame_buffer, timer);
+        };
+        let layout = TextLayout::default().with_max_width(self.width as f32 * 0.8);
+        let prompt = self.paragraph(&item.prompt, prompt_size, foreground, &layout);
+        let boxes = self.option_rects(item);
+        let labels: Vec<usize> = match &item.kind {
+            QuestionKind::Likert { points, .. } => (1..=*points as usize)
+                .map(|n| self.interner.intern(&n.to_string()))
+                .collect(),
+            QuestionKind::MultipleChoice(options) => options
+                .iter()
+                .enumerate()
+                .map(|(i, option)| self.interner.intern(&format!("{}. {}", i + 1, option)))
+                .collect(),
+        };
+        let ends = match &item.kind {
+            QuestionKind::Likert { low, high, .. } => {
+                Some((self.interner.intern(low), self.interner.intern(high)))
+            }
+            QuestionKind::MultipleChoice(_) => None,
+        };
+        let hint = self.interner.intern(self.catalog.get("questionnaire-hint"));
+        let count = self.interner.intern(&format!(
+            "{}/{}",
+            questionnaire.item_index() + 1,
+            questionnaire.item_count()
+        ));
+
+        let stats = self.present(frame_buffer, timer, |r| {
+            let pos = (r.center.0, r.height as f32 * 0.3);
+            if let Some(rect) = blit_pixmap(&mut r.canvas, &r.paragraph_cache[&prompt], pos) {
+                r.dirty_regions.push(rect);
+            }
+            let mut paint = Paint::default();
+            paint.set_color_rgba8(70, 70, 70, 255);
+            for (rect, label) in boxes.iter().zip(&labels) {
+                r.canvas
+                    .fill_rect(*rect, &paint, Transform::identity(), None);
+                r.dirty_regions.push(*rect);
+                r.blit_text_by_intern_id(
+                    *label,
+                    (
+                        rect.x() + rect.width() * 0.5,
+                        rect.y() + rect.height() * 0.5,
+                    ),
+                );
+            }
+            if let (Some((low, high)), Some(first), Some(last)) =
+                (ends, boxes.first(), boxes.last())
+            {
+                let y = first.bottom() + r.px(30.0);
+                r.blit_text_by_intern_id(low, (first.x() + first.width() * 0.5, y));
+                r.blit_text_by_intern_id(high, (last.x() + last.width() * 0.5, y));
+            }
+            let bottom = r.height as f32 - r.px(40.0);
+            r.blit_text_by_intern_id(hint, (r.center.0, bottom));
+            r.blit_text_by_intern_id(count, (r.width as f32 - r.px(60.0), bottom));
+            Ok(())
+        })?;
+        self.snapshot_screen(&format!(
+            "questionnaire_q{}",
+            questionnaire.item_index() + 1
+        ));
+        Ok(stats)
+    }
+
+    /// The questionnaire option drawn under `pos`, in pixels
+    pub fn questionnaire_option_at(&self, item: &QuestionItem, pos: (f32, f32)) -> Option<usize> {
+        self.option_rects(item).iter().position(|rect| {
+            (rect.left()..rect.right()).contains(&pos.0)
+                && (rect.top()..rect.bottom()).contains(&pos.1)
+        })
+    }
+
+    fn option_rects(&self, item: &QuestionItem) -> Vec<Rect> {
+        let count = item.option_count();
+        match item.kind {
+            QuestionKind::Likert { .. } => {
+                let gap = self.px(16.0);
+                let size = self
+                    .px(64.0)
+                    .min(self.width as f32 * 0.8 / count as f32 - gap);
+                let step = size + gap;
+                let x0 = self.center.0 - step * count as f32 * 0.5 + gap * 0.5;
+                let y = self.height as f32 * 0.55 - size * 0.5;
+                (0..count)
+                    .filter_map(|i| Rect::from_xywh(x0 + i as f32 * step, y, size, size))
+                    .collect()
+            }
+            QuestionKind::MultipleChoice(_) => {
+                let width = self.width as f32 * 0.6;
+                let x = self.center.0 - width * 0.5;
+                let y0 = self.height as f32 * 0.45;
+                (0..count)
+                    .filter_map(|i| {
+                        Rect::from_xywh(x, y0 + i as f32 * self.px(64.0), width, self.px(52.0))
+                    })
+                    .collect()
             }
-            out.push(r);
         }
-        *rects = out;
     }
 
-    pub fn render_frame<P: Phase>(
+    /// Renders the end-of-session screen: a thank-you, overall accuracy and
+    /// reaction times, then any paradigm-specific `details`
+    pub fn render_debrief(
+        &mut self,
+        summary: &DebriefSummary,
+        details: &[String],
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let c = &self.catalog;
+        let mut lines = vec![c.get("debrief-title").to_string(), String::new()];
+        if let Some(accuracy) = summary.accuracy() {
+            lines.push(c.format(
+                "debrief-trials",
+                &[("correct", &summary.correct), ("total", &summary.trials)],
+            ));
+            let percent = (accuracy * 100.0).round();
+            lines.push(c.format("debrief-accuracy", &[("percent", &percent)]));
+        }
+        if let Some(mean) = summary.mean_rt_ms {
+            lines.push(c.format("debrief-mean-rt", &[("ms", &mean.round())]));
+        }
+        if let Some(median) = summary.median_rt_ms {
+            lines.push(c.format("debrief-median-rt", &[("ms", &median.round())]));
+        }
+        if !details.is_empty() {
+            lines.push(String::new());
+            lines.extend(details.iter().cloned());
+        }
+        lines.push(String::new());
+        lines.push(c.get("debrief-exit").to_string());
+        self.render_text_lines(&lines, frame_buffer, timer)
+    }
+
+    /// Renders the neutral screen shown after a session is ended early
+    pub fn render_session_ended(
+        &mut self,
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let c = &self.catalog;
+        let lines = vec![
+            c.get("session-ended").to_string(),
+            String::new(),
+            c.get("session-ended-contact").to_string(),
+        ];
+        self.render_text_lines(&lines, frame_buffer, timer)
+    }
+
+    /// Renders the screen asking a participant who left the window to come
+    /// back and press Space
+    pub fn render_interrupted(
+        &mut self,
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let c = &self.catalog;
+        let lines = vec![
+            c.get("interrupted").to_string(),
+            String::new(),
+            c.get("interrupted-continue").to_string(),
+        ];
+        self.render_text_lines(&lines, frame_buffer, timer)
+    }
+
+    /// Renders lines of text centred on screen, wrapping any too wide for it
+    pub fn render_text_lines(
+        &mut self,
+        lines: &[String],
+        frame_buffer: &mut [u8],
+        timer: &mut HighPrecisionTimer,
+    ) -> Result<FrameStats> {
+        let text_size = self.px(32.0);
+        let line_height = self.px(40.0);
+        let layout = TextLayout::default()
+            .with_max_width(self.width as f32 * 0.9)
+            .with_line_spacing(line_height / text_size);
+        let keys: Vec<Option<ParagraphKey>> = lines
+            .iter()
+            .map(|line| {
+                (!line.is_empty())
+                    .then(|| self.paragraph(line, text_size, self.theme.foreground, &layout))
+            })
+            .collect();
+        // Wrapped lines take extra rows
+        let rows: Vec<f32> = keys
+            .iter()
+            .map(|key| {
+                key.map_or(1.0, |k| {
+                    (self.paragraph_cache[&k].height() as f32 / line_height)
+                        .round()
+                        .max(1.0)
+                })
+            })
+            .collect();
+
+        let stats = self.present(frame_buffer, timer, |r| {
+            let total: f32 = rows.iter().sum();
+            let mut y = r.center.1 - total * line_height * 0.5;
+            for (key, rows) in keys.iter().zip(&rows) {
+                let h = rows * line_height;
+                if let Some(key) = key {
+                    let pos = (r.center.0, y + h * 0.5);
+                    if let Some(rect) = blit_pixmap(&mut r.canvas, &r.paragraph_cache[key], pos) {
+                        r.dirty_regions.push(rect);
+                    }
+                }
+                y += h;
+            }
+            Ok(())
+        })?;
+        if self.snapshots.is_some() {
+            let first = lines.iter().find(|l| !l.is_empty());
+            let screen: String = first
+                .map_or("blank", |l| l.as_str())
+                .chars()
+                .take(24)
+                .collect();
+            self.snapshot_screen(&format!("text_{}", screen));
+        }
+        Ok(stats)
+    }
+
+    /// Lays out and caches a paragraph, returning its cache key
+    fn paragraph(
+        &mut self,
+        text: &str,
+        size: f32,
+        color: [u8; 4],
+        layout: &TextLayout,
+    ) -> ParagraphKey {
+        let key = ParagraphKey::new(&self.interner, text, size, color, layout);
+        if self.paragraph_cache.get(&key).is_none() {
+            let [r, g, b, a] = color;
+            let pixmap = render_paragraph_pixmap(
+                text,
+                size,
+                &self.fonts,
+                Color::from_rgba8(r, g, b, a),
+                layout,
+            );
+            self.paragraph_cache.insert(key, pixmap);
+        }
+        key
+    }
+
+    /// Runs one frame: clears last frame's damage, draws via `draw`, and copies
+    /// the union of old and new damage to `frame_buffer`
+    fn present<F>(
         &mut self,
-        phase: &P,
-        stimulus: Option<(&StimulusType, (f32, f32))>,
-        trial_state: Option<&TrialState>,
-        progress: Option<(usize, usize)>,
         frame_buffer: &mut [u8],
         timer: &mut HighPrecisionTimer,
+        draw: F,
     ) -> Result<FrameStats>
     where
-        P: Phase,
+        F: FnOnce(&mut Self) -> Result<()>,
     {
+        self.text_cache.map.next_frame();
+        self.paragraph_cache.next_frame();
         if self.first_frame {
             self.first_frame = false;
-            self.canvas.fill(Color::from_rgba8(0, 0, 0, 255));
+            self.canvas.fill(self.background());
             frame_buffer.copy_from_slice(&self.clear_buffer);
+            if let Some(lut) = &self.gamma {
+                lut.apply(frame_buffer);
+            }
             self.dirty_regions.clear();
         }
 
-        // 1) Extract old dirty rects
-        let mut old_dirty = Vec::new();
-        std::mem::swap(&mut old_dirty, &mut self.dirty_regions);
+        // 1) Last frame's damage becomes the back list, taken out of self
+        // while it is cleared; this frame draws into the emptied front list
+        std::mem::swap(&mut self.dirty_regions, &mut self.back_dirty);
+        self.dirty_regions.clear();
+        let old_dirty = std::mem::take(&mut self.back_dirty);
 
         // 2) CLEAR old regions on offscreen canvas
-        let clear_slice =
-            unsafe { std::slice::from_raw_parts(old_dirty.as_ptr(), old_dirty.len()) };
         let t_clear_off = {
             let t = timer.now();
-            SkiaRenderer::clear_dirty(self, clear_slice);
+            SkiaRenderer::clear_dirty(self, &old_dirty);
             timer.elapsed(t)
         };
 
         // 4) DRAW new content
         let t_phase = {
             let t = timer.now();
-            self.render_phase(phase, stimulus, trial_state, progress)?;
+            draw(self)?;
             timer.elapsed(t)
         };
-        // 5) COPY new dirty regions to visible frame_buffer
-        let ptr = self.dirty_regions.as_ptr();
-        let len = self.dirty_regions.len();
-        let mut present_rects = old_dirty;
-        unsafe {
-            present_rects.extend_from_slice(std::slice::from_raw_parts(ptr, len));
+        // 5) COPY old and new damage to visible frame_buffer, merged into
+        // disjoint tile runs
+        for rect in old_dirty.iter().chain(&self.dirty_regions) {
+            self.damage.mark(rect);
         }
-        SkiaRenderer::coalesce_dirty(&mut present_rects);
+        self.back_dirty = old_dirty;
+        let mut present_rects = std::mem::take(&mut self.present_rects);
+        present_rects.clear();
+        self.damage.drain_into(&mut present_rects);
 
         let t_copy = {
             let t = timer.now();
             for rect in &present_rects {
                 self.copy_dirty_region(*rect, frame_buffer);
             }
+            self.present_rects = present_rects;
             timer.elapsed(t)
         };
         let t_clear_vis = Duration::ZERO;
@@ -647,110 +1911,355 @@ impl SkiaRenderer {
             copy: t_copy,
             total,
             dirty_count: self.dirty_regions.len(),
+            text_cache: self.take_text_cache_stats(),
         })
     }
 
+    fn take_text_cache_stats(&mut self) -> CacheStats {
+        let text = self.text_cache.map.take_stats();
+        let paragraphs = self.paragraph_cache.take_stats();
+        CacheStats {
+            hits: text.hits + paragraphs.hits,
+            misses: text.misses + paragraphs.misses,
+            evictions: text.evictions + paragraphs.evictions,
+        }
+    }
+
     fn blit_cached_fast(&mut self, index: usize, pos: (f32, f32)) {
         if index >= self.static_cache.len() {
             return;
         }
 
-        let pixmap = &self.static_cache[index];
-        let (w_u32, h_u32) = self.static_sizes[index];
-        let w = w_u32 as usize;
-        let h = h_u32 as usize;
-
-        // Compute top-left corner
-        let x0 = (pos.0 - w as f32 * 0.5).floor() as i32;
-        let y0 = (pos.1 - h as f32 * 0.5).floor() as i32;
+        if let Some(rect) = blit_pixmap(&mut self.canvas, &self.static_cache[index], pos) {
+            self.dirty_regions.push(rect);
+        }
+    }
 
-        // // Skip if entirely outside canvas
-        // if x0 >= self.canvas.width() as i32 || y0 >= self.canvas.height() as i32 {
-        //     return;
-        // }
-        // if x0 + w as i32 <= 0 || y0 + h as i32 <= 0 {
-        //     return;
-        // }
+    /// Draws the verdict and, when enabled, the reaction time below it
+    fn draw_feedback(&mut self) {
+        let Some(feedback) = self.feedback else {
+            self.blit_cached(CacheIndex::Feedback as usize, self.center);
+            return;
+        };
+        let index = match feedback.outcome {
+            FeedbackOutcome::Correct => CacheIndex::Correct,
+            FeedbackOutcome::Wrong => CacheIndex::Wrong,
+            FeedbackOutcome::TooSlow => CacheIndex::TooSlow,
+        };
+        self.blit_cached(index as usize, self.center);
+        if self.feedback_rt
+            && let Some(rt) = feedback.reaction_time_ms
+        {
+            let intern_id = self.planned_text.rt(rt, &self.catalog, &self.interner);
+            let pos = (self.center.0, self.center.1 + self.px(50.0));
+            self.blit_text_by_intern_id(intern_id, pos);
+        }
+    }
 
-        // Determine source and destination ranges
-        let dst_x_start = x0.max(0) as usize;
-        let dst_y_start = y0.max(0) as usize;
-        let dst_x_end = (x0 + w as i32).min(self.canvas.width() as i32) as usize;
-        let dst_y_end = (y0 + h as i32).min(self.canvas.height() as i32) as usize;
+    /// Draws a display's stimuli, the first on top; the paradigm's
+    /// primitives stand in for the first only
+    fn blit_stimuli(&mut self, stimuli: &[(&StimulusType, (f32, f32))]) {
+        let Some((&(stimulus, pos), rest)) = stimuli.split_first() else {
+            return;
+        };
+        let primitives = self.primitives.take();
+        for &(s, pos) in rest {
+            self.blit_stimulus(s, pos);
+        }
+        self.primitives = primitives;
+        self.blit_stimulus(stimulus, pos);
+    }
 
-        let src_x_start = if x0 < 0 { (-x0) as usize } else { 0 };
-        let src_y_start = if y0 < 0 { (-y0) as usize } else { 0 };
+    /// Draws a visual stimulus; auditory ones keep the fixation cross up
+    fn blit_stimulus(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        if stimulus.is_audio() {
+            self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+            return;
+        }
+        // Taken while drawing so a nested stimulus primitive draws as usual
+        if let Some(primitives) = self.primitives.take() {
+            self.draw_primitives(primitives.clone(), pos);
+            self.primitives = Some(primitives);
+            return;
+        }
+        match stimulus {
+            StimulusType::Gabor { .. } => self.blit_gabor(stimulus, pos),
+            StimulusType::NoiseMask { .. } => self.blit_noise(stimulus, pos),
+            s if s.is_dynamic() => self.draw_dynamic(s, pos),
+            s if s.is_rating_scale() => self.draw_primitives(s.rating_primitives(self.rating), pos),
+            s if self.display.is_some() || s.is_text() => self.blit_shape(s, pos),
+            StimulusType::Circle { .. } => self.blit_static_stimulus(CacheIndex::CircleStim, pos),
+            StimulusType::Rectangle { .. } => self.blit_static_stimulus(CacheIndex::RectStim, pos),
+            StimulusType::Arrow { .. } => self.blit_static_stimulus(CacheIndex::ArrowStim, pos),
+            other => panic!("unexpected StimType passed to render phase: {:?}", other),
+        }
+    }
 
-        let max_w = dst_x_end - dst_x_start;
-        let max_h = dst_y_end - dst_y_start;
+    /// Steps the animation to this frame's time and draws its primitives. A
+    /// new stimulus, or time running backwards into a new trial, restarts it.
+    fn draw_dynamic(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        let elapsed = self.stimulus_elapsed.unwrap_or_default();
+        let running = match (&mut self.animation, self.animation_elapsed) {
+            (Some((s, animation)), Some(last)) if s == stimulus && elapsed >= last => {
+                animation.advance(elapsed - last);
+                true
+            }
+            _ => false,
+        };
+        if !running {
+            self.animation_seed += 1;
+            self.animation = stimulus
+                .animate(self.animation_seed)
+                .map(|animation| (stimulus.clone(), animation));
+        }
+        self.animation_elapsed = Some(elapsed);
 
-        if max_w == 0 || max_h == 0 {
-            return;
+        if let Some(primitives) = self.animation.as_ref().map(|(_, a)| a.primitives()) {
+            self.draw_primitives(primitives, pos);
         }
+    }
 
-        let src_data = pixmap.data();
-        let canvas_stride = self.canvas.width() as usize;
-        let dst_data = self.canvas.data_mut();
+    /// Draws each widget's primitives as they stand. Widgets are never
+    /// advanced, so they leave the trial's animation running.
+    fn draw_widgets(&mut self) {
+        for (widget, pos) in self.widgets.clone() {
+            let (widget, pos) =
+                on_screen(self.display, self.scale_factor, self.center, &widget, pos)
+                    .unwrap_or((widget, pos));
+            if let Some(primitives) = widget.animate(0).map(|w| w.primitives()) {
+                self.draw_primitives(primitives, pos);
+            }
+        }
+    }
 
-        let pixmap_stride = pixmap.width() as usize;
-
-        // Check if the region is fully opaque
-        let mut fully_opaque = true;
-        'opaque_check: for y in 0..max_h {
-            let row_start = (src_y_start + y) * pixmap_stride + src_x_start;
-            for x in 0..max_w {
-                let alpha = src_data[(row_start + x) * 4 + 3];
-                if alpha != 255 {
-                    fully_opaque = false;
-                    break 'opaque_check;
+    /// Draws primitives relative to `pos`
+    fn draw_primitives(&mut self, primitives: Vec<Primitive>, pos: (f32, f32)) {
+        let mut bounds: Option<(f32, f32, f32, f32)> = None;
+        let mut grow = |x0: f32, y0: f32, x1: f32, y1: f32| {
+            let b = bounds.get_or_insert((x0, y0, x1, y1));
+            *b = (b.0.min(x0), b.1.min(y0), b.2.max(x1), b.3.max(y1));
+        };
+        let mut nested = Vec::new();
+        for primitive in primitives {
+            match primitive {
+                Primitive::Dot {
+                    offset: (dx, dy),
+                    size,
+                    color: [r, g, b, a],
+                } => {
+                    let (x, y) = (pos.0 + dx - size * 0.5, pos.1 + dy - size * 0.5);
+                    let Some(rect) = Rect::from_xywh(x, y, size, size) else {
+                        continue;
+                    };
+                    let mut paint = Paint::default();
+                    paint.set_color_rgba8(r, g, b, a);
+                    self.canvas
+                        .fill_rect(rect, &paint, Transform::identity(), None);
+                    grow(x, y, x + size, y + size);
+                }
+                Primitive::Polygon {
+                    points,
+                    color: [r, g, b, a],
+                } => {
+                    let mut pb = PathBuilder::new();
+                    for (i, &(dx, dy)) in points.iter().enumerate() {
+                        let (x, y) = (pos.0 + dx, pos.1 + dy);
+                        if i == 0 {
+                            pb.move_to(x, y);
+                        } else {
+                            pb.line_to(x, y);
+                        }
+                        grow(x, y, x, y);
+                    }
+                    pb.close();
+                    let Some(path) = pb.finish() else {
+                        continue;
+                    };
+                    let mut paint = Paint::default();
+                    paint.set_color_rgba8(r, g, b, a);
+                    paint.anti_alias = true;
+                    self.canvas.fill_path(
+                        &path,
+                        &paint,
+                        FillRule::Winding,
+                        Transform::identity(),
+                        None,
+                    );
+                }
+                Primitive::Text { .. } | Primitive::Stimulus { .. } => nested.push(primitive),
+            }
+        }
+        if let Some((x0, y0, x1, y1)) = bounds
+            && let Some(rect) =
+                Rect::from_ltrb(x0.floor() - 1.0, y0.floor() - 1.0, x1 + 1.0, y1 + 1.0)
+        {
+            self.dirty_regions.push(rect);
+        }
+        for primitive in nested {
+            match primitive {
+                Primitive::Text {
+                    content,
+                    offset: (dx, dy),
+                    size,
+                    color,
+                } => self.blit_text(&content, size, color, (pos.0 + dx, pos.1 + dy)),
+                // A dynamic stimulus nested in another would fight over the animation
+                Primitive::Stimulus {
+                    stimulus,
+                    offset: (dx, dy),
+                } if !stimulus.is_dynamic() => {
+                    self.blit_stimulus(&stimulus, (pos.0 + dx, pos.1 + dy))
                 }
+                _ => {}
             }
         }
+    }
+
+    /// Blits a stimulus from the startup cache
+    fn blit_static_stimulus(&mut self, index: CacheIndex, pos: (f32, f32)) {
+        let pixmap = &self.static_cache[index as usize];
+        if let Some(rect) = blit_placed(&mut self.canvas, pixmap, pos, self.subpixel) {
+            self.dirty_regions.push(rect);
+        }
+    }
+
+    /// Blits a shape or text stimulus rasterized from its own parameters,
+    /// including its colour, rather than the fixed startup cache
+    fn blit_shape(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        let Some(key) = self.cache_shape(stimulus) else {
+            return;
+        };
+        if let Some(rect) = blit_placed(
+            &mut self.canvas,
+            &self.shape_cache[&key],
+            pos,
+            self.subpixel,
+        ) {
+            self.dirty_regions.push(rect);
+        }
+    }
+
+    /// Blits text in its own size and colour, rasterizing it on first use
+    fn blit_text(&mut self, content: &str, size: f32, color: [u8; 4], pos: (f32, f32)) {
+        let key = self.cache_text(content, size, color);
+        if let Some(rect) = blit_placed(
+            &mut self.canvas,
+            &self.shape_cache[&key],
+            pos,
+            self.subpixel,
+        ) {
+            self.dirty_regions.push(rect);
+        }
+    }
+
+    /// Blits a Gabor patch, rasterizing it into the cache on first use
+    fn blit_gabor(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        let Some(key) = self.cache_gabor(stimulus) else {
+            return;
+        };
+        if let Some(rect) = blit_placed(
+            &mut self.canvas,
+            &self.gabor_cache[&key],
+            pos,
+            self.subpixel,
+        ) {
+            self.dirty_regions.push(rect);
+        }
+    }
+
+    /// Blits a noise mask, generating its pattern on first use of its size
+    fn blit_noise(&mut self, stimulus: &StimulusType, pos: (f32, f32)) {
+        let Some(key) = self.cache_noise(stimulus) else {
+            return;
+        };
+        if let Some(rect) = blit_placed(
+            &mut self.canvas,
+            &self.noise_cache[&key],
+            pos,
+            self.subpixel,
+        ) {
+            self.dirty_regions.push(rect);
+        }
+    }
+
+    fn cache_shape(&mut self, stimulus: &StimulusType) -> Option<ShapeKey> {
+        if let StimulusType::Text {
+            content,
+            size,
+            color,
+        } = stimulus
+        {
+            return Some(self.cache_text(content, *size, *color));
+        }
+        let key = ShapeKey::from_stimulus(&self.interner, stimulus)?;
+        let anti_alias = self.anti_alias;
+        self.shape_cache
+            .entry(key)
+            .or_insert_with(|| render_shape_pixmap(stimulus, anti_alias));
+        Some(key)
+    }
+
+    fn cache_text(&mut self, content: &str, size: f32, color: [u8; 4]) -> ShapeKey {
+        let key = ShapeKey::text(&self.interner, content, size, color);
+        if !self.shape_cache.contains_key(&key) {
+            let [r, g, b, a] = color;
+            let pixmap =
+                render_text_pixmap(content, size, &self.fonts, Color::from_rgba8(r, g, b, a));
+            self.shape_cache.insert(key, pixmap);
+        }
+        key
+    }
+
+    fn cache_gabor(&mut self, stimulus: &StimulusType) -> Option<GaborKey> {
+        let key = GaborKey::from_stimulus(stimulus)?;
+        self.gabor_cache
+            .entry(key)
+            .or_insert_with(|| render_gabor_pixmap(stimulus));
+        Some(key)
+    }
+
+    fn cache_noise(&mut self, stimulus: &StimulusType) -> Option<NoiseKey> {
+        let key = NoiseKey::from_stimulus(stimulus)?;
+        self.noise_cache
+            .entry(key)
+            .or_insert_with(|| render_noise_pixmap(stimulus));
+        Some(key)
+    }
 
-        if fully_opaque {
-            // Fast memcpy per row for opaque regions
-            for y in 0..max_h {
-                let src_row_start = (src_y_start + y) * pixmap_stride * 4 + src_x_start * 4;
-                let dst_row_start = (dst_y_start + y) * canvas_stride * 4 + dst_x_start * 4;
-                dst_data[dst_row_start..dst_row_start + max_w * 4]
-                    .copy_from_slice(&src_data[src_row_start..src_row_start + max_w * 4]);
+    /// Rasterizes a stimulus the way `blit_stimulus` would draw it, without
+    /// drawing. Animated stimuli are drawn afresh every frame and have
+    /// nothing to cache.
+    fn warm_stimulus(&mut self, stimulus: &StimulusType) {
+        match stimulus {
+            s if s.is_audio() || s.is_dynamic() => {}
+            StimulusType::Gabor { .. } => {
+                self.cache_gabor(stimulus);
             }
-        } else {
-            // Blend per pixel (premultiplied)
-            for y in 0..max_h {
-                for x in 0..max_w {
-                    let src_idx = ((src_y_start + y) * pixmap_stride + (src_x_start + x)) * 4;
-                    let dst_idx = ((dst_y_start + y) * canvas_stride + (dst_x_start + x)) * 4;
-
-                    let sa = src_data[src_idx + 3] as u32;
-                    let sr = src_data[src_idx + 0] as u32;
-                    let sg = src_data[src_idx + 1] as u32;
-                    let sb = src_data[src_idx + 2] as u32;
-
-                    let da = dst_data[dst_idx + 3] as u32;
-                    let dr = dst_data[dst_idx + 0] as u32;
-                    let dg = dst_data[dst_idx + 1] as u32;
-                    let db = dst_data[dst_idx + 2] as u32;
-
-                    let inv_a = 255 - sa;
-
-                    dst_data[dst_idx + 0] = (sr + (dr * inv_a + 127) / 255) as u8;
-                    dst_data[dst_idx + 1] = (sg + (dg * inv_a + 127) / 255) as u8;
-                    dst_data[dst_idx + 2] = (sb + (db * inv_a + 127) / 255) as u8;
-                    dst_data[dst_idx + 3] = (sa + (da * inv_a + 127) / 255) as u8;
+            StimulusType::NoiseMask { .. } => {
+                self.cache_noise(stimulus);
+            }
+            s if s.is_rating_scale() => {
+                for primitive in s.rating_primitives(None) {
+                    match primitive {
+                        Primitive::Text {
+                            content,
+                            size,
+                            color,
+                            ..
+                        } => {
+                            self.cache_text(&content, size, color);
+                        }
+                        Primitive::Stimulus { stimulus, .. } => self.warm_stimulus(&stimulus),
+                        _ => {}
+                    }
                 }
             }
+            s if self.display.is_some() || s.is_text() => {
+                self.cache_shape(s);
+            }
+            // Drawn from the startup cache
+            _ => {}
         }
-
-        self.dirty_regions.push(
-            Rect::from_xywh(
-                dst_x_start as f32,
-                dst_y_start as f32,
-                max_w as f32,
-                max_h as f32,
-            )
-            .unwrap(),
-        );
     }
 }
 
@@ -764,11 +2273,10 @@ impl Renderer for SkiaRenderer {
     }
 
     fn blit_text_by_intern_id(&mut self, intern_id: usize, pos: (f32, f32)) {
-        if intern_id >= text_count() {
+        let Some(text) = self.interner.try_get(intern_id) else {
             return;
-        }
-
-        let atom = Atom::from(get_text(intern_id).as_str());
+        };
+        let atom = Atom::from(text.as_str());
         let pm = self.text_cache.get_or_render(atom);
         let (w, h) = (pm.width(), pm.height());
         let (cw, ch) = (self.width as usize, self.height as usize);
@@ -807,49 +2315,21 @@ impl Renderer for SkiaRenderer {
             opaque
         };
 
-        // Convert rows to u32 slices for fast copying
-        let src_u32 = cast_slice(src_data);
-        let dst_u32 = cast_slice_mut(dst_data);
-
-        if fully_opaque {
-            for row in 0..copy_h {
-                let src_row_start = (src_y_offset + row) * pm.width() as usize + src_x_offset;
-                let dst_row_start = (dst_y + row) * cw + dst_x;
-                dst_u32[dst_row_start..dst_row_start + copy_w]
-                    .copy_from_slice(&src_u32[src_row_start..src_row_start + copy_w]);
-            }
-        } else {
-            // Branch-free alpha blending
-            for row in 0..copy_h {
-                let src_row_start = (src_y_offset + row) * pm.width() as usize + src_x_offset;
-                let dst_row_start = (dst_y + row) * cw + dst_x;
-
-                for i in 0..copy_w {
-                    let s = src_u32[src_row_start + i];
-                    let d = dst_u32[dst_row_start + i];
-
-                    let sa = ((s >> 24) as u32 & 0xFF) as u32;
-                    let inv = 255 - sa;
-
-                    // Premultiplied blending
-                    let sr = (s & 0xFF) as u32;
-                    let sg = ((s >> 8) as u32 & 0xFF) as u32;
-                    let sb = ((s >> 16) as u32 & 0xFF) as u32;
-
-                    let dr = (d & 0xFF) as u32;
-                    let dg = ((d >> 8) as u32 & 0xFF) as u32;
-                    let db = ((d >> 16) as u32 & 0xFF) as u32;
-                    let da = ((d >> 24) as u32 & 0xFF) as u32;
-
-                    let r = sr + (dr * inv + 127) / 255;
-                    let g = sg + (dg * inv + 127) / 255;
-                    let b = sb + (db * inv + 127) / 255;
-                    let a = sa + (da * inv + 127) / 255;
-
-                    dst_u32[dst_row_start + i] = (a << 24) | (b << 16) | (g << 8) | r;
+        for_each_row(
+            dst_data,
+            cw * 4,
+            dst_y..dst_y + copy_h,
+            dst_x * 4..(dst_x + copy_w) * 4,
+            |y, dst_row| {
+                let src_row_start = (src_y_offset + y - dst_y) * src_row_bytes + src_x_offset * 4;
+                let src_row = &src_data[src_row_start..src_row_start + copy_w * 4];
+                if fully_opaque {
+                    dst_row.copy_from_slice(src_row);
+                } else {
+                    blend::source_over(dst_row, src_row);
                 }
-            }
-        }
+            },
+        );
 
         self.dirty_regions.push(
             Rect::from_xywh(dst_x as f32, dst_y as f32, copy_w as f32, copy_h as f32).unwrap(),
@@ -864,7 +2344,7 @@ where
     fn render_phase(
         &mut self,
         phase: &P,
-        stimulus: Option<(&StimulusType, (f32, f32))>,
+        stimuli: &[(&StimulusType, (f32, f32))],
         trial_state: Option<&TrialState>,
         progress: Option<(usize, usize)>,
     ) -> Result<()> {
@@ -881,56 +2361,56 @@ where
                         TrialState::Fixation => {
                             self.blit_cached(CacheIndex::FixationCross as usize, self.center);
                         }
+                        TrialState::Cue => {
+                            self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+                            self.blit_stimuli(stimuli);
+                        }
                         TrialState::Stimulus | TrialState::Response => {
-                            if let Some((s, pos)) = stimulus {
-                                if let Some(cache_idx) = match s {
-                                    StimulusType::Circle { .. } => {
-                                        Some(CacheIndex::CircleStim as usize)
-                                    }
-                                    StimulusType::Rectangle { .. } => {
-                                        Some(CacheIndex::RectStim as usize)
-                                    }
-                                    StimulusType::Arrow { .. } => {
-                                        Some(CacheIndex::ArrowStim as usize)
-                                    }
-                                    other => panic!(
-                                        "unexpected StimType passed to render phase: {:?}",
-                                        other
-                                    ),
-                                } {
-                                    self.blit_cached(cache_idx, pos);
-                                }
-                            }
+                            self.blit_stimuli(stimuli);
                             if *state == TrialState::Response {
                                 self.blit_cached(
                                     CacheIndex::Respond as usize,
-                                    (self.center.0, self.center.1 + 100.0),
+                                    (self.center.0, self.center.1 + self.px(100.0)),
                                 );
                             }
                         }
-                        TrialState::Feedback => {
-                            self.blit_cached(CacheIndex::Feedback as usize, self.center);
-                        }
+                        TrialState::Feedback => self.draw_feedback(),
                         TrialState::Complete => {
-                            // Blank inter-trial interval
+                            // The inter-trial interval, blank unless it shows
+                            // the fixation cross or a stimulus
+                            if self.iti_fixation {
+                                self.blit_cached(CacheIndex::FixationCross as usize, self.center);
+                            }
+                            self.blit_stimuli(stimuli);
                         }
-                    }
-                    if let Some((current, total)) = progress {
-                        if let Some(intern_id) = self
-                            .progress_text_interns
-                            .get(total)
-                            .and_then(|row| row.get(current))
-                        {
-                            let pos = (50.0, 30.0); // same fixed offset as before
-                            self.blit_text_by_intern_id(*intern_id, pos);
+                        TrialState::Break => match self.rest_remaining {
+                            Some(seconds) => {
+                                let text = rest_text(&self.catalog, seconds);
+                                let size = self.px(LABEL_SIZE);
+                                self.blit_text(&text, size, self.theme.foreground, self.center);
+                            }
+                            None => {
+                                self.blit_cached(CacheIndex::BreakPrompt as usize, self.center);
+                            }
+                        },
+                        TrialState::Countdown => {
+                            // The countdown is one of the widgets
                         }
                     }
+                    self.draw_widgets();
+                    if let Some((current, total)) = progress
+                        && let Some(&intern_id) =
+                            self.planned_text.progress_from(current, total).first()
+                    {
+                        let pos = (self.px(50.0), self.px(30.0));
+                        self.blit_text_by_intern_id(intern_id, pos);
+                    }
                 }
 
                 if p.is_practice() {
                     self.blit_cached(
                         CacheIndex::PracticeMode as usize,
-                        (self.center.0 - 100.0, 30.0),
+                        (self.center.0 - self.px(100.0), self.px(30.0)),
                     );
                 }
             }
@@ -0,0 +1,35 @@
+//! Row loops over pixel buffers, split across the rayon pool for large spans
+//! when the `parallel` feature is on.
+
+use std::ops::Range;
+
+/// Bytes a span must cover (a 256×256 sprite) before its rows are split
+/// across threads; smaller ones copy faster than the pool hands out work
+#[cfg(feature = "parallel")]
+pub(crate) const PARALLEL_MIN_BYTES: usize = 256 * 256 * 4;
+
+/// Calls `f` with each row index in `rows` and that row's `columns` byte
+/// range of `data`, whose rows are `stride` bytes apart
+pub(crate) fn for_each_row<F>(
+    data: &mut [u8],
+    stride: usize,
+    rows: Range<usize>,
+    columns: Range<usize>,
+    f: F,
+) where
+    F: Fn(usize, &mut [u8]) + Send + Sync,
+{
+    let first = rows.start;
+    let span = &mut data[rows.start * stride..rows.end * stride];
+    #[cfg(feature = "parallel")]
+    if rows.len() * columns.len() >= PARALLEL_MIN_BYTES {
+        use rayon::prelude::*;
+        span.par_chunks_mut(stride)
+            .enumerate()
+            .for_each(|(i, row)| f(first + i, &mut row[columns.clone()]));
+        return;
+    }
+    for (i, row) in span.chunks_mut(stride).enumerate() {
+        f(first + i, &mut row[columns.clone()]);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::font::{FontSet, ShapedGlyph};
+use tiny_skia::{Color, Pixmap, PremultipliedColorU8};
+
+/// Horizontal alignment of lines within a paragraph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextAlign {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+/// How a paragraph is broken into lines and stacked
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextLayout {
+    /// Wrap width in pixels; `None` breaks only at newlines
+    pub max_width: Option<f32>,
+    /// Baseline-to-baseline distance as a multiple of the font size
+    pub line_spacing: f32,
+    pub align: TextAlign,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            max_width: None,
+            line_spacing: 1.4,
+            align: TextAlign::Center,
+        }
+    }
+}
+
+impl TextLayout {
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// Advance width of `text` on one line, in pixels
+pub fn text_width(fonts: &FontSet, size: f32, text: &str) -> f32 {
+    fonts.shape(text, size, (0.0, 0.0)).1
+}
+
+/// Breaks `text` at newlines and, with `max_width`, greedily between words
+/// so no line is wider; a word wider than that gets a line of its own
+pub fn wrap_text(fonts: &FontSet, size: f32, text: &str, max_width: Option<f32>) -> Vec<String> {
+    let mut lines = Vec::new();
+    for hard_line in text.lines() {
+        let Some(max_width) = max_width else {
+            lines.push(hard_line.to_string());
+            continue;
+        };
+        let mut line = String::new();
+        for word in hard_line.split_whitespace() {
+            if !line.is_empty() {
+                let candidate = format!("{} {}", line, word);
+                if text_width(fonts, size, &candidate) <= max_width {
+                    line = candidate;
+                    continue;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Renders a single line into a pixmap cropped to its ink
+pub fn render_text_pixmap(text: &str, font_size: f32, fonts: &FontSet, color: Color) -> Pixmap {
+    let (ascent, _) = fonts.v_metrics(font_size);
+    let (glyphs, _) = fonts.shape(text, font_size, (0.0, ascent));
+
+    // Union pixel bounds from outlined glyphs
+    let mut min_x = f32::INFINITY;
+    let mut min_y = f32::INFINITY;
+    let mut max_x = f32::NEG_INFINITY;
+    let mut max_y = f32::NEG_INFINITY;
+
+    for g in &glyphs {
+        if let Some(out) = fonts.outline(g) {
+            let b = out.px_bounds();
+            min_x = min_x.min(b.min.x);
+            min_y = min_y.min(b.min.y);
+            max_x = max_x.max(b.max.x);
+            max_y = max_y.max(b.max.y);
+        }
+    }
+
+    if min_x == f32::INFINITY {
+        return Pixmap::new(1, 1).expect("pixmap");
+    }
+
+    let w = (max_x.ceil() - min_x.floor()).max(1.0) as u32;
+    let h = (max_y.ceil() - min_y.floor()).max(1.0) as u32;
+    let mut pm = Pixmap::new(w, h).expect("pixmap");
+    draw_glyphs(&mut pm, fonts, &glyphs, color, (-min_x, -min_y));
+    pm
+}
+
+/// Renders a paragraph wrapped and aligned per `layout`. The pixmap spans
+/// whole line boxes, so paragraphs stack at a steady rhythm.
+pub fn render_paragraph_pixmap(
+    text: &str,
+    font_size: f32,
+    fonts: &FontSet,
+    color: Color,
+    layout: &TextLayout,
+) -> Pixmap {
+    let (ascent, descent) = fonts.v_metrics(font_size);
+    let lines = wrap_text(fonts, font_size, text, layout.max_width);
+    let widths: Vec<f32> = lines
+        .iter()
+        .map(|l| text_width(fonts, font_size, l))
+        .collect();
+    let box_width = widths.iter().cloned().fold(0.0f32, f32::max).ceil();
+    let line_height = font_size * layout.line_spacing;
+    let height = ascent - descent + line_height * lines.len().saturating_sub(1) as f32;
+
+    // One pixel of slack on each side for glyphs overhanging their advance
+    let mut pm = Pixmap::new(box_width as u32 + 2, height.ceil() as u32 + 2).expect("pixmap");
+    for (i, (line, width)) in lines.iter().zip(&widths).enumerate() {
+        let x = match layout.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (box_width - width) * 0.5,
+            TextAlign::Right => box_width - width,
+        };
+        let baseline = ascent + i as f32 * line_height;
+        let (glyphs, _) = fonts.shape(line, font_size, (x, baseline));
+        draw_glyphs(&mut pm, fonts, &glyphs, color, (1.0, 1.0));
+    }
+    pm
+}
+
+/// Rasterizes `glyphs`, shifted by `offset`, over `pm` with premultiplied
+/// alpha blending
+fn draw_glyphs(
+    pm: &mut Pixmap,
+    fonts: &FontSet,
+    glyphs: &[ShapedGlyph],
+    color: Color,
+    offset: (f32, f32),
+) {
+    let (w, h) = (pm.width() as i32, pm.height() as i32);
+    let stride = pm.width() as usize;
+    let dst = pm.pixels_mut();
+
+    // Convert desired color to straight u8
+    let cu = [
+        (color.red() * 255.0) as u8,
+        (color.green() * 255.0) as u8,
+        (color.blue() * 255.0) as u8,
+        (color.alpha() * 255.0) as u8,
+    ];
+
+    for g in glyphs {
+        if let Some(out) = fonts.outline(g) {
+            let b = out.px_bounds();
+            out.draw(|x, y, cov| {
+                if cov <= f32::EPSILON {
+                    return;
+                }
+                // Map local outline coords to pixmap coords
+                let fx = x as f32 + b.min.x + offset.0;
+                let fy = y as f32 + b.min.y + offset.1;
+
+                let ix = fx.floor() as i32;
+                let iy = fy.floor() as i32;
+                if ix < 0 || iy < 0 || ix >= w || iy >= h {
+                    return;
+                }
+
+                let i = iy as usize * stride + ix as usize;
+                if i >= dst.len() {
+                    return;
+                }
+
+                // Premultiply source by (coverage * alpha)
+                let a_lin = (cov * cu[3] as f32 / 255.0).clamp(0.0, 1.0);
+                let sr = (cu[0] as f32 * a_lin) as u8;
+                let sg = (cu[1] as f32 * a_lin) as u8;
+                let sb = (cu[2] as f32 * a_lin) as u8;
+                let sa = (a_lin * 255.0) as u8;
+
+                let src = PremultipliedColorU8::from_rgba(sr, sg, sb, sa).unwrap();
+                let bg = dst[i];
+
+                // Porter-Duff over in premultiplied space: out = src + bg * (1 - src.a)
+                let inv = 1.0 - (sa as f32 / 255.0);
+                let r = src.red().saturating_add((bg.red() as f32 * inv) as u8);
+                let g = src.green().saturating_add((bg.green() as f32 * inv) as u8);
+                let b = src.blue().saturating_add((bg.blue() as f32 * inv) as u8);
+                let a = src.alpha().saturating_add((bg.alpha() as f32 * inv) as u8);
+
+                dst[i] = PremultipliedColorU8::from_rgba(r, g, b, a).unwrap();
+            });
+        }
+    }
+}
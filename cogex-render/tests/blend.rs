@@ -0,0 +1,49 @@
+use cogex_render::blend::{source_over, source_over_scalar};
+
+/// Premultiplied pixels from a fixed linear congruential sequence
+fn pixels(count: usize, seed: u32) -> Vec<u8> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (state >> 24) as u8
+    };
+    (0..count)
+        .flat_map(|_| {
+            let a = next() as u32;
+            let mut channel = || (next() as u32 * a / 255) as u8;
+            [channel(), channel(), channel(), a as u8]
+        })
+        .collect()
+}
+
+#[test]
+fn vector_blending_matches_the_scalar_reference() {
+    // Lengths off the vector width leave a tail for the scalar path
+    for count in [0, 1, 3, 4, 15, 16, 17, 63, 1000] {
+        let src = pixels(count, 1);
+        let mut expected = pixels(count, 2);
+        let mut blended = expected.clone();
+        source_over_scalar(&mut expected, &src);
+        source_over(&mut blended, &src);
+        assert_eq!(blended, expected, "{} pixels", count);
+    }
+}
+
+#[test]
+fn opaque_sources_replace_and_transparent_ones_keep_the_canvas() {
+    let canvas = pixels(37, 3);
+    let opaque: Vec<u8> = canvas
+        .iter()
+        .map(|&c| c / 2)
+        .collect::<Vec<_>>()
+        .chunks_exact(4)
+        .flat_map(|p| [p[0], p[1], p[2], 255])
+        .collect();
+    let mut dst = canvas.clone();
+    source_over(&mut dst, &opaque);
+    assert_eq!(dst, opaque);
+
+    let mut dst = canvas.clone();
+    source_over(&mut dst, &vec![0; canvas.len()]);
+    assert_eq!(dst, canvas);
+}
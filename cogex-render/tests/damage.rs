@@ -0,0 +1,46 @@
+use cogex_render::damage::{TileGrid, TILE_SIZE};
+use tiny_skia::Rect;
+
+fn drained(grid: &mut TileGrid) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    grid.drain_into(&mut rects);
+    rects
+}
+
+#[test]
+fn overlapping_damage_merges_into_disjoint_tile_runs() {
+    let tile = TILE_SIZE as f32;
+    let mut grid = TileGrid::new(1000, 600);
+    // Stacked text lines over the same tiles, and a patch elsewhere
+    for line in 0..5 {
+        let y = 10.0 + line as f32 * 20.0;
+        grid.mark(&Rect::from_xywh(20.0, y, 200.0, 24.0).unwrap());
+    }
+    grid.mark(&Rect::from_xywh(900.0, 500.0, 10.0, 10.0).unwrap());
+
+    let rects = drained(&mut grid);
+    assert_eq!(
+        rects,
+        vec![
+            Rect::from_ltrb(0.0, 0.0, 4.0 * tile, 2.0 * tile).unwrap(),
+            Rect::from_ltrb(14.0 * tile, 7.0 * tile, 15.0 * tile, 8.0 * tile).unwrap(),
+        ]
+    );
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            assert!(a.intersect(b).is_none_or(|r| r.width() * r.height() == 0.0));
+        }
+    }
+    assert!(drained(&mut grid).is_empty());
+}
+
+#[test]
+fn damage_off_the_canvas_is_clipped() {
+    let mut grid = TileGrid::new(100, 100);
+    grid.mark(&Rect::from_xywh(-50.0, 90.0, 80.0, 40.0).unwrap());
+    grid.mark(&Rect::from_xywh(200.0, 200.0, 10.0, 10.0).unwrap());
+    assert_eq!(
+        drained(&mut grid),
+        vec![Rect::from_ltrb(0.0, 64.0, 64.0, 100.0).unwrap()]
+    );
+}
@@ -0,0 +1,76 @@
+use cogex_render::FontSet;
+use std::path::Path;
+
+const SIZE: f32 = 32.0;
+
+fn advance(fonts: &FontSet, text: &str) -> f32 {
+    fonts.shape(text, SIZE, (0.0, 0.0)).1
+}
+
+#[test]
+fn shaping_advances_the_pen_along_the_line() {
+    let fonts = FontSet::bundled();
+    assert_eq!(fonts.len(), 1);
+    let (glyphs, width) = fonts.shape("Hello", SIZE, (10.0, 40.0));
+    assert_eq!(glyphs.len(), 5);
+    assert!(width > 0.0);
+    assert!(advance(&fonts, "Hello world") > width);
+    assert_eq!(glyphs[0].glyph.position.x, 10.0);
+    assert!(glyphs.iter().all(|g| g.glyph.position.y == 40.0));
+    assert!(glyphs
+        .windows(2)
+        .all(|w| w[0].glyph.position.x < w[1].glyph.position.x));
+    assert!(glyphs.iter().all(|g| fonts.outline(g).is_some()));
+}
+
+#[test]
+fn combining_marks_take_no_advance_of_their_own() {
+    let fonts = FontSet::bundled();
+    assert!((advance(&fonts, "e\u{301}") - advance(&fonts, "e")).abs() < 0.01);
+}
+
+#[test]
+fn arabic_letters_take_their_joined_forms() {
+    let fonts = FontSet::bundled();
+    let (isolated, _) = fonts.shape("\u{628}", SIZE, (0.0, 0.0));
+    let (joined, _) = fonts.shape("\u{628}\u{628}", SIZE, (0.0, 0.0));
+    assert_eq!(joined.len(), 2);
+    assert!(joined.iter().all(|g| g.glyph.id != isolated[0].glyph.id));
+}
+
+#[test]
+fn fallbacks_are_only_used_for_what_the_primary_lacks() {
+    let fonts = FontSet::bundled()
+        .with_fallback(include_bytes!("../../assets/DejaVuSans.ttf"))
+        .unwrap();
+    assert_eq!(fonts.len(), 2);
+    let (glyphs, _) = fonts.shape("Hello", SIZE, (0.0, 0.0));
+    assert!(glyphs.iter().all(|g| g.face == 0));
+
+    assert!(FontSet::bundled()
+        .load_fallback(Path::new("no/such/font.ttf"))
+        .is_err());
+    assert!(FontSet::bundled()
+        .load_fallback(Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/Cargo.toml"
+        )))
+        .is_err());
+}
+
+#[test]
+fn fallbacks_read_from_disk_outlive_the_set_they_were_added_to() {
+    let fonts = FontSet::bundled()
+        .load_fallback(Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/DejaVuSans.ttf"
+        )))
+        .unwrap();
+    let copy = fonts.clone();
+    drop(fonts);
+    assert_eq!(copy.len(), 2);
+    let (glyphs, width) = copy.shape("Hello", SIZE, (0.0, 0.0));
+    assert_eq!(glyphs.len(), 5);
+    assert!(width > 0.0);
+    assert!(glyphs.iter().all(|g| copy.outline(g).is_some()));
+}
@@ -0,0 +1,665 @@
+use cogex_core::{
+    DebriefSummary, DisplayGeometry, FeedbackOutcome, StandardPhase, StimulusType, Theme,
+    TrialFeedback, TrialState,
+};
+use cogex_render::{Catalog, Corner, PhotodiodeMarker, SkiaRenderer};
+use cogex_timing::HighPrecisionTimer;
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+fn pixel(frame: &[u8], (x, y): (u32, u32)) -> [u8; 4] {
+    let i = ((y * WIDTH + x) * 4) as usize;
+    frame[i..i + 4].try_into().unwrap()
+}
+
+#[test]
+fn a_moved_stimulus_leaves_no_trace_in_the_frame() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut draw_at = |frame: &mut Vec<u8>, pos: (f32, f32)| {
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&circle, pos)],
+                Some(&TrialState::Stimulus),
+                None,
+                frame,
+                &mut timer,
+            )
+            .unwrap();
+    };
+
+    draw_at(&mut frame, (80.0, 120.0));
+    assert_eq!(pixel(&frame, (80, 120)), [255, 0, 0, 255]);
+    draw_at(&mut frame, (240.0, 120.0));
+    assert_eq!(pixel(&frame, (80, 120)), [0, 0, 0, 255]);
+    assert_eq!(pixel(&frame, (240, 120)), [255, 0, 0, 255]);
+    // Held still, it is cleared and redrawn in place
+    draw_at(&mut frame, (240.0, 120.0));
+    assert_eq!(pixel(&frame, (240, 120)), [255, 0, 0, 255]);
+}
+
+#[test]
+fn every_stimulus_of_a_display_is_drawn() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let circle = StimulusType::Circle {
+        radius: 30.0,
+        color: [255, 0, 0, 255],
+    };
+    let display = [(60.0, 60.0), (160.0, 180.0), (260.0, 60.0)];
+    let stimuli: Vec<_> = display.iter().map(|&pos| (&circle, pos)).collect();
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &stimuli,
+            Some(&TrialState::Stimulus),
+            None,
+            &mut frame,
+            &mut HighPrecisionTimer::new(),
+        )
+        .unwrap();
+
+    for (x, y) in display {
+        assert_eq!(pixel(&frame, (x as u32, y as u32)), [255, 0, 0, 255]);
+    }
+    assert_eq!(pixel(&frame, (160, 60)), [0, 0, 0, 255]);
+}
+
+/// Red-weighted mean x of a row, at pixel centres
+fn centroid(frame: &[u8], y: u32) -> f32 {
+    let (sum, weight) = (0..WIDTH).fold((0.0, 0.0), |(sum, weight), x| {
+        let red = pixel(frame, (x, y))[0] as f32;
+        (sum + red * (x as f32 + 0.5), weight + red)
+    });
+    sum / weight
+}
+
+#[test]
+fn subpixel_placement_shifts_a_stimulus_by_a_fraction_of_a_pixel() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut centroid_at = |subpixel: bool, x: f32| {
+        renderer.set_subpixel(subpixel);
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&circle, (x, 120.0))],
+                Some(&TrialState::Stimulus),
+                None,
+                &mut frame,
+                &mut timer,
+            )
+            .unwrap();
+        centroid(&frame, 120)
+    };
+
+    let snapped = centroid_at(false, 160.0);
+    assert_eq!(centroid_at(false, 160.25), snapped);
+    let exact = centroid_at(true, 160.0);
+    let shifted = centroid_at(true, 160.25);
+    assert!(
+        (shifted - exact - 0.25).abs() < 0.02,
+        "moved {} px",
+        shifted - exact
+    );
+}
+
+#[test]
+fn anti_aliased_shapes_blend_their_edges_into_the_background() {
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let reds = |anti_alias: bool| {
+        let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10).with_anti_alias(anti_alias);
+        let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&circle, (160.0, 120.0))],
+                Some(&TrialState::Stimulus),
+                None,
+                &mut frame,
+                &mut HighPrecisionTimer::new(),
+            )
+            .unwrap();
+        // Along a diagonal, where the edge cuts pixels at every angle
+        let mut reds: Vec<u8> = (0..60)
+            .map(|i| pixel(&frame, (160 + i, 120 + i)))
+            .inspect(|p| assert_eq!(p[1..], [0, 0, 255]))
+            .map(|p| p[0])
+            .collect();
+        reds.dedup();
+        reds
+    };
+
+    assert_eq!(reds(false), [255, 0]);
+    let smooth = reds(true);
+    assert!(smooth.len() > 2, "{:?}", smooth);
+    assert!(smooth.windows(2).all(|pair| pair[0] >= pair[1]));
+}
+
+#[test]
+fn a_theme_change_recolours_the_background_and_cached_shapes() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut draw = |renderer: &mut SkiaRenderer, frame: &mut Vec<u8>| {
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&circle, (160.0, 120.0))],
+                Some(&TrialState::Stimulus),
+                None,
+                frame,
+                &mut timer,
+            )
+            .unwrap();
+    };
+
+    draw(&mut renderer, &mut frame);
+    assert_eq!(pixel(&frame, (5, 5)), [0, 0, 0, 255]);
+    renderer.set_theme(Theme {
+        // Alpha is ignored, the screen stays opaque
+        background: [128, 128, 128, 0],
+        circle: [255, 255, 0, 255],
+        ..Theme::default()
+    });
+    draw(&mut renderer, &mut frame);
+    assert_eq!(pixel(&frame, (5, 5)), [128, 128, 128, 255]);
+    assert_eq!(pixel(&frame, (160, 120)), [255, 255, 0, 255]);
+}
+
+#[test]
+fn stimuli_are_scaled_from_logical_pixels() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10).with_scale_factor(2.0);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &[(&circle, (80.0, 60.0))],
+            Some(&TrialState::Stimulus),
+            None,
+            &mut frame,
+            &mut HighPrecisionTimer::new(),
+        )
+        .unwrap();
+
+    // A 50 px radius at (80, 60) covers 100 physical pixels around (160, 120)
+    assert_eq!(pixel(&frame, (160, 120)), [255, 0, 0, 255]);
+    assert_eq!(pixel(&frame, (160 + 95, 120)), [255, 0, 0, 255]);
+    assert_eq!(pixel(&frame, (160 + 105, 120)), [0, 0, 0, 255]);
+}
+
+#[test]
+fn a_resized_renderer_repaints_the_whole_new_frame() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut timer = HighPrecisionTimer::new();
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &[(&circle, (80.0, 120.0))],
+            Some(&TrialState::Stimulus),
+            Some((1, 10)),
+            &mut frame,
+            &mut timer,
+        )
+        .unwrap();
+
+    // A minimised window reports a zero size, which is ignored
+    renderer.resize(0, 0);
+    let (width, height) = (WIDTH * 2, HEIGHT * 2);
+    renderer.resize(width, height);
+    let mut frame = vec![7u8; (width * height * 4) as usize];
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &[(&circle, (480.0, 240.0))],
+            Some(&TrialState::Stimulus),
+            Some((2, 10)),
+            &mut frame,
+            &mut timer,
+        )
+        .unwrap();
+
+    let at = |(x, y): (u32, u32)| -> [u8; 4] {
+        let i = ((y * width + x) * 4) as usize;
+        frame[i..i + 4].try_into().unwrap()
+    };
+    assert_eq!(at((480, 240)), [255, 0, 0, 255]);
+    assert_eq!(at((80, 120)), [0, 0, 0, 255]);
+    assert_eq!(at((width - 1, height - 1)), [0, 0, 0, 255]);
+    // The trial counter is still drawn from the interned text
+    let counter = (0..60).flat_map(|y| (0..120).map(move |x| (x, y)));
+    assert!(counter.into_iter().any(|p| at(p) != [0, 0, 0, 255]));
+}
+
+#[test]
+fn snapshots_save_each_new_trial_state_once() {
+    let dir = std::env::temp_dir().join(format!("cogex_snapshots_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    renderer.set_snapshot_dir(Some(dir.clone())).unwrap();
+    for state in [
+        TrialState::Fixation,
+        TrialState::Stimulus,
+        TrialState::Stimulus,
+        TrialState::Response,
+    ] {
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&circle, (160.0, 120.0))],
+                Some(&state),
+                None,
+                &mut frame,
+                &mut timer,
+            )
+            .unwrap();
+    }
+
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "0000_practice_fixation.png",
+            "0001_practice_stimulus.png",
+            "0002_practice_response.png",
+        ]
+    );
+    let stimulus = tiny_skia::Pixmap::load_png(dir.join(&names[1])).unwrap();
+    assert_eq!(stimulus.pixel(160, 120).unwrap().red(), 255);
+
+    // An explicit snapshot is the frame as last drawn
+    let path = dir.join("explicit.png");
+    renderer.snapshot(&path).unwrap();
+    let response = tiny_skia::Pixmap::load_png(&path).unwrap();
+    assert_eq!(response.data(), &frame[..]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn text_stimuli_take_words_built_at_runtime() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let word: String = ["HO", "USE"].concat();
+    let text = StimulusType::Text {
+        content: word.as_str().into(),
+        size: 64.0,
+        color: [255, 0, 0, 255],
+    };
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &[(&text, (160.0, 120.0))],
+            Some(&TrialState::Stimulus),
+            None,
+            &mut frame,
+            &mut timer,
+        )
+        .unwrap();
+
+    let red = (0..WIDTH)
+        .filter(|&x| pixel(&frame, (x, 120))[0] > 128)
+        .count();
+    assert!(red > 0);
+}
+
+#[test]
+fn noise_masks_of_one_size_show_the_same_black_and_white_checks() {
+    let mask = StimulusType::NoiseMask {
+        width: 100.0,
+        height: 60.0,
+        grain: 10.0,
+    };
+    let draw = || {
+        let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+        let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&mask, (160.0, 120.0))],
+                Some(&TrialState::Stimulus),
+                None,
+                &mut frame,
+                &mut HighPrecisionTimer::new(),
+            )
+            .unwrap();
+        frame
+    };
+    let frame = draw();
+    assert_eq!(frame, draw());
+
+    // The mask spans x 110..210 and y 90..150 in checks of 10 pixels
+    let mut colours = std::collections::HashSet::new();
+    for y in 90..150 {
+        for x in 110..210 {
+            let colour = pixel(&frame, (x, y));
+            assert!(colour == [0, 0, 0, 255] || colour == [255, 255, 255, 255]);
+            let corner = (110 + (x - 110) / 10 * 10, 90 + (y - 90) / 10 * 10);
+            assert_eq!(colour, pixel(&frame, corner));
+            colours.insert(colour);
+        }
+    }
+    assert_eq!(colours.len(), 2);
+}
+
+#[test]
+fn progress_bar_widget_fills_its_share_over_a_dim_track() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let bar = StimulusType::ProgressBar {
+        width: 200.0,
+        height: 10.0,
+        fraction: 0.25,
+        color: [0, 255, 0, 255],
+    };
+    let mut draw = |renderer: &mut SkiaRenderer, frame: &mut Vec<u8>| {
+        renderer
+            .render_frame(
+                &StandardPhase::Experiment,
+                &[],
+                Some(&TrialState::Fixation),
+                None,
+                frame,
+                &mut timer,
+            )
+            .unwrap();
+    };
+
+    // The bar spans x 60..260, filled up to 110
+    renderer.set_widgets(vec![(bar, (160.0, 220.0))]);
+    draw(&mut renderer, &mut frame);
+    assert_eq!(pixel(&frame, (80, 220)), [0, 255, 0, 255]);
+    let [r, g, b, _] = pixel(&frame, (200, 220));
+    assert_eq!((r, b), (0, 0));
+    assert!((60..=66).contains(&g), "track green {}", g);
+    assert_eq!(pixel(&frame, (270, 220)), [0, 0, 0, 255]);
+
+    renderer.set_widgets(Vec::new());
+    draw(&mut renderer, &mut frame);
+    assert_eq!(pixel(&frame, (80, 220)), [0, 0, 0, 255]);
+}
+
+#[test]
+fn inter_trial_interval_keeps_the_fixation_cross_when_asked() {
+    let draw = |fixation: bool| {
+        let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10).with_iti_fixation(fixation);
+        let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+        renderer
+            .render_frame(
+                &StandardPhase::Experiment,
+                &[],
+                Some(&TrialState::Complete),
+                None,
+                &mut frame,
+                &mut HighPrecisionTimer::new(),
+            )
+            .unwrap();
+        pixel(&frame, (WIDTH / 2, HEIGHT / 2))
+    };
+    assert_eq!(draw(false), [0, 0, 0, 255]);
+    assert_eq!(draw(true), [255, 255, 255, 255]);
+}
+
+#[test]
+fn the_photodiode_patch_flips_on_each_stimulus_onset() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10).with_photodiode(PhotodiodeMarker {
+        corner: Corner::TopRight,
+        size: 20,
+    });
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let circle = StimulusType::Circle {
+        radius: 10.0,
+        color: [255, 0, 0, 255],
+    };
+    let mut patch = |state| {
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&circle, (160.0, 120.0))],
+                Some(&state),
+                None,
+                &mut frame,
+                &mut timer,
+            )
+            .unwrap();
+        assert_eq!(pixel(&frame, (WIDTH - 21, 10)), [0, 0, 0, 255]);
+        pixel(&frame, (WIDTH - 10, 10))
+    };
+    let (white, black) = ([255, 255, 255, 255], [0, 0, 0, 255]);
+
+    assert_eq!(patch(TrialState::Fixation), black);
+    assert_eq!(patch(TrialState::Stimulus), white);
+    // Held through the stimulus and its response window
+    assert_eq!(patch(TrialState::Stimulus), white);
+    assert_eq!(patch(TrialState::Response), white);
+    assert_eq!(patch(TrialState::Feedback), white);
+    assert_eq!(patch(TrialState::Fixation), white);
+    assert_eq!(patch(TrialState::Stimulus), black);
+}
+
+#[test]
+fn stimuli_are_placed_and_sized_in_degrees_with_a_display_geometry() {
+    // 10 px per cm viewed from 57 cm, just under 10 px per degree
+    let mut renderer =
+        SkiaRenderer::new(WIDTH, HEIGHT, 10).with_display(DisplayGeometry::new(32.0, WIDTH, 57.0));
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let circle = StimulusType::Circle {
+        radius: 1.0,
+        color: [255, 0, 0, 255],
+    };
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &[(&circle, (5.0, 0.0))],
+            Some(&TrialState::Stimulus),
+            None,
+            &mut frame,
+            &mut HighPrecisionTimer::new(),
+        )
+        .unwrap();
+
+    // 5 degrees right of centre is 57 cm × tan 5° ≈ 50 px
+    assert_eq!(pixel(&frame, (210, 120)), [255, 0, 0, 255]);
+    assert_eq!(pixel(&frame, (202, 120)), [255, 0, 0, 255]);
+    assert_ne!(pixel(&frame, (198, 120)), [255, 0, 0, 255]);
+    assert_ne!(pixel(&frame, (222, 120)), [255, 0, 0, 255]);
+}
+
+#[test]
+fn text_stimuli_are_drawn_in_their_own_colour() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let mut inks = Vec::new();
+    for color in [[220, 30, 30, 255], [30, 180, 30, 255]] {
+        let word = StimulusType::Text {
+            content: "RED".into(),
+            size: 64.0,
+            color,
+        };
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&word, (160.0, 120.0))],
+                Some(&TrialState::Stimulus),
+                None,
+                &mut frame,
+                &mut timer,
+            )
+            .unwrap();
+        inks.push(frame.chunks_exact(4).any(|px| *px == color));
+    }
+    assert_eq!(inks, [true, true]);
+}
+
+#[test]
+fn dynamic_stimuli_advance_with_the_time_since_onset() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut timer = HighPrecisionTimer::new();
+    let white = [255, 255, 255, 255];
+    let bar = StimulusType::RotatingBar {
+        width: 100.0,
+        height: 10.0,
+        speed: 360.0,
+        color: white,
+    };
+    let mut draw_at = |frame: &mut Vec<u8>, elapsed_ms: u64| {
+        renderer.set_stimulus_elapsed(Some(std::time::Duration::from_millis(elapsed_ms)));
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[(&bar, (160.0, 120.0))],
+                Some(&TrialState::Stimulus),
+                None,
+                frame,
+                &mut timer,
+            )
+            .unwrap();
+    };
+
+    draw_at(&mut frame, 0);
+    assert_eq!(pixel(&frame, (200, 120)), white);
+    assert_ne!(pixel(&frame, (160, 80)), white);
+    // A quarter turn later the bar stands upright
+    draw_at(&mut frame, 250);
+    assert_ne!(pixel(&frame, (200, 120)), white);
+    assert_eq!(pixel(&frame, (160, 80)), white);
+}
+
+#[test]
+fn the_feedback_screen_shows_the_verdict_and_optionally_the_rt() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut timer = HighPrecisionTimer::new();
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    // Whole frames are compared with assert! so failures do not print them
+    let mut draw = |feedback: TrialFeedback, show_rt: bool| {
+        renderer.set_feedback(Some(feedback));
+        renderer.set_feedback_rt(show_rt);
+        renderer
+            .render_frame(
+                &StandardPhase::Practice,
+                &[],
+                Some(&TrialState::Feedback),
+                None,
+                &mut frame,
+                &mut timer,
+            )
+            .unwrap();
+        frame.clone()
+    };
+    let verdict = |outcome| TrialFeedback {
+        outcome,
+        reaction_time_ms: Some(432),
+    };
+
+    let correct = draw(verdict(FeedbackOutcome::Correct), false);
+    let wrong = draw(verdict(FeedbackOutcome::Wrong), false);
+    let too_slow = draw(verdict(FeedbackOutcome::TooSlow), false);
+    assert!(correct != wrong && wrong != too_slow && correct != too_slow);
+    let with_rt = draw(verdict(FeedbackOutcome::Correct), true);
+    assert!(with_rt != correct);
+    let without_rt = TrialFeedback {
+        reaction_time_ms: None,
+        ..verdict(FeedbackOutcome::Correct)
+    };
+    assert!(draw(without_rt, true) == correct);
+}
+
+#[test]
+fn the_welcome_screen_is_drawn_in_the_catalogs_language() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut timer = HighPrecisionTimer::new();
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let mut draw = |catalog: Catalog| {
+        renderer.set_catalog(catalog);
+        renderer
+            .render_frame(
+                &StandardPhase::Welcome,
+                &[],
+                None,
+                None,
+                &mut frame,
+                &mut timer,
+            )
+            .unwrap();
+        frame.clone()
+    };
+
+    let english = draw(Catalog::english());
+    let german = draw(Catalog::builtin("de").unwrap());
+    assert!(english != german);
+    assert!(draw(Catalog::english()) == english);
+}
+
+#[test]
+fn the_debrief_screen_shows_the_summary_in_the_catalogs_language() {
+    let mut renderer = SkiaRenderer::new(WIDTH, HEIGHT, 10);
+    let mut timer = HighPrecisionTimer::new();
+    let mut frame = vec![0u8; (WIDTH * HEIGHT * 4) as usize];
+    let summary = DebriefSummary {
+        trials: 10,
+        correct: 8,
+        mean_rt_ms: Some(412.0),
+        median_rt_ms: Some(398.0),
+        dropped_frames: 0,
+    };
+    let mut draw = |summary: &DebriefSummary, details: &[String]| {
+        renderer
+            .render_debrief(summary, details, &mut frame, &mut timer)
+            .unwrap();
+        frame.clone()
+    };
+
+    let english = draw(&summary, &[]);
+    assert!(draw(&DebriefSummary::default(), &[]) != english);
+    assert!(draw(&summary, &["d' = 1.2".to_string()]) != english);
+    renderer.set_catalog(Catalog::builtin("hu").unwrap());
+    let mut draw = |summary: &DebriefSummary| {
+        renderer
+            .render_debrief(summary, &[], &mut frame, &mut timer)
+            .unwrap();
+        frame.clone()
+    };
+    assert!(draw(&summary) != english);
+}
@@ -0,0 +1,69 @@
+use cogex_core::StimulusType;
+use cogex_render::render::render_gabor_pixmap;
+
+fn gabor(orientation: f32, contrast: f32) -> StimulusType {
+    StimulusType::Gabor {
+        spatial_freq: 0.05,
+        orientation,
+        contrast,
+        sigma: 10.0,
+        phase: 0.0,
+    }
+}
+
+/// Luminance the patch shows over a mid-gray background, 0 to 255
+fn shown(stimulus: &StimulusType, x: u32, y: u32) -> f32 {
+    let p = render_gabor_pixmap(stimulus).pixel(x, y).unwrap();
+    p.red() as f32 + 127.5 * (1.0 - p.alpha() as f32 / 255.0)
+}
+
+#[test]
+fn the_patch_spans_six_sigma_with_the_envelope_fading_out() {
+    let pm = render_gabor_pixmap(&gabor(0.0, 1.0));
+    assert_eq!((pm.width(), pm.height()), (60, 60));
+    assert!(pm.pixel(30, 30).unwrap().alpha() > 250);
+    assert!(pm.pixel(0, 0).unwrap().alpha() < 3);
+    assert!(pm.pixels().iter().all(|p| p.red() <= p.alpha()));
+}
+
+#[test]
+fn over_mid_gray_the_blend_shows_the_gabor_function() {
+    for orientation in [0.0f32, 30.0, 90.0] {
+        let stimulus = gabor(orientation, 0.8);
+        let (sin_t, cos_t) = orientation.to_radians().sin_cos();
+        for (x, y) in [(30, 30), (35, 30), (40, 30), (30, 40), (22, 37), (45, 18)] {
+            let (dx, dy) = (x as f32 + 0.5 - 30.0, y as f32 + 0.5 - 30.0);
+            let envelope = (-(dx * dx + dy * dy) / 200.0).exp();
+            let carrier = (std::f32::consts::TAU * 0.05 * (dx * cos_t + dy * sin_t)).cos();
+            let expected = 127.5 * (1.0 + 0.8 * carrier * envelope);
+            let actual = shown(&stimulus, x, y);
+            assert!(
+                (actual - expected).abs() <= 1.5,
+                "{} deg at ({}, {}): {} != {}",
+                orientation,
+                x,
+                y,
+                actual,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn zero_contrast_is_uniform_mid_gray() {
+    let flat = gabor(45.0, 0.0);
+    for (x, y) in [(30, 30), (40, 30), (20, 45), (5, 5)] {
+        assert!((shown(&flat, x, y) - 127.5).abs() <= 1.0, "({}, {})", x, y);
+    }
+}
+
+#[test]
+fn other_stimuli_get_an_empty_pixmap() {
+    let circle = StimulusType::Circle {
+        radius: 10.0,
+        color: [255, 255, 255, 255],
+    };
+    let pm = render_gabor_pixmap(&circle);
+    assert_eq!((pm.width(), pm.height()), (1, 1));
+}
@@ -0,0 +1,72 @@
+use cogex_core::{StandardPhase, StimulusType, TrialState};
+use cogex_render::{GammaLut, SkiaRenderer};
+use cogex_timing::HighPrecisionTimer;
+
+fn calibration_file(rows: usize) -> String {
+    let mut text = String::from("# level r g b\n\n");
+    for i in 0..rows {
+        let v = i as f32 / 255.0;
+        text.push_str(&format!("{v}, {}, {}\n", v * v, 1.0 - v));
+    }
+    text
+}
+
+#[test]
+fn a_power_law_lut_inverts_the_display_gamma() {
+    let lut = GammaLut::from_gamma(2.2);
+    assert_eq!((lut.r[0], lut.r[255]), (0, 255));
+    // Mid-gray is raised so the display's 2.2 power brings it back to half luminance
+    let half = (lut.r[128] as f32 / 255.0).powf(2.2);
+    assert!((half - 128.0 / 255.0).abs() < 0.01, "{}", half);
+    assert!(lut.r.windows(2).all(|w| w[0] <= w[1]));
+
+    let lut = GammaLut::from_channel_gammas(1.0, 2.0, 2.2);
+    assert_eq!(lut.r, GammaLut::identity().r);
+    assert!(lut.g[64] < lut.b[64]);
+}
+
+#[test]
+fn calibration_files_give_each_channel_its_own_table() {
+    let lut = GammaLut::parse(&calibration_file(256)).unwrap();
+    assert_eq!(lut.r, GammaLut::identity().r);
+    assert_eq!((lut.g[0], lut.g[128], lut.g[255]), (0, 64, 255));
+    assert_eq!((lut.b[0], lut.b[255]), (255, 0));
+
+    assert!(GammaLut::parse(&calibration_file(255)).is_err());
+    assert!(GammaLut::parse(&calibration_file(257)).is_err());
+    assert!(GammaLut::parse(&calibration_file(256).replace("1, 1, 0", "1, 1")).is_err());
+}
+
+#[test]
+fn the_lut_maps_colour_but_not_alpha() {
+    let lut = GammaLut::parse(&calibration_file(256)).unwrap();
+    let mut pixels = [128, 128, 128, 7, 255, 0, 10, 255];
+    lut.apply(&mut pixels);
+    assert_eq!(pixels, [128, 64, 127, 7, 255, 0, 245, 255]);
+}
+
+#[test]
+fn frames_are_gamma_corrected_on_the_way_to_the_frame_buffer() {
+    let (width, height) = (64, 64);
+    let lut = GammaLut::parse(&calibration_file(256)).unwrap();
+    let mut renderer = SkiaRenderer::new(width, height, 10).with_gamma(lut);
+    let mut frame = vec![0u8; (width * height * 4) as usize];
+    let square = StimulusType::Rectangle {
+        width: 40.0,
+        height: 40.0,
+        color: [0, 255, 0, 255],
+    };
+    renderer
+        .render_frame(
+            &StandardPhase::Practice,
+            &[(&square, (32.0, 32.0))],
+            Some(&TrialState::Stimulus),
+            None,
+            &mut frame,
+            &mut HighPrecisionTimer::new(),
+        )
+        .unwrap();
+    // The blue table inverts, lighting up the green square's empty channel
+    let i = ((16 * width + 16) * 4) as usize;
+    assert_eq!(frame[i..i + 4], [0, 255, 255, 255]);
+}
@@ -0,0 +1,70 @@
+use cogex_render::Catalog;
+
+#[test]
+fn bundled_languages_translate_and_fall_back_to_english() {
+    let english = Catalog::english();
+    assert_eq!(english.language(), "en");
+    assert_eq!(english.get("welcome"), "WELCOME");
+
+    let german = Catalog::builtin("de").unwrap();
+    assert_eq!(german.get("welcome"), "WILLKOMMEN");
+    assert_eq!(german.get("feedback-correct"), "RICHTIG");
+    assert!(Catalog::builtin("xx").is_none());
+
+    let partial = Catalog::parse("fr", "welcome = BIENVENUE\n").unwrap();
+    assert_eq!(partial.get("welcome"), "BIENVENUE");
+    assert_eq!(partial.get("feedback-correct"), "CORRECT");
+    assert_eq!(partial.get("no-such-message"), "no-such-message");
+}
+
+#[test]
+fn placeables_are_filled_from_the_arguments() {
+    let english = Catalog::english();
+    assert_eq!(
+        english.format("trial-progress", &[("current", &3), ("total", &40)]),
+        "Trial: 3/40"
+    );
+    assert_eq!(
+        english.format("response-time", &[("ms", &432)]),
+        "Response time: 432ms"
+    );
+    // Placeables without an argument are left as written
+    assert_eq!(
+        english.format("response-time", &[]),
+        "Response time: { $ms }ms"
+    );
+}
+
+#[test]
+fn catalogs_parse_comments_and_continuation_lines() {
+    let catalog = Catalog::parse(
+        "en",
+        "# Instructions\nintro = First line\n    second line\n\nwelcome = HELLO\n",
+    )
+    .unwrap();
+    assert_eq!(catalog.get("intro"), "First line\nsecond line");
+    assert_eq!(catalog.get("welcome"), "HELLO");
+
+    assert!(Catalog::parse("en", "  orphan continuation\n").is_err());
+    assert!(Catalog::parse("en", "no equals sign\n").is_err());
+}
+
+#[test]
+fn languages_resolve_from_codes_or_catalog_files() {
+    assert_eq!(Catalog::resolve("hu").unwrap().language(), "hu");
+    assert!(Catalog::resolve("xx").is_err());
+
+    let path = std::env::temp_dir().join(format!("cogex-locale-{}.ftl", std::process::id()));
+    std::fs::write(&path, "welcome = BIENVENUE\n").unwrap();
+    let catalog = Catalog::resolve(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        catalog.language(),
+        path.file_stem().unwrap().to_str().unwrap()
+    );
+    assert_eq!(catalog.get("welcome"), "BIENVENUE");
+
+    let overridden =
+        Catalog::english().with_messages([("feedback-correct".to_string(), "YES".to_string())]);
+    assert_eq!(overridden.get("feedback-correct"), "YES");
+}
@@ -0,0 +1,49 @@
+use cogex_render::lru::{CacheStats, PixmapLru};
+use tiny_skia::Pixmap;
+
+/// A 16×16 pixmap, 1 KiB of pixel data
+fn tile() -> Pixmap {
+    Pixmap::new(16, 16).unwrap()
+}
+
+#[test]
+fn the_least_recently_used_entry_is_evicted_over_budget() {
+    let mut cache = PixmapLru::new(2 * 1024);
+    cache.insert("a", tile());
+    cache.next_frame();
+    cache.insert("b", tile());
+    cache.next_frame();
+    assert!(cache.get(&"a").is_some());
+    cache.next_frame();
+    cache.insert("c", tile());
+
+    assert!(cache.contains(&"a"));
+    assert!(!cache.contains(&"b"));
+    assert!(cache.contains(&"c"));
+    assert_eq!(cache.bytes(), 2 * 1024);
+    assert_eq!(
+        cache.take_stats(),
+        CacheStats {
+            hits: 1,
+            misses: 0,
+            evictions: 1,
+        }
+    );
+}
+
+#[test]
+fn entries_looked_up_this_frame_outlast_the_budget() {
+    let mut cache = PixmapLru::new(1024);
+    for key in 0..3 {
+        cache.insert(key, tile());
+    }
+    assert_eq!(cache.len(), 3);
+    assert_eq!(cache[&0].width(), 16);
+
+    // The next frame's first insert drops the rest
+    cache.next_frame();
+    assert!(cache.get(&5).is_none());
+    cache.insert(5, tile());
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.take_stats().misses, 1);
+}
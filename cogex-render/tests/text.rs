@@ -0,0 +1,79 @@
+use cogex_render::text::{render_paragraph_pixmap, text_width, wrap_text};
+use cogex_render::{FontSet, TextAlign, TextLayout};
+use tiny_skia::{Color, Pixmap};
+
+const SIZE: f32 = 24.0;
+const TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+/// First and last columns with ink within `rows`
+fn ink_columns(pixmap: &Pixmap, rows: std::ops::Range<u32>) -> (u32, u32) {
+    let w = pixmap.width();
+    let inked: Vec<u32> = (0..w)
+        .filter(|&x| {
+            rows.clone()
+                .any(|y| pixmap.pixels()[(y * w + x) as usize].alpha() > 0)
+        })
+        .collect();
+    (inked[0], *inked.last().unwrap())
+}
+
+#[test]
+fn lines_wrap_between_words_within_the_width() {
+    let fonts = FontSet::bundled();
+    let max = text_width(&fonts, SIZE, "The quick brown fox");
+    let lines = wrap_text(&fonts, SIZE, TEXT, Some(max));
+    assert_eq!(lines, ["The quick brown fox", "jumps over the lazy", "dog"]);
+    assert!(lines.iter().all(|l| text_width(&fonts, SIZE, l) <= max));
+
+    // A word too wide for any line gets one of its own
+    let narrow = wrap_text(&fonts, SIZE, "a extraordinarily b", Some(40.0));
+    assert_eq!(narrow, ["a", "extraordinarily", "b"]);
+}
+
+#[test]
+fn without_a_width_only_newlines_break() {
+    let fonts = FontSet::bundled();
+    assert_eq!(wrap_text(&fonts, SIZE, TEXT, None), [TEXT]);
+    assert_eq!(
+        wrap_text(&fonts, SIZE, "one\ntwo three", Some(1000.0)),
+        ["one", "two three"]
+    );
+}
+
+#[test]
+fn line_spacing_sets_the_paragraph_height() {
+    let fonts = FontSet::bundled();
+    let height = |spacing| {
+        let layout = TextLayout::default()
+            .with_max_width(120.0)
+            .with_line_spacing(spacing);
+        render_paragraph_pixmap(TEXT, SIZE, &fonts, Color::WHITE, &layout).height()
+    };
+    let lines = wrap_text(&fonts, SIZE, TEXT, Some(120.0)).len() as f32;
+    let extra = (lines - 1.0) * SIZE;
+    let grown = height(2.0) as f32 - height(1.0) as f32;
+    assert!((grown - extra).abs() <= 1.0, "{} vs {}", grown, extra);
+}
+
+#[test]
+fn short_lines_align_within_the_widest() {
+    let fonts = FontSet::bundled();
+    let text = "a much longer first line\nshort";
+    let (ascent, descent) = fonts.v_metrics(SIZE);
+    let line_height = SIZE * TextLayout::default().line_spacing;
+    let second = (ascent + line_height - (ascent - descent)) as u32..(ascent + line_height) as u32;
+
+    let mut spans = Vec::new();
+    for align in [TextAlign::Left, TextAlign::Center, TextAlign::Right] {
+        let layout = TextLayout::default().with_align(align);
+        let pixmap = render_paragraph_pixmap(text, SIZE, &fonts, Color::WHITE, &layout);
+        spans.push((pixmap.width(), ink_columns(&pixmap, second.clone())));
+    }
+    let [(width, left), (_, centre), (_, right)] = spans[..] else {
+        unreachable!();
+    };
+    assert!(left.0 <= 3);
+    assert!(right.1 >= width - 4);
+    let margins = (centre.0 as i32, (width - 1 - centre.1) as i32);
+    assert!((margins.0 - margins.1).abs() <= 3, "{:?}", margins);
+}
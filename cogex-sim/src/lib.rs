@@ -0,0 +1,8 @@
+pub mod responder;
+pub mod runner;
+pub mod simulate;
+pub mod timer;
+pub use responder::{Responder, ResponseScript, ScriptedResponse, SimulatedParticipant};
+pub use runner::{Simulation, SimulationReport};
+pub use simulate::{simulate, SimulateOptions};
+pub use timer::SimTimer;
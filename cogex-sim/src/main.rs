@@ -0,0 +1,70 @@
+use anyhow::{bail, Context, Result};
+use cogex_experiment::{ExperimentConfig, ParadigmRegistry, PracticeCriterion};
+use cogex_sim::{simulate, SimulateOptions};
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage: cogex-sim [--seed N] [--out DIR] [--participant ID] \
+[--task default|gonogo|stroop|nback|posner|lexical|search|priming] [--config FILE] \
+[--script FILE | --accuracy P --rt MEAN_MS[,SD_MS]] [--refresh HZ] \
+[--practice-criterion ACCURACY[,MAX_REPEATS]]";
+
+fn parse_args() -> Result<SimulateOptions> {
+    let mut options = SimulateOptions::default();
+    let mut config = None;
+    let mut practice_criterion = None;
+    let mut iter = std::env::args().skip(1);
+    while let Some(flag) = iter.next() {
+        if flag == "-h" || flag == "--help" {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+        let value = iter
+            .next()
+            .with_context(|| format!("{} needs a value\n{}", flag, USAGE))?;
+        match flag.as_str() {
+            "--seed" => options.seed = value.parse().context("Invalid --seed")?,
+            "--out" => options.out = PathBuf::from(value),
+            "--participant" => options.participant = value,
+            "--task" => options.experiment = value,
+            "--config" => config = Some(PathBuf::from(value)),
+            "--script" => options.script = Some(PathBuf::from(value)),
+            "--accuracy" => options.accuracy = value.parse().context("Invalid --accuracy")?,
+            "--refresh" => options.refresh_hz = value.parse().context("Invalid --refresh")?,
+            "--rt" => {
+                let (mean, sd) = value.split_once(',').unwrap_or((&value, "80"));
+                options.rt = (
+                    mean.parse().context("Invalid --rt mean")?,
+                    sd.parse().context("Invalid --rt sd")?,
+                );
+            }
+            "--practice-criterion" => {
+                let (accuracy, repeats) = value.split_once(',').unwrap_or((&value, "2"));
+                practice_criterion = Some(PracticeCriterion::new(
+                    accuracy
+                        .parse()
+                        .context("Invalid --practice-criterion accuracy")?,
+                    repeats
+                        .parse()
+                        .context("Invalid --practice-criterion repeats")?,
+                ));
+            }
+            _ => bail!("Unknown option {}\n{}", flag, USAGE),
+        }
+    }
+    if let Some(path) = config {
+        options.config = ExperimentConfig::load(&path)?;
+    }
+    if practice_criterion.is_some() {
+        options.config.practice_criterion = practice_criterion;
+    }
+    Ok(options)
+}
+
+fn main() -> Result<()> {
+    let options = parse_args()?;
+    // Experiment logs are per-trial noise here; `COGEX_LOG=info` shows them
+    let filter = tracing_subscriber::EnvFilter::try_from_env("COGEX_LOG")
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn"));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+    simulate(options, &ParadigmRegistry::builtin())
+}
@@ -0,0 +1,203 @@
+use anyhow::{bail, Context, Result};
+use cogex_core::{ResponseKey, ResponseMapping, StimulusType};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::path::Path;
+
+/// Key pressed, and when, after the response window opens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScriptedResponse {
+    pub key: ResponseKey,
+    pub rt_ms: u64,
+}
+
+/// Decides how the simulated participant answers each trial
+pub trait Responder {
+    /// Called once per trial when its response window opens with the key
+    /// the trial expects; `None` withholds
+    fn respond(
+        &mut self,
+        stimulus: &StimulusType,
+        expected: Option<ResponseKey>,
+        mapping: &ResponseMapping<StimulusType>,
+    ) -> Option<ScriptedResponse>;
+}
+
+/// Random participant with a fixed accuracy and normally distributed RTs
+#[derive(Debug, Clone)]
+pub struct SimulatedParticipant {
+    pub accuracy: f64,
+    pub rt_mean_ms: f64,
+    pub rt_sd_ms: f64,
+    /// Fastest RT produced; samples below it are clamped
+    pub rt_min_ms: f64,
+    rng: StdRng,
+}
+
+impl SimulatedParticipant {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            accuracy: 0.9,
+            rt_mean_ms: 450.0,
+            rt_sd_ms: 80.0,
+            rt_min_ms: 150.0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn with_accuracy(mut self, accuracy: f64) -> Self {
+        self.accuracy = accuracy.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_rt(mut self, mean_ms: f64, sd_ms: f64) -> Self {
+        self.rt_mean_ms = mean_ms;
+        self.rt_sd_ms = sd_ms.max(0.0);
+        self
+    }
+
+    fn sample_rt(&mut self) -> u64 {
+        // Box-Muller
+        let u1: f64 = self.rng.random_range(f64::EPSILON..1.0);
+        let u2: f64 = self.rng.random();
+        let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+        (self.rt_mean_ms + z * self.rt_sd_ms).max(self.rt_min_ms) as u64
+    }
+}
+
+impl Responder for SimulatedParticipant {
+    fn respond(
+        &mut self,
+        _stimulus: &StimulusType,
+        expected: Option<ResponseKey>,
+        mapping: &ResponseMapping<StimulusType>,
+    ) -> Option<ScriptedResponse> {
+        let correct = self.rng.random_bool(self.accuracy);
+        let key = match (expected, correct) {
+            (Some(key), true) => key,
+            (None, true) => return None,
+            (expected, false) => {
+                let wrong: Vec<ResponseKey> = mapping
+                    .keys()
+                    .iter()
+                    .copied()
+                    .filter(|&k| Some(k) != expected)
+                    .collect();
+                if wrong.is_empty() {
+                    return None;
+                }
+                wrong[self.rng.random_range(0..wrong.len())]
+            }
+        };
+        Some(ScriptedResponse {
+            key,
+            rt_ms: self.sample_rt(),
+        })
+    }
+}
+
+/// One line of a response script
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptStep {
+    /// Whatever the mapping expects; withholds when nothing is expected
+    Correct {
+        rt_ms: u64,
+    },
+    /// Any accepted key other than the expected one
+    Wrong {
+        rt_ms: u64,
+    },
+    Key {
+        key: ResponseKey,
+        rt_ms: u64,
+    },
+    Withhold,
+}
+
+/// Fixed sequence of responses, repeated once exhausted.
+///
+/// Script files have one step per line: `correct <rt>`, `wrong <rt>`,
+/// `none`, or `<key> <rt>` where key is `space`, `left`, `right`, `up`,
+/// `down`, `mouse-left`, `mouse-right` or a single character. Blank lines
+/// and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseScript {
+    steps: Vec<ScriptStep>,
+    next: usize,
+}
+
+impl ResponseScript {
+    pub fn new(steps: Vec<ScriptStep>) -> Self {
+        Self { steps, next: 0 }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read script {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid script {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut steps = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let action = fields.next().unwrap_or_default();
+            let rt_ms = fields
+                .next()
+                .map(|rt| rt.parse::<u64>())
+                .transpose()
+                .with_context(|| format!("Line {}: bad reaction time", i + 1))?;
+            let needs_rt = |rt: Option<u64>| {
+                rt.with_context(|| format!("Line {}: '{}' needs a reaction time", i + 1, action))
+            };
+            steps.push(match action {
+                "none" => ScriptStep::Withhold,
+                "correct" => ScriptStep::Correct {
+                    rt_ms: needs_rt(rt_ms)?,
+                },
+                "wrong" => ScriptStep::Wrong {
+                    rt_ms: needs_rt(rt_ms)?,
+                },
+                key => ScriptStep::Key {
+                    key: ResponseKey::from_name(key)
+                        .with_context(|| format!("Line {}: unknown key '{}'", i + 1, key))?,
+                    rt_ms: needs_rt(rt_ms)?,
+                },
+            });
+        }
+        if steps.is_empty() {
+            bail!("Script has no steps");
+        }
+        Ok(Self::new(steps))
+    }
+}
+
+impl Responder for ResponseScript {
+    fn respond(
+        &mut self,
+        _stimulus: &StimulusType,
+        expected: Option<ResponseKey>,
+        mapping: &ResponseMapping<StimulusType>,
+    ) -> Option<ScriptedResponse> {
+        let step = *self.steps.get(self.next % self.steps.len().max(1))?;
+        self.next += 1;
+        let (key, rt_ms) = match step {
+            ScriptStep::Withhold => return None,
+            ScriptStep::Correct { rt_ms } => (expected?, rt_ms),
+            ScriptStep::Wrong { rt_ms } => (
+                mapping
+                    .keys()
+                    .iter()
+                    .copied()
+                    .find(|&k| Some(k) != expected)?,
+                rt_ms,
+            ),
+            ScriptStep::Key { key, rt_ms } => (key, rt_ms),
+        };
+        Some(ScriptedResponse { key, rt_ms })
+    }
+}
@@ -0,0 +1,128 @@
+use crate::responder::Responder;
+use crate::timer::SimTimer;
+use anyhow::{bail, Result};
+use cogex_core::{Phase, ResponseKey, StimulusType};
+use cogex_experiment::{ExperimentEvent, ExperimentStateMachine};
+use cogex_timing::Timer;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Summary of a finished simulation run
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    pub trials: usize,
+    pub frames: u64,
+    pub duration: Duration,
+    pub accuracy_by_condition: BTreeMap<String, f64>,
+}
+
+/// Drives an experiment frame by frame without a window, answering each
+/// trial through a `Responder`.
+pub struct Simulation<P, R>
+where
+    P: Phase,
+    R: Rng,
+{
+    pub experiment: ExperimentStateMachine<P, StimulusType, SimTimer, R>,
+    responder: Box<dyn Responder>,
+    /// Simulated time after which the run is considered stuck
+    pub time_limit: Duration,
+}
+
+impl<P, R> Simulation<P, R>
+where
+    P: Phase + Default,
+    R: Rng,
+{
+    pub fn new(
+        experiment: ExperimentStateMachine<P, StimulusType, SimTimer, R>,
+        responder: Box<dyn Responder>,
+    ) -> Self {
+        Self {
+            experiment,
+            responder,
+            time_limit: Duration::from_secs(6 * 60 * 60),
+        }
+    }
+
+    pub fn with_time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = limit;
+        self
+    }
+
+    /// Runs until the last phase is reached or the experiment is aborted
+    pub fn run(&mut self) -> Result<SimulationReport> {
+        let mut asked: Option<usize> = None;
+        let mut pending: Option<(usize, u64, ResponseKey)> = None;
+
+        while !self.experiment.is_aborted() && !self.experiment.is_final_phase() {
+            if self.experiment.timer.elapsed(0) > self.time_limit {
+                bail!(
+                    "Simulation did not finish within {:?}; stuck in {:?}",
+                    self.time_limit,
+                    self.experiment.current_phase()
+                );
+            }
+            // Welcome and break screens are dismissed straight away
+            if self.experiment.is_awaiting_input() {
+                self.experiment.handle_event(ExperimentEvent::SpacePressed);
+            }
+
+            self.experiment.timer.tick();
+            let now = self.experiment.timer.now();
+
+            if let Some(trial) = &self.experiment.current
+                && trial.state.accepts_response()
+                && asked != Some(trial.id)
+            {
+                asked = Some(trial.id);
+                let onset = trial.timestamps.stimulus_start.unwrap_or(now);
+                pending = self
+                    .responder
+                    .respond(
+                        &trial.stimulus,
+                        trial.expected_response(&self.experiment.response_mapping),
+                        &self.experiment.response_mapping,
+                    )
+                    .map(|r| (trial.id, onset + r.rt_ms * 1_000_000, r.key));
+            }
+            if let Some((id, due, key)) = pending
+                && now >= due
+            {
+                pending = None;
+                let event = match &self.experiment.current {
+                    Some(trial) if trial.id == id && trial.state.accepts_response() => {
+                        Some(match key {
+                            ResponseKey::Mouse(button) => ExperimentEvent::MouseClicked {
+                                position: trial.position,
+                                button,
+                                timestamp_ns: Some(due),
+                            },
+                            key => ExperimentEvent::ResponseReceived {
+                                key,
+                                timestamp_ns: Some(due),
+                            },
+                        })
+                    }
+                    _ => None,
+                };
+                if let Some(event) = event {
+                    self.experiment.handle_event(event);
+                }
+            }
+
+            for event in self.experiment.update() {
+                self.experiment.handle_event(event);
+            }
+            self.experiment.frame_presented(now);
+        }
+
+        Ok(SimulationReport {
+            trials: self.experiment.results().len(),
+            frames: self.experiment.timer.frame_count(),
+            duration: self.experiment.timer.elapsed(0),
+            accuracy_by_condition: self.experiment.accuracy_by_condition(),
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use crate::responder::{Responder, ResponseScript, SimulatedParticipant};
+use crate::runner::Simulation;
+use crate::timer::SimTimer;
+use anyhow::Result;
+use cogex_core::StandardPhase;
+use cogex_experiment::{
+    ExperimentConfig, ExperimentStateMachine, OutputManager, ParadigmRegistry, SessionInfo,
+    DEFAULT_OUTPUT_ROOT,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::path::PathBuf;
+
+/// One headless session as asked for on the command line
+#[derive(Debug, Clone)]
+pub struct SimulateOptions {
+    /// Paradigm name in the registry passed to [`simulate`]
+    pub experiment: String,
+    pub config: ExperimentConfig<StandardPhase>,
+    pub participant: String,
+    pub seed: u64,
+    /// Root the experiment's output directory is created under
+    pub out: PathBuf,
+    /// Scripted responses; without one a `SimulatedParticipant` answers
+    pub script: Option<PathBuf>,
+    pub accuracy: f64,
+    /// RT mean and SD in milliseconds
+    pub rt: (f64, f64),
+    pub refresh_hz: f64,
+}
+
+impl Default for SimulateOptions {
+    fn default() -> Self {
+        Self {
+            experiment: "default".to_string(),
+            config: ExperimentConfig::default(),
+            participant: "sim".to_string(),
+            seed: 0,
+            out: PathBuf::from(DEFAULT_OUTPUT_ROOT),
+            script: None,
+            accuracy: 0.9,
+            rt: (450.0, 80.0),
+            refresh_hz: 60.0,
+        }
+    }
+}
+
+/// Runs the session with the paradigm `registry` names, prints its summary
+/// and writes its output files
+pub fn simulate(
+    options: SimulateOptions,
+    registry: &ParadigmRegistry<StandardPhase>,
+) -> Result<()> {
+    let responder: Box<dyn Responder> = match &options.script {
+        Some(path) => Box::new(ResponseScript::load(path)?),
+        None => Box::new(
+            SimulatedParticipant::new(options.seed)
+                .with_accuracy(options.accuracy)
+                .with_rt(options.rt.0, options.rt.1),
+        ),
+    };
+
+    let timer = SimTimer::new(options.refresh_hz);
+    let rng = StdRng::seed_from_u64(options.seed);
+    let mut experiment = ExperimentStateMachine::new(options.config, timer, rng)
+        .with_named_paradigm(registry, &options.experiment)?;
+    experiment.session = Some(SessionInfo::new(options.participant, None, 1));
+
+    let mut simulation = Simulation::new(experiment, responder);
+    let report = simulation.run()?;
+
+    println!(
+        "\nSimulated {} trials over {} frames ({:.1} s)",
+        report.trials,
+        report.frames,
+        report.duration.as_secs_f64()
+    );
+    for (condition, accuracy) in &report.accuracy_by_condition {
+        println!("  {}: {:.1}% correct", condition, accuracy * 100.0);
+    }
+    for line in simulation.experiment.debrief_summary() {
+        println!("  {}", line);
+    }
+
+    let output = OutputManager::new(
+        &options.out,
+        &simulation.experiment.config.task,
+        simulation.experiment.session.as_ref(),
+    )?;
+    let metadata = simulation
+        .experiment
+        .metadata()
+        .with_crate("cogex-sim", env!("CARGO_PKG_VERSION"));
+    let path = output.write_metadata(&metadata)?;
+    println!("Session metadata saved to {}", path.display());
+    let path = cogex_experiment::write_results(
+        &output,
+        simulation.experiment.session.as_ref(),
+        simulation.experiment.results(),
+    )?;
+    println!("Results saved to {}", path.display());
+    let path = cogex_experiment::write_bids_events(
+        &output,
+        simulation.experiment.session.as_ref(),
+        &simulation.experiment.config.task,
+        simulation.experiment.results(),
+        simulation.experiment.logged_markers(),
+        simulation.experiment.scanner_zero_ns(),
+    )?;
+    println!("BIDS events saved to {}", path.display());
+    #[cfg(feature = "parquet")]
+    {
+        let path = cogex_experiment::write_results_parquet(
+            &output,
+            simulation.experiment.session.as_ref(),
+            simulation.experiment.results(),
+        )?;
+        println!("Results saved to {}", path.display());
+    }
+    Ok(())
+}
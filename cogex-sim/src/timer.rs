@@ -0,0 +1,83 @@
+use cogex_timing::{CalibrationStats, Timer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Virtual clock advanced by the simulation instead of wall time.
+///
+/// Clones share the clock, so a copy handed to a renderer sees the same time.
+#[derive(Debug, Clone)]
+pub struct SimTimer {
+    now_ns: Arc<AtomicU64>,
+    frame_ns: u64,
+    frames: u64,
+}
+
+impl SimTimer {
+    pub fn new(refresh_hz: f64) -> Self {
+        Self {
+            now_ns: Arc::new(AtomicU64::new(0)),
+            frame_ns: (1e9 / refresh_hz.max(1.0)) as u64,
+            frames: 0,
+        }
+    }
+
+    /// Duration of one simulated frame
+    pub fn frame_duration(&self) -> Duration {
+        Duration::from_nanos(self.frame_ns)
+    }
+
+    pub fn advance(&self, d: Duration) {
+        self.now_ns.fetch_add(d.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// Advances the clock by one frame and counts it
+    pub fn tick(&mut self) {
+        self.advance(self.frame_duration());
+        self.record_frame(self.frame_duration());
+    }
+}
+
+impl Default for SimTimer {
+    fn default() -> Self {
+        Self::new(60.0)
+    }
+}
+
+impl Timer for SimTimer {
+    type Timestamp = u64;
+
+    fn now(&self) -> u64 {
+        self.now_ns.load(Ordering::SeqCst)
+    }
+
+    fn elapsed(&self, ts: u64) -> Duration {
+        Duration::from_nanos(self.now().saturating_sub(ts))
+    }
+
+    fn sleep(&self, d: Duration) {
+        self.advance(d);
+    }
+
+    fn frame_count(&self) -> u64 {
+        self.frames
+    }
+
+    fn record_frame(&mut self, _d: Duration) {
+        self.frames += 1;
+    }
+
+    /// A perfectly steady display
+    fn calibration_stats(&self) -> CalibrationStats {
+        let frame_ns = self.frame_ns as f64;
+        CalibrationStats {
+            average_frame_time_ns: frame_ns,
+            jitter_ns: 0.0,
+            min_frame_time_ns: frame_ns,
+            max_frame_time_ns: frame_ns,
+            effective_fps: 1e9 / frame_ns,
+            frames: self.frames,
+            ..Default::default()
+        }
+    }
+}
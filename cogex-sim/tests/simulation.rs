@@ -0,0 +1,92 @@
+use cogex_core::{ResponseMapping, StandardPhase, StimulusType};
+use cogex_experiment::{ExperimentConfig, ExperimentStateMachine};
+use cogex_sim::{Responder, ResponseScript, SimTimer, SimulatedParticipant, Simulation};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn simulation(seed: u64, responder: Box<dyn Responder>) -> Simulation<StandardPhase, StdRng> {
+    let mut config = ExperimentConfig::default();
+    config.practice_trials = 2;
+    config.experiment_trials = 6;
+    let experiment =
+        ExperimentStateMachine::new(config, SimTimer::new(60.0), StdRng::seed_from_u64(seed));
+    Simulation::new(experiment, responder)
+}
+
+#[test]
+fn scripts_parse_each_kind_of_step() {
+    let script = "# practice\ncorrect 300\n\nnone\nwrong 450\nspace 250\nmouse-left 600\n";
+    assert!(ResponseScript::parse(script).is_ok());
+    for bad in [
+        "",
+        "# only a comment",
+        "correct",
+        "wrong fast",
+        "banana 300",
+    ] {
+        assert!(ResponseScript::parse(bad).is_err(), "{:?}", bad);
+    }
+}
+
+#[test]
+fn a_scripted_session_runs_to_the_end_without_a_window() {
+    let script = ResponseScript::parse("correct 300").unwrap();
+    let mut simulation = simulation(1, Box::new(script));
+    let report = simulation.run().unwrap();
+
+    assert!(simulation.experiment.is_final_phase());
+    assert_eq!(report.trials, 8);
+    assert!(report.frames > 0);
+    assert!(report.accuracy_by_condition.values().all(|&a| a == 1.0));
+    for result in simulation.experiment.results() {
+        assert_eq!(result.reaction_time_ns, Some(300_000_000));
+    }
+}
+
+#[test]
+fn withheld_responses_are_misses() {
+    let script = ResponseScript::parse("none").unwrap();
+    let mut simulation = simulation(1, Box::new(script));
+    simulation.run().unwrap();
+    assert!(simulation
+        .experiment
+        .results()
+        .iter()
+        .all(|r| r.response_key.is_none() && r.reaction_time_ns.is_none()));
+}
+
+#[test]
+fn simulated_participants_are_reproducible_from_their_seed() {
+    let run = |seed| {
+        let participant = SimulatedParticipant::new(seed)
+            .with_accuracy(0.7)
+            .with_rt(400.0, 60.0);
+        let mut simulation = simulation(5, Box::new(participant));
+        simulation.run().unwrap();
+        simulation
+            .experiment
+            .results()
+            .iter()
+            .map(|r| (r.response_key, r.reaction_time_ns, r.correct))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(run(3), run(3));
+    assert_ne!(run(3), run(4));
+}
+
+#[test]
+fn a_participant_who_is_always_wrong_never_presses_the_expected_key() {
+    let mut participant = SimulatedParticipant::new(0).with_accuracy(0.0);
+    let mapping = ResponseMapping::standard();
+    let circle = StimulusType::Circle {
+        radius: 50.0,
+        color: [255, 0, 0, 255],
+    };
+    let expected = mapping.expected_key(&circle);
+    assert!(expected.is_some());
+    for _ in 0..20 {
+        let response = participant.respond(&circle, expected, &mapping).unwrap();
+        assert_ne!(Some(response.key), expected);
+        assert!(response.rt_ms >= 150);
+    }
+}
@@ -0,0 +1,15 @@
+pub mod marker;
+pub mod outlet;
+pub mod trigger;
+pub use marker::{EventMarker, Marker};
+#[cfg(feature = "lsl")]
+pub use outlet::LslOutlet;
+pub use outlet::{MarkerOutlet, NullOutlet};
+#[cfg(target_os = "linux")]
+pub use trigger::ParallelPort;
+#[cfg(feature = "serial")]
+pub use trigger::SerialPort;
+pub use trigger::{TriggerCodes, TriggerOutlet, TriggerPort};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,72 @@
+use cogex_core::ResponseKey;
+
+/// Trial event forwarded to external recording systems
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventMarker {
+    StimulusOnset {
+        trial_id: usize,
+        condition: String,
+    },
+    Response {
+        trial_id: usize,
+        key: ResponseKey,
+    },
+    Feedback {
+        trial_id: usize,
+        correct: bool,
+    },
+    /// Sent by an external controller, e.g. an experiment orchestrator
+    External {
+        label: String,
+    },
+    /// MRI scanner volume trigger, counted from 1
+    ScannerPulse {
+        volume: usize,
+    },
+    /// The participant left the experiment window, e.g. `focus_lost`
+    Interrupted {
+        reason: String,
+    },
+    /// The participant came back after an interruption
+    Returned,
+}
+
+impl EventMarker {
+    /// Marker string, e.g. `stimulus_onset/3/circle`
+    pub fn label(&self) -> String {
+        match self {
+            EventMarker::StimulusOnset {
+                trial_id,
+                condition,
+            } => format!("stimulus_onset/{}/{}", trial_id, condition),
+            EventMarker::Response { trial_id, key } => {
+                format!("response/{}/{:?}", trial_id, key)
+            }
+            EventMarker::Feedback { trial_id, correct } => format!(
+                "feedback/{}/{}",
+                trial_id,
+                if *correct { "correct" } else { "incorrect" }
+            ),
+            EventMarker::External { label } => format!("external/{}", label),
+            EventMarker::ScannerPulse { volume } => format!("scanner_pulse/{}", volume),
+            EventMarker::Interrupted { reason } => format!("interrupted/{}", reason),
+            EventMarker::Returned => "returned".to_string(),
+        }
+    }
+}
+
+/// An event stamped with the experiment timer, in nanoseconds since start
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub event: EventMarker,
+    pub timestamp_ns: u64,
+}
+
+impl Marker {
+    pub fn new(event: EventMarker, timestamp_ns: u64) -> Self {
+        Self {
+            event,
+            timestamp_ns,
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::marker::Marker;
+use anyhow::Result;
+
+/// Destination for event markers
+pub trait MarkerOutlet {
+    fn push(&mut self, marker: &Marker) -> Result<()>;
+}
+
+/// Discards all markers
+#[derive(Debug, Clone, Default)]
+pub struct NullOutlet;
+
+impl MarkerOutlet for NullOutlet {
+    fn push(&mut self, _marker: &Marker) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Publishes markers as an irregular-rate LSL string stream.
+///
+/// Each sample has two channels: the marker label and the experiment timer
+/// value in nanoseconds. The LSL timestamp is the event time mapped onto the
+/// LSL clock, so late pushes do not shift the marker.
+#[cfg(feature = "lsl")]
+pub struct LslOutlet {
+    outlet: lsl::StreamOutlet,
+    /// LSL clock (seconds) and experiment timer (ns) sampled together
+    epoch: (f64, u64),
+}
+
+#[cfg(feature = "lsl")]
+impl LslOutlet {
+    /// Creates a `Markers` stream; `now_ns` is the experiment timer's current value
+    pub fn new(name: &str, source_id: &str, now_ns: u64) -> Result<Self> {
+        let info = lsl::StreamInfo::new(
+            name,
+            "Markers",
+            2,
+            lsl::IRREGULAR_RATE,
+            lsl::ChannelFormat::String,
+            source_id,
+        )?;
+        let outlet = lsl::StreamOutlet::new(&info, 1, 360)?;
+        Ok(Self {
+            outlet,
+            epoch: (lsl::local_clock(), now_ns),
+        })
+    }
+}
+
+#[cfg(feature = "lsl")]
+impl MarkerOutlet for LslOutlet {
+    fn push(&mut self, marker: &Marker) -> Result<()> {
+        use lsl::ExPushable;
+
+        let offset_s = (marker.timestamp_ns as f64 - self.epoch.1 as f64) / 1e9;
+        let sample = vec![marker.event.label(), marker.timestamp_ns.to_string()];
+        self.outlet
+            .push_sample_ex(&sample, self.epoch.0 + offset_s, true)?;
+        Ok(())
+    }
+}
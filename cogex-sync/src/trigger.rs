@@ -0,0 +1,209 @@
+use crate::marker::{EventMarker, Marker};
+use crate::outlet::MarkerOutlet;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::warn;
+
+/// Trigger codes sent for each event; 0 sends nothing
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriggerCodes {
+    pub stimulus_onset: u8,
+    /// Onset codes overriding `stimulus_onset` for specific conditions
+    pub conditions: BTreeMap<String, u8>,
+    pub response: u8,
+    pub feedback_correct: u8,
+    pub feedback_incorrect: u8,
+    /// Code for markers injected by an external controller
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub external: u8,
+    /// How long a code is held before the lines return to 0
+    pub pulse_ms: u64,
+}
+
+impl Default for TriggerCodes {
+    fn default() -> Self {
+        Self {
+            stimulus_onset: 1,
+            conditions: BTreeMap::new(),
+            response: 2,
+            feedback_correct: 0,
+            feedback_incorrect: 0,
+            external: 0,
+            pulse_ms: 5,
+        }
+    }
+}
+
+impl TriggerCodes {
+    pub fn with_condition(mut self, condition: &str, code: u8) -> Self {
+        self.conditions.insert(condition.to_string(), code);
+        self
+    }
+
+    /// Code for `event`, or `None` if it is not triggered
+    pub fn code_for(&self, event: &EventMarker) -> Option<u8> {
+        let code = match event {
+            EventMarker::StimulusOnset { condition, .. } => self
+                .conditions
+                .get(condition)
+                .copied()
+                .unwrap_or(self.stimulus_onset),
+            EventMarker::Response { .. } => self.response,
+            EventMarker::Feedback { correct: true, .. } => self.feedback_correct,
+            EventMarker::Feedback { correct: false, .. } => self.feedback_incorrect,
+            EventMarker::External { .. } => self.external,
+            // The scanner already has them
+            EventMarker::ScannerPulse { .. } => 0,
+            // Logged for excluding data, not for the recording
+            EventMarker::Interrupted { .. } | EventMarker::Returned => 0,
+        };
+        (code != 0).then_some(code)
+    }
+}
+
+/// Hardware port that sets its 8 data lines to a code
+pub trait TriggerPort: Send {
+    fn write(&mut self, code: u8) -> Result<()>;
+}
+
+/// Parallel port driven through the Linux ppdev interface (e.g. `/dev/parport0`)
+#[cfg(target_os = "linux")]
+pub struct ParallelPort {
+    file: std::fs::File,
+}
+
+#[cfg(target_os = "linux")]
+impl ParallelPort {
+    // _IO('p', 0x8b), _IO('p', 0x8c), _IOW('p', 0x86, unsigned char)
+    const PPCLAIM: libc::c_ulong = 0x708b;
+    const PPRELEASE: libc::c_ulong = 0x708c;
+    const PPWDATA: libc::c_ulong = 0x4001_7086;
+
+    pub fn open(path: &str) -> Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::OpenOptions::new().write(true).open(path)?;
+        if unsafe { libc::ioctl(file.as_raw_fd(), Self::PPCLAIM as _) } < 0 {
+            return Err(anyhow!(
+                "Failed to claim {}: {}",
+                path,
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(Self { file })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl TriggerPort for ParallelPort {
+    fn write(&mut self, code: u8) -> Result<()> {
+        use std::os::fd::AsRawFd;
+
+        let data: libc::c_uchar = code;
+        if unsafe { libc::ioctl(self.file.as_raw_fd(), Self::PPWDATA as _, &data) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for ParallelPort {
+    fn drop(&mut self) {
+        use std::os::fd::AsRawFd;
+
+        unsafe {
+            libc::ioctl(self.file.as_raw_fd(), Self::PPRELEASE as _);
+        }
+    }
+}
+
+/// USB-TTL trigger box on a serial port, one byte per code
+#[cfg(feature = "serial")]
+pub struct SerialPort {
+    port: Box<dyn serialport::SerialPort>,
+}
+
+#[cfg(feature = "serial")]
+impl SerialPort {
+    pub fn open(path: &str, baud_rate: u32) -> Result<Self> {
+        let port = serialport::new(path, baud_rate)
+            .timeout(Duration::from_millis(10))
+            .open()?;
+        Ok(Self { port })
+    }
+}
+
+#[cfg(feature = "serial")]
+impl TriggerPort for SerialPort {
+    fn write(&mut self, code: u8) -> Result<()> {
+        use std::io::Write;
+
+        self.port.write_all(&[code])?;
+        self.port.flush()?;
+        Ok(())
+    }
+}
+
+/// Sends trigger codes for event markers.
+///
+/// Writes happen on a worker thread which holds each code for `pulse_ms`
+/// before resetting to 0, so the caller never blocks on the pulse.
+pub struct TriggerOutlet {
+    codes: TriggerCodes,
+    sender: Option<Sender<u8>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl TriggerOutlet {
+    pub fn new(mut port: Box<dyn TriggerPort>, codes: TriggerCodes) -> Self {
+        let (sender, receiver) = mpsc::channel::<u8>();
+        let pulse = Duration::from_millis(codes.pulse_ms);
+        let worker = std::thread::spawn(move || {
+            for code in receiver {
+                if let Err(e) = port.write(code) {
+                    warn!("Failed to send trigger {}: {}", code, e);
+                    continue;
+                }
+                if !pulse.is_zero() {
+                    std::thread::sleep(pulse);
+                    if let Err(e) = port.write(0) {
+                        warn!("Failed to reset trigger lines: {}", e);
+                    }
+                }
+            }
+        });
+        Self {
+            codes,
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+}
+
+impl MarkerOutlet for TriggerOutlet {
+    fn push(&mut self, marker: &Marker) -> Result<()> {
+        let Some(code) = self.codes.code_for(&marker.event) else {
+            return Ok(());
+        };
+        self.sender
+            .as_ref()
+            .ok_or_else(|| anyhow!("Trigger outlet closed"))?
+            .send(code)
+            .map_err(|_| anyhow!("Trigger worker stopped"))
+    }
+}
+
+impl Drop for TriggerOutlet {
+    fn drop(&mut self) {
+        // Closing the channel lets the worker drain pending codes and exit
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use cogex_core::ResponseKey;
+use cogex_sync::{EventMarker, Marker, MarkerOutlet, TriggerCodes, TriggerOutlet, TriggerPort};
+use std::sync::{Arc, Mutex};
+
+/// Records every code written to its lines
+#[derive(Clone, Default)]
+struct Lines(Arc<Mutex<Vec<u8>>>);
+
+impl TriggerPort for Lines {
+    fn write(&mut self, code: u8) -> anyhow::Result<()> {
+        self.0.lock().unwrap().push(code);
+        Ok(())
+    }
+}
+
+fn onset(condition: &str) -> EventMarker {
+    EventMarker::StimulusOnset {
+        trial_id: 0,
+        condition: condition.to_string(),
+    }
+}
+
+#[test]
+fn events_map_to_their_codes_and_zero_sends_nothing() {
+    let codes = TriggerCodes {
+        feedback_incorrect: 9,
+        ..TriggerCodes::default().with_condition("incongruent", 12)
+    };
+    assert_eq!(codes.code_for(&onset("congruent")), Some(1));
+    assert_eq!(codes.code_for(&onset("incongruent")), Some(12));
+    let response = EventMarker::Response {
+        trial_id: 0,
+        key: ResponseKey::Space,
+    };
+    assert_eq!(codes.code_for(&response), Some(2));
+    let feedback = |correct| EventMarker::Feedback {
+        trial_id: 0,
+        correct,
+    };
+    assert_eq!(codes.code_for(&feedback(true)), None);
+    assert_eq!(codes.code_for(&feedback(false)), Some(9));
+    for event in [
+        EventMarker::External {
+            label: "run-1".to_string(),
+        },
+        EventMarker::ScannerPulse { volume: 1 },
+        EventMarker::Returned,
+    ] {
+        assert_eq!(codes.code_for(&event), None, "{:?}", event);
+    }
+}
+
+#[test]
+fn each_code_is_pulsed_then_reset_in_order() {
+    let lines = Lines::default();
+    let codes = TriggerCodes {
+        pulse_ms: 1,
+        ..TriggerCodes::default()
+    };
+    let mut outlet = TriggerOutlet::new(Box::new(lines.clone()), codes);
+    for event in [onset("a"), EventMarker::Returned, onset("b")] {
+        outlet.push(&Marker::new(event, 0)).unwrap();
+    }
+    // Dropping drains the codes still queued
+    drop(outlet);
+    assert_eq!(*lines.0.lock().unwrap(), [1, 0, 1, 0]);
+}
+
+#[test]
+fn without_a_pulse_width_codes_stay_on_the_lines() {
+    let lines = Lines::default();
+    let codes = TriggerCodes {
+        pulse_ms: 0,
+        ..TriggerCodes::default()
+    };
+    let mut outlet = TriggerOutlet::new(Box::new(lines.clone()), codes);
+    outlet.push(&Marker::new(onset("a"), 0)).unwrap();
+    drop(outlet);
+    assert_eq!(*lines.0.lock().unwrap(), [1]);
+}
@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// Monotonic clock the high-precision timer reads: `Instant` natively and
+/// `performance.now()` in browsers, which have no `Instant`.
+///
+/// Clones share one epoch, so input, audio and render threads each holding
+/// a clone stamp events on the same timeline, and readings never go back
+/// even when taken on different threads (or cores whose counters disagree
+/// slightly).
+///
+/// Browsers coarsen `performance.now()` against timing attacks, to 5 µs in
+/// cross-origin isolated pages and 100 µs or worse otherwise, so check
+/// [`resolution`](Self::resolution) before trusting sub-millisecond times.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    epoch: Arc<Epoch>,
+}
+
+#[derive(Debug)]
+struct Epoch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: Instant,
+    /// `performance.now()` at creation, in milliseconds
+    #[cfg(target_arch = "wasm32")]
+    start_ms: f64,
+    /// Latest reading handed out on any thread, in nanoseconds
+    latest_ns: AtomicU64,
+}
+
+impl Clock {
+    /// A clock with a new epoch; clone an existing one to share its timeline
+    pub fn new() -> Self {
+        Self {
+            epoch: Arc::new(Epoch {
+                #[cfg(not(target_arch = "wasm32"))]
+                start: Instant::now(),
+                #[cfg(target_arch = "wasm32")]
+                start_ms: performance_now(),
+                latest_ns: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Time since the epoch, never less than any earlier reading of this
+    /// clock or its clones
+    pub fn elapsed(&self) -> Duration {
+        let ns = self.raw_elapsed().as_nanos() as u64;
+        let latest = self.epoch.latest_ns.fetch_max(ns, Ordering::AcqRel);
+        Duration::from_nanos(latest.max(ns))
+    }
+
+    /// Whether `other` reads the same timeline
+    pub fn shares_epoch(&self, other: &Clock) -> bool {
+        Arc::ptr_eq(&self.epoch, &other.epoch)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn raw_elapsed(&self) -> Duration {
+        self.epoch.start.elapsed()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn raw_elapsed(&self) -> Duration {
+        Duration::from_secs_f64((performance_now() - self.epoch.start_ms).max(0.0) / 1e3)
+    }
+
+    /// Smallest step the clock was seen to advance by
+    pub fn resolution(&self) -> Duration {
+        (0..8)
+            .map(|_| {
+                let before = self.elapsed();
+                loop {
+                    let after = self.elapsed();
+                    if after > before {
+                        return after - before;
+                    }
+                    std::hint::spin_loop();
+                }
+            })
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Milliseconds since the page's time origin; zero outside a window
+#[cfg(target_arch = "wasm32")]
+fn performance_now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
@@ -1,2 +1,11 @@
+pub mod clock;
+pub mod mock;
 pub mod timer;
-pub use timer::{CalibrationStats, HighPrecisionTimer, Timer};
+pub use clock::Clock;
+pub use mock::MockTimer;
+pub use timer::{
+    CalibrationStats, HighPrecisionTimer, Timer, DROPPED_FRAME_FACTOR, REFRESH_MISMATCH_TOLERANCE,
+};
+
+/// Crate version, recorded in session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,87 @@
+use crate::timer::{CalibrationStats, Timer};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Timer whose clock only moves when told to, for stepping a state machine
+/// through its deadlines deterministically in tests.
+///
+/// Clones share the clock. `sleep` advances it instead of blocking, and
+/// calibration reports a jitter-free display at the configured refresh rate.
+#[derive(Debug, Clone)]
+pub struct MockTimer {
+    now_ns: Arc<AtomicU64>,
+    frames: Arc<AtomicU64>,
+    frame_ns: u64,
+}
+
+impl MockTimer {
+    pub fn new(refresh_hz: f64) -> Self {
+        Self {
+            now_ns: Arc::new(AtomicU64::new(0)),
+            frames: Arc::new(AtomicU64::new(0)),
+            frame_ns: (1e9 / refresh_hz.max(1.0)) as u64,
+        }
+    }
+
+    pub fn advance(&self, d: Duration) {
+        self.now_ns.fetch_add(d.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    pub fn advance_ms(&self, ms: u64) {
+        self.advance(Duration::from_millis(ms));
+    }
+
+    /// Moves the clock to `ns`, which may be earlier than now
+    pub fn set(&self, ns: u64) {
+        self.now_ns.store(ns, Ordering::SeqCst);
+    }
+
+    /// Duration of one refresh at the configured rate
+    pub fn frame_duration(&self) -> Duration {
+        Duration::from_nanos(self.frame_ns)
+    }
+}
+
+impl Default for MockTimer {
+    fn default() -> Self {
+        Self::new(60.0)
+    }
+}
+
+impl Timer for MockTimer {
+    type Timestamp = u64;
+
+    fn now(&self) -> u64 {
+        self.now_ns.load(Ordering::SeqCst)
+    }
+
+    fn elapsed(&self, ts: u64) -> Duration {
+        Duration::from_nanos(self.now().saturating_sub(ts))
+    }
+
+    fn sleep(&self, d: Duration) {
+        self.advance(d);
+    }
+
+    fn frame_count(&self) -> u64 {
+        self.frames.load(Ordering::SeqCst)
+    }
+
+    fn record_frame(&mut self, _d: Duration) {
+        self.frames.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn calibration_stats(&self) -> CalibrationStats {
+        let frame_ns = self.frame_ns as f64;
+        CalibrationStats {
+            average_frame_time_ns: frame_ns,
+            jitter_ns: 0.0,
+            min_frame_time_ns: frame_ns,
+            max_frame_time_ns: frame_ns,
+            effective_fps: 1e9 / frame_ns,
+            frames: self.frame_count(),
+            ..Default::default()
+        }
+    }
+}
@@ -1,56 +1,164 @@
-use std::{
-    collections::VecDeque,
-    time::{Duration, Instant},
-};
+use crate::clock::Clock;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::{collections::VecDeque, time::Duration};
 
-/// Trait for high-precision timers
+/// Trait for high-precision timers.
+///
+/// Clones must read the same timeline, so a clone handed to an input or
+/// audio thread stamps events comparably with the render thread's.
 pub trait Timer: Clone + Send + Sync {
     type Timestamp: Copy + Clone + Send + Sync;
     fn now(&self) -> Self::Timestamp;
     fn elapsed(&self, ts: Self::Timestamp) -> Duration;
     fn sleep(&self, d: Duration);
+    /// Frames recorded since the timer was created
     fn frame_count(&self) -> u64;
     fn record_frame(&mut self, d: Duration);
     fn calibration_stats(&self) -> CalibrationStats;
+
+    /// Notes a frame reaching the display, returning how many refreshes were
+    /// missed since the previous one
+    fn record_present(&mut self, _timestamp: Self::Timestamp) -> u64 {
+        0
+    }
+
+    /// Forgets the previous present, so a deliberate gap is not counted as drops
+    fn reset_present(&mut self) {}
+
+    /// Refreshes missed since the timer was created
+    fn dropped_frames(&self) -> u64 {
+        0
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Presents more than this many refresh periods apart count as dropped frames
+pub const DROPPED_FRAME_FACTOR: f64 = 1.5;
+
+/// Relative difference between the estimated and reported refresh rates
+/// beyond which they are flagged as disagreeing
+pub const REFRESH_MISMATCH_TOLERANCE: f64 = 0.02;
+
+/// Present intervals kept for estimating the refresh rate
+const PRESENT_WINDOW: usize = 240;
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CalibrationStats {
     pub average_frame_time_ns: f64,
     pub jitter_ns: f64,
     pub min_frame_time_ns: f64,
     pub max_frame_time_ns: f64,
     pub effective_fps: f64,
+    /// Frames measured: all recorded, or those of the calibration phase
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub frames: u64,
+    /// How long the calibration phase measured
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub duration_ns: Option<u64>,
+    /// Whether the jitter estimate settled before the calibration time
+    /// limit; `None` when no convergence was asked for
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub converged: Option<bool>,
+    /// Refresh rate estimated from the intervals between consecutive
+    /// presents: the shortest interval they cluster at
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub estimated_refresh_hz: Option<f64>,
+    /// Share of present intervals within 10 % of a whole number of
+    /// estimated refresh periods; well below 1 means presents are not
+    /// locked to the display's refresh
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub quantized_share: Option<f64>,
+    /// Refresh rate the monitor reported
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reported_refresh_hz: Option<f64>,
+}
+
+impl CalibrationStats {
+    /// Whether presents ran at a different rate than the monitor reported,
+    /// e.g. a 144 Hz panel driven at 60 Hz or every frame missing a refresh
+    pub fn refresh_mismatch(&self) -> bool {
+        match (self.estimated_refresh_hz, self.reported_refresh_hz) {
+            (Some(estimated), Some(reported)) => {
+                (estimated - reported).abs() > reported * REFRESH_MISMATCH_TOLERANCE
+            }
+            _ => false,
+        }
+    }
 }
 
+/// High-precision timer; clones share both the clock and the frame
+/// statistics, so frames recorded through a clone handed to the renderer
+/// count towards calibration and dropped-frame detection.
 #[derive(Debug, Clone)]
 pub struct HighPrecisionTimer {
-    start: Instant,
+    clock: Clock,
+    frames: Arc<Mutex<FrameStats>>,
+}
+
+#[derive(Debug)]
+struct FrameStats {
     frame_times: VecDeque<f64>, // nanoseconds
     capacity: usize,
+    /// Frames recorded in all, beyond those still in the window
+    total: u64,
     // Welford's running stats
     count: usize,
     mean: f64,
     m2: f64,
     min: f64,
     max: f64,
+    /// Nominal refresh period, enabling dropped-frame detection
+    refresh_period_ns: Option<f64>,
+    last_present_ns: Option<u64>,
+    /// Latest intervals between consecutive presents, in nanoseconds
+    present_intervals: VecDeque<f64>,
+    dropped_frames: u64,
 }
 
 impl HighPrecisionTimer {
     pub fn new() -> Self {
+        Self::with_clock(Clock::new())
+    }
+
+    /// A timer on `clock`'s timeline with frame statistics of its own, e.g.
+    /// for a second window
+    pub fn with_clock(clock: Clock) -> Self {
         let capacity = 1000;
-        Self {
-            start: Instant::now(),
+        let frames = FrameStats {
             frame_times: VecDeque::with_capacity(capacity),
             capacity,
+            total: 0,
             count: 0,
             mean: 0.0,
             m2: 0.0,
             min: f64::INFINITY,
             max: 0.0,
+            refresh_period_ns: None,
+            last_present_ns: None,
+            present_intervals: VecDeque::with_capacity(PRESENT_WINDOW),
+            dropped_frames: 0,
+        };
+        Self {
+            clock,
+            frames: Arc::new(Mutex::new(frames)),
         }
     }
 
+    /// Checks present intervals against the period of `refresh_hz`
+    pub fn with_refresh_rate(mut self, refresh_hz: f64) -> Self {
+        self.set_refresh_rate(Some(refresh_hz));
+        self
+    }
+
+    pub fn set_refresh_rate(&mut self, refresh_hz: Option<f64>) {
+        self.frames().refresh_period_ns = refresh_hz.filter(|hz| *hz > 0.0).map(|hz| 1e9 / hz);
+    }
+
+    fn frames(&self) -> MutexGuard<'_, FrameStats> {
+        // Stats stay usable even if a thread panicked while recording
+        self.frames.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     pub fn high_precision_sleep(&self, duration: Duration) {
         #[cfg(target_os = "windows")]
         self.windows_sleep(duration);
@@ -58,10 +166,36 @@ impl HighPrecisionTimer {
         self.linux_sleep(duration);
         #[cfg(target_os = "macos")]
         self.macos_sleep(duration);
-        #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+        #[cfg(target_arch = "wasm32")]
+        self.spin_sleep(duration);
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "macos",
+            target_arch = "wasm32"
+        )))]
         std::thread::sleep(duration);
     }
 
+    /// The clock timestamps are read from, for threads that only stamp
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Smallest step of the underlying clock, coarse in browsers
+    pub fn resolution(&self) -> Duration {
+        self.clock.resolution()
+    }
+
+    /// Browsers cannot block their main thread, so this spins instead
+    #[cfg(target_arch = "wasm32")]
+    fn spin_sleep(&self, duration: Duration) {
+        let until = self.clock.elapsed() + duration;
+        while self.clock.elapsed() < until {
+            std::hint::spin_loop();
+        }
+    }
+
     #[cfg(target_os = "windows")]
     fn windows_sleep(&self, duration: Duration) {
         use windows::Win32::Foundation::CloseHandle;
@@ -126,7 +260,7 @@ impl Timer for HighPrecisionTimer {
     type Timestamp = u64;
 
     fn now(&self) -> u64 {
-        self.start.elapsed().as_nanos() as u64
+        self.clock.elapsed().as_nanos() as u64
     }
 
     fn elapsed(&self, ts: u64) -> Duration {
@@ -138,12 +272,32 @@ impl Timer for HighPrecisionTimer {
     }
 
     fn frame_count(&self) -> u64 {
-        self.count as u64
+        self.frames().total
     }
 
     fn record_frame(&mut self, d: Duration) {
-        let sample = d.as_nanos() as f64;
+        self.frames().record(d.as_nanos() as f64);
+    }
+
+    fn calibration_stats(&self) -> CalibrationStats {
+        self.frames().stats()
+    }
+
+    fn record_present(&mut self, timestamp: u64) -> u64 {
+        self.frames().present(timestamp)
+    }
+
+    fn reset_present(&mut self) {
+        self.frames().last_present_ns = None;
+    }
 
+    fn dropped_frames(&self) -> u64 {
+        self.frames().dropped_frames
+    }
+}
+
+impl FrameStats {
+    fn record(&mut self, sample: f64) {
         // Evict oldest if full
         if self.frame_times.len() == self.capacity {
             self.frame_times.pop_front();
@@ -165,6 +319,7 @@ impl Timer for HighPrecisionTimer {
         }
 
         // Add new sample
+        self.total += 1;
         self.frame_times.push_back(sample);
         self.count += 1;
         let delta = sample - self.mean;
@@ -175,7 +330,8 @@ impl Timer for HighPrecisionTimer {
         self.max = self.max.max(sample);
     }
 
-    fn calibration_stats(&self) -> CalibrationStats {
+    fn stats(&self) -> CalibrationStats {
+        let refresh = refresh_period(&self.present_intervals);
         let avg = if self.count > 0 { self.mean } else { 0.0 };
         let jitter = if self.count > 1 {
             (self.m2 / self.count as f64).sqrt()
@@ -188,6 +344,62 @@ impl Timer for HighPrecisionTimer {
             min_frame_time_ns: if self.min.is_finite() { self.min } else { 0.0 },
             max_frame_time_ns: self.max,
             effective_fps: if avg > 0.0 { 1e9 / avg } else { 0.0 },
+            frames: self.total,
+            estimated_refresh_hz: refresh.map(|(period_ns, _)| 1e9 / period_ns),
+            quantized_share: refresh.map(|(_, share)| share),
+            reported_refresh_hz: self.refresh_period_ns.map(|ns| 1e9 / ns),
+            ..Default::default()
         }
     }
+
+    fn present(&mut self, timestamp: u64) -> u64 {
+        let Some(previous) = self.last_present_ns.replace(timestamp) else {
+            return 0;
+        };
+        let interval = timestamp.saturating_sub(previous) as f64;
+        if self.present_intervals.len() == PRESENT_WINDOW {
+            self.present_intervals.pop_front();
+        }
+        self.present_intervals.push_back(interval);
+        let Some(period) = self.refresh_period_ns else {
+            return 0;
+        };
+        if interval <= period * DROPPED_FRAME_FACTOR {
+            return 0;
+        }
+        let missed = ((interval / period).round() as u64)
+            .saturating_sub(1)
+            .max(1);
+        self.dropped_frames += missed;
+        missed
+    }
+}
+
+/// Refresh period the present `intervals` are quantized to, and the share of
+/// them within 10 % of a whole number of periods.
+///
+/// The period is the mean of the intervals near the 10th percentile, so the
+/// occasional early present does not set it and missed refreshes, which
+/// land on multiples of it, do not stretch it.
+fn refresh_period(intervals: &VecDeque<f64>) -> Option<(f64, f64)> {
+    let mut sorted: Vec<f64> = intervals.iter().copied().filter(|i| *i > 0.0).collect();
+    if sorted.len() < 10 {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+    let low = sorted[sorted.len() / 10];
+    let cluster: Vec<f64> = sorted
+        .iter()
+        .copied()
+        .filter(|i| (i - low).abs() <= low * 0.25)
+        .collect();
+    let period = cluster.iter().sum::<f64>() / cluster.len() as f64;
+    let quantized = sorted
+        .iter()
+        .filter(|i| {
+            let periods = (*i / period).round().max(1.0);
+            (*i - periods * period).abs() <= period * 0.1
+        })
+        .count();
+    Some((period, quantized as f64 / sorted.len() as f64))
 }
@@ -0,0 +1,103 @@
+use cogex_timing::{Clock, HighPrecisionTimer, Timer};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn clones_stamp_one_monotonic_timeline_across_threads() {
+    let timer = HighPrecisionTimer::new();
+    let clone = timer.clone();
+    assert!(clone.clock().shares_epoch(timer.clock()));
+    assert!(!Clock::new().shares_epoch(timer.clock()));
+
+    // Stamps taken in turn never go back, whichever thread took them
+    let stamps = Arc::new(Mutex::new(Vec::new()));
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let (timer, stamps) = (timer.clone(), stamps.clone());
+            std::thread::spawn(move || {
+                for _ in 0..1000 {
+                    let mut stamps = stamps.lock().unwrap();
+                    stamps.push(timer.now());
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let stamps = stamps.lock().unwrap();
+    assert_eq!(stamps.len(), 4000);
+    assert!(stamps.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(clone.now() >= *stamps.last().unwrap());
+}
+
+#[test]
+fn clones_share_frame_statistics_and_with_clock_starts_fresh() {
+    let mut timer = HighPrecisionTimer::new().with_refresh_rate(100.0);
+    // What the renderer records through its per-frame clone
+    let mut render = timer.clone();
+    render.record_frame(Duration::from_millis(4));
+    render.record_frame(Duration::from_millis(6));
+    assert_eq!(timer.frame_count(), 2);
+    assert_eq!(timer.calibration_stats().average_frame_time_ns, 5e6);
+
+    render.record_present(0);
+    assert_eq!(timer.record_present(30_000_000), 2);
+    assert_eq!(render.dropped_frames(), 2);
+
+    let mirror = HighPrecisionTimer::with_clock(timer.clock().clone());
+    assert_eq!(mirror.frame_count(), 0);
+}
+
+#[test]
+fn refresh_rate_is_estimated_from_presents_and_checked_against_the_monitor() {
+    let mut timer = HighPrecisionTimer::new().with_refresh_rate(144.0);
+    // A 60 Hz display with every fifth frame missing a refresh
+    let period_ns = 16_666_667;
+    let mut at = 0;
+    for frame in 0..100 {
+        timer.record_present(at);
+        at += if frame % 5 == 4 {
+            2 * period_ns
+        } else {
+            period_ns
+        };
+    }
+    let stats = timer.calibration_stats();
+    let hz = stats.estimated_refresh_hz.unwrap();
+    assert!((hz - 60.0).abs() < 0.1, "{}", hz);
+    assert_eq!(stats.quantized_share, Some(1.0));
+    assert!((stats.reported_refresh_hz.unwrap() - 144.0).abs() < 1e-9);
+    assert!(stats.refresh_mismatch());
+
+    let mut timer = HighPrecisionTimer::new().with_refresh_rate(60.0);
+    // Presents not locked to any refresh, each up to 9 ms late
+    for frame in 0..100 {
+        timer.record_present(frame * 16_000_000 + (frame * 7_919 % 10) * 1_000_000);
+    }
+    let stats = timer.calibration_stats();
+    assert!(stats.quantized_share.unwrap() < 0.9);
+}
+
+#[test]
+fn presents_over_one_and_a_half_periods_apart_count_missed_refreshes() {
+    let period_ns = 16_666_667;
+    let mut timer = HighPrecisionTimer::new();
+    // Without a refresh rate nothing counts as dropped
+    timer.record_present(0);
+    assert_eq!(timer.record_present(10 * period_ns), 0);
+
+    let mut timer = HighPrecisionTimer::new().with_refresh_rate(60.0);
+    assert_eq!(timer.record_present(0), 0);
+    assert_eq!(timer.record_present(period_ns), 0);
+    // Just under 1.5 periods is a late frame, not a dropped one
+    let at = period_ns * 5 / 2 - 1_000_000;
+    assert_eq!(timer.record_present(at), 0);
+    assert_eq!(timer.record_present(at + 3 * period_ns), 2);
+    assert_eq!(timer.dropped_frames(), 2);
+
+    // A deliberate gap after a reset is not counted
+    timer.reset_present();
+    assert_eq!(timer.record_present(at + 100 * period_ns), 0);
+    assert_eq!(timer.dropped_frames(), 2);
+}
@@ -0,0 +1,432 @@
+use crate::output;
+use anyhow::{anyhow, Context, Result};
+use cogex_core::{MouseButton, RawEvent, RawInput, ResponseKey, StandardPhase, StimulusType};
+use cogex_experiment::{
+    DisplayInfo, ExperimentConfig, ExperimentEvent, ExperimentStateMachine, ParadigmRegistry,
+    Screen, SessionInfo,
+};
+use cogex_render::{Catalog, SkiaRenderer};
+use cogex_timing::{HighPrecisionTimer, Timer};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::json;
+use std::time::Duration;
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, ImageData};
+use winit::{
+    application::ApplicationHandler,
+    dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
+    event::{KeyEvent, WindowEvent},
+    event_loop::ActiveEventLoop,
+    keyboard::{Key, NamedKey},
+    platform::web::{WindowAttributesExtWebSys, WindowExtWebSys},
+    window::{Fullscreen, Window, WindowId},
+};
+
+type Machine = ExperimentStateMachine<StandardPhase, StimulusType, HighPrecisionTimer, StdRng>;
+
+/// The experiment drawn by the Skia renderer into a 2D canvas, one frame per
+/// `requestAnimationFrame`
+pub struct WebApp {
+    window: Option<Window>,
+    context: Option<CanvasRenderingContext2d>,
+    /// RGBA pixels of the frame being drawn
+    frame: Vec<u8>,
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+    experiment: Machine,
+    renderer: Option<SkiaRenderer>,
+    /// Where the session's data is POSTed; downloaded without one
+    upload_url: Option<String>,
+    /// Smallest step of the browser clock, measured at start
+    clock_resolution: Duration,
+    /// Last cursor position in canvas pixels
+    cursor: (f32, f32),
+    /// Whether the data was sent, so it goes out only once
+    saved: bool,
+}
+
+impl WebApp {
+    pub fn new(
+        config: ExperimentConfig<StandardPhase>,
+        registry: &ParadigmRegistry<StandardPhase>,
+        experiment: &str,
+        session: SessionInfo,
+        upload_url: Option<String>,
+    ) -> Result<Self> {
+        let timer = HighPrecisionTimer::new();
+        let clock_resolution = timer.resolution();
+        let mut experiment = Machine::new(config, timer, StdRng::from_os_rng())
+            .with_named_paradigm(registry, experiment)?;
+        experiment.session = Some(session);
+        Ok(Self {
+            window: None,
+            context: None,
+            frame: Vec::new(),
+            size: PhysicalSize::new(0, 0),
+            scale_factor: 1.0,
+            experiment,
+            renderer: None,
+            upload_url,
+            clock_resolution,
+            cursor: (0.0, 0.0),
+            saved: false,
+        })
+    }
+
+    fn create_window(&mut self, event_loop: &ActiveEventLoop) -> Result<()> {
+        let page = web_sys::window().context("No browser window")?;
+        let width = page.inner_width().ok().and_then(|w| w.as_f64());
+        let height = page.inner_height().ok().and_then(|h| h.as_f64());
+        let attributes = Window::default_attributes()
+            .with_title("Cogex")
+            .with_inner_size(LogicalSize::new(
+                width.unwrap_or(800.0),
+                height.unwrap_or(600.0),
+            ))
+            .with_append(true);
+        let window = event_loop.create_window(attributes)?;
+        let context = window
+            .canvas()
+            .context("Window has no canvas")?
+            .get_context("2d")
+            .map_err(|e| anyhow!("{:?}", e))?
+            .context("Canvas has no 2D context")?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|e| anyhow!("{:?}", e))?;
+
+        let size = window.inner_size();
+        let mut renderer = SkiaRenderer::new(
+            size.width,
+            size.height,
+            self.experiment.config.total_experiment_trials(),
+        );
+        renderer.set_display(self.experiment.config.display);
+        renderer.set_anti_alias(self.experiment.config.anti_alias);
+        renderer.set_text_cache_budget(self.experiment.config.text_cache_mb << 20);
+        renderer.set_theme(self.experiment.config.theme);
+        renderer.set_scale_factor(window.scale_factor() as f32);
+        renderer.set_feedback_rt(self.experiment.config.feedback_show_rt);
+        renderer.set_iti_fixation(self.experiment.config.iti_display.shows_fixation());
+        renderer.set_preintern_plan(self.experiment.config.preintern_plan());
+        let catalog =
+            Catalog::resolve(&self.experiment.config.locale).unwrap_or_else(|_| Catalog::english());
+        renderer.set_catalog(catalog.with_messages(self.experiment.paradigm_messages()));
+
+        window.set_cursor_visible(self.experiment.response_mapping.accepts_mouse());
+        window.request_redraw();
+        self.size = size;
+        self.scale_factor = window.scale_factor();
+        self.experiment.set_screen(Screen::new(
+            size.width,
+            size.height,
+            self.scale_factor as f32,
+        ));
+        self.frame = vec![0; size.width as usize * size.height as usize * 4];
+        self.renderer = Some(renderer);
+        self.context = Some(context);
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn render(&mut self) -> Result<()> {
+        let renderer = self.renderer.as_mut().unwrap();
+        // Shares the frame statistics with the state machine's timer
+        let mut timer = self.experiment.timer.clone();
+        let frame = &mut self.frame;
+
+        let trial_frame = if self.experiment.is_aborted() {
+            renderer.render_session_ended(frame, &mut timer)?;
+            false
+        } else if let Some(instructions) = self.experiment.current_instructions() {
+            renderer.render_instructions(instructions, frame, &mut timer)?;
+            false
+        } else if let Some(questionnaire) = self.experiment.current_questionnaire() {
+            renderer.render_questionnaire(questionnaire, frame, &mut timer)?;
+            false
+        } else if self.experiment.is_final_phase() {
+            let details = self.experiment.debrief_summary();
+            renderer.render_debrief(&self.experiment.debrief(), &details, frame, &mut timer)?;
+            false
+        } else {
+            renderer.set_stimulus_elapsed(self.experiment.stimulus_elapsed());
+            renderer.set_rating(self.experiment.current_rating());
+            renderer.set_subpixel(self.experiment.subpixel_positioning());
+            renderer.set_primitives(self.experiment.paradigm_primitives());
+            renderer.set_widgets(self.experiment.widgets());
+            renderer.set_rest_remaining(self.experiment.rest_remaining());
+            renderer.set_feedback(self.experiment.feedback());
+            renderer.render_frame(
+                self.experiment.current_phase(),
+                &self.experiment.current_stimuli(),
+                self.experiment.current_trial_state(),
+                self.experiment.trial_progress(),
+                frame,
+                &mut timer,
+            )?;
+            true
+        };
+        self.present()?;
+
+        if trial_frame {
+            // Handed to the compositor, which shows it on a later refresh
+            self.experiment.frame_presented(timer.now());
+            self.warm_up();
+        }
+        Ok(())
+    }
+
+    /// Rasterizes the stimuli of the next onset into the renderer's caches
+    fn warm_up(&mut self) {
+        let stimuli = self.experiment.upcoming_stimuli();
+        if let Some(renderer) = self.renderer.as_mut()
+            && !stimuli.is_empty()
+        {
+            renderer.warm_up(&stimuli, self.experiment.trial_progress());
+        }
+    }
+
+    /// Copies the frame onto the canvas
+    fn present(&self) -> Result<()> {
+        let context = self.context.as_ref().unwrap();
+        let image = ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(&self.frame),
+            self.size.width,
+            self.size.height,
+        )
+        .map_err(|e| anyhow!("{:?}", e))?;
+        context
+            .put_image_data(&image, 0.0, 0.0)
+            .map_err(|e| anyhow!("{:?}", e))
+    }
+
+    fn update(&mut self) {
+        if !self.experiment.is_aborted() {
+            let events = self.experiment.update();
+            for event in events {
+                self.experiment.handle_event(event);
+            }
+        }
+        if self.experiment.is_aborted() || self.experiment.is_final_phase() {
+            self.save();
+        }
+    }
+
+    fn handle_key(&mut self, event: &KeyEvent) {
+        match &event.logical_key {
+            // Browsers also leave fullscreen on Escape
+            Key::Named(NamedKey::Escape) => {
+                self.experiment.handle_event(ExperimentEvent::Abort {
+                    reason: "participant pressed Escape".to_string(),
+                });
+            }
+            Key::Named(NamedKey::Backspace) => {
+                self.experiment.handle_event(ExperimentEvent::PreviousPage);
+            }
+            key => {
+                let Some(key) = response_key(key) else {
+                    return;
+                };
+                // Fullscreen needs a user gesture, which a key press is
+                if let Some(window) = &self.window
+                    && window.fullscreen().is_none()
+                {
+                    window.set_fullscreen(Some(Fullscreen::Borderless(None)));
+                }
+                let now = self.experiment.timer.now();
+                self.experiment
+                    .handle_raw_input(RawInput::new(RawEvent::Key(key), now));
+            }
+        }
+    }
+
+    fn handle_click(&mut self, button: winit::event::MouseButton) {
+        if let (Some(item), Some(renderer)) = (
+            self.experiment
+                .current_questionnaire()
+                .and_then(|q| q.item()),
+            &self.renderer,
+        ) {
+            if button == winit::event::MouseButton::Left
+                && let Some(option) = renderer.questionnaire_option_at(item, self.cursor)
+            {
+                self.experiment
+                    .handle_event(ExperimentEvent::QuestionnaireAnswer { option });
+            }
+            return;
+        }
+        let button = match button {
+            winit::event::MouseButton::Left => MouseButton::Left,
+            winit::event::MouseButton::Right => MouseButton::Right,
+            winit::event::MouseButton::Middle => MouseButton::Middle,
+            winit::event::MouseButton::Back => MouseButton::Other(3),
+            winit::event::MouseButton::Forward => MouseButton::Other(4),
+            winit::event::MouseButton::Other(n) => MouseButton::Other(n),
+        };
+        let mut position = self.cursor;
+        if let Some(display) = self.experiment.config.display {
+            let centre = (self.size.width as f32 * 0.5, self.size.height as f32 * 0.5);
+            position = display.px_to_position(position, centre);
+        } else {
+            // Stimulus positions are in logical pixels
+            let scale = self.scale_factor as f32;
+            position = (position.0 / scale, position.1 / scale);
+        }
+        let now = self.experiment.timer.now();
+        self.experiment
+            .handle_raw_input(RawInput::new(RawEvent::Click { button, position }, now));
+    }
+
+    fn handle_cursor(&mut self, cursor: PhysicalPosition<f64>) {
+        self.cursor = (cursor.x as f32, cursor.y as f32);
+    }
+
+    fn handle_resize(&mut self, size: PhysicalSize<u32>) {
+        // A hidden canvas; the renderer keeps its size until it is shown
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+        self.size = size;
+        self.experiment.set_screen(Screen::new(
+            size.width,
+            size.height,
+            self.scale_factor as f32,
+        ));
+        self.frame
+            .resize(size.width as usize * size.height as usize * 4, 0);
+        if let Some(renderer) = &mut self.renderer {
+            renderer.resize(size.width, size.height);
+        }
+    }
+
+    /// Sends everything collected so far; later calls do nothing
+    fn save(&mut self) {
+        if std::mem::replace(&mut self.saved, true) {
+            return;
+        }
+        self.experiment.sample_clock();
+        let session = self.experiment.session.as_ref();
+        let results = self.experiment.results();
+        let metadata = self
+            .experiment
+            .metadata()
+            .with_crate("cogex-web", crate::VERSION)
+            .with_crate("cogex-render", cogex_render::VERSION)
+            .with_display(
+                DisplayInfo::new(self.size.width, self.size.height, self.scale_factor)
+                    .with_geometry(self.experiment.config.display),
+            )
+            .with_caveat(format!(
+                "Browser clock: performance.now() advanced in steps of {:.1} µs",
+                self.clock_resolution.as_secs_f64() * 1e6
+            ))
+            .with_caveat(
+                "Stimulus onsets are when requestAnimationFrame handed the frame to the \
+                 compositor, one or more refreshes before it was shown",
+            )
+            .with_caveat(
+                "Response times are taken when the browser dispatched the key or click, \
+                 after its own input latency",
+            );
+        let mut bundle = json!({
+            "metadata": metadata,
+            "results": cogex_experiment::results_to_json(session, results),
+            "events_tsv": cogex_experiment::bids_events_tsv(
+                results,
+                self.experiment.logged_markers(),
+                self.experiment.scanner_zero_ns(),
+            ),
+        });
+        if let Some(questionnaire) = &self.experiment.questionnaire
+            && questionnaire.answers().iter().any(Option::is_some)
+        {
+            bundle["questionnaire"] =
+                cogex_experiment::questionnaire_to_json(session, questionnaire);
+        }
+
+        let name = match session {
+            Some(session) => format!(
+                "{}_{}_{}.json",
+                self.experiment.config.task,
+                session.file_stem(),
+                session.started_at
+            ),
+            None => format!("{}.json", self.experiment.config.task),
+        };
+        let body = bundle.to_string();
+        match self.upload_url.clone() {
+            Some(url) => wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = output::upload(&url, &body).await {
+                    web_sys::console::error_2(&"Upload failed, downloading instead:".into(), &e);
+                    download(&name, &body);
+                }
+            }),
+            None => download(&name, &body),
+        }
+    }
+}
+
+fn download(name: &str, body: &str) {
+    if let Err(e) = output::download(name, body) {
+        web_sys::console::error_2(&"Failed to download the session data:".into(), &e);
+    }
+}
+
+/// Response key for a key as the keyboard layout reads it
+fn response_key(key: &Key) -> Option<ResponseKey> {
+    match key {
+        Key::Named(NamedKey::Space) => Some(ResponseKey::Space),
+        Key::Named(NamedKey::ArrowLeft) => Some(ResponseKey::ArrowLeft),
+        Key::Named(NamedKey::ArrowRight) => Some(ResponseKey::ArrowRight),
+        Key::Named(NamedKey::ArrowUp) => Some(ResponseKey::ArrowUp),
+        Key::Named(NamedKey::ArrowDown) => Some(ResponseKey::ArrowDown),
+        Key::Character(text) => ResponseKey::from_name(&text.to_lowercase()),
+        _ => None,
+    }
+}
+
+impl ApplicationHandler for WebApp {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_none()
+            && let Err(e) = self.create_window(event_loop)
+        {
+            web_sys::console::error_1(&format!("Failed to create canvas: {:#}", e).into());
+            event_loop.exit();
+        }
+    }
+
+    fn window_event(&mut self, _event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::RedrawRequested => {
+                if let Err(e) = self.render() {
+                    web_sys::console::error_1(&format!("Render failed: {:#}", e).into());
+                }
+                self.update();
+                // Every animation frame, so the loop runs at the refresh rate
+                if let Some(window) = &self.window {
+                    window.request_redraw();
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. } if event.state.is_pressed() => {
+                self.handle_key(&event);
+            }
+            WindowEvent::CursorMoved { position, .. } => self.handle_cursor(position),
+            WindowEvent::MouseInput { state, button, .. } if state.is_pressed() => {
+                self.handle_click(button);
+            }
+            WindowEvent::Resized(size) => self.handle_resize(size),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.scale_factor = scale_factor;
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.set_scale_factor(scale_factor as f32);
+                }
+                self.experiment.set_screen(Screen::new(
+                    self.size.width,
+                    self.size.height,
+                    scale_factor as f32,
+                ));
+            }
+            _ => {}
+        }
+    }
+}
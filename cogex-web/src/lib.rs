@@ -0,0 +1,42 @@
+//! Browser front end for online data collection: build with
+//! `wasm-pack build --target web` in this directory, then call `start` from
+//! the page. Native builds compile an empty crate.
+#![cfg(target_arch = "wasm32")]
+
+mod app;
+mod output;
+
+pub use app::WebApp;
+
+use cogex_core::StandardPhase;
+use cogex_experiment::{ExperimentConfig, ParadigmRegistry, SessionInfo};
+use wasm_bindgen::prelude::*;
+use winit::event_loop::EventLoop;
+use winit::platform::web::EventLoopExtWebSys;
+
+/// Crate version, recorded in the session metadata
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Runs the built-in paradigm `experiment` on a canvas filling the page,
+/// configured by `config_toml` as a config file would. The session's data is
+/// POSTed as JSON to `upload_url`, or downloaded without one or when the
+/// upload fails.
+#[wasm_bindgen]
+pub fn start(
+    config_toml: &str,
+    experiment: &str,
+    participant: &str,
+    session: u32,
+    upload_url: Option<String>,
+) -> Result<(), JsValue> {
+    let config = ExperimentConfig::<StandardPhase>::from_toml(config_toml).map_err(js_error)?;
+    let registry = ParadigmRegistry::builtin();
+    let session = SessionInfo::new(participant, None, session);
+    let app = WebApp::new(config, &registry, experiment, session, upload_url).map_err(js_error)?;
+    EventLoop::new().map_err(js_error)?.spawn_app(app);
+    Ok(())
+}
+
+fn js_error(e: impl std::fmt::Display) -> JsValue {
+    JsValue::from_str(&format!("{:#}", e))
+}